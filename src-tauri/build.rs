@@ -32,6 +32,42 @@ fn main() {
         "get_startup_state",
         "mark_startup_seen",
         "dismiss_whats_new",
+        "get_description_settings",
+        "save_description_settings",
+        "retry_step_description",
+        "delete_auto_steps",
+        "reset_step_crop_to_auto",
+        "recapture_step",
+        "deduplicate_steps",
+        "get_recording_diagnostics",
+        "set_recording_scope",
+        "copy_step_to_clipboard",
+        "get_session_diagnostics",
+        "preview_capture",
+        "set_auth_dialog_timing",
+        "dedupe_steps",
+        "get_session_storage_info",
+        "apply_crop_to_steps",
+        "set_step_wait",
+        "get_session_metadata",
+        "set_session_metadata",
+        "preview_export",
+        "get_recorder_settings",
+        "save_recorder_settings",
+        "export_session_bundle",
+        "list_sessions",
+        "load_session",
+        "delete_session",
+        "get_session_disk_usage",
+        "set_step_annotations",
+        "set_export_branding",
+        "reset_export_branding",
+        "validate_session",
+        "get_ai_queue_status",
+        "update_step_title",
+        "list_session_dirs",
+        "undo_edit",
+        "redo_edit",
     ]);
 
     let attrs = tauri_build::Attributes::new().app_manifest(manifest);
@@ -1,6 +1,7 @@
 use crate::i18n::Locale;
-use crate::recorder::types::Step;
+use crate::recorder::types::{BoundsPercent, Step};
 use serde::{Deserialize, Serialize};
+use std::io::BufRead;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
@@ -42,6 +43,69 @@ pub struct GenerateResponse {
     pub failures: Vec<GenerateFailureItem>,
 }
 
+impl GenerateResponse {
+    /// Results first, then failures, as single items.
+    pub fn into_items(self) -> impl Iterator<Item = GenerateItem> {
+        self.results
+            .into_iter()
+            .map(GenerateItem::Result)
+            .chain(self.failures.into_iter().map(GenerateItem::Failure))
+    }
+}
+
+/// One step's outcome; `generate --stream` prints one per line as it completes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GenerateItem {
+    Result(GenerateResultItem),
+    Failure(GenerateFailureItem),
+}
+
+impl GenerateItem {
+    pub fn id(&self) -> &str {
+        match self {
+            GenerateItem::Result(item) => &item.id,
+            GenerateItem::Failure(item) => &item.id,
+        }
+    }
+}
+
+/// A line of `generate` output. Helpers without `--stream` support ignore the flag and
+/// print a single whole response instead.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GenerateLine {
+    Response(GenerateResponse),
+    Item(GenerateItem),
+}
+
+/// Items in one line of `generate` output; blank lines have none.
+pub fn parse_generate_line(line: &str) -> Result<Vec<GenerateItem>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(Vec::new());
+    }
+    match serde_json::from_str(line).map_err(|e| format!("parse generate json: {e}"))? {
+        GenerateLine::Response(resp) => Ok(resp.into_items().collect()),
+        GenerateLine::Item(item) => Ok(vec![item]),
+    }
+}
+
+/// Reads newline-delimited `generate --stream` output, calling `on_item` for each item
+/// as its line arrives. Stops at the first line that doesn't parse.
+fn read_generate_stream(
+    reader: &mut dyn BufRead,
+    on_item: &mut dyn FnMut(GenerateItem),
+) -> Result<(), String> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("read ai helper output: {e}"))?;
+        parse_generate_line(&line)?
+            .into_iter()
+            .for_each(&mut *on_item);
+    }
+    Ok(())
+}
+
 #[cfg(target_os = "macos")]
 static AI_HELPER_PATH: OnceLock<PathBuf> = OnceLock::new();
 
@@ -125,11 +189,66 @@ fn run_helper(args: &[&str], stdin: Option<&[u8]>) -> Result<Vec<u8>, String> {
     Ok(out.stdout)
 }
 
+/// Like [`run_helper`], but hands the helper's stdout to `read` while it runs. An `Err`
+/// from `read` stops the helper and is returned.
+#[cfg(target_os = "macos")]
+fn run_helper_streaming(
+    args: &[&str],
+    stdin: &[u8],
+    read: &mut dyn FnMut(&mut dyn BufRead) -> Result<(), String>,
+) -> Result<(), String> {
+    use std::io::{BufReader, Write};
+    use std::process::{Command, Stdio};
+
+    let helper = helper_path()?;
+    let mut child = Command::new(helper)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("spawn ai helper: {e}"))?;
+    if let Some(mut w) = child.stdin.take() {
+        // Dropped at the end of the block, closing stdin so the helper starts.
+        w.write_all(stdin)
+            .map_err(|e| format!("write helper stdin: {e}"))?;
+    }
+
+    let stdout = child.stdout.take().ok_or("ai helper stdout missing")?;
+    let read_result = read(&mut BufReader::new(stdout));
+    if read_result.is_err() {
+        let _ = child.kill();
+    }
+
+    let out = child
+        .wait_with_output()
+        .map_err(|e| format!("wait ai helper: {e}"))?;
+    read_result?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(format!(
+            "ai helper failed ({}): {}",
+            out.status,
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(not(target_os = "macos"))]
 fn run_helper(_args: &[&str], _stdin: Option<&[u8]>) -> Result<Vec<u8>, String> {
     Err("not supported on this platform".into())
 }
 
+#[cfg(not(target_os = "macos"))]
+fn run_helper_streaming(
+    _args: &[&str],
+    _stdin: &[u8],
+    _read: &mut dyn FnMut(&mut dyn BufRead) -> Result<(), String>,
+) -> Result<(), String> {
+    Err("not supported on this platform".into())
+}
+
 pub fn availability(locale: Option<Locale>) -> Result<AvailabilityResponse, String> {
     let mut args = vec!["availability"];
     if let Some(locale) = locale {
@@ -143,11 +262,28 @@ pub fn availability(locale: Option<Locale>) -> Result<AvailabilityResponse, Stri
     serde_json::from_slice(&out).map_err(|e| format!("parse availability json: {e}"))
 }
 
-pub fn generate_descriptions(
-    steps: Vec<Step>,
-    max_chars: usize,
-    locale: Locale,
-) -> Result<GenerateResponse, String> {
+/// Steps per helper invocation. Small enough that the editor sees steady progress,
+/// large enough to amortize the helper's model warm-up.
+pub const GENERATE_CHUNK_SIZE: usize = 4;
+
+/// Split steps into sequential chunks for per-chunk `generate` invocations.
+pub fn chunk_steps(steps: Vec<Step>, chunk_size: usize) -> Vec<Vec<Step>> {
+    let size = chunk_size.max(1);
+    let mut chunks = Vec::with_capacity(steps.len().div_ceil(size));
+    let mut current = Vec::with_capacity(size);
+    for step in steps {
+        current.push(step);
+        if current.len() == size {
+            chunks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn generate_input(steps: Vec<Step>, max_chars: usize, locale: Locale) -> Result<Vec<u8>, String> {
     // Keep the Swift helper API stable: snake_case JSON.
     let req = GenerateRequest {
         steps,
@@ -157,16 +293,129 @@ pub fn generate_descriptions(
             Locale::De => "de".to_string(),
         }),
     };
-    let input = serde_json::to_vec(&req).map_err(|e| format!("encode generate json: {e}"))?;
+    serde_json::to_vec(&req).map_err(|e| format!("encode generate json: {e}"))
+}
+
+pub fn generate_descriptions(
+    steps: Vec<Step>,
+    max_chars: usize,
+    locale: Locale,
+) -> Result<GenerateResponse, String> {
+    let input = generate_input(steps, max_chars, locale)?;
     let out = run_helper(&["generate"], Some(&input))?;
     serde_json::from_slice(&out).map_err(|e| format!("parse generate json: {e}"))
 }
 
-pub fn is_auth_placeholder(step: &Step) -> bool {
-    step.window_title == "Authentication dialog (secure)"
-        || step.app.to_lowercase() == "authentication"
+/// Like [`generate_descriptions`], but calls `on_item` for each step as the helper
+/// finishes it. Older helpers answer all at once, and their items arrive together.
+pub fn generate_descriptions_streaming(
+    steps: Vec<Step>,
+    max_chars: usize,
+    locale: Locale,
+    on_item: &mut dyn FnMut(GenerateItem),
+) -> Result<(), String> {
+    let input = generate_input(steps, max_chars, locale)?;
+    run_helper_streaming(&["generate", "--stream"], &input, &mut |stdout| {
+        read_generate_stream(stdout, on_item)
+    })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TextRegionsResponse {
+    regions: Vec<BoundsPercent>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Text rectangles Vision finds in the image at `path`, in screenshot percent.
+pub fn detect_text_regions(path: &std::path::Path) -> Result<Vec<BoundsPercent>, String> {
+    let path = path.to_str().ok_or("screenshot path is not valid UTF-8")?;
+    let out = run_helper(&["text-regions", path], None)?;
+    let resp: TextRegionsResponse =
+        serde_json::from_slice(&out).map_err(|e| format!("parse text regions json: {e}"))?;
+    match resp.error {
+        Some(err) => Err(format!("text detection failed: {err}")),
+        None => Ok(resp.regions),
+    }
 }
 
 pub fn is_blank_description(desc: Option<&str>) -> bool {
     desc.unwrap_or("").trim().is_empty()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(n: usize) -> Vec<Step> {
+        (1..=n)
+            .map(|i| {
+                let mut s = Step::sample();
+                s.id = format!("step-{i:03}");
+                s
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunk_steps_splits_in_order() {
+        let chunks = chunk_steps(steps(10), 4);
+        let sizes: Vec<usize> = chunks.iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![4, 4, 2]);
+        assert_eq!(chunks[0][0].id, "step-001");
+        assert_eq!(chunks[2][1].id, "step-010");
+    }
+
+    fn read_stream(output: &str) -> (Vec<GenerateItem>, Result<(), String>) {
+        let mut items = Vec::new();
+        let result = read_generate_stream(&mut output.as_bytes(), &mut |item| items.push(item));
+        (items, result)
+    }
+
+    #[test]
+    fn read_generate_stream_yields_one_item_per_line() {
+        let output = concat!(
+            r#"{"id":"step-001","text":"Click Save","debug":{"kind":"button"}}"#,
+            "\n",
+            r#"{"id":"step-002","error":"Model refused."}"#,
+            "\n\n",
+            r#"{"id":"step-003","text":"Open the File menu"}"#,
+            "\n",
+        );
+        let (items, result) = read_stream(output);
+        result.expect("read stream");
+        let ids: Vec<&str> = items.iter().map(GenerateItem::id).collect();
+        assert_eq!(ids, ["step-001", "step-002", "step-003"]);
+        assert!(matches!(&items[0], GenerateItem::Result(r) if r.text == "Click Save"));
+        assert!(matches!(&items[1], GenerateItem::Failure(f) if f.error == "Model refused."));
+        assert!(matches!(&items[2], GenerateItem::Result(r) if r.debug.is_none()));
+    }
+
+    #[test]
+    fn read_generate_stream_accepts_a_whole_response_from_older_helpers() {
+        let output = r#"{"results":[{"id":"step-001","text":"Click Save"}],"failures":[{"id":"step-002","error":"No model output."}]}"#;
+        let (items, result) = read_stream(output);
+        result.expect("read response");
+        let ids: Vec<&str> = items.iter().map(GenerateItem::id).collect();
+        assert_eq!(ids, ["step-001", "step-002"]);
+    }
+
+    #[test]
+    fn read_generate_stream_keeps_items_before_a_bad_line() {
+        let output = concat!(
+            r#"{"id":"step-001","text":"Click Save"}"#,
+            "\n{not json\n",
+            r#"{"id":"step-002","text":"Open the File menu"}"#,
+        );
+        let (items, result) = read_stream(output);
+        assert!(result.is_err());
+        let ids: Vec<&str> = items.iter().map(GenerateItem::id).collect();
+        assert_eq!(ids, ["step-001"]);
+    }
+
+    #[test]
+    fn chunk_steps_handles_empty_and_zero_size() {
+        assert!(chunk_steps(Vec::new(), 4).is_empty());
+        assert_eq!(chunk_steps(steps(3), 0).len(), 3);
+    }
+}
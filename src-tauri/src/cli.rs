@@ -0,0 +1,244 @@
+//! Headless export, for regenerating guides in a docs build without opening the UI:
+//!
+//! `stepcast --export --input <steps.json>
+//! --format <html|html-zip|md|md-notion|md-confluence|pdf|gif|automation> --output <path>
+//! [--locale en|de] [--title <title>] [--text-only]`
+//!
+//! The input is a `steps.json` as written by `recorder::storage::write_steps`. A
+//! `session.json` next to it supplies the default title, author, date and tags.
+
+use crate::export::{self, pdf::HeadlessPdfHost, ExportFormat, ExportOptions};
+use crate::i18n::{self, Locale};
+use crate::recorder::storage;
+use crate::recorder::types::SessionMetadata;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: stepcast --export --input <steps.json> --format <html|html-zip|md|md-notion|md-confluence|pdf|gif|automation> --output <path> [--locale en|de] [--title <title>] [--text-only]";
+
+#[derive(Debug)]
+struct ExportArgs {
+    input: PathBuf,
+    format: ExportFormat,
+    output: String,
+    locale: Locale,
+    title: String,
+    metadata: SessionMetadata,
+    text_only: bool,
+}
+
+/// Whether the process was launched in export mode rather than as the app.
+pub fn is_export_invocation(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--export")
+}
+
+/// Run an export from command line arguments (without the program name).
+/// Errors go to stderr; returns the process exit code.
+pub fn run_export(args: &[String]) -> i32 {
+    match parse_args(args).and_then(|args| export_guide(&args)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("stepcast: {e}");
+            1
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<ExportArgs, String> {
+    let mut input = None;
+    let mut format = None;
+    let mut output = None;
+    let mut locale = None;
+    let mut title = None;
+    let mut text_only = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--export" => continue,
+            "--text-only" => {
+                text_only = true;
+                continue;
+            }
+            _ => {}
+        }
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} needs a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--input" => input = Some(PathBuf::from(value()?)),
+            "--format" => {
+                format = Some(ExportFormat::from_str(&value()?).map_err(|e| e.to_string())?)
+            }
+            "--output" => output = Some(value()?),
+            "--locale" => locale = Some(parse_locale(&value()?)?),
+            "--title" => title = Some(value()?),
+            other => return Err(format!("Unknown argument: {other}\n{USAGE}")),
+        }
+    }
+
+    let input = input.ok_or_else(|| format!("--input is required\n{USAGE}"))?;
+    let metadata = read_metadata(&input);
+    let title = title
+        .or_else(|| Some(metadata.title.clone()).filter(|title| !title.is_empty()))
+        .unwrap_or_else(|| default_title(&input));
+    Ok(ExportArgs {
+        format: format.ok_or_else(|| format!("--format is required\n{USAGE}"))?,
+        output: output.ok_or_else(|| format!("--output is required\n{USAGE}"))?,
+        locale: locale.unwrap_or_else(i18n::system_locale),
+        input,
+        title,
+        metadata,
+        text_only,
+    })
+}
+
+fn parse_locale(value: &str) -> Result<Locale, String> {
+    match value {
+        "en" => Ok(Locale::En),
+        "de" => Ok(Locale::De),
+        other => Err(format!("Unknown locale: {other} (expected en or de)")),
+    }
+}
+
+/// Guides are usually saved as `<dir>/steps.json`; name them after the directory then.
+fn default_title(input: &Path) -> String {
+    let named = |path: Option<&Path>| {
+        path.and_then(Path::file_stem)
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .filter(|stem| !stem.is_empty())
+    };
+    match named(Some(input)) {
+        Some(stem) if stem != "steps" => stem,
+        _ => named(input.parent()).unwrap_or_else(|| "Guide".to_string()),
+    }
+}
+
+/// Session metadata saved next to the steps; empty when there is none.
+fn read_metadata(input: &Path) -> SessionMetadata {
+    input
+        .parent()
+        .and_then(|dir| storage::read_session_metadata(dir).ok())
+        .unwrap_or_default()
+}
+
+fn export_guide(args: &ExportArgs) -> Result<(), String> {
+    let steps = storage::read_steps(&args.input)
+        .map_err(|e| format!("Cannot read {}: {e}", args.input.display()))?;
+    if steps.is_empty() {
+        return Err(format!("{} contains no steps", args.input.display()));
+    }
+    let pdf_host = HeadlessPdfHost::new()?;
+    export::export(
+        &args.title,
+        &steps,
+        args.format,
+        &args.output,
+        &pdf_host,
+        args.locale,
+        &ExportOptions {
+            text_only: args.text_only,
+            author: args.metadata.author.clone(),
+            recorded_on: args.metadata.created_on(),
+            tags: args.metadata.tags.clone(),
+            ..ExportOptions::default()
+        },
+        &|_| {},
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_export_mode() {
+        assert!(is_export_invocation(&args(&["--export", "--input", "a"])));
+        assert!(!is_export_invocation(&args(&[])));
+        assert!(!is_export_invocation(&args(&["--input", "a"])));
+    }
+
+    #[test]
+    fn parses_all_arguments() {
+        let parsed = parse_args(&args(&[
+            "--export",
+            "--input",
+            "guides/onboarding/steps.json",
+            "--format",
+            "md",
+            "--output",
+            "out/onboarding.md",
+            "--locale",
+            "de",
+            "--text-only",
+        ]))
+        .expect("valid arguments");
+
+        assert_eq!(parsed.input, PathBuf::from("guides/onboarding/steps.json"));
+        assert!(matches!(parsed.format, ExportFormat::Markdown(_)));
+        assert_eq!(parsed.output, "out/onboarding.md");
+        assert_eq!(parsed.locale, Locale::De);
+        assert_eq!(parsed.title, "onboarding");
+        assert!(parsed.text_only);
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_arguments() {
+        let missing_output = parse_args(&args(&[
+            "--export", "--input", "a.json", "--format", "html",
+        ]));
+        assert!(missing_output
+            .unwrap_err()
+            .starts_with("--output is required"));
+
+        let dangling = parse_args(&args(&["--export", "--input"]));
+        assert!(dangling.unwrap_err().starts_with("--input needs a value"));
+
+        let unknown = parse_args(&args(&["--export", "--verbose"]));
+        assert!(unknown
+            .unwrap_err()
+            .starts_with("Unknown argument: --verbose"));
+
+        let bad_format = parse_args(&args(&["--export", "--format", "docx"]));
+        assert_eq!(bad_format.unwrap_err(), "Unknown export format: docx");
+
+        let bad_locale = parse_args(&args(&["--export", "--locale", "fr"]));
+        assert!(bad_locale.unwrap_err().starts_with("Unknown locale: fr"));
+    }
+
+    #[test]
+    fn title_defaults_to_session_metadata() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut metadata = SessionMetadata::default();
+        metadata.update("Set up mail", Some("Ana"), &["mail".to_string()]);
+        storage::write_session_metadata(dir.path(), &metadata).expect("write metadata");
+        let input = dir.path().join("steps.json");
+        let input = input.to_str().unwrap();
+
+        let base = [
+            "--export", "--input", input, "--format", "md", "--output", "o",
+        ];
+        let parsed = parse_args(&args(&base)).expect("valid arguments");
+        assert_eq!(parsed.title, "Set up mail");
+        assert_eq!(parsed.metadata.author.as_deref(), Some("Ana"));
+
+        let mut with_title = base.to_vec();
+        with_title.extend(["--title", "Mail"]);
+        let parsed = parse_args(&args(&with_title)).expect("valid arguments");
+        assert_eq!(parsed.title, "Mail");
+    }
+
+    #[test]
+    fn title_defaults_to_guide_name() {
+        assert_eq!(default_title(Path::new("docs/setup.json")), "setup");
+        assert_eq!(default_title(Path::new("docs/setup/steps.json")), "setup");
+        assert_eq!(default_title(Path::new("steps.json")), "Guide");
+    }
+}
@@ -0,0 +1,220 @@
+//! Copy a single step to the system clipboard, e.g. for pasting into chat.
+//!
+//! The screenshot goes through the export pipeline (crop, optional number badge) so
+//! a pasted step looks the same as in an exported guide.
+
+use crate::export::helpers::{
+    effective_description_localized, html_escape, load_step_image, ImageTarget,
+};
+use crate::export::{ExportImageFormat, ExportImageOptions, ExportOptions};
+use crate::i18n::{self, Locale};
+use crate::recorder::types::Step;
+
+/// What [`copy_step`] puts on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMode {
+    Image,
+    Text,
+    /// Image and text as flavors of one pasteboard item.
+    Both,
+}
+
+impl std::str::FromStr for CopyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "image" => Ok(Self::Image),
+            "text" => Ok(Self::Text),
+            "both" => Ok(Self::Both),
+            other => Err(format!("Unknown copy mode: {other}")),
+        }
+    }
+}
+
+/// Plain-text flavor: "Step N: <description>", then the note on its own line.
+pub fn step_text(step: &Step, num: usize, locale: Locale) -> String {
+    let mut text = format!(
+        "{}: {}",
+        i18n::export_step_heading(locale, num),
+        effective_description_localized(step, locale)
+    );
+    if let Some(note) = step_note(step) {
+        text.push('\n');
+        text.push_str(note);
+    }
+    text
+}
+
+/// HTML flavor of [`step_text`], for apps that paste rich text.
+pub fn step_html(step: &Step, num: usize, locale: Locale) -> String {
+    let mut html = format!(
+        "<p><strong>{}:</strong> {}</p>",
+        html_escape(&i18n::export_step_heading(locale, num)),
+        html_escape(&effective_description_localized(step, locale))
+    );
+    if let Some(note) = step_note(step) {
+        html.push_str(&format!(
+            "<p>{}</p>",
+            html_escape(note).replace('\n', "<br>")
+        ));
+    }
+    html
+}
+
+fn step_note(step: &Step) -> Option<&str> {
+    step.note
+        .as_deref()
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+}
+
+/// Full-size PNG of the step's cropped screenshot, badged when `annotate` is set.
+fn step_png(step: &Step, num: usize, annotate: bool) -> Option<Vec<u8>> {
+    let options = ExportOptions {
+        number_badges: annotate,
+        image: ExportImageOptions {
+            max_width: None,
+            max_dimension: None,
+            format: ExportImageFormat::Png,
+            ..ExportImageOptions::default()
+        },
+        ..ExportOptions::default()
+    };
+    load_step_image(step, None, num, ImageTarget::Web, &options).map(|img| img.bytes)
+}
+
+fn png_to_tiff(png: &[u8]) -> Option<Vec<u8>> {
+    let img = image::load_from_memory(png).ok()?;
+    let mut buf = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Tiff).ok()?;
+    Some(buf.into_inner())
+}
+
+/// Replace the clipboard contents with step `num` (1-based).
+///
+/// Image mode fails when the step has no screenshot; "both" then copies the text only.
+pub fn copy_step(
+    step: &Step,
+    num: usize,
+    mode: CopyMode,
+    annotate: bool,
+    locale: Locale,
+) -> Result<(), String> {
+    let png = match mode {
+        CopyMode::Text => None,
+        CopyMode::Image | CopyMode::Both => step_png(step, num, annotate),
+    };
+    if mode == CopyMode::Image && png.is_none() {
+        return Err("step has no screenshot to copy".into());
+    }
+    let text = match mode {
+        CopyMode::Image => None,
+        CopyMode::Text | CopyMode::Both => {
+            Some((step_text(step, num, locale), step_html(step, num, locale)))
+        }
+    };
+    let tiff = png.as_deref().and_then(png_to_tiff);
+    write_pasteboard(
+        png.as_deref(),
+        tiff.as_deref(),
+        text.as_ref()
+            .map(|(plain, html)| (plain.as_str(), html.as_str())),
+    )
+}
+
+/// Write one multi-flavor item to the general pasteboard.
+#[cfg(target_os = "macos")]
+fn write_pasteboard(
+    png: Option<&[u8]>,
+    tiff: Option<&[u8]>,
+    text: Option<(&str, &str)>,
+) -> Result<(), String> {
+    use objc2::rc::Retained;
+    use objc2::runtime::ProtocolObject;
+    use objc2_app_kit::{
+        NSPasteboard, NSPasteboardItem, NSPasteboardTypeHTML, NSPasteboardTypePNG,
+        NSPasteboardTypeString, NSPasteboardTypeTIFF, NSPasteboardWriting,
+    };
+    use objc2_foundation::{NSArray, NSData, NSString};
+
+    let item = NSPasteboardItem::new();
+    // SAFETY: the pasteboard type constants are immutable statics owned by AppKit.
+    unsafe {
+        if let Some(png) = png {
+            item.setData_forType(&NSData::with_bytes(png), NSPasteboardTypePNG);
+        }
+        if let Some(tiff) = tiff {
+            item.setData_forType(&NSData::with_bytes(tiff), NSPasteboardTypeTIFF);
+        }
+        if let Some((plain, html)) = text {
+            item.setString_forType(&NSString::from_str(plain), NSPasteboardTypeString);
+            item.setString_forType(&NSString::from_str(html), NSPasteboardTypeHTML);
+        }
+    }
+
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    let writer: Retained<ProtocolObject<dyn NSPasteboardWriting>> =
+        ProtocolObject::from_retained(item);
+    let objects = NSArray::from_retained_slice(&[writer]);
+    if unsafe { pasteboard.writeObjects(&objects) } {
+        Ok(())
+    } else {
+        Err("failed to write to the clipboard".into())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn write_pasteboard(
+    _png: Option<&[u8]>,
+    _tiff: Option<&[u8]>,
+    _text: Option<(&str, &str)>,
+) -> Result<(), String> {
+    Err("not supported on this platform".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_mode_parses_known_values() {
+        assert_eq!("image".parse::<CopyMode>(), Ok(CopyMode::Image));
+        assert_eq!("text".parse::<CopyMode>(), Ok(CopyMode::Text));
+        assert_eq!("both".parse::<CopyMode>(), Ok(CopyMode::Both));
+        assert!("png".parse::<CopyMode>().is_err());
+    }
+
+    #[test]
+    fn step_text_includes_note_on_second_line() {
+        let mut step = Step::sample();
+        step.description = Some("Click Save".into());
+        assert_eq!(step_text(&step, 2, Locale::En), "Step 2: Click Save");
+
+        step.note = Some("  Wait for the upload.  ".into());
+        assert_eq!(
+            step_text(&step, 2, Locale::De),
+            "Schritt 2: Click Save\nWait for the upload."
+        );
+    }
+
+    #[test]
+    fn step_html_escapes_text() {
+        let mut step = Step::sample();
+        step.description = Some("Open <Settings>".into());
+        step.note = Some("a & b\nc".into());
+        assert_eq!(
+            step_html(&step, 1, Locale::En),
+            "<p><strong>Step 1:</strong> Open &lt;Settings&gt;</p><p>a &amp; b<br>c</p>"
+        );
+    }
+
+    #[test]
+    fn image_mode_requires_a_screenshot() {
+        let mut step = Step::sample();
+        step.screenshot_path = None;
+        let err = copy_step(&step, 1, CopyMode::Image, false, Locale::En).unwrap_err();
+        assert_eq!(err, "step has no screenshot to copy");
+    }
+}
@@ -0,0 +1,300 @@
+//! Error returned by Tauri commands. Serializes as `{ "code": ..., "message": ... }`:
+//! the UI branches on the stable `code` and shows `message` as before.
+
+use crate::export::ExportError;
+use crate::recorder::capture::CaptureError;
+use crate::recorder::pipeline::PipelineError;
+use crate::recorder::session::RevisionConflict;
+use crate::recorder::state::RecorderStateError;
+use crate::recorder::window_info::WindowError;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CommandError {
+    /// A state mutex was poisoned by a panicking thread; names the state.
+    LockPoisoned(&'static str),
+    /// Screen recording or accessibility permission is not granted.
+    MissingPermission,
+    /// Start/pause/resume/stop is not valid in the current recorder state.
+    InvalidState(RecorderStateError),
+    NoSession,
+    /// No finished session with the requested ID in the history.
+    SessionNotFound,
+    StepNotFound,
+    /// The step has a hand-written description; retry with `force` to overwrite it.
+    ManualDescription,
+    /// The editor's write was based on steps that changed since.
+    Conflict(RevisionConflict),
+    /// A command argument could not be parsed, e.g. an unknown image format.
+    InvalidArgument(String),
+    Pipeline(PipelineError),
+    Window(WindowError),
+    Capture(CaptureError),
+    Export(ExportError),
+    /// Anything without its own code yet.
+    Failed(String),
+}
+
+impl CommandError {
+    /// Stable identifier for the UI; never changes once shipped.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::LockPoisoned(_) => "lock_poisoned",
+            CommandError::MissingPermission => "missing_permission",
+            CommandError::InvalidState(_) => "invalid_state",
+            CommandError::NoSession => "no_session",
+            CommandError::SessionNotFound => "session_not_found",
+            CommandError::StepNotFound => "step_not_found",
+            CommandError::ManualDescription => "manual_description",
+            CommandError::Conflict(_) => "revision_conflict",
+            CommandError::InvalidArgument(_) => "invalid_argument",
+            CommandError::Pipeline(error) => match error {
+                PipelineError::StepNotFound => "step_not_found",
+                PipelineError::NotRecapturable => "not_recapturable",
+                PipelineError::AppNotRunning(_) => "app_not_running",
+                PipelineError::WindowClosed(_) => "window_closed",
+                PipelineError::WindowInfoFailed(_) => "window_info_failed",
+                PipelineError::ScreenshotFailed(_) => "capture_failed",
+                _ => "pipeline_error",
+            },
+            CommandError::Window(_) => "window_info_failed",
+            CommandError::Capture(_) => "capture_failed",
+            CommandError::Export(error) => match error {
+                ExportError::UnknownFormat(_) => "export_unknown_format",
+                ExportError::PermissionDenied(_) => "export_permission_denied",
+                ExportError::NotEnoughSpace(_) => "export_not_enough_space",
+                ExportError::Io(_) => "export_io",
+                ExportError::Render(_) => "export_render",
+            },
+            CommandError::Failed(_) => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::LockPoisoned(name) => write!(f, "{name} lock poisoned"),
+            CommandError::MissingPermission => {
+                write!(f, "missing screen recording or accessibility permission")
+            }
+            CommandError::InvalidState(error) => write!(f, "{error:?}"),
+            CommandError::NoSession => write!(f, "no active session"),
+            CommandError::SessionNotFound => write!(f, "session not found"),
+            CommandError::StepNotFound => write!(f, "step not found"),
+            CommandError::ManualDescription => {
+                write!(
+                    f,
+                    "step has a manual description; retry with force to overwrite it"
+                )
+            }
+            CommandError::Conflict(conflict) => write!(f, "{conflict}"),
+            CommandError::InvalidArgument(msg) | CommandError::Failed(msg) => f.write_str(msg),
+            CommandError::Pipeline(error) => write!(f, "{error}"),
+            CommandError::Window(error) => write!(f, "{error}"),
+            CommandError::Capture(error) => write!(f, "{error}"),
+            CommandError::Export(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // A conflict also carries the current revision, so the UI can reload and retry.
+        let revision = match self {
+            CommandError::Conflict(conflict) => Some(conflict.current),
+            _ => None,
+        };
+        let mut state =
+            serializer.serialize_struct("CommandError", 2 + usize::from(revision.is_some()))?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(revision) = revision {
+            state.serialize_field("revision", &revision)?;
+        }
+        state.end()
+    }
+}
+
+impl From<RecorderStateError> for CommandError {
+    fn from(error: RecorderStateError) -> Self {
+        CommandError::InvalidState(error)
+    }
+}
+
+impl From<RevisionConflict> for CommandError {
+    fn from(conflict: RevisionConflict) -> Self {
+        CommandError::Conflict(conflict)
+    }
+}
+
+impl From<PipelineError> for CommandError {
+    fn from(error: PipelineError) -> Self {
+        CommandError::Pipeline(error)
+    }
+}
+
+impl From<WindowError> for CommandError {
+    fn from(error: WindowError) -> Self {
+        CommandError::Window(error)
+    }
+}
+
+impl From<CaptureError> for CommandError {
+    fn from(error: CaptureError) -> Self {
+        CommandError::Capture(error)
+    }
+}
+
+impl From<ExportError> for CommandError {
+    fn from(error: ExportError) -> Self {
+        CommandError::Export(error)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Failed(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::Failed(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::state::{RecorderAction, SessionState};
+    use serde_json::json;
+
+    fn to_json(error: CommandError) -> serde_json::Value {
+        serde_json::to_value(error).expect("serialize")
+    }
+
+    #[test]
+    fn serializes_code_and_message() {
+        assert_eq!(
+            to_json(CommandError::LockPoisoned("session")),
+            json!({ "code": "lock_poisoned", "message": "session lock poisoned" })
+        );
+        assert_eq!(
+            to_json(CommandError::MissingPermission),
+            json!({
+                "code": "missing_permission",
+                "message": "missing screen recording or accessibility permission",
+            })
+        );
+        assert_eq!(
+            to_json(CommandError::NoSession),
+            json!({ "code": "no_session", "message": "no active session" })
+        );
+        assert_eq!(
+            to_json(CommandError::ManualDescription),
+            json!({
+                "code": "manual_description",
+                "message": "step has a manual description; retry with force to overwrite it",
+            })
+        );
+        assert_eq!(
+            to_json("Failed to create editor window: boom".into()),
+            json!({ "code": "failed", "message": "Failed to create editor window: boom" })
+        );
+    }
+
+    #[test]
+    fn recorder_state_errors_keep_their_debug_message() {
+        let error = RecorderStateError::InvalidTransition {
+            from: SessionState::Idle,
+            action: RecorderAction::Pause,
+        };
+        let value = to_json(error.into());
+        assert_eq!(value["code"], "invalid_state");
+        assert_eq!(value["message"], format!("{error:?}"));
+    }
+
+    #[test]
+    fn revision_conflicts_carry_the_current_revision() {
+        let conflict = RevisionConflict {
+            expected: 3,
+            current: 5,
+        };
+        assert_eq!(
+            to_json(conflict.into()),
+            json!({
+                "code": "revision_conflict",
+                "message": "steps changed since revision 3 (now 5); reload and try again",
+                "revision": 5,
+            })
+        );
+    }
+
+    #[test]
+    fn pipeline_window_and_capture_errors_get_distinct_codes() {
+        assert_eq!(
+            to_json(PipelineError::AppNotRunning("Safari".into()).into()),
+            json!({
+                "code": "app_not_running",
+                "message": "Safari is no longer running; reopen it to retake the screenshot",
+            })
+        );
+        assert_eq!(
+            to_json(PipelineError::WindowClosed("Safari".into()).into()),
+            json!({
+                "code": "window_closed",
+                "message": "Safari has no open window; open one to retake the screenshot",
+            })
+        );
+        assert_eq!(
+            to_json(PipelineError::StepNotFound.into())["code"],
+            "step_not_found"
+        );
+        assert_eq!(
+            to_json(PipelineError::DebouncedClick.into())["code"],
+            "pipeline_error"
+        );
+        assert_eq!(
+            to_json(WindowError::NoWindows.into()),
+            json!({ "code": "window_info_failed", "message": "no windows found" })
+        );
+        assert_eq!(
+            to_json(CaptureError::CgImage("empty image".into()).into()),
+            json!({ "code": "capture_failed", "message": "capture error: empty image" })
+        );
+    }
+
+    #[test]
+    fn export_errors_get_a_code_per_cause() {
+        assert_eq!(
+            to_json(ExportError::UnknownFormat("docx".into()).into()),
+            json!({
+                "code": "export_unknown_format",
+                "message": "Unknown export format: docx",
+            })
+        );
+        assert_eq!(
+            to_json(ExportError::Io("The folder \"/x\" does not exist.".into()).into()),
+            json!({
+                "code": "export_io",
+                "message": "The folder \"/x\" does not exist.",
+            })
+        );
+        assert_eq!(
+            to_json(ExportError::PermissionDenied("read-only".into()).into())["code"],
+            "export_permission_denied"
+        );
+        assert_eq!(
+            to_json(ExportError::NotEnoughSpace("full".into()).into())["code"],
+            "export_not_enough_space"
+        );
+        assert_eq!(
+            to_json(ExportError::Render("WebKit crashed".into()).into())["code"],
+            "export_render"
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use super::DescriptionProvider;
+use crate::apple_intelligence::{self, GenerateItem, GenerateResponse};
+use crate::i18n::Locale;
+use crate::recorder::types::Step;
+
+/// On-device Apple Intelligence via the bundled Swift helper.
+pub struct AppleIntelligenceProvider;
+
+impl DescriptionProvider for AppleIntelligenceProvider {
+    fn generate(
+        &self,
+        steps: Vec<Step>,
+        max_chars: usize,
+        locale: Locale,
+    ) -> Result<GenerateResponse, String> {
+        apple_intelligence::generate_descriptions(steps, max_chars, locale)
+    }
+
+    fn generate_streaming(
+        &self,
+        steps: Vec<Step>,
+        max_chars: usize,
+        locale: Locale,
+        on_item: &mut dyn FnMut(GenerateItem),
+    ) -> Result<(), String> {
+        apple_intelligence::generate_descriptions_streaming(steps, max_chars, locale, on_item)
+    }
+}
@@ -0,0 +1,86 @@
+//! Pluggable backends for AI step descriptions.
+//!
+//! Every provider takes the same step context and answers with the Swift helper's
+//! `GenerateResponse` shape, so the editor pipeline doesn't care who wrote the text.
+
+mod apple;
+mod noop;
+mod openai;
+pub mod queue;
+pub mod settings;
+
+use crate::apple_intelligence::{GenerateItem, GenerateResponse};
+use crate::i18n::Locale;
+use crate::recorder::types::Step;
+use std::sync::Arc;
+
+pub trait DescriptionProvider: Send + Sync {
+    /// Describe `steps`. Per-step problems belong in `failures`; `Err` fails the batch.
+    fn generate(
+        &self,
+        steps: Vec<Step>,
+        max_chars: usize,
+        locale: Locale,
+    ) -> Result<GenerateResponse, String>;
+
+    /// Like [`generate`](Self::generate), but hands each step to `on_item` as soon as
+    /// it is described. The default waits for the whole batch.
+    fn generate_streaming(
+        &self,
+        steps: Vec<Step>,
+        max_chars: usize,
+        locale: Locale,
+        on_item: &mut dyn FnMut(GenerateItem),
+    ) -> Result<(), String> {
+        self.generate(steps, max_chars, locale)?
+            .into_items()
+            .for_each(on_item);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Apple,
+    OpenAi,
+}
+
+impl ProviderKind {
+    /// Parse the frontend value. Missing means Apple Intelligence, the original backend.
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim).unwrap_or("apple") {
+            "" | "apple" => Ok(Self::Apple),
+            "openai" => Ok(Self::OpenAi),
+            other => Err(format!("Unknown description provider: {other}")),
+        }
+    }
+}
+
+pub fn provider_for(kind: ProviderKind) -> Result<Arc<dyn DescriptionProvider>, String> {
+    match kind {
+        ProviderKind::Apple if cfg!(target_os = "macos") => {
+            Ok(Arc::new(apple::AppleIntelligenceProvider))
+        }
+        // No Swift helper elsewhere; steps fail with a message saying why.
+        ProviderKind::Apple => Ok(Arc::new(noop::NoopProvider)),
+        ProviderKind::OpenAi => Ok(Arc::new(openai::OpenAiProvider::new(
+            settings::load().openai,
+        )?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_provider_kind() {
+        assert_eq!(ProviderKind::parse(None), Ok(ProviderKind::Apple));
+        assert_eq!(ProviderKind::parse(Some("apple")), Ok(ProviderKind::Apple));
+        assert_eq!(
+            ProviderKind::parse(Some(" openai ")),
+            Ok(ProviderKind::OpenAi)
+        );
+        assert!(ProviderKind::parse(Some("gemini")).is_err());
+    }
+}
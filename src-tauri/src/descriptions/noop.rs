@@ -0,0 +1,60 @@
+use super::DescriptionProvider;
+use crate::apple_intelligence::{GenerateFailureItem, GenerateResponse};
+use crate::i18n::{self, Locale};
+use crate::recorder::types::Step;
+
+/// Stands in for Apple Intelligence off macOS: every step fails with a message that
+/// says why, instead of an error from spawning a helper that isn't there.
+pub struct NoopProvider;
+
+impl DescriptionProvider for NoopProvider {
+    fn generate(
+        &self,
+        steps: Vec<Step>,
+        _max_chars: usize,
+        locale: Locale,
+    ) -> Result<GenerateResponse, String> {
+        let error = i18n::ai_descriptions_require_macos(locale);
+        Ok(GenerateResponse {
+            results: Vec::new(),
+            failures: steps
+                .into_iter()
+                .map(|step| GenerateFailureItem {
+                    id: step.id,
+                    error: error.to_string(),
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple_intelligence::GenerateItem;
+
+    #[test]
+    fn every_requested_step_fails_with_the_macos_message() {
+        let steps: Vec<Step> = ["step-001", "step-002", "step-003"]
+            .into_iter()
+            .map(|id| Step {
+                id: id.to_string(),
+                ..Step::sample()
+            })
+            .collect();
+
+        let mut items = Vec::new();
+        NoopProvider
+            .generate_streaming(steps, 110, Locale::En, &mut |item| items.push(item))
+            .expect("noop never fails the batch");
+
+        let ids: Vec<&str> = items.iter().map(GenerateItem::id).collect();
+        assert_eq!(ids, ["step-001", "step-002", "step-003"]);
+        for item in &items {
+            let GenerateItem::Failure(failure) = item else {
+                panic!("expected a failure, got {item:?}");
+            };
+            assert_eq!(failure.error, "AI descriptions require macOS.");
+        }
+    }
+}
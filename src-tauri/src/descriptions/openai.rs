@@ -0,0 +1,327 @@
+//! OpenAI-compatible chat-completions provider.
+//!
+//! One request per step so a single bad response only fails that step. Works with
+//! any server that speaks `/chat/completions` (OpenAI, Azure-style proxies, Ollama, LM Studio).
+
+use super::settings::OpenAiSettings;
+use super::DescriptionProvider;
+use crate::apple_intelligence::{GenerateFailureItem, GenerateResponse, GenerateResultItem};
+use crate::i18n::Locale;
+use crate::recorder::types::Step;
+use base64::Engine;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest screenshot edge sent to the model; plenty for UI context, cheap on tokens.
+const SCREENSHOT_MAX_EDGE: u32 = 1024;
+/// Cap on server error text echoed back into a step's error message.
+const ERROR_BODY_MAX_CHARS: usize = 200;
+
+pub struct OpenAiProvider {
+    settings: OpenAiSettings,
+    client: reqwest::Client,
+}
+
+impl OpenAiProvider {
+    pub fn new(settings: OpenAiSettings) -> Result<Self, String> {
+        if settings.endpoint.trim().is_empty() {
+            return Err("OpenAI-compatible provider has no endpoint configured.".into());
+        }
+        if !settings.has_api_key() {
+            return Err("OpenAI-compatible provider has no API key configured.".into());
+        }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| format!("create http client: {e}"))?;
+        Ok(Self { settings, client })
+    }
+
+    async fn describe(
+        &self,
+        step: &Step,
+        max_chars: usize,
+        locale: Locale,
+    ) -> Result<String, String> {
+        let image = if self.settings.send_screenshots {
+            screenshot_data_url(step)
+        } else {
+            None
+        };
+        let body = serde_json::json!({
+            "model": self.settings.model,
+            "messages": build_messages(step, max_chars, locale, image.as_deref()),
+            "temperature": 0.2,
+        });
+        let url = chat_completions_url(&self.settings.endpoint);
+        let api_key = self.settings.api_key.as_deref().unwrap_or("").trim();
+
+        let resp = self
+            .client
+            .post(&url)
+            .bearer_auth(api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| request_error(&e, &url))?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| request_error(&e, &url))?;
+        if !status.is_success() {
+            return Err(http_status_error(status.as_u16(), &text));
+        }
+        parse_completion(&text, max_chars)
+    }
+}
+
+impl DescriptionProvider for OpenAiProvider {
+    fn generate(
+        &self,
+        steps: Vec<Step>,
+        max_chars: usize,
+        locale: Locale,
+    ) -> Result<GenerateResponse, String> {
+        let mut results = Vec::new();
+        let mut failures = Vec::new();
+        for step in &steps {
+            match tauri::async_runtime::block_on(self.describe(step, max_chars, locale)) {
+                Ok(text) => results.push(GenerateResultItem {
+                    id: step.id.clone(),
+                    text,
+                    debug: None,
+                }),
+                Err(error) => failures.push(GenerateFailureItem {
+                    id: step.id.clone(),
+                    error,
+                }),
+            }
+        }
+        Ok(GenerateResponse { results, failures })
+    }
+}
+
+fn chat_completions_url(endpoint: &str) -> String {
+    let base = endpoint.trim().trim_end_matches('/');
+    if base.ends_with("/chat/completions") {
+        base.to_string()
+    } else {
+        format!("{base}/chat/completions")
+    }
+}
+
+fn request_error(err: &reqwest::Error, url: &str) -> String {
+    if err.is_timeout() {
+        format!(
+            "Request to {url} timed out after {}s.",
+            REQUEST_TIMEOUT.as_secs()
+        )
+    } else if err.is_connect() {
+        format!("Could not connect to {url}: {err}")
+    } else {
+        format!("Request to {url} failed: {err}")
+    }
+}
+
+fn http_status_error(status: u16, body: &str) -> String {
+    // OpenAI-style servers put the useful part in `error.message`.
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/message")
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().to_string());
+    let message: String = message.chars().take(ERROR_BODY_MAX_CHARS).collect();
+    if message.is_empty() {
+        format!("Provider returned HTTP {status}.")
+    } else {
+        format!("Provider returned HTTP {status}: {message}")
+    }
+}
+
+fn parse_completion(body: &str, max_chars: usize) -> Result<String, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("parse provider response: {e}"))?;
+    let content = value
+        .pointer("/choices/0/message/content")
+        .and_then(|c| c.as_str())
+        .ok_or("Provider response has no message content.")?;
+    let text = clean_description(content, max_chars);
+    if text.is_empty() {
+        return Err("No model output.".into());
+    }
+    Ok(text)
+}
+
+/// First line only, without wrapping quotes, capped at `max_chars`.
+fn clean_description(content: &str, max_chars: usize) -> String {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    let line = line.trim_matches(|c| c == '"' || c == '\u{201C}' || c == '\u{201D}');
+    line.trim().chars().take(max_chars).collect()
+}
+
+fn build_messages(
+    step: &Step,
+    max_chars: usize,
+    locale: Locale,
+    image_data_url: Option<&str>,
+) -> serde_json::Value {
+    let language = match locale {
+        Locale::En => "English",
+        Locale::De => "German",
+    };
+    let system = format!(
+        "You write step descriptions for a how-to guide. Reply with one imperative \
+         sentence in {language}, at most {max_chars} characters, no quotes."
+    );
+
+    let mut context = format!(
+        "Action: {:?}\nApp: {}\nWindow: {}",
+        step.action, step.app, step.window_title
+    );
+    if let Some(ax) = &step.ax {
+        context.push_str(&format!("\nClicked element: {} \"{}\"", ax.role, ax.label));
+        if let Some(menu_path) = &ax.menu_path {
+            context.push_str(&format!("\nMenu path: {menu_path}"));
+        }
+        if let (Some(was), Some(now)) = (ax.was_checked, ax.now_checked) {
+            context.push_str(&format!("\nChecked before click: {was}, after: {now}"));
+        }
+        if let Some(placeholder) = &ax.placeholder {
+            context.push_str(&format!("\nField placeholder: \"{placeholder}\""));
+        }
+        if let Some(value) = &ax.value {
+            context.push_str(&format!("\nField value: \"{value}\""));
+        }
+    }
+    if let Some(keys) = &step.keys {
+        context.push_str(&format!("\nKeys pressed: {keys}"));
+    }
+    if let Some(url) = &step.page_url {
+        context.push_str(&format!("\nPage URL: {url}"));
+    }
+    if let Some(note) = step.note.as_deref().filter(|n| !n.trim().is_empty()) {
+        context.push_str(&format!("\nUser note: {note}"));
+    }
+
+    let mut content = vec![serde_json::json!({ "type": "text", "text": context })];
+    if let Some(url) = image_data_url {
+        content.push(serde_json::json!({
+            "type": "image_url",
+            "image_url": { "url": url },
+        }));
+    }
+
+    serde_json::json!([
+        { "role": "system", "content": system },
+        { "role": "user", "content": content },
+    ])
+}
+
+fn screenshot_data_url(step: &Step) -> Option<String> {
+    let img = image::open(step.screenshot_path.as_deref()?).ok()?;
+    let img = if img.width().max(img.height()) > SCREENSHOT_MAX_EDGE {
+        img.thumbnail(SCREENSHOT_MAX_EDGE, SCREENSHOT_MAX_EDGE)
+    } else {
+        img
+    };
+    let mut buf = std::io::Cursor::new(Vec::new());
+    img.to_rgb8()
+        .write_to(&mut buf, image::ImageFormat::Jpeg)
+        .ok()?;
+    Some(format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(buf.into_inner())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_completions_url_appends_path_once() {
+        assert_eq!(
+            chat_completions_url("https://api.openai.com/v1/"),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert_eq!(
+            chat_completions_url("http://localhost:11434/v1/chat/completions"),
+            "http://localhost:11434/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn http_status_error_prefers_api_message() {
+        let body = r#"{"error":{"message":"Invalid API key","type":"auth"}}"#;
+        assert_eq!(
+            http_status_error(401, body),
+            "Provider returned HTTP 401: Invalid API key"
+        );
+        assert_eq!(http_status_error(502, "  "), "Provider returned HTTP 502.");
+    }
+
+    #[test]
+    fn parse_completion_cleans_first_line() {
+        let body =
+            r#"{"choices":[{"message":{"content":"\"Click Save in the toolbar\"\nExtra"}}]}"#;
+        assert_eq!(
+            parse_completion(body, 110).unwrap(),
+            "Click Save in the toolbar"
+        );
+        assert_eq!(parse_completion(body, 5).unwrap(), "Click");
+    }
+
+    #[test]
+    fn parse_completion_rejects_empty_and_malformed() {
+        let empty = r#"{"choices":[{"message":{"content":"  "}}]}"#;
+        assert_eq!(
+            parse_completion(empty, 110).unwrap_err(),
+            "No model output."
+        );
+        assert!(parse_completion(r#"{"choices":[]}"#, 110).is_err());
+        assert!(parse_completion("not json", 110).is_err());
+    }
+
+    #[test]
+    fn build_messages_includes_context_and_image() {
+        let step = Step::sample();
+        let messages = build_messages(&step, 110, Locale::De, Some("data:image/jpeg;base64,AA"));
+        let system = messages[0]["content"].as_str().unwrap();
+        assert!(system.contains("German"));
+        let user = &messages[1]["content"];
+        assert!(user[0]["text"].as_str().unwrap().contains(&step.app));
+        assert_eq!(user[1]["image_url"]["url"], "data:image/jpeg;base64,AA");
+    }
+
+    #[test]
+    fn build_messages_describes_form_fields() {
+        let mut step = Step::sample();
+        step.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXTextField",
+            "label": "Email",
+            "placeholder": "name@example.com",
+            "value": "you@example.com",
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .unwrap();
+        let messages = build_messages(&step, 110, Locale::En, None);
+        let text = messages[1]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Clicked element: AXTextField \"Email\""));
+        assert!(text.contains("Field placeholder: \"name@example.com\""));
+        assert!(text.contains("Field value: \"you@example.com\""));
+    }
+
+    #[test]
+    fn new_requires_api_key() {
+        assert!(OpenAiProvider::new(OpenAiSettings::default()).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Work queue for description requests. Requests made while a batch runs wait their
+//! turn instead of failing; one worker drains the queue a job at a time, so results
+//! never race each other on the same step.
+
+use super::DescriptionProvider;
+use crate::i18n::Locale;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+/// Which steps a `generate_step_descriptions` call asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptionMode {
+    /// Steps without a description, or with only a rule-based one.
+    MissingOnly,
+    /// Every step without a manual description.
+    All,
+    /// Steps the user picked; their manual descriptions may be replaced.
+    Ids,
+}
+
+pub struct DescriptionJob {
+    pub mode: DescriptionMode,
+    pub step_ids: Vec<String>,
+    pub locale: Locale,
+    pub provider: Arc<dyn DescriptionProvider>,
+}
+
+impl DescriptionJob {
+    /// Picked steps may be replaced; others keep edits made meanwhile.
+    pub fn overwrite_manual(&self) -> bool {
+        self.mode == DescriptionMode::Ids
+    }
+}
+
+/// Payload of `get_ai_queue_status`: steps waiting for the worker and steps it is
+/// describing right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct QueueStatus {
+    pub pending: usize,
+    pub active: usize,
+}
+
+/// What [`DescriptionQueue::enqueue`] took from a job.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Enqueued {
+    /// Steps newly queued; the rest were already waiting or running.
+    pub step_ids: Vec<String>,
+    /// No worker is draining the queue; the caller has to start one.
+    pub start_worker: bool,
+}
+
+#[derive(Default)]
+pub struct DescriptionQueue {
+    pending: VecDeque<DescriptionJob>,
+    /// Steps of the job the worker is running.
+    active: Vec<String>,
+    worker_running: bool,
+}
+
+impl DescriptionQueue {
+    /// Queue `job` without the steps that are already waiting or running. Nothing is
+    /// queued when no step is left.
+    pub fn enqueue(&mut self, mut job: DescriptionJob) -> Enqueued {
+        let mut seen = HashSet::new();
+        job.step_ids
+            .retain(|id| !self.contains(id) && seen.insert(id.clone()));
+        let step_ids = job.step_ids.clone();
+        if step_ids.is_empty() {
+            return Enqueued {
+                step_ids,
+                start_worker: false,
+            };
+        }
+        self.pending.push_back(job);
+        let start_worker = !self.worker_running;
+        self.worker_running = true;
+        Enqueued {
+            step_ids,
+            start_worker,
+        }
+    }
+
+    /// Whether `step_id` is waiting or being described.
+    pub fn contains(&self, step_id: &str) -> bool {
+        self.active.iter().any(|id| id == step_id)
+            || self
+                .pending
+                .iter()
+                .any(|job| job.step_ids.iter().any(|id| id == step_id))
+    }
+
+    /// Hand the worker its next job. `None` means the queue is drained and the worker
+    /// stops; the next [`enqueue`](Self::enqueue) asks for a new one.
+    pub fn next_job(&mut self) -> Option<DescriptionJob> {
+        let job = self.pending.pop_front();
+        match &job {
+            Some(job) => self.active = job.step_ids.clone(),
+            None => {
+                self.active.clear();
+                self.worker_running = false;
+            }
+        }
+        job
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        QueueStatus {
+            pending: self.pending.iter().map(|job| job.step_ids.len()).sum(),
+            active: self.active.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple_intelligence::GenerateResponse;
+    use crate::recorder::types::Step;
+
+    struct Unused;
+
+    impl DescriptionProvider for Unused {
+        fn generate(
+            &self,
+            _steps: Vec<Step>,
+            _max_chars: usize,
+            _locale: Locale,
+        ) -> Result<GenerateResponse, String> {
+            unreachable!("the queue never calls providers")
+        }
+    }
+
+    fn job(ids: &[&str]) -> DescriptionJob {
+        DescriptionJob {
+            mode: DescriptionMode::Ids,
+            step_ids: ids.iter().map(|id| id.to_string()).collect(),
+            locale: Locale::En,
+            provider: Arc::new(Unused),
+        }
+    }
+
+    #[test]
+    fn first_job_starts_the_worker_and_later_ones_wait() {
+        let mut queue = DescriptionQueue::default();
+        let first = queue.enqueue(job(&["a", "b"]));
+        assert_eq!(first.step_ids, vec!["a", "b"]);
+        assert!(first.start_worker);
+        assert!(!queue.enqueue(job(&["c"])).start_worker);
+        assert_eq!(
+            queue.status(),
+            QueueStatus {
+                pending: 3,
+                active: 0
+            }
+        );
+
+        assert_eq!(queue.next_job().unwrap().step_ids, vec!["a", "b"]);
+        assert_eq!(
+            queue.status(),
+            QueueStatus {
+                pending: 1,
+                active: 2
+            }
+        );
+        assert_eq!(queue.next_job().unwrap().step_ids, vec!["c"]);
+        assert!(queue.next_job().is_none());
+        assert_eq!(queue.status(), QueueStatus::default());
+
+        // Drained: the next request needs a new worker.
+        assert!(queue.enqueue(job(&["d"])).start_worker);
+    }
+
+    #[test]
+    fn skips_steps_already_waiting_or_running() {
+        let mut queue = DescriptionQueue::default();
+        queue.enqueue(job(&["a", "b"]));
+        queue.next_job();
+        queue.enqueue(job(&["c"]));
+
+        let again = queue.enqueue(job(&["a", "c", "d"]));
+        assert_eq!(again.step_ids, vec!["d"]);
+        assert!(!again.start_worker);
+
+        let nothing = queue.enqueue(job(&["b"]));
+        assert!(nothing.step_ids.is_empty());
+        assert_eq!(queue.status().pending, 2);
+        assert!(queue.contains("b"));
+        assert!(!queue.contains("e"));
+    }
+}
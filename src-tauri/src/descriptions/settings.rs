@@ -0,0 +1,100 @@
+//! Persisted configuration for description providers (endpoint, model, API key).
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DescriptionSettings {
+    #[serde(default)]
+    pub openai: OpenAiSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiSettings {
+    /// Base URL of an OpenAI-compatible API, e.g. `https://api.openai.com/v1`.
+    pub endpoint: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Attach a downscaled screenshot to each request.
+    #[serde(default = "default_true")]
+    pub send_screenshots: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for OpenAiSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.openai.com/v1".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            api_key: None,
+            send_screenshots: true,
+        }
+    }
+}
+
+impl OpenAiSettings {
+    pub fn has_api_key(&self) -> bool {
+        self.api_key
+            .as_deref()
+            .is_some_and(|k| !k.trim().is_empty())
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| {
+        d.join("com.w0nk1.stepcast")
+            .join("description_settings.json")
+    })
+}
+
+pub fn load() -> DescriptionSettings {
+    let Some(path) = settings_path() else {
+        return DescriptionSettings::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => DescriptionSettings::default(),
+    }
+}
+
+pub fn save(settings: &DescriptionSettings) -> Result<(), String> {
+    let path = settings_path().ok_or("config dir not found")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    // The file holds an API key: keep it readable by the current user only.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_json_loads_defaults() {
+        let settings: DescriptionSettings = serde_json::from_str("{}").expect("deserialize");
+        assert_eq!(settings.openai.endpoint, "https://api.openai.com/v1");
+        assert!(settings.openai.send_screenshots);
+        assert!(!settings.openai.has_api_key());
+    }
+
+    #[test]
+    fn blank_api_key_is_missing() {
+        let settings = OpenAiSettings {
+            api_key: Some("  ".to_string()),
+            ..OpenAiSettings::default()
+        };
+        assert!(!settings.has_api_key());
+    }
+}
@@ -0,0 +1,579 @@
+//! Animated GIF walkthrough of a guide.
+//!
+//! Each step's (cropped, badged) screenshot is letterboxed into a fixed-size frame and
+//! held while its click marker pulses; notes and text-only steps become title cards.
+//! Frames go to the encoder as they are rendered, so only one step's pixels are in
+//! memory at a time.
+
+use super::helpers::{
+    effective_description_localized, load_step_pixels, marker_position_percent, previous_step,
+};
+use super::{friendly_write_error, ExportError, ExportOptions, ProgressReporter};
+use crate::i18n::{self, Locale};
+use crate::recorder::types::{ActionType, Step};
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, Rgba, RgbaImage};
+use std::io::Write;
+
+/// Each half of the marker pulse is shown this long.
+const PULSE_FRAME_MS: u32 = 500;
+/// GIF quantizer speed, 1 (best) to 30 (fastest). 1 takes seconds per frame.
+const ENCODER_SPEED: i32 = 10;
+
+const BACKGROUND: Rgba<u8> = Rgba([24, 24, 27, 255]);
+const CAPTION_BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([250, 250, 250, 255]);
+const MARKER_COLOR: Rgba<u8> = Rgba([124, 92, 252, 255]);
+
+/// Fonts for captions and title cards, first readable one wins. Without any, frames
+/// have no text and title cards only show the step number.
+const FONT_PATHS: &[&str] = &[
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+pub(super) fn load_font() -> Option<FontVec> {
+    FONT_PATHS.iter().find_map(|path| {
+        let data = std::fs::read(path).ok()?;
+        FontVec::try_from_vec_and_index(data, 0).ok()
+    })
+}
+
+/// Write `steps` as an animated GIF to `output_path`.
+pub fn write(
+    title: &str,
+    steps: &[Step],
+    output_path: &str,
+    locale: Locale,
+    options: &ExportOptions,
+    progress: &ProgressReporter,
+) -> Result<(), ExportError> {
+    let file =
+        std::fs::File::create(output_path).map_err(|e| friendly_write_error(&e, output_path))?;
+    let mut writer = std::io::BufWriter::new(file);
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut writer, ENCODER_SPEED);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| encode_error(e, output_path))?;
+        let mut emit = |img: RgbaImage, ms: u32| {
+            let delay = Delay::from_numer_denom_ms(ms, 1);
+            encoder
+                .encode_frame(Frame::from_parts(img, 0, 0, delay))
+                .map_err(|e| encode_error(e, output_path))
+        };
+
+        let renderer = FrameRenderer::new(locale, options);
+        let hold_ms = options.animation.step_hold_ms.max(PULSE_FRAME_MS);
+        if steps.is_empty() {
+            // A GIF needs at least one frame.
+            emit(renderer.title_card(title, &[]), hold_ms)?;
+        }
+        for (i, step) in steps.iter().enumerate() {
+            let rendered = renderer.render(step, previous_step(steps, i + 1), i + 1);
+            match rendered.marker {
+                Some(marker) => {
+                    let pulses = (hold_ms / PULSE_FRAME_MS).max(2);
+                    for phase in 0..pulses {
+                        let ms = if phase + 1 == pulses {
+                            hold_ms - PULSE_FRAME_MS * (pulses - 1)
+                        } else {
+                            PULSE_FRAME_MS
+                        };
+                        emit(pulse_frame(&rendered.base, marker, phase % 2 == 1), ms)?;
+                    }
+                }
+                None => emit(rendered.base, hold_ms)?,
+            }
+            progress.step_done();
+        }
+    }
+    writer
+        .flush()
+        .map_err(|e| friendly_write_error(&e, output_path))
+}
+
+/// PNG of the first frame, for the export preview.
+pub fn first_frame_png(
+    title: &str,
+    steps: &[Step],
+    locale: Locale,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, ExportError> {
+    let renderer = FrameRenderer::new(locale, options);
+    let frame = match steps.first() {
+        Some(step) => {
+            let rendered = renderer.render(step, None, 1);
+            match rendered.marker {
+                Some(marker) => pulse_frame(&rendered.base, marker, false),
+                None => rendered.base,
+            }
+        }
+        None => renderer.title_card(title, &[]),
+    };
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(frame)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| ExportError::Render(format!("Could not render preview frame: {e}")))?;
+    Ok(out.into_inner())
+}
+
+fn encode_error(e: image::ImageError, output_path: &str) -> ExportError {
+    match e {
+        image::ImageError::IoError(e) => friendly_write_error(&e, output_path),
+        other => ExportError::Render(format!("Could not encode GIF: {other}")),
+    }
+}
+
+/// A step's frame without the marker, plus where the marker pulses.
+struct RenderedStep {
+    base: RgbaImage,
+    marker: Option<Marker>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Marker {
+    x: i32,
+    y: i32,
+    radius: i32,
+}
+
+struct FrameRenderer<'a> {
+    width: u32,
+    height: u32,
+    locale: Locale,
+    options: &'a ExportOptions,
+    font: Option<FontVec>,
+}
+
+impl<'a> FrameRenderer<'a> {
+    fn new(locale: Locale, options: &'a ExportOptions) -> Self {
+        Self {
+            width: options.animation.width.max(16),
+            height: options.animation.height.max(16),
+            locale,
+            options,
+            font: load_font(),
+        }
+    }
+
+    fn render(&self, step: &Step, previous: Option<&Step>, num: usize) -> RenderedStep {
+        let description = effective_description_localized(step, self.locale);
+        let pixels = (!self.options.text_only && step.action != ActionType::Note)
+            .then(|| load_step_pixels(step, previous, num, self.options))
+            .flatten();
+        let Some(pixels) = pixels else {
+            let mut body = vec![description];
+            body.extend(step.note.clone());
+            let heading = i18n::export_step_heading(self.locale, num);
+            return RenderedStep {
+                base: self.title_card(&heading, &body),
+                marker: None,
+            };
+        };
+
+        let mut frame = RgbaImage::from_pixel(self.width, self.height, BACKGROUND);
+        let caption_h = match (&self.font, self.options.animation.captions) {
+            (Some(_), true) => self.height / 6,
+            _ => 0,
+        };
+        let (x, y, w, h) = letterbox(
+            pixels.width(),
+            pixels.height(),
+            self.width,
+            self.height - caption_h,
+        );
+        let scaled = image::imageops::resize(&pixels, w, h, image::imageops::FilterType::Triangle);
+        drop(pixels);
+        image::imageops::overlay(&mut frame, &scaled, i64::from(x), i64::from(y));
+
+        if let (Some(font), true) = (&self.font, caption_h > 0) {
+            let band_y = self.height - caption_h;
+            fill_rect(
+                &mut frame,
+                0,
+                band_y,
+                self.width,
+                caption_h,
+                CAPTION_BACKGROUND,
+            );
+            let scale = PxScale::from(caption_h as f32 / 3.2);
+            let padding = caption_h as f32 / 6.0;
+            let lines = wrap_lines(font, scale, &description, self.width as f32 - 2.0 * padding);
+            draw_lines(
+                &mut frame,
+                font,
+                scale,
+                &clamp_lines(font, scale, lines, 2, self.width as f32 - 2.0 * padding),
+                band_y as f32 + padding,
+            );
+        }
+
+        let marker = marker_position_percent(step).map(|(mx, my)| Marker {
+            x: x as i32 + (mx / 100.0 * w as f32).round() as i32,
+            y: y as i32 + (my / 100.0 * h as f32).round() as i32,
+            radius: (self.width.min(self.height) as i32 / 40).clamp(6, 28),
+        });
+        RenderedStep {
+            base: frame,
+            marker,
+        }
+    }
+
+    /// Centered heading and body text; just the step number's disc without a font.
+    fn title_card(&self, heading: &str, body: &[String]) -> RgbaImage {
+        let mut frame = RgbaImage::from_pixel(self.width, self.height, BACKGROUND);
+        let Some(font) = &self.font else {
+            let radius = (self.height as i32 / 8).max(4);
+            let center = (self.width as i32 / 2, self.height as i32 / 2);
+            draw_ring(&mut frame, center, radius, radius, MARKER_COLOR, 1.0);
+            return frame;
+        };
+        let max_width = self.width as f32 * 0.8;
+        let heading_scale = PxScale::from(self.height as f32 / 10.0);
+        let body_scale = PxScale::from(self.height as f32 / 20.0);
+        let heading_lines = wrap_lines(font, heading_scale, heading, max_width);
+        let body_lines: Vec<String> = body
+            .iter()
+            .flat_map(|text| text.lines())
+            .flat_map(|line| wrap_lines(font, body_scale, line, max_width))
+            .collect();
+        let max_body_lines = 8;
+        let body_lines = clamp_lines(font, body_scale, body_lines, max_body_lines, max_width);
+
+        let heading_h = line_height(font, heading_scale) * heading_lines.len() as f32;
+        let gap = if body_lines.is_empty() {
+            0.0
+        } else {
+            self.height as f32 / 24.0
+        };
+        let body_h = line_height(font, body_scale) * body_lines.len() as f32;
+        let top = (self.height as f32 - heading_h - gap - body_h) / 2.0;
+        draw_lines(&mut frame, font, heading_scale, &heading_lines, top);
+        draw_lines(
+            &mut frame,
+            font,
+            body_scale,
+            &body_lines,
+            top + heading_h + gap,
+        );
+        frame
+    }
+}
+
+/// Where an `img_w` x `img_h` image lands when scaled to fit an `area_w` x `area_h`
+/// box and centered in it, as (x, y, width, height).
+fn letterbox(img_w: u32, img_h: u32, area_w: u32, area_h: u32) -> (u32, u32, u32, u32) {
+    let scale = f64::min(
+        f64::from(area_w) / f64::from(img_w.max(1)),
+        f64::from(area_h) / f64::from(img_h.max(1)),
+    );
+    let w = ((f64::from(img_w) * scale).round() as u32).clamp(1, area_w.max(1));
+    let h = ((f64::from(img_h) * scale).round() as u32).clamp(1, area_h.max(1));
+    ((area_w - w) / 2, (area_h - h) / 2, w, h)
+}
+
+/// `base` with the marker drawn; `expanded` is the wide, fainter half of the pulse.
+fn pulse_frame(base: &RgbaImage, marker: Marker, expanded: bool) -> RgbaImage {
+    let mut frame = base.clone();
+    let thickness = (marker.radius / 4).max(2);
+    if expanded {
+        let radius = marker.radius * 8 / 5;
+        draw_ring(
+            &mut frame,
+            (marker.x, marker.y),
+            radius,
+            thickness,
+            MARKER_COLOR,
+            0.5,
+        );
+    } else {
+        draw_ring(
+            &mut frame,
+            (marker.x, marker.y),
+            marker.radius,
+            marker.radius,
+            MARKER_COLOR,
+            0.35,
+        );
+        draw_ring(
+            &mut frame,
+            (marker.x, marker.y),
+            marker.radius,
+            thickness,
+            MARKER_COLOR,
+            1.0,
+        );
+    }
+    frame
+}
+
+/// Blend a ring of `thickness` pixels inside `radius` onto `img`; a thickness of
+/// `radius` fills the disc.
+fn draw_ring(
+    img: &mut RgbaImage,
+    (cx, cy): (i32, i32),
+    radius: i32,
+    thickness: i32,
+    color: Rgba<u8>,
+    alpha: f32,
+) {
+    let outer_sq = radius * radius;
+    let inner = (radius - thickness).max(0);
+    let inner_sq = if inner == 0 { -1 } else { inner * inner };
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let d_sq = dx * dx + dy * dy;
+            if d_sq > outer_sq || d_sq <= inner_sq {
+                continue;
+            }
+            blend_pixel(img, cx + dx, cy + dy, color, alpha);
+        }
+    }
+}
+
+fn fill_rect(img: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32, color: Rgba<u8>) {
+    for py in y..(y + h).min(img.height()) {
+        for px in x..(x + w).min(img.width()) {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn blend_pixel(img: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>, alpha: f32) {
+    if x < 0 || y < 0 || x as u32 >= img.width() || y as u32 >= img.height() {
+        return;
+    }
+    let alpha = alpha.clamp(0.0, 1.0);
+    let pixel = img.get_pixel_mut(x as u32, y as u32);
+    for c in 0..3 {
+        let blended = f32::from(pixel[c]) * (1.0 - alpha) + f32::from(color[c]) * alpha;
+        pixel[c] = blended.round() as u8;
+    }
+}
+
+pub(super) fn line_height(font: &FontVec, scale: PxScale) -> f32 {
+    let scaled = font.as_scaled(scale);
+    scaled.height() + scaled.line_gap()
+}
+
+pub(super) fn text_width(font: &FontVec, scale: PxScale, text: &str) -> f32 {
+    let scaled = font.as_scaled(scale);
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            width += scaled.kern(previous, id);
+        }
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// Greedy word wrap to `max_width` pixels. Words wider than a line get one to themselves.
+fn wrap_lines(font: &FontVec, scale: PxScale, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if !current.is_empty() && text_width(font, scale, &candidate) > max_width {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Keep the first `max` lines, ending the last kept one with "…" when text was dropped.
+fn clamp_lines(
+    font: &FontVec,
+    scale: PxScale,
+    mut lines: Vec<String>,
+    max: usize,
+    max_width: f32,
+) -> Vec<String> {
+    if lines.len() <= max {
+        return lines;
+    }
+    lines.truncate(max);
+    if let Some(last) = lines.last_mut() {
+        while !last.is_empty() && text_width(font, scale, &format!("{last}…")) > max_width {
+            last.pop();
+        }
+        last.push('…');
+    }
+    lines
+}
+
+/// Draw `lines` centered horizontally, the first line's top at `top`.
+fn draw_lines(img: &mut RgbaImage, font: &FontVec, scale: PxScale, lines: &[String], top: f32) {
+    let scaled = font.as_scaled(scale);
+    let mut baseline = top + scaled.ascent();
+    for line in lines {
+        let x = (img.width() as f32 - text_width(font, scale, line)) / 2.0;
+        draw_text(img, font, scale, x.max(0.0), baseline, line);
+        baseline += line_height(font, scale);
+    }
+}
+
+pub(super) fn draw_text(
+    img: &mut RgbaImage,
+    font: &FontVec,
+    scale: PxScale,
+    x: f32,
+    baseline: f32,
+    text: &str,
+) {
+    let scaled = font.as_scaled(scale);
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            blend_pixel(img, px, py, TEXT_COLOR, coverage);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::AnimationOptions;
+    use image::AnimationDecoder;
+
+    #[test]
+    fn letterbox_fits_and_centers() {
+        // Wide image in a 16:9 frame: full width, bars top and bottom.
+        assert_eq!(letterbox(2000, 500, 1280, 720), (0, 200, 1280, 320));
+        // Tall image: full height, bars left and right.
+        assert_eq!(letterbox(500, 1000, 1280, 720), (460, 0, 360, 720));
+        // Small images are scaled up.
+        assert_eq!(letterbox(64, 36, 1280, 720), (0, 0, 1280, 720));
+    }
+
+    #[test]
+    fn pulse_alternates_marker_size() {
+        let base = RgbaImage::from_pixel(100, 100, BACKGROUND);
+        let marker = Marker {
+            x: 50,
+            y: 50,
+            radius: 10,
+        };
+        let small = pulse_frame(&base, marker, false);
+        let large = pulse_frame(&base, marker, true);
+        // The contracted marker is filled; the expanded one is a wider ring.
+        assert_ne!(small.get_pixel(50, 50), &BACKGROUND);
+        assert_eq!(large.get_pixel(50, 50), &BACKGROUND);
+        assert_eq!(small.get_pixel(50, 35), &BACKGROUND);
+        assert_ne!(large.get_pixel(50, 35), &BACKGROUND);
+    }
+
+    #[test]
+    fn wrap_lines_breaks_at_words() {
+        let Some(font) = load_font() else {
+            return;
+        };
+        let scale = PxScale::from(20.0);
+        let text = "Click the Save button in the toolbar";
+        let max_width = text_width(&font, scale, "Click the Save");
+        let lines = wrap_lines(&font, scale, text, max_width);
+        assert_eq!(lines.first().map(String::as_str), Some("Click the Save"));
+        assert_eq!(lines.join(" "), text);
+        assert!(lines
+            .iter()
+            .all(|line| text_width(&font, scale, line) <= max_width));
+
+        let clamped = clamp_lines(&font, scale, lines, 1, max_width);
+        assert_eq!(clamped.len(), 1);
+        assert!(clamped[0].ends_with('…'));
+    }
+
+    #[test]
+    fn writes_pulsing_steps_and_note_cards() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shot = tmp.path().join("step.png");
+        RgbaImage::from_pixel(320, 200, Rgba([200, 100, 50, 255]))
+            .save(&shot)
+            .unwrap();
+        let mut click = Step::sample();
+        click.screenshot_path = Some(shot.to_str().unwrap().to_string());
+        let mut note = Step::sample();
+        note.action = ActionType::Note;
+        note.screenshot_path = None;
+        note.description = Some("Wait for the upload to finish".into());
+
+        let options = ExportOptions {
+            animation: AnimationOptions {
+                width: 160,
+                height: 90,
+                step_hold_ms: 1000,
+                captions: true,
+            },
+            ..ExportOptions::default()
+        };
+        let out = tmp.path().join("guide.gif");
+        write(
+            "Guide",
+            &[click, note],
+            out.to_str().unwrap(),
+            Locale::En,
+            &options,
+            &ProgressReporter::silent(),
+        )
+        .expect("gif written");
+
+        let file = std::io::BufReader::new(std::fs::File::open(&out).unwrap());
+        let frames = image::codecs::gif::GifDecoder::new(file)
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        // Two pulse frames for the click, one title card for the note.
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.buffer().dimensions() == (160, 90)));
+        let delays: Vec<u32> = frames
+            .iter()
+            .map(|f| f.delay().numer_denom_ms().0 / f.delay().numer_denom_ms().1.max(1))
+            .collect();
+        assert_eq!(delays, vec![500, 500, 1000]);
+    }
+
+    #[test]
+    fn preview_is_the_first_frame() {
+        let options = ExportOptions {
+            animation: AnimationOptions {
+                width: 64,
+                height: 36,
+                ..AnimationOptions::default()
+            },
+            ..ExportOptions::default()
+        };
+        let png = first_frame_png("Guide", &[], Locale::De, &options).unwrap();
+        let img = image::load_from_memory(&png).unwrap();
+        assert_eq!((img.width(), img.height()), (64, 36));
+    }
+}
@@ -0,0 +1,177 @@
+//! Machine-readable guide for QA automation: per step the clicked element's full AX
+//! data, the app's bundle ID, the action and the time since the previous action, to
+//! turn recordings into UI test skeletons. Screenshots are left out to keep files small.
+
+use super::{friendly_write_error, ExportError, ProgressReporter};
+use crate::recorder::types::{ActionType, AxClickInfo, Step};
+use serde::Serialize;
+
+/// Bumped when a field changes meaning or goes away; new fields don't bump it.
+pub const AUTOMATION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct AutomationGuide<'a> {
+    pub format_version: u32,
+    pub title: &'a str,
+    pub steps: Vec<AutomationStep<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutomationStep<'a> {
+    /// 1-based, as numbered in the other exports.
+    pub number: usize,
+    pub id: &'a str,
+    pub action: &'a ActionType,
+    pub app: &'a str,
+    pub bundle_id: Option<&'a str>,
+    pub window_title: &'a str,
+    /// Heading set by hand.
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub note: Option<&'a str>,
+    /// Click point in global screen points.
+    pub x: i32,
+    pub y: i32,
+    /// Milliseconds since the Unix epoch.
+    pub ts: i64,
+    /// Milliseconds since the previous action. `None` for the first action and for
+    /// notes and the overview, which don't count as actions.
+    pub delay_ms: Option<i64>,
+    /// Wait set by hand after this step.
+    pub wait_ms: Option<u32>,
+    pub ax: Option<&'a AxClickInfo>,
+}
+
+/// Whether `step` is something the user did, as opposed to a note or the overview.
+fn is_action(step: &Step) -> bool {
+    !matches!(step.action, ActionType::Note | ActionType::Overview)
+}
+
+pub fn guide<'a>(title: &'a str, steps: &'a [Step]) -> AutomationGuide<'a> {
+    let mut previous_ts: Option<i64> = None;
+    let steps = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let delay_ms = if is_action(step) {
+                let delay = previous_ts.map(|prev| (step.ts - prev).max(0));
+                previous_ts = Some(step.ts);
+                delay
+            } else {
+                None
+            };
+            AutomationStep {
+                number: i + 1,
+                id: &step.id,
+                action: &step.action,
+                app: &step.app,
+                bundle_id: step.bundle_id.as_deref(),
+                window_title: &step.window_title,
+                title: step.title.as_deref(),
+                description: step.description.as_deref(),
+                note: step.note.as_deref(),
+                x: step.x,
+                y: step.y,
+                ts: step.ts,
+                delay_ms,
+                wait_ms: step.wait_ms,
+                ax: step.ax.as_ref(),
+            }
+        })
+        .collect();
+    AutomationGuide {
+        format_version: AUTOMATION_FORMAT_VERSION,
+        title,
+        steps,
+    }
+}
+
+/// The guide as pretty-printed JSON.
+pub fn generate(title: &str, steps: &[Step]) -> String {
+    serde_json::to_string_pretty(&guide(title, steps)).unwrap_or_default()
+}
+
+pub fn write(
+    title: &str,
+    steps: &[Step],
+    output_path: &str,
+    progress: &ProgressReporter,
+) -> Result<(), ExportError> {
+    let json = generate(title, steps);
+    for _ in steps {
+        progress.step_done();
+    }
+    std::fs::write(output_path, json).map_err(|e| friendly_write_error(&e, output_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, action: ActionType, ts: i64) -> Step {
+        Step {
+            id: id.to_string(),
+            action,
+            ts,
+            ..Step::sample()
+        }
+    }
+
+    #[test]
+    fn emits_ax_data_and_bundle_id_without_screenshots() {
+        let mut click = step("step-1", ActionType::Click, 1_000);
+        click.bundle_id = Some("com.apple.finder".to_string());
+        click.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXButton",
+            "identifier": "save-button",
+            "label": "Save",
+            "container_identifier": "toolbar",
+            "is_cancel_button": false,
+            "is_default_button": true,
+        }))
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&generate("Guide", &[click])).unwrap();
+        assert_eq!(json["format_version"], AUTOMATION_FORMAT_VERSION);
+        let step = &json["steps"][0];
+        assert_eq!(step["number"], 1);
+        assert_eq!(step["action"], "Click");
+        assert_eq!(step["bundle_id"], "com.apple.finder");
+        assert_eq!(step["ax"]["identifier"], "save-button");
+        assert_eq!(step["ax"]["container_identifier"], "toolbar");
+        assert!(step.get("screenshot_path").is_none());
+        assert!(!generate("Guide", &[]).contains("screenshot"));
+    }
+
+    #[test]
+    fn emits_toggle_state_before_and_after_the_click() {
+        let mut click = step("step-1", ActionType::Click, 1_000);
+        click.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXCheckBox",
+            "label": "Use dark menu bar",
+            "is_checked": false,
+            "was_checked": false,
+            "now_checked": true,
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&generate("Guide", &[click])).unwrap();
+        assert_eq!(json["steps"][0]["ax"]["was_checked"], false);
+        assert_eq!(json["steps"][0]["ax"]["now_checked"], true);
+    }
+
+    #[test]
+    fn delays_count_from_the_previous_action() {
+        let steps = [
+            step("step-1", ActionType::Click, 1_000),
+            step("step-2", ActionType::Note, 9_000),
+            step("step-3", ActionType::DoubleClick, 3_500),
+            step("step-4", ActionType::Click, 3_000),
+        ];
+        let guide = guide("Guide", &steps);
+        let delays: Vec<Option<i64>> = guide.steps.iter().map(|s| s.delay_ms).collect();
+        assert_eq!(delays, [None, None, Some(2_500), Some(0)]);
+    }
+}
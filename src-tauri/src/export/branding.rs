@@ -0,0 +1,302 @@
+//! Team branding for HTML and PDF exports: a logo above the title, an accent color for
+//! step numbers and click markers, a footer line and a font.
+//!
+//! `set_export_branding` copies the logo into the app support folder, so exports keep
+//! working when the original file moves. A logo that has gone missing or no longer
+//! decodes is left out of the export with a warning instead of failing it.
+
+use super::helpers::html_escape;
+use crate::i18n::Locale;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File name of the logo copy, before its extension.
+const LOGO_FILE_STEM: &str = "logo";
+
+/// Branding applied to HTML and PDF exports; the default is StepCast's own styling.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportBranding {
+    /// Logo shown above the guide title.
+    pub logo_path: Option<String>,
+    /// Accent color for step numbers and click markers, as `#rrggbb`.
+    pub primary_color: Option<String>,
+    /// Line at the bottom of HTML guides and on every PDF page.
+    pub footer_text: Option<String>,
+    pub font: BrandingFont,
+}
+
+/// Font family for the guide's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrandingFont {
+    /// The system UI font the default styling uses.
+    #[default]
+    System,
+    Serif,
+    Rounded,
+    Monospace,
+}
+
+impl BrandingFont {
+    /// CSS `font-family` stack; `None` keeps the default.
+    pub(super) fn css_stack(self) -> Option<&'static str> {
+        match self {
+            Self::System => None,
+            Self::Serif => Some("'New York', 'Iowan Old Style', Georgia, serif"),
+            Self::Rounded => Some("ui-rounded, 'SF Pro Rounded', -apple-system, sans-serif"),
+            Self::Monospace => Some("ui-monospace, 'SF Mono', Menlo, monospace"),
+        }
+    }
+}
+
+impl ExportBranding {
+    pub fn footer_text(&self) -> Option<&str> {
+        self.footer_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    fn logo_path(&self) -> Option<&str> {
+        self.logo_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+    }
+
+    /// Check the color and copy the logo into `dir`, returning the branding to persist.
+    /// A logo path already inside `dir` is kept as is.
+    pub fn prepare(mut self, dir: &Path) -> Result<Self, String> {
+        self.primary_color = match self.primary_color.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(color) => Some(
+                normalize_hex_color(color)
+                    .ok_or_else(|| format!("\"{color}\" is not a hex color like #7c5cfc."))?,
+            ),
+        };
+        self.footer_text = self.footer_text().map(str::to_string);
+        self.logo_path = match self.logo_path() {
+            None => None,
+            Some(path) if Path::new(path).parent() == Some(dir) => Some(path.to_string()),
+            Some(path) => Some(install_logo(Path::new(path), dir)?.to_string_lossy().into()),
+        };
+        Ok(self)
+    }
+}
+
+/// Folder in the app support directory holding the branding logo.
+pub fn branding_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("com.w0nk1.stepcast").join("branding"))
+}
+
+/// `#rgb` or `#rrggbb` (the `#` is optional) as lowercase `#rrggbb`.
+pub fn normalize_hex_color(color: &str) -> Option<String> {
+    let hex = color.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let full = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    Some(format!("#{}", full.to_ascii_lowercase()))
+}
+
+/// Copy the image at `source` into `dir` as `logo.<ext>`, replacing an earlier logo.
+fn install_logo(source: &Path, dir: &Path) -> Result<PathBuf, String> {
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("Cannot read {}: {e}", source.display()))?;
+    let format = decodable_format(&bytes)
+        .ok_or_else(|| format!("{} is not a supported image.", source.display()))?;
+    let ext = format.extensions_str().first().copied().unwrap_or("png");
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    remove_logo(dir);
+    let target = dir.join(format!("{LOGO_FILE_STEM}.{ext}"));
+    std::fs::write(&target, bytes).map_err(|e| e.to_string())?;
+    Ok(target)
+}
+
+/// Delete any logo copy in `dir`.
+pub fn remove_logo(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.file_stem().and_then(|s| s.to_str()) == Some(LOGO_FILE_STEM) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn decodable_format(bytes: &[u8]) -> Option<image::ImageFormat> {
+    let format = image::guess_format(bytes).ok()?;
+    image::load_from_memory_with_format(bytes, format).ok()?;
+    Some(format)
+}
+
+/// The logo as a `data:` URL; `Ok(None)` without a logo, `Err` when it cannot be loaded.
+fn load_logo(branding: &ExportBranding) -> Result<Option<String>, String> {
+    let Some(path) = branding.logo_path() else {
+        return Ok(None);
+    };
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let format = decodable_format(&bytes).ok_or("not a supported image")?;
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(Some(format!("data:{};base64,{b64}", format.to_mime_type())))
+}
+
+/// `<img>` for the logo above the guide title; empty without a usable logo.
+pub fn logo_html(branding: &ExportBranding) -> String {
+    load_logo(branding)
+        .ok()
+        .flatten()
+        .map(|src| format!("<img class=\"brand-logo\" src=\"{src}\" alt=\"\">\n"))
+        .unwrap_or_default()
+}
+
+/// Footer line for the end of an HTML guide; empty without footer text.
+pub fn footer_html(branding: &ExportBranding) -> String {
+    branding
+        .footer_text()
+        .map(|text| format!("<p class=\"brand-footer\">{}</p>\n", html_escape(text)))
+        .unwrap_or_default()
+}
+
+/// Style rules layered over the default stylesheet; empty for the default branding.
+pub fn css(branding: &ExportBranding) -> String {
+    let mut css = String::new();
+    if let Some(stack) = branding.font.css_stack() {
+        css.push_str(&format!("body {{ font-family: {stack}; }}\n"));
+    }
+    // Re-validated: the settings file may have been edited by hand.
+    if let Some(color) = branding
+        .primary_color
+        .as_deref()
+        .and_then(normalize_hex_color)
+    {
+        css.push_str(&format!(
+            ".timeline-badge {{ background: {color}; }}\n\
+             .toc-num, .toc a:hover {{ color: {color}; }}\n\
+             .click-marker, .click-marker.double-click::after, .element-highlight {{ border-color: {color}; }}\n"
+        ));
+    }
+    css
+}
+
+/// Problems to report with a finished export: the branding logo that was left out.
+pub fn warnings(branding: &ExportBranding, locale: Locale) -> Vec<String> {
+    match (branding.logo_path(), load_logo(branding)) {
+        (Some(path), Err(error)) => {
+            eprintln!("Branding logo {path} skipped: {error}");
+            vec![crate::i18n::export_branding_logo_skipped(locale, path)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_png(path: &Path) {
+        image::RgbaImage::from_pixel(4, 2, image::Rgba([10, 20, 30, 255]))
+            .save(path)
+            .expect("write png");
+    }
+
+    #[test]
+    fn hex_colors_are_normalized() {
+        assert_eq!(normalize_hex_color("#7C5CFC").as_deref(), Some("#7c5cfc"));
+        assert_eq!(normalize_hex_color("f0a").as_deref(), Some("#ff00aa"));
+        assert_eq!(normalize_hex_color("#12345"), None);
+        assert_eq!(normalize_hex_color("red"), None);
+        assert_eq!(normalize_hex_color("#12345g"), None);
+    }
+
+    #[test]
+    fn prepare_copies_the_logo_and_normalizes_fields() {
+        let src = tempfile::tempdir().expect("create temp dir");
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let logo = src.path().join("Acme Logo.png");
+        write_png(&logo);
+
+        let branding = ExportBranding {
+            logo_path: Some(logo.to_string_lossy().into()),
+            primary_color: Some("#ABC".into()),
+            footer_text: Some("  ".into()),
+            font: BrandingFont::Serif,
+        }
+        .prepare(dir.path())
+        .expect("prepare");
+
+        let copy = dir.path().join("logo.png");
+        assert_eq!(branding.logo_path, Some(copy.to_string_lossy().into()));
+        assert!(copy.exists());
+        assert_eq!(branding.primary_color.as_deref(), Some("#aabbcc"));
+        assert_eq!(branding.footer_text, None);
+
+        // Saving the prepared branding again keeps the copy instead of copying it onto itself.
+        assert_eq!(branding.clone().prepare(dir.path()), Ok(branding));
+    }
+
+    #[test]
+    fn prepare_rejects_bad_colors_and_non_images() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let bad_color = ExportBranding {
+            primary_color: Some("purple".into()),
+            ..ExportBranding::default()
+        };
+        assert!(bad_color.prepare(dir.path()).is_err());
+
+        let src = tempfile::tempdir().expect("create temp dir");
+        let text = src.path().join("notes.txt");
+        std::fs::write(&text, "not an image").expect("write");
+        let not_an_image = ExportBranding {
+            logo_path: Some(text.to_string_lossy().into()),
+            ..ExportBranding::default()
+        };
+        assert!(not_an_image.prepare(dir.path()).is_err());
+    }
+
+    #[test]
+    fn missing_logo_is_left_out_with_a_warning() {
+        let branding = ExportBranding {
+            logo_path: Some("/nonexistent/logo.png".into()),
+            ..ExportBranding::default()
+        };
+        assert_eq!(logo_html(&branding), "");
+        let warnings = warnings(&branding, Locale::En);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("/nonexistent/logo.png"));
+    }
+
+    #[test]
+    fn readable_logo_is_embedded_without_warnings() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let logo = dir.path().join("logo.png");
+        write_png(&logo);
+        let branding = ExportBranding {
+            logo_path: Some(logo.to_string_lossy().into()),
+            ..ExportBranding::default()
+        };
+        assert!(logo_html(&branding)
+            .starts_with(r#"<img class="brand-logo" src="data:image/png;base64,"#));
+        assert!(warnings(&branding, Locale::En).is_empty());
+    }
+
+    #[test]
+    fn default_branding_adds_no_css() {
+        assert_eq!(css(&ExportBranding::default()), "");
+        let css = css(&ExportBranding {
+            primary_color: Some("#0a84ff".into()),
+            font: BrandingFont::Monospace,
+            ..ExportBranding::default()
+        });
+        assert!(css.contains(".timeline-badge { background: #0a84ff; }"));
+        assert!(css.contains("font-family: ui-monospace"));
+    }
+}
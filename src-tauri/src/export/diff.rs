@@ -0,0 +1,89 @@
+//! "What changed" highlight: compare a step's screenshot with the previous step's and
+//! outline the area that differs, e.g. a flipped toggle or a filled-in field.
+
+use image::{Rgba, RgbaImage};
+
+/// Largest per-channel difference still counted as unchanged. Absorbs compression
+/// noise and font smoothing that shifts between captures.
+const CHANNEL_TOLERANCE: u8 = 24;
+
+const CHANGE_BOX_COLOR: Rgba<u8> = Rgba([255, 179, 0, 255]);
+
+/// Bounding box (x, y, w, h) in pixels of everything that differs between `before`
+/// and `after`. `None` when the images differ in size or nothing changed.
+pub fn changed_region(before: &RgbaImage, after: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+    if before.dimensions() != after.dimensions() {
+        return None;
+    }
+    let (mut left, mut top) = (u32::MAX, u32::MAX);
+    let (mut right, mut bottom) = (0, 0);
+    for (x, y, pixel) in after.enumerate_pixels() {
+        let old = before.get_pixel(x, y);
+        let differs = pixel
+            .0
+            .iter()
+            .zip(old.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE);
+        if differs {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x + 1);
+            bottom = bottom.max(y + 1);
+        }
+    }
+    (right > left).then(|| (left, top, right - left, bottom - top))
+}
+
+/// Outline stroke width in pixels, scaled to the image like annotations.
+fn stroke_px(img_w: u32, img_h: u32) -> u32 {
+    (img_w.min(img_h) / 200).clamp(2, 8)
+}
+
+/// Draw a box around the pixel rect (x, y, w, h), padded by one stroke so it doesn't
+/// cover the change itself.
+pub fn draw_change_box(img: &mut RgbaImage, (x, y, w, h): (u32, u32, u32, u32)) {
+    let (img_w, img_h) = img.dimensions();
+    let stroke = stroke_px(img_w, img_h);
+    let left = x.saturating_sub(2 * stroke);
+    let top = y.saturating_sub(2 * stroke);
+    let right = (x + w + 2 * stroke).min(img_w);
+    let bottom = (y + h + 2 * stroke).min(img_h);
+    for py in top..bottom {
+        for px in left..right {
+            let on_edge = px < left + stroke
+                || px + stroke >= right
+                || py < top + stroke
+                || py + stroke >= bottom;
+            if on_edge {
+                img.put_pixel(px, py, CHANGE_BOX_COLOR);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_box_around_a_single_changed_rectangle() {
+        let before = RgbaImage::from_pixel(200, 100, Rgba([240, 240, 240, 255]));
+        let mut after = before.clone();
+        for y in 30..50 {
+            for x in 120..160 {
+                after.put_pixel(x, y, Rgba([52, 199, 89, 255]));
+            }
+        }
+        // Noise below the tolerance is not a change.
+        after.put_pixel(5, 5, Rgba([250, 236, 240, 255]));
+        assert_eq!(changed_region(&before, &after), Some((120, 30, 40, 20)));
+    }
+
+    #[test]
+    fn identical_or_differently_sized_images_have_no_change() {
+        let img = RgbaImage::from_pixel(20, 20, Rgba([0, 0, 0, 255]));
+        assert_eq!(changed_region(&img, &img.clone()), None);
+        let larger = RgbaImage::from_pixel(40, 20, Rgba([255, 255, 255, 255]));
+        assert_eq!(changed_region(&img, &larger), None);
+    }
+}
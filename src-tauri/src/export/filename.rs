@@ -0,0 +1,213 @@
+//! Export file names from a template, for exporting into a folder without a save dialog.
+//!
+//! Placeholders: `{title}`, `{date}` (2024-06-12), `{time}` (14-05) and `{count}`
+//! (number of steps). Unknown placeholders are kept as written.
+
+use super::ExportFormat;
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+/// Used when exporting into a folder without a template.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{title}";
+
+/// Longest file name stem, in characters. Leaves room for the extension and a
+/// " 2" suffix within the usual 255-byte limit, even for multi-byte titles.
+const MAX_STEM_CHARS: usize = 120;
+
+/// Make `name` usable as a file name on macOS and on the file systems guides get
+/// shared over: path separators, `:` and other reserved characters become spaces,
+/// runs of whitespace collapse, and leading dots (hidden files) are dropped.
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let collapsed = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed
+        .trim_start_matches(['.', ' '])
+        .trim_end_matches(['.', ' ']);
+    let truncated: String = trimmed.chars().take(MAX_STEM_CHARS).collect();
+    let truncated = truncated.trim_end_matches(['.', ' ']);
+    if truncated.is_empty() {
+        "Guide".to_string()
+    } else {
+        truncated.to_string()
+    }
+}
+
+/// Expand `template` into a sanitized file name stem.
+pub fn expand_filename_template(
+    template: &str,
+    title: &str,
+    now: NaiveDateTime,
+    step_count: usize,
+) -> String {
+    let expanded = template
+        .replace("{title}", title.trim())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H-%M").to_string())
+        .replace("{count}", &step_count.to_string());
+    sanitize_filename(&expanded)
+}
+
+/// Where to write the export: `output_path` itself when it names a file, or a file
+/// named from `template` inside it when it is a directory. Existing files in the
+/// directory are not overwritten; the name gets a " 2", " 3", … suffix instead.
+pub fn resolve_output_path(
+    output_path: &str,
+    template: Option<&str>,
+    format: ExportFormat,
+    title: &str,
+    now: NaiveDateTime,
+    step_count: usize,
+) -> String {
+    let dir = Path::new(output_path);
+    if !dir.is_dir() {
+        return output_path.to_string();
+    }
+    let template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+    let stem = expand_filename_template(template, title, now, step_count);
+    let ext = format.extension();
+    // Templates may spell out the extension themselves.
+    let stem = stem
+        .strip_suffix(&format!(".{ext}"))
+        .map(str::to_string)
+        .unwrap_or(stem);
+    unique_path(dir, &stem, ext).to_string_lossy().into_owned()
+}
+
+fn unique_path(dir: &Path, stem: &str, ext: &str) -> PathBuf {
+    let mut path = dir.join(format!("{stem}.{ext}"));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem} {n}.{ext}"));
+        n += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::markdown::MarkdownVariant;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").expect("date")
+    }
+
+    #[test]
+    fn expands_all_placeholders() {
+        let name = expand_filename_template(
+            "{date}_{title} ({count} steps) {time}",
+            "Onboarding",
+            at("2024-06-12 09:05"),
+            7,
+        );
+        assert_eq!(name, "2024-06-12_Onboarding (7 steps) 09-05");
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_sanitizes_the_result() {
+        let name = expand_filename_template("{author}/{title}", "VPN", at("2024-06-12 09:05"), 1);
+        assert_eq!(name, "{author} VPN");
+    }
+
+    #[test]
+    fn sanitizes_tricky_titles() {
+        assert_eq!(sanitize_filename("Setup: Wi-Fi/VPN"), "Setup Wi-Fi VPN");
+        assert_eq!(sanitize_filename("a\\b*c?d\"e<f>g|h"), "a b c d e f g h");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "etc passwd");
+        assert_eq!(sanitize_filename(".hidden"), "hidden");
+        assert_eq!(
+            sanitize_filename("Tabs\tand\nnewlines"),
+            "Tabs and newlines"
+        );
+        assert_eq!(
+            sanitize_filename("Ärger mit Ölförderung"),
+            "Ärger mit Ölförderung"
+        );
+        assert_eq!(sanitize_filename("  ...  "), "Guide");
+        assert_eq!(sanitize_filename("///"), "Guide");
+        assert_eq!(sanitize_filename("Ends with dot."), "Ends with dot");
+    }
+
+    #[test]
+    fn sanitize_caps_length_on_char_boundaries() {
+        let long = "ü".repeat(300);
+        let name = sanitize_filename(&long);
+        assert_eq!(name.chars().count(), MAX_STEM_CHARS);
+        assert!(name.len() < 255);
+    }
+
+    #[test]
+    fn resolves_directory_output_to_templated_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().to_str().unwrap();
+        let now = at("2024-06-12 09:05");
+
+        let path = resolve_output_path(
+            out,
+            Some("{date}_{title}"),
+            ExportFormat::Pdf,
+            "Onboarding",
+            now,
+            3,
+        );
+        assert_eq!(
+            path,
+            dir.path()
+                .join("2024-06-12_Onboarding.pdf")
+                .to_str()
+                .unwrap()
+        );
+
+        std::fs::write(&path, b"taken").unwrap();
+        let second = resolve_output_path(
+            out,
+            Some("{date}_{title}.pdf"),
+            ExportFormat::Pdf,
+            "Onboarding",
+            now,
+            3,
+        );
+        assert_eq!(
+            second,
+            dir.path()
+                .join("2024-06-12_Onboarding 2.pdf")
+                .to_str()
+                .unwrap()
+        );
+
+        let default = resolve_output_path(
+            out,
+            None,
+            ExportFormat::Markdown(MarkdownVariant::Standard),
+            "Guide",
+            now,
+            3,
+        );
+        assert_eq!(default, dir.path().join("Guide.zip").to_str().unwrap());
+    }
+
+    #[test]
+    fn keeps_file_output_paths() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("chosen.html");
+        let file = file.to_str().unwrap();
+        let path = resolve_output_path(
+            file,
+            Some("{title}"),
+            ExportFormat::Html,
+            "Other",
+            at("2024-06-12 09:05"),
+            1,
+        );
+        assert_eq!(path, file);
+    }
+}
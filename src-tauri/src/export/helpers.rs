@@ -1,14 +1,9 @@
+use super::{ExportImageFormat, ExportImageOptions, ExportOptions, DEFAULT_EXPORT_QUALITY};
 use crate::i18n::Locale;
-use crate::recorder::types::{ActionType, BoundsPercent, Step};
+use crate::recorder::types::{ActionType, Annotation, BoundsPercent, DragEnd, Step};
 use base64::Engine;
 use std::fs;
 
-/// Check if a step represents an authentication placeholder
-pub fn is_auth_placeholder(step: &Step) -> bool {
-    step.window_title == "Authentication dialog (secure)"
-        || step.app.to_lowercase() == "authentication"
-}
-
 fn normalize_crop_region(crop_region: Option<&BoundsPercent>) -> Option<BoundsPercent> {
     let crop = crop_region?;
     let values = [
@@ -43,6 +38,75 @@ fn normalize_crop_region(crop_region: Option<&BoundsPercent>) -> Option<BoundsPe
     })
 }
 
+/// Capture size in points, from the screenshot's pixel size and scale. `None` for steps
+/// recorded before these were stored.
+fn capture_size_points(step: &Step) -> Option<(f32, f32)> {
+    let scale = step.pixel_scale.filter(|s| s.is_finite() && *s > 0.0)?;
+    let pixels = step.capture_pixels?;
+    let (width, height) = (pixels.width as f32 / scale, pixels.height as f32 / scale);
+    (width >= 1.0 && height >= 1.0).then_some((width, height))
+}
+
+/// Tolerance for percent -> point conversions landing a hair off a whole point.
+const POINT_EPSILON: f32 = 1e-3;
+
+/// The step's crop, widened to whole points of the capture when its scale is known.
+/// Crops on 1x and 2x screenshots then cover the same UI instead of differing by a
+/// rounded pixel at each edge.
+fn effective_crop(step: &Step) -> Option<BoundsPercent> {
+    let crop = normalize_crop_region(step.crop_region.as_ref())?;
+    let Some((width_pt, height_pt)) = capture_size_points(step) else {
+        return Some(crop);
+    };
+    let snap = |start: f32, len: f32, total: f32| {
+        let first = (start / 100.0 * total + POINT_EPSILON).floor().max(0.0);
+        let end = ((start + len) / 100.0 * total - POINT_EPSILON)
+            .ceil()
+            .min(total);
+        (first / total * 100.0, (end - first) / total * 100.0)
+    };
+    let (x_percent, width_percent) = snap(crop.x_percent, crop.width_percent, width_pt);
+    let (y_percent, height_percent) = snap(crop.y_percent, crop.height_percent, height_pt);
+    Some(BoundsPercent {
+        x_percent,
+        y_percent,
+        width_percent,
+        height_percent,
+    })
+}
+
+/// Click position (percent of the capture), moved to the centre of the clicked point
+/// when the capture's scale is known. Recorded positions sit on the point's top-left
+/// corner, which is a full pixel off-centre on Retina captures and none on 1x ones.
+fn click_percent(step: &Step) -> (f32, f32) {
+    let click_x = step.click_x_percent.clamp(0.0, 100.0);
+    let click_y = step.click_y_percent.clamp(0.0, 100.0);
+    let Some((width_pt, height_pt)) = capture_size_points(step) else {
+        return (click_x, click_y);
+    };
+    let centre = |percent: f32, total: f32| {
+        let point = (percent / 100.0 * total + POINT_EPSILON)
+            .floor()
+            .clamp(0.0, (total - 1.0).max(0.0));
+        ((point + 0.5) / total * 100.0).min(100.0)
+    };
+    (centre(click_x, width_pt), centre(click_y, height_pt))
+}
+
+/// Width / height of the step's exported image: its capture area, cropped. `None` when
+/// the capture area is unknown (steps recorded before it was stored).
+pub fn crop_aspect_ratio(step: &Step) -> Option<f32> {
+    let (capture_w, capture_h) = capture_size_points(step).or_else(|| {
+        let bounds = step.capture_bounds.as_ref()?;
+        Some((bounds.width as f32, bounds.height as f32))
+    })?;
+    let (w_percent, h_percent) =
+        effective_crop(step).map_or((100.0, 100.0), |c| (c.width_percent, c.height_percent));
+    let width = capture_w * w_percent;
+    let height = capture_h * h_percent;
+    (width > 0.0 && height > 0.0).then(|| width / height)
+}
+
 fn crop_rect_px(
     img_w: u32,
     img_h: u32,
@@ -59,8 +123,9 @@ fn crop_rect_px(
 
     let w_f = img_w as f64;
     let h_f = img_h as f64;
-    let x = ((crop.x_percent as f64 / 100.0) * w_f).floor() as u32;
-    let y = ((crop.y_percent as f64 / 100.0) * h_f).floor() as u32;
+    // The epsilon keeps crops snapped to whole points from flooring a pixel short.
+    let x = ((crop.x_percent as f64 / 100.0) * w_f + 1e-3).floor() as u32;
+    let y = ((crop.y_percent as f64 / 100.0) * h_f + 1e-3).floor() as u32;
     let mut width = ((crop.width_percent as f64 / 100.0) * w_f).round() as u32;
     let mut height = ((crop.height_percent as f64 / 100.0) * h_f).round() as u32;
 
@@ -83,15 +148,47 @@ fn maybe_crop_image(raw: &[u8], crop_region: Option<&BoundsPercent>) -> Option<V
     Some(out.into_inner())
 }
 
+/// Export size of a `width`×`height` image: at most `image.max_width` wide and
+/// `image.max_dimension` on the long edge, keeping aspect ratio. `None` when it
+/// already fits.
+pub fn downscaled_size(width: u32, height: u32, image: &ExportImageOptions) -> Option<(u32, u32)> {
+    let (w, h) = (f64::from(width.max(1)), f64::from(height.max(1)));
+    let by_width = image.max_width.map_or(1.0, |max| f64::from(max.max(1)) / w);
+    let by_edge = image
+        .max_dimension
+        .map_or(1.0, |max| f64::from(max.max(1)) / w.max(h));
+    let scale = by_width.min(by_edge);
+    if scale >= 1.0 {
+        return None;
+    }
+    let scaled = |side: f64| ((side * scale).round() as u32).max(1);
+    Some((scaled(w), scaled(h)))
+}
+
+/// Downscale with Lanczos to [`downscaled_size`]. `None` when no limit is set or the
+/// image already fits.
+fn maybe_downscale_image(raw: &[u8], image: &ExportImageOptions) -> Option<Vec<u8>> {
+    let img = image::load_from_memory(raw).ok()?;
+    let (width, height) = downscaled_size(img.width(), img.height(), image)?;
+    let resized = img.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
+    let mut out = std::io::Cursor::new(Vec::new());
+    if resized.write_to(&mut out, image::ImageFormat::Png).is_err() {
+        return None;
+    }
+    Some(out.into_inner())
+}
+
 /// Map click marker into cropped image coordinate space.
 /// Returns `None` when marker is outside the crop.
 pub fn marker_position_percent(step: &Step) -> Option<(f32, f32)> {
-    if step.screenshot_path.is_none() || is_auth_placeholder(step) {
+    if step.screenshot_path.is_none()
+        || step.is_auth_placeholder()
+        || step.action == ActionType::Overview
+    {
         return None;
     }
-    let click_x = step.click_x_percent.clamp(0.0, 100.0);
-    let click_y = step.click_y_percent.clamp(0.0, 100.0);
-    let Some(crop) = normalize_crop_region(step.crop_region.as_ref()) else {
+    let (click_x, click_y) = click_percent(step);
+    let Some(crop) = effective_crop(step) else {
         return Some((click_x, click_y));
     };
 
@@ -103,6 +200,92 @@ pub fn marker_position_percent(step: &Step) -> Option<(f32, f32)> {
     Some((x.clamp(0.0, 100.0), y.clamp(0.0, 100.0)))
 }
 
+/// Elements covering more of the image than this (percent of area) are containers
+/// such as web views; a box around them highlights nothing.
+const MAX_HIGHLIGHT_AREA_PERCENT: f32 = 50.0;
+
+/// Map the clicked element's rectangle into cropped image coordinate space, clipped
+/// to the crop. Returns `None` when there is no usable element rect.
+pub fn highlight_rect_percent(step: &Step) -> Option<BoundsPercent> {
+    if step.screenshot_path.is_none() || step.is_auth_placeholder() {
+        return None;
+    }
+    let element = step.ax.as_ref()?.element_bounds.as_ref()?;
+    let rect = rect_in_crop(step, element)?;
+    (rect.width_percent * rect.height_percent / 100.0 <= MAX_HIGHLIGHT_AREA_PERCENT).then_some(rect)
+}
+
+/// Map `element`, in percent of the uncropped capture, into the step's cropped image,
+/// clipped to the crop.
+fn rect_in_crop(step: &Step, element: &BoundsPercent) -> Option<BoundsPercent> {
+    let values = [
+        element.x_percent,
+        element.y_percent,
+        element.width_percent,
+        element.height_percent,
+    ];
+    if values.iter().any(|v| !v.is_finite()) {
+        return None;
+    }
+    let crop = effective_crop(step).unwrap_or(BoundsPercent {
+        x_percent: 0.0,
+        y_percent: 0.0,
+        width_percent: 100.0,
+        height_percent: 100.0,
+    });
+
+    let left = element.x_percent.max(crop.x_percent);
+    let top = element.y_percent.max(crop.y_percent);
+    let right =
+        (element.x_percent + element.width_percent).min(crop.x_percent + crop.width_percent);
+    let bottom =
+        (element.y_percent + element.height_percent).min(crop.y_percent + crop.height_percent);
+    if right <= left || bottom <= top {
+        return None;
+    }
+
+    Some(BoundsPercent {
+        x_percent: (left - crop.x_percent) / crop.width_percent * 100.0,
+        y_percent: (top - crop.y_percent) / crop.height_percent * 100.0,
+        width_percent: (right - left) / crop.width_percent * 100.0,
+        height_percent: (bottom - top) / crop.height_percent * 100.0,
+    })
+}
+
+/// What changed since `previous`'s screenshot, in percent of the step's cropped image.
+/// Only consecutive captures of the same screen area are compared. `None` when nothing
+/// changed or most of the image did, which is a new screen rather than a change.
+pub fn change_rect_percent(step: &Step, previous: &Step) -> Option<BoundsPercent> {
+    if step.is_auth_placeholder() || previous.is_auth_placeholder() {
+        return None;
+    }
+    if step.capture_bounds.is_none() || step.capture_bounds != previous.capture_bounds {
+        return None;
+    }
+    let before = image::open(previous.screenshot_path.as_deref()?)
+        .ok()?
+        .to_rgba8();
+    let after = image::open(step.screenshot_path.as_deref()?)
+        .ok()?
+        .to_rgba8();
+    let (x, y, w, h) = super::diff::changed_region(&before, &after)?;
+    let (img_w, img_h) = (after.width() as f32, after.height() as f32);
+    let changed = BoundsPercent {
+        x_percent: x as f32 / img_w * 100.0,
+        y_percent: y as f32 / img_h * 100.0,
+        width_percent: w as f32 / img_w * 100.0,
+        height_percent: h as f32 / img_h * 100.0,
+    };
+    let rect = rect_in_crop(step, &changed)?;
+    (rect.width_percent * rect.height_percent / 100.0 <= MAX_HIGHLIGHT_AREA_PERCENT).then_some(rect)
+}
+
+/// The step before step `num` (1-based) in `steps`, whose screenshot a change
+/// highlight compares against.
+pub fn previous_step(steps: &[Step], num: usize) -> Option<&Step> {
+    steps.get(num.checked_sub(2)?)
+}
+
 /// Human-readable description of what happened in a step
 #[allow(dead_code)]
 pub fn action_description(step: &Step) -> String {
@@ -111,24 +294,76 @@ pub fn action_description(step: &Step) -> String {
 
 /// Localized human-readable description of what happened in a step.
 pub fn action_description_localized(step: &Step, locale: Locale) -> String {
-    if is_auth_placeholder(step) {
+    if step.is_auth_placeholder() {
         return crate::i18n::auth_placeholder_description(locale).to_string();
     }
 
+    if let Some(end) = step
+        .drag_end
+        .as_ref()
+        .filter(|end| drag_changes_place(step, end))
+    {
+        return crate::i18n::step_action_dragged_from_to(
+            locale,
+            &place(&step.app, &step.window_title),
+            &place(&end.app, &end.window_title),
+        );
+    }
+
+    if let Some(kind) = step
+        .window_control()
+        .filter(|_| step.action == ActionType::Click)
+    {
+        return crate::i18n::step_action_window_control(locale, kind);
+    }
+
+    if let Some(keys) = step
+        .keys
+        .as_deref()
+        .filter(|_| step.action == ActionType::Shortcut)
+    {
+        return format!(
+            "{} in {}",
+            crate::i18n::step_action_press_keys(locale, keys),
+            place(&step.app, &step.window_title)
+        );
+    }
+
     match step.action {
         ActionType::Note => crate::i18n::step_action_note(locale).to_string(),
+        ActionType::Overview if step.app.is_empty() => {
+            crate::i18n::step_action_overview(locale).to_string()
+        }
+        ActionType::Overview => format!(
+            "{}: {}",
+            crate::i18n::step_action_overview(locale),
+            place(&step.app, &step.window_title)
+        ),
         _ => {
             let verb = match step.action {
                 ActionType::DoubleClick => crate::i18n::step_action_double_clicked_in(locale),
                 ActionType::RightClick => crate::i18n::step_action_right_clicked_in(locale),
                 ActionType::Shortcut => crate::i18n::step_action_shortcut_in(locale),
+                ActionType::Drag => crate::i18n::step_action_dragged_in(locale),
+                ActionType::Hover => crate::i18n::step_action_pointed_in(locale),
                 _ => crate::i18n::step_action_clicked_in(locale),
             };
-            format!("{} {} \u{2014} \"{}\"", verb, step.app, step.window_title)
+            format!("{} {}", verb, place(&step.app, &step.window_title))
         }
     }
 }
 
+/// True when a drag was released over a different app or window than it started in.
+fn drag_changes_place(step: &Step, end: &DragEnd) -> bool {
+    step.action == ActionType::Drag
+        && !end.app.is_empty()
+        && (end.app != step.app || end.window_title != step.window_title)
+}
+
+fn place(app: &str, window_title: &str) -> String {
+    format!("{app} \u{2014} \"{window_title}\"")
+}
+
 /// Prefer enhanced description if present, otherwise fall back to `action_description`.
 #[allow(dead_code)]
 pub fn effective_description(step: &Step) -> String {
@@ -138,7 +373,7 @@ pub fn effective_description(step: &Step) -> String {
 /// Prefer enhanced description if present, otherwise use localized baseline text.
 pub fn effective_description_localized(step: &Step, locale: Locale) -> String {
     let desc = step.description.as_deref().unwrap_or("").trim();
-    if is_auth_placeholder(step) && (desc.is_empty() || is_auth_placeholder_description(desc)) {
+    if step.is_auth_placeholder() && (desc.is_empty() || is_auth_placeholder_description(desc)) {
         return action_description_localized(step, locale);
     }
     if !desc.is_empty() {
@@ -147,6 +382,95 @@ pub fn effective_description_localized(step: &Step, locale: Locale) -> String {
     action_description_localized(step, locale)
 }
 
+/// Menu breadcrumb recorded for a menu-item click ("File ▸ Export ▸ PDF").
+pub fn menu_path(step: &Step) -> Option<&str> {
+    step.ax
+        .as_ref()?
+        .menu_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+}
+
+/// Page URL recorded for a click in a browser, when it is a web address.
+pub fn page_url(step: &Step) -> Option<&str> {
+    step.page_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+}
+
+/// What a screen reader announces for the step's clicked element: its name, taken from
+/// the accessibility label, else the visible title, else the help tag, followed by
+/// the role ("“Save”, button"). `None` when the element has no name at all.
+pub fn screen_reader_announcement(step: &Step) -> Option<String> {
+    let ax = step.ax.as_ref()?;
+    fn non_empty(s: Option<&str>) -> Option<&str> {
+        s.map(str::trim).filter(|s| !s.is_empty())
+    }
+    let name = non_empty(ax.accessibility_label.as_deref())
+        .or_else(|| non_empty(Some(&ax.label)))
+        .or_else(|| non_empty(ax.help.as_deref()))?;
+    Some(match non_empty(ax.role_description.as_deref()) {
+        Some(role) => format!("\u{201C}{name}\u{201D}, {role}"),
+        None => format!("\u{201C}{name}\u{201D}"),
+    })
+}
+
+/// Short title for a step in the table of contents: its own title when set, else the
+/// first line of its description when one was written or generated, else the window
+/// title, else the baseline text.
+pub fn step_title(step: &Step, locale: Locale) -> String {
+    if let Some(title) = &step.title {
+        return title.clone();
+    }
+    let has_description = !step.description.as_deref().unwrap_or("").trim().is_empty();
+    let window_title = step.window_title.trim();
+    if !has_description && !window_title.is_empty() && !step.is_auth_placeholder() {
+        return window_title.to_string();
+    }
+    let desc = effective_description_localized(step, locale);
+    desc.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or(desc.trim())
+        .to_string()
+}
+
+/// PDF table-of-contents entry: the step's title, else the first line of its
+/// description, else "Step N — <app>".
+pub fn pdf_toc_title(step: &Step, num: usize, locale: Locale) -> String {
+    if let Some(title) = &step.title {
+        return title.clone();
+    }
+    let desc = step.description.as_deref().unwrap_or("");
+    match desc.lines().map(str::trim).find(|l| !l.is_empty()) {
+        Some(line) => line.to_string(),
+        None => crate::i18n::export_toc_step_fallback(locale, num, &step.app),
+    }
+}
+
+/// Time since the first step as "+MM:SS", or "+H:MM:SS" past an hour. Timestamps are
+/// in milliseconds; a step that sorts before the first (reordered steps) shows "+00:00".
+pub fn format_relative_time(first_ts: i64, step_ts: i64) -> String {
+    let secs = step_ts.saturating_sub(first_ts).max(0) / 1000;
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("+{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("+{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Relative time of every step, measured from the first step's `ts`.
+pub fn relative_times(steps: &[Step]) -> Vec<String> {
+    let first_ts = steps.first().map_or(0, |s| s.ts);
+    steps
+        .iter()
+        .map(|s| format_relative_time(first_ts, s.ts))
+        .collect()
+}
+
 fn is_auth_placeholder_description(desc: &str) -> bool {
     let normalized = desc.trim();
     normalized == crate::i18n::auth_placeholder_description(Locale::En)
@@ -176,11 +500,7 @@ pub fn to_webp_or_png(png_bytes: &[u8]) -> OptimizedImage {
             }
         }
     }
-    OptimizedImage {
-        bytes: png_bytes.to_vec(),
-        mime: "image/png",
-        ext: "png",
-    }
+    png_image(png_bytes)
 }
 
 /// Target format for image optimization.
@@ -192,6 +512,29 @@ pub enum ImageTarget {
     Pdf,
 }
 
+fn encode_for_target(source: &[u8], target: ImageTarget) -> OptimizedImage {
+    match target {
+        ImageTarget::Web => to_webp_or_png(source),
+        ImageTarget::Pdf => to_jpeg(source),
+    }
+}
+
+fn encode_with_options(
+    source: &[u8],
+    target: ImageTarget,
+    image: &ExportImageOptions,
+) -> OptimizedImage {
+    match (image.format, target) {
+        (ExportImageFormat::Png, _) => png_image(source),
+        (ExportImageFormat::Jpeg, _) | (_, ImageTarget::Pdf) => {
+            to_jpeg_with_quality(source, image.quality)
+        }
+        (ExportImageFormat::Auto | ExportImageFormat::WebP, ImageTarget::Web) => {
+            to_webp_or_png(source)
+        }
+    }
+}
+
 /// Load a screenshot and return optimized bytes + MIME/ext.
 pub fn load_screenshot_optimized_image(
     path: &str,
@@ -201,34 +544,504 @@ pub fn load_screenshot_optimized_image(
     let raw = fs::read(path).ok()?;
     let cropped = maybe_crop_image(&raw, crop_region);
     let source = cropped.as_deref().unwrap_or(&raw);
-    let img = match target {
-        ImageTarget::Web => to_webp_or_png(source),
-        ImageTarget::Pdf => to_jpeg(source),
+    Some(encode_for_target(source, target))
+}
+
+/// Load a step's screenshot for export: crop, downscale, annotations, optional change
+/// highlight and number badge, then encode.
+///
+/// Shared by HTML, PDF and Markdown so every format renders the same pixels. Works on
+/// an in-memory copy; the session screenshot is never written.
+pub fn load_step_image(
+    step: &Step,
+    previous: Option<&Step>,
+    num: usize,
+    target: ImageTarget,
+    options: &ExportOptions,
+) -> Option<OptimizedImage> {
+    let path = step.screenshot_path.as_deref()?;
+    let raw = fs::read(path).ok()?;
+    let cropped = maybe_crop_image(&raw, effective_crop(step).as_ref());
+    let mut source = cropped.unwrap_or(raw);
+    // Before the badge, so it keeps its legible size on downscaled Retina captures.
+    if let Some(scaled) = maybe_downscale_image(&source, &options.image) {
+        source = scaled;
+    }
+    if let Some(annotated) = stamp_annotations(&source, &step.annotations) {
+        source = annotated;
+    }
+    if let Some(highlighted) = stamp_change_box(&source, step, previous, options) {
+        source = highlighted;
+    }
+    if options.number_badges {
+        if let Some(stamped) = stamp_step_badge(&source, num, marker_position_percent(step)) {
+            source = stamped;
+        }
+    }
+    Some(encode_with_options(&source, target, &options.image))
+}
+
+/// Load a step's screenshot as pixels: cropped and badged like [`load_step_image`],
+/// but neither downscaled nor encoded. For exports that compose their own frames.
+pub fn load_step_pixels(
+    step: &Step,
+    previous: Option<&Step>,
+    num: usize,
+    options: &ExportOptions,
+) -> Option<image::RgbaImage> {
+    let path = step.screenshot_path.as_deref()?;
+    let raw = fs::read(path).ok()?;
+    let cropped = maybe_crop_image(&raw, effective_crop(step).as_ref());
+    let mut source = cropped.unwrap_or(raw);
+    if let Some(annotated) = stamp_annotations(&source, &step.annotations) {
+        source = annotated;
+    }
+    if let Some(highlighted) = stamp_change_box(&source, step, previous, options) {
+        source = highlighted;
+    }
+    if options.number_badges {
+        if let Some(stamped) = stamp_step_badge(&source, num, marker_position_percent(step)) {
+            source = stamped;
+        }
+    }
+    Some(image::load_from_memory(&source).ok()?.to_rgba8())
+}
+
+/// Load a step's result frame for export: downscaled and encoded like the screenshot,
+/// but never cropped or badged. `None` when the step has none or it can't be read.
+pub fn load_result_image(
+    step: &Step,
+    target: ImageTarget,
+    options: &ExportOptions,
+) -> Option<OptimizedImage> {
+    let raw = fs::read(step.result_screenshot_path.as_deref()?).ok()?;
+    let source = maybe_downscale_image(&raw, &options.image).unwrap_or(raw);
+    Some(encode_with_options(&source, target, &options.image))
+}
+
+/// How many of the images [`load_step_image`] and [`load_result_image`] would export
+/// for `steps` get scaled down. Reads only the image headers; screenshots are measured
+/// after cropping, like the export does.
+pub fn downscaled_image_count(steps: &[Step], options: &ExportOptions) -> usize {
+    if options.text_only {
+        return 0;
+    }
+    let oversized = |size: Option<(u32, u32)>| {
+        size.is_some_and(|(w, h)| downscaled_size(w, h, &options.image).is_some())
     };
-    Some(img)
+    steps
+        .iter()
+        .map(|step| {
+            let screenshot = step.screenshot_path.as_deref().and_then(|path| {
+                let (w, h) = image::image_dimensions(path).ok()?;
+                Some(
+                    crop_rect_px(w, h, effective_crop(step).as_ref())
+                        .map_or((w, h), |(_, _, cw, ch)| (cw, ch)),
+                )
+            });
+            let result = step
+                .result_screenshot_path
+                .as_deref()
+                .and_then(|path| image::image_dimensions(path).ok());
+            usize::from(oversized(screenshot)) + usize::from(oversized(result))
+        })
+        .sum()
 }
 
-/// Load a screenshot, convert to optimized format, return base64 + MIME.
-pub fn load_screenshot_optimized(
-    path: &str,
+/// Same as `load_result_image`, returned as base64 + MIME for data URIs.
+pub fn load_result_image_base64(
+    step: &Step,
     target: ImageTarget,
-    crop_region: Option<&BoundsPercent>,
+    options: &ExportOptions,
 ) -> Option<(String, &'static str)> {
-    let img = load_screenshot_optimized_image(path, target, crop_region)?;
+    let img = load_result_image(step, target, options)?;
     Some((
         base64::engine::general_purpose::STANDARD.encode(&img.bytes),
         img.mime,
     ))
 }
 
+/// Same as `load_step_image`, returned as base64 + MIME for data URIs.
+pub fn load_step_image_base64(
+    step: &Step,
+    previous: Option<&Step>,
+    num: usize,
+    target: ImageTarget,
+    options: &ExportOptions,
+) -> Option<(String, &'static str)> {
+    let img = load_step_image(step, previous, num, target, options)?;
+    Some((
+        base64::engine::general_purpose::STANDARD.encode(&img.bytes),
+        img.mime,
+    ))
+}
+
+const BADGE_FILL: image::Rgba<u8> = image::Rgba([124, 92, 252, 255]);
+const BADGE_RING: image::Rgba<u8> = image::Rgba([255, 255, 255, 255]);
+
+/// 3x5 bitmap glyphs for digits 0-9 (one row per entry, MSB = left column).
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Badge radius in pixels, scaled to the image so it stays legible on Retina captures.
+fn badge_radius_px(img_w: u32, img_h: u32) -> i32 {
+    let short_side = img_w.min(img_h) as i32;
+    (short_side / 30).clamp(12, 28).min((short_side / 2).max(1))
+}
+
+/// Pick the badge center for a click at `(click_x, click_y)` (pixels).
+///
+/// Tries the four diagonals around the click (upper-right first), clamped inside
+/// the image, and takes the first one that leaves the click target uncovered.
+/// Without a click point the badge goes to the top-left corner.
+pub fn badge_center_px(
+    img_w: u32,
+    img_h: u32,
+    click: Option<(i32, i32)>,
+    radius: i32,
+) -> (i32, i32) {
+    let max_x = (img_w as i32 - radius).max(radius);
+    let max_y = (img_h as i32 - radius).max(radius);
+    let clamp = |x: i32, y: i32| (x.clamp(radius, max_x), y.clamp(radius, max_y));
+
+    let Some((cx, cy)) = click else {
+        return clamp(radius + 8, radius + 8);
+    };
+
+    // Keep the click point clear of the badge plus a small gap for the click marker.
+    let clearance = radius + radius / 2 + 6;
+    let offset = clearance + 2;
+    let candidates = [
+        (cx + offset, cy - offset),
+        (cx - offset, cy - offset),
+        (cx + offset, cy + offset),
+        (cx - offset, cy + offset),
+    ];
+
+    let dist_sq = |(x, y): (i32, i32)| {
+        let dx = (x - cx) as i64;
+        let dy = (y - cy) as i64;
+        dx * dx + dy * dy
+    };
+    let min_dist_sq = (clearance as i64) * (clearance as i64);
+
+    let clamped: Vec<(i32, i32)> = candidates.iter().map(|&(x, y)| clamp(x, y)).collect();
+    clamped
+        .iter()
+        .copied()
+        .find(|&c| dist_sq(c) >= min_dist_sq)
+        .unwrap_or_else(|| {
+            clamped
+                .iter()
+                .copied()
+                .max_by_key(|&c| dist_sq(c))
+                .unwrap_or_else(|| clamp(cx, cy))
+        })
+}
+
+fn draw_disc(img: &mut image::RgbaImage, cx: i32, cy: i32, radius: i32, color: image::Rgba<u8>) {
+    let r_sq = radius * radius;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy > r_sq {
+                continue;
+            }
+            let (x, y) = (cx + dx, cy + dy);
+            if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+                img.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+fn draw_number(img: &mut image::RgbaImage, cx: i32, cy: i32, radius: i32, num: usize) {
+    let digits: Vec<usize> = num
+        .to_string()
+        .chars()
+        .filter_map(|c| c.to_digit(10).map(|d| d as usize))
+        .collect();
+    let count = digits.len() as i32;
+    // Glyph cell is 3 wide + 1 spacing; fit the text into ~1.2x radius.
+    let max_text_w = radius * 6 / 5;
+    let scale = (radius / 5).min(max_text_w / (count * 4 - 1).max(1)).max(1);
+    let text_w = (count * 4 - 1) * scale;
+    let text_h = 5 * scale;
+    let origin_x = cx - text_w / 2;
+    let origin_y = cy - text_h / 2;
+
+    for (i, digit) in digits.iter().enumerate() {
+        let glyph = DIGIT_GLYPHS[*digit];
+        let glyph_x = origin_x + i as i32 * 4 * scale;
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        let x = glyph_x + col * scale + sx;
+                        let y = origin_y + row as i32 * scale + sy;
+                        if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height()
+                        {
+                            img.put_pixel(x as u32, y as u32, BADGE_RING);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Composite a numbered step badge onto PNG bytes. `marker` is the click
+/// position in percent of the (already cropped) image.
+fn stamp_step_badge(raw: &[u8], num: usize, marker: Option<(f32, f32)>) -> Option<Vec<u8>> {
+    let mut img = image::load_from_memory(raw).ok()?.to_rgba8();
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return None;
+    }
+    let radius = badge_radius_px(w, h);
+    let click = marker.map(|(x, y)| {
+        (
+            ((x as f64 / 100.0) * w as f64).round() as i32,
+            ((y as f64 / 100.0) * h as f64).round() as i32,
+        )
+    });
+    let (cx, cy) = badge_center_px(w, h, click, radius);
+    let ring = (radius / 8).max(2);
+    draw_disc(&mut img, cx, cy, radius, BADGE_RING);
+    draw_disc(&mut img, cx, cy, radius - ring, BADGE_FILL);
+    draw_number(&mut img, cx, cy, radius - ring, num);
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(img)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+const ANNOTATION_COLOR: image::Rgba<u8> = image::Rgba([255, 59, 48, 255]);
+
+/// Annotation stroke width in pixels, scaled to the image like the badge.
+fn annotation_stroke_px(img_w: u32, img_h: u32) -> f32 {
+    (img_w.min(img_h) / 200).clamp(2, 8) as f32
+}
+
+fn percent_to_px(img: &image::RgbaImage, x: f32, y: f32) -> (f32, f32) {
+    (
+        x / 100.0 * img.width() as f32,
+        y / 100.0 * img.height() as f32,
+    )
+}
+
+/// Paint every pixel within `half_width` of the segment `a`-`b`.
+fn fill_segment(img: &mut image::RgbaImage, a: (f32, f32), b: (f32, f32), half_width: f32) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let min_x = (a.0.min(b.0) - half_width).floor().max(0.0) as u32;
+    let min_y = (a.1.min(b.1) - half_width).floor().max(0.0) as u32;
+    let max_x = ((a.0.max(b.0) + half_width).ceil() as u32).min(img.width());
+    let max_y = ((a.1.max(b.1) + half_width).ceil() as u32).min(img.height());
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let t = if len_sq > 0.0 {
+                (((px - a.0) * dx + (py - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (cx, cy) = (a.0 + t * dx - px, a.1 + t * dy - py);
+            if cx * cx + cy * cy <= half_width * half_width {
+                img.put_pixel(x, y, ANNOTATION_COLOR);
+            }
+        }
+    }
+}
+
+fn fill_triangle(img: &mut image::RgbaImage, corners: [(f32, f32); 3]) {
+    let cross = |a: (f32, f32), b: (f32, f32), p: (f32, f32)| {
+        (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+    };
+    let [a, b, c] = corners;
+    let min_x = a.0.min(b.0).min(c.0).floor().max(0.0) as u32;
+    let min_y = a.1.min(b.1).min(c.1).floor().max(0.0) as u32;
+    let max_x = (a.0.max(b.0).max(c.0).ceil() as u32).min(img.width());
+    let max_y = (a.1.max(b.1).max(c.1).ceil() as u32).min(img.height());
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            let (d1, d2, d3) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+            let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+            let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+            if !(has_neg && has_pos) {
+                img.put_pixel(x, y, ANNOTATION_COLOR);
+            }
+        }
+    }
+}
+
+/// Shaft ending at the base of a filled head whose tip is at `to`.
+fn draw_arrow(img: &mut image::RgbaImage, from: (f32, f32), to: (f32, f32), stroke: f32) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 {
+        return;
+    }
+    let (ux, uy) = (dx / len, dy / len);
+    let head_len = (stroke * 5.0).min(len);
+    let half_head = stroke * 3.0;
+    let base = (to.0 - ux * head_len, to.1 - uy * head_len);
+    fill_segment(img, from, base, stroke / 2.0);
+    fill_triangle(
+        img,
+        [
+            to,
+            (base.0 - uy * half_head, base.1 + ux * half_head),
+            (base.0 + uy * half_head, base.1 - ux * half_head),
+        ],
+    );
+}
+
+/// Label with its top-left corner at `pos`, white text on the annotation color.
+/// Skipped when no font is available.
+fn draw_label(
+    img: &mut image::RgbaImage,
+    font: Option<&ab_glyph::FontVec>,
+    pos: (f32, f32),
+    text: &str,
+    stroke: f32,
+) {
+    use ab_glyph::{Font, PxScale, ScaleFont};
+
+    let Some(font) = font else {
+        return;
+    };
+    let scale = PxScale::from(stroke * 8.0);
+    let padding = stroke * 2.0;
+    let width = super::animation::text_width(font, scale, text) + 2.0 * padding;
+    let height = super::animation::line_height(font, scale) + 2.0 * padding;
+    // Keep the label inside the image when placed near the right or bottom edge.
+    let x = pos.0.min(img.width() as f32 - width).max(0.0);
+    let y = pos.1.min(img.height() as f32 - height).max(0.0);
+    for py in (y as u32)..((y + height) as u32).min(img.height()) {
+        for px in (x as u32)..((x + width) as u32).min(img.width()) {
+            img.put_pixel(px, py, ANNOTATION_COLOR);
+        }
+    }
+    let baseline = y + padding + font.as_scaled(scale).ascent();
+    super::animation::draw_text(img, font, scale, x + padding, baseline, text);
+}
+
+/// Draw a step's annotations onto its (cropped) screenshot.
+pub fn draw_annotations(img: &mut image::RgbaImage, annotations: &[Annotation]) {
+    let stroke = annotation_stroke_px(img.width(), img.height());
+    let needs_font = annotations
+        .iter()
+        .any(|a| matches!(a, Annotation::Text { .. }));
+    let font = needs_font.then(super::animation::load_font).flatten();
+    for annotation in annotations {
+        match annotation {
+            Annotation::Arrow { from, to } => {
+                let from = percent_to_px(img, from.x, from.y);
+                let to = percent_to_px(img, to.x, to.y);
+                draw_arrow(img, from, to, stroke);
+            }
+            Annotation::Rect { bounds } => {
+                let (x0, y0) = percent_to_px(img, bounds.x_percent, bounds.y_percent);
+                let (x1, y1) = percent_to_px(
+                    img,
+                    bounds.x_percent + bounds.width_percent,
+                    bounds.y_percent + bounds.height_percent,
+                );
+                let half = stroke / 2.0;
+                for (a, b) in [
+                    ((x0, y0), (x1, y0)),
+                    ((x1, y0), (x1, y1)),
+                    ((x1, y1), (x0, y1)),
+                    ((x0, y1), (x0, y0)),
+                ] {
+                    fill_segment(img, a, b, half);
+                }
+            }
+            Annotation::Text { pos, content } => {
+                let pos = percent_to_px(img, pos.x, pos.y);
+                draw_label(img, font.as_ref(), pos, content, stroke);
+            }
+        }
+    }
+}
+
+/// Bake annotations into PNG bytes. `None` when there are none or decoding fails.
+fn stamp_annotations(raw: &[u8], annotations: &[Annotation]) -> Option<Vec<u8>> {
+    if annotations.is_empty() {
+        return None;
+    }
+    let mut img = image::load_from_memory(raw).ok()?.to_rgba8();
+    draw_annotations(&mut img, annotations);
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(img)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+/// Outline what changed since `previous` on PNG bytes of the step's cropped image.
+/// `None` when the highlight is off or there is nothing to outline.
+fn stamp_change_box(
+    raw: &[u8],
+    step: &Step,
+    previous: Option<&Step>,
+    options: &ExportOptions,
+) -> Option<Vec<u8>> {
+    if !options.diff_highlight {
+        return None;
+    }
+    let rect = change_rect_percent(step, previous?)?;
+    let mut img = image::load_from_memory(raw).ok()?.to_rgba8();
+    let (x0, y0) = percent_to_px(&img, rect.x_percent, rect.y_percent);
+    let (x1, y1) = percent_to_px(
+        &img,
+        rect.x_percent + rect.width_percent,
+        rect.y_percent + rect.height_percent,
+    );
+    let (x0, y0) = (x0.floor() as u32, y0.floor() as u32);
+    let (x1, y1) = (x1.ceil() as u32, y1.ceil() as u32);
+    super::diff::draw_change_box(
+        &mut img,
+        (x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0)),
+    );
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(img)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
 /// Convert raw PNG bytes to JPEG at quality 85. Falls back to PNG on failure.
 pub fn to_jpeg(png_bytes: &[u8]) -> OptimizedImage {
+    to_jpeg_with_quality(png_bytes, DEFAULT_EXPORT_QUALITY)
+}
+
+/// Convert raw PNG bytes to JPEG at `quality` (clamped to 1-100). Falls back to PNG on
+/// failure.
+pub fn to_jpeg_with_quality(png_bytes: &[u8], quality: u8) -> OptimizedImage {
     use image::ImageEncoder;
     if let Ok(img) = image::load_from_memory(png_bytes) {
         // JPEG doesn't support alpha — convert RGBA to RGB
         let rgb = img.to_rgb8();
         let mut buf = std::io::Cursor::new(Vec::new());
-        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 85);
+        let encoder =
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality.clamp(1, 100));
         if encoder
             .write_image(
                 rgb.as_raw(),
@@ -245,6 +1058,10 @@ pub fn to_jpeg(png_bytes: &[u8]) -> OptimizedImage {
             };
         }
     }
+    png_image(png_bytes)
+}
+
+fn png_image(png_bytes: &[u8]) -> OptimizedImage {
     OptimizedImage {
         bytes: png_bytes.to_vec(),
         mime: "image/png",
@@ -269,7 +1086,24 @@ pub fn slugify_title(title: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::recorder::types::{ActionType, Step};
+    use crate::recorder::types::{ActionType, PixelSize, Step};
+
+    #[test]
+    fn relative_time_formats_seconds_minutes_and_hours() {
+        assert_eq!(format_relative_time(1_000, 1_000), "+00:00");
+        assert_eq!(format_relative_time(0, 42_999), "+00:42");
+        assert_eq!(format_relative_time(0, 59_000), "+00:59");
+        assert_eq!(format_relative_time(0, 60_000), "+01:00");
+        assert_eq!(format_relative_time(0, 3_599_000), "+59:59");
+        assert_eq!(format_relative_time(0, 3_600_000), "+1:00:00");
+        assert_eq!(format_relative_time(0, 3_725_000), "+1:02:05");
+    }
+
+    #[test]
+    fn relative_time_clamps_out_of_order_steps() {
+        assert_eq!(format_relative_time(10_000, 4_000), "+00:00");
+        assert_eq!(format_relative_time(i64::MAX, i64::MIN), "+00:00");
+    }
 
     fn sample_step() -> Step {
         Step {
@@ -292,6 +1126,22 @@ mod tests {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
         }
     }
 
@@ -334,6 +1184,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn action_description_shortcut_names_the_keys() {
+        let mut s = sample_step();
+        s.action = ActionType::Shortcut;
+        s.keys = Some("⇧⌘N".to_string());
+        assert_eq!(
+            action_description(&s),
+            "Press ⇧⌘N in Finder \u{2014} \"Downloads\""
+        );
+        assert_eq!(
+            action_description_localized(&s, Locale::De),
+            "Drücke ⇧⌘N in Finder \u{2014} \"Downloads\""
+        );
+    }
+
     #[test]
     fn action_description_note() {
         let mut s = sample_step();
@@ -342,6 +1207,23 @@ mod tests {
         assert_eq!(action_description(&s), "Note");
     }
 
+    #[test]
+    fn overview_step_is_an_intro_without_marker() {
+        let mut s = sample_step();
+        s.action = ActionType::Overview;
+        s.screenshot_path = Some("/tmp/step-001.png".into());
+        assert_eq!(
+            action_description(&s),
+            "Screen overview: Finder \u{2014} \"Downloads\""
+        );
+        assert_eq!(marker_position_percent(&s), None);
+        s.app.clear();
+        assert_eq!(
+            action_description_localized(&s, Locale::De),
+            "Bildschirmübersicht"
+        );
+    }
+
     #[test]
     fn action_description_auth_placeholder() {
         let mut s = sample_step();
@@ -352,6 +1234,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn action_description_drag() {
+        let mut s = sample_step();
+        s.action = ActionType::Drag;
+        assert_eq!(
+            action_description(&s),
+            "Dragged in Finder \u{2014} \"Downloads\""
+        );
+
+        s.drag_end = Some(DragEnd {
+            x: 400,
+            y: 300,
+            x_percent: 60.0,
+            y_percent: 40.0,
+            app: "Mail".into(),
+            window_title: "New Message".into(),
+        });
+        assert_eq!(
+            action_description(&s),
+            "Dragged from Finder \u{2014} \"Downloads\" to Mail \u{2014} \"New Message\""
+        );
+        assert_eq!(
+            action_description_localized(&s, Locale::De),
+            "Gezogen von Finder \u{2014} \"Downloads\" nach Mail \u{2014} \"New Message\""
+        );
+
+        // Released in the same window: nothing to add beyond where it happened.
+        s.drag_end = s.drag_end.map(|end| DragEnd {
+            app: "Finder".into(),
+            window_title: "Downloads".into(),
+            ..end
+        });
+        assert_eq!(
+            action_description(&s),
+            "Dragged in Finder \u{2014} \"Downloads\""
+        );
+    }
+
+    #[test]
+    fn action_description_auth_by_flag() {
+        let mut s = sample_step();
+        s.is_secure_placeholder = true;
+        s.window_title = "Authentifizierungsdialog".into();
+        assert_eq!(
+            action_description(&s),
+            "Authenticate with Touch ID or enter your password to continue."
+        );
+    }
+
+    #[test]
+    fn step_title_prefers_description_then_window_title() {
+        let mut s = sample_step();
+        s.window_title = "Downloads".into();
+        assert_eq!(step_title(&s, Locale::En), "Downloads");
+        s.description = Some("Open the Downloads folder\nthen pick a file".into());
+        assert_eq!(step_title(&s, Locale::En), "Open the Downloads folder");
+        s.description = None;
+        s.window_title = "  ".into();
+        assert_eq!(step_title(&s, Locale::En), action_description(&s));
+    }
+
+    #[test]
+    fn pdf_toc_title_falls_back_to_step_and_app() {
+        let mut s = sample_step();
+        assert_eq!(pdf_toc_title(&s, 4, Locale::En), "Step 4 \u{2014} Finder");
+        assert_eq!(
+            pdf_toc_title(&s, 4, Locale::De),
+            "Schritt 4 \u{2014} Finder"
+        );
+        s.description = Some("  \n Open the Downloads folder\nthen sort by date".into());
+        assert_eq!(
+            pdf_toc_title(&s, 4, Locale::En),
+            "Open the Downloads folder"
+        );
+    }
+
     #[test]
     fn action_description_auth_by_app() {
         let mut s = sample_step();
@@ -388,6 +1346,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn close_button_click_gets_window_control_phrasing() {
+        let mut s = sample_step();
+        s.ax = Some(
+            serde_json::from_str(
+                r#"{"role": "AXButton", "subrole": "AXCloseButton", "label": "",
+                    "is_cancel_button": false, "is_default_button": false}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(effective_description(&s), "Click the window's Close button");
+        assert_eq!(
+            effective_description_localized(&s, crate::i18n::Locale::De),
+            "Klicke auf die Schaltfläche „Schließen“ des Fensters"
+        );
+
+        // A written description still wins; other actions keep the baseline.
+        s.description = Some("Close the preview".into());
+        assert_eq!(effective_description(&s), "Close the preview");
+        s.description = None;
+        s.action = ActionType::RightClick;
+        assert!(effective_description(&s).starts_with("Right-clicked in Finder"));
+    }
+
     #[test]
     fn html_escape_special_chars() {
         assert_eq!(html_escape("a < b & c > d"), "a &lt; b &amp; c &gt; d");
@@ -418,19 +1400,6 @@ mod tests {
         assert_eq!(slugify_title("Hello World! (2026)"), "hello-world-2026");
     }
 
-    #[test]
-    fn is_auth_placeholder_checks() {
-        let mut s = sample_step();
-        assert!(!is_auth_placeholder(&s));
-
-        s.window_title = "Authentication dialog (secure)".into();
-        assert!(is_auth_placeholder(&s));
-
-        s.window_title = "Normal".into();
-        s.app = "Authentication".into();
-        assert!(is_auth_placeholder(&s));
-    }
-
     #[test]
     fn to_webp_or_png_converts_valid_png() {
         // Create a small 2x2 red PNG in memory
@@ -456,9 +1425,10 @@ mod tests {
     }
 
     #[test]
-    fn load_screenshot_optimized_missing_file() {
+    fn load_screenshot_optimized_image_missing_file() {
         assert!(
-            load_screenshot_optimized("/nonexistent/file.png", ImageTarget::Web, None).is_none()
+            load_screenshot_optimized_image("/nonexistent/file.png", ImageTarget::Web, None)
+                .is_none()
         );
     }
 
@@ -486,6 +1456,175 @@ mod tests {
         assert_eq!(marker_position_percent(&s), Some((50.0, 50.0)));
     }
 
+    fn step_at_scale(scale: f32) -> Step {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/x.png".into());
+        s.pixel_scale = Some(scale);
+        s.capture_pixels = Some(PixelSize {
+            width: (1000.0 * scale) as u32,
+            height: (600.0 * scale) as u32,
+        });
+        // 334.6 pt: rounds to different points at 1x and 2x without snapping.
+        s.click_x_percent = 33.46;
+        s.click_y_percent = 50.0;
+        s.crop_region = Some(BoundsPercent {
+            x_percent: 30.06,
+            y_percent: 10.0,
+            width_percent: 40.0,
+            height_percent: 50.0,
+        });
+        s
+    }
+
+    #[test]
+    fn crops_and_markers_match_across_display_scales() {
+        let crop_points = |s: &Step| {
+            let pixels = s.capture_pixels.unwrap();
+            let scale = s.pixel_scale.unwrap();
+            let (x, y, w, h) =
+                crop_rect_px(pixels.width, pixels.height, effective_crop(s).as_ref()).unwrap();
+            [x, y, w, h].map(|v| v as f32 / scale)
+        };
+        let (external, retina) = (step_at_scale(1.0), step_at_scale(2.0));
+        assert_eq!(crop_points(&external), [300.0, 60.0, 401.0, 300.0]);
+        assert_eq!(crop_points(&retina), crop_points(&external));
+
+        let marker = marker_position_percent(&retina).unwrap();
+        assert_eq!(marker, marker_position_percent(&external).unwrap());
+        // Centre of point (334, 300): 34.5 pt into the 401 pt wide crop, 240.5 pt into
+        // the 300 pt tall one.
+        assert!((marker.0 - 34.5 / 401.0 * 100.0).abs() < 1e-3);
+        assert!((marker.1 - 240.5 / 300.0 * 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn crop_aspect_ratio_prefers_measured_capture_size() {
+        let mut s = step_at_scale(2.0);
+        s.capture_bounds = None;
+        s.crop_region = None;
+        assert_eq!(crop_aspect_ratio(&s), Some(1000.0 / 600.0));
+
+        s.pixel_scale = None;
+        assert_eq!(crop_aspect_ratio(&s), None);
+    }
+
+    fn step_with_names(label: &str, accessibility_label: Option<&str>, help: Option<&str>) -> Step {
+        let mut s = sample_step();
+        s.ax = Some(
+            serde_json::from_value(serde_json::json!({
+                "role": "AXButton",
+                "role_description": "button",
+                "label": label,
+                "accessibility_label": accessibility_label,
+                "help": help,
+                "is_cancel_button": false,
+                "is_default_button": false,
+            }))
+            .unwrap(),
+        );
+        s
+    }
+
+    #[test]
+    fn screen_reader_announcement_falls_back_from_label_to_title_to_help() {
+        let announce = |s: Step| screen_reader_announcement(&s);
+        assert_eq!(
+            announce(step_with_names(
+                "Save",
+                Some("Save document"),
+                Some("Saves")
+            ))
+            .as_deref(),
+            Some("\u{201C}Save document\u{201D}, button")
+        );
+        assert_eq!(
+            announce(step_with_names("Save", Some("  "), Some("Saves"))).as_deref(),
+            Some("\u{201C}Save\u{201D}, button")
+        );
+        assert_eq!(
+            announce(step_with_names("", None, Some("Saves the document"))).as_deref(),
+            Some("\u{201C}Saves the document\u{201D}, button")
+        );
+        assert_eq!(announce(step_with_names(" ", None, None)), None);
+        assert_eq!(announce(sample_step()), None);
+    }
+
+    fn step_with_element(x: f32, y: f32, w: f32, h: f32) -> Step {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/x.png".into());
+        s.ax = Some(
+            serde_json::from_value(serde_json::json!({
+                "role": "AXButton",
+                "label": "OK",
+                "element_bounds": {
+                    "x_percent": x,
+                    "y_percent": y,
+                    "width_percent": w,
+                    "height_percent": h,
+                },
+                "is_cancel_button": false,
+                "is_default_button": false,
+            }))
+            .unwrap(),
+        );
+        s
+    }
+
+    #[test]
+    fn highlight_rect_percent_maps_into_crop() {
+        let mut s = step_with_element(40.0, 40.0, 10.0, 5.0);
+        assert_eq!(
+            highlight_rect_percent(&s),
+            Some(BoundsPercent {
+                x_percent: 40.0,
+                y_percent: 40.0,
+                width_percent: 10.0,
+                height_percent: 5.0,
+            })
+        );
+
+        s.crop_region = Some(BoundsPercent {
+            x_percent: 20.0,
+            y_percent: 20.0,
+            width_percent: 40.0,
+            height_percent: 40.0,
+        });
+        assert_eq!(
+            highlight_rect_percent(&s),
+            Some(BoundsPercent {
+                x_percent: 50.0,
+                y_percent: 50.0,
+                width_percent: 25.0,
+                height_percent: 12.5,
+            })
+        );
+    }
+
+    #[test]
+    fn highlight_rect_percent_skips_missing_outside_and_huge_elements() {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/x.png".into());
+        assert_eq!(highlight_rect_percent(&s), None);
+
+        let mut s = step_with_element(80.0, 80.0, 10.0, 10.0);
+        s.crop_region = Some(BoundsPercent {
+            x_percent: 0.0,
+            y_percent: 0.0,
+            width_percent: 50.0,
+            height_percent: 50.0,
+        });
+        assert_eq!(highlight_rect_percent(&s), None);
+
+        assert_eq!(
+            highlight_rect_percent(&step_with_element(0.0, 0.0, 90.0, 90.0)),
+            None
+        );
+
+        let mut s = step_with_element(40.0, 40.0, 10.0, 5.0);
+        s.is_secure_placeholder = true;
+        assert_eq!(highlight_rect_percent(&s), None);
+    }
+
     #[test]
     fn load_screenshot_optimized_image_applies_crop() {
         use tempfile::TempDir;
@@ -512,6 +1651,368 @@ mod tests {
         assert_eq!(decoded.height(), 50);
     }
 
+    fn assert_badge_clear_of_click(w: u32, h: u32, click: (i32, i32)) {
+        let radius = badge_radius_px(w, h);
+        let (cx, cy) = badge_center_px(w, h, Some(click), radius);
+        assert!(
+            cx - radius >= 0 && cx + radius <= w as i32,
+            "x out of bounds"
+        );
+        assert!(
+            cy - radius >= 0 && cy + radius <= h as i32,
+            "y out of bounds"
+        );
+        let dx = (cx - click.0) as f64;
+        let dy = (cy - click.1) as f64;
+        assert!(
+            (dx * dx + dy * dy).sqrt() > radius as f64,
+            "badge at ({cx}, {cy}) covers click {click:?}"
+        );
+    }
+
+    #[test]
+    fn badge_center_clamps_at_image_corners() {
+        let (w, h) = (800, 600);
+        assert_badge_clear_of_click(w, h, (0, 0));
+        assert_badge_clear_of_click(w, h, (800, 0));
+        assert_badge_clear_of_click(w, h, (0, 600));
+        assert_badge_clear_of_click(w, h, (800, 600));
+    }
+
+    #[test]
+    fn badge_center_clamps_at_image_edges() {
+        let (w, h) = (1440, 900);
+        assert_badge_clear_of_click(w, h, (720, 0));
+        assert_badge_clear_of_click(w, h, (720, 900));
+        assert_badge_clear_of_click(w, h, (0, 450));
+        assert_badge_clear_of_click(w, h, (1440, 450));
+    }
+
+    #[test]
+    fn badge_center_prefers_upper_right_of_click() {
+        let radius = badge_radius_px(800, 600);
+        let (cx, cy) = badge_center_px(800, 600, Some((400, 300)), radius);
+        assert!(cx > 400);
+        assert!(cy < 300);
+    }
+
+    #[test]
+    fn badge_center_without_click_uses_top_left() {
+        let radius = badge_radius_px(800, 600);
+        let (cx, cy) = badge_center_px(800, 600, None, radius);
+        assert!(cx < 100 && cy < 100);
+    }
+
+    #[test]
+    fn load_step_image_stamps_badge_when_enabled() {
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let img = image::RgbaImage::from_pixel(400, 300, image::Rgba([0, 0, 0, 255]));
+        let img_path = tmp.path().join("shot.png");
+        img.save(&img_path).unwrap();
+
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+
+        let plain = load_step_image(&s, None, 3, ImageTarget::Web, &ExportOptions::default())
+            .expect("plain image");
+        let plain = image::load_from_memory(&plain.bytes).unwrap().to_rgba8();
+        assert!(plain.pixels().all(|p| p.0 == [0, 0, 0, 255]));
+
+        let options = ExportOptions {
+            number_badges: true,
+            ..ExportOptions::default()
+        };
+        let badged =
+            load_step_image(&s, None, 3, ImageTarget::Web, &options).expect("badged image");
+        let badged = image::load_from_memory(&badged.bytes).unwrap().to_rgba8();
+        assert_eq!(badged.dimensions(), (400, 300));
+        assert!(badged.pixels().any(|p| p.0 != [0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn change_highlight_outlines_what_changed_since_the_previous_step() {
+        use crate::recorder::types::CaptureBounds;
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let before = image::RgbaImage::from_pixel(400, 300, image::Rgba([0, 0, 0, 255]));
+        let mut after = before.clone();
+        for y in 150..180 {
+            for x in 200..240 {
+                after.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+            }
+        }
+        let (before_path, after_path) = (tmp.path().join("a.png"), tmp.path().join("b.png"));
+        before.save(&before_path).unwrap();
+        after.save(&after_path).unwrap();
+
+        let bounds = CaptureBounds {
+            x: 0,
+            y: 0,
+            width: 400,
+            height: 300,
+            display_width: 1440,
+            display_height: 900,
+        };
+        let mut previous = sample_step();
+        previous.screenshot_path = Some(before_path.to_str().unwrap().to_string());
+        previous.capture_bounds = Some(bounds);
+        let mut s = sample_step();
+        s.screenshot_path = Some(after_path.to_str().unwrap().to_string());
+        s.capture_bounds = Some(bounds);
+
+        let rect = change_rect_percent(&s, &previous).expect("changed area");
+        assert_eq!(
+            (
+                rect.x_percent,
+                rect.y_percent,
+                rect.width_percent,
+                rect.height_percent
+            ),
+            (50.0, 50.0, 10.0, 10.0)
+        );
+
+        let options = ExportOptions {
+            diff_highlight: true,
+            ..ExportOptions::default()
+        };
+        let out = load_step_image(&s, Some(&previous), 2, ImageTarget::Web, &options).unwrap();
+        let out = image::load_from_memory(&out.bytes).unwrap().to_rgba8();
+        let outlined = |p: &image::Rgba<u8>| p.0 != [0, 0, 0, 255] && p.0 != [255, 255, 255, 255];
+        assert!(out.pixels().any(outlined));
+
+        // A capture of another screen area is not compared.
+        previous.capture_bounds = Some(CaptureBounds { x: 50, ..bounds });
+        assert!(change_rect_percent(&s, &previous).is_none());
+        let out = load_step_image(&s, Some(&previous), 2, ImageTarget::Web, &options).unwrap();
+        let out = image::load_from_memory(&out.bytes).unwrap().to_rgba8();
+        assert!(!out.pixels().any(outlined));
+    }
+
+    #[test]
+    fn arrow_annotation_draws_shaft_and_head_at_known_coordinates() {
+        use crate::recorder::types::PointPercent;
+
+        let white = image::Rgba([255, 255, 255, 255]);
+        let mut img = image::RgbaImage::from_pixel(200, 100, white);
+        draw_annotations(
+            &mut img,
+            &[Annotation::Arrow {
+                from: PointPercent { x: 10.0, y: 50.0 },
+                to: PointPercent { x: 90.0, y: 50.0 },
+            }],
+        );
+        // Stroke 2px: shaft from x=20 to the head base at x=170, head tip at x=180.
+        assert_eq!(*img.get_pixel(100, 50), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(20, 50), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(100, 45), white);
+        // The head is wider than the shaft and narrows toward the tip.
+        assert_eq!(*img.get_pixel(171, 45), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(178, 50), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(178, 45), white);
+        assert_eq!(*img.get_pixel(185, 50), white);
+        assert_eq!(*img.get_pixel(15, 50), white);
+    }
+
+    #[test]
+    fn rect_annotation_outlines_without_filling() {
+        let white = image::Rgba([255, 255, 255, 255]);
+        let mut img = image::RgbaImage::from_pixel(200, 100, white);
+        draw_annotations(
+            &mut img,
+            &[Annotation::Rect {
+                bounds: BoundsPercent {
+                    x_percent: 25.0,
+                    y_percent: 20.0,
+                    width_percent: 50.0,
+                    height_percent: 60.0,
+                },
+            }],
+        );
+        // Corners at (50, 20) and (150, 80).
+        assert_eq!(*img.get_pixel(100, 20), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(150, 50), ANNOTATION_COLOR);
+        assert_eq!(*img.get_pixel(100, 50), white);
+        assert_eq!(*img.get_pixel(40, 50), white);
+    }
+
+    #[test]
+    fn load_step_image_bakes_in_annotations() {
+        use crate::recorder::types::PointPercent;
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let img = image::RgbaImage::from_pixel(400, 300, image::Rgba([0, 0, 0, 255]));
+        let img_path = tmp.path().join("shot.png");
+        img.save(&img_path).unwrap();
+
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+        s.annotations = vec![Annotation::Arrow {
+            from: PointPercent { x: 10.0, y: 10.0 },
+            to: PointPercent { x: 50.0, y: 50.0 },
+        }];
+
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &ExportOptions::default())
+            .expect("annotated image");
+        let out = image::load_from_memory(&out.bytes).unwrap().to_rgba8();
+        assert_eq!(*out.get_pixel(120, 90), ANNOTATION_COLOR);
+        // The session screenshot keeps its pixels.
+        let stored = image::open(&img_path).unwrap().to_rgba8();
+        assert!(stored.pixels().all(|p| p.0 == [0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn load_step_image_downscales_wide_screenshots() {
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let img = image::RgbaImage::from_pixel(3200, 1800, image::Rgba([40, 80, 120, 255]));
+        let img_path = tmp.path().join("retina.png");
+        img.save(&img_path).unwrap();
+        let original = fs::read(&img_path).unwrap();
+
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &ExportOptions::default())
+            .expect("exported image");
+        let decoded = image::load_from_memory(&out.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (1600, 900));
+        // The session screenshot itself is left alone.
+        assert_eq!(fs::read(&img_path).unwrap(), original);
+
+        let options = ExportOptions {
+            image: ExportImageOptions {
+                max_width: None,
+                max_dimension: None,
+                quality: 60,
+                format: ExportImageFormat::Jpeg,
+            },
+            ..ExportOptions::default()
+        };
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &options).expect("full size");
+        assert_eq!(out.mime, "image/jpeg");
+        let decoded = image::load_from_memory(&out.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (3200, 1800));
+    }
+
+    #[test]
+    fn long_edge_cap_applies_after_cropping() {
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let img_path = tmp.path().join("5k.png");
+        image::RgbaImage::from_pixel(5120, 1440, image::Rgba([40, 80, 120, 255]))
+            .save(&img_path)
+            .unwrap();
+        let options = ExportOptions {
+            image: ExportImageOptions {
+                max_width: None,
+                ..ExportImageOptions::default()
+            },
+            ..ExportOptions::default()
+        };
+
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &options).expect("exported image");
+        let decoded = image::load_from_memory(&out.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2560, 720));
+        assert_eq!(downscaled_image_count(&[s.clone()], &options), 1);
+
+        // A crop within the cap keeps its full resolution.
+        s.crop_region = Some(BoundsPercent {
+            x_percent: 0.0,
+            y_percent: 0.0,
+            width_percent: 25.0,
+            height_percent: 50.0,
+        });
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &options).expect("exported crop");
+        let decoded = image::load_from_memory(&out.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (1280, 720));
+        assert_eq!(downscaled_image_count(&[s], &options), 0);
+    }
+
+    #[test]
+    fn long_edge_cap_downscales_tall_captures() {
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let img_path = tmp.path().join("tall.png");
+        image::RgbaImage::from_pixel(1200, 3200, image::Rgba([40, 80, 120, 255]))
+            .save(&img_path)
+            .unwrap();
+        // Narrower than the width cap, so only the long edge limits it.
+        let options = ExportOptions::default();
+
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+        let out = load_step_image(&s, None, 1, ImageTarget::Web, &options).expect("exported image");
+        let decoded = image::load_from_memory(&out.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (960, 2560));
+        assert_eq!(downscaled_image_count(&[s], &options), 1);
+    }
+
+    #[test]
+    fn downscaled_size_honors_both_limits() {
+        let opts = |max_width, max_dimension| ExportImageOptions {
+            max_width,
+            max_dimension,
+            ..ExportImageOptions::default()
+        };
+        assert_eq!(
+            downscaled_size(1000, 3000, &opts(None, Some(2560))),
+            Some((853, 2560))
+        );
+        assert_eq!(
+            downscaled_size(3000, 1000, &opts(Some(1600), Some(2560))),
+            Some((1600, 533))
+        );
+        assert_eq!(downscaled_size(2000, 1000, &opts(None, Some(2560))), None);
+        assert_eq!(downscaled_size(6000, 4000, &opts(None, None)), None);
+    }
+
+    #[test]
+    fn maybe_downscale_image_keeps_narrow_images() {
+        let img = image::RgbaImage::from_pixel(1200, 700, image::Rgba([0, 0, 0, 255]));
+        let mut png_buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut png_buf, image::ImageFormat::Png).unwrap();
+        let png_bytes = png_buf.into_inner();
+        let width = |max_width| ExportImageOptions {
+            max_width,
+            ..ExportImageOptions::default()
+        };
+        assert!(maybe_downscale_image(&png_bytes, &width(Some(1600))).is_none());
+        assert!(maybe_downscale_image(&png_bytes, &width(None)).is_none());
+        let scaled = maybe_downscale_image(&png_bytes, &width(Some(600))).expect("downscaled");
+        let scaled = image::load_from_memory(&scaled).unwrap();
+        assert_eq!((scaled.width(), scaled.height()), (600, 350));
+    }
+
+    #[test]
+    fn encode_with_options_respects_format_and_target() {
+        let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([200, 10, 10, 255]));
+        let mut png_buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut png_buf, image::ImageFormat::Png).unwrap();
+        let png_bytes = png_buf.into_inner();
+        let with = |format| ExportImageOptions {
+            format,
+            ..ExportImageOptions::default()
+        };
+
+        let png = encode_with_options(&png_bytes, ImageTarget::Web, &with(ExportImageFormat::Png));
+        assert_eq!(png.mime, "image/png");
+        let jpeg =
+            encode_with_options(&png_bytes, ImageTarget::Web, &with(ExportImageFormat::Jpeg));
+        assert_eq!(jpeg.mime, "image/jpeg");
+        // PDFs embed JPEG natively, so WebP falls back to it there.
+        let pdf = encode_with_options(&png_bytes, ImageTarget::Pdf, &with(ExportImageFormat::WebP));
+        assert_eq!(pdf.mime, "image/jpeg");
+    }
+
     #[test]
     fn to_jpeg_converts_valid_png() {
         let img = image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]));
@@ -1,9 +1,44 @@
 use super::helpers::{
-    effective_description_localized, html_escape, load_screenshot_optimized,
-    marker_position_percent, ImageTarget,
+    effective_description_localized, highlight_rect_percent, html_escape, load_result_image,
+    load_result_image_base64, load_step_image, load_step_image_base64, marker_position_percent,
+    menu_path, page_url, pdf_toc_title, previous_step, relative_times, screen_reader_announcement,
+    step_title, ImageTarget,
 };
+use super::{branding, ExportError, ExportOptions, ProgressReporter};
 use crate::i18n::Locale;
 use crate::recorder::types::{ActionType, Step};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write as _};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Archive folder holding the screenshots of a zipped HTML guide.
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+
+/// How HTML guides lay out each step. PDFs always use the full layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlLayout {
+    /// Text above a full-width screenshot.
+    #[default]
+    Full,
+    /// Small thumbnail on the left, text on the right, for guides meant to be skimmed.
+    Compact,
+}
+
+impl std::str::FromStr for HtmlLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "full" => Ok(Self::Full),
+            "compact" => Ok(Self::Compact),
+            other => Err(format!("Unknown HTML layout: {other}")),
+        }
+    }
+}
 
 /// Generate a self-contained HTML document from steps.
 #[allow(dead_code)]
@@ -12,6 +47,7 @@ pub fn generate(title: &str, steps: &[Step]) -> String {
 }
 
 /// Generate a self-contained localized HTML document from steps.
+#[allow(dead_code)]
 pub fn generate_localized(title: &str, steps: &[Step], locale: Locale) -> String {
     generate_for_locale(title, steps, ImageTarget::Web, locale)
 }
@@ -29,11 +65,142 @@ pub fn generate_for_locale(
     target: ImageTarget,
     locale: Locale,
 ) -> String {
+    generate_with_options(title, steps, target, locale, &ExportOptions::default())
+}
+
+/// Generate localized HTML with a specific image target and export options.
+pub fn generate_with_options(
+    title: &str,
+    steps: &[Step],
+    target: ImageTarget,
+    locale: Locale,
+    options: &ExportOptions,
+) -> String {
+    generate_with_progress(
+        title,
+        steps,
+        target,
+        locale,
+        options,
+        &ProgressReporter::silent(),
+    )
+}
+
+/// Same as [`generate_with_options`], reporting each embedded step to `progress`.
+pub fn generate_with_progress(
+    title: &str,
+    steps: &[Step],
+    target: ImageTarget,
+    locale: Locale,
+    options: &ExportOptions,
+    progress: &ProgressReporter,
+) -> String {
+    render_document(
+        title,
+        steps,
+        target,
+        locale,
+        options,
+        |num, step| {
+            let previous = previous_step(steps, num);
+            let src = load_step_image_base64(step, previous, num, target, options)
+                .map(|(b64, mime)| format!("data:{mime};base64,{b64}"));
+            progress.step_done();
+            src
+        },
+        |_, step| {
+            load_result_image_base64(step, target, options)
+                .map(|(b64, mime)| format!("data:{mime};base64,{b64}"))
+        },
+    )
+}
+
+/// Generate localized HTML that references screenshots by relative path instead of
+/// embedding them. `image_srcs[i]` is the path for step `i`, `result_srcs[i]` the path
+/// of its result frame.
+pub fn generate_with_image_srcs(
+    title: &str,
+    steps: &[Step],
+    locale: Locale,
+    options: &ExportOptions,
+    image_srcs: &[Option<String>],
+    result_srcs: &[Option<String>],
+) -> String {
+    render_document(
+        title,
+        steps,
+        ImageTarget::Web,
+        locale,
+        options,
+        |num, _| image_srcs.get(num - 1).cloned().flatten(),
+        |num, _| result_srcs.get(num - 1).cloned().flatten(),
+    )
+}
+
+/// `image_src` and `result_src` return the `src` for a step's screenshot and result
+/// frame, given its 1-based number.
+fn render_document(
+    title: &str,
+    steps: &[Step],
+    target: ImageTarget,
+    locale: Locale,
+    options: &ExportOptions,
+    image_src: impl Fn(usize, &Step) -> Option<String>,
+    result_src: impl Fn(usize, &Step) -> Option<String>,
+) -> String {
+    let times = options.elapsed_time.then(|| relative_times(steps));
+    let layout = match target {
+        ImageTarget::Web => options.html_layout,
+        ImageTarget::Pdf => HtmlLayout::Full,
+    };
     let steps_html: String = steps
         .iter()
         .enumerate()
-        .map(|(i, step)| render_step(i + 1, step, target, locale))
+        .map(|(i, step)| {
+            let elapsed = times.as_ref().map(|t| t[i].as_str());
+            let images = if options.text_only {
+                None
+            } else {
+                Some((image_src(i + 1, step), result_src(i + 1, step)))
+            };
+            render_step(i + 1, step, images, elapsed, layout, locale, options)
+        })
         .collect();
+    let header_html = match target {
+        ImageTarget::Web => {
+            let toc_html = if options.include_toc {
+                render_toc(steps, locale, |step, _| step_title(step, locale))
+            } else {
+                String::new()
+            };
+            format!(
+                "{}<h1>{}</h1>\n{}<p class=\"subtitle\">{}</p>\n{toc_html}",
+                branding::logo_html(&options.branding),
+                html_escape(title),
+                render_guide_meta(locale, options),
+                crate::i18n::export_step_count(locale, steps.len()),
+            )
+        }
+        ImageTarget::Pdf => render_pdf_header(title, steps, locale, options),
+    };
+    let (body_class, pdf_css) = match target {
+        ImageTarget::Web => ("", ""),
+        ImageTarget::Pdf => (r#" class="pdf""#, PDF_CSS),
+    };
+    let layout_css = match layout {
+        HtmlLayout::Full => "",
+        HtmlLayout::Compact => COMPACT_CSS,
+    };
+    // PDF pages carry the footer text and credit in their page footers instead.
+    let credit_html = match target {
+        ImageTarget::Web => format!(
+            "{}<footer class=\"credit\">{} · {}</footer>\n",
+            branding::footer_html(&options.branding),
+            crate::i18n::export_recorded_with(locale),
+            crate::i18n::export_date(locale, chrono::Local::now().date_naive()),
+        ),
+        ImageTarget::Pdf => String::new(),
+    };
 
     format!(
         r#"<!doctype html>
@@ -42,79 +209,433 @@ pub fn generate_for_locale(
 <meta charset="utf-8">
 <meta name="viewport" content="width=device-width, initial-scale=1">
 <title>{title_esc}</title>
-<style>
-{css}
+{meta_tags}<style>
+{css}{pdf_css}{layout_css}{brand_css}
 </style>
 </head>
-<body>
+<body{body_class}>
 <div class="container">
-<h1>{title_esc}</h1>
-<p class="subtitle">{step_count}</p>
-<div class="timeline">
+{header_html}<div class="timeline">
 {steps_html}
 </div>
-</div>
+{credit_html}</div>
 </body>
 </html>"#,
         html_lang = locale.as_html_lang(),
         title_esc = html_escape(title),
+        meta_tags = render_meta_tags(options),
         css = CSS,
-        step_count = crate::i18n::export_step_count(locale, steps.len()),
+        brand_css = branding::css(&options.branding),
         steps_html = steps_html,
     )
 }
 
-fn render_step(num: usize, step: &Step, target: ImageTarget, locale: Locale) -> String {
-    let desc = html_escape(&effective_description_localized(step, locale));
+/// Byline and tags under the guide title; empty without session metadata.
+fn render_guide_meta(locale: Locale, options: &ExportOptions) -> String {
+    let byline = crate::i18n::export_byline(locale, options.author.as_deref(), options.recorded_on)
+        .map(|line| format!("<p class=\"byline\">{}</p>\n", html_escape(&line)))
+        .unwrap_or_default();
+    if options.tags.is_empty() {
+        return byline;
+    }
+    let tags: String = options
+        .tags
+        .iter()
+        .map(|tag| format!("<li>{}</li>", html_escape(tag)))
+        .collect();
+    format!("{byline}<ul class=\"tags\">{tags}</ul>\n")
+}
+
+/// `<meta>` author and keywords for the document head.
+fn render_meta_tags(options: &ExportOptions) -> String {
+    let mut tags = String::new();
+    if let Some(author) = &options.author {
+        tags.push_str(&format!(
+            "<meta name=\"author\" content=\"{}\">\n",
+            html_escape(author)
+        ));
+    }
+    if !options.tags.is_empty() {
+        tags.push_str(&format!(
+            "<meta name=\"keywords\" content=\"{}\">\n",
+            html_escape(&options.tags.join(", "))
+        ));
+    }
+    tags
+}
+
+/// PDF title block: guide title, step count and generation date. With a table of
+/// contents it becomes a cover page of its own, followed by a page break.
+fn render_pdf_header(
+    title: &str,
+    steps: &[Step],
+    locale: Locale,
+    options: &ExportOptions,
+) -> String {
+    let (class, toc_html) = if options.include_toc {
+        (
+            "pdf-header pdf-cover",
+            render_toc(steps, locale, |step, num| pdf_toc_title(step, num, locale)),
+        )
+    } else {
+        ("pdf-header", String::new())
+    };
+    format!(
+        r#"<header class="{class}">
+{logo_html}<h1>{title}</h1>
+{meta_html}<p class="subtitle">{step_count}</p>
+<p class="generated">{generated}</p>
+{toc_html}</header>
+"#,
+        logo_html = branding::logo_html(&options.branding),
+        title = html_escape(title),
+        meta_html = render_guide_meta(locale, options),
+        step_count = crate::i18n::export_step_count(locale, steps.len()),
+        generated = crate::i18n::export_generated_on(locale, chrono::Local::now().date_naive()),
+    )
+}
+
+/// Linked step index shown above the timeline. `entry_title` gets the step and its
+/// 1-based number.
+fn render_toc(
+    steps: &[Step],
+    locale: Locale,
+    entry_title: impl Fn(&Step, usize) -> String,
+) -> String {
+    let items: String = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let num = i + 1;
+            let title = escape_text(&entry_title(step, num));
+            format!(
+                r##"
+  <li><a href="#step-{num}"><span class="toc-num">{num}</span>{title}</a></li>"##
+            )
+        })
+        .collect();
+    format!(
+        r#"<nav class="toc">
+<h2>{heading}</h2>
+<ol>{items}
+</ol>
+</nav>
+"#,
+        heading = crate::i18n::export_toc_heading(locale),
+    )
+}
 
-    let image_html = step
-        .screenshot_path
+/// `images` holds the `src` of the step's screenshot and of its result frame; `None`
+/// renders the step as text only.
+fn render_step(
+    num: usize,
+    step: &Step,
+    images: Option<(Option<String>, Option<String>)>,
+    elapsed: Option<&str>,
+    layout: HtmlLayout,
+    locale: Locale,
+    options: &ExportOptions,
+) -> String {
+    let desc = html_escape(&effective_description_localized(step, locale));
+    let title_html = step
+        .title
         .as_ref()
-        .and_then(|p| load_screenshot_optimized(p, target, step.crop_region.as_ref()))
-        .map(|(b64, mime)| {
-            let alt = crate::i18n::export_step_image_alt(locale, num);
-            format!(r#"<img src="data:{mime};base64,{b64}" alt="{alt}">"#)
+        .map(|t| format!(r#"<h3 class="step-title">{}</h3>"#, escape_text(t)))
+        .unwrap_or_default();
+    let time_html = elapsed
+        .map(|t| format!(r#"<span class="step-time">{t}</span>"#))
+        .unwrap_or_default();
+    let wait_html = step
+        .wait_ms
+        .map(|ms| {
+            format!(
+                r#"<p class="step-wait">⏳ {}</p>"#,
+                crate::i18n::export_wait(locale, ms)
+            )
+        })
+        .unwrap_or_default();
+    let note_html = step
+        .note
+        .as_ref()
+        .map(|n| {
+            format!(
+                r#"<p class="step-note"><strong>{}</strong> {}</p>"#,
+                crate::i18n::export_note_label(locale),
+                escape_text(n)
+            )
+        })
+        .unwrap_or_default();
+    let menu_html = menu_path(step)
+        .map(|path| {
+            format!(
+                r#"<p class="step-menu-path">{} {}</p>"#,
+                crate::i18n::export_menu_path_label(locale),
+                escape_text(path)
+            )
+        })
+        .unwrap_or_default();
+    let url_html = page_url(step)
+        .map(|url| {
+            let url = html_escape(url);
+            format!(r#"<p class="step-url"><a href="{url}">{url}</a></p>"#)
+        })
+        .unwrap_or_default();
+    let a11y_html = options
+        .screen_reader_text
+        .then(|| screen_reader_announcement(step))
+        .flatten()
+        .map(|announcement| {
+            format!(
+                r#"<p class="step-a11y">{}</p>"#,
+                escape_text(&crate::i18n::export_screen_reader_announces(
+                    locale,
+                    &announcement
+                ))
+            )
+        })
+        .unwrap_or_default();
+    let space_html = if step.space_changed {
+        format!(
+            r#"<p class="step-space-change">{}</p>"#,
+            crate::i18n::step_space_changed(locale)
+        )
+    } else {
+        String::new()
+    };
+    let Some((image_src, result_src)) = images else {
+        return format!(
+            r#"
+    <div class="timeline-item" id="step-{num}">
+      <div class="timeline-badge">{num}</div>
+      <article class="step">
+        {space_html}
+        {title_html}
+        <div class="step-header">
+          <span class="step-desc">{desc}</span>
+          {time_html}
+        </div>
+        {url_html}
+        {menu_html}
+        {a11y_html}
+        {note_html}
+        {wait_html}
+      </article>
+    </div>"#
+        );
+    };
+
+    let has_image = image_src.is_some();
+    let image_html = image_src
+        .map(|src| {
+            let alt = crate::i18n::export_step_image_alt(locale, num, &step.action);
+            format!(r#"<img src="{}" alt="{alt}">"#, html_escape(&src))
+        })
+        .unwrap_or_default();
+    let caption_html = if has_image && step.is_auth_placeholder() {
+        format!(
+            r#"<p class="step-caption">{}</p>"#,
+            crate::i18n::export_auth_placeholder_caption(locale)
+        )
+    } else {
+        String::new()
+    };
+    let result_html = result_src
+        .map(|src| {
+            format!(
+                r#"<figure class="step-result">
+          <figcaption>{caption}</figcaption>
+          <img src="{src}" alt="{alt}">
+        </figure>"#,
+                caption = crate::i18n::export_result_caption(locale),
+                src = html_escape(&src),
+                alt = crate::i18n::export_result_image_alt(locale, num),
+            )
         })
         .unwrap_or_default();
 
     let marker_class = match step.action {
         ActionType::DoubleClick => "click-marker double-click",
         ActionType::RightClick => "click-marker right-click",
+        ActionType::Hover => "click-marker hover",
         _ => "click-marker",
     };
 
-    let click_marker = marker_position_percent(step)
-        .map(|(x, y)| {
-            format!(r#"<div class="{marker_class}" style="left: {x}%; top: {y}%;"></div>"#)
-        })
-        .unwrap_or_default();
+    // Drag screenshots already have start/end marks drawn into the pixels, and a
+    // shortcut clicks nothing. A known element rect is outlined; otherwise the click
+    // point gets a dot.
+    let click_marker = if matches!(step.action, ActionType::Drag | ActionType::Shortcut) {
+        String::new()
+    } else if let Some(rect) = highlight_rect_percent(step) {
+        format!(
+            r#"<div class="element-highlight" style="left: {}%; top: {}%; width: {}%; height: {}%;"></div>"#,
+            rect.x_percent, rect.y_percent, rect.width_percent, rect.height_percent
+        )
+    } else {
+        marker_position_percent(step)
+            .map(|(x, y)| {
+                format!(r#"<div class="{marker_class}" style="left: {x}%; top: {y}%;"></div>"#)
+            })
+            .unwrap_or_default()
+    };
 
-    let note_html = step
-        .note
-        .as_ref()
-        .map(|n| format!(r#"<p class="step-note">{}</p>"#, escape_text(n)))
-        .unwrap_or_default();
+    if layout == HtmlLayout::Compact {
+        return format!(
+            r#"
+    <div class="timeline-item" id="step-{num}">
+      <div class="timeline-badge">{num}</div>
+      <article class="step step-compact">
+        <div class="step-thumb">
+          <div class="image-wrapper">
+            {image_html}
+            {click_marker}
+          </div>
+          {caption_html}
+          {result_html}
+        </div>
+        <div class="step-body">
+          {space_html}
+          {title_html}
+          <div class="step-header">
+            <span class="step-desc">{desc}</span>
+            {time_html}
+          </div>
+          {url_html}
+          {menu_html}
+          {a11y_html}
+          {note_html}
+          {wait_html}
+        </div>
+      </article>
+    </div>"#
+        );
+    }
 
     format!(
         r#"
-    <div class="timeline-item">
+    <div class="timeline-item" id="step-{num}">
       <div class="timeline-badge">{num}</div>
       <article class="step">
+        {space_html}
+        {title_html}
         <div class="step-header">
           <span class="step-desc">{desc}</span>
+          {time_html}
         </div>
+        {url_html}
+        {menu_html}
+        {a11y_html}
         <div class="step-image">
           <div class="image-wrapper">
             {image_html}
             {click_marker}
           </div>
         </div>
+        {caption_html}
+        {result_html}
         {note_html}
+        {wait_html}
       </article>
     </div>"#
     )
 }
 
+/// Write a zip archive with the HTML guide at its root and the screenshots under
+/// [`SCREENSHOTS_DIR`]. The document is named after the zip stem:
+/// "My Guide.zip" → "My Guide.html". A partly written archive is removed on failure.
+pub fn write_zip(
+    title: &str,
+    steps: &[Step],
+    output_path: &str,
+    locale: Locale,
+    options: &ExportOptions,
+    progress: &ProgressReporter,
+) -> Result<(), ExportError> {
+    let file =
+        fs::File::create(output_path).map_err(|e| super::friendly_write_error(&e, output_path))?;
+    let result = write_zip_entries(title, steps, file, output_path, locale, options, progress);
+    if result.is_err() {
+        let _ = fs::remove_file(output_path);
+    }
+    result
+}
+
+fn write_zip_entries(
+    title: &str,
+    steps: &[Step],
+    file: fs::File,
+    output_path: &str,
+    locale: Locale,
+    options: &ExportOptions,
+    progress: &ProgressReporter,
+) -> Result<(), ExportError> {
+    let stem = Path::new(output_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("export");
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let doc_opts =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // WebP/PNG are already compressed.
+    let image_opts =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    // Stream screenshots one at a time so only one is held in memory.
+    let mut image_srcs = Vec::with_capacity(steps.len());
+    let mut result_srcs = Vec::with_capacity(steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        let num = i + 1;
+        if options.text_only {
+            image_srcs.push(None);
+            result_srcs.push(None);
+            progress.step_done();
+            continue;
+        }
+        // Result frames are best-effort extras; an unreadable one is left out.
+        let result = load_result_image(step, ImageTarget::Web, options);
+        result_srcs.push(match result {
+            Some(img) => {
+                let entry_name = format!("{SCREENSHOTS_DIR}/step-{num}-result.{}", img.ext);
+                zip.start_file(&entry_name, image_opts).map_err(|e| {
+                    ExportError::Io(format!("Failed to create image entry in zip: {e}"))
+                })?;
+                zip.write_all(&img.bytes)
+                    .map_err(|e| ExportError::Io(format!("Failed to write image data: {e}")))?;
+                Some(entry_name)
+            }
+            None => None,
+        });
+        let Some(src) = &step.screenshot_path else {
+            image_srcs.push(None);
+            progress.step_done();
+            continue;
+        };
+        let previous = previous_step(steps, num);
+        let img =
+            load_step_image(step, previous, num, ImageTarget::Web, options).ok_or_else(|| {
+                ExportError::Render(format!("Failed to read screenshot {num}: {src}"))
+            })?;
+        let entry_name = format!("{SCREENSHOTS_DIR}/step-{num}.{}", img.ext);
+        zip.start_file(&entry_name, image_opts)
+            .map_err(|e| ExportError::Io(format!("Failed to create image entry in zip: {e}")))?;
+        zip.write_all(&img.bytes)
+            .map_err(|e| ExportError::Io(format!("Failed to write image data: {e}")))?;
+        image_srcs.push(Some(entry_name));
+        progress.step_done();
+    }
+
+    let content =
+        generate_with_image_srcs(title, steps, locale, options, &image_srcs, &result_srcs);
+    zip.start_file(format!("{stem}.html"), doc_opts)
+        .map_err(|e| ExportError::Io(format!("Failed to create html entry in zip: {e}")))?;
+    zip.write_all(content.as_bytes())
+        .map_err(|e| ExportError::Io(format!("Failed to write html content: {e}")))?;
+
+    zip.finish()
+        .map_err(|e| ExportError::Io(format!("Failed to finalize zip: {e}")))?
+        .flush()
+        .map_err(|e| super::friendly_write_error(&e, output_path))
+}
+
 /// Escape text content (includes single quotes for note text)
 fn escape_text(s: &str) -> String {
     html_escape(s).replace('\'', "&#x27;")
@@ -125,41 +646,108 @@ body { font-family: -apple-system, BlinkMacSystemFont, 'SF Pro Text', 'Segoe UI'
 .container { max-width: 860px; margin: 0 auto; padding: 40px 32px 64px; }
 h1 { font-size: 20px; font-weight: 700; letter-spacing: -0.01em; margin: 0 0 4px; }
 .subtitle { font-size: 14px; color: #86868b; margin-bottom: 32px; }
+.byline { font-size: 14px; color: #6e6e73; margin-bottom: 6px; }
+.tags { list-style: none; display: flex; flex-wrap: wrap; gap: 6px; margin-bottom: 8px; }
+.tags li { font-size: 12px; font-weight: 600; color: #7c5cfc; background: rgba(124,92,252,0.08); border-radius: 10px; padding: 1px 8px; }
+.toc { margin-bottom: 32px; padding: 16px 20px; border: 1px solid #d1d1d6; border-radius: 14px; background: #fff; }
+.toc h2 { font-size: 14px; font-weight: 700; margin-bottom: 8px; }
+.toc ol { list-style: none; columns: 2; column-gap: 24px; }
+.toc li { font-size: 13px; padding: 2px 0; break-inside: avoid; }
+.toc a { color: inherit; text-decoration: none; display: flex; gap: 8px; }
+.toc a:hover { color: #7c5cfc; }
+.toc-num { color: #7c5cfc; font-weight: 700; min-width: 20px; text-align: right; }
 .timeline { display: flex; flex-direction: column; position: relative; }
 .timeline::before { content: ''; position: absolute; left: 15px; top: 16px; bottom: 16px; width: 2px; background: #d1d1d6; border-radius: 1px; }
 .timeline-item { display: grid; grid-template-columns: 32px 1fr; gap: 16px; padding-bottom: 24px; position: relative; }
 .timeline-item:last-child { padding-bottom: 0; }
 .timeline-badge { width: 32px; height: 32px; border-radius: 50%; background: #7c5cfc; color: #fff; font-size: 13px; font-weight: 700; display: flex; align-items: center; justify-content: center; position: relative; z-index: 1; box-shadow: 0 0 0 4px #f5f5f7; flex-shrink: 0; }
 .step { border: 1px solid #d1d1d6; border-radius: 14px; overflow: hidden; background: #fff; box-shadow: 0 1px 3px rgba(0,0,0,0.04), 0 4px 12px rgba(0,0,0,0.03); }
+.step-title { margin: 0; padding: 14px 20px 0; font-size: 16px; font-weight: 700; color: #1d1d1f; overflow-wrap: anywhere; }
+.step-title + .step-header { padding-top: 4px; }
 .step-header { display: flex; align-items: center; gap: 12px; padding: 14px 20px; }
-.step-desc { font-size: 14px; font-weight: 600; color: #1d1d1f; }
+.step-desc { font-size: 14px; font-weight: 600; color: #1d1d1f; min-width: 0; overflow-wrap: anywhere; }
+.step-time { margin-left: auto; font-size: 12px; color: #86868b; font-variant-numeric: tabular-nums; white-space: nowrap; }
 .step-image { padding: 0 20px 16px; display: flex; align-items: center; justify-content: center; }
 .image-wrapper { position: relative; display: inline-block; max-width: 100%; border-radius: 8px; overflow: hidden; box-shadow: 0 1px 2px rgba(0,0,0,0.06), 0 4px 16px rgba(0,0,0,0.08); border: 1px solid #d1d1d6; }
 .image-wrapper img { display: block; max-width: 100%; height: auto; }
 .step-note { margin: 0; padding: 12px 20px 16px; font-size: 13px; color: #1d1d1f; background: rgba(124,92,252,0.05); border-top: none; }
+.step-wait { margin: 0; padding: 10px 20px 14px; font-size: 13px; font-weight: 600; color: #86868b; }
+.step-url { margin: 0; padding: 0 20px 12px; font-size: 12px; overflow-wrap: anywhere; }
+.step-url a { color: #7c5cfc; text-decoration: none; }
+.step-a11y { margin: 0; padding: 0 20px 12px; font-size: 12px; color: #86868b; }
+.step-menu-path { margin: 0; padding: 0 20px 12px; font-size: 12px; color: #86868b; overflow-wrap: anywhere; }
+.step-space-change { margin: 0; padding: 12px 20px 0; font-size: 12px; font-style: italic; color: #86868b; }
+.step-caption { padding: 0 20px 12px; font-size: 12px; color: #86868b; text-align: center; }
+.step-result { margin: 0; padding: 0 20px 16px; text-align: center; }
+.step-result figcaption { font-size: 12px; font-weight: 600; color: #86868b; text-transform: uppercase; letter-spacing: 0.04em; margin-bottom: 6px; }
+.step-result img { max-width: 100%; border-radius: 8px; border: 1px solid #d1d1d6; }
+.brand-logo { display: block; max-height: 48px; max-width: 240px; margin-bottom: 16px; }
+.brand-footer { margin-top: 32px; font-size: 13px; color: #1d1d1f; text-align: center; overflow-wrap: anywhere; }
+.credit { margin-top: 32px; font-size: 12px; color: #86868b; text-align: center; }
+.brand-footer + .credit { margin-top: 8px; }
 .click-marker { position: absolute; width: 24px; height: 24px; border-radius: 50%; background: transparent; border: 2.5px solid #ff3b30; box-shadow: 0 0 0 1.5px rgba(255,255,255,0.9), 0 2px 6px rgba(0,0,0,0.25); transform: translate(-50%, -50%); pointer-events: none; }
 .click-marker.double-click { width: 18px; height: 18px; border-width: 2px; }
 .click-marker.double-click::after { content: ''; position: absolute; top: 50%; left: 50%; transform: translate(-50%, -50%); width: 30px; height: 30px; border-radius: 50%; border: 2px solid #ff3b30; box-shadow: 0 0 0 1.5px rgba(255,255,255,0.9); pointer-events: none; }
 .click-marker.right-click { border-style: dashed; }
+.click-marker.hover { border-style: dotted; }
+.element-highlight { position: absolute; border: 2.5px solid #ff3b30; border-radius: 6px; box-shadow: 0 0 0 1.5px rgba(255,255,255,0.9), 0 2px 6px rgba(0,0,0,0.25); pointer-events: none; }
 @media print {
   body { background: #fff !important; }
   .container { padding: 20px !important; }
   .timeline::before { background: #d1d1d6 !important; }
   .timeline-badge { box-shadow: 0 0 0 4px #fff !important; }
   .timeline-item { break-inside: avoid; }
+  .toc { break-after: page; background: #fff !important; }
   .step { box-shadow: none !important; border-color: #d1d1d6 !important; }
 }
 @media (prefers-color-scheme: dark) {
   body { background: #1c1c1e; color: #f5f5f7; }
   .subtitle { color: #98989d; }
+  .byline { color: #aeaeb2; }
+  .tags li { color: #a78bfa; background: rgba(167,139,250,0.12); }
+  .toc { background: #2c2c2e; border-color: #38383a; }
   .timeline::before { background: #38383a; }
   .timeline-badge { box-shadow: 0 0 0 4px #1c1c1e; }
   .step { background: #2c2c2e; border-color: #38383a; box-shadow: inset 0 1px 0 rgba(255,255,255,0.04), 0 1px 3px rgba(0,0,0,0.2), 0 4px 12px rgba(0,0,0,0.15); }
-  .step-desc { color: #f5f5f7; }
+  .step-title, .step-desc { color: #f5f5f7; }
+  .brand-footer { color: #f5f5f7; }
   .image-wrapper { border-color: #38383a; }
   .step-note { color: #f5f5f7; background: rgba(167,139,250,0.08); }
 }"#;
 
+/// Paged PDF layout. Page breaks and footers are placed by `pdf_pages::paginate_js`
+/// after load; these rules keep each step small enough to fit on one page.
+const PDF_CSS: &str = r#"
+body.pdf { position: relative; background: #fff; }
+.pdf .subtitle { margin-bottom: 4px; }
+.pdf .generated { font-size: 12px; color: #86868b; margin-bottom: 32px; }
+.pdf .timeline::before { display: none; }
+.pdf .timeline-badge { box-shadow: none; }
+.pdf .toc ol { columns: 1; }
+.pdf .toc li { font-size: 12px; }
+.pdf .toc a { overflow-wrap: anywhere; }
+.pdf .image-wrapper img { max-height: 720px; width: auto; }
+.pdf .step-result img { max-height: 480px; width: auto; }
+.pdf-footer { position: absolute; left: 0; right: 0; display: flex; align-items: center; justify-content: center; font-size: 11px; color: #86868b; }
+"#;
+
+/// [`HtmlLayout::Compact`]: one row per step with the screenshot as a thumbnail, stacked
+/// again on narrow screens.
+const COMPACT_CSS: &str = r#"
+.step-compact { display: flex; align-items: flex-start; gap: 16px; padding: 16px; }
+.step-thumb { flex: 0 0 220px; max-width: 220px; }
+.step-thumb .image-wrapper { display: block; }
+.step-body { flex: 1; min-width: 0; }
+.step-compact .step-header { padding: 0 0 8px; }
+.step-compact .step-url, .step-compact .step-menu-path, .step-compact .step-a11y, .step-compact .step-space-change, .step-compact .step-wait { padding: 0 0 8px; }
+.step-compact .step-note { padding: 8px 12px; border-radius: 8px; }
+.step-compact .step-caption, .step-compact .step-result { padding: 8px 0 0; }
+@media (max-width: 560px) {
+  .step-compact { flex-direction: column; }
+  .step-thumb { flex-basis: auto; max-width: 100%; }
+}
+"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +774,22 @@ mod tests {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
         }
     }
 
@@ -197,6 +801,18 @@ mod tests {
         assert!(html.contains("1 step"));
     }
 
+    #[test]
+    fn step_title_renders_as_heading_above_the_description() {
+        let mut titled = sample_step();
+        titled.title = Some("Save <draft>".into());
+        let html = generate("G", &[titled, sample_step()]);
+        let heading = html
+            .find(r#"<h3 class="step-title">Save &lt;draft&gt;</h3>"#)
+            .expect("title heading");
+        assert!(heading < html.find(r#"<span class="step-desc">"#).unwrap());
+        assert_eq!(html.matches(r#"<h3 class="step-title">"#).count(), 1);
+    }
+
     #[test]
     fn generate_contains_step_count() {
         let html = generate("G", &[sample_step(), sample_step()]);
@@ -211,6 +827,111 @@ mod tests {
         assert!(html.contains("Geklickt in Finder"));
     }
 
+    #[test]
+    fn generate_german_chrome_differs_from_english() {
+        let mut s = sample_step();
+        s.note = Some("Wichtig".into());
+        let steps = [s];
+        let en = generate_localized("G", &steps, Locale::En);
+        let de = generate_localized("G", &steps, Locale::De);
+        assert_ne!(en, de);
+        assert!(en.contains("<strong>Note:</strong> Wichtig"));
+        assert!(de.contains("<strong>Notiz:</strong> Wichtig"));
+        assert!(en.contains(r#"<footer class="credit">Recorded with StepCast · "#));
+        assert!(de.contains(r#"<footer class="credit">Aufgenommen mit StepCast · "#));
+    }
+
+    #[test]
+    fn text_only_mode_leaves_out_every_image() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let img_path = tmp.path().join("shot.png");
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([0, 128, 255, 255]))
+            .save(&img_path)
+            .unwrap();
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+        s.result_screenshot_path = s.screenshot_path.clone();
+        s.note = Some("Check the toggle".into());
+        let options = ExportOptions {
+            text_only: true,
+            include_toc: true,
+            ..ExportOptions::default()
+        };
+
+        for target in [ImageTarget::Web, ImageTarget::Pdf] {
+            let html = generate_with_options("G", &[s.clone()], target, Locale::En, &options);
+            assert!(!html.contains("<img"), "{target:?}");
+            assert!(!html.contains("data:image"));
+            assert!(!html.contains(r#"class="image-wrapper""#));
+            assert!(!html.contains("click-marker\" style"));
+            assert!(html.contains(r#"<div class="timeline-badge">1</div>"#));
+            assert!(html.contains("Clicked"));
+            assert!(html.contains("<strong>Note:</strong> Check the toggle"));
+        }
+    }
+
+    #[test]
+    fn generate_shows_result_frame_below_screenshot() {
+        let html = generate_with_image_srcs(
+            "G",
+            &[sample_step(), sample_step()],
+            Locale::En,
+            &ExportOptions::default(),
+            &[
+                Some("screenshots/step-1.png".into()),
+                Some("screenshots/step-2.png".into()),
+            ],
+            &[None, Some("screenshots/step-2-result.webp".into())],
+        );
+        assert_eq!(html.matches(r#"<figure class="step-result">"#).count(), 1);
+        assert!(html.contains("<figcaption>Result</figcaption>"));
+        assert!(html.contains(r#"<img src="screenshots/step-2-result.webp" alt="Step 2: Result">"#));
+        let screenshot = html.find("screenshots/step-2.png").unwrap();
+        let result = html.find("screenshots/step-2-result.webp").unwrap();
+        assert!(screenshot < result);
+    }
+
+    #[test]
+    fn generate_captions_auth_placeholder_image() {
+        let mut s = sample_step();
+        s.is_secure_placeholder = true;
+        let html = generate_with_image_srcs(
+            "G",
+            &[s],
+            Locale::En,
+            &ExportOptions::default(),
+            &[Some("screenshots/step-1.png".into())],
+            &[],
+        );
+        assert!(html.contains("The password prompt was not captured for privacy."));
+    }
+
+    #[test]
+    fn generate_outlines_element_instead_of_click_dot() {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
+        s.ax = Some(
+            serde_json::from_value(serde_json::json!({
+                "role": "AXButton",
+                "label": "OK",
+                "element_bounds": {
+                    "x_percent": 40.0,
+                    "y_percent": 20.0,
+                    "width_percent": 10.0,
+                    "height_percent": 5.0,
+                },
+                "is_cancel_button": false,
+                "is_default_button": false,
+            }))
+            .unwrap(),
+        );
+        let html = generate("G", &[s]);
+        assert!(html.contains(
+            r#"<div class="element-highlight" style="left: 40%; top: 20%; width: 10%; height: 5%;"></div>"#
+        ));
+        assert!(!html.contains(r#"<div class="click-marker""#));
+    }
+
     #[test]
     fn generate_contains_dark_mode() {
         let html = generate("G", &[sample_step()]);
@@ -240,6 +961,144 @@ mod tests {
         assert!(!html.contains(r#"<p class="step-note">"#));
     }
 
+    #[test]
+    fn generate_applies_branding() {
+        let options = ExportOptions {
+            branding: branding::ExportBranding {
+                logo_path: Some("/nonexistent/logo.png".into()),
+                primary_color: Some("#0a84ff".into()),
+                footer_text: Some("Acme <Internal>".into()),
+                ..branding::ExportBranding::default()
+            },
+            ..ExportOptions::default()
+        };
+        let html = generate_with_options(
+            "G",
+            &[sample_step()],
+            ImageTarget::Web,
+            Locale::En,
+            &options,
+        );
+        assert!(html.contains(".timeline-badge { background: #0a84ff; }"));
+        assert!(html.contains(r#"<p class="brand-footer">Acme &lt;Internal&gt;</p>"#));
+        // The unreadable logo is left out rather than failing the export.
+        assert!(!html.contains(r#"<img class="brand-logo""#));
+
+        let plain = generate("G", &[sample_step()]);
+        assert!(!plain.contains(r#"<p class="brand-footer">"#));
+    }
+
+    #[test]
+    fn compact_layout_puts_thumbnails_beside_the_text() {
+        let options = ExportOptions {
+            html_layout: HtmlLayout::Compact,
+            ..ExportOptions::default()
+        };
+        let compact = generate_with_options(
+            "G",
+            &[sample_step()],
+            ImageTarget::Web,
+            Locale::En,
+            &options,
+        );
+        assert!(compact.contains(r#"<article class="step step-compact">"#));
+        assert!(compact.contains(".step-thumb { flex: 0 0 220px;"));
+
+        let full = generate("G", &[sample_step()]);
+        assert!(!full.contains("step-compact"));
+        // PDFs keep the full layout.
+        let pdf = generate_with_options(
+            "G",
+            &[sample_step()],
+            ImageTarget::Pdf,
+            Locale::En,
+            &options,
+        );
+        assert!(!pdf.contains("step-compact"));
+    }
+
+    #[test]
+    fn generate_marks_space_changes() {
+        let html = generate("G", &[sample_step()]);
+        assert!(!html.contains(r#"<p class="step-space-change">"#));
+
+        let mut s = sample_step();
+        s.space_changed = true;
+        let html = generate("G", &[s]);
+        assert!(html
+            .contains(r#"<p class="step-space-change">Switched to another desktop (Space)</p>"#));
+    }
+
+    #[test]
+    fn generate_links_page_urls() {
+        let html = generate("G", &[sample_step()]);
+        assert!(!html.contains(r#"<p class="step-url">"#));
+
+        let mut s = sample_step();
+        s.page_url = Some("https://example.com/a?b=1&c=2".into());
+        let html = generate("G", &[s]);
+        assert!(html.contains(
+            r#"<p class="step-url"><a href="https://example.com/a?b=1&amp;c=2">https://example.com/a?b=1&amp;c=2</a></p>"#
+        ));
+
+        let mut s = sample_step();
+        s.page_url = Some("javascript:alert(1)".into());
+        assert!(!generate("G", &[s]).contains(r#"<p class="step-url">"#));
+    }
+
+    #[test]
+    fn generate_adds_screen_reader_text_only_when_asked() {
+        let mut s = sample_step();
+        s.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXButton",
+            "role_description": "button",
+            "label": "Save",
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .unwrap();
+        let html = generate("G", &[s.clone()]);
+        assert!(!html.contains(r#"<p class="step-a11y">"#));
+
+        let options = ExportOptions {
+            screen_reader_text: true,
+            ..ExportOptions::default()
+        };
+        let html = generate_with_options("G", &[s], ImageTarget::Web, Locale::En, &options);
+        assert!(
+            html.contains(r#"<p class="step-a11y">Screen reader announces: “Save”, button</p>"#)
+        );
+    }
+
+    #[test]
+    fn generate_shows_menu_breadcrumbs() {
+        let html = generate("G", &[sample_step()]);
+        assert!(!html.contains(r#"<p class="step-menu-path">"#));
+
+        let mut s = sample_step();
+        s.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXMenuItem",
+            "label": "PDF",
+            "is_cancel_button": false,
+            "is_default_button": false,
+            "menu_path": "File ▸ Export ▸ PDF",
+        }))
+        .unwrap();
+        let html = generate("G", &[s]);
+        assert!(html.contains(r#"<p class="step-menu-path">Menu: File ▸ Export ▸ PDF</p>"#));
+    }
+
+    #[test]
+    fn generate_renders_wait_only_when_set() {
+        let html = generate("G", &[sample_step()]);
+        assert!(!html.contains(r#"<p class="step-wait">"#));
+
+        let mut s = sample_step();
+        s.wait_ms = Some(3_000);
+        let html = generate("G", &[s]);
+        assert!(html.contains(r#"<p class="step-wait">⏳ Wait ~3s</p>"#));
+    }
+
     #[test]
     fn generate_shortcut_action_text() {
         let mut s = sample_step();
@@ -248,6 +1107,16 @@ mod tests {
         assert!(html.contains("Used keyboard shortcut in"));
     }
 
+    #[test]
+    fn generate_shortcut_shows_keys_without_marker() {
+        let mut s = sample_step();
+        s.action = ActionType::Shortcut;
+        s.keys = Some("⌘S".to_string());
+        let html = generate("G", &[s]);
+        assert!(html.contains("Press ⌘S in"));
+        assert!(!html.contains(r#"<div class="click-marker"#));
+    }
+
     #[test]
     fn generate_click_marker_classes() {
         let mut dc = sample_step();
@@ -261,6 +1130,28 @@ mod tests {
         assert!(html.contains("right-click"));
     }
 
+    #[test]
+    fn generate_shows_byline_and_tags_under_title() {
+        let plain = generate("G", &[sample_step()]);
+        assert!(!plain.contains(r#"class="byline""#));
+        assert!(!plain.contains(r#"<meta name="author""#));
+
+        let options = ExportOptions {
+            author: Some("Ana <AJ>".into()),
+            recorded_on: chrono::NaiveDate::from_ymd_opt(2025, 3, 7),
+            tags: vec!["onboarding".into(), "mail".into()],
+            ..ExportOptions::default()
+        };
+        for target in [ImageTarget::Web, ImageTarget::Pdf] {
+            let html = generate_with_options("G", &[sample_step()], target, Locale::En, &options);
+            assert!(html
+                .contains(r#"<p class="byline">Recorded by Ana &lt;AJ&gt; on March 7, 2025</p>"#));
+            assert!(html.contains(r#"<ul class="tags"><li>onboarding</li><li>mail</li></ul>"#));
+            assert!(html.contains(r#"<meta name="author" content="Ana &lt;AJ&gt;">"#));
+            assert!(html.contains(r#"<meta name="keywords" content="onboarding, mail">"#));
+        }
+    }
+
     #[test]
     fn html_escape_in_title() {
         let html = generate("<script>alert(1)</script>", &[]);
@@ -326,4 +1217,173 @@ mod tests {
             "Should not contain PNG data URI when WebP is smaller"
         );
     }
+
+    #[test]
+    fn toc_has_one_anchor_per_step() {
+        let mut steps = vec![sample_step(), sample_step(), sample_step()];
+        steps[1].description = Some("Open <Settings>".into());
+        let options = ExportOptions {
+            include_toc: true,
+            ..ExportOptions::default()
+        };
+        let html = generate_with_options("G", &steps, ImageTarget::Web, Locale::En, &options);
+        assert!(html.contains(r#"<nav class="toc">"#));
+        assert!(html.contains("<h2>Contents</h2>"));
+        for num in 1..=steps.len() {
+            let link = format!(r##"<a href="#step-{num}">"##);
+            let target = format!(r#"id="step-{num}""#);
+            assert_eq!(html.matches(&link).count(), 1, "link for step {num}");
+            assert_eq!(html.matches(&target).count(), 1, "target for step {num}");
+        }
+        assert!(html.contains("Open &lt;Settings&gt;"));
+    }
+
+    #[test]
+    fn toc_omitted_by_default() {
+        let html = generate("G", &[sample_step()]);
+        assert!(!html.contains(r#"<nav class="toc">"#));
+        assert!(!html.contains(r##"href="#step-1""##));
+    }
+
+    #[test]
+    fn elapsed_time_shown_per_step_when_enabled() {
+        let mut steps = vec![sample_step(), sample_step()];
+        steps[0].ts = 5_000;
+        steps[1].ts = 47_000;
+        let options = ExportOptions {
+            elapsed_time: true,
+            ..ExportOptions::default()
+        };
+        let html = generate_with_options("G", &steps, ImageTarget::Web, Locale::En, &options);
+        assert!(html.contains(r#"<span class="step-time">+00:00</span>"#));
+        assert!(html.contains(r#"<span class="step-time">+00:42</span>"#));
+
+        let html = generate("G", &steps);
+        assert!(!html.contains(r#"<span class="step-time">"#));
+    }
+
+    #[test]
+    fn pdf_cover_lists_steps_with_fallback_titles() {
+        let mut steps = vec![sample_step(), sample_step()];
+        steps[0].description = Some("Open the Downloads folder\nand sort by date".into());
+        let options = ExportOptions {
+            include_toc: true,
+            ..ExportOptions::default()
+        };
+        let html = generate_with_options("G", &steps, ImageTarget::Pdf, Locale::En, &options);
+        assert!(html.contains(r#"<body class="pdf">"#));
+        assert!(html.contains(r#"<header class="pdf-header pdf-cover">"#));
+        assert!(html.contains(r#"<p class="generated">Generated on "#));
+        assert!(html.contains("Open the Downloads folder</a>"));
+        assert!(html.contains("Step 2 \u{2014} Finder</a>"));
+        assert!(html.contains(r##"<a href="#step-2">"##));
+
+        // Without a TOC the title block stays on the first page with the steps.
+        let html = generate_with_options(
+            "G",
+            &steps,
+            ImageTarget::Pdf,
+            Locale::De,
+            &ExportOptions::default(),
+        );
+        assert!(html.contains(r#"<header class="pdf-header">"#));
+        assert!(html.contains("Erstellt am "));
+        assert!(!html.contains(r#"<nav class="toc">"#));
+    }
+
+    #[test]
+    fn write_zip_text_only_has_no_screenshots() {
+        use tempfile::TempDir;
+        use zip::ZipArchive;
+
+        let tmp = TempDir::new().unwrap();
+        let img_path = tmp.path().join("shot.png");
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([0, 128, 255, 255]))
+            .save(&img_path)
+            .unwrap();
+        let mut step = sample_step();
+        step.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+
+        let zip_path = tmp.path().join("Guide.zip");
+        let options = ExportOptions {
+            text_only: true,
+            ..ExportOptions::default()
+        };
+        write_zip(
+            "Guide",
+            &[step],
+            zip_path.to_str().unwrap(),
+            Locale::En,
+            &options,
+            &ProgressReporter::silent(),
+        )
+        .unwrap();
+
+        let archive = ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names, ["Guide.html"]);
+    }
+
+    #[test]
+    fn write_zip_bundles_document_and_screenshots() {
+        use std::io::Read as _;
+        use tempfile::TempDir;
+        use zip::ZipArchive;
+
+        let tmp = TempDir::new().unwrap();
+        let mut steps = Vec::new();
+        for i in 0..2u8 {
+            let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([i * 100, 128, 255, 255]));
+            let img_path = tmp.path().join(format!("shot-{i}.png"));
+            img.save(&img_path).unwrap();
+            let mut step = sample_step();
+            step.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+            steps.push(step);
+        }
+        steps.push(sample_step());
+
+        let zip_path = tmp.path().join("My Guide.zip");
+        write_zip(
+            "My Guide",
+            &steps,
+            zip_path.to_str().unwrap(),
+            Locale::En,
+            &ExportOptions::default(),
+            &ProgressReporter::silent(),
+        )
+        .unwrap();
+
+        let file = std::fs::File::open(&zip_path).unwrap();
+        let mut archive = ZipArchive::new(file).unwrap();
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        assert_eq!(
+            names.len(),
+            3,
+            "document plus one entry per screenshot: {names:?}"
+        );
+        let screenshots: Vec<&String> = names
+            .iter()
+            .filter(|n| n.starts_with("screenshots/step-"))
+            .collect();
+        assert_eq!(screenshots.len(), 2);
+
+        let mut html = String::new();
+        archive
+            .by_name("My Guide.html")
+            .unwrap()
+            .read_to_string(&mut html)
+            .unwrap();
+        assert!(!html.contains("data:image/"));
+        for name in screenshots {
+            assert!(html.contains(&format!(r#"<img src="{name}""#)), "{name}");
+        }
+    }
+
+    #[test]
+    fn web_export_has_no_pdf_layout() {
+        let html = generate("G", &[sample_step()]);
+        assert!(html.contains("<body>"));
+        assert!(!html.contains("pdf-header"));
+        assert!(!html.contains(".pdf-footer"));
+    }
 }
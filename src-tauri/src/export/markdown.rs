@@ -1,6 +1,9 @@
 use super::helpers::{
-    effective_description_localized, load_screenshot_optimized_image, ImageTarget,
+    crop_aspect_ratio, effective_description_localized, html_escape, load_result_image,
+    load_step_image, menu_path, page_url, previous_step, relative_times,
+    screen_reader_announcement, ImageTarget,
 };
+use super::{ExportError, ExportOptions, ProgressReporter};
 use crate::i18n::Locale;
 use crate::recorder::types::Step;
 use std::fs;
@@ -9,6 +12,19 @@ use std::path::Path;
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
+/// Markdown dialect of an export, for the platform the file is imported into. All
+/// variants bundle the screenshots in a folder next to the `.md` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkdownVariant {
+    /// CommonMark, with `<img>` tags when images get a maximum width.
+    #[default]
+    Standard,
+    /// Notion's importer: callouts for notes, toggles for multi-line descriptions.
+    Notion,
+    /// Confluence's Markdown import: no inline HTML, info macros for notes.
+    Confluence,
+}
+
 /// Derive the images directory name from a stem.
 /// "My Guide" → "My Guide-images"
 pub fn images_dir_name(output_path: &Path) -> String {
@@ -38,51 +54,279 @@ pub fn generate_content_localized(
     image_exts: &[&str],
     locale: Locale,
 ) -> String {
-    let mut md = format!(
+    generate_content_with_options(
+        title,
+        steps,
+        images_dir,
+        image_exts,
+        &[],
+        locale,
+        &ExportOptions::default(),
+    )
+}
+
+/// Same as [`generate_content_localized`]; `options.elapsed_time` appends each step's
+/// time since the first step to its heading and `options.markdown_image_max_width`
+/// switches images to sized `<img>` tags. `result_exts[i]` is the extension of step
+/// `i`'s result frame, `None` when it has none.
+pub fn generate_content_with_options(
+    title: &str,
+    steps: &[Step],
+    images_dir: &str,
+    image_exts: &[&str],
+    result_exts: &[Option<&str>],
+    locale: Locale,
+    options: &ExportOptions,
+) -> String {
+    generate_variant(
+        title,
+        steps,
+        images_dir,
+        image_exts,
+        result_exts,
+        locale,
+        options,
+        MarkdownVariant::Standard,
+    )
+}
+
+/// Same as [`generate_content_with_options`] in the given dialect. Notion and
+/// Confluence get no front matter and plain `![]()` images, which both importers
+/// resolve against the bundled images folder.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_variant(
+    title: &str,
+    steps: &[Step],
+    images_dir: &str,
+    image_exts: &[&str],
+    result_exts: &[Option<&str>],
+    locale: Locale,
+    options: &ExportOptions,
+    variant: MarkdownVariant,
+) -> String {
+    let times = options.elapsed_time.then(|| relative_times(steps));
+    let mut md = match variant {
+        MarkdownVariant::Standard => front_matter(title, steps.len(), options),
+        MarkdownVariant::Notion | MarkdownVariant::Confluence => String::new(),
+    };
+    md.push_str(&format!(
         "# {title} — {step_count}\n\n",
         step_count = crate::i18n::export_step_count(locale, steps.len()),
-    );
+    ));
 
     for (i, step) in steps.iter().enumerate() {
         let num = i + 1;
         let desc = effective_description_localized(step, locale);
 
-        md.push_str(&format!(
-            "## {}\n\n",
-            crate::i18n::export_step_heading(locale, num)
-        ));
+        let heading = crate::i18n::export_step_heading(locale, num);
+        match times.as_ref() {
+            Some(times) => md.push_str(&format!("## {heading} · {}\n\n", times[i])),
+            None => md.push_str(&format!("## {heading}\n\n")),
+        }
+        if let Some(title) = &step.title {
+            md.push_str(&format!("### {title}\n\n"));
+        }
 
-        md.push_str(&format!("**{desc}**\n\n"));
+        if step.space_changed {
+            md.push_str(&format!(
+                "*{}*\n\n",
+                crate::i18n::step_space_changed(locale)
+            ));
+        }
+        md.push_str(&description_block(&desc, variant));
+        if let Some(url) = page_url(step) {
+            md.push_str(&format!("<{url}>\n\n"));
+        }
+        if let Some(path) = menu_path(step) {
+            md.push_str(&format!(
+                "*{} {path}*\n\n",
+                crate::i18n::export_menu_path_label(locale)
+            ));
+        }
+        if let Some(announcement) = options
+            .screen_reader_text
+            .then(|| screen_reader_announcement(step))
+            .flatten()
+        {
+            md.push_str(&format!(
+                "*{}*\n\n",
+                crate::i18n::export_screen_reader_announces(locale, &announcement)
+            ));
+        }
 
         // Image reference (relative path into images dir)
-        if step.screenshot_path.is_some() {
+        if step.screenshot_path.is_some() && !options.text_only {
             let ext = image_exts.get(i).unwrap_or(&"png");
-            let alt = crate::i18n::export_step_image_alt(locale, num);
-            md.push_str(&format!("![{alt}](<./{images_dir}/step-{num}.{ext}>)\n\n"));
+            let alt = crate::i18n::export_step_image_alt(locale, num, &step.action);
+            match options.markdown_image_max_width {
+                _ if variant != MarkdownVariant::Standard => md.push_str(&format!(
+                    "![{alt}]({}/step-{num}.{ext})\n\n",
+                    url_path(images_dir)
+                )),
+                Some(max_width) => md.push_str(&format!(
+                    "<img src=\"./{}/step-{num}.{ext}\" alt=\"{alt}\" width=\"{}\">\n\n",
+                    html_escape(&images_dir.replace(' ', "%20")),
+                    image_display_width(step, max_width),
+                )),
+                None => md.push_str(&format!("![{alt}](<./{images_dir}/step-{num}.{ext}>)\n\n")),
+            }
+            if step.is_auth_placeholder() {
+                md.push_str(&format!(
+                    "*{}*\n\n",
+                    crate::i18n::export_auth_placeholder_caption(locale)
+                ));
+            }
+        }
+
+        if let Some(ext) = result_exts
+            .get(i)
+            .copied()
+            .flatten()
+            .filter(|_| !options.text_only)
+        {
+            let alt = crate::i18n::export_result_image_alt(locale, num);
+            md.push_str(&format!(
+                "*{}*\n\n",
+                crate::i18n::export_result_caption(locale)
+            ));
+            match options.markdown_image_max_width {
+                _ if variant != MarkdownVariant::Standard => md.push_str(&format!(
+                    "![{alt}]({}/step-{num}-result.{ext})\n\n",
+                    url_path(images_dir)
+                )),
+                Some(max_width) => md.push_str(&format!(
+                    "<img src=\"./{}/step-{num}-result.{ext}\" alt=\"{alt}\" width=\"{max_width}\">\n\n",
+                    html_escape(&images_dir.replace(' ', "%20")),
+                )),
+                None => md.push_str(&format!(
+                    "![{alt}](<./{images_dir}/step-{num}-result.{ext}>)\n\n"
+                )),
+            }
         }
 
         if let Some(note) = &step.note {
-            md.push_str(&format!("> {note}\n\n"));
+            md.push_str(&note_block(
+                crate::i18n::export_note_label(locale),
+                note,
+                variant,
+            ));
+        }
+
+        if let Some(wait_ms) = step.wait_ms {
+            md.push_str(&format!(
+                "⏳ *{}*\n\n",
+                crate::i18n::export_wait(locale, wait_ms)
+            ));
         }
     }
 
+    md.push_str(&format!(
+        "---\n\n*{} · {}*\n",
+        crate::i18n::export_recorded_with(locale),
+        crate::i18n::export_date(locale, chrono::Local::now().date_naive()),
+    ));
+
     md
 }
 
-/// Write a zip archive containing the markdown file and screenshot images.
-/// `output_path` should end in `.zip`. The inner `.md` file derives its name
-/// from the zip stem: "My Guide.zip" → "My Guide.md".
-#[allow(dead_code)]
-pub fn write(title: &str, steps: &[Step], output_path: &str) -> Result<(), String> {
-    write_localized(title, steps, output_path, Locale::En)
+/// The step's bold description. Notion folds descriptions of several lines into a
+/// toggle under their first line.
+fn description_block(desc: &str, variant: MarkdownVariant) -> String {
+    let mut lines = desc.lines().map(str::trim).filter(|l| !l.is_empty());
+    match (variant, lines.next()) {
+        (MarkdownVariant::Notion, Some(first)) if lines.clone().next().is_some() => {
+            let rest: Vec<&str> = lines.collect();
+            format!(
+                "<details>\n<summary><strong>{}</strong></summary>\n\n{}\n\n</details>\n\n",
+                html_escape(first),
+                rest.join("\n\n")
+            )
+        }
+        _ => format!("**{desc}**\n\n"),
+    }
+}
+
+/// A step note: a quote, a Notion callout or a Confluence info macro.
+fn note_block(label: &str, note: &str, variant: MarkdownVariant) -> String {
+    match variant {
+        MarkdownVariant::Standard => format!("> **{label}** {note}\n\n"),
+        MarkdownVariant::Notion => format!("<aside>\n💡 **{label}** {note}\n</aside>\n\n"),
+        // A brace in the note would end the macro early.
+        MarkdownVariant::Confluence => format!(
+            "{{info}}\n**{label}** {}\n{{info}}\n\n",
+            note.replace('{', "\\{").replace('}', "\\}")
+        ),
+    }
+}
+
+/// Relative folder path usable in a bare `![]()` link: spaces and parentheses would
+/// end the link, so they are percent-encoded.
+fn url_path(dir: &str) -> String {
+    dir.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// Width for a width-constrained image: `max_width` for landscape crops; portrait crops
+/// get narrower so their height stays within `max_width` too.
+fn image_display_width(step: &Step, max_width: u32) -> u32 {
+    match crop_aspect_ratio(step) {
+        Some(aspect) if aspect < 1.0 => ((max_width as f32 * aspect).round() as u32).max(1),
+        _ => max_width,
+    }
+}
+
+/// YAML front matter with the guide's author, date and tags, for static site
+/// generators. Empty when none of them is set, unless `markdown_front_matter` asks for
+/// it; then it always has a date (today when unknown), the step count and the weight.
+fn front_matter(title: &str, step_count: usize, options: &ExportOptions) -> String {
+    let always = options.markdown_front_matter;
+    if !always
+        && options.author.is_none()
+        && options.recorded_on.is_none()
+        && options.tags.is_empty()
+    {
+        return String::new();
+    }
+    // JSON strings are valid YAML scalars and take care of quoting.
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let mut yaml = format!("---\ntitle: {}\n", quote(title));
+    if let Some(author) = &options.author {
+        yaml.push_str(&format!("author: {}\n", quote(author)));
+    }
+    let date = options
+        .recorded_on
+        .or_else(|| always.then(|| chrono::Local::now().date_naive()));
+    if let Some(date) = date {
+        yaml.push_str(&format!("date: {}\n", date.format("%Y-%m-%d")));
+    }
+    if !options.tags.is_empty() {
+        let tags: Vec<String> = options.tags.iter().map(|tag| quote(tag)).collect();
+        yaml.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    }
+    if always {
+        if let Some(weight) = options.markdown_weight {
+            yaml.push_str(&format!("weight: {weight}\n"));
+        }
+        yaml.push_str(&format!("step_count: {step_count}\n"));
+    }
+    yaml.push_str("---\n\n");
+    yaml
 }
 
-pub fn write_localized(
+/// Write a zip archive containing the markdown file and screenshot images in the
+/// given dialect, reporting each converted screenshot to `progress`. `output_path`
+/// should end in `.zip`. The inner `.md` file derives its name from the zip stem:
+/// "My Guide.zip" → "My Guide.md".
+pub fn write_with_progress(
     title: &str,
     steps: &[Step],
     output_path: &str,
     locale: Locale,
-) -> Result<(), String> {
+    options: &ExportOptions,
+    variant: MarkdownVariant,
+    progress: &ProgressReporter,
+) -> Result<(), ExportError> {
     let path = Path::new(output_path);
     let stem = path
         .file_stem()
@@ -96,21 +340,49 @@ pub fn write_localized(
     // Convert images and collect (bytes, extension) per step
     let mut converted: Vec<Option<(Vec<u8>, &str)>> = Vec::with_capacity(steps.len());
     for (i, step) in steps.iter().enumerate() {
-        if let Some(src) = &step.screenshot_path {
-            let img =
-                load_screenshot_optimized_image(src, ImageTarget::Web, step.crop_region.as_ref())
-                    .ok_or_else(|| format!("Failed to read screenshot {}: {src}", i + 1))?;
+        if options.text_only {
+            converted.push(None);
+        } else if let Some(src) = &step.screenshot_path {
+            let previous = previous_step(steps, i + 1);
+            let img = load_step_image(step, previous, i + 1, ImageTarget::Web, options)
+                .ok_or_else(|| {
+                    ExportError::Render(format!("Failed to read screenshot {}: {src}", i + 1))
+                })?;
             converted.push(Some((img.bytes, img.ext)));
         } else {
             converted.push(None);
         }
+        progress.step_done();
     }
+    // Result frames are best-effort extras; an unreadable one is left out.
+    let results: Vec<Option<(Vec<u8>, &str)>> = steps
+        .iter()
+        .map(|step| {
+            if options.text_only {
+                return None;
+            }
+            load_result_image(step, ImageTarget::Web, options).map(|img| (img.bytes, img.ext))
+        })
+        .collect();
+    let result_exts: Vec<Option<&str>> = results
+        .iter()
+        .map(|r| r.as_ref().map(|(_, ext)| *ext))
+        .collect();
 
     let image_exts: Vec<&str> = converted
         .iter()
         .map(|c| c.as_ref().map(|(_, ext)| *ext).unwrap_or("png"))
         .collect();
-    let content = generate_content_localized(title, steps, &images_dir, &image_exts, locale);
+    let content = generate_variant(
+        title,
+        steps,
+        &images_dir,
+        &image_exts,
+        &result_exts,
+        locale,
+        options,
+        variant,
+    );
 
     let buf: Vec<u8> = {
         let cursor = Cursor::new(Vec::new());
@@ -118,23 +390,34 @@ pub fn write_localized(
 
         // Write the markdown file
         zip.start_file(&md_filename, opts)
-            .map_err(|e| format!("Failed to create md entry in zip: {e}"))?;
+            .map_err(|e| ExportError::Io(format!("Failed to create md entry in zip: {e}")))?;
         zip.write_all(content.as_bytes())
-            .map_err(|e| format!("Failed to write md content: {e}"))?;
+            .map_err(|e| ExportError::Io(format!("Failed to write md content: {e}")))?;
 
         // Write screenshot images
         for (i, conv) in converted.iter().enumerate() {
             if let Some((bytes, ext)) = conv {
                 let entry_name = format!("{images_dir}/step-{}.{ext}", i + 1);
-                zip.start_file(&entry_name, opts)
-                    .map_err(|e| format!("Failed to create image entry in zip: {e}"))?;
+                zip.start_file(&entry_name, opts).map_err(|e| {
+                    ExportError::Io(format!("Failed to create image entry in zip: {e}"))
+                })?;
                 zip.write_all(bytes)
-                    .map_err(|e| format!("Failed to write image data: {e}"))?;
+                    .map_err(|e| ExportError::Io(format!("Failed to write image data: {e}")))?;
+            }
+        }
+        for (i, result) in results.iter().enumerate() {
+            if let Some((bytes, ext)) = result {
+                let entry_name = format!("{images_dir}/step-{}-result.{ext}", i + 1);
+                zip.start_file(&entry_name, opts).map_err(|e| {
+                    ExportError::Io(format!("Failed to create image entry in zip: {e}"))
+                })?;
+                zip.write_all(bytes)
+                    .map_err(|e| ExportError::Io(format!("Failed to write image data: {e}")))?;
             }
         }
 
         zip.finish()
-            .map_err(|e| format!("Failed to finalize zip: {e}"))?
+            .map_err(|e| ExportError::Io(format!("Failed to finalize zip: {e}")))?
             .into_inner()
     };
 
@@ -146,9 +429,28 @@ pub fn write_localized(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::recorder::types::{ActionType, Step};
+    use crate::recorder::types::{ActionType, BoundsPercent, CaptureBounds, Step};
     use std::path::Path;
 
+    /// Standard-dialect export without progress reporting.
+    fn write_with_options(
+        title: &str,
+        steps: &[Step],
+        output_path: &str,
+        locale: Locale,
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        write_with_progress(
+            title,
+            steps,
+            output_path,
+            locale,
+            options,
+            MarkdownVariant::Standard,
+            &ProgressReporter::silent(),
+        )
+    }
+
     fn sample_step() -> Step {
         Step {
             id: "s1".into(),
@@ -170,9 +472,120 @@ mod tests {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
         }
     }
 
+    /// Three steps touching every variant difference: a note, a multi-line
+    /// description, an images folder with a space and a step without screenshot.
+    fn golden_fixture(variant: MarkdownVariant) -> String {
+        let steps = [
+            Step {
+                screenshot_path: Some("step-1.png".into()),
+                description: Some("Click \"Share\"".into()),
+                note: Some("Files land in the {team} folder.".into()),
+                ..sample_step()
+            },
+            Step {
+                id: "s2".into(),
+                ts: 4_000,
+                screenshot_path: Some("step-2.png".into()),
+                description: Some("Open the Export dialog\nPick PDF as the format".into()),
+                wait_ms: Some(2_000),
+                ..sample_step()
+            },
+            Step {
+                id: "s3".into(),
+                ts: 9_000,
+                action: ActionType::Note,
+                description: Some("Check that the file was copied.".into()),
+                ..sample_step()
+            },
+        ];
+        let md = generate_variant(
+            "My Guide",
+            &steps,
+            "My Guide-images",
+            &["webp", "png", "png"],
+            &[],
+            Locale::En,
+            &ExportOptions::default(),
+            variant,
+        );
+        let today = crate::i18n::export_date(Locale::En, chrono::Local::now().date_naive());
+        md.replace(&today, "{date}")
+    }
+
+    #[test]
+    fn variants_match_golden_files() {
+        let cases = [
+            (
+                MarkdownVariant::Standard,
+                include_str!("../../tests/fixtures/markdown/md.md"),
+            ),
+            (
+                MarkdownVariant::Notion,
+                include_str!("../../tests/fixtures/markdown/md-notion.md"),
+            ),
+            (
+                MarkdownVariant::Confluence,
+                include_str!("../../tests/fixtures/markdown/md-confluence.md"),
+            ),
+        ];
+        for (variant, expected) in cases {
+            assert_eq!(golden_fixture(variant), expected, "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn variants_skip_html_where_the_platform_rejects_it() {
+        let options = ExportOptions {
+            markdown_image_max_width: Some(600),
+            author: Some("Ada".into()),
+            ..ExportOptions::default()
+        };
+        let step = Step {
+            screenshot_path: Some("step-1.png".into()),
+            ..sample_step()
+        };
+        let render = |variant| {
+            generate_variant(
+                "G",
+                &[step.clone()],
+                "G-images",
+                &["png"],
+                &[],
+                Locale::En,
+                &options,
+                variant,
+            )
+        };
+
+        let standard = render(MarkdownVariant::Standard);
+        assert!(standard.starts_with("---\ntitle:"));
+        assert!(standard.contains("<img src="));
+
+        let confluence = render(MarkdownVariant::Confluence);
+        assert!(confluence.starts_with("# G"));
+        assert!(!confluence.contains('<'));
+        assert!(confluence.contains("![Step 1: Click](G-images/step-1.png)"));
+    }
+
     #[test]
     fn generate_contains_title() {
         let md = generate_content(
@@ -184,6 +597,16 @@ mod tests {
         assert!(md.starts_with("# Test Guide — "));
     }
 
+    #[test]
+    fn step_title_is_a_subheading_above_the_description() {
+        let mut titled = sample_step();
+        titled.title = Some("Save the draft".into());
+        let md = generate_content("G", &[titled, sample_step()], "g-images", &["png", "png"]);
+        assert!(md.contains("## Step 1\n\n### Save the draft\n\n**"));
+        assert!(md.contains("## Step 2\n\n**"));
+        assert_eq!(md.matches("### ").count(), 1);
+    }
+
     #[test]
     fn generate_contains_step_count() {
         let md = generate_content(
@@ -228,7 +651,239 @@ mod tests {
         let mut s = sample_step();
         s.note = Some("Important!".into());
         let md = generate_content("G", &[s], "g-images", &["png"]);
-        assert!(md.contains("> Important!"));
+        assert!(md.contains("> **Note:** Important!"));
+    }
+
+    #[test]
+    fn generate_includes_wait_only_when_set() {
+        let md = generate_content("G", &[sample_step()], "g-images", &["png"]);
+        assert!(!md.contains('⏳'));
+
+        let mut s = sample_step();
+        s.wait_ms = Some(2_000);
+        let md = generate_content("G", &[s], "g-images", &["png"]);
+        assert!(md.contains("⏳ *Wait ~2s*"));
+    }
+
+    #[test]
+    fn generate_marks_space_changes() {
+        let md = generate_content("G", &[sample_step()], "g-images", &["png"]);
+        assert!(!md.contains("Switched to another desktop"));
+
+        let mut s = sample_step();
+        s.space_changed = true;
+        let md = generate_content("G", &[s], "g-images", &["png"]);
+        assert!(md.contains("## Step 1\n\n*Switched to another desktop (Space)*\n\n**"));
+    }
+
+    #[test]
+    fn generate_links_page_urls() {
+        let mut s = sample_step();
+        s.page_url = Some("https://docs.google.com/document/d/1".into());
+        let md = generate_content("G", &[s], "g-images", &["png"]);
+        assert!(md.contains("\n\n<https://docs.google.com/document/d/1>\n\n"));
+    }
+
+    #[test]
+    fn generate_shows_menu_breadcrumbs() {
+        let mut s = sample_step();
+        s.ax = serde_json::from_value(serde_json::json!({
+            "role": "AXMenuItem",
+            "label": "PDF",
+            "is_cancel_button": false,
+            "is_default_button": false,
+            "menu_path": "File ▸ Export ▸ PDF",
+        }))
+        .unwrap();
+        let md = generate_content("G", &[s], "g-images", &["png"]);
+        assert!(md.contains("*Menu: File ▸ Export ▸ PDF*\n\n"));
+    }
+
+    #[test]
+    fn generate_front_matter_only_with_metadata() {
+        let md = generate_content("G", &[sample_step()], "g-images", &["png"]);
+        assert!(md.starts_with("# G"));
+
+        let options = ExportOptions {
+            author: Some("Ana \"AJ\" Jones".into()),
+            recorded_on: chrono::NaiveDate::from_ymd_opt(2025, 3, 7),
+            tags: vec!["onboarding".into(), "mail".into()],
+            ..ExportOptions::default()
+        };
+        let md = generate_content_with_options(
+            "Set up mail",
+            &[sample_step()],
+            "g-images",
+            &["png"],
+            &[None],
+            Locale::En,
+            &options,
+        );
+        assert!(md.starts_with(
+            "---\ntitle: \"Set up mail\"\nauthor: \"Ana \\\"AJ\\\" Jones\"\ndate: 2025-03-07\ntags: [\"onboarding\", \"mail\"]\n---\n\n# Set up mail"
+        ));
+    }
+
+    #[test]
+    fn front_matter_option_adds_a_leading_block() {
+        let render = |options: &ExportOptions| {
+            generate_content_with_options(
+                "Set up mail",
+                &[sample_step(), sample_step()],
+                "g-images",
+                &["png", "png"],
+                &[None, None],
+                Locale::En,
+                options,
+            )
+        };
+        assert!(render(&ExportOptions::default()).starts_with("# Set up mail"));
+
+        let options = ExportOptions {
+            markdown_front_matter: true,
+            markdown_weight: Some(20),
+            recorded_on: chrono::NaiveDate::from_ymd_opt(2025, 3, 7),
+            ..ExportOptions::default()
+        };
+        assert!(render(&options).starts_with(
+            "---\ntitle: \"Set up mail\"\ndate: 2025-03-07\nweight: 20\nstep_count: 2\n---\n\n# Set up mail"
+        ));
+
+        // Without a recording date it still has one, so the block stays valid for Hugo.
+        let md = render(&ExportOptions {
+            markdown_front_matter: true,
+            ..ExportOptions::default()
+        });
+        let block = md
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("\n---\n\n"))
+            .map(|(yaml, _)| yaml)
+            .expect("leading front matter block");
+        assert!(block.lines().any(|line| line.starts_with("date: ")));
+        assert!(block.ends_with("step_count: 2"));
+        assert!(!block.contains("weight"));
+    }
+
+    #[test]
+    fn generate_german_chrome_differs_from_english() {
+        let mut s = sample_step();
+        s.note = Some("Wichtig".into());
+        let steps = [s];
+        let en = generate_content_localized("G", &steps, "g-images", &["png"], Locale::En);
+        let de = generate_content_localized("G", &steps, "g-images", &["png"], Locale::De);
+        assert_ne!(en, de);
+        assert!(en.contains("> **Note:** Wichtig"));
+        assert!(de.contains("> **Notiz:** Wichtig"));
+        assert!(en.contains("*Recorded with StepCast · "));
+        assert!(de.contains("*Aufgenommen mit StepCast · "));
+    }
+
+    #[test]
+    fn generate_elapsed_time_in_headings() {
+        let mut steps = [sample_step(), sample_step(), sample_step()];
+        steps[0].ts = 10_000;
+        steps[1].ts = 75_000;
+        // Moved before the first step in the editor.
+        steps[2].ts = 4_000;
+        let options = ExportOptions {
+            elapsed_time: true,
+            ..ExportOptions::default()
+        };
+        let md = generate_content_with_options(
+            "G",
+            &steps,
+            "g-images",
+            &["png"; 3],
+            &[],
+            Locale::En,
+            &options,
+        );
+        assert!(md.contains("## Step 1 · +00:00\n"));
+        assert!(md.contains("## Step 2 · +01:05\n"));
+        assert!(md.contains("## Step 3 · +00:00\n"));
+
+        let md = generate_content("G", &steps, "g-images", &["png"; 3]);
+        assert!(md.contains("## Step 2\n"));
+    }
+
+    #[test]
+    fn width_constrained_mode_emits_img_tags() {
+        let mut landscape = sample_step();
+        landscape.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
+        landscape.capture_bounds = Some(CaptureBounds {
+            x: 0,
+            y: 0,
+            width: 1600,
+            height: 1000,
+            display_width: 1600,
+            display_height: 1000,
+        });
+        // Cropped to a 400x800 pt portrait region.
+        let mut portrait = landscape.clone();
+        portrait.crop_region = Some(BoundsPercent {
+            x_percent: 10.0,
+            y_percent: 10.0,
+            width_percent: 25.0,
+            height_percent: 80.0,
+        });
+        let steps = [landscape, portrait];
+        let options = ExportOptions {
+            markdown_image_max_width: Some(800),
+            ..ExportOptions::default()
+        };
+
+        let md = generate_content_with_options(
+            "G",
+            &steps,
+            "My Guide-images",
+            &["webp", "png"],
+            &[],
+            Locale::En,
+            &options,
+        );
+        assert!(md.contains(
+            r#"<img src="./My%20Guide-images/step-1.webp" alt="Step 1: Click" width="800">"#
+        ));
+        assert!(md.contains(
+            r#"<img src="./My%20Guide-images/step-2.png" alt="Step 2: Click" width="400">"#
+        ));
+        assert!(!md.contains("!["));
+
+        let md = generate_content("G", &steps, "My Guide-images", &["webp", "png"]);
+        assert!(md.contains("![Step 1: Click](<./My Guide-images/step-1.webp>)"));
+        assert!(!md.contains("<img"));
+    }
+
+    #[test]
+    fn generate_adds_captioned_result_frame() {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
+        let steps = [s.clone(), s];
+        let md = generate_content_with_options(
+            "G",
+            &steps,
+            "g-images",
+            &["png", "png"],
+            &[None, Some("webp")],
+            Locale::De,
+            &ExportOptions::default(),
+        );
+        assert!(md.contains(
+            "![Schritt 2: Klicke](<./g-images/step-2.png>)\n\n*Ergebnis*\n\n\
+             ![Schritt 2: Ergebnis](<./g-images/step-2-result.webp>)"
+        ));
+        assert!(!md.contains("step-1-result"));
+    }
+
+    #[test]
+    fn generate_captions_auth_placeholder() {
+        let mut s = sample_step();
+        s.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
+        s.is_secure_placeholder = true;
+        let md = generate_content_localized("G", &[s], "g-images", &["png"], Locale::De);
+        assert!(
+            md.contains("*Die Passwortabfrage wurde aus Datenschutzgründen nicht aufgenommen.*")
+        );
     }
 
     #[test]
@@ -236,7 +891,7 @@ mod tests {
         let mut s = sample_step();
         s.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
         let md = generate_content("G", &[s], "my-guide-images", &["webp"]);
-        assert!(md.contains("![Step 1](<./my-guide-images/step-1.webp>)"));
+        assert!(md.contains("![Step 1: Click](<./my-guide-images/step-1.webp>)"));
     }
 
     #[test]
@@ -244,7 +899,7 @@ mod tests {
         let mut s = sample_step();
         s.screenshot_path = Some("/tmp/nonexistent-fake-file.png".into());
         let md = generate_content("G", &[s], "my-guide-images", &["png"]);
-        assert!(md.contains("![Step 1](<./my-guide-images/step-1.png>)"));
+        assert!(md.contains("![Step 1: Click](<./my-guide-images/step-1.png>)"));
     }
 
     #[test]
@@ -266,6 +921,54 @@ mod tests {
         assert_eq!(images_dir_name(p), "readme-images");
     }
 
+    #[test]
+    fn text_only_mode_has_no_image_references() {
+        use std::io::Read as _;
+        use tempfile::TempDir;
+        use zip::ZipArchive;
+
+        let tmp = TempDir::new().unwrap();
+        let img_path = tmp.path().join("screenshot.png");
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 128, 255, 255]))
+            .save(&img_path)
+            .unwrap();
+        let mut s = sample_step();
+        s.screenshot_path = Some(img_path.to_str().unwrap().to_string());
+        s.result_screenshot_path = s.screenshot_path.clone();
+        s.is_secure_placeholder = true;
+        s.note = Some("Use your admin password".into());
+        let options = ExportOptions {
+            text_only: true,
+            markdown_image_max_width: Some(800),
+            ..ExportOptions::default()
+        };
+
+        let zip_path = tmp.path().join("Guide.zip");
+        write_with_options(
+            "Guide",
+            &[s],
+            zip_path.to_str().unwrap(),
+            Locale::En,
+            &options,
+        )
+        .unwrap();
+
+        let mut archive = ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names, ["Guide.md"]);
+        let mut md = String::new();
+        archive
+            .by_name("Guide.md")
+            .unwrap()
+            .read_to_string(&mut md)
+            .unwrap();
+        assert!(!md.contains("!["));
+        assert!(!md.contains("<img"));
+        assert!(!md.contains("Guide-images"));
+        assert!(md.contains("## Step 1\n"));
+        assert!(md.contains("> **Note:** Use your admin password"));
+    }
+
     #[test]
     fn write_creates_valid_zip() {
         use std::io::Cursor;
@@ -285,10 +988,12 @@ mod tests {
         let step_no_img = sample_step();
 
         let zip_path = tmp.path().join("My Guide.zip");
-        write(
+        write_with_options(
             "My Guide",
             &[step_with_img, step_no_img],
             zip_path.to_str().unwrap(),
+            Locale::En,
+            &ExportOptions::default(),
         )
         .unwrap();
 
@@ -348,7 +1053,14 @@ mod tests {
         step.screenshot_path = Some(img_path.to_str().unwrap().to_string());
 
         let zip_path = tmp.path().join("Guide.zip");
-        write("Guide", &[step], zip_path.to_str().unwrap()).unwrap();
+        write_with_options(
+            "Guide",
+            &[step],
+            zip_path.to_str().unwrap(),
+            Locale::En,
+            &ExportOptions::default(),
+        )
+        .unwrap();
 
         let data = std::fs::read(&zip_path).unwrap();
         let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
@@ -1,43 +1,311 @@
+pub mod animation;
+pub mod automation;
+pub mod branding;
+mod diff;
+pub mod filename;
 pub mod helpers;
 pub mod html;
 pub mod markdown;
 pub mod pdf;
+mod pdf_outline;
+mod pdf_pages;
+pub mod upload;
 
 use crate::i18n::Locale;
 use crate::recorder::types::Step;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fmt;
 use std::path::Path;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ExportFormat {
     Html,
-    Markdown,
+    /// HTML with its screenshots as separate files, bundled into one zip.
+    HtmlZip,
+    /// Markdown in a zip with its images, in the dialect of the target platform.
+    Markdown(markdown::MarkdownVariant),
     Pdf,
+    /// Animated walkthrough: each screenshot held with a pulsing click marker.
+    Gif,
+    /// JSON with each step's AX data and timing for UI test automation; no screenshots.
+    Automation,
 }
 
 impl ExportFormat {
-    pub fn from_str(s: &str) -> Result<Self, String> {
+    pub fn from_str(s: &str) -> Result<Self, ExportError> {
         match s {
             "html" => Ok(Self::Html),
-            "md" => Ok(Self::Markdown),
+            "html-zip" => Ok(Self::HtmlZip),
+            "md" => Ok(Self::Markdown(markdown::MarkdownVariant::Standard)),
+            "md-notion" => Ok(Self::Markdown(markdown::MarkdownVariant::Notion)),
+            "md-confluence" => Ok(Self::Markdown(markdown::MarkdownVariant::Confluence)),
             "pdf" => Ok(Self::Pdf),
-            other => Err(format!("Unknown export format: {other}")),
+            "gif" => Ok(Self::Gif),
+            "automation" => Ok(Self::Automation),
+            other => Err(ExportError::UnknownFormat(other.to_string())),
+        }
+    }
+
+    /// File extension of the written file. Markdown is bundled with its images.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::HtmlZip | Self::Markdown(_) => "zip",
+            Self::Pdf => "pdf",
+            Self::Gif => "gif",
+            Self::Automation => "json",
+        }
+    }
+
+    /// Content type of the written file.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Html => "text/html",
+            Self::HtmlZip | Self::Markdown(_) => "application/zip",
+            Self::Pdf => "application/pdf",
+            Self::Gif => "image/gif",
+            Self::Automation => "application/json",
+        }
+    }
+}
+
+/// Why an export failed. Variants other than `UnknownFormat` carry the message shown
+/// to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportError {
+    /// The requested format name is not one of ours.
+    UnknownFormat(String),
+    /// The output folder or file is read-only or locked.
+    PermissionDenied(String),
+    /// The volume is (about to be) full.
+    NotEnoughSpace(String),
+    /// Any other file system failure, including a missing output folder.
+    Io(String),
+    /// Building the document failed: unreadable screenshots, WebKit errors.
+    Render(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::UnknownFormat(format) => write!(f, "Unknown export format: {format}"),
+            ExportError::PermissionDenied(msg)
+            | ExportError::NotEnoughSpace(msg)
+            | ExportError::Io(msg)
+            | ExportError::Render(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// Export-time rendering options chosen in the export sheet.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Composite a numbered badge near the click point into each screenshot.
+    pub number_badges: bool,
+    /// Outline what changed since the previous step when both screenshots show the
+    /// same screen area.
+    pub diff_highlight: bool,
+    /// Prepend a linked step index (HTML) and add per-step bookmarks (PDF).
+    pub include_toc: bool,
+    /// Show each step's time since the first step, e.g. "+00:42".
+    pub elapsed_time: bool,
+    /// Markdown only: emit screenshots as `<img width>` tags fitting this many pixels
+    /// instead of plain `![]()` references, which render at full size.
+    pub markdown_image_max_width: Option<u32>,
+    /// Markdown only: always start with YAML front matter (title, date, step count)
+    /// for Hugo and Jekyll, not just when author, date or tags are set.
+    pub markdown_front_matter: bool,
+    /// Markdown only: `weight` in that front matter, which orders pages in site menus.
+    pub markdown_weight: Option<i32>,
+    /// HTML only: full-width screenshots or a compact row with thumbnails per step.
+    pub html_layout: html::HtmlLayout,
+    /// Leave out all screenshots: only step numbers, descriptions and notes. For
+    /// screen readers and for diffing guides in version control.
+    pub text_only: bool,
+    /// Add what a screen reader announces for each step's clicked element.
+    pub screen_reader_text: bool,
+    /// Size and encoding of embedded screenshots.
+    pub image: ExportImageOptions,
+    /// Guide author, shown under the title (HTML, PDF) and in Markdown front matter.
+    pub author: Option<String>,
+    /// Day the guide was recorded, shown with the author.
+    pub recorded_on: Option<chrono::NaiveDate>,
+    /// Free-form tags, listed under the title and in Markdown front matter.
+    pub tags: Vec<String>,
+    /// Frame size and timing of GIF exports.
+    pub animation: AnimationOptions,
+    /// Logo, colors, footer and font of HTML and PDF exports.
+    pub branding: branding::ExportBranding,
+}
+
+/// Encoding for exported screenshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportImageFormat {
+    /// WebP (or PNG when smaller) for HTML/Markdown, JPEG for PDF.
+    #[default]
+    Auto,
+    Jpeg,
+    /// Lossless WebP. PDFs get JPEG instead, which they embed without re-encoding.
+    WebP,
+    Png,
+}
+
+impl std::str::FromStr for ExportImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            "webp" => Ok(Self::WebP),
+            "png" => Ok(Self::Png),
+            other => Err(format!("Unknown image format: {other}")),
+        }
+    }
+}
+
+/// Default cap on exported image width: Retina captures are 2x and rarely need it.
+pub const DEFAULT_EXPORT_MAX_WIDTH: u32 = 1600;
+/// Default cap on the long edge of exported images, so 5K and multi-monitor captures
+/// don't bloat PDFs.
+pub const DEFAULT_EXPORT_MAX_DIMENSION: u32 = 2560;
+/// Default JPEG quality for exported images.
+pub const DEFAULT_EXPORT_QUALITY: u8 = 85;
+
+/// How screenshots are processed for export. Applied to copies only; session
+/// screenshots on disk are never modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportImageOptions {
+    /// Downscale (Lanczos) images wider than this, keeping aspect ratio. `None` keeps
+    /// the original size.
+    pub max_width: Option<u32>,
+    /// Downscale images whose long edge, after cropping, exceeds this. `None` keeps
+    /// the original size.
+    pub max_dimension: Option<u32>,
+    /// JPEG quality, 1-100. WebP output is lossless and ignores it.
+    pub quality: u8,
+    pub format: ExportImageFormat,
+}
+
+impl Default for ExportImageOptions {
+    fn default() -> Self {
+        Self {
+            max_width: Some(DEFAULT_EXPORT_MAX_WIDTH),
+            max_dimension: Some(DEFAULT_EXPORT_MAX_DIMENSION),
+            quality: DEFAULT_EXPORT_QUALITY,
+            format: ExportImageFormat::Auto,
         }
     }
 }
 
-/// Turn an IO error into a user-friendly message.
-fn friendly_write_error(e: &std::io::Error, path: &str) -> String {
+/// Default GIF frame size; screenshots are letterboxed into it.
+pub const DEFAULT_ANIMATION_WIDTH: u32 = 1280;
+pub const DEFAULT_ANIMATION_HEIGHT: u32 = 720;
+/// Default time each step stays on screen in a GIF.
+pub const DEFAULT_STEP_HOLD_MS: u32 = 3000;
+
+/// How GIF exports are laid out and timed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationOptions {
+    pub width: u32,
+    pub height: u32,
+    /// How long each step is shown, in milliseconds.
+    pub step_hold_ms: u32,
+    /// Show each step's description in a band below the screenshot.
+    pub captions: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_ANIMATION_WIDTH,
+            height: DEFAULT_ANIMATION_HEIGHT,
+            step_hold_ms: DEFAULT_STEP_HOLD_MS,
+            captions: false,
+        }
+    }
+}
+
+/// What a finished export produced, returned to the frontend by `export_guide`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct ExportSummary {
+    /// Screenshots and result frames scaled down to fit the image size limits.
+    pub images_downscaled: usize,
+    /// Size of the written file in bytes.
+    pub output_bytes: u64,
+    /// Localized problems that did not stop the export, e.g. a branding logo left out.
+    pub warnings: Vec<String>,
+}
+
+/// Export progress, sent to the frontend as `export-progress` events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ExportProgress {
+    pub done: usize,
+    pub total: usize,
+    /// Set once the output file is written.
+    pub finished: bool,
+}
+
+/// Counts processed steps and reports them to an export's progress callback.
+pub struct ProgressReporter<'a> {
+    total: usize,
+    done: Cell<usize>,
+    callback: &'a dyn Fn(ExportProgress),
+}
+
+fn ignore_progress(_: ExportProgress) {}
+
+impl<'a> ProgressReporter<'a> {
+    pub fn new(total: usize, callback: &'a dyn Fn(ExportProgress)) -> Self {
+        Self {
+            total,
+            done: Cell::new(0),
+            callback,
+        }
+    }
+
+    /// Reporter for callers that don't track progress.
+    pub fn silent() -> ProgressReporter<'static> {
+        ProgressReporter::new(0, &ignore_progress)
+    }
+
+    /// Call after each step's image has been processed.
+    pub fn step_done(&self) {
+        let done = self.done.get() + 1;
+        self.done.set(done);
+        (self.callback)(ExportProgress {
+            done,
+            total: self.total,
+            finished: false,
+        });
+    }
+
+    pub fn finish(&self) {
+        (self.callback)(ExportProgress {
+            done: self.total,
+            total: self.total,
+            finished: true,
+        });
+    }
+}
+
+/// Turn an IO error into an [`ExportError`] with a user-friendly message.
+fn friendly_write_error(e: &std::io::Error, path: &str) -> ExportError {
     match e.kind() {
         std::io::ErrorKind::PermissionDenied => {
-            format!("Cannot save to \"{path}\" — permission denied. Is the file open in another app or the folder read-only?")
+            ExportError::PermissionDenied(format!("Cannot save to \"{path}\" — permission denied. Is the file open in another app or the folder read-only?"))
         }
         std::io::ErrorKind::NotFound => {
-            format!("The folder for \"{path}\" does not exist.")
+            ExportError::Io(format!("The folder for \"{path}\" does not exist."))
         }
         _ if e.raw_os_error() == Some(28) /* ENOSPC */ => {
-            "Not enough disk space to save the file.".to_string()
+            ExportError::NotEnoughSpace("Not enough disk space to save the file.".to_string())
         }
-        _ => format!("Could not save file: {e}"),
+        _ => ExportError::Io(format!("Could not save file: {e}")),
     }
 }
 
@@ -45,18 +313,18 @@ fn friendly_write_error(e: &std::io::Error, path: &str) -> String {
 ///
 /// Checks: parent dir writable (tempfile probe), existing file writable,
 /// sufficient disk space. Total cost: ~3 syscalls, <1ms.
-fn validate_write_access(output_path: &str, estimated_bytes: u64) -> Result<(), String> {
+fn validate_write_access(output_path: &str, estimated_bytes: u64) -> Result<(), ExportError> {
     let path = Path::new(output_path);
 
     let parent = path
         .parent()
-        .ok_or_else(|| format!("Invalid output path: \"{output_path}\""))?;
+        .ok_or_else(|| ExportError::Io(format!("Invalid output path: \"{output_path}\"")))?;
 
     if !parent.exists() {
-        return Err(format!(
+        return Err(ExportError::Io(format!(
             "The folder \"{}\" does not exist.",
             parent.display()
-        ));
+        )));
     }
 
     // Probe writability: create a temp file in the same directory
@@ -68,11 +336,14 @@ fn validate_write_access(output_path: &str, estimated_bytes: u64) -> Result<(),
         Err(e) => {
             let _ = std::fs::remove_file(&probe_path);
             return Err(match e.kind() {
-                std::io::ErrorKind::PermissionDenied => format!(
+                std::io::ErrorKind::PermissionDenied => ExportError::PermissionDenied(format!(
                     "Cannot write to folder \"{}\" — permission denied.",
                     parent.display()
-                ),
-                _ => format!("Cannot write to folder \"{}\": {e}", parent.display()),
+                )),
+                _ => ExportError::Io(format!(
+                    "Cannot write to folder \"{}\": {e}",
+                    parent.display()
+                )),
             });
         }
     }
@@ -81,11 +352,11 @@ fn validate_write_access(output_path: &str, estimated_bytes: u64) -> Result<(),
     if path.exists() {
         if let Err(e) = std::fs::OpenOptions::new().write(true).open(path) {
             return Err(match e.kind() {
-                std::io::ErrorKind::PermissionDenied => format!(
+                std::io::ErrorKind::PermissionDenied => ExportError::PermissionDenied(format!(
                     "Cannot overwrite \"{}\" — the file is read-only or locked.",
                     path.display()
-                ),
-                _ => format!("Cannot write to \"{}\": {e}", path.display()),
+                )),
+                _ => ExportError::Io(format!("Cannot write to \"{}\": {e}", path.display())),
             });
         }
     }
@@ -94,13 +365,13 @@ fn validate_write_access(output_path: &str, estimated_bytes: u64) -> Result<(),
     if let Some(dir_str) = parent.to_str() {
         if let Ok(avail) = available_disk_space(dir_str) {
             const MIN_BUFFER: u64 = 10 * 1024 * 1024; // 10 MB safety margin
-            let needed = estimated_bytes + MIN_BUFFER;
+            let needed = estimated_bytes.saturating_add(MIN_BUFFER);
             if avail < needed {
                 let need_mb = needed / (1024 * 1024);
                 let have_mb = avail / (1024 * 1024);
-                return Err(format!(
+                return Err(ExportError::NotEnoughSpace(format!(
                     "Not enough disk space. Need ~{need_mb} MB, but only {have_mb} MB available."
-                ));
+                )));
             }
         }
     }
@@ -121,25 +392,190 @@ fn available_disk_space(path: &str) -> std::io::Result<u64> {
 }
 
 /// Unified export: writes the given steps to output_path in the requested format.
+///
+/// `on_progress` is called after each step's image is processed and once more when
+/// the file has been written.
+#[allow(clippy::too_many_arguments)]
 pub fn export(
     title: &str,
     steps: &[Step],
     format: ExportFormat,
     output_path: &str,
-    app: &tauri::AppHandle,
+    pdf_host: &dyn pdf::PdfHost,
     locale: Locale,
-) -> Result<(), String> {
+    options: &ExportOptions,
+    on_progress: &dyn Fn(ExportProgress),
+) -> Result<ExportSummary, ExportError> {
     // Pre-validate before expensive work (~500KB per step estimate)
     let estimated_bytes = (steps.len() as u64) * 500_000 + 100_000;
     validate_write_access(output_path, estimated_bytes)?;
 
+    let progress = ProgressReporter::new(steps.len(), on_progress);
     match format {
         ExportFormat::Html => {
-            let content = html::generate_localized(title, steps, locale);
+            let content = html::generate_with_progress(
+                title,
+                steps,
+                helpers::ImageTarget::Web,
+                locale,
+                options,
+                &progress,
+            );
             std::fs::write(output_path, content).map_err(|e| friendly_write_error(&e, output_path))
         }
-        ExportFormat::Markdown => markdown::write_localized(title, steps, output_path, locale),
-        ExportFormat::Pdf => pdf::write(title, steps, output_path, app, locale),
+        ExportFormat::HtmlZip => {
+            html::write_zip(title, steps, output_path, locale, options, &progress)
+        }
+        ExportFormat::Markdown(variant) => markdown::write_with_progress(
+            title,
+            steps,
+            output_path,
+            locale,
+            options,
+            variant,
+            &progress,
+        ),
+        ExportFormat::Pdf => pdf::write(
+            title,
+            steps,
+            output_path,
+            pdf_host,
+            locale,
+            options,
+            &pdf::PdfTheme::from_branding(&options.branding),
+            &progress,
+        ),
+        ExportFormat::Gif => {
+            animation::write(title, steps, output_path, locale, options, &progress)
+        }
+        ExportFormat::Automation => automation::write(title, steps, output_path, &progress),
+    }?;
+    progress.finish();
+    Ok(ExportSummary {
+        // GIF frames are letterboxed to the animation size instead.
+        images_downscaled: match format {
+            ExportFormat::Gif | ExportFormat::Automation => 0,
+            _ => helpers::downscaled_image_count(steps, options),
+        },
+        output_bytes: std::fs::metadata(output_path).map_or(0, |m| m.len()),
+        warnings: match format {
+            ExportFormat::Html | ExportFormat::HtmlZip | ExportFormat::Pdf => {
+                branding::warnings(&options.branding, locale)
+            }
+            _ => Vec::new(),
+        },
+    })
+}
+
+/// Width of the PDF preview image in pixels, sharp at the export sheet's size on Retina.
+pub const PDF_PREVIEW_WIDTH: u32 = 800;
+
+/// What an export would produce, rendered for the export sheet without touching the
+/// output location.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "content", rename_all = "snake_case")]
+pub enum ExportPreview {
+    /// The HTML document; screenshots are URLs from the caller's `image_url`.
+    Html(String),
+    /// The Markdown file, referencing images as the export bundle would.
+    Markdown(String),
+    /// Base64 PNG of the PDF's first page.
+    PdfPage(String),
+    /// Base64 PNG of the GIF's first frame.
+    GifFrame(String),
+    /// The automation JSON.
+    Automation(String),
+}
+
+/// Render a preview of exporting `steps` as `format`. Nothing is written to disk.
+///
+/// The HTML preview links the session's screenshot files through `image_url` instead
+/// of embedding re-encoded copies, so it shows them uncropped and without number badges;
+/// click markers are placed for the uncropped image.
+#[allow(clippy::too_many_arguments)]
+pub fn preview(
+    title: &str,
+    steps: &[Step],
+    format: ExportFormat,
+    pdf_host: &dyn pdf::PdfHost,
+    locale: Locale,
+    options: &ExportOptions,
+    image_url: &dyn Fn(&Path) -> Option<String>,
+) -> Result<ExportPreview, ExportError> {
+    match format {
+        ExportFormat::Html | ExportFormat::HtmlZip => {
+            let uncropped: Vec<Step> = steps
+                .iter()
+                .map(|step| Step {
+                    crop_region: None,
+                    ..step.clone()
+                })
+                .collect();
+            let url = |path: Option<&String>| path.and_then(|p| image_url(Path::new(p)));
+            let image_srcs: Vec<Option<String>> = steps
+                .iter()
+                .map(|step| url(step.screenshot_path.as_ref()))
+                .collect();
+            let result_srcs: Vec<Option<String>> = steps
+                .iter()
+                .map(|step| url(step.result_screenshot_path.as_ref()))
+                .collect();
+            Ok(ExportPreview::Html(html::generate_with_image_srcs(
+                title,
+                &uncropped,
+                locale,
+                options,
+                &image_srcs,
+                &result_srcs,
+            )))
+        }
+        ExportFormat::Markdown(variant) => {
+            // Auto picks WebP or PNG per image after encoding; WebP is the usual outcome.
+            let ext = match options.image.format {
+                ExportImageFormat::Jpeg => "jpg",
+                ExportImageFormat::Png => "png",
+                ExportImageFormat::Auto | ExportImageFormat::WebP => "webp",
+            };
+            let result_exts: Vec<Option<&str>> = steps
+                .iter()
+                .map(|step| step.result_screenshot_path.as_ref().map(|_| ext))
+                .collect();
+            Ok(ExportPreview::Markdown(markdown::generate_variant(
+                title,
+                steps,
+                &markdown::images_dir_name(Path::new(title)),
+                &vec![ext; steps.len()],
+                &result_exts,
+                locale,
+                options,
+                variant,
+            )))
+        }
+        ExportFormat::Pdf => {
+            use base64::Engine as _;
+            let png = pdf::first_page_png(
+                title,
+                steps,
+                pdf_host,
+                locale,
+                options,
+                &pdf::PdfTheme::from_branding(&options.branding),
+                PDF_PREVIEW_WIDTH,
+            )?;
+            Ok(ExportPreview::PdfPage(
+                base64::engine::general_purpose::STANDARD.encode(png),
+            ))
+        }
+        ExportFormat::Gif => {
+            use base64::Engine as _;
+            let png = animation::first_frame_png(title, steps, locale, options)?;
+            Ok(ExportPreview::GifFrame(
+                base64::engine::general_purpose::STANDARD.encode(png),
+            ))
+        }
+        ExportFormat::Automation => Ok(ExportPreview::Automation(automation::generate(
+            title, steps,
+        ))),
     }
 }
 
@@ -153,19 +589,77 @@ mod tests {
             ExportFormat::from_str("html"),
             Ok(ExportFormat::Html)
         ));
+        assert!(matches!(
+            ExportFormat::from_str("html-zip"),
+            Ok(ExportFormat::HtmlZip)
+        ));
         assert!(matches!(
             ExportFormat::from_str("md"),
-            Ok(ExportFormat::Markdown)
+            Ok(ExportFormat::Markdown(markdown::MarkdownVariant::Standard))
+        ));
+        assert!(matches!(
+            ExportFormat::from_str("md-notion"),
+            Ok(ExportFormat::Markdown(markdown::MarkdownVariant::Notion))
+        ));
+        assert!(matches!(
+            ExportFormat::from_str("md-confluence"),
+            Ok(ExportFormat::Markdown(
+                markdown::MarkdownVariant::Confluence
+            ))
         ));
         assert!(matches!(
             ExportFormat::from_str("pdf"),
             Ok(ExportFormat::Pdf)
         ));
+        assert!(matches!(
+            ExportFormat::from_str("gif"),
+            Ok(ExportFormat::Gif)
+        ));
+        assert!(matches!(
+            ExportFormat::from_str("automation"),
+            Ok(ExportFormat::Automation)
+        ));
+        assert_eq!(ExportFormat::Automation.extension(), "json");
+    }
+
+    #[test]
+    fn progress_reports_each_step_then_completion() {
+        let events = std::cell::RefCell::new(Vec::new());
+        let on_progress = |p: ExportProgress| events.borrow_mut().push(p);
+        let progress = ProgressReporter::new(3, &on_progress);
+        let steps = vec![Step::sample(), Step::sample(), Step::sample()];
+
+        html::generate_with_progress(
+            "Guide",
+            &steps,
+            helpers::ImageTarget::Web,
+            Locale::En,
+            &ExportOptions::default(),
+            &progress,
+        );
+        progress.finish();
+
+        let events = events.into_inner();
+        assert_eq!(events.len(), 4);
+        let done: Vec<usize> = events.iter().map(|p| p.done).collect();
+        assert_eq!(done, vec![1, 2, 3, 3]);
+        assert!(events.iter().all(|p| p.total == 3));
+        assert!(events[..3].iter().all(|p| !p.finished));
+        assert!(events[3].finished);
     }
 
     #[test]
     fn format_from_str_invalid() {
-        assert!(ExportFormat::from_str("docx").is_err());
+        let err = ExportFormat::from_str("docx").unwrap_err();
+        assert_eq!(err, ExportError::UnknownFormat("docx".into()));
+        assert_eq!(err.to_string(), "Unknown export format: docx");
+    }
+
+    #[test]
+    fn image_format_from_str() {
+        assert_eq!("jpg".parse(), Ok(ExportImageFormat::Jpeg));
+        assert_eq!("webp".parse(), Ok(ExportImageFormat::WebP));
+        assert!("gif".parse::<ExportImageFormat>().is_err());
     }
 
     #[test]
@@ -178,8 +672,119 @@ mod tests {
     #[test]
     fn validate_write_access_nonexistent_parent() {
         let result = validate_write_access("/nonexistent/dir/file.html", 1000);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        assert!(matches!(&result, Err(ExportError::Io(msg)) if msg.contains("does not exist")));
+    }
+
+    #[test]
+    fn validate_write_access_reports_missing_space() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("huge.pdf");
+        let result = validate_write_access(path.to_str().unwrap(), u64::MAX);
+        assert!(matches!(result, Err(ExportError::NotEnoughSpace(_))));
+    }
+
+    #[test]
+    fn validate_write_access_reports_read_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("locked.html");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o444)).unwrap();
+        // Root ignores file modes; nothing to check then.
+        if std::fs::OpenOptions::new().write(true).open(&path).is_ok() {
+            return;
+        }
+        let result = validate_write_access(path.to_str().unwrap(), 1000);
+        assert!(matches!(result, Err(ExportError::PermissionDenied(_))));
+    }
+
+    /// Preview tests never render PDFs.
+    struct NoPdfHost;
+
+    impl pdf::PdfHost for NoPdfHost {
+        fn dispatch(&self, _task: Box<dyn FnOnce() + Send>) -> Result<(), String> {
+            Err("no PDF rendering in tests".into())
+        }
+    }
+
+    #[test]
+    fn preview_links_session_images_instead_of_embedding_them() {
+        let mut step = Step::sample();
+        step.screenshot_path = Some("/sessions/abc/step-1.png".into());
+        step.crop_region = Some(crate::recorder::types::BoundsPercent {
+            x_percent: 10.0,
+            y_percent: 10.0,
+            width_percent: 50.0,
+            height_percent: 50.0,
+        });
+        let image_url = |path: &Path| {
+            let name = path.file_name()?.to_str()?;
+            Some(format!("stepcast-session://localhost/{name}"))
+        };
+
+        let preview = preview(
+            "Guide",
+            &[step.clone()],
+            ExportFormat::Html,
+            &NoPdfHost,
+            Locale::En,
+            &ExportOptions::default(),
+            &image_url,
+        )
+        .expect("html preview");
+        let ExportPreview::Html(html) = preview else {
+            panic!("expected HTML, got {preview:?}");
+        };
+        assert!(html.contains(r#"src="stepcast-session://localhost/step-1.png""#));
+        assert!(!html.contains("data:image"));
+
+        let preview = preview(
+            "Guide",
+            &[step],
+            ExportFormat::Markdown(markdown::MarkdownVariant::Standard),
+            &NoPdfHost,
+            Locale::En,
+            &ExportOptions::default(),
+            &image_url,
+        )
+        .expect("markdown preview");
+        let ExportPreview::Markdown(md) = preview else {
+            panic!("expected Markdown, got {preview:?}");
+        };
+        assert!(md.starts_with("# Guide"));
+        assert!(md.contains("Guide-images/"));
+    }
+
+    #[test]
+    fn preview_serializes_kind_and_content() {
+        assert_eq!(
+            serde_json::to_value(ExportPreview::PdfPage("iVBO".into())).unwrap(),
+            serde_json::json!({ "kind": "pdf_page", "content": "iVBO" })
+        );
+    }
+
+    #[test]
+    fn write_errors_map_to_variants() {
+        use std::io::{Error, ErrorKind};
+
+        let path = "/tmp/guide.pdf";
+        assert!(matches!(
+            friendly_write_error(&Error::from(ErrorKind::PermissionDenied), path),
+            ExportError::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            friendly_write_error(&Error::from_raw_os_error(28), path),
+            ExportError::NotEnoughSpace(_)
+        ));
+        assert_eq!(
+            friendly_write_error(&Error::from(ErrorKind::NotFound), path).to_string(),
+            "The folder for \"/tmp/guide.pdf\" does not exist."
+        );
+        assert!(matches!(
+            friendly_write_error(&Error::other("broken pipe"), path),
+            ExportError::Io(msg) if msg == "Could not save file: broken pipe"
+        ));
     }
 
     #[test]
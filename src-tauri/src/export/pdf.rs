@@ -1,12 +1,102 @@
+use super::pdf_outline::{self, StepOutline};
+use super::pdf_pages::{self, PageLayout};
+use super::ExportError;
 use crate::i18n::Locale;
 use crate::recorder::types::Step;
 use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long WebKit gets to load, paginate and render the guide.
+const PDF_RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where PDF rendering runs. WKWebView must live on the main thread, and its
+/// callbacks only fire while that thread's run loop turns.
+pub trait PdfHost {
+    /// Run `task` on the main thread.
+    fn dispatch(&self, task: Box<dyn FnOnce() + Send>) -> Result<(), String>;
+
+    /// Wait up to `timeout` for the PDF `task` reports on `rx`.
+    fn wait(
+        &self,
+        rx: &mpsc::Receiver<Result<Vec<u8>, ExportError>>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, ExportError> {
+        rx.recv_timeout(timeout)
+            .map_err(|_| timeout_error(timeout))?
+    }
+}
+
+/// The app's event loop owns the main thread; export threads hand work to it.
+impl PdfHost for tauri::AppHandle {
+    fn dispatch(&self, task: Box<dyn FnOnce() + Send>) -> Result<(), String> {
+        self.run_on_main_thread(task)
+            .map_err(|e| format!("Failed to dispatch to main thread: {e}"))
+    }
+}
+
+/// Renders without a Tauri event loop (the `--export` command line mode): created on
+/// the main thread, it runs the work inline and turns the run loop itself while waiting.
+pub struct HeadlessPdfHost {
+    _main_thread: objc2_foundation::MainThreadMarker,
+}
 
-/// Post-process PDF bytes via PDFKit to optimize images.
+impl HeadlessPdfHost {
+    pub fn new() -> Result<Self, String> {
+        let mtm = objc2_foundation::MainThreadMarker::new()
+            .ok_or("PDF export must run on the main thread")?;
+        // WebKit expects an application object, even for an off-screen view.
+        let _ = objc2_app_kit::NSApplication::sharedApplication(mtm);
+        Ok(Self { _main_thread: mtm })
+    }
+}
+
+impl PdfHost for HeadlessPdfHost {
+    fn dispatch(&self, task: Box<dyn FnOnce() + Send>) -> Result<(), String> {
+        task();
+        Ok(())
+    }
+
+    fn wait(
+        &self,
+        rx: &mpsc::Receiver<Result<Vec<u8>, ExportError>>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, ExportError> {
+        use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match rx.try_recv() {
+                Ok(result) => return result,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Err(ExportError::Render(
+                        "PDF generation failed: renderer stopped".into(),
+                    ))
+                }
+                Err(mpsc::TryRecvError::Empty) if Instant::now() >= deadline => {
+                    return Err(timeout_error(timeout))
+                }
+                Err(mpsc::TryRecvError::Empty) => unsafe {
+                    CFRunLoop::run_in_mode(kCFRunLoopDefaultMode, Duration::from_millis(50), true);
+                },
+            }
+        }
+    }
+}
+
+fn timeout_error(timeout: Duration) -> ExportError {
+    ExportError::Render(format!("PDF generation timed out ({}s)", timeout.as_secs()))
+}
+
+/// Post-process PDF bytes via PDFKit: split into pages, link the table of contents,
+/// add step bookmarks and optimize images.
 ///
 /// Applies `saveAllImagesAsJPEG` + `optimizeImagesForScreen` options.
 /// Best-effort: returns original bytes on any failure.
-fn optimize_pdf_bytes(pdf_bytes: &[u8]) -> Vec<u8> {
+fn optimize_pdf_bytes(
+    pdf_bytes: &[u8],
+    outline: Option<&StepOutline>,
+    pages: Option<&PageLayout>,
+) -> Vec<u8> {
     use objc2::msg_send;
     use objc2::rc::Retained;
     use objc2::AnyThread;
@@ -21,6 +111,14 @@ fn optimize_pdf_bytes(pdf_bytes: &[u8]) -> Vec<u8> {
         return pdf_bytes.to_vec();
     };
 
+    if let Some(pages) = pages {
+        pdf_pages::split_into_pages(&doc, pages.pages);
+        pdf_pages::add_toc_links(&doc, pages);
+    }
+    if let Some(outline) = outline {
+        pdf_outline::add_step_outline(&doc, outline);
+    }
+
     // Build options dict via msg_send (bypass typed NSDictionary generics)
     let yes = NSNumber::new_bool(true);
     let keys: [&NSString; 2] = unsafe {
@@ -47,54 +145,270 @@ fn optimize_pdf_bytes(pdf_bytes: &[u8]) -> Vec<u8> {
     optimized.to_vec()
 }
 
+/// Page footer: the branding's footer text, "Page N of M" and the StepCast credit.
+fn pdf_footer_template(locale: Locale, footer_text: Option<&str>) -> String {
+    let pages = format!(
+        "{} · {}",
+        crate::i18n::export_page_footer_template(locale),
+        crate::i18n::export_recorded_with(locale)
+    );
+    match footer_text {
+        Some(text) => format!("{text} · {pages}"),
+        None => pages,
+    }
+}
+
+/// Look of a PDF export: heading color, a logo on the cover or header, and the body font.
+/// Absent fields keep the default styling.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfTheme {
+    /// Heading color as a hex color like `#7c5cfc`; invalid values are ignored.
+    pub accent_color: Option<String>,
+    /// Logo image; left out when the file is missing or no image.
+    pub logo_path: Option<String>,
+    /// CSS font family for the body text, e.g. `Helvetica Neue`.
+    pub font_family: Option<String>,
+}
+
+impl PdfTheme {
+    /// The theme the export branding asks for.
+    pub fn from_branding(branding: &super::branding::ExportBranding) -> Self {
+        Self {
+            accent_color: branding.primary_color.clone(),
+            logo_path: branding.logo_path.clone(),
+            font_family: branding.font.css_stack().map(str::to_string),
+        }
+    }
+
+    /// Style rules layered over the PDF stylesheet; empty for the default theme.
+    fn css(&self) -> String {
+        let mut css = String::new();
+        if let Some(color) = self
+            .accent_color
+            .as_deref()
+            .and_then(super::branding::normalize_hex_color)
+        {
+            css.push_str(&format!(".pdf h1, .pdf h2 {{ color: {color}; }}\n"));
+        }
+        if let Some(family) = self.font_family.as_deref().and_then(css_font_family) {
+            css.push_str(&format!(
+                "body.pdf {{ font-family: {family}, -apple-system, sans-serif; }}\n"
+            ));
+        }
+        css
+    }
+}
+
+/// `family` with everything but name characters removed, so it cannot end the rule.
+fn css_font_family(family: &str) -> Option<String> {
+    let cleaned: String = family
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | ',' | '\'' | '"'))
+        .collect();
+    let cleaned = cleaned.trim().trim_matches(',').trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
 /// Export steps as PDF using macOS WKWebView.createPDF() (macOS 11+).
+///
+/// The guide is paginated with "Page N of M" footers; with `include_toc` it opens with
+/// a linked table of contents and gets per-step bookmarks.
+#[allow(clippy::too_many_arguments)]
 pub fn write(
     title: &str,
     steps: &[Step],
     output_path: &str,
-    app: &tauri::AppHandle,
+    host: &dyn PdfHost,
     locale: Locale,
-) -> Result<(), String> {
-    let html =
-        super::html::generate_for_locale(title, steps, super::helpers::ImageTarget::Pdf, locale);
-    let path = output_path.to_string();
+    options: &super::ExportOptions,
+    theme: &PdfTheme,
+    progress: &super::ProgressReporter,
+) -> Result<(), ExportError> {
+    let bytes = render(title, steps, host, locale, options, theme, progress)?;
+    std::fs::write(output_path, bytes).map_err(|e| super::friendly_write_error(&e, output_path))
+}
 
-    let (tx, rx) = mpsc::channel::<Result<(), String>>();
+/// PNG of the PDF's first page, `width` pixels wide, for the export preview.
+pub fn first_page_png(
+    title: &str,
+    steps: &[Step],
+    host: &dyn PdfHost,
+    locale: Locale,
+    options: &super::ExportOptions,
+    theme: &PdfTheme,
+    width: u32,
+) -> Result<Vec<u8>, ExportError> {
+    let bytes = render(
+        title,
+        steps,
+        host,
+        locale,
+        options,
+        theme,
+        &super::ProgressReporter::silent(),
+    )?;
+    page_png(&bytes, 0, width)
+        .ok_or_else(|| ExportError::Render("Could not render the PDF preview".into()))
+}
 
-    app.run_on_main_thread(move || {
-        render_pdf_on_main_thread(&html, &path, tx);
-    })
-    .map_err(|e| format!("Failed to dispatch to main thread: {e}"))?;
+/// The guide's HTML for PDF rendering, styled with `theme`.
+fn themed_html(
+    title: &str,
+    steps: &[Step],
+    locale: Locale,
+    options: &super::ExportOptions,
+    theme: &PdfTheme,
+    progress: &super::ProgressReporter,
+) -> String {
+    // The theme's logo and accent color take the branding's place on the cover.
+    let options = super::ExportOptions {
+        branding: super::branding::ExportBranding {
+            logo_path: theme.logo_path.clone(),
+            primary_color: theme.accent_color.clone(),
+            ..options.branding.clone()
+        },
+        ..options.clone()
+    };
+    let html = super::html::generate_with_progress(
+        title,
+        steps,
+        super::helpers::ImageTarget::Pdf,
+        locale,
+        &options,
+        progress,
+    );
+    html.replacen("</style>", &format!("{}</style>", theme.css()), 1)
+}
 
-    rx.recv_timeout(std::time::Duration::from_secs(30))
-        .map_err(|_| "PDF generation timed out (30s)".to_string())?
+/// Render the guide to finished PDF bytes.
+fn render(
+    title: &str,
+    steps: &[Step],
+    host: &dyn PdfHost,
+    locale: Locale,
+    options: &super::ExportOptions,
+    theme: &PdfTheme,
+    progress: &super::ProgressReporter,
+) -> Result<Vec<u8>, ExportError> {
+    let html = themed_html(title, steps, locale, options, theme, progress);
+    let bookmark_labels: Vec<String> = if options.include_toc {
+        steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let num = i + 1;
+                format!(
+                    "{num}. {}",
+                    super::helpers::pdf_toc_title(step, num, locale)
+                )
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let footer_template = pdf_footer_template(locale, options.branding.footer_text());
+
+    let (tx, rx) = mpsc::channel::<Result<Vec<u8>, ExportError>>();
+
+    host.dispatch(Box::new(move || {
+        render_pdf_on_main_thread(&html, bookmark_labels, footer_template, tx);
+    }))
+    .map_err(ExportError::Render)?;
+
+    host.wait(&rx, PDF_RENDER_TIMEOUT)
+}
+
+/// Draw page `index` of a PDF as a PNG `width` pixels wide. `None` when the bytes are
+/// no PDF or the page does not exist.
+fn page_png(pdf_bytes: &[u8], index: usize, width: u32) -> Option<Vec<u8>> {
+    use objc2::AnyThread;
+    use objc2_foundation::{NSData, NSSize};
+    use objc2_pdf_kit::{PDFDisplayBox, PDFDocument};
+
+    let ns_data = NSData::with_bytes(pdf_bytes);
+    let doc = unsafe { PDFDocument::initWithData(PDFDocument::alloc(), &ns_data) }?;
+    let page = unsafe { doc.pageAtIndex(index) }?;
+    let bounds = unsafe { page.boundsForBox(PDFDisplayBox::MediaBox) };
+    if bounds.size.width <= 0.0 {
+        return None;
+    }
+    let scale = f64::from(width) / bounds.size.width;
+    let size = NSSize::new(f64::from(width), (bounds.size.height * scale).round());
+    let thumbnail = unsafe { page.thumbnailOfSize_forBox(size, PDFDisplayBox::MediaBox) };
+    let tiff = thumbnail.TIFFRepresentation()?;
+    let image = image::load_from_memory(&tiff.to_vec()).ok()?;
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png).ok()?;
+    Some(png.into_inner())
 }
 
-/// Must be called on the main thread. Creates an off-screen WKWebView,
-/// loads the HTML, waits for navigation to finish, then calls createPDF.
-fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Result<(), String>>) {
+/// Must be called on the main thread (see [`PdfHost`]). Creates an off-screen WKWebView,
+/// loads the HTML, waits for navigation to finish, paginates, then calls createPDF.
+/// With bookmark labels, the measured step positions also give the PDF an outline.
+fn render_pdf_on_main_thread(
+    html: &str,
+    bookmark_labels: Vec<String>,
+    footer_template: String,
+    tx: mpsc::Sender<Result<Vec<u8>, ExportError>>,
+) {
     use block2::RcBlock;
     use objc2::rc::Retained;
-    use objc2::runtime::{NSObjectProtocol, ProtocolObject};
-    use objc2::{define_class, msg_send, DefinedClass, MainThreadOnly};
+    use objc2::runtime::{AnyObject, NSObjectProtocol, ProtocolObject};
+    use objc2::{define_class, msg_send, DefinedClass, MainThreadOnly, Message};
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
     use objc2_foundation::{MainThreadMarker, NSData, NSError, NSObject, NSString};
     use objc2_web_kit::{
         WKNavigation, WKNavigationDelegate, WKPDFConfiguration, WKWebView, WKWebViewConfiguration,
     };
 
-    // SAFETY: This function is only called from run_on_main_thread.
+    // SAFETY: only called through `PdfHost::dispatch`, which runs on the main thread.
     let mtm = unsafe { MainThreadMarker::new_unchecked() };
 
     // ── Navigation delegate ────────────────────────────────────────────
     // When didFinishNavigation fires, we generate the PDF.
 
     struct DelegateIvars {
-        output_path: String,
-        tx: Option<mpsc::Sender<Result<(), String>>>,
+        bookmark_labels: Vec<String>,
+        footer_template: String,
+        tx: Option<mpsc::Sender<Result<Vec<u8>, ExportError>>>,
         webview: Option<Retained<WKWebView>>,
     }
 
+    /// Render the loaded page to PDF, post-process it, and report the bytes on `tx`.
+    unsafe fn create_pdf(
+        web_view: &WKWebView,
+        outline: Option<StepOutline>,
+        pages: Option<PageLayout>,
+        tx: mpsc::Sender<Result<Vec<u8>, ExportError>>,
+    ) {
+        // SAFETY: only reached from main-thread WebKit callbacks.
+        let mtm = MainThreadMarker::new_unchecked();
+        let pdf_config = WKPDFConfiguration::new(mtm);
+
+        let block = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+            let result = if !data.is_null() {
+                let raw_bytes = (*data).to_vec();
+                Ok(optimize_pdf_bytes(
+                    &raw_bytes,
+                    outline.as_ref(),
+                    pages.as_ref(),
+                ))
+            } else if !error.is_null() {
+                Err(ExportError::Render(format!(
+                    "PDF generation failed: {}",
+                    *error
+                )))
+            } else {
+                Err(ExportError::Render(
+                    "PDF generation failed: no data returned".into(),
+                ))
+            };
+            let _ = tx.send(result);
+        });
+
+        web_view.createPDFWithConfiguration_completionHandler(Some(&pdf_config), &block);
+    }
+
     define_class!(
         #[unsafe(super(NSObject))]
         #[thread_kind = MainThreadOnly]
@@ -112,10 +426,9 @@ fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Res
                 _navigation: Option<&WKNavigation>,
             ) {
                 let ivars = self.ivars();
-                let path = ivars.output_path.clone();
 
                 // Take the sender so it's consumed exactly once.
-                let tx: mpsc::Sender<Result<(), String>> = {
+                let tx: mpsc::Sender<Result<Vec<u8>, ExportError>> = {
                     let ptr = ivars as *const DelegateIvars as *mut DelegateIvars;
                     match (*ptr).tx.take() {
                         Some(tx) => tx,
@@ -123,25 +436,24 @@ fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Res
                     }
                 };
 
-                // SAFETY: We are on the main thread (delegate is MainThreadOnly).
-                let mtm = MainThreadMarker::new_unchecked();
-                let pdf_config = WKPDFConfiguration::new(mtm);
-
-                let block = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
-                    let result = if !data.is_null() {
-                        let raw_bytes = (*data).to_vec();
-                        let bytes = optimize_pdf_bytes(&raw_bytes);
-                        std::fs::write(&path, bytes)
-                            .map_err(|e| super::friendly_write_error(&e, &path))
-                    } else if !error.is_null() {
-                        Err(format!("PDF generation failed: {}", *error))
-                    } else {
-                        Err("PDF generation failed: no data returned".into())
-                    };
-                    let _ = tx.send(result);
+                // Paginate and measure first; a failed script only loses page breaks,
+                // links and the outline.
+                let labels = ivars.bookmark_labels.clone();
+                let web_view_ref = web_view.retain();
+                let js_block = RcBlock::new(move |result: *mut AnyObject, _error: *mut NSError| {
+                    let layout_json = result
+                        .as_ref()
+                        .and_then(|obj| obj.downcast_ref::<NSString>())
+                        .map(|json| json.to_string());
+                    let outline = layout_json
+                        .as_deref()
+                        .filter(|_| !labels.is_empty())
+                        .and_then(|json| pdf_outline::step_outline(&labels, json));
+                    let pages = layout_json.as_deref().and_then(pdf_pages::page_layout);
+                    create_pdf(&web_view_ref, outline, pages, tx.clone());
                 });
-
-                web_view.createPDFWithConfiguration_completionHandler(Some(&pdf_config), &block);
+                let js = NSString::from_str(&pdf_pages::paginate_js(&ivars.footer_template));
+                web_view.evaluateJavaScript_completionHandler(&js, Some(&js_block));
             }
         }
     );
@@ -149,11 +461,13 @@ fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Res
     impl NavDelegate {
         fn new(
             mtm: MainThreadMarker,
-            output_path: String,
-            tx: mpsc::Sender<Result<(), String>>,
+            bookmark_labels: Vec<String>,
+            footer_template: String,
+            tx: mpsc::Sender<Result<Vec<u8>, ExportError>>,
         ) -> Retained<Self> {
             let this = Self::alloc(mtm).set_ivars(DelegateIvars {
-                output_path,
+                bookmark_labels,
+                footer_template,
                 tx: Some(tx),
                 webview: None,
             });
@@ -169,7 +483,7 @@ fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Res
     let webview =
         unsafe { WKWebView::initWithFrame_configuration(WKWebView::alloc(mtm), frame, &config) };
 
-    let delegate = NavDelegate::new(mtm, output_path.to_string(), tx);
+    let delegate = NavDelegate::new(mtm, bookmark_labels, footer_template, tx);
 
     // Store webview in delegate so it stays alive.
     {
@@ -196,12 +510,139 @@ fn render_pdf_on_main_thread(html: &str, output_path: &str, tx: mpsc::Sender<Res
 
 #[cfg(test)]
 mod tests {
-    use super::optimize_pdf_bytes;
+    use super::{optimize_pdf_bytes, page_png, themed_html, PdfHost, PdfTheme};
+    use crate::export::{ExportError, ExportOptions, ProgressReporter};
+    use crate::i18n::Locale;
+    use crate::recorder::types::Step;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Skips WebKit and hands back fixed PDF bytes.
+    struct CannedHost;
+
+    impl PdfHost for CannedHost {
+        fn dispatch(&self, _task: Box<dyn FnOnce() + Send>) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn wait(
+            &self,
+            _rx: &mpsc::Receiver<Result<Vec<u8>, ExportError>>,
+            _timeout: Duration,
+        ) -> Result<Vec<u8>, ExportError> {
+            Ok(b"%PDF-1.7 guide".to_vec())
+        }
+    }
+
+    #[test]
+    fn invalid_logo_path_exports_without_logo() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let output = dir.path().join("guide.pdf");
+        let theme = PdfTheme {
+            logo_path: Some("/nonexistent/logo.png".into()),
+            ..PdfTheme::default()
+        };
+        let steps = [Step::sample()];
+
+        let html = themed_html(
+            "Guide",
+            &steps,
+            Locale::En,
+            &ExportOptions::default(),
+            &theme,
+            &ProgressReporter::silent(),
+        );
+        assert!(!html.contains(r#"<img class="brand-logo""#));
+
+        super::write(
+            "Guide",
+            &steps,
+            output.to_str().unwrap(),
+            &CannedHost,
+            Locale::En,
+            &ExportOptions::default(),
+            &theme,
+            &ProgressReporter::silent(),
+        )
+        .expect("export succeeds without the logo");
+        assert_eq!(std::fs::read(&output).unwrap(), b"%PDF-1.7 guide");
+    }
+
+    #[test]
+    fn theme_styles_headings_and_body_font() {
+        let theme = PdfTheme {
+            accent_color: Some("#0A84FF".into()),
+            logo_path: None,
+            font_family: Some("Helvetica Neue; } body { color: red".into()),
+        };
+        let html = themed_html(
+            "Guide",
+            &[Step::sample()],
+            Locale::En,
+            &ExportOptions::default(),
+            &theme,
+            &ProgressReporter::silent(),
+        );
+        assert!(html.contains(".pdf h1, .pdf h2 { color: #0a84ff; }"));
+        assert!(html.contains(
+            "body.pdf { font-family: Helvetica Neue  body  color red, -apple-system, sans-serif; }"
+        ));
+    }
+
+    #[test]
+    fn each_theme_field_changes_the_output() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let logo = dir.path().join("logo.png");
+        image::RgbaImage::new(4, 4).save(&logo).expect("write logo");
+        let html = |theme: &PdfTheme| {
+            themed_html(
+                "Guide",
+                &[Step::sample()],
+                Locale::En,
+                &ExportOptions::default(),
+                theme,
+                &ProgressReporter::silent(),
+            )
+        };
+        let plain = html(&PdfTheme::default());
+
+        let accent = html(&PdfTheme {
+            accent_color: Some("#ff3b30".into()),
+            ..PdfTheme::default()
+        });
+        assert_ne!(accent, plain);
+        assert!(accent.contains(".pdf h1, .pdf h2 { color: #ff3b30; }"));
+
+        let logo = html(&PdfTheme {
+            logo_path: Some(logo.to_string_lossy().into_owned()),
+            ..PdfTheme::default()
+        });
+        assert_ne!(logo, plain);
+        assert!(logo.contains(r#"<img class="brand-logo" src="data:image/png;base64,"#));
+
+        let font = html(&PdfTheme {
+            font_family: Some("Avenir Next".into()),
+            ..PdfTheme::default()
+        });
+        assert_ne!(font, plain);
+        assert!(font.contains("body.pdf { font-family: Avenir Next, -apple-system, sans-serif; }"));
+    }
+
+    #[test]
+    fn default_theme_adds_no_css() {
+        assert_eq!(PdfTheme::default().css(), "");
+        let invalid = PdfTheme {
+            accent_color: Some("blue-ish".into()),
+            font_family: Some(" ;{} ".into()),
+            ..PdfTheme::default()
+        };
+        assert_eq!(invalid.css(), "");
+    }
 
     #[test]
     fn optimize_pdf_bytes_returns_original_on_invalid_input() {
         let garbage = b"not a real pdf";
-        let result = optimize_pdf_bytes(garbage);
+        let result = optimize_pdf_bytes(garbage, None, None);
         assert_eq!(
             result, garbage,
             "invalid input should return original bytes"
@@ -211,7 +652,7 @@ mod tests {
     #[test]
     fn optimize_pdf_bytes_returns_original_on_empty_input() {
         let empty: &[u8] = &[];
-        let result = optimize_pdf_bytes(empty);
+        let result = optimize_pdf_bytes(empty, None, None);
         assert_eq!(result, empty, "empty input should return empty bytes");
     }
 
@@ -224,7 +665,7 @@ mod tests {
             xref\n0 4\n0000000000 65535 f \n0000000009 00000 n \n\
             0000000058 00000 n \n0000000115 00000 n \n\
             trailer<</Size 4/Root 1 0 R>>\nstartxref\n190\n%%EOF";
-        let result = optimize_pdf_bytes(minimal_pdf);
+        let result = optimize_pdf_bytes(minimal_pdf, None, None);
         // Should return *some* valid bytes (possibly re-encoded, possibly original)
         assert!(
             !result.is_empty(),
@@ -232,6 +673,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn page_png_draws_the_requested_page_width() {
+        let pixels = vec![200u8; 40 * 20 * 4];
+        let pdf = make_pdf_with_image(40, 20, &pixels);
+        let png = page_png(&pdf, 0, 120).expect("first page");
+        let image = image::load_from_memory(&png).expect("valid png");
+        assert_eq!(image.width(), 120);
+        assert_eq!(image.height(), 60);
+
+        assert!(page_png(&pdf, 1, 120).is_none());
+        assert!(page_png(b"not a pdf", 0, 120).is_none());
+    }
+
     /// Creates a PDFDocument with a single image page for size testing.
     fn make_pdf_with_image(w: usize, h: usize, pixels: &[u8]) -> Vec<u8> {
         use objc2::AnyThread;
@@ -360,11 +814,11 @@ mod tests {
             rgba_data.push(255);
         }
         let pdfkit_bytes = make_pdf_with_image(w, h, &rgba_data);
-        let pdfkit_opt = optimize_pdf_bytes(&pdfkit_bytes);
+        let pdfkit_opt = optimize_pdf_bytes(&pdfkit_bytes, None, None);
 
         // Test 2: Raw-stream PDF (simulates WKWebView lossless output)
         let raw_bytes = make_pdf_with_raw_image_stream(w, h, &rgb_data);
-        let raw_opt = optimize_pdf_bytes(&raw_bytes);
+        let raw_opt = optimize_pdf_bytes(&raw_bytes, None, None);
 
         let report = |label: &str, orig: &[u8], opt: &[u8]| {
             let o_kb = orig.len() / 1024;
@@ -422,6 +876,22 @@ mod tests {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
         };
         let result = super::super::html::generate("Test", &[step]);
         assert!(result.contains("<!doctype html>"));
@@ -458,6 +928,22 @@ mod tests {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
         };
 
         let html = super::super::html::generate_for("Test", &[step], ImageTarget::Pdf);
@@ -470,4 +956,24 @@ mod tests {
             "PDF path should not use WebP"
         );
     }
+
+    #[test]
+    fn footer_template_is_localized() {
+        assert_eq!(
+            pdf_footer_template(Locale::En, None),
+            "Page {page} of {pages} · Recorded with StepCast"
+        );
+        assert_eq!(
+            pdf_footer_template(Locale::De, None),
+            "Seite {page} von {pages} · Aufgenommen mit StepCast"
+        );
+    }
+
+    #[test]
+    fn footer_template_starts_with_branding_footer_text() {
+        assert_eq!(
+            pdf_footer_template(Locale::En, Some("Acme Corp · Internal")),
+            "Acme Corp · Internal · Page {page} of {pages} · Recorded with StepCast"
+        );
+    }
 }
@@ -0,0 +1,127 @@
+//! PDF bookmarks (outline) for exported guides.
+//!
+//! WKWebView's `createPDF` has no outline support, so the step positions are read
+//! from the laid-out page with JavaScript (see `pdf_pages::paginate_js`) and mapped
+//! onto the PDF pages afterwards.
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct StepLayout {
+    height: f64,
+    tops: Vec<f64>,
+}
+
+/// Bookmark labels paired with where each step starts in the rendered page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepOutline {
+    pub doc_height: f64,
+    pub entries: Vec<(String, f64)>,
+}
+
+/// Pair bookmark labels with the layout reported by `pdf_pages::paginate_js`.
+pub fn step_outline(labels: &[String], layout_json: &str) -> Option<StepOutline> {
+    let layout: StepLayout = serde_json::from_str(layout_json).ok()?;
+    if layout.height <= 0.0 {
+        return None;
+    }
+    Some(StepOutline {
+        doc_height: layout.height,
+        entries: labels.iter().cloned().zip(layout.tops).collect(),
+    })
+}
+
+/// Map a CSS offset from the top of the document to (page index, PDF y).
+///
+/// Pages are assumed equal height; PDF y grows upwards from the page bottom.
+pub fn bookmark_location(
+    top_px: f64,
+    doc_height_px: f64,
+    page_count: usize,
+    page_height: f64,
+) -> (usize, f64) {
+    let total = page_height * page_count as f64;
+    let offset = (top_px / doc_height_px).clamp(0.0, 1.0) * total;
+    let index = ((offset / page_height) as usize).min(page_count.saturating_sub(1));
+    let y = page_height - (offset - index as f64 * page_height);
+    (index, y.clamp(0.0, page_height))
+}
+
+/// Attach one top-level bookmark per step to `doc`.
+pub fn add_step_outline(doc: &objc2_pdf_kit::PDFDocument, outline: &StepOutline) {
+    use objc2::AnyThread;
+    use objc2_core_foundation::CGPoint;
+    use objc2_foundation::NSString;
+    use objc2_pdf_kit::{PDFDestination, PDFDisplayBox, PDFOutline};
+
+    let page_count = unsafe { doc.pageCount() };
+    if page_count == 0 {
+        return;
+    }
+    let Some(first_page) = (unsafe { doc.pageAtIndex(0) }) else {
+        return;
+    };
+    let page_height = unsafe { first_page.boundsForBox(PDFDisplayBox::MediaBox) }
+        .size
+        .height;
+    if page_height <= 0.0 {
+        return;
+    }
+
+    let root = unsafe { PDFOutline::init(PDFOutline::alloc()) };
+    for (i, (label, top)) in outline.entries.iter().enumerate() {
+        let (index, y) = bookmark_location(*top, outline.doc_height, page_count, page_height);
+        let Some(page) = (unsafe { doc.pageAtIndex(index) }) else {
+            continue;
+        };
+        // Split pages keep the tall page's coordinate space, offset by their origin.
+        let origin = unsafe { page.boundsForBox(PDFDisplayBox::MediaBox) }.origin;
+        unsafe {
+            let dest = PDFDestination::initWithPage_atPoint(
+                PDFDestination::alloc(),
+                &page,
+                CGPoint::new(origin.x, origin.y + y),
+            );
+            let item = PDFOutline::init(PDFOutline::alloc());
+            item.setLabel(Some(&NSString::from_str(label)));
+            item.setDestination(Some(&dest));
+            root.insertChild_atIndex(&item, i);
+        }
+    }
+    unsafe { doc.setOutlineRoot(Some(&root)) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_outline_pairs_labels_with_tops() {
+        let labels = vec!["1. Open".to_string(), "2. Save".to_string()];
+        let outline = step_outline(&labels, r#"{"height":2000,"tops":[120.5,900]}"#).unwrap();
+        assert_eq!(outline.doc_height, 2000.0);
+        assert_eq!(
+            outline.entries,
+            vec![
+                ("1. Open".to_string(), 120.5),
+                ("2. Save".to_string(), 900.0)
+            ]
+        );
+        assert!(step_outline(&labels, "not json").is_none());
+        assert!(step_outline(&labels, r#"{"height":0,"tops":[]}"#).is_none());
+    }
+
+    #[test]
+    fn bookmark_location_single_tall_page() {
+        assert_eq!(bookmark_location(0.0, 2000.0, 1, 2000.0), (0, 2000.0));
+        assert_eq!(bookmark_location(500.0, 2000.0, 1, 2000.0), (0, 1500.0));
+    }
+
+    #[test]
+    fn bookmark_location_spans_pages() {
+        // 3 pages of 800pt for a 2400px document: 1000px is 200pt into page 2.
+        assert_eq!(bookmark_location(1000.0, 2400.0, 3, 800.0), (1, 600.0));
+        // Past the end clamps to the last page bottom.
+        assert_eq!(bookmark_location(5000.0, 2400.0, 3, 800.0), (2, 0.0));
+    }
+}
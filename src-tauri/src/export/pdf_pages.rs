@@ -0,0 +1,264 @@
+//! Page layout for exported PDFs.
+//!
+//! WKWebView's `createPDF` renders the whole document as one tall page. Before
+//! rendering, [`paginate_js`] pushes step cards past page boundaries and adds
+//! "Page N of M" footers; afterwards the tall page is cut into equal pages and the
+//! table-of-contents entries become internal links.
+
+use super::pdf_outline::bookmark_location;
+use serde::Deserialize;
+
+/// Page height in CSS pixels: A4 portrait at the 800px render width.
+const PAGE_HEIGHT_PX: f64 = 1132.0;
+/// Band at the bottom of each page reserved for the footer.
+const FOOTER_HEIGHT_PX: f64 = 40.0;
+/// Content starting this close to a page top already counts as at the top.
+const PAGE_TOP_MARGIN_PX: f64 = 40.0;
+
+/// Script that paginates the loaded guide and returns its layout as JSON: document
+/// height, page count, the top of each step card and every table-of-contents link.
+pub fn paginate_js(footer_template: &str) -> String {
+    let footer = serde_json::to_string(footer_template).unwrap_or_else(|_| "\"\"".into());
+    format!(
+        r##"(() => {{
+const pageH = {PAGE_HEIGHT_PX}, footerH = {FOOTER_HEIGHT_PX}, topMargin = {PAGE_TOP_MARGIN_PX};
+const footer = {footer};
+const top = e => e.getBoundingClientRect().top + window.scrollY;
+let breakBefore = false;
+for (const el of document.querySelectorAll('.pdf-header, .timeline-item')) {{
+  const y = top(el);
+  const page = Math.floor(y / pageH);
+  const offset = y - page * pageH;
+  const fits = offset + el.offsetHeight <= pageH - footerH;
+  if (offset > topMargin && (breakBefore || !fits)) {{
+    el.style.marginTop = ((page + 1) * pageH + topMargin - y) + 'px';
+  }}
+  breakBefore = el.classList.contains('pdf-cover');
+}}
+const pages = Math.max(1, Math.ceil(document.documentElement.scrollHeight / pageH));
+document.body.style.minHeight = (pages * pageH) + 'px';
+for (let i = 0; i < pages; i++) {{
+  const f = document.createElement('div');
+  f.className = 'pdf-footer';
+  f.style.top = ((i + 1) * pageH - footerH) + 'px';
+  f.style.height = footerH + 'px';
+  f.textContent = footer.replace('{{page}}', i + 1).replace('{{pages}}', pages);
+  document.body.appendChild(f);
+}}
+return JSON.stringify({{
+  height: document.documentElement.scrollHeight,
+  pages,
+  tops: Array.from(document.querySelectorAll('.timeline-item')).map(top),
+  links: Array.from(document.querySelectorAll('.toc a[href^="#step-"]')).map(a => {{
+    const r = a.getBoundingClientRect();
+    return {{x: r.left + window.scrollX, y: r.top + window.scrollY, width: r.width, height: r.height,
+      step: parseInt(a.getAttribute('href').slice(6), 10) - 1}};
+  }}),
+}});
+}})()"##
+    )
+}
+
+/// A table-of-contents link in CSS pixels, pointing at the 0-based `step`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TocLink {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub step: usize,
+}
+
+/// Layout reported by [`paginate_js`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PageLayout {
+    #[serde(rename = "height")]
+    pub doc_height: f64,
+    pub pages: usize,
+    pub tops: Vec<f64>,
+    #[serde(default)]
+    pub links: Vec<TocLink>,
+}
+
+pub fn page_layout(layout_json: &str) -> Option<PageLayout> {
+    let layout: PageLayout = serde_json::from_str(layout_json).ok()?;
+    (layout.doc_height > 0.0 && layout.pages > 0).then_some(layout)
+}
+
+/// Vertical span (y, height) of page `index` when a tall page of `total_height`
+/// starting at `origin_y` is cut into `page_count` slices. PDF y grows upwards, so
+/// the first page is the top slice.
+fn page_slice(origin_y: f64, total_height: f64, page_count: usize, index: usize) -> (f64, f64) {
+    let height = total_height / page_count as f64;
+    (
+        origin_y + total_height - (index + 1) as f64 * height,
+        height,
+    )
+}
+
+/// Map a link to (page index, x, y, width, height) in PDF units, with y relative to
+/// the bottom of its page.
+fn link_bounds(
+    link: &TocLink,
+    doc_height: f64,
+    page_count: usize,
+    page_height: f64,
+) -> (usize, f64, f64, f64, f64) {
+    let scale = page_height * page_count as f64 / doc_height;
+    let (index, top) = bookmark_location(link.y, doc_height, page_count, page_height);
+    let height = link.height * scale;
+    (
+        index,
+        link.x * scale,
+        (top - height).max(0.0),
+        link.width * scale,
+        height,
+    )
+}
+
+/// Cut the single tall page WKWebView renders into `page_count` equal pages.
+/// Documents that already have several pages are left alone.
+pub fn split_into_pages(doc: &objc2_pdf_kit::PDFDocument, page_count: usize) {
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+    use objc2_pdf_kit::{PDFDisplayBox, PDFPage};
+
+    if page_count < 2 || unsafe { doc.pageCount() } != 1 {
+        return;
+    }
+    let Some(tall) = (unsafe { doc.pageAtIndex(0) }) else {
+        return;
+    };
+    let media = unsafe { tall.boundsForBox(PDFDisplayBox::MediaBox) };
+
+    // Copy every slice first so a failed copy leaves the document untouched.
+    let mut slices = Vec::with_capacity(page_count);
+    for _ in 0..page_count {
+        let copy: Option<Retained<PDFPage>> = unsafe { msg_send![&*tall, copy] };
+        let Some(page) = copy else {
+            return;
+        };
+        slices.push(page);
+    }
+    for (i, page) in slices.iter().enumerate() {
+        let (y, height) = page_slice(media.origin.y, media.size.height, page_count, i);
+        let rect = CGRect::new(
+            CGPoint::new(media.origin.x, y),
+            CGSize::new(media.size.width, height),
+        );
+        unsafe {
+            page.setBounds_forBox(rect, PDFDisplayBox::MediaBox);
+            page.setBounds_forBox(rect, PDFDisplayBox::CropBox);
+            doc.insertPage_atIndex(page, i + 1);
+        }
+    }
+    unsafe { doc.removePageAtIndex(0) };
+}
+
+/// Turn the table-of-contents entries into links to their step's page.
+pub fn add_toc_links(doc: &objc2_pdf_kit::PDFDocument, layout: &PageLayout) {
+    use objc2::AnyThread;
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+    use objc2_pdf_kit::{PDFAnnotation, PDFAnnotationSubtypeLink, PDFDestination, PDFDisplayBox};
+
+    let page_count = unsafe { doc.pageCount() };
+    let Some(first_page) = (unsafe { doc.pageAtIndex(0) }) else {
+        return;
+    };
+    let page_height = unsafe { first_page.boundsForBox(PDFDisplayBox::MediaBox) }
+        .size
+        .height;
+    if page_height <= 0.0 {
+        return;
+    }
+
+    for link in &layout.links {
+        let Some(&target_top) = layout.tops.get(link.step) else {
+            continue;
+        };
+        let (index, x, y, width, height) =
+            link_bounds(link, layout.doc_height, page_count, page_height);
+        let (target_index, target_y) =
+            bookmark_location(target_top, layout.doc_height, page_count, page_height);
+        let (Some(page), Some(target)) = (unsafe { doc.pageAtIndex(index) }, unsafe {
+            doc.pageAtIndex(target_index)
+        }) else {
+            continue;
+        };
+        // Split pages keep the tall page's coordinate space, offset by their origin.
+        let origin = unsafe { page.boundsForBox(PDFDisplayBox::MediaBox) }.origin;
+        let target_origin = unsafe { target.boundsForBox(PDFDisplayBox::MediaBox) }.origin;
+        unsafe {
+            let bounds = CGRect::new(
+                CGPoint::new(origin.x + x, origin.y + y),
+                CGSize::new(width, height),
+            );
+            let annotation = PDFAnnotation::initWithBounds_forType_withProperties(
+                PDFAnnotation::alloc(),
+                bounds,
+                PDFAnnotationSubtypeLink,
+                None,
+            );
+            let dest = PDFDestination::initWithPage_atPoint(
+                PDFDestination::alloc(),
+                &target,
+                CGPoint::new(target_origin.x, target_origin.y + target_y),
+            );
+            annotation.setDestination(Some(&dest));
+            page.addAnnotation(&annotation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paginate_js_embeds_escaped_footer() {
+        let js = paginate_js("Seite {page} von \"{pages}\"");
+        assert!(js.contains(r#"const footer = "Seite {page} von \"{pages}\"";"#));
+        assert!(js.contains("const pageH = 1132"));
+        assert!(js.contains(".replace('{page}', i + 1)"));
+    }
+
+    #[test]
+    fn page_layout_parses_script_output() {
+        let layout = page_layout(
+            r##"{"height":2264,"pages":2,"tops":[1172],
+            "links":[{"x":40,"y":200,"width":300,"height":18,"step":0}]}"##,
+        )
+        .unwrap();
+        assert_eq!(layout.doc_height, 2264.0);
+        assert_eq!(layout.pages, 2);
+        assert_eq!(layout.links[0].step, 0);
+        assert!(page_layout(r#"{"height":0,"pages":1,"tops":[]}"#).is_none());
+        assert!(page_layout("garbage").is_none());
+    }
+
+    #[test]
+    fn page_slice_starts_at_the_top() {
+        // A 3000pt tall page cut into 3: the first page is the top third.
+        assert_eq!(page_slice(0.0, 3000.0, 3, 0), (2000.0, 1000.0));
+        assert_eq!(page_slice(0.0, 3000.0, 3, 2), (0.0, 1000.0));
+        assert_eq!(page_slice(10.0, 3000.0, 3, 1), (1010.0, 1000.0));
+    }
+
+    #[test]
+    fn link_bounds_maps_to_page_and_scale() {
+        let link = TocLink {
+            x: 40.0,
+            y: 1232.0,
+            width: 300.0,
+            height: 20.0,
+            step: 3,
+        };
+        // Two 1132px pages rendered at 0.5pt per px.
+        let (index, x, y, width, height) = link_bounds(&link, 2264.0, 2, 566.0);
+        assert_eq!(index, 1);
+        assert_eq!((x, width, height), (20.0, 150.0, 10.0));
+        // Link top is 100px (50pt) below the top of page 2.
+        assert_eq!(y, 566.0 - 50.0 - 10.0);
+    }
+}
@@ -0,0 +1,250 @@
+//! Opt-in upload of a finished export to a team portal or other upload API.
+//!
+//! After `export_guide` writes a file, it is POSTed as `multipart/form-data` with the
+//! file in a `file` field. The upload runs in the background: the export result never
+//! waits for it, and the outcome arrives as an `export-uploaded` or
+//! `export-upload-failed` event.
+
+use super::ExportFormat;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Cap on server error text echoed back in `export-upload-failed`.
+const ERROR_BODY_MAX_CHARS: usize = 200;
+
+/// Where finished exports are uploaded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostExportWebhook {
+    /// Endpoint that receives the POST; empty turns the upload off.
+    pub url: String,
+    /// Sent verbatim as the `Authorization` header, e.g. `Bearer …`.
+    pub auth_header: Option<String>,
+}
+
+impl PostExportWebhook {
+    pub fn is_enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    fn auth_header(&self) -> Option<&str> {
+        self.auth_header
+            .as_deref()
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+    }
+}
+
+/// Payload of `export-uploaded`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportUploaded {
+    pub path: String,
+    pub url: String,
+    pub status: u16,
+}
+
+/// Payload of `export-upload-failed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportUploadFailed {
+    pub path: String,
+    pub url: String,
+    pub error: String,
+}
+
+/// POST the exported file at `path`. Returns the HTTP status on success.
+pub async fn upload(
+    webhook: &PostExportWebhook,
+    path: &Path,
+    format: ExportFormat,
+) -> Result<u16, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("guide.{}", format.extension()));
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("create http client: {e}"))?;
+    let url = webhook.url.trim();
+
+    let resp = build_request(&client, webhook, file_name, bytes, format)?
+        .send()
+        .await
+        .map_err(|e| request_error(&e, url))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(http_status_error(status.as_u16(), &body));
+    }
+    Ok(status.as_u16())
+}
+
+fn build_request(
+    client: &reqwest::Client,
+    webhook: &PostExportWebhook,
+    file_name: String,
+    bytes: Vec<u8>,
+    format: ExportFormat,
+) -> Result<reqwest::RequestBuilder, String> {
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(file_name)
+        .mime_str(format.mime())
+        .map_err(|e| format!("invalid content type: {e}"))?;
+    let form = reqwest::multipart::Form::new().part("file", part);
+    let mut request = client.post(webhook.url.trim()).multipart(form);
+    if let Some(auth) = webhook.auth_header() {
+        request = request.header(reqwest::header::AUTHORIZATION, auth);
+    }
+    Ok(request)
+}
+
+fn request_error(err: &reqwest::Error, url: &str) -> String {
+    if err.is_timeout() {
+        format!(
+            "Upload to {url} timed out after {}s.",
+            REQUEST_TIMEOUT.as_secs()
+        )
+    } else if err.is_connect() {
+        format!("Could not connect to {url}: {err}")
+    } else {
+        format!("Upload to {url} failed: {err}")
+    }
+}
+
+fn http_status_error(status: u16, body: &str) -> String {
+    let message: String = body.trim().chars().take(ERROR_BODY_MAX_CHARS).collect();
+    if message.is_empty() {
+        format!("Upload returned HTTP {status}.")
+    } else {
+        format!("Upload returned HTTP {status}: {message}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept one connection, answer with `status`, and return the raw request.
+    fn mock_server(status: &'static str) -> (String, std::thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/upload", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request_complete(&request) {
+                let n = stream.read(&mut buf).expect("read");
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            stream.write_all(response.as_bytes()).expect("write");
+            request
+        });
+        (url, handle)
+    }
+
+    fn request_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some(header_end) = text.find("\r\n\r\n") else {
+            return false;
+        };
+        let content_length = text[..header_end].lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())
+                .flatten()
+        });
+        match content_length {
+            Some(len) => request.len() >= header_end + 4 + len,
+            // Chunked body: done at the terminating zero-length chunk.
+            None => text.ends_with("0\r\n\r\n"),
+        }
+    }
+
+    fn write_export(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("Onboarding.pdf");
+        std::fs::write(&path, b"%PDF-1.7 guide").expect("write export");
+        path
+    }
+
+    #[test]
+    fn upload_posts_the_file_as_multipart_with_auth() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_export(dir.path());
+        let (url, server) = mock_server("201 Created");
+        let webhook = PostExportWebhook {
+            url,
+            auth_header: Some("Bearer secret".into()),
+        };
+
+        let status = tauri::async_runtime::block_on(upload(&webhook, &path, ExportFormat::Pdf));
+        assert_eq!(status, Ok(201));
+
+        let request = String::from_utf8_lossy(&server.join().unwrap()).into_owned();
+        assert!(request.starts_with("POST /upload HTTP/1.1\r\n"));
+        let headers = request.to_ascii_lowercase();
+        assert!(headers.contains("content-type: multipart/form-data; boundary="));
+        assert!(headers.contains("authorization: bearer secret"));
+        assert!(request
+            .contains(r#"Content-Disposition: form-data; name="file"; filename="Onboarding.pdf""#));
+        assert!(request.contains("Content-Type: application/pdf"));
+        assert!(request.contains("%PDF-1.7 guide"));
+    }
+
+    #[test]
+    fn upload_without_auth_header_sends_none() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_export(dir.path());
+        let (url, server) = mock_server("200 OK");
+        let webhook = PostExportWebhook {
+            url,
+            auth_header: Some("  ".into()),
+        };
+
+        let status = tauri::async_runtime::block_on(upload(&webhook, &path, ExportFormat::Pdf));
+        assert_eq!(status, Ok(200));
+        let request = String::from_utf8_lossy(&server.join().unwrap()).to_ascii_lowercase();
+        assert!(!request.contains("authorization:"));
+    }
+
+    #[test]
+    fn upload_reports_http_errors() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_export(dir.path());
+        let (url, server) = mock_server("403 Forbidden");
+        let webhook = PostExportWebhook {
+            url,
+            auth_header: None,
+        };
+
+        let result = tauri::async_runtime::block_on(upload(&webhook, &path, ExportFormat::Pdf));
+        assert_eq!(result, Err("Upload returned HTTP 403.".to_string()));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn blank_url_disables_the_upload() {
+        assert!(!PostExportWebhook::default().is_enabled());
+        assert!(!PostExportWebhook {
+            url: "  ".into(),
+            auth_header: None,
+        }
+        .is_enabled());
+        assert!(PostExportWebhook {
+            url: "https://portal.example.com/api/guides".into(),
+            auth_header: None,
+        }
+        .is_enabled());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::recorder::types::{ActionType, WindowControlKind};
 use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,6 +14,13 @@ impl Locale {
             Self::De => "de",
         }
     }
+
+    fn as_chrono(self) -> chrono::Locale {
+        match self {
+            Self::En => chrono::Locale::en_US,
+            Self::De => chrono::Locale::de_DE,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -116,8 +124,178 @@ pub fn export_step_heading(locale: Locale, num: usize) -> String {
     }
 }
 
-pub fn export_step_image_alt(locale: Locale, num: usize) -> String {
-    export_step_heading(locale, num)
+pub fn export_toc_heading(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Contents",
+        Locale::De => "Inhalt",
+    }
+}
+
+/// Table-of-contents entry for a step without a description.
+pub fn export_toc_step_fallback(locale: Locale, num: usize, app: &str) -> String {
+    format!("{} \u{2014} {app}", export_step_heading(locale, num))
+}
+
+/// Long date with localized month names ("March 7, 2025", "7. März 2025").
+pub fn export_date(locale: Locale, date: chrono::NaiveDate) -> String {
+    let pattern = match locale {
+        Locale::En => "%B %-d, %Y",
+        Locale::De => "%-d. %B %Y",
+    };
+    date.format_localized(pattern, locale.as_chrono())
+        .to_string()
+}
+
+pub fn export_generated_on(locale: Locale, date: chrono::NaiveDate) -> String {
+    let date = export_date(locale, date);
+    match locale {
+        Locale::En => format!("Generated on {date}"),
+        Locale::De => format!("Erstellt am {date}"),
+    }
+}
+
+/// Byline under a guide's title, "Recorded by Ana on March 7, 2025". `None` without
+/// author and date.
+pub fn export_byline(
+    locale: Locale,
+    author: Option<&str>,
+    date: Option<chrono::NaiveDate>,
+) -> Option<String> {
+    let date = date.map(|d| export_date(locale, d));
+    Some(match (locale, author, date) {
+        (Locale::En, Some(author), Some(date)) => format!("Recorded by {author} on {date}"),
+        (Locale::En, Some(author), None) => format!("Recorded by {author}"),
+        (Locale::En, None, Some(date)) => format!("Recorded on {date}"),
+        (Locale::De, Some(author), Some(date)) => format!("Aufgenommen von {author} am {date}"),
+        (Locale::De, Some(author), None) => format!("Aufgenommen von {author}"),
+        (Locale::De, None, Some(date)) => format!("Aufgenommen am {date}"),
+        (_, None, None) => return None,
+    })
+}
+
+/// Credit line at the end of exported guides.
+pub fn export_recorded_with(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Recorded with StepCast",
+        Locale::De => "Aufgenommen mit StepCast",
+    }
+}
+
+/// Label in front of a step's note.
+pub fn export_note_label(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Note:",
+        Locale::De => "Notiz:",
+    }
+}
+
+/// Export warning when the branding logo could not be loaded.
+pub fn export_branding_logo_skipped(locale: Locale, path: &str) -> String {
+    match locale {
+        Locale::En => format!("The branding logo \"{path}\" could not be loaded and was left out."),
+        Locale::De => {
+            format!("Das Branding-Logo „{path}“ konnte nicht geladen werden und wurde weggelassen.")
+        }
+    }
+}
+
+/// What a screen reader says for a step's clicked element, e.g. `“Save”, button`.
+pub fn export_screen_reader_announces(locale: Locale, announcement: &str) -> String {
+    match locale {
+        Locale::En => format!("Screen reader announces: {announcement}"),
+        Locale::De => format!("Screenreader liest vor: {announcement}"),
+    }
+}
+
+/// Label before a menu-item step's breadcrumb ("Menu: File ▸ Export ▸ PDF").
+pub fn export_menu_path_label(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Menu:",
+        Locale::De => "Menü:",
+    }
+}
+
+/// Caption under the placeholder image that stands in for an auth prompt.
+pub fn export_auth_placeholder_caption(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "The password prompt was not captured for privacy.",
+        Locale::De => "Die Passwortabfrage wurde aus Datenschutzgründen nicht aufgenommen.",
+    }
+}
+
+/// Page footer with `{page}` and `{pages}` placeholders, filled in during layout.
+pub fn export_page_footer_template(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Page {page} of {pages}",
+        Locale::De => "Seite {page} von {pages}",
+    }
+}
+
+/// Screenshot alt text: the step heading plus what was done on it.
+pub fn export_step_image_alt(locale: Locale, num: usize, action: &ActionType) -> String {
+    match action {
+        ActionType::Note | ActionType::Overview => export_step_heading(locale, num),
+        _ => format!(
+            "{}: {}",
+            export_step_heading(locale, num),
+            action_label(action, locale)
+        ),
+    }
+}
+
+/// Caption above a step's result frame, the window the click brought up.
+pub fn export_result_caption(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Result",
+        Locale::De => "Ergebnis",
+    }
+}
+
+pub fn export_result_image_alt(locale: Locale, num: usize) -> String {
+    format!(
+        "{}: {}",
+        export_step_heading(locale, num),
+        export_result_caption(locale)
+    )
+}
+
+/// A step's wait annotation, "Wait ~3s" / "Warten ~3 s". Seconds are rounded to
+/// halves; anything shorter reads as half a second.
+pub fn export_wait(locale: Locale, wait_ms: u32) -> String {
+    let halves = ((wait_ms as f64 / 500.0).round() as u64).max(1);
+    let seconds = if halves % 2 == 0 {
+        (halves / 2).to_string()
+    } else {
+        let seconds = format!("{}.5", halves / 2);
+        match locale {
+            Locale::En => seconds,
+            Locale::De => seconds.replace('.', ","),
+        }
+    };
+    match locale {
+        Locale::En => format!("Wait ~{seconds}s"),
+        Locale::De => format!("Warten ~{seconds} s"),
+    }
+}
+
+/// Imperative verb for a step's action, as shown next to its screenshot.
+pub fn action_label(action: &ActionType, locale: Locale) -> &'static str {
+    match (action, locale) {
+        (ActionType::Click, Locale::En) => "Click",
+        (ActionType::Click, Locale::De) => "Klicke",
+        (ActionType::DoubleClick, Locale::En) => "Double-click",
+        (ActionType::DoubleClick, Locale::De) => "Doppelklicke",
+        (ActionType::RightClick, Locale::En) => "Right-click",
+        (ActionType::RightClick, Locale::De) => "Rechtsklicke",
+        (ActionType::Shortcut, Locale::En) => "Press the shortcut",
+        (ActionType::Shortcut, Locale::De) => "Drücke das Tastenkürzel",
+        (ActionType::Drag, Locale::En) => "Drag",
+        (ActionType::Drag, Locale::De) => "Ziehe",
+        (ActionType::Hover, Locale::En) => "Point at",
+        (ActionType::Hover, Locale::De) => "Zeige auf",
+        (ActionType::Note, _) => step_action_note(locale),
+        (ActionType::Overview, _) => step_action_overview(locale),
+    }
 }
 
 pub fn step_action_note(locale: Locale) -> &'static str {
@@ -127,6 +305,48 @@ pub fn step_action_note(locale: Locale) -> &'static str {
     }
 }
 
+/// Text of the auto-inserted section break when the window context changes.
+pub fn step_context_break(locale: Locale, title: &str) -> String {
+    match locale {
+        Locale::En => format!("Now in: {title}"),
+        Locale::De => format!("Jetzt in: {title}"),
+    }
+}
+
+/// Annotation for steps recorded right after a switch to another Space.
+pub fn step_space_changed(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Switched to another desktop (Space)",
+        Locale::De => "Zu einem anderen Schreibtisch (Space) gewechselt",
+    }
+}
+
+/// A title-bar button's name, as macOS labels it in each language.
+pub fn window_control_label(locale: Locale, kind: WindowControlKind) -> &'static str {
+    match (locale, kind) {
+        (Locale::En, kind) => kind.label(),
+        (Locale::De, WindowControlKind::Close) => "Schließen",
+        (Locale::De, WindowControlKind::Minimize) => "Minimieren",
+        (Locale::De, WindowControlKind::Zoom) => "Zoomen",
+    }
+}
+
+/// Baseline text for a click on a title-bar button.
+pub fn step_action_window_control(locale: Locale, kind: WindowControlKind) -> String {
+    let label = window_control_label(locale, kind);
+    match locale {
+        Locale::En => format!("Click the window's {label} button"),
+        Locale::De => format!("Klicke auf die Schaltfläche „{label}“ des Fensters"),
+    }
+}
+
+pub fn step_action_overview(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Screen overview",
+        Locale::De => "Bildschirmübersicht",
+    }
+}
+
 pub fn step_action_clicked_in(locale: Locale) -> &'static str {
     match locale {
         Locale::En => "Clicked in",
@@ -148,6 +368,35 @@ pub fn step_action_right_clicked_in(locale: Locale) -> &'static str {
     }
 }
 
+/// "Press ⌘S" for a recorded keyboard shortcut.
+pub fn step_action_press_keys(locale: Locale, keys: &str) -> String {
+    match locale {
+        Locale::En => format!("Press {keys}"),
+        Locale::De => format!("Drücke {keys}"),
+    }
+}
+
+pub fn step_action_pointed_in(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Pointed at an item in",
+        Locale::De => "Auf ein Element gezeigt in",
+    }
+}
+
+pub fn step_action_dragged_in(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Dragged in",
+        Locale::De => "Gezogen in",
+    }
+}
+
+pub fn step_action_dragged_from_to(locale: Locale, from: &str, to: &str) -> String {
+    match locale {
+        Locale::En => format!("Dragged from {from} to {to}"),
+        Locale::De => format!("Gezogen von {from} nach {to}"),
+    }
+}
+
 pub fn step_action_shortcut_in(locale: Locale) -> &'static str {
     match locale {
         Locale::En => "Used keyboard shortcut in",
@@ -171,6 +420,13 @@ pub fn ai_eligibility_requires_apple_silicon(locale: Locale) -> &'static str {
     }
 }
 
+pub fn ai_descriptions_require_macos(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "AI descriptions require macOS.",
+        Locale::De => "KI-Beschreibungen erfordern macOS.",
+    }
+}
+
 pub fn ai_eligibility_unknown_macos_version(locale: Locale) -> &'static str {
     match locale {
         Locale::En => "Could not detect macOS version.",
@@ -238,6 +494,52 @@ pub fn tray_recording_tooltip(locale: Locale) -> &'static str {
     }
 }
 
+/// Tray status line while a session runs, e.g. "Recording — 14 steps · 3:05".
+pub fn tray_recording_status(
+    locale: Locale,
+    paused: bool,
+    step_count: usize,
+    recorded: std::time::Duration,
+) -> String {
+    let label = match (locale, paused) {
+        (Locale::En, false) => "Recording",
+        (Locale::En, true) => "Paused",
+        (Locale::De, false) => "Aufnahme",
+        (Locale::De, true) => "Pausiert",
+    };
+    let secs = recorded.as_secs();
+    let time = if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    };
+    format!(
+        "{label} \u{2014} {} \u{00b7} {time}",
+        export_step_count(locale, step_count)
+    )
+}
+
+pub fn tray_menu_pause(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Pause Recording",
+        Locale::De => "Aufnahme pausieren",
+    }
+}
+
+pub fn tray_menu_resume(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Resume Recording",
+        Locale::De => "Aufnahme fortsetzen",
+    }
+}
+
+pub fn tray_menu_stop(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "Stop Recording",
+        Locale::De => "Aufnahme beenden",
+    }
+}
+
 pub fn tray_menu_open(locale: Locale) -> &'static str {
     match locale {
         Locale::En => "Open StepCast",
@@ -292,17 +594,84 @@ mod tests {
         assert_eq!(resolve_locale(AppLanguage::De), Locale::De);
     }
 
+    #[test]
+    fn tray_recording_status_shows_count_and_time() {
+        use std::time::Duration;
+        assert_eq!(
+            tray_recording_status(Locale::En, false, 14, Duration::from_secs(185)),
+            "Recording \u{2014} 14 steps \u{00b7} 3:05"
+        );
+        assert_eq!(
+            tray_recording_status(Locale::De, true, 1, Duration::from_secs(3_725)),
+            "Pausiert \u{2014} 1 Schritt \u{00b7} 1:02:05"
+        );
+    }
+
     #[test]
     fn export_text_helpers_render_translated_strings() {
         assert_eq!(export_step_count(Locale::En, 2), "2 steps");
         assert_eq!(export_step_count(Locale::De, 1), "1 Schritt");
         assert_eq!(export_step_heading(Locale::De, 3), "Schritt 3");
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        assert_eq!(
+            export_generated_on(Locale::En, date),
+            "Generated on March 7, 2025"
+        );
+        assert_eq!(
+            export_generated_on(Locale::De, date),
+            "Erstellt am 7. März 2025"
+        );
+        assert_eq!(
+            export_byline(Locale::En, Some("Ana"), Some(date)).as_deref(),
+            Some("Recorded by Ana on March 7, 2025")
+        );
+        assert_eq!(
+            export_byline(Locale::De, None, Some(date)).as_deref(),
+            Some("Aufgenommen am 7. März 2025")
+        );
+        assert_eq!(export_byline(Locale::En, None, None), None);
+        assert_eq!(export_note_label(Locale::De), "Notiz:");
+        assert_eq!(export_wait(Locale::En, 3_000), "Wait ~3s");
+        assert_eq!(export_wait(Locale::En, 1_400), "Wait ~1.5s");
+        assert_eq!(export_wait(Locale::De, 1_500), "Warten ~1,5 s");
+        assert_eq!(export_wait(Locale::De, 100), "Warten ~0,5 s");
+        assert_eq!(export_recorded_with(Locale::De), "Aufgenommen mit StepCast");
         assert_eq!(
             auth_placeholder_description(Locale::De),
             "Authentifiziere dich mit Touch ID oder gib dein Passwort ein, um fortzufahren."
         );
     }
 
+    #[test]
+    fn action_labels_translate_double_click() {
+        assert_eq!(
+            action_label(&ActionType::DoubleClick, Locale::En),
+            "Double-click"
+        );
+        assert_eq!(
+            action_label(&ActionType::DoubleClick, Locale::De),
+            "Doppelklicke"
+        );
+        assert_eq!(action_label(&ActionType::Note, Locale::De), "Notiz");
+        assert_eq!(action_label(&ActionType::Hover, Locale::En), "Point at");
+    }
+
+    #[test]
+    fn step_image_alt_names_the_action() {
+        assert_eq!(
+            export_step_image_alt(Locale::En, 2, &ActionType::DoubleClick),
+            "Step 2: Double-click"
+        );
+        assert_eq!(
+            export_step_image_alt(Locale::De, 2, &ActionType::RightClick),
+            "Schritt 2: Rechtsklicke"
+        );
+        assert_eq!(
+            export_step_image_alt(Locale::En, 1, &ActionType::Overview),
+            "Step 1"
+        );
+    }
+
     #[test]
     fn tray_helpers_render_translated_strings() {
         assert_eq!(tray_menu_open(Locale::En), "Open StepCast");
@@ -325,6 +694,10 @@ mod tests {
             ai_eligibility_requires_apple_silicon(Locale::De),
             "Erfordert Apple Silicon (M1+)."
         );
+        assert_eq!(
+            ai_descriptions_require_macos(Locale::De),
+            "KI-Beschreibungen erfordern macOS."
+        );
         assert_eq!(
             ai_eligibility_not_enabled(Locale::De),
             "Apple Intelligence ist in den Systemeinstellungen deaktiviert."
@@ -1,17 +1,40 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod apple_intelligence;
+pub mod cli;
+mod clipboard;
+mod command_error;
+mod descriptions;
 mod export;
 mod i18n;
 mod panel;
 mod recorder;
+mod session_protocol;
+mod settings;
 mod startup_state;
 mod tray;
+use apple_intelligence::GenerateItem;
+use command_error::CommandError;
+#[cfg(target_os = "macos")]
 use recorder::click_listener::ClickListener;
+use recorder::idle_timeout::IdleTimer;
+use recorder::interruption::{Interruption, InterruptionObserver};
+#[cfg(target_os = "linux")]
+use recorder::linux::ClickListener;
+use recorder::listener_watchdog::{
+    should_restart_listener, RecorderHealth, LISTENER_RESTART_AFTER,
+};
+use recorder::permission_watchdog::{Permission, PermissionWatchdog, PERMISSION_CHECK_INTERVAL};
 use recorder::pipeline;
 use recorder::session::Session;
+use recorder::session_registry::{CompletedSession, SessionRegistry};
+use recorder::space_observer::SpaceObserver;
 use recorder::state::{RecorderState, SessionState};
-use recorder::types::{ActionType, BoundsPercent, DescriptionSource, DescriptionStatus, Step};
-use serde::Serialize;
+use recorder::types::{
+    ActionType, Annotation, BoundsPercent, DescriptionSource, DescriptionStatus, SessionMetadata,
+    Step,
+};
+use recorder::window_info::WindowSnapshot;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
@@ -78,11 +101,18 @@ fn json_escape_one_line(s: &str) -> String {
 struct RecorderAppState {
     recorder_state: Mutex<RecorderState>,
     session: Mutex<Option<Session>>,
+    /// Finished sessions moved out of `session`, kept for the recording history.
+    sessions: Mutex<SessionRegistry>,
     click_listener: Mutex<Option<ClickListener>>,
     pre_click_buffer: Mutex<Option<recorder::pre_click_buffer::PreClickFrameBuffer>>,
     processing_running: Arc<AtomicBool>,
     pipeline_state: Mutex<pipeline::PipelineState>,
-    ai_descriptions_running: Arc<AtomicBool>,
+    /// Description requests waiting for, or being handled by, the single worker.
+    ai_queue: Mutex<descriptions::queue::DescriptionQueue>,
+    /// Set while recording; dropping it unregisters the Space change observer.
+    space_observer: Mutex<Option<SpaceObserver>>,
+    /// Set while recording; dropping it unregisters the lock and sleep observers.
+    interruption_observer: Mutex<Option<InterruptionObserver>>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Default)]
@@ -91,6 +121,13 @@ struct PermissionStatus {
     accessibility: bool,
 }
 
+/// Payload for `ai-generation-progress`: steps processed so far out of the batch.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct AiGenerationProgress {
+    completed: usize,
+    total: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct AppleIntelligenceEligibility {
     eligible: bool,
@@ -126,19 +163,19 @@ fn get_apple_intelligence_eligibility(
     app_language: Option<String>,
 ) -> AppleIntelligenceEligibility {
     let arch = std::env::consts::ARCH;
+    let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
 
     #[cfg(not(target_os = "macos"))]
     {
         return AppleIntelligenceEligibility {
             eligible: false,
-            reason: "Not supported on this platform.".to_string(),
+            reason: i18n::ai_descriptions_require_macos(locale).to_string(),
             details: Some(format!("{} ({arch})", std::env::consts::OS)),
         };
     }
 
     #[cfg(target_os = "macos")]
     {
-        let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
         let version = macos_product_version();
         let platform_details = version
             .as_ref()
@@ -232,11 +269,16 @@ fn get_apple_intelligence_eligibility(
 async fn check_permissions() -> PermissionStatus {
     #[cfg(target_os = "macos")]
     let screen_recording = check_screen_recording();
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    let screen_recording = recorder::linux::can_capture_screen();
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     let screen_recording = false;
     #[cfg(target_os = "macos")]
     let accessibility = ax_is_process_trusted();
-    #[cfg(not(target_os = "macos"))]
+    // X11 has no permission prompt; global clicks only need XInput2.
+    #[cfg(target_os = "linux")]
+    let accessibility = recorder::linux::can_observe_input();
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     let accessibility = false;
     if cfg!(debug_assertions) {
         eprintln!(
@@ -249,6 +291,26 @@ async fn check_permissions() -> PermissionStatus {
     }
 }
 
+/// The first required permission that is currently missing, if any.
+fn missing_permission() -> Option<Permission> {
+    #[cfg(target_os = "macos")]
+    let (screen_recording, accessibility) = (check_screen_recording(), ax_is_process_trusted());
+    #[cfg(target_os = "linux")]
+    let (screen_recording, accessibility) = (
+        recorder::linux::can_capture_screen(),
+        recorder::linux::can_observe_input(),
+    );
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let (screen_recording, accessibility) = (false, false);
+    if !screen_recording {
+        Some(Permission::ScreenRecording)
+    } else if !accessibility {
+        Some(Permission::Accessibility)
+    } else {
+        None
+    }
+}
+
 /// Check screen recording permission.
 ///
 /// We use the well-known window-name heuristic: without screen recording
@@ -495,8 +557,20 @@ async fn request_accessibility(app: tauri::AppHandle) -> PermissionStatus {
     check_permissions().await
 }
 
+/// Emit `diagnostics-updated` after every this many received clicks.
+const DIAGNOSTICS_EVENT_INTERVAL: u32 = 5;
+
 /// Background loop that processes clicks and emits step-captured events.
 fn process_clicks_loop(app: tauri::AppHandle, processing_running: Arc<AtomicBool>) {
+    let mut permission_watchdog = PermissionWatchdog::new(PERMISSION_CHECK_INTERVAL);
+    let idle_timeout = app
+        .state::<RecorderAppState>()
+        .pipeline_state
+        .lock()
+        .ok()
+        .and_then(|ps| ps.idle_timeout);
+    let mut idle_timer = IdleTimer::new(idle_timeout, std::time::Instant::now());
+    let mut last_click = std::time::Instant::now();
     loop {
         // Check if we should stop
         if !processing_running.load(Ordering::SeqCst) {
@@ -515,28 +589,125 @@ fn process_clicks_loop(app: tauri::AppHandle, processing_running: Arc<AtomicBool
         };
 
         if !should_process {
+            // A result frame is only worth taking right after its click.
+            if let Ok(mut ps) = state.pipeline_state.lock() {
+                ps.pending_result_frame = None;
+            }
+            idle_timer.reset(std::time::Instant::now());
+            last_click = std::time::Instant::now();
             std::thread::sleep(std::time::Duration::from_millis(10));
             continue;
         }
 
-        // Get click from listener
-        let click = {
+        if idle_timer.timed_out(std::time::Instant::now()) {
+            handle_idle_timeout(&app, idle_timer.timeout().unwrap_or_default());
+            break;
+        }
+
+        // Captures fail silently once a permission is revoked; pause instead.
+        if let Some(lost) = permission_watchdog.poll(std::time::Instant::now(), missing_permission)
+        {
+            handle_permission_lost(&app, lost);
+            continue;
+        }
+
+        // A switch to another Space annotates whichever step comes next.
+        if take_space_change(&state) {
+            if let Ok(mut ps) = state.pipeline_state.lock() {
+                ps.space_change_pending = true;
+            }
+        }
+
+        if let Some((step, storage_warning)) = take_result_frame(&state) {
+            emit_step_updated(&app, &step, session_revision(&state));
+            if let Some(info) = storage_warning {
+                handle_storage_cap(&app, info);
+            }
+        }
+
+        // A hover requested with the hover shortcut goes first, then clicks from the listener
+        let hover = take_pending_hover(&state);
+        let click = hover.or_else(|| {
             let listener_lock = state.click_listener.lock().ok();
             listener_lock
                 .as_ref()
                 .and_then(|opt| opt.as_ref())
                 .and_then(|listener| listener.recv_timeout(std::time::Duration::from_millis(50)))
-        };
+        });
+
+        // A listener whose event tap the system disabled for good is replaced.
+        if click.as_ref().is_some_and(|c| !c.hover) {
+            last_click = std::time::Instant::now();
+        } else if listener_needs_restart(&state, last_click) {
+            restart_click_listener(&app);
+            last_click = std::time::Instant::now();
+            continue;
+        }
+
+        // Linux has its own simpler pipeline: no AX metadata, auth prompts or drags.
+        #[cfg(target_os = "linux")]
+        if let Some(click) = click {
+            let recorded = {
+                let mut session_lock = state.session.lock().ok();
+                session_lock
+                    .as_mut()
+                    .and_then(|s| s.as_mut())
+                    .and_then(|session| {
+                        let step = recorder::linux::process_click(&click, session).ok()?;
+                        let storage_warning = store_screenshots(session, &step);
+                        Some((step, storage_warning))
+                    })
+            };
+            if let Some((step, storage_warning)) = recorded {
+                idle_timer.reset(std::time::Instant::now());
+                emit_step_captured(&app, &step);
+                if let Some(info) = storage_warning {
+                    handle_storage_cap(&app, info);
+                }
+            }
+            continue;
+        }
+
+        if let Some(click) = click.as_ref().filter(|c| c.drag_start.is_some()) {
+            let updated = {
+                let mut session_lock = state.session.lock().ok();
+                session_lock
+                    .as_mut()
+                    .and_then(|s| s.as_mut())
+                    .and_then(|session| pipeline::process_drag_end(click, session).ok())
+            };
+            if let Some(step) = updated {
+                emit_step_updated(&app, &step, session_revision(&state));
+            }
+            continue;
+        }
 
         if let Some(click) = click {
             let mut recorded_step: Option<Step> = None;
+            let mut context_break: Option<Step> = None;
+            let mut space_note: Option<Step> = None;
             let mut auth_step: Option<Step> = None;
+            let mut merged_step: Option<Step> = None;
+            let mut blocked_app: Option<String> = None;
+            let mut stats: Option<recorder::session::SessionStats> = None;
+            let mut storage_warning: Option<recorder::storage::StorageInfo> = None;
 
             {
                 let mut session_lock = state.session.lock().ok();
                 if let Some(ref mut session) = session_lock.as_mut().and_then(|s| s.as_mut()) {
-                    let (prompt_step, suppress_click) =
-                        pipeline::handle_auth_prompt(&click, session, &state.pipeline_state);
+                    let received_before = session.diagnostics.clicks_received;
+                    // One copy of the window list serves every lookup for this click.
+                    let windows = WindowSnapshot::capture().unwrap_or_default();
+                    let (prompt_step, suppress_click) = if click.is_synthetic() {
+                        (None, false)
+                    } else {
+                        pipeline::handle_auth_prompt(
+                            &click,
+                            session,
+                            &state.pipeline_state,
+                            &windows,
+                        )
+                    };
                     auth_step = prompt_step;
 
                     if !suppress_click {
@@ -545,28 +716,164 @@ fn process_clicks_loop(app: tauri::AppHandle, processing_running: Arc<AtomicBool
                             .lock()
                             .ok()
                             .and_then(|g| g.as_ref().cloned());
-                        if let Ok(step) = pipeline::process_click(
+                        match pipeline::process_pointer_event(
                             &click,
                             session,
                             &state.pipeline_state,
                             pre_click_buffer.as_ref(),
+                            &windows,
                         ) {
-                            recorded_step = Some(step);
+                            Ok(step) if captured_after_interruption(&state) => {
+                                eprintln!(
+                                    "Dropping {}: captured right after a lock or sleep",
+                                    step.id
+                                );
+                                session.discard_steps(|s| s.id == step.id);
+                                if let Some(path) = &step.screenshot_path {
+                                    let _ = std::fs::remove_file(path);
+                                }
+                            }
+                            Ok(mut step) => {
+                                let (split, locale, blur_text, space_change) = state
+                                    .pipeline_state
+                                    .lock()
+                                    .map(|mut ps| {
+                                        let pending = std::mem::take(&mut ps.space_change_pending);
+                                        (
+                                            ps.split_on_context_change,
+                                            ps.locale,
+                                            ps.privacy_blur_text,
+                                            pending.then_some(ps.space_change_notes),
+                                        )
+                                    })
+                                    .unwrap_or((false, i18n::Locale::En, false, None));
+                                if blur_text {
+                                    pipeline::apply_privacy_blur(session, &step);
+                                }
+                                storage_warning = store_screenshots(session, &step);
+                                if split {
+                                    context_break = pipeline::insert_context_break(session, locale);
+                                }
+                                if let Some(as_note) = space_change {
+                                    space_note = pipeline::mark_space_change(
+                                        session, &mut step, locale, as_note,
+                                    );
+                                }
+                                if let Ok(mut ps) = state.pipeline_state.lock() {
+                                    pipeline::schedule_result_frame(
+                                        &mut ps,
+                                        &step,
+                                        click.timestamp_ms,
+                                    );
+                                }
+                                recorded_step = Some(step);
+                            }
+                            Err(pipeline::PipelineError::MergedIntoContextMenu(step_id)) => {
+                                merged_step =
+                                    session.steps.iter().find(|s| s.id == step_id).cloned();
+                            }
+                            Err(pipeline::PipelineError::BlockedApp(app_name)) => {
+                                blocked_app = Some(app_name);
+                            }
+                            Err(_) => {}
                         }
                     }
+
+                    // Suppressed auth clicks don't count, so only emit when this click did.
+                    let received = session.diagnostics.clicks_received;
+                    if received != received_before && received % DIAGNOSTICS_EVENT_INTERVAL == 0 {
+                        stats = Some(session.stats());
+                    }
                 }
             }
 
+            if let Some(step) = context_break {
+                emit_step_captured(&app, &step);
+            }
+            if let Some(step) = space_note {
+                emit_step_captured(&app, &step);
+            }
             if let Some(step) = recorded_step {
-                let _ = app.emit("step-captured", &step);
+                idle_timer.reset(std::time::Instant::now());
+                if step.capture_status == Some(recorder::types::CaptureStatus::Failed) {
+                    permission_watchdog.check_soon();
+                }
+                emit_step_captured(&app, &step);
+            }
+            if let Some(step) = merged_step {
+                emit_step_updated(&app, &step, session_revision(&state));
             }
             if let Some(step) = auth_step {
-                let _ = app.emit("step-captured", &step);
+                emit_step_captured(&app, &step);
+            }
+            if let Some(stats) = stats {
+                let _ = app.emit("diagnostics-updated", &stats);
+            }
+            if let Some(info) = storage_warning {
+                handle_storage_cap(&app, info);
+            }
+            if let Some(app_name) = blocked_app {
+                handle_blocked_app(&app, app_name);
             }
         }
     }
 }
 
+/// Unregister the Space change, lock and sleep observers of the recording that is
+/// ending.
+fn drop_session_observers(state: &RecorderAppState) -> Result<(), CommandError> {
+    state
+        .space_observer
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("space observer"))?
+        .take();
+    state
+        .interruption_observer
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("interruption observer"))?
+        .take();
+    Ok(())
+}
+
+/// True when the active Space changed since the last check.
+fn take_space_change(state: &RecorderAppState) -> bool {
+    state
+        .space_observer
+        .lock()
+        .ok()
+        .is_some_and(|observer| observer.as_ref().is_some_and(SpaceObserver::take_changed))
+}
+
+/// Apply the session's storage policy to a new step's screenshots. Returns the
+/// storage info when this step took the session over its size cap.
+fn store_screenshots(session: &mut Session, step: &Step) -> Option<recorder::storage::StorageInfo> {
+    session
+        .store_step_screenshots(step)
+        .then(|| session.storage_info())
+}
+
+/// Capture the pending result frame once it is due. Returns the updated step, plus
+/// the storage info if the frame took the session over its size cap.
+fn take_result_frame(
+    state: &RecorderAppState,
+) -> Option<(Step, Option<recorder::storage::StorageInfo>)> {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    let pending = {
+        let mut ps = state.pipeline_state.lock().ok()?;
+        pipeline::take_due_result_frame(&mut ps, now_ms)?
+    };
+    let mut session_lock = state.session.lock().ok()?;
+    let session = session_lock.as_mut()?;
+    let step = pipeline::capture_result_frame(session, &pending.step_id)
+        .inspect_err(|e| eprintln!("Result frame for {} failed: {e}", pending.step_id))
+        .ok()?;
+    let storage_warning = store_screenshots(session, &step);
+    Some((step, storage_warning))
+}
+
 /// Perform a tiny screen capture to trigger the macOS 26 runtime confirmation
 /// dialog ("StepCast möchte … direkt auf deinen Bildschirm und Ton zugreifen").
 /// On Tahoe, the System Settings entry alone is not enough — the first real
@@ -626,66 +933,173 @@ fn probe_screen_capture() {
     ));
 }
 
+/// Per-recording choices from the panel. Unset ones fall back to the saved settings or
+/// their defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RecordingOptions {
+    split_on_context_change: Option<bool>,
+    privacy_blur_text: Option<bool>,
+    app_language: Option<String>,
+    overview_step: Option<bool>,
+    overview_all_displays: Option<bool>,
+    result_frame: Option<bool>,
+    result_frame_delay_ms: Option<i64>,
+    max_screenshot_edge_px: Option<u32>,
+    storage_cap_mb: Option<u64>,
+    include_cursor: Option<bool>,
+    idle_timeout_min: Option<u32>,
+    space_change_notes: Option<bool>,
+    ignore_desktop_clicks: Option<bool>,
+}
+
 #[tauri::command]
 async fn start_recording(
     app: tauri::AppHandle,
     state: tauri::State<'_, RecorderAppState>,
-) -> Result<(), String> {
+    options: Option<RecordingOptions>,
+) -> Result<(), CommandError> {
+    let RecordingOptions {
+        split_on_context_change,
+        privacy_blur_text,
+        app_language,
+        overview_step,
+        overview_all_displays,
+        result_frame,
+        result_frame_delay_ms,
+        max_screenshot_edge_px,
+        storage_cap_mb,
+        include_cursor,
+        idle_timeout_min,
+        space_change_notes,
+        ignore_desktop_clicks,
+    } = options.unwrap_or_default();
     let permissions = check_permissions().await;
     if !permissions.screen_recording || !permissions.accessibility {
-        return Err("missing screen recording or accessibility permission".to_string());
+        return Err(CommandError::MissingPermission);
     }
 
     // Trigger the macOS 26 runtime capture confirmation (one-time dialog).
     #[cfg(target_os = "macos")]
     probe_screen_capture();
 
+    let settings = settings::load();
+
     // Reset pipeline state for the new session
     {
         let mut ps = state
             .pipeline_state
             .lock()
-            .map_err(|_| "pipeline state lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("pipeline state"))?;
         ps.reset();
+        ps.debounce_ms = settings.debounce_ms.max(0);
+        ps.auto_crop = settings.auto_crop;
+        ps.blocked_apps = settings.blocked_apps.clone();
+        ps.pause_on_blocked_app = settings.pause_on_blocked_app;
+        ps.prefer_full_window_capture = settings.prefer_full_window_capture;
+        ps.own_shortcuts = own_shortcut_labels(&settings);
+        ps.set_auth_dialog_cooldown_ms(settings.auth_dialog_cooldown_ms);
+        ps.set_auth_prompt_dedup_ms(settings.auth_prompt_dedup_ms);
+        ps.split_on_context_change =
+            split_on_context_change.unwrap_or(settings.split_on_context_change);
+        ps.privacy_blur_text = privacy_blur_text.unwrap_or(settings.privacy_blur_text);
+        ps.locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
+        ps.result_frame_delay_ms = result_frame.unwrap_or(false).then(|| {
+            result_frame_delay_ms
+                .unwrap_or(pipeline::DEFAULT_RESULT_FRAME_DELAY_MS)
+                .max(0)
+        });
+        ps.idle_timeout = recorder::idle_timeout::idle_timeout_from_minutes(Some(
+            idle_timeout_min.unwrap_or(settings.idle_timeout_min),
+        ));
+        ps.space_change_notes = space_change_notes.unwrap_or(false);
+        ps.ignore_desktop_clicks = ignore_desktop_clicks.unwrap_or(true);
     }
 
-    // Clean up previous session if any
-    {
-        let session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-        if let Some(old_session) = session_lock.as_ref() {
-            // In dev, keep old session dirs so we can audit screenshots/logs/AI output.
-            if !cfg!(debug_assertions) {
-                old_session.cleanup();
-            }
-        }
-    }
+    // Move the previous session into the history
+    archive_active_session(&state)?;
 
     // Create new session
-    let session = Session::new().map_err(|e| format!("Failed to create session: {e}"))?;
+    let mut session = Session::new().map_err(|e| format!("Failed to create session: {e}"))?;
+    let default_policy = recorder::storage::StoragePolicy::default();
+    session.storage_policy = recorder::storage::StoragePolicy {
+        max_screenshot_edge_px: Some(
+            max_screenshot_edge_px.unwrap_or(settings.max_screenshot_edge_px),
+        )
+        .filter(|&px| px > 0)
+        .unwrap_or(default_policy.max_screenshot_edge_px),
+        max_session_bytes: Some(storage_cap_mb.unwrap_or(settings.storage_cap_mb))
+            .filter(|&mb| mb > 0)
+            .map_or(default_policy.max_session_bytes, |mb| mb * 1024 * 1024),
+    };
+    session.capture_options.include_cursor = include_cursor.unwrap_or(settings.include_cursor);
+    session.capture_exclusions = settings.capture_exclusions.clone();
 
     // Start click listener
     let click_listener =
         ClickListener::start().map_err(|e| format!("Failed to start click listener: {e}"))?;
 
+    // Optional full-display (or full-desktop) overview as the first step. The panel
+    // must be gone first, or it ends up in the screenshot.
+    let overview = if overview_step.unwrap_or(false) {
+        hide_panel_blocking(&app);
+        let started_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        pipeline::capture_overview_step(
+            &mut session,
+            started_ms,
+            overview_all_displays.unwrap_or(false),
+        )
+        .inspect_err(|e| eprintln!("Overview capture failed: {e}"))
+        .ok()
+    } else {
+        None
+    };
+    let storage_warning = overview
+        .as_ref()
+        .and_then(|step| store_screenshots(&mut session, step));
+
     // Store session and click listener in state
     {
-        let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
+        let mut session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
         *session_lock = Some(session);
     }
     {
         let mut listener_lock = state
             .click_listener
             .lock()
-            .map_err(|_| "click listener lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("click listener"))?;
         *listener_lock = Some(click_listener);
     }
     {
         let mut pre_click_lock = state
             .pre_click_buffer
             .lock()
-            .map_err(|_| "pre-click buffer lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("pre-click buffer"))?;
         *pre_click_lock = recorder::pre_click_buffer::PreClickFrameBuffer::start().ok();
     }
+    {
+        let mut observer_lock = state
+            .space_observer
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("space observer"))?;
+        *observer_lock = Some(SpaceObserver::start());
+    }
+    {
+        let mut observer_lock = state
+            .interruption_observer
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("interruption observer"))?;
+        let handle = app.clone();
+        *observer_lock = Some(InterruptionObserver::start(move |kind, started| {
+            handle_interruption(&handle, kind, started)
+        }));
+    }
 
     // Set processing flag to running
     state.processing_running.store(true, Ordering::SeqCst);
@@ -698,15 +1112,21 @@ async fn start_recording(
     });
 
     // Update recorder state
-    let mut recorder_state = state
+    state
         .recorder_state
         .lock()
-        .map_err(|_| "recorder state lock poisoned".to_string())?;
-    recorder_state
+        .map_err(|_| CommandError::LockPoisoned("recorder state"))?
         .start()
-        .map_err(|error| format!("{error:?}"))?;
+        .map_err(CommandError::from)?;
 
-    // Hide panel and set recording icon on main thread (required for macOS UI operations)
+    if let Some(step) = overview {
+        emit_step_captured(&app, &step);
+    }
+    if let Some(info) = storage_warning {
+        handle_storage_cap(&app, info);
+    }
+
+    // Hide panel on main thread (required for macOS UI operations)
     let app_clone = app.clone();
     let _ = app.run_on_main_thread(move || {
         if cfg!(debug_assertions) {
@@ -722,49 +1142,364 @@ async fn start_recording(
             recorder::pipeline::set_panel_visible(&ps_state.pipeline_state, false);
         }
 
-        // Set recording icon
-        if let Err(e) = tray::set_recording_icon(&app_clone) {
-            eprintln!("Failed to set recording icon: {e}");
-        }
-
         if cfg!(debug_assertions) {
             eprintln!("Recording UI updated successfully");
         }
     });
+    tray::refresh_recording_status(&app);
 
     Ok(())
 }
 
-#[tauri::command]
-fn pause_recording(state: tauri::State<'_, RecorderAppState>) -> Result<(), String> {
-    let mut recorder_state = state
+/// Hide the panel and wait for the window server to remove it, so a capture taken
+/// right after doesn't include it.
+fn hide_panel_blocking(app: &tauri::AppHandle) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Some(window) = handle.get_webview_window(panel::panel_label()) {
+            let _ = window.hide();
+        }
+        let _ = tx.send(());
+    });
+    let _ = rx.recv();
+    std::thread::sleep(std::time::Duration::from_millis(150));
+}
+
+/// Tell the UI the recorder state changed and update the tray to match.
+fn emit_recorder_state(app: &tauri::AppHandle, status: &str) {
+    let _ = app.emit("recorder-state-changed", status);
+    tray::refresh_recording_status(app);
+}
+
+/// Send a new step to the UI and update the tray's step count and preview.
+fn emit_step_captured(app: &tauri::AppHandle, step: &Step) {
+    let _ = app.emit("step-captured", step);
+    tray::refresh_recording_status(app);
+}
+
+/// Pause the recording. Shared by the `pause_recording` command and the tray menu.
+fn pause_session(app: &tauri::AppHandle) -> Result<(), CommandError> {
+    app.state::<RecorderAppState>()
         .recorder_state
         .lock()
-        .map_err(|_| "recorder state lock poisoned".to_string())?;
-    recorder_state.pause().map_err(|error| format!("{error:?}"))
+        .map_err(|_| CommandError::LockPoisoned("recorder state"))?
+        .pause()
+        .map_err(CommandError::from)?;
+    emit_recorder_state(app, "paused");
+    Ok(())
 }
 
 #[tauri::command]
-async fn resume_recording(state: tauri::State<'_, RecorderAppState>) -> Result<(), String> {
+fn pause_recording(app: tauri::AppHandle) -> Result<(), CommandError> {
+    pause_session(&app)
+}
+
+/// Pause when the screen locks or the Mac goes to sleep, and drop the clicks queued
+/// meanwhile and the pre-click frames so nothing from the lock screen ends up in the
+/// guide. Called on the main queue. Once the user is back, the panel opens and
+/// `recording-interrupted` lets it offer to resume.
+fn handle_interruption(app: &tauri::AppHandle, kind: Interruption, started: bool) {
+    let state = app.state::<RecorderAppState>();
+    let changed = if started {
+        if let Ok(mut ps) = state.pipeline_state.lock() {
+            ps.interrupted_at_ms = Some(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64,
+            );
+        }
+        state
+            .recorder_state
+            .lock()
+            .is_ok_and(|mut recorder| recorder.on_interrupted())
+    } else {
+        state
+            .recorder_state
+            .lock()
+            .is_ok_and(|mut recorder| recorder.on_interruption_ended())
+    };
+
+    // On wake the listener may deliver a burst of stale events.
+    let dropped = state
+        .click_listener
+        .lock()
+        .ok()
+        .and_then(|listener| listener.as_ref().map(ClickListener::clear_queue))
+        .unwrap_or(0);
+    if dropped > 0 {
+        eprintln!("Dropped {dropped} queued click events ({kind:?})");
+    }
+
+    if started {
+        if let Some(buffer) = state
+            .pre_click_buffer
+            .lock()
+            .ok()
+            .and_then(|mut buffer| buffer.take())
+        {
+            buffer.stop();
+        }
+        if changed {
+            emit_recorder_state(app, "paused");
+        }
+    } else if changed {
+        // The panel stays hidden while recording; bring it up to ask.
+        tray::show_panel(app);
+        let _ = app.emit(
+            "recording-interrupted",
+            RecordingInterrupted { reason: kind },
+        );
+    }
+}
+
+/// Payload of the `recording-interrupted` event.
+#[derive(Serialize)]
+struct RecordingInterrupted {
+    reason: Interruption,
+}
+
+/// True when a step whose capture finishes now may show the lock screen.
+fn captured_after_interruption(state: &RecorderAppState) -> bool {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    state.pipeline_state.lock().is_ok_and(|ps| {
+        recorder::interruption::is_lock_screen_capture(now_ms, ps.interrupted_at_ms)
+    })
+}
+
+/// Pause once the session's screenshots reach the size cap, before the disk fills up
+/// and captures start failing, and tell the UI how much space the session takes.
+fn handle_storage_cap(app: &tauri::AppHandle, info: recorder::storage::StorageInfo) {
+    let state = app.state::<RecorderAppState>();
+    let paused = match state.recorder_state.lock() {
+        Ok(mut recorder) => recorder.pause().is_ok(),
+        Err(_) => return,
+    };
+    eprintln!(
+        "Session storage cap reached: {} bytes in {} files",
+        info.total_bytes, info.file_count
+    );
+    if paused {
+        emit_recorder_state(app, "paused");
+    }
+    let _ = app.emit("session-storage-warning", &info);
+}
+
+/// Pause when a required permission was revoked mid-recording and tell the UI which.
+fn handle_permission_lost(app: &tauri::AppHandle, permission: Permission) {
+    let state = app.state::<RecorderAppState>();
+    let changed = match state.recorder_state.lock() {
+        Ok(mut recorder) => recorder.on_permission_lost(),
+        Err(_) => return,
+    };
+    if changed {
+        eprintln!("Permission lost while recording: {permission:?}");
+        emit_recorder_state(app, "paused");
+        let _ = app.emit("permission-lost", permission);
+    }
+}
+
+/// True when the click listener's event tap stayed disabled, with no clicks, for
+/// longer than [`LISTENER_RESTART_AFTER`].
+fn listener_needs_restart(state: &RecorderAppState, last_click: std::time::Instant) -> bool {
+    let disabled_since = state.click_listener.lock().ok().and_then(|listener| {
+        listener
+            .as_ref()
+            .and_then(ClickListener::tap_disabled_since)
+    });
+    should_restart_listener(
+        disabled_since,
+        last_click,
+        std::time::Instant::now(),
+        LISTENER_RESTART_AFTER,
+    )
+}
+
+/// Replace the click listener and tell the UI via `recorder-health`. The old one is
+/// only dropped once the new one runs, so a failed start is retried later.
+fn restart_click_listener(app: &tauri::AppHandle) {
+    let state = app.state::<RecorderAppState>();
+    let health = match ClickListener::start() {
+        Ok(listener) => {
+            if let Ok(mut listener_lock) = state.click_listener.lock() {
+                if let Some(old) = listener_lock.replace(listener) {
+                    old.stop();
+                }
+            }
+            let listener_restarts = state
+                .session
+                .lock()
+                .ok()
+                .and_then(|mut session| {
+                    let diagnostics = &mut session.as_mut()?.diagnostics;
+                    diagnostics.listener_restarts += 1;
+                    Some(diagnostics.listener_restarts)
+                })
+                .unwrap_or(0);
+            eprintln!("Click listener stopped receiving events; recreated it");
+            RecorderHealth::Recovered { listener_restarts }
+        }
+        Err(error) => {
+            eprintln!("Failed to recreate the click listener: {error}");
+            RecorderHealth::ListenerFailed { error }
+        }
+    };
+    let _ = app.emit("recorder-health", health);
+}
+
+/// Stop a recording nobody has clicked in for `idle`, as if Stop was pressed.
+fn handle_idle_timeout(app: &tauri::AppHandle, idle: std::time::Duration) {
+    eprintln!("No clicks for {}s, stopping the recording", idle.as_secs());
+    match stop_session(app, None) {
+        Ok(steps) => {
+            let _ = app.emit(
+                "recording-auto-stopped",
+                RecordingAutoStopped {
+                    idle_ms: idle.as_millis() as u64,
+                    steps,
+                },
+            );
+        }
+        Err(e) => eprintln!("Idle auto-stop failed: {e}"),
+    }
+}
+
+/// StepCast's own global shortcuts as recorded shortcut steps would label them.
+fn own_shortcut_labels(settings: &settings::RecorderSettings) -> Vec<String> {
+    let panel = panel_shortcut();
+    [
+        recorder::key_combo::shortcut_label(panel.mods, panel.key),
+        recorder::key_combo::accelerator_label(&settings.hover_shortcut),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Cmd+Shift+S, which toggles the panel.
+fn panel_shortcut() -> tauri_plugin_global_shortcut::Shortcut {
+    use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+    Shortcut::new(Some(Modifiers::META | Modifiers::SHIFT), Code::KeyS)
+}
+
+/// Take the hover queued by the hover shortcut, if any.
+fn take_pending_hover(state: &RecorderAppState) -> Option<recorder::click_event::ClickEvent> {
+    state.pipeline_state.lock().ok()?.pending_hover.take()
+}
+
+/// Hover shortcut pressed: while recording, queue a hover step at the pointer for the
+/// click loop. The pre-click buffer still holds the tooltip visible right now.
+fn request_hover_capture(app: &tauri::AppHandle) {
+    let state = app.state::<RecorderAppState>();
+    let recording = state
+        .recorder_state
+        .lock()
+        .is_ok_and(|r| r.current_state() == SessionState::Recording);
+    if !recording {
+        return;
+    }
+    let Some((x, y)) = recorder::cg_capture::cursor_location() else {
+        return;
+    };
+    if let Ok(mut ps) = state.pipeline_state.lock() {
+        ps.pending_hover = Some(recorder::click_event::ClickEvent::hover(
+            x.round() as i32,
+            y.round() as i32,
+        ));
+    }
+}
+
+/// A click in a blocklisted app was dropped; pause as well when the settings say so.
+fn handle_blocked_app(app: &tauri::AppHandle, app_name: String) {
+    let state = app.state::<RecorderAppState>();
+    let pause = state
+        .pipeline_state
+        .lock()
+        .is_ok_and(|ps| ps.pause_on_blocked_app);
+    let paused = pause
+        && state
+            .recorder_state
+            .lock()
+            .is_ok_and(|mut recorder| recorder.pause().is_ok());
+    if paused {
+        emit_recorder_state(app, "paused");
+    }
+    let _ = app.emit(
+        "recording-blocked-app",
+        RecordingBlockedApp {
+            app: app_name,
+            paused,
+        },
+    );
+}
+
+/// Payload of the `recording-blocked-app` event.
+#[derive(Serialize)]
+struct RecordingBlockedApp {
+    app: String,
+    paused: bool,
+}
+
+/// Payload of the `recording-auto-stopped` event.
+#[derive(Serialize)]
+struct RecordingAutoStopped {
+    idle_ms: u64,
+    steps: Vec<Step>,
+}
+
+/// Resume a paused recording once both permissions are granted again. Shared by the
+/// `resume_recording` command and the tray menu.
+async fn resume_session(app: &tauri::AppHandle) -> Result<(), CommandError> {
     let permissions = check_permissions().await;
     if !permissions.screen_recording || !permissions.accessibility {
-        return Err("missing screen recording or accessibility permission".to_string());
+        return Err(CommandError::MissingPermission);
     }
 
-    let mut recorder_state = state
+    let state = app.state::<RecorderAppState>();
+    state
         .recorder_state
         .lock()
-        .map_err(|_| "recorder state lock poisoned".to_string())?;
-    recorder_state
+        .map_err(|_| CommandError::LockPoisoned("recorder state"))?
         .resume()
-        .map_err(|error| format!("{error:?}"))
+        .map_err(CommandError::from)?;
+    // A lock or sleep stopped the pre-click buffer; restart it.
+    {
+        let mut pre_click_lock = state
+            .pre_click_buffer
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("pre-click buffer"))?;
+        if pre_click_lock.is_none() {
+            *pre_click_lock = recorder::pre_click_buffer::PreClickFrameBuffer::start().ok();
+        }
+    }
+    emit_recorder_state(app, "recording");
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_recording(app: tauri::AppHandle) -> Result<(), CommandError> {
+    resume_session(&app).await
 }
 
 #[tauri::command]
 fn stop_recording(
-    _app: tauri::AppHandle,
-    state: tauri::State<'_, RecorderAppState>,
-) -> Result<Vec<Step>, String> {
+    app: tauri::AppHandle,
+    deduplicate: Option<bool>,
+) -> Result<Vec<Step>, CommandError> {
+    stop_session(&app, deduplicate)
+}
+
+/// Stop the recording and bring the panel back. Shared by the `stop_recording`
+/// command, the tray menu and the idle auto-stop.
+fn stop_session(
+    app: &tauri::AppHandle,
+    deduplicate: Option<bool>,
+) -> Result<Vec<Step>, CommandError> {
+    let state = app.state::<RecorderAppState>();
     // Stop the processing loop
     state.processing_running.store(false, Ordering::SeqCst);
 
@@ -773,7 +1508,7 @@ fn stop_recording(
         let mut listener_lock = state
             .click_listener
             .lock()
-            .map_err(|_| "click listener lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("click listener"))?;
         if let Some(listener) = listener_lock.take() {
             listener.stop();
         }
@@ -782,7 +1517,7 @@ fn stop_recording(
         let mut pre_click_lock = state
             .pre_click_buffer
             .lock()
-            .map_err(|_| "pre-click buffer lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("pre-click buffer"))?;
         if let Some(buffer) = pre_click_lock.take() {
             buffer.stop();
         }
@@ -791,17 +1526,25 @@ fn stop_recording(
         let mut pre_click_lock = state
             .pre_click_buffer
             .lock()
-            .map_err(|_| "pre-click buffer lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("pre-click buffer"))?;
         if let Some(buffer) = pre_click_lock.take() {
             buffer.stop();
         }
     }
 
+    drop_session_observers(&state)?;
+
     // Write diagnostics and get steps from session
     let steps = {
-        let session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-        if let Some(s) = session_lock.as_ref() {
+        let mut session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
+        if let Some(s) = session_lock.as_mut() {
             s.write_diagnostics();
+            if deduplicate.unwrap_or(false) {
+                emit_deduplicated(app, &recorder::dedup::deduplicate(s));
+            }
         }
         session_lock
             .as_ref()
@@ -810,17 +1553,16 @@ fn stop_recording(
     };
 
     // Update recorder state
-    let mut recorder_state = state
+    state
         .recorder_state
         .lock()
-        .map_err(|_| "recorder state lock poisoned".to_string())?;
-    recorder_state
+        .map_err(|_| CommandError::LockPoisoned("recorder state"))?
         .stop()
-        .map_err(|error| format!("{error:?}"))?;
+        .map_err(CommandError::from)?;
 
-    // Show panel and reset icon on main thread
-    let app_clone = _app.clone();
-    let _ = _app.run_on_main_thread(move || {
+    // Show panel on main thread
+    let app_clone = app.clone();
+    let _ = app.run_on_main_thread(move || {
         if cfg!(debug_assertions) {
             eprintln!("Showing window after recording stopped (main thread)...");
         }
@@ -830,30 +1572,22 @@ fn stop_recording(
             if let Err(err) = tray::position_panel_at_current_tray_icon(&app_clone) {
                 eprintln!("Failed to position panel: {err}");
             }
+            tray::record_panel_bounds_when_settled(&app_clone);
             let ps_state = app_clone.state::<RecorderAppState>();
-            if let Ok(bounds) = panel::panel_bounds(&app_clone) {
-                if cfg!(debug_assertions) {
-                    eprintln!(
-                        "Panel bounds after auto-show: x={} y={} w={} h={}",
-                        bounds.x, bounds.y, bounds.width, bounds.height
-                    );
-                }
-                recorder::pipeline::record_panel_bounds(&ps_state.pipeline_state, bounds);
-            }
             recorder::pipeline::set_panel_visible(&ps_state.pipeline_state, true);
         }
-
-        if let Err(e) = tray::set_default_icon(&app_clone) {
-            eprintln!("Failed to reset tray icon: {e}");
-        }
     });
+    emit_recorder_state(app, "stopped");
 
     Ok(steps)
 }
 
 #[tauri::command]
-fn get_steps(state: tauri::State<'_, RecorderAppState>) -> Result<Vec<Step>, String> {
-    let session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
+fn get_steps(state: tauri::State<'_, RecorderAppState>) -> Result<Vec<Step>, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
     let steps = session_lock
         .as_ref()
         .map(|s| s.get_steps().to_vec())
@@ -862,30 +1596,322 @@ fn get_steps(state: tauri::State<'_, RecorderAppState>) -> Result<Vec<Step>, Str
 }
 
 #[tauri::command]
-fn discard_recording(
-    app: tauri::AppHandle,
+fn get_session_metadata(
     state: tauri::State<'_, RecorderAppState>,
-) -> Result<(), String> {
-    // Stop the processing loop first
-    state.processing_running.store(false, Ordering::SeqCst);
-
-    // Small delay to let processing loop exit
-    std::thread::sleep(std::time::Duration::from_millis(50));
+) -> Result<SessionMetadata, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_ref().ok_or(CommandError::NoSession)?;
+    Ok(session.metadata.clone())
+}
 
-    // Stop and remove click listener
-    {
-        let mut listener_lock = state
-            .click_listener
-            .lock()
-            .map_err(|_| "click listener lock poisoned")?;
-        if let Some(listener) = listener_lock.take() {
-            listener.stop();
-        }
-    }
+/// Set the guide's title, author and tags; exports use them as defaults.
+#[tauri::command]
+fn set_session_metadata(
+    state: tauri::State<'_, RecorderAppState>,
+    title: String,
+    author: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<SessionMetadata, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let metadata = session
+        .set_metadata(&title, author.as_deref(), &tags.unwrap_or_default())
+        .map_err(|e| CommandError::Failed(e.to_string()))?;
+    Ok(metadata.clone())
+}
+
+/// Limit recording to clicks in `app_name`; `None` or a blank name records every app.
+#[tauri::command]
+fn set_recording_scope(
+    state: tauri::State<'_, RecorderAppState>,
+    app_name: Option<String>,
+) -> Result<(), CommandError> {
+    let mut ps = state
+        .pipeline_state
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("pipeline state"))?;
+    ps.record_only_app = app_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    Ok(())
+}
+
+/// Tune auth dialog handling: the phantom-click cooldown after an auth dialog click
+/// and the dedup window for repeated prompts. `None` leaves a value unchanged; the
+/// values are also saved as recorder settings.
+#[tauri::command]
+fn set_auth_dialog_timing(
+    state: tauri::State<'_, RecorderAppState>,
+    cooldown_ms: Option<i64>,
+    prompt_dedup_ms: Option<i64>,
+) -> Result<(), CommandError> {
+    let mut ps = state
+        .pipeline_state
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("pipeline state"))?;
+    let mut settings = settings::load();
+    if let Some(ms) = cooldown_ms {
+        ps.set_auth_dialog_cooldown_ms(ms);
+        settings.auth_dialog_cooldown_ms = ms.max(0);
+    }
+    if let Some(ms) = prompt_dedup_ms {
+        ps.set_auth_prompt_dedup_ms(ms);
+        settings.auth_prompt_dedup_ms = ms.max(0);
+    }
+    settings::save(&settings)?;
+    Ok(())
+}
+
+/// Capture counters for the current session, available during and after recording.
+#[tauri::command]
+fn get_recording_diagnostics(
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<recorder::session::DiagnosticsSnapshot, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    Ok(session_lock
+        .as_ref()
+        .map(|s| s.diagnostics.snapshot())
+        .unwrap_or_default())
+}
+
+/// Zip the session cache folder (screenshots, `recording.log`, diagnostics, AI traces)
+/// for attaching to a bug report. `redact_screenshots` swaps images for blank ones.
+/// Returns the number of files in the archive.
+#[tauri::command]
+async fn export_session_bundle(
+    state: tauri::State<'_, RecorderAppState>,
+    output_path: String,
+    redact_screenshots: Option<bool>,
+) -> Result<usize, CommandError> {
+    let session_dir = {
+        let session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
+        let session = session_lock.as_ref().ok_or(CommandError::NoSession)?;
+        // Counters change while recording; bundle the current ones.
+        session.write_diagnostics();
+        session.temp_dir.clone()
+    };
+    let redact = redact_screenshots.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        recorder::bundle::write_session_bundle(
+            &session_dir,
+            std::path::Path::new(&output_path),
+            redact,
+        )
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("Bundle task failed: {e}")))?
+    .map_err(|e| CommandError::Failed(format!("Failed to write session bundle: {e}")))
+}
+
+/// Check the current session's screenshots and mark steps whose file is gone as failed
+/// captures. Returns the IDs of those steps.
+#[tauri::command]
+fn validate_session(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<Vec<String>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let missing = session.validate_screenshots();
+    for step in session.steps.iter().filter(|s| missing.contains(&s.id)) {
+        emit_step_updated(&app, step, session.revision());
+    }
+    Ok(missing)
+}
+
+/// Session directory, file count and size, for showing how much space a recording takes.
+#[tauri::command]
+fn get_session_storage_info(
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<recorder::storage::StorageInfo, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    Ok(session_lock
+        .as_ref()
+        .map(|s| s.storage_info())
+        .unwrap_or_default())
+}
+
+/// Bytes taken by the current session's screenshots; 0 without a session.
+#[tauri::command]
+fn get_session_disk_usage(state: tauri::State<'_, RecorderAppState>) -> Result<u64, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    Ok(session_lock.as_ref().map_or(0, |s| s.disk_usage()))
+}
+
+/// Capture counters plus per-app / per-action step statistics for the current session.
+#[tauri::command]
+fn get_session_diagnostics(
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<recorder::session::SessionStats, CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    Ok(session_lock.as_ref().map(|s| s.stats()).unwrap_or_default())
+}
+
+/// Move the session in the editor into the registry. Sessions without steps have
+/// nothing to come back to and are removed instead.
+fn archive_active_session(state: &RecorderAppState) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let Some(old_session) = session_lock.take() else {
+        return Ok(());
+    };
+    if old_session.steps.is_empty() {
+        // In dev, keep old session dirs so we can audit screenshots/logs/AI output.
+        if !cfg!(debug_assertions) {
+            old_session.cleanup();
+        }
+        return Ok(());
+    }
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("sessions"))?;
+    if let Err(e) = sessions.register(&old_session) {
+        // Keep it in the editor rather than lose it.
+        *session_lock = Some(old_session);
+        return Err(CommandError::Failed(format!("Failed to save session: {e}")));
+    }
+    Ok(())
+}
+
+/// Finished sessions, newest first. The one in the editor is not included.
+#[tauri::command]
+fn list_sessions(
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<Vec<CompletedSession>, CommandError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("sessions"))?;
+    Ok(sessions.list().to_vec())
+}
+
+/// Every session directory left on disk, newest first, with its step count and size.
+/// Unlike [`list_sessions`] this includes the session in the editor and unregistered ones.
+#[tauri::command]
+fn list_session_dirs() -> Result<Vec<recorder::session::SessionSummary>, CommandError> {
+    Ok(Session::list_sessions())
+}
+
+/// Open a finished session in the editor. The session there moves into the history.
+#[tauri::command]
+fn load_session(
+    state: tauri::State<'_, RecorderAppState>,
+    id: String,
+) -> Result<Vec<Step>, CommandError> {
+    {
+        let recorder_state = state
+            .recorder_state
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("recorder state"))?;
+        if matches!(
+            recorder_state.current_state(),
+            SessionState::Recording | SessionState::Paused
+        ) {
+            return Err(CommandError::Failed(
+                "Stop the recording before opening another session".into(),
+            ));
+        }
+    }
+    let session = {
+        let mut sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("sessions"))?;
+        sessions
+            .take_session(&id)
+            .ok_or(CommandError::SessionNotFound)?
+            .map_err(|e| CommandError::Failed(format!("Failed to open session: {e}")))?
+    };
+    if let Err(e) = archive_active_session(&state) {
+        // Put the session back so it isn't lost from the history.
+        if let Ok(mut sessions) = state.sessions.lock() {
+            let _ = sessions.register(&session);
+        }
+        return Err(e);
+    }
+    let steps = session.steps.clone();
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    *session_lock = Some(session);
+    Ok(steps)
+}
+
+/// Remove a finished session from the history and delete its screenshots.
+#[tauri::command]
+fn delete_session(
+    state: tauri::State<'_, RecorderAppState>,
+    id: String,
+) -> Result<(), CommandError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("sessions"))?;
+    sessions
+        .delete(&id)
+        .map_err(|e| CommandError::Failed(format!("Failed to delete session: {e}")))?
+        .map(|_| ())
+        .ok_or(CommandError::SessionNotFound)
+}
+
+#[tauri::command]
+fn discard_recording(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<(), CommandError> {
+    // Stop the processing loop first
+    state.processing_running.store(false, Ordering::SeqCst);
+
+    // Small delay to let processing loop exit
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // Stop and remove click listener
+    {
+        let mut listener_lock = state
+            .click_listener
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("click listener"))?;
+        if let Some(listener) = listener_lock.take() {
+            listener.stop();
+        }
+    }
+
+    drop_session_observers(&state)?;
 
     // Write diagnostics, then clean up session temp dir and clear session
     {
-        let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
+        let mut session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
         if let Some(session) = session_lock.as_ref() {
             session.write_diagnostics();
             session.cleanup();
@@ -898,7 +1924,7 @@ fn discard_recording(
         let mut recorder_state = state
             .recorder_state
             .lock()
-            .map_err(|_| "recorder state lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("recorder state"))?;
         // Force reset to idle state
         *recorder_state = RecorderState::new();
     }
@@ -908,7 +1934,7 @@ fn discard_recording(
         let mut ps = state
             .pipeline_state
             .lock()
-            .map_err(|_| "pipeline state lock poisoned")?;
+            .map_err(|_| CommandError::LockPoisoned("pipeline state"))?;
         ps.reset();
     }
 
@@ -923,41 +1949,85 @@ fn discard_recording(
             if let Err(err) = tray::position_panel_at_current_tray_icon(&app_clone) {
                 eprintln!("Failed to position panel: {err}");
             }
+            tray::record_panel_bounds_when_settled(&app_clone);
             let ps_state = app_clone.state::<RecorderAppState>();
-            if let Ok(bounds) = panel::panel_bounds(&app_clone) {
-                if cfg!(debug_assertions) {
-                    eprintln!(
-                        "Panel bounds after discard: x={} y={} w={} h={}",
-                        bounds.x, bounds.y, bounds.width, bounds.height
-                    );
-                }
-                recorder::pipeline::record_panel_bounds(&ps_state.pipeline_state, bounds);
-            }
             recorder::pipeline::set_panel_visible(&ps_state.pipeline_state, true);
         }
-
-        if let Err(e) = tray::set_default_icon(&app_clone) {
-            eprintln!("Failed to reset tray icon: {e}");
-        }
     });
+    tray::refresh_recording_status(&app);
 
     Ok(())
 }
 
+/// `step-updated` payload: the step's fields plus the session revision after the change.
+#[derive(Serialize)]
+struct StepUpdated<'a> {
+    #[serde(flatten)]
+    step: &'a Step,
+    revision: u64,
+}
+
+#[derive(Serialize)]
+struct StepsReordered<'a> {
+    steps: &'a [Step],
+    revision: u64,
+}
+
+fn emit_step_updated(app: &tauri::AppHandle, step: &Step, revision: u64) {
+    let _ = app.emit("step-updated", StepUpdated { step, revision });
+}
+
+/// Revision of the active session, for events sent after its lock was released.
+fn session_revision(state: &RecorderAppState) -> u64 {
+    state
+        .session
+        .lock()
+        .ok()
+        .and_then(|session| session.as_ref().map(Session::revision))
+        .unwrap_or_default()
+}
+
 #[tauri::command]
 fn update_step_note(
     app: tauri::AppHandle,
     state: tauri::State<'_, RecorderAppState>,
     step_id: String,
     note: Option<String>,
-) -> Result<(), String> {
-    let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-    let session = session_lock.as_mut().ok_or("no active session")?;
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
     let updated = session
         .update_step_note(&step_id, note)
-        .ok_or("step not found")?
+        .ok_or(CommandError::StepNotFound)?
+        .clone();
+    emit_step_updated(&app, &updated, session.revision());
+    Ok(())
+}
+
+#[tauri::command]
+fn update_step_title(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    title: Option<String>,
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
+    let updated = session
+        .update_step_title(&step_id, title)
+        .ok_or(CommandError::StepNotFound)?
         .clone();
-    let _ = app.emit("step-updated", &updated);
+    emit_step_updated(&app, &updated, session.revision());
     Ok(())
 }
 
@@ -967,14 +2037,65 @@ fn update_step_description(
     state: tauri::State<'_, RecorderAppState>,
     step_id: String,
     description: Option<String>,
-) -> Result<(), String> {
-    let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-    let session = session_lock.as_mut().ok_or("no active session")?;
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
     let updated = session
         .set_step_description_manual(&step_id, description)
-        .ok_or("step not found")?
+        .ok_or(CommandError::StepNotFound)?
+        .clone();
+    emit_step_updated(&app, &updated, session.revision());
+    Ok(())
+}
+
+/// Annotate a step with a pause before the next one; `None` or 0 clears it.
+#[tauri::command]
+fn set_step_wait(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    wait_ms: Option<u32>,
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
+    let updated = session
+        .set_step_wait(&step_id, wait_ms)
+        .ok_or(CommandError::StepNotFound)?
+        .clone();
+    emit_step_updated(&app, &updated, session.revision());
+    Ok(())
+}
+
+/// Replace the arrows, boxes and labels drawn over a step; an empty list clears them.
+#[tauri::command]
+fn set_step_annotations(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    annotations: Vec<Annotation>,
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
+    let updated = session
+        .set_step_annotations(&step_id, annotations)
+        .ok_or(CommandError::StepNotFound)?
         .clone();
-    let _ = app.emit("step-updated", &updated);
+    emit_step_updated(&app, &updated, session.revision());
     Ok(())
 }
 
@@ -1020,17 +2141,180 @@ fn update_step_crop(
     state: tauri::State<'_, RecorderAppState>,
     step_id: String,
     crop_region: Option<BoundsPercent>,
-) -> Result<(), String> {
-    let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-    let session = session_lock.as_mut().ok_or("no active session")?;
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
     let updated = session
         .update_step_crop(&step_id, normalize_crop_region_input(crop_region))
-        .ok_or("step not found")?
+        .ok_or(CommandError::StepNotFound)?
         .clone();
-    let _ = app.emit("step-updated", &updated);
+    emit_step_updated(&app, &updated, session.revision());
     Ok(())
 }
 
+/// Outcome of applying a crop to one step in [`apply_crop_to_steps`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct StepCropResult {
+    step_id: String,
+    updated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Set (or clear, with `None`) the same crop on each listed step. Steps without a
+/// screenshot are skipped. Returns one result per ID and the updated steps.
+fn apply_crop(
+    session: &mut Session,
+    step_ids: &[String],
+    crop_region: Option<BoundsPercent>,
+) -> (Vec<StepCropResult>, Vec<Step>) {
+    let crop_region = normalize_crop_region_input(crop_region);
+    let mut results = Vec::with_capacity(step_ids.len());
+    let mut updated = Vec::new();
+    for step_id in step_ids {
+        let error = match session.steps.iter().find(|s| &s.id == step_id) {
+            None => Some("step not found"),
+            Some(step) if step.screenshot_path.is_none() => Some("step has no screenshot"),
+            Some(_) => None,
+        };
+        if error.is_none() {
+            if let Some(step) = session.update_step_crop(step_id, crop_region.clone()) {
+                updated.push(step.clone());
+            }
+        }
+        results.push(StepCropResult {
+            step_id: step_id.clone(),
+            updated: error.is_none(),
+            error: error.map(str::to_string),
+        });
+    }
+    (results, updated)
+}
+
+/// Apply one crop preset to many steps, or clear their crops with `crop_region: None`.
+#[tauri::command]
+fn apply_crop_to_steps(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_ids: Vec<String>,
+    crop_region: Option<BoundsPercent>,
+    expected_revision: Option<u64>,
+) -> Result<Vec<StepCropResult>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
+    let (results, updated) = apply_crop(session, &step_ids, crop_region);
+    for step in &updated {
+        emit_step_updated(&app, step, session.revision());
+    }
+    Ok(results)
+}
+
+/// Capture the frontmost window once, outside any recording, so permission or capture
+/// problems show up before the user records a workflow.
+#[tauri::command]
+fn preview_capture() -> Result<pipeline::CapturePreview, CommandError> {
+    Ok(pipeline::preview_capture(&pipeline::preview_capture_path())?)
+}
+
+#[tauri::command]
+fn recapture_step(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+) -> Result<Step, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let updated = pipeline::recapture_step(&step_id, session)?;
+    let blur_text = state
+        .pipeline_state
+        .lock()
+        .map(|ps| ps.privacy_blur_text)
+        .unwrap_or(false);
+    if blur_text {
+        pipeline::apply_privacy_blur(session, &updated);
+    }
+    let storage_warning = store_screenshots(session, &updated);
+    let revision = session.revision();
+    drop(session_lock);
+    emit_step_updated(&app, &updated, revision);
+    if let Some(info) = storage_warning {
+        handle_storage_cap(&app, info);
+    }
+    Ok(updated)
+}
+
+/// Put one step on the clipboard. `mode` is "image", "text" or "both".
+#[tauri::command]
+fn copy_step_to_clipboard(
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    mode: String,
+    annotate: Option<bool>,
+    app_language: Option<String>,
+) -> Result<(), CommandError> {
+    let mode = mode
+        .parse::<clipboard::CopyMode>()
+        .map_err(CommandError::InvalidArgument)?;
+    let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
+    let (num, step) = {
+        let session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
+        let session = session_lock.as_ref().ok_or(CommandError::NoSession)?;
+        session
+            .get_steps()
+            .iter()
+            .enumerate()
+            .find(|(_, s)| s.id == step_id)
+            .map(|(i, s)| (i + 1, s.clone()))
+            .ok_or(CommandError::StepNotFound)?
+    };
+    Ok(clipboard::copy_step(
+        &step,
+        num,
+        mode,
+        annotate.unwrap_or(false),
+        locale,
+    )?)
+}
+
+#[tauri::command]
+fn reset_step_crop_to_auto(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    expected_revision: Option<u64>,
+) -> Result<Step, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
+    let updated = session
+        .reset_step_crop_to_auto(&step_id)
+        .ok_or("step not found or has no capture bounds")?
+        .clone();
+    emit_step_updated(&app, &updated, session.revision());
+    Ok(updated)
+}
+
+/// Slightly longer than a one-liner, still "no novels" — enables useful context like "from the Dock".
+const AI_DESCRIPTION_MAX_CHARS: usize = 110;
+
 #[tauri::command]
 fn generate_step_descriptions(
     app: tauri::AppHandle,
@@ -1038,84 +2322,152 @@ fn generate_step_descriptions(
     mode: Option<String>,
     step_ids: Option<Vec<String>>,
     app_language: Option<String>,
-) -> Result<(), String> {
-    // Serialize description generation to avoid racing step updates.
-    if state.ai_descriptions_running.swap(true, Ordering::SeqCst) {
-        return Err("AI description generation already running.".into());
-    }
-
-    struct ResetOnDrop(std::sync::Arc<std::sync::atomic::AtomicBool>);
-    impl Drop for ResetOnDrop {
-        fn drop(&mut self) {
-            self.0.store(false, Ordering::SeqCst);
-        }
-    }
-    let running_guard = ResetOnDrop(state.ai_descriptions_running.clone());
+    provider: Option<String>,
+) -> Result<(), CommandError> {
+    use descriptions::queue::{DescriptionJob, DescriptionMode};
 
-    #[derive(Debug, Clone, Copy)]
-    enum Mode {
-        MissingOnly,
-        All,
-        Ids,
-    }
+    let provider = descriptions::provider_for(
+        descriptions::ProviderKind::parse(provider.as_deref())
+            .map_err(CommandError::InvalidArgument)?,
+    )?;
 
     let parsed_mode = match (mode.as_deref(), step_ids.as_ref()) {
-        (_, Some(ids)) if !ids.is_empty() => Mode::Ids,
-        (Some("all"), _) => Mode::All,
-        _ => Mode::MissingOnly,
+        (_, Some(ids)) if !ids.is_empty() => DescriptionMode::Ids,
+        (Some("all"), _) => DescriptionMode::All,
+        _ => DescriptionMode::MissingOnly,
     };
-
-    // Slightly longer than a one-liner, still "no novels" — enables useful context like "from the Dock".
-    let max_chars = 110usize;
     let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
-    let mut ids_to_generate: Vec<String> = Vec::new();
-    let (steps_to_generate, session_dir): (Vec<Step>, std::path::PathBuf) = {
-        let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-        let Some(session) = session_lock.as_mut() else {
-            return Err("no active session".into());
-        };
 
-        let session_dir = session.temp_dir.clone();
-        let mut steps: Vec<Step> = Vec::new();
-        let id_set: std::collections::HashSet<String> =
-            step_ids.clone().unwrap_or_default().into_iter().collect();
+    // The session stays locked while queueing, so the worker can't pick the job up
+    // before its steps are marked as generating.
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let Some(session) = session_lock.as_mut() else {
+        return Err(CommandError::NoSession);
+    };
 
-        for step in session.steps.iter_mut() {
-            if crate::apple_intelligence::is_auth_placeholder(step) {
-                continue;
-            }
-            if step.action == ActionType::Note {
-                continue;
+    let id_set: std::collections::HashSet<String> =
+        step_ids.unwrap_or_default().into_iter().collect();
+    let mut ids_to_generate: Vec<String> = Vec::new();
+    for step in session.steps.iter() {
+        if step.is_auth_placeholder() {
+            continue;
+        }
+        if step.action == ActionType::Note {
+            continue;
+        }
+
+        let should_generate = match parsed_mode {
+            DescriptionMode::Ids => id_set.contains(&step.id),
+            DescriptionMode::All => {
+                !matches!(step.description_source, Some(DescriptionSource::Manual))
             }
+            // Rule-based descriptions count as missing: AI may improve on them.
+            DescriptionMode::MissingOnly => match step.description_source {
+                Some(DescriptionSource::Manual) => false,
+                Some(DescriptionSource::Auto) => true,
+                _ => crate::apple_intelligence::is_blank_description(step.description.as_deref()),
+            },
+        };
 
-            let should_generate = match parsed_mode {
-                Mode::Ids => id_set.contains(&step.id),
-                Mode::All => !matches!(step.description_source, Some(DescriptionSource::Manual)),
-                Mode::MissingOnly => {
-                    crate::apple_intelligence::is_blank_description(step.description.as_deref())
-                        && !matches!(step.description_source, Some(DescriptionSource::Manual))
-                }
-            };
+        if should_generate {
+            ids_to_generate.push(step.id.clone());
+        }
+    }
 
-            if !should_generate {
-                continue;
-            }
+    let enqueued = state
+        .ai_queue
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("AI queue"))?
+        .enqueue(DescriptionJob {
+            mode: parsed_mode,
+            step_ids: ids_to_generate,
+            locale,
+            provider,
+        });
+    // Only the steps marked here accept results, so deleting or editing one while
+    // it waits or generates drops its answer.
+    for id in &enqueued.step_ids {
+        if let Some(step) = session.begin_step_description(id).cloned() {
+            emit_step_updated(&app, &step, session.revision());
+        }
+    }
+    drop(session_lock);
 
-            step.description_status = Some(DescriptionStatus::Generating);
-            step.description_error = None;
+    if enqueued.start_worker {
+        spawn_description_worker(app);
+    }
+    Ok(())
+}
 
-            let updated = step.clone();
-            ids_to_generate.push(step.id.clone());
-            steps.push(updated.clone());
-            let _ = app.emit("step-updated", &updated);
+/// Steps waiting for AI descriptions and steps being described right now.
+#[tauri::command]
+fn get_ai_queue_status(
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<descriptions::queue::QueueStatus, CommandError> {
+    Ok(state
+        .ai_queue
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("AI queue"))?
+        .status())
+}
+
+/// Drain the description queue one job at a time. The worker stops once the queue is
+/// empty; the next request starts a new one.
+fn spawn_description_worker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let job = {
+                let state = app.state::<RecorderAppState>();
+                let mut queue = match state.ai_queue.lock() {
+                    Ok(q) => q,
+                    Err(e) => e.into_inner(),
+                };
+                queue.next_job()
+            };
+            let Some(job) = job else {
+                break;
+            };
+            run_description_job(&app, job).await;
         }
+    });
+}
 
-        (steps, session_dir)
+/// Describe the steps of one queued job that still wait for it, emitting progress and
+/// per-step updates as results come in.
+async fn run_description_job(app: &tauri::AppHandle, job: descriptions::queue::DescriptionJob) {
+    let max_chars = AI_DESCRIPTION_MAX_CHARS;
+    let locale = job.locale;
+    let overwrite_manual = job.overwrite_manual();
+
+    // Steps deleted or edited while the job waited are no longer generating.
+    let (steps_to_generate, session_dir): (Vec<Step>, std::path::PathBuf) = {
+        let state = app.state::<RecorderAppState>();
+        let session_lock = match state.session.lock() {
+            Ok(l) => l,
+            Err(e) => e.into_inner(),
+        };
+        let Some(session) = session_lock.as_ref() else {
+            return;
+        };
+        let steps = session
+            .steps
+            .iter()
+            .filter(|s| {
+                job.step_ids.contains(&s.id)
+                    && s.description_status == Some(DescriptionStatus::Generating)
+            })
+            .cloned()
+            .collect();
+        (steps, session.temp_dir.clone())
     };
 
     if steps_to_generate.is_empty() {
-        return Ok(());
+        return;
     }
+    let ids_to_generate: Vec<String> = steps_to_generate.iter().map(|s| s.id.clone()).collect();
 
     #[cfg(debug_assertions)]
     let trace_ts = std::time::SystemTime::now()
@@ -1130,198 +2482,316 @@ fn generate_step_descriptions(
             &format!(
                 "ai_generate_start trace={} mode={:?} count={} max_chars={}",
                 trace_ts,
-                mode.as_deref().unwrap_or("missing_only"),
+                job.mode,
                 steps_to_generate.len(),
                 max_chars
             ),
         );
         let req_json = serde_json::json!({
             "trace": trace_ts,
-            "mode": mode.as_deref().unwrap_or("missing_only"),
+            "mode": format!("{:?}", job.mode),
             "max_chars": max_chars,
             "step_ids": ids_to_generate,
             "steps": steps_to_generate,
         });
         write_session_json(
             &session_dir,
-            &format!("ai-trace-{trace_ts}-request.json"),
-            &req_json,
-        );
-    }
-
-    let running = state.ai_descriptions_running.clone();
-    let app_handle = app.clone();
-    let session_dir_for_logs = session_dir.clone();
-
-    tauri::async_runtime::spawn(async move {
-        let generate_steps = steps_to_generate;
-
-        let resp = tauri::async_runtime::spawn_blocking(move || {
-            crate::apple_intelligence::generate_descriptions(generate_steps, max_chars, locale)
-        })
-        .await;
-
-        let apply_error_to_all = |app_handle: &tauri::AppHandle, ids: &[String], err: String| {
-            let state = app_handle.state::<RecorderAppState>();
-            let mut session_lock = match state.session.lock() {
-                Ok(l) => l,
-                Err(e) => e.into_inner(),
-            };
-            let Some(session) = session_lock.as_mut() else {
-                return;
-            };
-            for id in ids {
-                if let Some(step) = session.mark_step_description_failed(id, err.clone()) {
-                    let _ = app_handle.emit("step-updated", step);
-                }
-            }
-        };
-
-        match resp {
-            Ok(Ok(gen)) => {
-                #[cfg(debug_assertions)]
-                {
-                    let resp_json = serde_json::json!({
-                        "trace": trace_ts,
-                        "results": gen.results,
-                        "failures": gen.failures,
-                    });
-                    write_session_json(
-                        &session_dir_for_logs,
-                        &format!("ai-trace-{trace_ts}-response.json"),
-                        &resp_json,
-                    );
-                }
-
-                let state = app_handle.state::<RecorderAppState>();
-                let mut session_lock = match state.session.lock() {
-                    Ok(l) => l,
-                    Err(e) => e.into_inner(),
-                };
-                let Some(session) = session_lock.as_mut() else {
-                    running.store(false, Ordering::SeqCst);
-                    return;
-                };
-
-                let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-
-                for r in gen.results {
-                    seen.insert(r.id.clone());
-                    #[cfg(debug_assertions)]
-                    {
-                        session_debug_log(
-                            &session_dir_for_logs,
-                            &format!(
-                                "ai_desc trace={} id={} text={}",
-                                trace_ts,
-                                r.id,
-                                json_escape_one_line(&r.text)
-                            ),
-                        );
-                        if let Some(debug) = &r.debug {
-                            if let Ok(debug_json) = serde_json::to_string(debug) {
-                                session_debug_log(
-                                    &session_dir_for_logs,
-                                    &format!(
-                                        "ai_desc_debug trace={} id={} data={}",
-                                        trace_ts,
-                                        r.id,
-                                        json_escape_one_line(&debug_json)
-                                    ),
-                                );
-                            }
-                        }
-                    }
-                    if let Some(step) = session.apply_step_description_ai(&r.id, r.text) {
-                        let _ = app_handle.emit("step-updated", step);
-                    }
-                }
-                for f in gen.failures {
-                    seen.insert(f.id.clone());
-                    if f.id == "*" {
-                        continue;
-                    }
-                    #[cfg(debug_assertions)]
-                    {
-                        session_debug_log(
-                            &session_dir_for_logs,
-                            &format!(
-                                "ai_desc_failed trace={} id={} error={}",
-                                trace_ts,
-                                f.id,
-                                json_escape_one_line(&f.error)
-                            ),
-                        );
-                    }
-                    if let Some(step) = session.mark_step_description_failed(&f.id, f.error) {
-                        let _ = app_handle.emit("step-updated", step);
-                    }
+            &format!("ai-trace-{trace_ts}-request.json"),
+            &req_json,
+        );
+    }
+
+    let app_handle = app.clone();
+    let session_dir_for_logs = session_dir;
+
+    // Run the helper in small sequential chunks so steps update as they finish
+    // and one failing chunk doesn't take the whole batch down with it.
+    let chunks = crate::apple_intelligence::chunk_steps(
+        steps_to_generate,
+        crate::apple_intelligence::GENERATE_CHUNK_SIZE,
+    );
+    let total = ids_to_generate.len();
+    let mut completed = 0usize;
+    let _ = app_handle.emit(
+        "ai-generation-progress",
+        AiGenerationProgress { completed, total },
+    );
+
+    let apply_error_to_all = |app_handle: &tauri::AppHandle, ids: &[String], err: String| {
+        let state = app_handle.state::<RecorderAppState>();
+        let mut session_lock = match state.session.lock() {
+            Ok(l) => l,
+            Err(e) => e.into_inner(),
+        };
+        let Some(session) = session_lock.as_mut() else {
+            return;
+        };
+        for step in fail_step_descriptions(session, ids, &err) {
+            emit_step_updated(app_handle, &step, session.revision());
+        }
+    };
+
+    for (_chunk_index, chunk) in chunks.into_iter().enumerate() {
+        let chunk_ids: Vec<String> = chunk.iter().map(|s| s.id.clone()).collect();
+
+        let provider = job.provider.clone();
+        let chunk_app = app_handle.clone();
+        #[cfg(debug_assertions)]
+        let session_dir = session_dir_for_logs.clone();
+        // Steps are applied as the provider hands them over, so the editor fills in
+        // one step at a time instead of once per chunk.
+        let resp = tauri::async_runtime::spawn_blocking(move || {
+            let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+            let mut session_gone = false;
+            #[cfg(debug_assertions)]
+            let mut trace_items = Vec::new();
+            let outcome = provider.generate_streaming(chunk, max_chars, locale, &mut |item| {
+                seen.insert(item.id().to_string());
+                #[cfg(debug_assertions)]
+                {
+                    log_generated_item(&session_dir, trace_ts, &item);
+                    trace_items.push(item.clone());
                 }
+                session_gone |= !apply_generated_item(&chunk_app, item, overwrite_manual);
+            });
+            #[cfg(debug_assertions)]
+            write_session_json(
+                &session_dir,
+                &format!("ai-trace-{trace_ts}-response-{_chunk_index}.json"),
+                &serde_json::json!({
+                    "trace": trace_ts,
+                    "chunk": _chunk_index,
+                    "items": trace_items,
+                }),
+            );
+            (outcome, seen, session_gone)
+        })
+        .await;
 
-                // Any step that was marked generating but has no result should be failed.
-                for id in &ids_to_generate {
-                    if seen.contains(id) {
-                        continue;
-                    }
-                    #[cfg(debug_assertions)]
-                    {
+        match resp {
+            // Session discarded mid-run: nothing left to update.
+            Ok((_, _, true)) => break,
+            Ok((outcome, seen, false)) => {
+                // Steps of this chunk without a result fail, with the provider's
+                // error if it stopped early.
+                let missing: Vec<String> = chunk_ids
+                    .iter()
+                    .filter(|id| !seen.contains(*id))
+                    .cloned()
+                    .collect();
+                let err = match outcome {
+                    Ok(()) => "No model output.".to_string(),
+                    Err(err) => {
+                        #[cfg(debug_assertions)]
                         session_debug_log(
                             &session_dir_for_logs,
                             &format!(
-                                "ai_desc_failed trace={} id={} error={}",
-                                trace_ts, id, "No model output."
+                                "ai_generate_failed trace={} chunk={} error={}",
+                                trace_ts,
+                                _chunk_index,
+                                json_escape_one_line(&err)
                             ),
                         );
+                        err
                     }
-                    if let Some(step) =
-                        session.mark_step_description_failed(id, "No model output.".into())
-                    {
-                        let _ = app_handle.emit("step-updated", step);
-                    }
-                }
-
-                #[cfg(debug_assertions)]
-                session_debug_log(
-                    &session_dir_for_logs,
-                    &format!("ai_generate_done trace={trace_ts}"),
-                );
-            }
-            Ok(Err(err)) => {
+                };
                 #[cfg(debug_assertions)]
-                session_debug_log(
-                    &session_dir_for_logs,
-                    &format!(
-                        "ai_generate_failed trace={} error={}",
-                        trace_ts,
-                        json_escape_one_line(&err)
-                    ),
-                );
-                apply_error_to_all(&app_handle, &ids_to_generate, err)
+                for id in &missing {
+                    session_debug_log(
+                        &session_dir_for_logs,
+                        &format!(
+                            "ai_desc_failed trace={} id={} error={}",
+                            trace_ts,
+                            id,
+                            json_escape_one_line(&err)
+                        ),
+                    );
+                }
+                apply_error_to_all(&app_handle, &missing, err);
             }
             Err(err) => {
                 #[cfg(debug_assertions)]
                 session_debug_log(
                     &session_dir_for_logs,
                     &format!(
-                        "ai_generate_failed trace={} error={}",
+                        "ai_generate_failed trace={} chunk={} error={}",
                         trace_ts,
+                        _chunk_index,
                         json_escape_one_line(&err.to_string())
                     ),
                 );
                 apply_error_to_all(
                     &app_handle,
-                    &ids_to_generate,
+                    &chunk_ids,
                     format!("AI generation task failed: {err}"),
                 )
             }
         }
 
-        running.store(false, Ordering::SeqCst);
-    });
+        completed += chunk_ids.len();
+        let _ = app_handle.emit(
+            "ai-generation-progress",
+            AiGenerationProgress { completed, total },
+        );
+    }
 
-    // Background task owns resetting the running flag.
-    std::mem::forget(running_guard);
-    Ok(())
+    #[cfg(debug_assertions)]
+    session_debug_log(
+        &session_dir_for_logs,
+        &format!("ai_generate_done trace={trace_ts} completed={completed} total={total}"),
+    );
+}
+
+/// Apply one generated description, or its failure, to the session and emit
+/// `step-updated`. Returns `false` once the session is gone.
+fn apply_generated_item(
+    app: &tauri::AppHandle,
+    item: GenerateItem,
+    overwrite_manual: bool,
+) -> bool {
+    let state = app.state::<RecorderAppState>();
+    let mut session_lock = match state.session.lock() {
+        Ok(l) => l,
+        Err(e) => e.into_inner(),
+    };
+    let Some(session) = session_lock.as_mut() else {
+        return false;
+    };
+    if let Some(step) = apply_generated_item_to(session, item, overwrite_manual) {
+        emit_step_updated(app, &step, session.revision());
+    }
+    true
+}
+
+/// Apply one generated description, or its failure, to `session`. Returns the step to
+/// announce; `None` when the step was deleted or stopped generating meanwhile.
+fn apply_generated_item_to(
+    session: &mut Session,
+    item: GenerateItem,
+    overwrite_manual: bool,
+) -> Option<Step> {
+    match item {
+        GenerateItem::Result(r) => session
+            .apply_step_description_ai(&r.id, r.text, overwrite_manual)
+            .cloned(),
+        // Batch-level failure from the helper; the steps fail for lack of a result.
+        GenerateItem::Failure(f) if f.id == "*" => None,
+        GenerateItem::Failure(f) => session
+            .mark_step_description_failed(&f.id, f.error)
+            .cloned(),
+    }
+}
+
+/// Fail the description of each of `ids` still generating, returning the steps to announce.
+fn fail_step_descriptions(session: &mut Session, ids: &[String], err: &str) -> Vec<Step> {
+    ids.iter()
+        .filter_map(|id| {
+            session
+                .mark_step_description_failed(id, err.to_string())
+                .cloned()
+        })
+        .collect()
+}
+
+#[cfg(debug_assertions)]
+fn log_generated_item(session_dir: &std::path::Path, trace_ts: u128, item: &GenerateItem) {
+    match item {
+        GenerateItem::Result(r) => {
+            session_debug_log(
+                session_dir,
+                &format!(
+                    "ai_desc trace={} id={} text={}",
+                    trace_ts,
+                    r.id,
+                    json_escape_one_line(&r.text)
+                ),
+            );
+            if let Some(debug_json) = r.debug.as_ref().and_then(|d| serde_json::to_string(d).ok()) {
+                session_debug_log(
+                    session_dir,
+                    &format!(
+                        "ai_desc_debug trace={} id={} data={}",
+                        trace_ts,
+                        r.id,
+                        json_escape_one_line(&debug_json)
+                    ),
+                );
+            }
+        }
+        GenerateItem::Failure(f) => session_debug_log(
+            session_dir,
+            &format!(
+                "ai_desc_failed trace={} id={} error={}",
+                trace_ts,
+                f.id,
+                json_escape_one_line(&f.error)
+            ),
+        ),
+    }
+}
+
+/// Queue a regeneration of one step's description. Returns the step as queued; the
+/// worker reports the result through `step-updated`.
+#[tauri::command]
+fn retry_step_description(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    step_id: String,
+    force: Option<bool>,
+    app_language: Option<String>,
+    provider: Option<String>,
+) -> Result<Step, CommandError> {
+    let provider =
+        descriptions::provider_for(descriptions::ProviderKind::parse(provider.as_deref())?)?;
+    let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
+
+    // Queue behind running batches instead of failing; the worker applies the result.
+    let (step, start_worker) = {
+        let mut session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
+        let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+        let step = session
+            .steps
+            .iter()
+            .find(|s| s.id == step_id)
+            .ok_or(CommandError::StepNotFound)?;
+        if step.is_auth_placeholder() || step.action == ActionType::Note {
+            return Err("step has no generated description".into());
+        }
+        if !force.unwrap_or(false)
+            && matches!(step.description_source, Some(DescriptionSource::Manual))
+        {
+            return Err(CommandError::ManualDescription);
+        }
+        let enqueued = state
+            .ai_queue
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("AI queue"))?
+            .enqueue(descriptions::queue::DescriptionJob {
+                mode: descriptions::queue::DescriptionMode::Ids,
+                step_ids: vec![step_id.clone()],
+                locale,
+                provider,
+            });
+        for id in &enqueued.step_ids {
+            if let Some(step) = session.begin_step_description(id).cloned() {
+                emit_step_updated(&app, &step, session.revision());
+            }
+        }
+        let step = session
+            .steps
+            .iter()
+            .find(|s| s.id == step_id)
+            .cloned()
+            .ok_or(CommandError::StepNotFound)?;
+        (step, enqueued.start_worker)
+    };
+    if start_worker {
+        spawn_description_worker(app.clone());
+    }
+    Ok(step)
 }
 
 #[tauri::command]
@@ -1329,32 +2799,161 @@ fn delete_step(
     app: tauri::AppHandle,
     state: tauri::State<'_, RecorderAppState>,
     step_id: String,
-) -> Result<(), String> {
-    let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-    let session = session_lock.as_mut().ok_or("no active session")?;
+    expected_revision: Option<u64>,
+) -> Result<(), CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
     if !session.delete_step(&step_id) {
-        return Err("step not found".into());
+        return Err(CommandError::StepNotFound);
     }
     let _ = app.emit("step-deleted", &step_id);
     Ok(())
 }
 
+#[tauri::command]
+fn delete_auto_steps(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<Vec<String>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let removed = session.delete_auto_steps();
+    for step_id in &removed {
+        let _ = app.emit("step-deleted", step_id);
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+fn deduplicate_steps(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<recorder::dedup::DedupSummary, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let summary = recorder::dedup::deduplicate(session);
+    emit_deduplicated(&app, &summary);
+    Ok(summary)
+}
+
+/// Drop steps that repeat the previous one at (almost) the same spot and time, then
+/// return the cleaned list. Thresholds default to the session's.
+#[tauri::command]
+fn dedupe_steps(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    max_distance_px: Option<i32>,
+    max_interval_ms: Option<i64>,
+) -> Result<Vec<Step>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let removed = session.dedupe_consecutive(
+        max_distance_px.unwrap_or(recorder::session::DEFAULT_DEDUPE_DISTANCE_PX),
+        max_interval_ms.unwrap_or(recorder::session::DEFAULT_DEDUPE_INTERVAL_MS),
+    );
+    for step_id in &removed {
+        let _ = app.emit("step-deleted", step_id);
+    }
+    Ok(session.get_steps().to_vec())
+}
+
+fn emit_deduplicated(app: &tauri::AppHandle, summary: &recorder::dedup::DedupSummary) {
+    for dup in &summary.removed {
+        let _ = app.emit("step-deleted", &dup.step_id);
+    }
+}
+
 #[tauri::command]
 fn reorder_steps(
     app: tauri::AppHandle,
     state: tauri::State<'_, RecorderAppState>,
     step_ids: Vec<String>,
-) -> Result<Vec<Step>, String> {
-    let mut session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
-    let session = session_lock.as_mut().ok_or("no active session")?;
+    renumber_files: Option<bool>,
+    expected_revision: Option<u64>,
+) -> Result<Vec<Step>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    session.check_revision(expected_revision)?;
     session.reorder_steps(&step_ids);
+    if renumber_files.unwrap_or(false) {
+        session
+            .renumber_screenshots()
+            .map_err(|e| format!("Failed to renumber screenshots: {e}"))?;
+    }
+    let steps = session.get_steps().to_vec();
+    let _ = app.emit(
+        "steps-reordered",
+        StepsReordered {
+            steps: &steps,
+            revision: session.revision(),
+        },
+    );
+    Ok(steps)
+}
+
+/// Undo (`redo == false`) or redo the last editor change, then return the steps. The
+/// list goes out as `steps-reordered`, so every window replaces its copy.
+fn step_through_edit_history(
+    app: &tauri::AppHandle,
+    state: &RecorderAppState,
+    redo: bool,
+) -> Result<Vec<Step>, CommandError> {
+    let mut session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    let session = session_lock.as_mut().ok_or(CommandError::NoSession)?;
+    let changed = if redo { session.redo() } else { session.undo() };
     let steps = session.get_steps().to_vec();
-    let _ = app.emit("steps-reordered", &steps);
+    if changed {
+        let _ = app.emit(
+            "steps-reordered",
+            StepsReordered {
+                steps: &steps,
+                revision: session.revision(),
+            },
+        );
+    }
     Ok(steps)
 }
 
+/// Undo the last editor change (edit, delete or reorder). Nothing happens when there
+/// is none.
+#[tauri::command]
+fn undo_edit(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<Vec<Step>, CommandError> {
+    step_through_edit_history(&app, &state, false)
+}
+
+/// Reapply the last undone editor change. Nothing happens when there is none.
 #[tauri::command]
-fn open_editor_window(app: tauri::AppHandle) -> Result<(), String> {
+fn redo_edit(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+) -> Result<Vec<Step>, CommandError> {
+    step_through_edit_history(&app, &state, true)
+}
+
+#[tauri::command]
+fn open_editor_window(app: tauri::AppHandle) -> Result<(), CommandError> {
     // Hide the tray panel so it doesn't overlap the editor
     if let Some(panel_window) = app.get_webview_window(panel::panel_label()) {
         let _ = panel_window.hide();
@@ -1377,25 +2976,252 @@ fn open_editor_window(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
-async fn export_guide(
-    app: tauri::AppHandle,
-    state: tauri::State<'_, RecorderAppState>,
+fn session_export_input(
+    state: &RecorderAppState,
+) -> Result<(Vec<Step>, SessionMetadata), CommandError> {
+    let session_lock = state
+        .session
+        .lock()
+        .map_err(|_| CommandError::LockPoisoned("session"))?;
+    Ok(session_lock
+        .as_ref()
+        .map(|s| (s.get_steps().to_vec(), s.metadata.clone()))
+        .unwrap_or_default())
+}
+
+/// What the export sheet asks for. Unset options take their defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ExportRequest {
     title: String,
     format: String,
+    /// File or folder to write to; unused by `preview_export`.
     output_path: String,
+    filename_template: Option<String>,
     app_language: Option<String>,
-) -> Result<(), String> {
-    let fmt = export::ExportFormat::from_str(&format)?;
-    let locale = i18n::resolve_locale(i18n::parse_app_language(app_language.as_deref()));
-    let steps = {
-        let session_lock = state.session.lock().map_err(|_| "session lock poisoned")?;
+    number_badges: Option<bool>,
+    diff_highlight: Option<bool>,
+    include_toc: Option<bool>,
+    elapsed_time: Option<bool>,
+    markdown_image_max_width: Option<u32>,
+    markdown_front_matter: Option<bool>,
+    markdown_weight: Option<i32>,
+    text_only: Option<bool>,
+    screen_reader_text: Option<bool>,
+    html_layout: Option<String>,
+    image_max_width: Option<u32>,
+    image_quality: Option<u8>,
+    image_format: Option<String>,
+    gif_step_ms: Option<u32>,
+    gif_captions: Option<bool>,
+}
+
+/// Everything an export needs, resolved from an [`ExportRequest`] and the session.
+struct PreparedExport {
+    format: export::ExportFormat,
+    locale: i18n::Locale,
+    title: String,
+    steps: Vec<Step>,
+    options: export::ExportOptions,
+}
+
+impl ExportRequest {
+    fn prepare(self, state: &RecorderAppState) -> Result<PreparedExport, CommandError> {
+        let format = export::ExportFormat::from_str(&self.format)?;
+        let locale = i18n::resolve_locale(i18n::parse_app_language(self.app_language.as_deref()));
+        let (steps, metadata) = session_export_input(state)?;
+        // An empty title falls back to the one saved with the session.
+        let title = match self.title.trim() {
+            "" => metadata.title.clone(),
+            _ => self.title.clone(),
+        };
+        let options = self.options(metadata)?;
+        Ok(PreparedExport {
+            format,
+            locale,
+            title,
+            steps,
+            options,
+        })
+    }
+
+    /// Export options from the editor's settings; `None` picks each setting's default.
+    fn options(self, metadata: SessionMetadata) -> Result<export::ExportOptions, CommandError> {
+        let settings = settings::load();
+        let image_format = self
+            .image_format
+            .as_deref()
+            .map(str::parse::<export::ExportImageFormat>)
+            .transpose()
+            .map_err(CommandError::InvalidArgument)?
+            .unwrap_or(settings.image_format);
+        let html_layout = self
+            .html_layout
+            .as_deref()
+            .map(str::parse::<export::html::HtmlLayout>)
+            .transpose()
+            .map_err(CommandError::InvalidArgument)?
+            .unwrap_or_default();
+        Ok(export::ExportOptions {
+            number_badges: self.number_badges.unwrap_or(false),
+            diff_highlight: self.diff_highlight.unwrap_or(false),
+            include_toc: self.include_toc.unwrap_or(false),
+            elapsed_time: self.elapsed_time.unwrap_or(false),
+            // 0 keeps plain Markdown image references.
+            markdown_image_max_width: self.markdown_image_max_width.filter(|&width| width > 0),
+            markdown_front_matter: self.markdown_front_matter.unwrap_or(false),
+            markdown_weight: self.markdown_weight,
+            text_only: self.text_only.unwrap_or(false),
+            screen_reader_text: self.screen_reader_text.unwrap_or(false),
+            html_layout,
+            image: export::ExportImageOptions {
+                // 0 keeps the original size.
+                max_width: match self.image_max_width {
+                    Some(0) => None,
+                    Some(width) => Some(width),
+                    None => Some(export::DEFAULT_EXPORT_MAX_WIDTH),
+                },
+                max_dimension: Some(settings.export_max_edge_px).filter(|&edge| edge > 0),
+                quality: self.image_quality.unwrap_or(export::DEFAULT_EXPORT_QUALITY),
+                format: image_format,
+            },
+            recorded_on: metadata.created_on(),
+            author: metadata.author,
+            tags: metadata.tags,
+            animation: export::AnimationOptions {
+                step_hold_ms: self.gif_step_ms.unwrap_or(export::DEFAULT_STEP_HOLD_MS),
+                captions: self.gif_captions.unwrap_or(false),
+                ..export::AnimationOptions::default()
+            },
+            branding: settings.export_branding,
+        })
+    }
+}
+
+/// Result of `export_guide`: where the guide was written, plus the export summary.
+#[derive(Serialize)]
+struct ExportedGuide {
+    path: String,
+    #[serde(flatten)]
+    summary: export::ExportSummary,
+}
+
+#[tauri::command]
+async fn export_guide(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    request: ExportRequest,
+) -> Result<ExportedGuide, CommandError> {
+    let output_path = request.output_path.clone();
+    let filename_template = request.filename_template.clone();
+    let PreparedExport {
+        format: fmt,
+        locale,
+        title,
+        steps,
+        options,
+    } = request.prepare(&state)?;
+    // A folder gets a file named from the template; returned so the UI can reveal it.
+    let output_path = export::filename::resolve_output_path(
+        &output_path,
+        filename_template.as_deref(),
+        fmt,
+        &title,
+        chrono::Local::now().naive_local(),
+        steps.len(),
+    );
+    // Off the async runtime so progress events reach the UI while images are encoded.
+    let upload_app = app.clone();
+    let exported = tauri::async_runtime::spawn_blocking(move || {
+        let on_progress = |progress: export::ExportProgress| {
+            let _ = app.emit("export-progress", progress);
+        };
+        export::export(
+            &title,
+            &steps,
+            fmt,
+            &output_path,
+            &app,
+            locale,
+            &options,
+            &on_progress,
+        )
+        .map(|summary| ExportedGuide {
+            path: output_path,
+            summary,
+        })
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("Export task failed: {e}")))?
+    .map_err(CommandError::from)?;
+    spawn_export_upload(upload_app, exported.path.clone(), fmt);
+    Ok(exported)
+}
+
+/// Upload a finished export when `post_export_webhook` is set, without holding up
+/// the export result. The outcome arrives as `export-uploaded` or `export-upload-failed`.
+fn spawn_export_upload(app: tauri::AppHandle, path: String, fmt: export::ExportFormat) {
+    let Some(webhook) = settings::load()
+        .post_export_webhook
+        .filter(|w| w.is_enabled())
+    else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let url = webhook.url.trim().to_string();
+        match export::upload::upload(&webhook, std::path::Path::new(&path), fmt).await {
+            Ok(status) => {
+                let _ = app.emit(
+                    "export-uploaded",
+                    export::upload::ExportUploaded { path, url, status },
+                );
+            }
+            Err(error) => {
+                eprintln!("Export upload to {url} failed: {error}");
+                let _ = app.emit(
+                    "export-upload-failed",
+                    export::upload::ExportUploadFailed { path, url, error },
+                );
+                // The panel hides after exporting; bring it back to report the failure.
+                let handle = app.clone();
+                let _ = app.run_on_main_thread(move || tray::show_panel(&handle));
+            }
+        }
+    });
+}
+
+/// Render the guide the way `export_guide` would, without writing anything.
+#[tauri::command]
+async fn preview_export(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, RecorderAppState>,
+    request: ExportRequest,
+) -> Result<export::ExportPreview, CommandError> {
+    let session_dir = {
+        let session_lock = state
+            .session
+            .lock()
+            .map_err(|_| CommandError::LockPoisoned("session"))?;
         session_lock
             .as_ref()
-            .map(|s| s.get_steps().to_vec())
+            .map(|s| s.temp_dir.clone())
             .unwrap_or_default()
     };
-    export::export(&title, &steps, fmt, &output_path, &app, locale)
+    let PreparedExport {
+        format: fmt,
+        locale,
+        title,
+        steps,
+        options,
+    } = request.prepare(&state)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        export::preview(&title, &steps, fmt, &app, locale, &options, &|path| {
+            session_protocol::url_for(&session_dir, path)
+        })
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("Preview task failed: {e}")))?
+    .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -1404,7 +3230,7 @@ fn get_startup_state() -> startup_state::StartupState {
 }
 
 #[tauri::command]
-fn mark_startup_seen(app: tauri::AppHandle) -> Result<(), String> {
+fn mark_startup_seen(app: tauri::AppHandle) -> Result<(), CommandError> {
     let mut state = startup_state::load();
     state.has_launched_before = true;
     state.last_seen_version = Some(env!("CARGO_PKG_VERSION").to_string());
@@ -1418,20 +3244,129 @@ fn mark_startup_seen(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn dismiss_whats_new() -> Result<(), String> {
+fn dismiss_whats_new() -> Result<(), CommandError> {
     let mut state = startup_state::load();
     state.last_seen_version = Some(env!("CARGO_PKG_VERSION").to_string());
-    startup_state::save(&state)
+    Ok(startup_state::save(&state)?)
+}
+
+/// Description provider settings as the UI sees them; the API key never leaves the backend.
+#[derive(Debug, Clone, Serialize)]
+struct DescriptionSettingsView {
+    openai_endpoint: String,
+    openai_model: String,
+    openai_has_api_key: bool,
+    openai_send_screenshots: bool,
+}
+
+impl From<&descriptions::settings::DescriptionSettings> for DescriptionSettingsView {
+    fn from(settings: &descriptions::settings::DescriptionSettings) -> Self {
+        Self {
+            openai_endpoint: settings.openai.endpoint.clone(),
+            openai_model: settings.openai.model.clone(),
+            openai_has_api_key: settings.openai.has_api_key(),
+            openai_send_screenshots: settings.openai.send_screenshots,
+        }
+    }
+}
+
+#[tauri::command]
+fn get_description_settings() -> DescriptionSettingsView {
+    DescriptionSettingsView::from(&descriptions::settings::load())
+}
+
+/// Update provider settings. `None` keeps a value; an empty API key clears it.
+#[tauri::command]
+fn save_description_settings(
+    openai_endpoint: Option<String>,
+    openai_model: Option<String>,
+    openai_api_key: Option<String>,
+    openai_send_screenshots: Option<bool>,
+) -> Result<DescriptionSettingsView, CommandError> {
+    let mut settings = descriptions::settings::load();
+    if let Some(endpoint) = openai_endpoint {
+        settings.openai.endpoint = endpoint.trim().to_string();
+    }
+    if let Some(model) = openai_model {
+        settings.openai.model = model.trim().to_string();
+    }
+    if let Some(key) = openai_api_key {
+        let key = key.trim();
+        settings.openai.api_key = (!key.is_empty()).then(|| key.to_string());
+    }
+    if let Some(send) = openai_send_screenshots {
+        settings.openai.send_screenshots = send;
+    }
+    descriptions::settings::save(&settings)?;
+    Ok(DescriptionSettingsView::from(&settings))
+}
+
+/// Recorder defaults used by the next `start_recording`.
+#[tauri::command]
+fn get_recorder_settings() -> settings::RecorderSettings {
+    settings::load()
+}
+
+/// Persist recorder defaults. Negative timings are clamped to 0.
+#[tauri::command]
+fn save_recorder_settings(
+    mut settings: settings::RecorderSettings,
+) -> Result<settings::RecorderSettings, CommandError> {
+    settings.debounce_ms = settings.debounce_ms.max(0);
+    settings.auth_dialog_cooldown_ms = settings.auth_dialog_cooldown_ms.max(0);
+    settings.auth_prompt_dedup_ms = settings.auth_prompt_dedup_ms.max(0);
+    settings::save(&settings)?;
+    Ok(settings)
+}
+
+/// Save the branding of HTML and PDF exports. The logo is copied into the app support
+/// folder; returns the branding as stored.
+#[tauri::command]
+fn set_export_branding(
+    branding: export::branding::ExportBranding,
+) -> Result<export::branding::ExportBranding, CommandError> {
+    let dir = export::branding::branding_dir()
+        .ok_or_else(|| CommandError::Failed("config dir not found".into()))?;
+    let branding = branding
+        .prepare(&dir)
+        .map_err(CommandError::InvalidArgument)?;
+    let mut settings = settings::load();
+    settings.export_branding = branding.clone();
+    settings::save(&settings)?;
+    Ok(branding)
+}
+
+/// Back to StepCast's own export styling; deletes the copied logo.
+#[tauri::command]
+fn reset_export_branding() -> Result<(), CommandError> {
+    let mut settings = settings::load();
+    settings.export_branding = export::branding::ExportBranding::default();
+    settings::save(&settings)?;
+    if let Some(dir) = export::branding::branding_dir() {
+        export::branding::remove_logo(&dir);
+    }
+    Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let _recorder = recorder::Recorder::new();
 
-    // Clean up leftover session directories from previous runs
+    // Register persisted sessions from previous runs and clean up the rest.
     // In dev, keep session dirs so we can audit recorder + AI behavior.
+    let (mut sessions, orphans) = SessionRegistry::restore(&recorder::session::sessions_root());
     if !cfg!(debug_assertions) {
-        Session::cleanup_all_sessions();
+        Session::cleanup_orphaned_sessions(&orphans);
+        let settings = settings::load();
+        sessions.prune(
+            std::time::SystemTime::now(),
+            Some(settings.session_max_age_days)
+                .filter(|&days| days > 0)
+                .map(|days| std::time::Duration::from_secs(u64::from(days) * 24 * 60 * 60)),
+            Some(settings.session_cache_mb)
+                .filter(|&mb| mb > 0)
+                .map(|mb| mb * 1024 * 1024),
+        );
     }
 
     // Tokio runtime required by tauri-plugin-aptabase
@@ -1447,6 +3382,15 @@ pub fn run() {
         .plugin(tauri_nspanel::init())
         .plugin(tauri_plugin_aptabase::Builder::new("A-EU-6084625392").build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .register_uri_scheme_protocol(session_protocol::SCHEME, |ctx, request| {
+            let state = ctx.app_handle().state::<RecorderAppState>();
+            let session_dir = state
+                .session
+                .lock()
+                .ok()
+                .and_then(|session| session.as_ref().map(|s| s.temp_dir.clone()));
+            session_protocol::respond(session_dir.as_deref(), request.uri().path())
+        })
         .setup(|app| {
             let startup = startup_state::load();
 
@@ -1477,11 +3421,10 @@ pub fn run() {
 
             // Register global shortcut Cmd+Shift+S to toggle panel
             {
-                use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
-                let shortcut = Shortcut::new(Some(Modifiers::META | Modifiers::SHIFT), Code::KeyS);
+                use tauri_plugin_global_shortcut::GlobalShortcutExt;
                 if let Err(err) =
                     app.global_shortcut()
-                        .on_shortcut(shortcut, |app, _shortcut, event| {
+                        .on_shortcut(panel_shortcut(), |app, _shortcut, event| {
                             if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
                                 tray::toggle_panel(app);
                             }
@@ -1491,6 +3434,24 @@ pub fn run() {
                 }
             }
 
+            // Register the hover capture shortcut (Cmd+Shift+D unless configured)
+            {
+                use tauri_plugin_global_shortcut::GlobalShortcutExt;
+                let hover_shortcut = settings::load().hover_shortcut;
+                if !hover_shortcut.trim().is_empty() {
+                    if let Err(err) = app.global_shortcut().on_shortcut(
+                        hover_shortcut.as_str(),
+                        |app, _shortcut, event| {
+                            if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                                request_hover_capture(app);
+                            }
+                        },
+                    ) {
+                        eprintln!("Failed to register hover shortcut {hover_shortcut}: {err}");
+                    }
+                }
+            }
+
             #[cfg(not(debug_assertions))]
             let _ = app.track_event("app_started", None);
 
@@ -1499,11 +3460,14 @@ pub fn run() {
         .manage(RecorderAppState {
             recorder_state: Mutex::new(RecorderState::new()),
             session: Mutex::new(None),
+            sessions: Mutex::new(sessions),
             click_listener: Mutex::new(None),
             pre_click_buffer: Mutex::new(None),
             processing_running: Arc::new(AtomicBool::new(false)),
             pipeline_state: Mutex::new(pipeline::PipelineState::new()),
-            ai_descriptions_running: Arc::new(AtomicBool::new(false)),
+            ai_queue: Mutex::new(descriptions::queue::DescriptionQueue::default()),
+            space_observer: Mutex::new(None),
+            interruption_observer: Mutex::new(None),
         })
         .invoke_handler(tauri::generate_handler![
             check_permissions,
@@ -1515,18 +3479,54 @@ pub fn run() {
             resume_recording,
             stop_recording,
             get_steps,
+            get_session_metadata,
+            set_session_metadata,
+            get_recording_diagnostics,
+            export_session_bundle,
+            get_session_diagnostics,
+            get_session_disk_usage,
+            list_sessions,
+            list_session_dirs,
+            load_session,
+            delete_session,
+            get_session_storage_info,
+            validate_session,
+            preview_capture,
+            set_recording_scope,
+            set_auth_dialog_timing,
             update_step_note,
+            update_step_title,
             update_step_description,
             update_step_crop,
+            set_step_wait,
+            set_step_annotations,
+            apply_crop_to_steps,
+            reset_step_crop_to_auto,
+            recapture_step,
+            copy_step_to_clipboard,
             delete_step,
+            delete_auto_steps,
+            deduplicate_steps,
+            dedupe_steps,
             reorder_steps,
+            undo_edit,
+            redo_edit,
             open_editor_window,
             export_guide,
+            preview_export,
             discard_recording,
             generate_step_descriptions,
+            retry_step_description,
+            get_ai_queue_status,
             get_startup_state,
             mark_startup_seen,
             dismiss_whats_new,
+            get_description_settings,
+            save_description_settings,
+            get_recorder_settings,
+            save_recorder_settings,
+            set_export_branding,
+            reset_export_branding,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -1541,7 +3541,11 @@ pub fn run() {
 
 #[cfg(test)]
 mod tests {
-    use super::PermissionStatus;
+    use super::{
+        apply_crop, apply_generated_item_to, fail_step_descriptions, PermissionStatus, Session,
+    };
+    use crate::apple_intelligence::{GenerateFailureItem, GenerateItem, GenerateResultItem};
+    use crate::recorder::types::{BoundsPercent, Step};
 
     #[test]
     fn permission_status_defaults_false() {
@@ -1549,4 +3553,93 @@ mod tests {
         assert!(!status.screen_recording);
         assert!(!status.accessibility);
     }
+
+    #[test]
+    fn generated_descriptions_for_deleted_steps_are_not_announced() {
+        let mut session = Session::new().expect("create session");
+        for id in ["step-1", "step-2", "step-3"] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            session.add_step(step);
+            session.begin_step_description(id);
+        }
+        let result = |id: &str| {
+            GenerateItem::Result(GenerateResultItem {
+                id: id.into(),
+                text: "Save the draft".into(),
+                debug: None,
+            })
+        };
+
+        // The user deletes steps while the provider is still answering.
+        assert!(session.delete_step("step-1"));
+        assert!(apply_generated_item_to(&mut session, result("step-1"), false).is_none());
+        let updated = apply_generated_item_to(&mut session, result("step-2"), false);
+        assert_eq!(updated.map(|s| s.id).as_deref(), Some("step-2"));
+        assert!(session.delete_step("step-3"));
+        let failure = GenerateItem::Failure(GenerateFailureItem {
+            id: "step-3".into(),
+            error: "Model refused.".into(),
+        });
+        assert!(apply_generated_item_to(&mut session, failure, false).is_none());
+
+        // The pass over steps without an answer skips deleted and answered ones.
+        let ids: Vec<String> = ["step-1", "step-2", "step-3"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        assert!(fail_step_descriptions(&mut session, &ids, "No model output.").is_empty());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn apply_crop_reports_each_step_and_skips_missing_screenshots() {
+        let mut session = Session::new().expect("create session");
+        for (id, screenshot) in [("step-1", true), ("step-2", false), ("step-3", true)] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            if !screenshot {
+                step.screenshot_path = None;
+            }
+            session.add_step(step);
+        }
+        let ids: Vec<String> = ["step-1", "step-2", "step-3", "missing"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let crop = BoundsPercent {
+            x_percent: 50.0,
+            y_percent: 0.0,
+            width_percent: 80.0,
+            height_percent: 100.0,
+        };
+
+        let (results, updated) = apply_crop(&mut session, &ids, Some(crop));
+
+        let outcomes: Vec<(&str, bool, Option<&str>)> = results
+            .iter()
+            .map(|r| (r.step_id.as_str(), r.updated, r.error.as_deref()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("step-1", true, None),
+                ("step-2", false, Some("step has no screenshot")),
+                ("step-3", true, None),
+                ("missing", false, Some("step not found")),
+            ]
+        );
+        assert_eq!(updated.len(), 2);
+        // Normalized once: clamped to the right half.
+        let applied = updated[0].crop_region.as_ref().expect("crop applied");
+        assert_eq!(applied.width_percent, 50.0);
+        assert!(session.steps[1].crop_region.is_none());
+
+        let (results, updated) = apply_crop(&mut session, &ids[..1], None);
+        assert!(results[0].updated);
+        assert!(updated[0].crop_region.is_none());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
 }
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if stepcast_lib::cli::is_export_invocation(&args) {
+        std::process::exit(stepcast_lib::cli::run_export(&args));
+    }
     stepcast_lib::run()
 }
@@ -1,4 +1,5 @@
-use tauri::{AppHandle, Manager, Position, Size};
+use crate::recorder::pipeline::PanelRect;
+use tauri::{AppHandle, Manager, PhysicalRect, Position, Size};
 use tauri_nspanel::{
     tauri_panel, CollectionBehavior, ManagerExt, PanelLevel, StyleMask, WebviewWindowExt,
 };
@@ -49,22 +50,114 @@ fn should_convert_existing_window(has_panel: bool, has_window: bool) -> bool {
     !has_panel && has_window
 }
 
-#[cfg(test)]
+/// A display's full frame and its work area (without menu bar and Dock), in physical
+/// pixels of the global desktop space. Displays left of or above the main one have
+/// negative origins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Display {
+    frame: PhysicalRect<i32, u32>,
+    work_area: PhysicalRect<i32, u32>,
+}
+
+impl Display {
+    fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        Self {
+            frame: PhysicalRect {
+                position: *monitor.position(),
+                size: *monitor.size(),
+            },
+            work_area: *monitor.work_area(),
+        }
+    }
+}
+
+/// Displays attached right now and the primary one. Queried on every use: displays come
+/// and go with lids and docks between two showings of the panel.
+fn active_displays(
+    window: &tauri::WebviewWindow,
+) -> Result<(Vec<Display>, Option<Display>), String> {
+    let displays = window
+        .available_monitors()
+        .map_err(|err| err.to_string())?
+        .iter()
+        .map(Display::from_monitor)
+        .collect();
+    let primary = window
+        .primary_monitor()
+        .map_err(|err| err.to_string())?
+        .map(|monitor| Display::from_monitor(&monitor));
+    Ok((displays, primary))
+}
+
+fn rect_contains(rect: &PhysicalRect<i32, u32>, x: i32, y: i32) -> bool {
+    x >= rect.position.x
+        && x < rect.position.x + rect.size.width as i32
+        && y >= rect.position.y
+        && y < rect.position.y + rect.size.height as i32
+}
+
 fn resolve_monitor_work_area(
-    current: Option<tauri::PhysicalRect<i32, u32>>,
-    primary: Option<tauri::PhysicalRect<i32, u32>>,
-    available: Vec<tauri::PhysicalRect<i32, u32>>,
-) -> Option<tauri::PhysicalRect<i32, u32>> {
+    current: Option<PhysicalRect<i32, u32>>,
+    primary: Option<PhysicalRect<i32, u32>>,
+    available: Vec<PhysicalRect<i32, u32>>,
+) -> Option<PhysicalRect<i32, u32>> {
     current.or(primary).or_else(|| available.into_iter().next())
 }
 
-#[cfg(test)]
+/// Origin for a panel of `panel_size` that would like to sit at `desired`, moved into the
+/// work area of the display showing `anchor` (the tray icon), else of the display under
+/// `desired`, else of the primary display. `None` without any display.
+fn fit_panel_origin(
+    desired: (i32, i32),
+    panel_size: (u32, u32),
+    anchor: Option<(i32, i32)>,
+    displays: &[Display],
+    primary: Option<Display>,
+) -> Option<(i32, i32)> {
+    let work_area_at = |(x, y): (i32, i32)| {
+        displays
+            .iter()
+            .find(|display| rect_contains(&display.frame, x, y))
+            .map(|display| display.work_area)
+    };
+    let current = anchor
+        .and_then(work_area_at)
+        .or_else(|| work_area_at(desired));
+    let work_area = resolve_monitor_work_area(
+        current,
+        primary.map(|display| display.work_area),
+        displays.iter().map(|display| display.work_area).collect(),
+    )?;
+    let (x, y) = clamp_panel_position(
+        f64::from(desired.0),
+        f64::from(desired.1),
+        f64::from(panel_size.0),
+        f64::from(panel_size.1),
+        work_area,
+    );
+    Some((x.round() as i32, y.round() as i32))
+}
+
+/// Whether all of `frame` lies on a single display.
+fn frame_on_screen(frame: &PanelRect, displays: &[Display]) -> bool {
+    frame.width > 0
+        && frame.height > 0
+        && displays.iter().any(|display| {
+            rect_contains(&display.frame, frame.x, frame.y)
+                && rect_contains(
+                    &display.frame,
+                    frame.x + frame.width - 1,
+                    frame.y + frame.height - 1,
+                )
+        })
+}
+
 fn clamp_panel_position(
     x: f64,
     y: f64,
     panel_width: f64,
     panel_height: f64,
-    monitor_rect: tauri::PhysicalRect<i32, u32>,
+    monitor_rect: PhysicalRect<i32, u32>,
 ) -> (f64, f64) {
     let monitor_x = monitor_rect.position.x as f64;
     let monitor_y = monitor_rect.position.y as f64;
@@ -188,6 +281,23 @@ pub fn panel_bounds(
     })
 }
 
+/// The panel's frame if it lies on one of the attached displays. Frames read while the
+/// panel is still moving, or left on a display that went away, are errors.
+pub fn on_screen_panel_bounds(app_handle: &AppHandle) -> Result<PanelRect, String> {
+    let window = app_handle
+        .get_webview_window(PANEL_LABEL)
+        .ok_or_else(|| "panel window missing".to_string())?;
+    let bounds = panel_bounds(app_handle)?;
+    let (displays, _) = active_displays(&window)?;
+    if !frame_on_screen(&bounds, &displays) {
+        return Err(format!(
+            "panel frame x={} y={} w={} h={} is off screen",
+            bounds.x, bounds.y, bounds.width, bounds.height
+        ));
+    }
+    Ok(bounds)
+}
+
 /// Fallback position when tray icon location is unavailable (e.g. Menu Bar Hider).
 /// Places the panel at the top-right of the primary monitor, just below the menu bar.
 pub fn fallback_panel_position(app_handle: &AppHandle) -> Result<(), String> {
@@ -212,6 +322,15 @@ pub fn fallback_panel_position(app_handle: &AppHandle) -> Result<(), String> {
     let panel_x =
         screen_pos.x + screen_size.width as i32 - window_size.width as i32 - padding_right;
     let panel_y = screen_pos.y + menu_bar_gap;
+    let primary = Display::from_monitor(&monitor);
+    let (panel_x, panel_y) = fit_panel_origin(
+        (panel_x, panel_y),
+        (window_size.width, window_size.height),
+        None,
+        &[primary],
+        Some(primary),
+    )
+    .unwrap_or((panel_x, panel_y));
 
     window
         .set_position(tauri::PhysicalPosition::new(panel_x, panel_y))
@@ -238,6 +357,18 @@ pub fn position_panel_at_tray_icon(
     let gap_phys = (gap_points * scale_factor).round() as i32;
     let panel_y_phys = metrics.y + metrics.height + gap_phys;
 
+    // Keep the whole panel on the tray icon's display, e.g. when the icon sits near the
+    // edge of a display next to another one.
+    let (displays, primary) = active_displays(&window)?;
+    let (panel_x_phys, panel_y_phys) = fit_panel_origin(
+        (panel_x_phys, panel_y_phys),
+        (window_size.width, window_size.height),
+        Some((icon_center_x_phys, metrics.y + metrics.height / 2)),
+        &displays,
+        primary,
+    )
+    .ok_or_else(|| "no display attached".to_string())?;
+
     let position = tauri::PhysicalPosition::new(panel_x_phys, panel_y_phys);
     window
         .set_position(position)
@@ -249,9 +380,11 @@ pub fn position_panel_at_tray_icon(
 #[cfg(test)]
 mod tests {
     use super::{
-        clamp_panel_position, icon_rect_physical, panel_collection_behavior, panel_label,
-        panel_level, panel_style_mask, resolve_monitor_work_area, should_convert_existing_window,
+        clamp_panel_position, fit_panel_origin, frame_on_screen, icon_rect_physical,
+        panel_collection_behavior, panel_label, panel_level, panel_style_mask,
+        resolve_monitor_work_area, should_convert_existing_window, Display,
     };
+    use crate::recorder::pipeline::PanelRect;
     use serde_json::Value;
     use tauri::{
         LogicalPosition, LogicalSize, PhysicalPosition, PhysicalRect, PhysicalSize, Position, Size,
@@ -269,6 +402,22 @@ mod tests {
         (rect.position.x, rect.position.y)
     }
 
+    /// A display at (`x`, `y`) with a 50 px menu bar and no Dock.
+    fn display(x: i32, y: i32, width: u32, height: u32) -> Display {
+        Display {
+            frame: rect_at(x, y, width, height),
+            work_area: rect_at(x, y + 50, width, height - 50),
+        }
+    }
+
+    /// Built-in 2880x1800 display, with a 3840x2160 display left of it and raised above
+    /// its top edge, as arranged in System Settings.
+    fn laptop_and_left_monitor() -> Vec<Display> {
+        vec![display(0, 0, 2880, 1800), display(-3840, -400, 3840, 2160)]
+    }
+
+    const PANEL: (u32, u32) = (680, 1280);
+
     #[test]
     fn panel_label_is_stable() {
         assert_eq!(panel_label(), "main");
@@ -385,6 +534,69 @@ mod tests {
         assert_eq!((x, y), (0.0, 70.0));
     }
 
+    #[test]
+    fn panel_stays_on_the_tray_display_with_a_negative_origin() {
+        let displays = laptop_and_left_monitor();
+        // Tray icon near the right edge of the left monitor: centering the panel under it
+        // would push it onto the laptop display.
+        let origin = fit_panel_origin(
+            (-300, -346),
+            PANEL,
+            Some((-40, -380)),
+            &displays,
+            Some(displays[0]),
+        );
+        assert_eq!(origin, Some((-680, -346)));
+    }
+
+    #[test]
+    fn panel_above_the_menu_bar_moves_into_the_work_area() {
+        let displays = laptop_and_left_monitor();
+        let origin = fit_panel_origin(
+            (-2000, -420),
+            PANEL,
+            Some((-1700, -390)),
+            &displays,
+            Some(displays[0]),
+        );
+        assert_eq!(origin, Some((-2000, -350)));
+    }
+
+    #[test]
+    fn panel_left_on_a_detached_display_moves_to_the_primary_display() {
+        // Lid closed: only the external display (now primary) is left, and the frame
+        // computed for the built-in display is out of reach.
+        let external = display(0, 0, 3840, 2160);
+        let origin = fit_panel_origin((-2600, 60), PANEL, None, &[external], Some(external));
+        assert_eq!(origin, Some((0, 60)));
+    }
+
+    #[test]
+    fn panel_without_tray_anchor_stays_on_the_display_under_it() {
+        let displays = laptop_and_left_monitor();
+        let origin = fit_panel_origin((-1000, 1500), PANEL, None, &displays, Some(displays[0]));
+        // The left monitor ends at y = 1760, so the 1280 px panel moves up to fit.
+        assert_eq!(origin, Some((-1000, 480)));
+        assert_eq!(fit_panel_origin((0, 0), PANEL, None, &[], None), None);
+    }
+
+    #[test]
+    fn frame_on_screen_needs_one_display_holding_the_whole_frame() {
+        let displays = laptop_and_left_monitor();
+        let frame = |x, y| PanelRect {
+            x,
+            y,
+            width: 680,
+            height: 1280,
+        };
+        assert!(frame_on_screen(&frame(2000, 60), &displays));
+        assert!(frame_on_screen(&frame(-680, -346), &displays));
+        // Straddling both displays, or left where no display is.
+        assert!(!frame_on_screen(&frame(-300, 60), &displays));
+        assert!(!frame_on_screen(&frame(-5000, 60), &displays));
+        assert!(!frame_on_screen(&frame(3000, 60), &displays));
+    }
+
     #[test]
     fn clamp_panel_position_handles_panel_larger_than_monitor() {
         let monitor = rect_at(10, 20, 100, 80);
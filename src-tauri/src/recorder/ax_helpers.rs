@@ -81,6 +81,8 @@ pub(super) struct AxElementLabel {
     pub container_role: Option<String>,
     pub container_subrole: Option<String>,
     pub container_identifier: Option<String>,
+    /// Screen bounds of that container, used to snap the focus crop to it.
+    pub container_bounds: Option<WindowBounds>,
     pub window_role: Option<String>,
     pub window_subrole: Option<String>,
     pub window_bounds: Option<WindowBounds>,
@@ -91,8 +93,17 @@ pub(super) struct AxElementLabel {
     pub parent_dialog_subrole: Option<String>,
     pub parent_dialog_bounds: Option<WindowBounds>,
     pub is_checked: Option<bool>,
+    /// Text in a form field at click time; never set for secure fields.
+    pub value: Option<String>,
+    pub placeholder: Option<String>,
     pub is_cancel_button: bool,
     pub is_default_button: bool,
+    /// Menu breadcrumb for menu-item clicks, e.g. "File ▸ Export ▸ PDF".
+    pub menu_path: Option<String>,
+    /// `AXDescription`: the accessibility label screen readers announce.
+    pub accessibility_label: Option<String>,
+    /// `AXHelp`: the element's help tag.
+    pub help: Option<String>,
 }
 
 fn ax_copy_string_attr(
@@ -472,6 +483,64 @@ fn ax_copy_placeholder_value(element: accessibility_sys::AXUIElementRef) -> Opti
     ax_copy_string_attr(element, "AXPlaceholderValue")
 }
 
+fn is_text_field_role(role: &str, subrole: &str) -> bool {
+    let r = role.to_lowercase();
+    let s = subrole.to_lowercase();
+    r.contains("textfield")
+        || r.contains("text field")
+        || r.contains("textarea")
+        || s.contains("searchfield")
+}
+
+/// Password-style fields: their value must never be read, logged or sent anywhere.
+pub(super) fn is_secure_text_field(
+    role: &str,
+    subrole: Option<&str>,
+    role_description: Option<&str>,
+) -> bool {
+    let secure = |s: &str| {
+        let s = s.to_lowercase();
+        s.contains("secure") || s.contains("password")
+    };
+    secure(role) || subrole.is_some_and(secure) || role_description.is_some_and(secure)
+}
+
+/// Longest field value or placeholder kept on a step: enough for an email address or a
+/// search query, not a pasted document.
+const MAX_FIELD_TEXT_CHARS: usize = 120;
+
+/// Trimmed field text, cut at [`MAX_FIELD_TEXT_CHARS`]. `None` when blank.
+fn field_text(raw: &str) -> Option<String> {
+    let text = raw.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(match text.char_indices().nth(MAX_FIELD_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    })
+}
+
+/// Current value and placeholder of a text field or combo box, for descriptions like
+/// "Enter your email in the Email field". Secure fields only report their placeholder.
+fn ax_copy_form_field_text(
+    element: accessibility_sys::AXUIElementRef,
+    role: &str,
+    subrole: Option<&str>,
+    role_description: Option<&str>,
+) -> (Option<String>, Option<String>) {
+    if !is_text_field_role(role, subrole.unwrap_or_default()) && role != "AXComboBox" {
+        return (None, None);
+    }
+    let value = if is_secure_text_field(role, subrole, role_description) {
+        None
+    } else {
+        ax_copy_string_attr(element, "AXValue").and_then(|v| field_text(&v))
+    };
+    let placeholder = ax_copy_placeholder_value(element).and_then(|p| field_text(&p));
+    (value, placeholder)
+}
+
 fn ax_copy_element_attr(
     element: accessibility_sys::AXUIElementRef,
     attr_name: &str,
@@ -610,6 +679,52 @@ fn ax_find_dialog_parent(
     (None, None, None)
 }
 
+const MENU_BREADCRUMB_SEPARATOR: &str = " \u{25B8} ";
+
+/// Join the titles collected while walking a menu item's parent chain (clicked item
+/// first) into "File ▸ Export ▸ PDF". Blank titles and repeats of the previous title are
+/// dropped; a single remaining title is no path and yields `None`.
+fn join_menu_breadcrumb(chain: &[String]) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::with_capacity(chain.len());
+    for title in chain.iter().rev().map(|t| t.trim()) {
+        if !title.is_empty() && parts.last() != Some(&title) {
+            parts.push(title);
+        }
+    }
+    (parts.len() >= 2).then(|| parts.join(MENU_BREADCRUMB_SEPARATOR))
+}
+
+/// Breadcrumb of the menu item `element` (titled `label`): the titles of the menu items
+/// and the menu bar item it sits under. `None` for anything that is not a menu item.
+fn ax_menu_path(element: accessibility_sys::AXUIElementRef, label: &str) -> Option<String> {
+    use accessibility_sys::{kAXParentAttribute, kAXRoleAttribute, kAXTitleAttribute};
+
+    if ax_copy_string_attr(element, kAXRoleAttribute).as_deref() != Some("AXMenuItem") {
+        return None;
+    }
+    let mut chain = vec![label.to_string()];
+    let mut current_raw = element;
+    let mut current_guard: Option<CfRef> = None;
+
+    for _ in 0..12 {
+        let Some(parent) = ax_copy_element_attr(current_raw, kAXParentAttribute) else {
+            break;
+        };
+        current_raw = parent.as_type();
+        current_guard = Some(parent);
+        match ax_copy_string_attr(current_raw, kAXRoleAttribute).as_deref() {
+            Some("AXMenuItem") | Some("AXMenuBarItem") => {
+                chain.extend(ax_copy_string_attr(current_raw, kAXTitleAttribute));
+            }
+            Some("AXMenu") => {}
+            _ => break,
+        }
+    }
+    drop(current_guard);
+
+    join_menu_breadcrumb(&chain)
+}
+
 fn is_container_role(role: &str) -> bool {
     matches!(
         role,
@@ -627,7 +742,12 @@ fn is_container_role(role: &str) -> bool {
 
 fn ax_find_container_parent(
     element: accessibility_sys::AXUIElementRef,
-) -> (Option<String>, Option<String>, Option<String>) {
+) -> (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<WindowBounds>,
+) {
     use accessibility_sys::{kAXParentAttribute, kAXRoleAttribute, kAXSubroleAttribute};
 
     let mut current_raw = element;
@@ -654,7 +774,13 @@ fn ax_find_container_parent(
         score
     }
 
-    let mut best: (i32, String, Option<String>, Option<String>) = (0, String::new(), None, None);
+    let mut best: (
+        i32,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<WindowBounds>,
+    ) = (0, String::new(), None, None, None);
 
     for _ in 0..12 {
         let Some(parent) = ax_copy_element_attr(current_raw, kAXParentAttribute) else {
@@ -671,13 +797,14 @@ fn ax_find_container_parent(
                 let score = container_score(r, subrole.as_deref(), ident.as_deref());
 
                 if score > best.0 {
-                    best = (score, r.clone(), subrole.clone(), ident.clone());
+                    let bounds = ax_copy_window_bounds(current_raw);
+                    best = (score, r.clone(), subrole.clone(), ident.clone(), bounds);
                 }
 
                 // If we find a strong semantic signal (source list), stop early.
                 if score >= 180 {
                     drop(current_guard);
-                    return (Some(best.1), best.2, best.3);
+                    return (Some(best.1), best.2, best.3, best.4);
                 }
             }
         }
@@ -685,9 +812,9 @@ fn ax_find_container_parent(
     drop(current_guard);
 
     if best.0 > 0 {
-        (Some(best.1), best.2, best.3)
+        (Some(best.1), best.2, best.3, best.4)
     } else {
-        (None, None, None)
+        (None, None, None, None)
     }
 }
 
@@ -948,15 +1075,6 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
         }
         // Collect a short parent chain and pick the "best" interactive element.
         // This avoids vague labels like the app name when the hit-test returns a child/group.
-        fn is_text_field_role(role: &str, subrole: &str) -> bool {
-            let r = role.to_lowercase();
-            let s = subrole.to_lowercase();
-            r.contains("textfield")
-                || r.contains("text field")
-                || r.contains("textarea")
-                || s.contains("searchfield")
-        }
-
         let root = CfRef::wrap(raw_element as *mut _)?;
         let mut chain: Vec<CfRef> = Vec::with_capacity(8);
         chain.push(root);
@@ -1063,6 +1181,8 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
         let subrole = ax_copy_string_attr(el, kAXSubroleAttribute);
         let role_description = ax_copy_string_attr(el, "AXRoleDescription");
         let identifier = ax_copy_string_attr(el, "AXIdentifier");
+        let accessibility_label = ax_copy_string_attr(el, "AXDescription");
+        let help = ax_copy_string_attr(el, "AXHelp");
         let label = {
             let mut l = best_label;
             let best_role_is_listish = role.as_ref().map(|r| is_listish_role(r)).unwrap_or(false);
@@ -1106,7 +1226,7 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
         };
         let element_bounds = ax_copy_window_bounds(el);
 
-        let (container_role, container_subrole, container_identifier) =
+        let (container_role, container_subrole, container_identifier, container_bounds) =
             ax_find_container_parent(el);
 
         let (window_role, window_subrole, window_bounds, is_cancel_button, is_default_button) =
@@ -1145,6 +1265,10 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
         let (parent_dialog_role, parent_dialog_subrole, parent_dialog_bounds) =
             ax_find_dialog_parent(el);
         let is_checked = ax_copy_bool_attr(el, "AXValue");
+        let (value, placeholder) = role.as_deref().map_or((None, None), |r| {
+            ax_copy_form_field_text(el, r, subrole.as_deref(), role_description.as_deref())
+        });
+        let menu_path = ax_menu_path(el, &label);
 
         // Return best-effort metadata even when the label is missing.
         role.map(|role| AxElementLabel {
@@ -1157,6 +1281,7 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
             container_role,
             container_subrole,
             container_identifier,
+            container_bounds,
             window_role,
             window_subrole,
             window_bounds,
@@ -1167,8 +1292,13 @@ pub(super) fn get_clicked_element_label(x: f32, y: f32) -> Option<AxElementLabel
             parent_dialog_subrole,
             parent_dialog_bounds,
             is_checked,
+            value,
+            placeholder,
             is_cancel_button: is_cancel_button || top_level_cancel,
             is_default_button: is_default_button || top_level_default,
+            menu_path,
+            accessibility_label,
+            help,
         })
     }
 }
@@ -1231,6 +1361,147 @@ pub(super) fn is_system_ui_process(process_name: &str) -> bool {
         || name.contains("notificationcenter")
 }
 
+/// Bounds of the context menu open in the app with `pid` (the focused application when
+/// `None`), read from its AX tree, nearest to the click.
+///
+/// Open context menus are `AXMenu` children of the application element; menus of the
+/// `AXMenuBar` hang below it instead, so they are not picked up here.
+pub(super) fn get_open_context_menu_bounds(
+    pid: Option<i32>,
+    click_x: i32,
+    click_y: i32,
+) -> Option<WindowBounds> {
+    use accessibility_sys::{
+        kAXFocusedApplicationAttribute, kAXMenuRole, kAXRoleAttribute,
+        AXUIElementCreateApplication, AXUIElementCreateSystemWide,
+    };
+
+    let app = match pid {
+        Some(pid) => unsafe { CfRef::wrap(AXUIElementCreateApplication(pid) as *mut _)? },
+        None => {
+            let system_wide = unsafe { CfRef::wrap(AXUIElementCreateSystemWide() as *mut _)? };
+            ax_copy_element_attr(system_wide.as_type(), kAXFocusedApplicationAttribute)?
+        }
+    };
+
+    ax_copy_children(app.as_type())
+        .iter()
+        .filter(|child| {
+            ax_copy_string_attr(child.as_type(), kAXRoleAttribute).as_deref() == Some(kAXMenuRole)
+        })
+        .filter_map(|menu| ax_copy_window_bounds(menu.as_type()))
+        .min_by_key(|b| {
+            let dx = i64::from(b.x - click_x);
+            let dy = i64::from(b.y - click_y);
+            dx * dx + dy * dy
+        })
+}
+
+/// URL attribute (`AXURL`, `AXDocument`) as a string; apps return either a `CFURL` or
+/// a `CFString`.
+fn ax_copy_url_attr(element: accessibility_sys::AXUIElementRef, attr_name: &str) -> Option<String> {
+    use core_foundation::base::{CFGetTypeID, TCFType};
+    use core_foundation::string::CFString;
+    use core_foundation::url::CFURL;
+
+    let value = ax_copy_value_attr(element, attr_name)?;
+    unsafe {
+        let type_id = CFGetTypeID(value.as_ptr() as _);
+        if type_id == CFURL::type_id() {
+            let url = CFURL::wrap_under_get_rule(value.as_ptr() as _);
+            return Some(url.get_string().to_string());
+        }
+        if type_id == CFString::type_id() {
+            return Some(CFString::wrap_under_get_rule(value.as_ptr() as _).to_string());
+        }
+    }
+    None
+}
+
+/// URL of the page shown in the browser with `pid`: its focused window's `AXDocument`,
+/// else the `AXURL` of the outermost web area under (`x`, `y`). Gives up once `budget`
+/// is spent, so a slow browser cannot hold up the capture.
+pub(super) fn get_browser_page_url(
+    pid: i32,
+    x: f32,
+    y: f32,
+    budget: std::time::Duration,
+) -> Option<String> {
+    use super::browser::page_url;
+    use accessibility_sys::{
+        kAXFocusedWindowAttribute, kAXParentAttribute, kAXRoleAttribute, kAXWindowRole,
+        AXUIElementCopyElementAtPosition, AXUIElementCreateApplication,
+        AXUIElementSetMessagingTimeout,
+    };
+
+    let deadline = std::time::Instant::now() + budget;
+    unsafe {
+        let app = CfRef::wrap(AXUIElementCreateApplication(pid) as *mut _)?;
+        AXUIElementSetMessagingTimeout(app.as_type(), budget.as_secs_f32());
+
+        if let Some(url) = ax_copy_element_attr(app.as_type(), kAXFocusedWindowAttribute)
+            .and_then(|window| ax_copy_url_attr(window.as_type(), "AXDocument"))
+            .and_then(|raw| page_url(&raw))
+        {
+            return Some(url);
+        }
+
+        let mut raw_element: accessibility_sys::AXUIElementRef = std::ptr::null_mut();
+        if AXUIElementCopyElementAtPosition(app.as_type(), x, y, &mut raw_element) != 0 {
+            return None;
+        }
+        let mut current = CfRef::wrap(raw_element as *mut _)?;
+        // Frames are web areas of their own; the outermost one is the page.
+        let mut url = None;
+        for _ in 0..64 {
+            if std::time::Instant::now() >= deadline {
+                break;
+            }
+            match ax_copy_string_attr(current.as_type(), kAXRoleAttribute).as_deref() {
+                Some("AXWebArea") => {
+                    url = ax_copy_url_attr(current.as_type(), "AXURL")
+                        .and_then(|raw| page_url(&raw))
+                        .or(url);
+                }
+                Some(role) if role == kAXWindowRole => break,
+                _ => {}
+            }
+            let Some(parent) = ax_copy_element_attr(current.as_type(), kAXParentAttribute) else {
+                break;
+            };
+            current = parent;
+        }
+        url
+    }
+}
+
+/// Checked state of the `role` element under (`x`, `y`), read back after a click
+/// toggled it. `None` when no such element is there any more, e.g. the click closed
+/// its sheet or swapped the view.
+pub(super) fn get_toggle_state_at(x: f32, y: f32, role: &str) -> Option<bool> {
+    use accessibility_sys::{
+        kAXParentAttribute, kAXRoleAttribute, AXUIElementCopyElementAtPosition,
+        AXUIElementCreateSystemWide,
+    };
+
+    unsafe {
+        let system_wide = CfRef::wrap(AXUIElementCreateSystemWide() as *mut _)?;
+        let mut raw_element: accessibility_sys::AXUIElementRef = std::ptr::null_mut();
+        if AXUIElementCopyElementAtPosition(system_wide.as_type(), x, y, &mut raw_element) != 0 {
+            return None;
+        }
+        // The hit test may land on the checkbox's title text; the toggle is a parent.
+        let mut current = CfRef::wrap(raw_element as *mut _)?;
+        for _ in 0..4 {
+            if ax_copy_string_attr(current.as_type(), kAXRoleAttribute).as_deref() == Some(role) {
+                return ax_copy_bool_attr(current.as_type(), "AXValue");
+            }
+            current = ax_copy_element_attr(current.as_type(), kAXParentAttribute)?;
+        }
+        None
+    }
+}
+
 /// Get the PID and app name of the element at click position
 pub(super) fn get_clicked_element_info(x: i32, y: i32) -> Option<(i32, String)> {
     let pid = get_pid_at_position(x as f32, y as f32)?;
@@ -1279,6 +1550,74 @@ mod tests {
         assert_eq!(name, "Xcode");
     }
 
+    // --- menu breadcrumbs ---
+
+    fn chain(titles: &[&str]) -> Vec<String> {
+        titles.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn menu_breadcrumb_joins_the_chain_from_the_menu_bar_down() {
+        assert_eq!(
+            join_menu_breadcrumb(&chain(&["PDF", "Export", "File"])).as_deref(),
+            Some("File \u{25B8} Export \u{25B8} PDF")
+        );
+    }
+
+    #[test]
+    fn menu_breadcrumb_skips_blank_and_repeated_titles() {
+        assert_eq!(
+            join_menu_breadcrumb(&chain(&[" Save As… ", "", "File", "File"])).as_deref(),
+            Some("File \u{25B8} Save As…")
+        );
+    }
+
+    #[test]
+    fn menu_breadcrumb_needs_at_least_two_levels() {
+        assert_eq!(join_menu_breadcrumb(&chain(&["Copy"])), None);
+        assert_eq!(join_menu_breadcrumb(&chain(&["Copy", "  "])), None);
+        assert_eq!(join_menu_breadcrumb(&[]), None);
+    }
+
+    // --- form fields ---
+
+    #[test]
+    fn secure_fields_are_detected_by_subrole_or_description() {
+        assert!(is_secure_text_field(
+            "AXTextField",
+            Some("AXSecureTextField"),
+            None
+        ));
+        assert!(is_secure_text_field(
+            "AXTextField",
+            None,
+            Some("Secure Text Field")
+        ));
+        assert!(is_secure_text_field(
+            "AXTextField",
+            None,
+            Some("password field")
+        ));
+        assert!(!is_secure_text_field(
+            "AXTextField",
+            Some("AXSearchField"),
+            Some("search text field")
+        ));
+        assert!(!is_secure_text_field("AXComboBox", None, None));
+    }
+
+    #[test]
+    fn field_text_is_trimmed_and_capped() {
+        assert_eq!(
+            field_text("  you@example.com \n"),
+            Some("you@example.com".into())
+        );
+        assert_eq!(field_text("   "), None);
+        let long = field_text(&"ä".repeat(500)).unwrap();
+        assert_eq!(long.chars().count(), MAX_FIELD_TEXT_CHARS + 1);
+        assert!(long.ends_with('…'));
+    }
+
     // --- is_security_agent_process ---
 
     #[test]
@@ -0,0 +1,170 @@
+//! Session bundles for bug reports: the whole session cache folder (screenshots,
+//! `recording.log`, `diagnostics.json`, AI traces) zipped into one archive.
+
+use super::storage::StorageError;
+use image::{DynamicImage, ImageFormat};
+use std::fs;
+use std::io::{self, BufWriter, Cursor, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Zip everything under `session_dir` into `output_path`, keeping relative paths.
+///
+/// With `redact_screenshots` every image is replaced by a blank one of the same size
+/// and format, so crop and click coordinates in the logs still line up. Returns the
+/// number of files written.
+pub fn write_session_bundle(
+    session_dir: &Path,
+    output_path: &Path,
+    redact_screenshots: bool,
+) -> Result<usize, StorageError> {
+    let file = fs::File::create(output_path)?;
+    let result = write_entries(session_dir, file, redact_screenshots);
+    if result.is_err() {
+        let _ = fs::remove_file(output_path);
+    }
+    result
+}
+
+fn write_entries(
+    session_dir: &Path,
+    file: fs::File,
+    redact_screenshots: bool,
+) -> Result<usize, StorageError> {
+    let mut files = Vec::new();
+    collect_files(session_dir, &mut files)?;
+    files.sort();
+
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let text_opts =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // Screenshots are already compressed.
+    let image_opts =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    for path in &files {
+        let Ok(relative) = path.strip_prefix(session_dir) else {
+            continue;
+        };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let format = ImageFormat::from_path(path).ok();
+        let bytes = match format {
+            Some(format) if redact_screenshots => redacted_image(path, format)?,
+            _ => fs::read(path)?,
+        };
+        let opts = if format.is_some() {
+            image_opts
+        } else {
+            text_opts
+        };
+        zip.start_file(name, opts).map_err(io::Error::other)?;
+        zip.write_all(&bytes)?;
+    }
+
+    zip.finish().map_err(io::Error::other)?.flush()?;
+    Ok(files.len())
+}
+
+/// Files below `dir`, depth first. The bundle itself may live elsewhere, so nothing
+/// is skipped.
+fn collect_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// A mid-grey image with the dimensions of the one at `path`. Unreadable images
+/// become a 1×1 placeholder.
+fn redacted_image(path: &Path, format: ImageFormat) -> Result<Vec<u8>, StorageError> {
+    let (width, height) = image::image_dimensions(path).unwrap_or((1, 1));
+    let blank = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+        width,
+        height,
+        image::Rgb([128, 128, 128]),
+    ));
+    let mut out = Cursor::new(Vec::new());
+    if blank.write_to(&mut out, format).is_err() {
+        // Formats without an encoder get PNG bytes under the original name.
+        out = Cursor::new(Vec::new());
+        blank.write_to(&mut out, ImageFormat::Png)?;
+    }
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn session_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        fs::write(dir.path().join("recording.log"), "step-001 ok\n").expect("write log");
+        fs::write(
+            dir.path().join("diagnostics.json"),
+            r#"{"clicks_received":1}"#,
+        )
+        .expect("write diagnostics");
+        fs::write(dir.path().join("ai-trace-1-request.json"), "{}").expect("write trace");
+        image::RgbImage::from_pixel(4, 3, image::Rgb([255, 0, 0]))
+            .save(dir.path().join("step-001.png"))
+            .expect("write screenshot");
+        dir
+    }
+
+    fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        archive
+            .by_name(name)
+            .unwrap_or_else(|_| panic!("missing {name}"))
+            .read_to_end(&mut bytes)
+            .expect("read entry");
+        bytes
+    }
+
+    #[test]
+    fn bundle_contains_log_and_diagnostics() {
+        let dir = session_dir();
+        let out = tempfile::tempdir().expect("create output dir");
+        let zip_path = out.path().join("bundle.zip");
+
+        let count = write_session_bundle(dir.path(), &zip_path, false).expect("bundle");
+        assert_eq!(count, 4);
+
+        let mut archive = ZipArchive::new(fs::File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(read_entry(&mut archive, "recording.log"), b"step-001 ok\n");
+        assert_eq!(
+            read_entry(&mut archive, "diagnostics.json"),
+            br#"{"clicks_received":1}"#
+        );
+        assert!(archive.by_name("ai-trace-1-request.json").is_ok());
+        let original = fs::read(dir.path().join("step-001.png")).unwrap();
+        assert_eq!(read_entry(&mut archive, "step-001.png"), original);
+    }
+
+    #[test]
+    fn redaction_blanks_screenshots_but_keeps_size() {
+        let dir = session_dir();
+        let out = tempfile::tempdir().expect("create output dir");
+        let zip_path = out.path().join("bundle.zip");
+
+        write_session_bundle(dir.path(), &zip_path, true).expect("bundle");
+
+        let mut archive = ZipArchive::new(fs::File::open(&zip_path).unwrap()).unwrap();
+        let png = read_entry(&mut archive, "step-001.png");
+        let img = image::load_from_memory(&png)
+            .expect("decode placeholder")
+            .to_rgb8();
+        assert_eq!(img.dimensions(), (4, 3));
+        assert!(img.pixels().all(|p| p.0 == [128, 128, 128]));
+        assert_eq!(read_entry(&mut archive, "recording.log"), b"step-001 ok\n");
+    }
+}
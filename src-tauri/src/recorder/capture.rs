@@ -1,5 +1,16 @@
 use std::{fmt, io};
 
+/// Settings shared by the region capture paths.
+///
+/// Neither path shows the cursor by default: `screencapture` only draws it with
+/// `-C`, and CoreGraphics window-list images never contain it, so
+/// `include_cursor` composites an arrow glyph at the pointer's position there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CaptureOptions {
+    /// Show the mouse pointer in screenshots.
+    pub include_cursor: bool,
+}
+
 #[derive(Debug)]
 pub enum CaptureError {
     Io(io::Error),
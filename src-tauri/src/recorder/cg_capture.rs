@@ -1,6 +1,23 @@
-use crate::recorder::capture::CaptureError;
+use crate::recorder::capture::{CaptureError, CaptureOptions};
+use crate::recorder::window_info::WindowBounds;
+use image::RgbaImage;
 use std::path::Path;
 
+/// Arrow cursor outline in points with its tip at the origin, shaped like the
+/// system arrow.
+const CURSOR_ARROW: [(f64, f64); 7] = [
+    (0.0, 0.0),
+    (0.0, 17.0),
+    (4.0, 13.0),
+    (7.0, 19.5),
+    (9.5, 18.5),
+    (6.5, 12.0),
+    (12.0, 12.0),
+];
+
+/// White border of the cursor glyph, in points.
+const CURSOR_OUTLINE_PT: f64 = 1.0;
+
 /// Capture a screen region using CoreGraphics (fast, in-process).
 /// Falls back to CLI capture if the image cannot be converted.
 pub fn capture_region_fast(
@@ -9,13 +26,33 @@ pub fn capture_region_fast(
     width: i32,
     height: i32,
     output_path: &Path,
+    options: CaptureOptions,
 ) -> Result<(), CaptureError> {
+    let mut img = capture_rect_rgba(x, y, width, height)?;
+    if options.include_cursor {
+        if let Some((cursor_x, cursor_y)) = cursor_location() {
+            let scale = img.width() as f64 / width as f64;
+            draw_cursor(
+                &mut img,
+                (cursor_x - x as f64) * scale,
+                (cursor_y - y as f64) * scale,
+                scale,
+            );
+        }
+    }
+    img.save(output_path)
+        .map_err(|e| CaptureError::CgImage(format!("fast capture save failed: {e}")))?;
+
+    Ok(())
+}
+
+/// Screen contents of a rect in global points, at the display's pixel resolution.
+fn capture_rect_rgba(x: i32, y: i32, width: i32, height: i32) -> Result<RgbaImage, CaptureError> {
     use core_graphics::geometry::{CGPoint, CGRect, CGSize};
     use core_graphics::window::{
         create_image, kCGNullWindowID, kCGWindowImageBestResolution,
         kCGWindowListExcludeDesktopElements, kCGWindowListOptionOnScreenOnly,
     };
-    use image::RgbaImage;
 
     if width <= 0 || height <= 0 {
         return Err(CaptureError::CgImage("invalid capture size".to_string()));
@@ -79,12 +116,185 @@ pub fn capture_region_fast(
         }
     }
 
-    let img = RgbaImage::from_raw(w as u32, h as u32, out)
-        .ok_or_else(|| CaptureError::CgImage("failed to build image buffer".to_string()))?;
-    img.save(output_path)
-        .map_err(|e| CaptureError::CgImage(format!("fast capture save failed: {e}")))?;
+    RgbaImage::from_raw(w as u32, h as u32, out)
+        .ok_or_else(|| CaptureError::CgImage("failed to build image buffer".to_string()))
+}
 
-    Ok(())
+/// Where each display sits in a stitched desktop image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopLayout {
+    /// Union of all display frames in global points.
+    pub bounds: WindowBounds,
+    /// Top-left of each display relative to the union's top-left, in points.
+    pub offsets: Vec<(u32, u32)>,
+}
+
+/// Lay out display frames in one image. Displays left of or above the main display
+/// have negative origins; the union is shifted so its top-left becomes (0, 0).
+pub fn desktop_layout(frames: &[WindowBounds]) -> Option<DesktopLayout> {
+    let left = frames.iter().map(|f| i64::from(f.x)).min()?;
+    let top = frames.iter().map(|f| i64::from(f.y)).min()?;
+    let right = frames
+        .iter()
+        .map(|f| i64::from(f.x) + i64::from(f.width))
+        .max()?;
+    let bottom = frames
+        .iter()
+        .map(|f| i64::from(f.y) + i64::from(f.height))
+        .max()?;
+    let offsets = frames
+        .iter()
+        .map(|f| {
+            (
+                (i64::from(f.x) - left) as u32,
+                (i64::from(f.y) - top) as u32,
+            )
+        })
+        .collect();
+    Some(DesktopLayout {
+        bounds: WindowBounds {
+            x: left as i32,
+            y: top as i32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        },
+        offsets,
+    })
+}
+
+/// Capture every active display and stitch them into one image at their real
+/// relative positions; gaps between displays stay black. The image uses the lowest
+/// pixel scale among the displays, so a Retina display next to a 1x one is scaled down.
+/// Returns the union of the display frames in global points.
+pub fn capture_all_displays(output_path: &Path) -> Result<WindowBounds, CaptureError> {
+    use core_graphics::display::CGDisplay;
+    use image::imageops::{self, FilterType};
+
+    let displays: Vec<CGDisplay> = CGDisplay::active_displays()
+        .map_err(|e| CaptureError::CgImage(format!("listing displays failed: {e}")))?
+        .into_iter()
+        .map(CGDisplay::new)
+        .collect();
+    let frames: Vec<WindowBounds> = displays
+        .iter()
+        .map(|display| {
+            let b = display.bounds();
+            WindowBounds {
+                x: b.origin.x as i32,
+                y: b.origin.y as i32,
+                width: b.size.width as u32,
+                height: b.size.height as u32,
+            }
+        })
+        .collect();
+    let layout = desktop_layout(&frames)
+        .ok_or_else(|| CaptureError::CgImage("no active displays".to_string()))?;
+
+    let shots = frames
+        .iter()
+        .map(|f| capture_rect_rgba(f.x, f.y, f.width as i32, f.height as i32))
+        .collect::<Result<Vec<_>, _>>()?;
+    let scale = frames
+        .iter()
+        .zip(&shots)
+        .map(|(frame, shot)| shot.width() as f64 / f64::from(frame.width.max(1)))
+        .fold(f64::INFINITY, f64::min);
+    let scaled = |points: u32| (f64::from(points) * scale).round() as u32;
+
+    let mut canvas = RgbaImage::from_pixel(
+        scaled(layout.bounds.width),
+        scaled(layout.bounds.height),
+        image::Rgba([0, 0, 0, 255]),
+    );
+    for ((frame, shot), &(dx, dy)) in frames.iter().zip(shots).zip(&layout.offsets) {
+        let (w, h) = (scaled(frame.width), scaled(frame.height));
+        let shot = if shot.dimensions() == (w, h) {
+            shot
+        } else {
+            imageops::resize(&shot, w, h, FilterType::Triangle)
+        };
+        imageops::replace(
+            &mut canvas,
+            &shot,
+            i64::from(scaled(dx)),
+            i64::from(scaled(dy)),
+        );
+    }
+    canvas
+        .save(output_path)
+        .map_err(|e| CaptureError::CgImage(format!("desktop capture save failed: {e}")))?;
+    Ok(layout.bounds)
+}
+
+/// Current mouse position in global screen points.
+pub fn cursor_location() -> Option<(f64, f64)> {
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState).ok()?;
+    let location = CGEvent::new(source).ok()?.location();
+    Some((location.x, location.y))
+}
+
+/// Paint an arrow cursor with its tip at (`tip_x`, `tip_y`) image pixels, `scale`
+/// pixels per point: black with a white border, like the system arrow. Parts
+/// outside the image are clipped.
+fn draw_cursor(img: &mut RgbaImage, tip_x: f64, tip_y: f64, scale: f64) {
+    let (max_x, max_y) = CURSOR_ARROW
+        .iter()
+        .fold((0.0_f64, 0.0_f64), |(mx, my), &(px, py)| {
+            (mx.max(px), my.max(py))
+        });
+    let x_range = (tip_x.floor().max(0.0) as u32)
+        ..((tip_x + max_x * scale).ceil().max(0.0) as u32).min(img.width());
+    let y_range = (tip_y.floor().max(0.0) as u32)
+        ..((tip_y + max_y * scale).ceil().max(0.0) as u32).min(img.height());
+
+    for py in y_range {
+        for px in x_range.clone() {
+            // Pixel centre in cursor points.
+            let point = (
+                (px as f64 + 0.5 - tip_x) / scale,
+                (py as f64 + 0.5 - tip_y) / scale,
+            );
+            if !inside_polygon(point, &CURSOR_ARROW) {
+                continue;
+            }
+            let on_border = polygon_edges(&CURSOR_ARROW)
+                .any(|(a, b)| distance_to_segment(point, a, b) < CURSOR_OUTLINE_PT);
+            let shade = if on_border { 255 } else { 0 };
+            img.put_pixel(px, py, image::Rgba([shade, shade, shade, 255]));
+        }
+    }
+}
+
+fn polygon_edges(polygon: &[(f64, f64)]) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Even-odd point-in-polygon test.
+fn inside_polygon((x, y): (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    polygon_edges(polygon)
+        .filter(|&((ax, ay), (bx, by))| {
+            (ay > y) != (by > y) && x < ax + (y - ay) / (by - ay) * (bx - ax)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+fn distance_to_segment((x, y): (f64, f64), (ax, ay): (f64, f64), (bx, by): (f64, f64)) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq == 0.0 {
+        0.0
+    } else {
+        (((x - ax) * dx + (y - ay) * dy) / len_sq).clamp(0.0, 1.0)
+    };
+    ((x - ax - t * dx).powi(2) + (y - ay - t * dy).powi(2)).sqrt()
 }
 
 /// Capture a specific window by its CGWindow ID using CoreGraphics.
@@ -95,7 +305,6 @@ pub fn capture_window_cg(window_id: u32, output_path: &Path) -> Result<(), Captu
     use core_graphics::window::{
         create_image, kCGWindowImageBestResolution, kCGWindowImageBoundsIgnoreFraming,
     };
-    use image::RgbaImage;
 
     // kCGWindowListOptionIncludingWindow = 1 << 3 = 8
     const K_CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
@@ -178,13 +387,19 @@ pub fn capture_region_cg(
     width: i32,
     height: i32,
     output_path: &Path,
+    options: CaptureOptions,
 ) -> Result<(), CaptureError> {
     use std::process::Command;
 
-    let region_arg = format!("{x},{y},{width},{height}");
-
     let status = Command::new("screencapture")
-        .args(["-x", "-R", &region_arg, output_path.to_str().unwrap_or("")])
+        .args(screencapture_region_args(
+            x,
+            y,
+            width,
+            height,
+            output_path,
+            options,
+        ))
         .status()
         .map_err(|e| CaptureError::CgImage(format!("screencapture failed: {e}")))?;
 
@@ -196,3 +411,91 @@ pub fn capture_region_cg(
 
     Ok(())
 }
+
+/// Arguments for a silent `screencapture` of a region. The tool leaves the cursor
+/// out unless given `-C`.
+fn screencapture_region_args(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    output_path: &Path,
+    options: CaptureOptions,
+) -> Vec<String> {
+    let mut args = vec!["-x".to_string()];
+    if options.include_cursor {
+        args.push("-C".to_string());
+    }
+    args.push("-R".to_string());
+    args.push(format!("{x},{y},{width},{height}"));
+    args.push(output_path.to_str().unwrap_or("").to_string());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn desktop_layout_shifts_negative_origins_to_zero() {
+        // Main display, one to its left and one above-right.
+        let layout = desktop_layout(&[
+            frame(0, 0, 1512, 982),
+            frame(-1920, 100, 1920, 1080),
+            frame(1512, -300, 1080, 1920),
+        ])
+        .expect("layout");
+        assert_eq!(layout.bounds, frame(-1920, -300, 4512, 1920));
+        assert_eq!(layout.offsets, [(1920, 300), (0, 400), (3432, 0)]);
+    }
+
+    #[test]
+    fn desktop_layout_of_one_display_is_that_display() {
+        let layout = desktop_layout(&[frame(0, 0, 1440, 900)]).expect("layout");
+        assert_eq!(layout.bounds, frame(0, 0, 1440, 900));
+        assert_eq!(layout.offsets, [(0, 0)]);
+        assert_eq!(desktop_layout(&[]), None);
+    }
+
+    #[test]
+    fn screencapture_adds_cursor_flag_only_when_requested() {
+        let path = Path::new("/tmp/shot.png");
+        assert_eq!(
+            screencapture_region_args(10, 20, 300, 200, path, CaptureOptions::default()),
+            ["-x", "-R", "10,20,300,200", "/tmp/shot.png"]
+        );
+        let with_cursor = CaptureOptions {
+            include_cursor: true,
+        };
+        assert_eq!(
+            screencapture_region_args(10, 20, 300, 200, path, with_cursor),
+            ["-x", "-C", "-R", "10,20,300,200", "/tmp/shot.png"]
+        );
+    }
+
+    #[test]
+    fn cursor_glyph_is_drawn_at_the_pointer_and_clipped() {
+        let blank = image::Rgba([0, 0, 255, 255]);
+        let mut img = RgbaImage::from_pixel(40, 40, blank);
+        draw_cursor(&mut img, 10.0, 10.0, 1.0);
+
+        assert_eq!(*img.get_pixel(10, 14), image::Rgba([255, 255, 255, 255]));
+        assert_eq!(*img.get_pixel(12, 19), image::Rgba([0, 0, 0, 255]));
+        assert_eq!(*img.get_pixel(9, 9), blank);
+        assert_eq!(*img.get_pixel(30, 12), blank);
+
+        // Retina: twice the pixels, and a cursor half off the image does not panic.
+        let mut img = RgbaImage::from_pixel(40, 40, blank);
+        draw_cursor(&mut img, 30.0, -10.0, 2.0);
+        assert_eq!(*img.get_pixel(32, 10), image::Rgba([0, 0, 0, 255]));
+    }
+}
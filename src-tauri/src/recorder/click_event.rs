@@ -1,3 +1,4 @@
+use super::types::ActionType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,6 +15,28 @@ pub struct ClickEvent {
     pub button: MouseButton,
     /// Click count from CGEvent (1 = single, 2 = double, 3 = triple)
     pub click_count: i64,
+    /// Set on the mouse-up that ends a drag: where the drag started.
+    #[serde(default)]
+    pub drag_start: Option<(i32, i32)>,
+    /// Synthesized by the hover shortcut: the pointer rests at (`x`, `y`), nothing was
+    /// clicked.
+    #[serde(default)]
+    pub hover: bool,
+    /// A keyboard shortcut ("⌘S") pressed with the pointer at (`x`, `y`); nothing was
+    /// clicked.
+    #[serde(default)]
+    pub shortcut: Option<String>,
+}
+
+/// Minimum pointer travel (points) between mouse-down and mouse-up to count as a drag.
+/// Anything shorter is jitter or a short text selection and stays a plain click.
+pub const DRAG_MIN_DISTANCE: f64 = 30.0;
+
+/// Whether the pointer moved far enough between `start` and `end` to be a drag.
+pub fn is_drag(start: (i32, i32), end: (i32, i32)) -> bool {
+    let dx = f64::from(end.0 - start.0);
+    let dy = f64::from(end.1 - start.1);
+    dx.hypot(dy) >= DRAG_MIN_DISTANCE
 }
 
 impl ClickEvent {
@@ -29,6 +52,52 @@ impl ClickEvent {
             timestamp_ms,
             button,
             click_count,
+            drag_start: None,
+            hover: false,
+            shortcut: None,
+        }
+    }
+
+    /// The pointer resting at (`x`, `y`) when the hover shortcut was pressed.
+    pub fn hover(x: i32, y: i32) -> Self {
+        Self {
+            hover: true,
+            ..Self::new(x, y, MouseButton::Left, 1)
+        }
+    }
+
+    /// Shortcut `keys` pressed while the pointer rests at (`x`, `y`).
+    pub fn shortcut(x: i32, y: i32, keys: String) -> Self {
+        Self {
+            shortcut: Some(keys),
+            ..Self::new(x, y, MouseButton::Left, 1)
+        }
+    }
+
+    /// Hovers and shortcuts click nothing, so click-only handling (debouncing,
+    /// double-click upgrades, context menus, auth dialog cooldowns) skips them.
+    pub fn is_synthetic(&self) -> bool {
+        self.hover || self.shortcut.is_some()
+    }
+
+    /// The step action this event records. Triple-clicks count as double-clicks.
+    pub fn action(&self) -> ActionType {
+        if self.shortcut.is_some() {
+            return ActionType::Shortcut;
+        }
+        match (self.hover, self.button, self.click_count) {
+            (true, _, _) => ActionType::Hover,
+            (_, MouseButton::Right, _) => ActionType::RightClick,
+            (_, MouseButton::Left, n) if n >= 2 => ActionType::DoubleClick,
+            _ => ActionType::Click,
+        }
+    }
+
+    /// Mouse-up at (`x`, `y`) ending a left-button drag that began at `start`.
+    pub fn drag_end(x: i32, y: i32, start: (i32, i32)) -> Self {
+        Self {
+            drag_start: Some(start),
+            ..Self::new(x, y, MouseButton::Left, 1)
         }
     }
 }
@@ -51,4 +120,54 @@ mod tests {
         let event = ClickEvent::new(100, 200, MouseButton::Left, 2);
         assert_eq!(event.click_count, 2);
     }
+
+    #[test]
+    fn drag_end_records_start() {
+        let event = ClickEvent::drag_end(300, 200, (100, 200));
+        assert_eq!(event.drag_start, Some((100, 200)));
+        assert_eq!(event.button, MouseButton::Left);
+        assert!(ClickEvent::new(1, 2, MouseButton::Left, 1)
+            .drag_start
+            .is_none());
+    }
+
+    #[test]
+    fn action_follows_button_count_and_hover() {
+        assert_eq!(
+            ClickEvent::new(1, 2, MouseButton::Left, 1).action(),
+            ActionType::Click
+        );
+        assert_eq!(
+            ClickEvent::new(1, 2, MouseButton::Left, 3).action(),
+            ActionType::DoubleClick
+        );
+        assert_eq!(
+            ClickEvent::new(1, 2, MouseButton::Right, 2).action(),
+            ActionType::RightClick
+        );
+        let hover = ClickEvent::hover(1, 2);
+        assert_eq!((hover.x, hover.y), (1, 2));
+        assert_eq!(hover.action(), ActionType::Hover);
+        assert!(!ClickEvent::new(1, 2, MouseButton::Left, 1).hover);
+    }
+
+    #[test]
+    fn shortcut_events_record_the_keys() {
+        let event = ClickEvent::shortcut(1, 2, "⌘S".to_string());
+        assert_eq!(event.action(), ActionType::Shortcut);
+        assert_eq!(event.shortcut.as_deref(), Some("⌘S"));
+        assert!(event.is_synthetic());
+        assert!(ClickEvent::hover(1, 2).is_synthetic());
+        assert!(!ClickEvent::new(1, 2, MouseButton::Left, 2).is_synthetic());
+    }
+
+    #[test]
+    fn tiny_moves_are_not_drags() {
+        assert!(!is_drag((100, 100), (100, 100)));
+        assert!(!is_drag((100, 100), (105, 108)));
+        assert!(!is_drag((100, 100), (112, 100)));
+        assert!(!is_drag((100, 100), (118, 120)));
+        assert!(is_drag((100, 100), (130, 100)));
+        assert!(is_drag((100, 100), (40, 300)));
+    }
 }
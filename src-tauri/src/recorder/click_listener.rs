@@ -2,19 +2,45 @@
 //!
 //! This module provides a `ClickListener` that captures global mouse clicks
 //! using the Core Graphics event tap API and delivers them through a channel.
+//! Keyboard shortcuts (⌘ or ⌃ plus a key) arrive on the same channel as
+//! [`ClickEvent::shortcut`]; plain typing is ignored.
+//! A tap the system disabled is re-enabled by the listener thread; see
+//! [`super::listener_watchdog`].
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use core_foundation::base::TCFType;
+use core_foundation::mach_port::CFMachPortRef;
 use core_foundation::runloop::{kCFRunLoopCommonModes, kCFRunLoopDefaultMode, CFRunLoop};
 use core_graphics::event::{
-    CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType, EventField,
+    CGEvent, CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+    CGEventType, EventField,
 };
+use tauri_plugin_global_shortcut::Modifiers;
 
-use super::click_event::{ClickEvent, MouseButton};
+use super::click_event::{is_drag, ClickEvent, MouseButton};
+use super::key_combo::{code_from_mac_keycode, shortcut_label};
+use super::listener_watchdog::{EventTapControl, TapWatchdog};
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventTapIsEnabled(tap: CFMachPortRef) -> bool;
+}
+
+impl EventTapControl for CGEventTap<'_> {
+    fn is_enabled(&self) -> bool {
+        // SAFETY: the mach port belongs to a live event tap.
+        unsafe { CGEventTapIsEnabled(self.mach_port.as_concrete_TypeRef()) }
+    }
+
+    fn enable(&self) {
+        CGEventTap::enable(self);
+    }
+}
 
 /// A listener for global mouse click events on macOS.
 ///
@@ -24,6 +50,8 @@ pub struct ClickListener {
     running: Arc<AtomicBool>,
     receiver: Receiver<ClickEvent>,
     run_loop: Arc<std::sync::Mutex<Option<CFRunLoop>>>,
+    /// Since when the tap has been disabled and couldn't be re-enabled.
+    tap_disabled_since: Arc<std::sync::Mutex<Option<Instant>>>,
     _handle: JoinHandle<()>,
 }
 
@@ -41,9 +69,11 @@ impl ClickListener {
         let run_loop_holder: Arc<std::sync::Mutex<Option<CFRunLoop>>> =
             Arc::new(std::sync::Mutex::new(None));
         let run_loop_clone = Arc::clone(&run_loop_holder);
+        let tap_disabled_since = Arc::new(std::sync::Mutex::new(None));
+        let disabled_clone = Arc::clone(&tap_disabled_since);
 
         let handle = thread::spawn(move || {
-            Self::run_event_loop(running_clone, tx, setup_tx, run_loop_clone);
+            Self::run_event_loop(running_clone, tx, setup_tx, run_loop_clone, disabled_clone);
         });
 
         // Wait for the event tap to be set up (with timeout)
@@ -52,6 +82,7 @@ impl ClickListener {
                 running,
                 receiver: rx,
                 run_loop: run_loop_holder,
+                tap_disabled_since,
                 _handle: handle,
             }),
             Ok(Err(e)) => Err(e),
@@ -65,18 +96,56 @@ impl ClickListener {
         tx: Sender<ClickEvent>,
         setup_tx: Sender<Result<(), String>>,
         run_loop_holder: Arc<std::sync::Mutex<Option<CFRunLoop>>>,
+        tap_disabled_since: Arc<std::sync::Mutex<Option<Instant>>>,
     ) {
-        // Create event tap for left and right mouse down events
-        let events_of_interest = vec![CGEventType::LeftMouseDown, CGEventType::RightMouseDown];
+        // Mouse downs become clicks; the left mouse up is only used to detect drags.
+        // Key downs only count when they form a shortcut.
+        let events_of_interest = vec![
+            CGEventType::LeftMouseDown,
+            CGEventType::LeftMouseUp,
+            CGEventType::RightMouseDown,
+            CGEventType::KeyDown,
+        ];
 
         let tx_clone = tx.clone();
+        // Set by the callback when the system disabled the tap; the loop re-enables it.
+        let tap_disabled = Arc::new(AtomicBool::new(false));
+        let tap_disabled_clone = Arc::clone(&tap_disabled);
+        // Where the current left-button press started, while it is held.
+        let press_origin: std::sync::Mutex<Option<(i32, i32)>> = std::sync::Mutex::new(None);
         let tap_result = CGEventTap::new(
             CGEventTapLocation::HID,
             CGEventTapPlacement::HeadInsertEventTap,
             CGEventTapOptions::ListenOnly,
             events_of_interest,
             move |_proxy, event_type, event| {
+                // Delivered regardless of the event mask.
+                if matches!(
+                    event_type,
+                    CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput
+                ) {
+                    tap_disabled_clone.store(true, Ordering::SeqCst);
+                    return None;
+                }
+
                 let location = event.location();
+                let point = (location.x as i32, location.y as i32);
+
+                if matches!(event_type, CGEventType::KeyDown) {
+                    if let Some(keys) = shortcut_keys(event) {
+                        let _ = tx_clone.send(ClickEvent::shortcut(point.0, point.1, keys));
+                    }
+                    return None;
+                }
+
+                if matches!(event_type, CGEventType::LeftMouseUp) {
+                    let origin = press_origin.lock().ok().and_then(|mut o| o.take());
+                    if let Some(start) = origin.filter(|&start| is_drag(start, point)) {
+                        let _ = tx_clone.send(ClickEvent::drag_end(point.0, point.1, start));
+                    }
+                    return None;
+                }
+
                 let button = match event_type {
                     CGEventType::LeftMouseDown => MouseButton::Left,
                     CGEventType::RightMouseDown => MouseButton::Right,
@@ -87,8 +156,12 @@ impl ClickListener {
                 let click_count =
                     event.get_integer_value_field(EventField::MOUSE_EVENT_CLICK_STATE);
 
-                let click_event =
-                    ClickEvent::new(location.x as i32, location.y as i32, button, click_count);
+                // Only a fresh single press can start a drag (not the 2nd half of a double-click).
+                if let Ok(mut origin) = press_origin.lock() {
+                    *origin = (button == MouseButton::Left && click_count == 1).then_some(point);
+                }
+
+                let click_event = ClickEvent::new(point.0, point.1, button, click_count);
 
                 // Send event, ignoring errors if receiver is dropped
                 let _ = tx_clone.send(click_event);
@@ -140,7 +213,18 @@ impl ClickListener {
         // We use run_in_mode with a timeout to periodically check the running flag
         // Note: kCFRunLoopDefaultMode must be used here, not kCFRunLoopCommonModes
         // (kCFRunLoopCommonModes is a pseudo-mode for adding sources, not for running)
+        let mut watchdog = TapWatchdog::new();
+        let mut disabled = false;
         while running.load(Ordering::SeqCst) {
+            // Once reported, keep trying every iteration until the tap is back.
+            if tap_disabled.swap(false, Ordering::SeqCst) || disabled {
+                let since = watchdog.poll(&tap, Instant::now());
+                disabled = since.is_some();
+                if let Ok(mut slot) = tap_disabled_since.lock() {
+                    *slot = since;
+                }
+            }
+
             let result = unsafe {
                 CFRunLoop::run_in_mode(kCFRunLoopDefaultMode, Duration::from_millis(100), true)
             };
@@ -172,6 +256,11 @@ impl ClickListener {
         }
     }
 
+    /// Since when the event tap has been disabled, if re-enabling it keeps failing.
+    pub fn tap_disabled_since(&self) -> Option<Instant> {
+        self.tap_disabled_since.lock().ok().and_then(|since| *since)
+    }
+
     /// Try to receive a click event without blocking.
     ///
     /// Returns `Some(ClickEvent)` if an event is available, `None` otherwise.
@@ -184,6 +273,12 @@ impl ClickListener {
         }
     }
 
+    /// Drop every queued click event, e.g. the stale burst after a wake. Returns how
+    /// many were dropped.
+    pub fn clear_queue(&self) -> usize {
+        self.receiver.try_iter().count()
+    }
+
     /// Receive a click event with timeout.
     pub fn recv_timeout(&self, timeout: Duration) -> Option<ClickEvent> {
         self.receiver.recv_timeout(timeout).ok()
@@ -195,6 +290,28 @@ impl ClickListener {
     }
 }
 
+/// The shortcut a key down forms ("⌘S"), or `None` for typing and held-key repeats.
+fn shortcut_keys(event: &CGEvent) -> Option<String> {
+    if event.get_integer_value_field(EventField::KEYBOARD_EVENT_AUTOREPEAT) != 0 {
+        return None;
+    }
+    let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+    let code = code_from_mac_keycode(u16::try_from(keycode).ok()?)?;
+    let flags = event.get_flags();
+    let mut mods = Modifiers::empty();
+    for (flag, modifier) in [
+        (CGEventFlags::CGEventFlagCommand, Modifiers::META),
+        (CGEventFlags::CGEventFlagControl, Modifiers::CONTROL),
+        (CGEventFlags::CGEventFlagAlternate, Modifiers::ALT),
+        (CGEventFlags::CGEventFlagShift, Modifiers::SHIFT),
+    ] {
+        if flags.contains(flag) {
+            mods |= modifier;
+        }
+    }
+    shortcut_label(mods, code)
+}
+
 impl Drop for ClickListener {
     fn drop(&mut self) {
         self.stop();
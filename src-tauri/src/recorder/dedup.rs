@@ -0,0 +1,173 @@
+//! Post-recording cleanup of near-duplicate consecutive steps.
+//!
+//! Fast repeated clicks can slip past the pipeline debounce and produce two steps
+//! that look the same. A step is a duplicate of the one before it when the action,
+//! app and window match, the click lands within a small distance, the two happened
+//! close together, and the screenshots hash almost the same.
+
+use super::phash;
+use super::session::Session;
+use super::types::{ActionType, Step};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Steps further apart than this are separate actions even if identical.
+const MAX_INTERVAL_MS: i64 = 1_000;
+/// Maximum click offset, in percent of the screenshot, on each axis.
+const MAX_CLICK_DELTA_PERCENT: f32 = 1.5;
+/// Maximum perceptual-hash distance (out of 64 bits) for "visually identical".
+const MAX_HASH_DISTANCE: u32 = 6;
+
+/// A step removed as a near-duplicate of an earlier one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateStep {
+    pub step_id: String,
+    pub duplicate_of: String,
+}
+
+/// Result of a deduplication pass.
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupSummary {
+    pub removed: Vec<DuplicateStep>,
+    /// Steps left in the session afterwards.
+    pub remaining: usize,
+}
+
+/// Remove near-duplicate steps from `session`.
+pub fn deduplicate(session: &mut Session) -> DedupSummary {
+    let removed = find_duplicates(session.get_steps());
+    session.discard_steps(|s| removed.iter().any(|dup| dup.step_id == s.id));
+    DedupSummary {
+        removed,
+        remaining: session.get_steps().len(),
+    }
+}
+
+/// Find steps that duplicate their predecessor, oldest first.
+///
+/// Each step is compared with the last step that is kept, so a burst of three
+/// identical clicks collapses into one.
+pub fn find_duplicates(steps: &[Step]) -> Vec<DuplicateStep> {
+    let mut hashes: HashMap<String, Option<u64>> = HashMap::new();
+    let mut hash_of = |step: &Step| -> Option<u64> {
+        let path = step.screenshot_path.as_deref()?;
+        *hashes
+            .entry(path.to_string())
+            .or_insert_with(|| phash::dhash_file(Path::new(path)))
+    };
+
+    let mut duplicates = Vec::new();
+    let mut kept: Option<&Step> = None;
+    for step in steps {
+        if let Some(prev) = kept {
+            if is_candidate(prev, step) {
+                let similar = match (hash_of(prev), hash_of(step)) {
+                    (Some(a), Some(b)) => phash::distance(a, b) <= MAX_HASH_DISTANCE,
+                    _ => false,
+                };
+                if similar {
+                    duplicates.push(DuplicateStep {
+                        step_id: step.id.clone(),
+                        duplicate_of: prev.id.clone(),
+                    });
+                    continue;
+                }
+            }
+        }
+        kept = Some(step);
+    }
+    duplicates
+}
+
+/// Metadata checks, cheap enough to run before hashing any screenshots.
+fn is_candidate(prev: &Step, step: &Step) -> bool {
+    if matches!(step.action, ActionType::Note) || step.is_auth_placeholder() {
+        return false;
+    }
+    step.action == prev.action
+        && step.app == prev.app
+        && step.window_title == prev.window_title
+        && (step.ts - prev.ts).abs() < MAX_INTERVAL_MS
+        && (step.click_x_percent - prev.click_x_percent).abs() <= MAX_CLICK_DELTA_PERCENT
+        && (step.click_y_percent - prev.click_y_percent).abs() <= MAX_CLICK_DELTA_PERCENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn write_image(dir: &Path, name: &str, flip: bool) -> String {
+        let img = RgbaImage::from_fn(160, 100, |x, _| {
+            let v = if flip { 255 - x as u8 } else { x as u8 };
+            Rgba([v, v / 2, 255 - v, 255])
+        });
+        let path = dir.join(name);
+        img.save(&path).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn step(id: &str, ts: i64, x_pct: f32, screenshot: &str) -> Step {
+        let mut s = Step::sample();
+        s.id = id.into();
+        s.ts = ts;
+        s.click_x_percent = x_pct;
+        s.screenshot_path = Some(screenshot.into());
+        s
+    }
+
+    #[test]
+    fn removes_burst_of_identical_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_image(dir.path(), "a.png", false);
+        let b = write_image(dir.path(), "b.png", false);
+        let c = write_image(dir.path(), "c.png", false);
+        let steps = vec![
+            step("step-1", 1_000, 50.0, &a),
+            step("step-2", 1_300, 50.5, &b),
+            step("step-3", 1_600, 51.0, &c),
+        ];
+        let dups = find_duplicates(&steps);
+        assert_eq!(
+            dups,
+            vec![
+                DuplicateStep {
+                    step_id: "step-2".into(),
+                    duplicate_of: "step-1".into()
+                },
+                DuplicateStep {
+                    step_id: "step-3".into(),
+                    duplicate_of: "step-1".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_steps_that_differ() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_image(dir.path(), "a.png", false);
+        let flipped = write_image(dir.path(), "f.png", true);
+
+        // Different screenshot.
+        let steps = vec![step("1", 1_000, 50.0, &a), step("2", 1_200, 50.0, &flipped)];
+        assert!(find_duplicates(&steps).is_empty());
+
+        // Too far apart in time or position.
+        let steps = vec![step("1", 1_000, 50.0, &a), step("2", 2_500, 50.0, &a)];
+        assert!(find_duplicates(&steps).is_empty());
+        let steps = vec![step("1", 1_000, 50.0, &a), step("2", 1_200, 60.0, &a)];
+        assert!(find_duplicates(&steps).is_empty());
+
+        // Different action.
+        let mut right = step("2", 1_200, 50.0, &a);
+        right.action = ActionType::RightClick;
+        assert!(find_duplicates(&[step("1", 1_000, 50.0, &a), right]).is_empty());
+
+        // Missing screenshots can't be confirmed as duplicates.
+        let mut missing = step("2", 1_200, 50.0, &a);
+        missing.screenshot_path = None;
+        assert!(find_duplicates(&[step("1", 1_000, 50.0, &a), missing]).is_empty());
+    }
+}
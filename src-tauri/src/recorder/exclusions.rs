@@ -0,0 +1,170 @@
+//! Screen areas that never show up in screenshots, such as a clock or ticker overlay
+//! parked in a corner. Each capture has the parts of these areas it contains filled
+//! black, or blurred, before the file is kept.
+
+use super::window_info::WindowBounds;
+use image::{imageops, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Blur strength for blurred exclusions; same as the "blur all text" mode.
+const BLUR_SIGMA: f32 = 10.0;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureExclusions {
+    /// Rects in display coordinates: points, origin at the top-left of the main display.
+    pub regions: Vec<WindowBounds>,
+    /// Blur the areas instead of filling them black.
+    pub blur: bool,
+}
+
+impl CaptureExclusions {
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Pixel rect (x, y, w, h) of `region` inside an image of `img_w`×`img_h` pixels
+/// showing `capture`. `None` when they don't overlap. Edges round outwards, so no
+/// sliver of the region survives the scaling.
+pub fn region_in_capture(
+    region: &WindowBounds,
+    capture: &WindowBounds,
+    img_w: u32,
+    img_h: u32,
+) -> Option<(u32, u32, u32, u32)> {
+    if capture.width == 0 || capture.height == 0 {
+        return None;
+    }
+    let left = region.x.max(capture.x);
+    let top = region.y.max(capture.y);
+    let right = (region.x + region.width as i32).min(capture.x + capture.width as i32);
+    let bottom = (region.y + region.height as i32).min(capture.y + capture.height as i32);
+    if right <= left || bottom <= top {
+        return None;
+    }
+
+    let scale_x = img_w as f64 / capture.width as f64;
+    let scale_y = img_h as f64 / capture.height as f64;
+    let px_left = ((left - capture.x) as f64 * scale_x).floor() as u32;
+    let px_top = ((top - capture.y) as f64 * scale_y).floor() as u32;
+    let px_right = (((right - capture.x) as f64 * scale_x).ceil() as u32).min(img_w);
+    let px_bottom = (((bottom - capture.y) as f64 * scale_y).ceil() as u32).min(img_h);
+    (px_right > px_left && px_bottom > px_top)
+        .then(|| (px_left, px_top, px_right - px_left, px_bottom - px_top))
+}
+
+/// Fill or blur the parts of `exclusions` that `img`, a capture of `capture`, shows.
+/// Returns how many regions were covered.
+pub fn mask_exclusions(
+    img: &mut RgbaImage,
+    capture: &WindowBounds,
+    exclusions: &CaptureExclusions,
+) -> usize {
+    let (img_w, img_h) = img.dimensions();
+    let mut masked = 0;
+    for region in &exclusions.regions {
+        let Some((x, y, w, h)) = region_in_capture(region, capture, img_w, img_h) else {
+            continue;
+        };
+        if exclusions.blur {
+            let patch = imageops::crop_imm(img, x, y, w, h).to_image();
+            let patch = imageops::fast_blur(&patch, BLUR_SIGMA);
+            imageops::replace(img, &patch, x as i64, y as i64);
+        } else {
+            for py in y..y + h {
+                for px in x..x + w {
+                    img.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+                }
+            }
+        }
+        masked += 1;
+    }
+    masked
+}
+
+/// Mask the excluded areas in the screenshot at `path`, a capture of `capture`. The
+/// file is only rewritten when an area overlaps it.
+pub fn mask_exclusions_in_file(
+    path: &Path,
+    capture: &WindowBounds,
+    exclusions: &CaptureExclusions,
+) -> Result<usize, String> {
+    let overlaps = exclusions
+        .regions
+        .iter()
+        .any(|region| region_in_capture(region, capture, capture.width, capture.height).is_some());
+    if !overlaps {
+        return Ok(0);
+    }
+    let mut img = image::open(path)
+        .map_err(|e| format!("open screenshot: {e}"))?
+        .to_rgba8();
+    let masked = mask_exclusions(&mut img, capture, exclusions);
+    img.save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("save masked screenshot: {e}"))?;
+    Ok(masked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn partial_overlap_covers_only_the_shared_part() {
+        // Clock overlay in the top-right corner; the capture reaches into it.
+        let capture = rect(1000, 0, 600, 400);
+        let clock = rect(1500, -20, 200, 80);
+        assert_eq!(
+            region_in_capture(&clock, &capture, 600, 400),
+            Some((500, 0, 100, 60))
+        );
+        // Retina capture: twice the pixels per point.
+        assert_eq!(
+            region_in_capture(&clock, &capture, 1200, 800),
+            Some((1000, 0, 200, 120))
+        );
+    }
+
+    #[test]
+    fn regions_outside_the_capture_are_ignored() {
+        let capture = rect(0, 0, 800, 600);
+        assert_eq!(
+            region_in_capture(&rect(800, 0, 50, 50), &capture, 800, 600),
+            None
+        );
+        assert_eq!(
+            region_in_capture(&rect(-60, 10, 50, 50), &capture, 800, 600),
+            None
+        );
+        assert_eq!(
+            region_in_capture(&rect(10, 10, 50, 50), &rect(0, 0, 0, 600), 0, 600),
+            None
+        );
+    }
+
+    #[test]
+    fn masks_overlapping_regions_black() {
+        let capture = rect(100, 100, 4, 4);
+        let mut img = RgbaImage::from_pixel(4, 4, Rgba([255, 255, 255, 255]));
+        let exclusions = CaptureExclusions {
+            regions: vec![rect(102, 98, 10, 3), rect(0, 0, 10, 10)],
+            blur: false,
+        };
+        assert_eq!(mask_exclusions(&mut img, &capture, &exclusions), 1);
+        assert_eq!(img.get_pixel(2, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(img.get_pixel(3, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(img.get_pixel(1, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(2, 1), &Rgba([255, 255, 255, 255]));
+    }
+}
@@ -0,0 +1,121 @@
+//! Auto-stop for recordings left running.
+//!
+//! A forgotten recording keeps the click listener alive for hours. The processing
+//! loop remembers when it last recorded a step and stops the recorder once nothing
+//! has been recorded for the configured timeout. Time spent paused doesn't count.
+
+use std::time::{Duration, Instant};
+
+/// Idle time after which a recording stops unless the user picked another.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Timeout for the `idle_timeout_min` setting: `None` uses the default, 0 turns
+/// the auto-stop off.
+pub fn idle_timeout_from_minutes(minutes: Option<u32>) -> Option<Duration> {
+    match minutes {
+        None => Some(DEFAULT_IDLE_TIMEOUT),
+        Some(0) => None,
+        Some(min) => Some(Duration::from_secs(u64::from(min) * 60)),
+    }
+}
+
+/// True once `timeout` has passed since `last_activity`. Without a timeout the
+/// recording never goes idle.
+pub fn idle_timed_out(last_activity: Instant, now: Instant, timeout: Option<Duration>) -> bool {
+    timeout.is_some_and(|timeout| now.saturating_duration_since(last_activity) >= timeout)
+}
+
+/// Time of the last recorded step, for [`idle_timed_out`].
+pub struct IdleTimer {
+    timeout: Option<Duration>,
+    last_activity: Instant,
+}
+
+impl IdleTimer {
+    pub fn new(timeout: Option<Duration>, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_activity: now,
+        }
+    }
+
+    /// Restart the countdown, after a recorded step or while paused.
+    pub fn reset(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    pub fn timed_out(&self, now: Instant) -> bool {
+        idle_timed_out(self.last_activity, now, self.timeout)
+    }
+
+    /// The configured timeout; `None` when the auto-stop is off.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_out_once_the_timeout_has_elapsed() {
+        let start = Instant::now();
+        let timeout = Some(Duration::from_secs(60));
+        assert!(!idle_timed_out(start, start, timeout));
+        assert!(!idle_timed_out(
+            start,
+            start + Duration::from_secs(59),
+            timeout
+        ));
+        assert!(idle_timed_out(
+            start,
+            start + Duration::from_secs(60),
+            timeout
+        ));
+        assert!(idle_timed_out(
+            start,
+            start + Duration::from_secs(3600),
+            timeout
+        ));
+    }
+
+    #[test]
+    fn never_times_out_without_a_timeout() {
+        let start = Instant::now();
+        assert!(!idle_timed_out(
+            start,
+            start + Duration::from_secs(86_400),
+            None
+        ));
+    }
+
+    #[test]
+    fn clock_before_last_activity_is_not_idle() {
+        let start = Instant::now() + Duration::from_secs(10);
+        assert!(!idle_timed_out(
+            start,
+            start - Duration::from_secs(5),
+            Some(Duration::from_secs(1))
+        ));
+    }
+
+    #[test]
+    fn reset_restarts_the_countdown() {
+        let start = Instant::now();
+        let mut timer = IdleTimer::new(Some(Duration::from_secs(60)), start);
+        timer.reset(start + Duration::from_secs(50));
+        assert!(!timer.timed_out(start + Duration::from_secs(100)));
+        assert!(timer.timed_out(start + Duration::from_secs(110)));
+    }
+
+    #[test]
+    fn minutes_setting_maps_to_timeout() {
+        assert_eq!(idle_timeout_from_minutes(None), Some(DEFAULT_IDLE_TIMEOUT));
+        assert_eq!(idle_timeout_from_minutes(Some(0)), None);
+        assert_eq!(
+            idle_timeout_from_minutes(Some(5)),
+            Some(Duration::from_secs(300))
+        );
+    }
+}
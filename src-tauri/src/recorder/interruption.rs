@@ -0,0 +1,173 @@
+//! Screen lock and system sleep during a recording.
+//!
+//! macOS posts `com.apple.screenIsLocked` / `com.apple.screenIsUnlocked` on the
+//! distributed notification center when the session locks (including screensaver
+//! with password), and `NSWorkspace` posts will-sleep / did-wake. Captures in between
+//! only show the lock screen or black frames, and the click listener may deliver a
+//! burst of stale events on wake, so the recorder pauses and asks before resuming.
+
+use serde::Serialize;
+
+/// A capture finishing this long after a lock or sleep may show the lock screen.
+pub const LOCK_SCREEN_CAPTURE_WINDOW_MS: i64 = 2_000;
+
+/// What interrupted the recording. Serialized as the `recording-interrupted` reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interruption {
+    ScreenLock,
+    Sleep,
+}
+
+/// True when a step captured at `capture_ms` may show the lock screen: the screen
+/// locked or the Mac went to sleep at `interrupted_at_ms`, at most
+/// `LOCK_SCREEN_CAPTURE_WINDOW_MS` before.
+pub fn is_lock_screen_capture(capture_ms: i64, interrupted_at_ms: Option<i64>) -> bool {
+    interrupted_at_ms
+        .is_some_and(|at| (0..=LOCK_SCREEN_CAPTURE_WINDOW_MS).contains(&(capture_ms - at)))
+}
+
+/// Watches for lock/unlock and sleep/wake while a recording runs. Created by
+/// `start_recording`; dropping it (stop, discard) unregisters the observers.
+pub struct InterruptionObserver {
+    #[cfg(target_os = "macos")]
+    tokens: Vec<(
+        NotificationCenter,
+        objc2::rc::Retained<objc2::runtime::ProtocolObject<dyn objc2::runtime::NSObjectProtocol>>,
+    )>,
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+enum NotificationCenter {
+    Distributed,
+    Workspace,
+}
+
+// SAFETY: the tokens are only used to unregister, and both notification centers may
+// be called from any thread.
+#[cfg(target_os = "macos")]
+unsafe impl Send for InterruptionObserver {}
+
+impl InterruptionObserver {
+    /// Start observing. `on_change(kind, true)` fires when the screen locks or the Mac
+    /// goes to sleep, `on_change(kind, false)` on unlock or wake. Callbacks run on the
+    /// main queue, whatever thread this is called from.
+    #[cfg(target_os = "macos")]
+    pub fn start(on_change: impl Fn(Interruption, bool) + 'static) -> Self {
+        use block2::RcBlock;
+        use objc2_app_kit::{
+            NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+        };
+        use objc2_foundation::{
+            NSDistributedNotificationCenter, NSNotification, NSOperationQueue, NSString,
+        };
+        use std::ptr::NonNull;
+        use std::rc::Rc;
+
+        let on_change = Rc::new(on_change);
+        let queue = NSOperationQueue::mainQueue();
+        let distributed = NSDistributedNotificationCenter::defaultCenter();
+        let workspace = NSWorkspace::sharedWorkspace().notificationCenter();
+        let lock_names = [
+            (NSString::from_str("com.apple.screenIsLocked"), true),
+            (NSString::from_str("com.apple.screenIsUnlocked"), false),
+        ];
+        // SAFETY: constants exported by AppKit.
+        let sleep_names = unsafe {
+            [
+                (NSWorkspaceWillSleepNotification, true),
+                (NSWorkspaceDidWakeNotification, false),
+            ]
+        };
+
+        let mut tokens = Vec::new();
+        for (name, started) in &lock_names {
+            let (on_change, started) = (Rc::clone(&on_change), *started);
+            let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+                on_change(Interruption::ScreenLock, started);
+            });
+            // SAFETY: the block only captures an Rc'd callback and runs on the main queue.
+            let token = unsafe {
+                distributed.addObserverForName_object_queue_usingBlock(
+                    Some(name),
+                    None,
+                    Some(&queue),
+                    &block,
+                )
+            };
+            tokens.push((NotificationCenter::Distributed, token));
+        }
+        for (name, started) in sleep_names {
+            let on_change = Rc::clone(&on_change);
+            let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+                on_change(Interruption::Sleep, started);
+            });
+            // SAFETY: as above.
+            let token = unsafe {
+                workspace.addObserverForName_object_queue_usingBlock(
+                    Some(name),
+                    None,
+                    Some(&queue),
+                    &block,
+                )
+            };
+            tokens.push((NotificationCenter::Workspace, token));
+        }
+        Self { tokens }
+    }
+
+    /// Lock and sleep notifications are macOS-only; elsewhere nothing is reported.
+    #[cfg(not(target_os = "macos"))]
+    pub fn start(_on_change: impl Fn(Interruption, bool) + 'static) -> Self {
+        Self {}
+    }
+}
+
+impl Drop for InterruptionObserver {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        for (center, token) in self.tokens.drain(..) {
+            use objc2::runtime::AnyObject;
+            use objc2_app_kit::NSWorkspace;
+            use objc2_foundation::NSDistributedNotificationCenter;
+
+            let observer: &AnyObject = (*token).as_ref();
+            // SAFETY: `token` was returned by addObserverForName on this same center.
+            unsafe {
+                match center {
+                    NotificationCenter::Distributed => {
+                        NSDistributedNotificationCenter::defaultCenter().removeObserver(observer)
+                    }
+                    NotificationCenter::Workspace => NSWorkspace::sharedWorkspace()
+                        .notificationCenter()
+                        .removeObserver(observer),
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_shortly_after_an_interruption_show_the_lock_screen() {
+        assert!(is_lock_screen_capture(10_000, Some(10_000)));
+        assert!(is_lock_screen_capture(
+            10_000 + LOCK_SCREEN_CAPTURE_WINDOW_MS,
+            Some(10_000)
+        ));
+    }
+
+    #[test]
+    fn captures_before_or_long_after_an_interruption_are_kept() {
+        assert!(!is_lock_screen_capture(10_000, None));
+        assert!(!is_lock_screen_capture(9_999, Some(10_000)));
+        assert!(!is_lock_screen_capture(
+            10_001 + LOCK_SCREEN_CAPTURE_WINDOW_MS,
+            Some(10_000)
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! Keyboard shortcuts as recorded steps: turn a key press into the combo readers see
+//! in macOS menus ("⇧⌘S"), and tell shortcuts apart from plain typing.
+
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+/// macOS virtual key codes (`kVK_*` from Carbon's Events.h) of the keys a shortcut
+/// can end in. Keypad and media keys are left out.
+const MAC_KEYCODES: &[(u16, Code)] = &[
+    (0x00, Code::KeyA),
+    (0x01, Code::KeyS),
+    (0x02, Code::KeyD),
+    (0x03, Code::KeyF),
+    (0x04, Code::KeyH),
+    (0x05, Code::KeyG),
+    (0x06, Code::KeyZ),
+    (0x07, Code::KeyX),
+    (0x08, Code::KeyC),
+    (0x09, Code::KeyV),
+    (0x0B, Code::KeyB),
+    (0x0C, Code::KeyQ),
+    (0x0D, Code::KeyW),
+    (0x0E, Code::KeyE),
+    (0x0F, Code::KeyR),
+    (0x10, Code::KeyY),
+    (0x11, Code::KeyT),
+    (0x12, Code::Digit1),
+    (0x13, Code::Digit2),
+    (0x14, Code::Digit3),
+    (0x15, Code::Digit4),
+    (0x16, Code::Digit6),
+    (0x17, Code::Digit5),
+    (0x18, Code::Equal),
+    (0x19, Code::Digit9),
+    (0x1A, Code::Digit7),
+    (0x1B, Code::Minus),
+    (0x1C, Code::Digit8),
+    (0x1D, Code::Digit0),
+    (0x1E, Code::BracketRight),
+    (0x1F, Code::KeyO),
+    (0x20, Code::KeyU),
+    (0x21, Code::BracketLeft),
+    (0x22, Code::KeyI),
+    (0x23, Code::KeyP),
+    (0x24, Code::Enter),
+    (0x25, Code::KeyL),
+    (0x26, Code::KeyJ),
+    (0x27, Code::Quote),
+    (0x28, Code::KeyK),
+    (0x29, Code::Semicolon),
+    (0x2A, Code::Backslash),
+    (0x2B, Code::Comma),
+    (0x2C, Code::Slash),
+    (0x2D, Code::KeyN),
+    (0x2E, Code::KeyM),
+    (0x2F, Code::Period),
+    (0x30, Code::Tab),
+    (0x31, Code::Space),
+    (0x32, Code::Backquote),
+    (0x33, Code::Backspace),
+    (0x35, Code::Escape),
+    (0x60, Code::F5),
+    (0x61, Code::F6),
+    (0x62, Code::F7),
+    (0x63, Code::F3),
+    (0x64, Code::F8),
+    (0x65, Code::F9),
+    (0x67, Code::F11),
+    (0x6D, Code::F10),
+    (0x6F, Code::F12),
+    (0x73, Code::Home),
+    (0x74, Code::PageUp),
+    (0x75, Code::Delete),
+    (0x76, Code::F4),
+    (0x77, Code::End),
+    (0x78, Code::F2),
+    (0x79, Code::PageDown),
+    (0x7A, Code::F1),
+    (0x7B, Code::ArrowLeft),
+    (0x7C, Code::ArrowRight),
+    (0x7D, Code::ArrowDown),
+    (0x7E, Code::ArrowUp),
+];
+
+/// The key behind a macOS virtual key code, `None` for modifiers and unmapped keys.
+pub fn code_from_mac_keycode(keycode: u16) -> Option<Code> {
+    MAC_KEYCODES
+        .iter()
+        .find(|(k, _)| *k == keycode)
+        .map(|(_, code)| *code)
+}
+
+/// How macOS menus print `code`: "S", "5", "↩", "F3". `None` for keys a shortcut
+/// can't end in.
+pub fn key_symbol(code: Code) -> Option<String> {
+    let symbol = match code {
+        Code::Enter => "↩",
+        Code::Tab => "⇥",
+        Code::Space => "Space",
+        Code::Escape => "⎋",
+        Code::Backspace => "⌫",
+        Code::Delete => "⌦",
+        Code::ArrowLeft => "←",
+        Code::ArrowRight => "→",
+        Code::ArrowUp => "↑",
+        Code::ArrowDown => "↓",
+        Code::Home => "↖",
+        Code::End => "↘",
+        Code::PageUp => "⇞",
+        Code::PageDown => "⇟",
+        Code::Minus => "-",
+        Code::Equal => "=",
+        Code::BracketLeft => "[",
+        Code::BracketRight => "]",
+        Code::Backslash => "\\",
+        Code::Semicolon => ";",
+        Code::Quote => "'",
+        Code::Backquote => "`",
+        Code::Comma => ",",
+        Code::Period => ".",
+        Code::Slash => "/",
+        _ => {
+            // Letters, digits and function keys: "KeyS" → "S", "Digit5" → "5", "F3".
+            let name = code.to_string();
+            let key = name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name);
+            let is_function_key = key.len() > 1
+                && key.starts_with('F')
+                && key[1..].bytes().all(|b| b.is_ascii_digit());
+            return (key.len() == 1 || is_function_key).then(|| key.to_string());
+        }
+    };
+    Some(symbol.to_string())
+}
+
+/// "⇧⌘S" for Shift+Command+S, modifiers in the order macOS menus use (⌃⌥⇧⌘).
+/// `None` without Command or Control: with only Shift or Option held, the key types a
+/// character rather than triggering a shortcut.
+pub fn shortcut_label(mods: Modifiers, code: Code) -> Option<String> {
+    let command = mods.intersects(Modifiers::META | Modifiers::SUPER);
+    if !command && !mods.contains(Modifiers::CONTROL) {
+        return None;
+    }
+    let key = key_symbol(code)?;
+    let mut label = String::new();
+    for (modifier, symbol) in [
+        (mods.contains(Modifiers::CONTROL), '⌃'),
+        (mods.contains(Modifiers::ALT), '⌥'),
+        (mods.contains(Modifiers::SHIFT), '⇧'),
+        (command, '⌘'),
+    ] {
+        if modifier {
+            label.push(symbol);
+        }
+    }
+    label.push_str(&key);
+    Some(label)
+}
+
+/// Label of an accelerator string as used in settings ("CommandOrControl+Shift+D"),
+/// for matching recorded shortcuts against StepCast's own.
+pub fn accelerator_label(accelerator: &str) -> Option<String> {
+    let shortcut: Shortcut = accelerator.parse().ok()?;
+    shortcut_label(shortcut.mods, shortcut.key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_common_shortcuts_like_macos_menus() {
+        let cases = [
+            (Modifiers::META, Code::KeyS, "⌘S"),
+            (Modifiers::META | Modifiers::SHIFT, Code::KeyZ, "⇧⌘Z"),
+            (Modifiers::META | Modifiers::ALT, Code::Escape, "⌥⌘⎋"),
+            (Modifiers::CONTROL, Code::Tab, "⌃⇥"),
+            (Modifiers::CONTROL | Modifiers::META, Code::Space, "⌃⌘Space"),
+            (Modifiers::META, Code::Comma, "⌘,"),
+            (Modifiers::META, Code::Digit1, "⌘1"),
+            (Modifiers::META, Code::Enter, "⌘↩"),
+            (Modifiers::META, Code::Backspace, "⌘⌫"),
+            (Modifiers::CONTROL, Code::ArrowLeft, "⌃←"),
+            (Modifiers::META | Modifiers::SHIFT, Code::F3, "⇧⌘F3"),
+        ];
+        for (mods, code, expected) in cases {
+            assert_eq!(shortcut_label(mods, code).as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn typing_is_not_a_shortcut() {
+        assert_eq!(shortcut_label(Modifiers::empty(), Code::KeyS), None);
+        assert_eq!(shortcut_label(Modifiers::SHIFT, Code::KeyS), None);
+        assert_eq!(shortcut_label(Modifiers::ALT, Code::KeyE), None);
+        assert_eq!(shortcut_label(Modifiers::META, Code::ShiftLeft), None);
+    }
+
+    #[test]
+    fn maps_mac_keycodes() {
+        assert_eq!(code_from_mac_keycode(0x01), Some(Code::KeyS));
+        assert_eq!(code_from_mac_keycode(0x24), Some(Code::Enter));
+        assert_eq!(code_from_mac_keycode(0x17), Some(Code::Digit5));
+        assert_eq!(code_from_mac_keycode(0x7E), Some(Code::ArrowUp));
+        // Command key itself.
+        assert_eq!(code_from_mac_keycode(0x37), None);
+    }
+
+    #[test]
+    fn labels_accelerators_from_settings() {
+        assert_eq!(
+            accelerator_label(crate::settings::DEFAULT_HOVER_SHORTCUT).as_deref(),
+            Some("⇧⌘D")
+        );
+        assert_eq!(accelerator_label("not a shortcut"), None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Screenshots and active-window lookup through the X server.
+
+use std::path::Path;
+
+use image::RgbaImage;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, ImageFormat, Window};
+
+use super::super::capture::CaptureError;
+use super::super::window_info::WindowBounds;
+
+/// The window that had focus when the user clicked.
+#[derive(Debug, Clone)]
+pub struct ActiveWindow {
+    /// `WM_CLASS` class name (e.g. "Firefox"), the closest X11 has to an app name.
+    pub app_name: String,
+    pub title: String,
+    pub pid: Option<u32>,
+    pub bounds: WindowBounds,
+}
+
+/// Capture a root-window region into a PNG at `output_path`.
+pub fn capture_region(
+    conn: &impl Connection,
+    root: Window,
+    bounds: &WindowBounds,
+    output_path: &Path,
+) -> Result<(), CaptureError> {
+    let x11_err = |e: &dyn std::fmt::Display| CaptureError::CgImage(format!("X11 GetImage: {e}"));
+    let reply = conn
+        .get_image(
+            ImageFormat::Z_PIXMAP,
+            root,
+            bounds.x as i16,
+            bounds.y as i16,
+            bounds.width as u16,
+            bounds.height as u16,
+            !0,
+        )
+        .map_err(|e| x11_err(&e))?
+        .reply()
+        .map_err(|e| x11_err(&e))?;
+    if reply.depth != 24 && reply.depth != 32 {
+        return Err(CaptureError::CgImage(format!(
+            "unsupported X visual depth {}",
+            reply.depth
+        )));
+    }
+    let img = bgrx_to_rgba(&reply.data, bounds.width, bounds.height)
+        .ok_or_else(|| CaptureError::CgImage("X11 image buffer too small".to_string()))?;
+    img.save(output_path)
+        .map_err(|e| CaptureError::CgImage(format!("region capture save failed: {e}")))
+}
+
+/// Look up the focused top-level window via `_NET_ACTIVE_WINDOW` (EWMH).
+pub fn active_window(conn: &impl Connection, root: Window) -> Option<ActiveWindow> {
+    let active_atom = intern(conn, b"_NET_ACTIVE_WINDOW")?;
+    let window = conn
+        .get_property(false, root, active_atom, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .next()
+        .filter(|&w| w != 0)?;
+
+    let geometry = conn.get_geometry(window).ok()?.reply().ok()?;
+    let origin = conn
+        .translate_coordinates(window, root, 0, 0)
+        .ok()?
+        .reply()
+        .ok()?;
+
+    let utf8 = intern(conn, b"UTF8_STRING")?;
+    let title = intern(conn, b"_NET_WM_NAME")
+        .and_then(|name| string_property(conn, window, name, utf8))
+        .or_else(|| {
+            string_property(
+                conn,
+                window,
+                AtomEnum::WM_NAME.into(),
+                AtomEnum::STRING.into(),
+            )
+        })
+        .unwrap_or_default();
+    let app_name = string_property(
+        conn,
+        window,
+        AtomEnum::WM_CLASS.into(),
+        AtomEnum::STRING.into(),
+    )
+    .and_then(|class| wm_class_name(&class))
+    .unwrap_or_else(|| "Application".to_string());
+    let pid = intern(conn, b"_NET_WM_PID").and_then(|atom| {
+        conn.get_property(false, window, atom, AtomEnum::CARDINAL, 0, 1)
+            .ok()?
+            .reply()
+            .ok()?
+            .value32()?
+            .next()
+    });
+
+    Some(ActiveWindow {
+        app_name,
+        title,
+        pid,
+        bounds: WindowBounds {
+            x: i32::from(origin.dst_x),
+            y: i32::from(origin.dst_y),
+            width: u32::from(geometry.width),
+            height: u32::from(geometry.height),
+        },
+    })
+}
+
+fn intern(conn: &impl Connection, name: &[u8]) -> Option<u32> {
+    Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+}
+
+fn string_property(conn: &impl Connection, window: Window, name: u32, ty: u32) -> Option<String> {
+    let reply = conn
+        .get_property(false, window, name, ty, 0, 1024)
+        .ok()?
+        .reply()
+        .ok()?;
+    let text = String::from_utf8_lossy(&reply.value).to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// `WM_CLASS` is "instance\0Class\0"; the class is the human-facing app name.
+fn wm_class_name(raw: &str) -> Option<String> {
+    let mut parts = raw.split('\0').filter(|p| !p.is_empty());
+    let instance = parts.next()?;
+    Some(parts.next().unwrap_or(instance).to_string())
+}
+
+/// Convert a 32-bit little-endian ZPixmap (B, G, R, X per pixel) to opaque RGBA.
+fn bgrx_to_rgba(data: &[u8], width: u32, height: u32) -> Option<RgbaImage> {
+    let len = width as usize * height as usize * 4;
+    let pixels = data.get(..len)?;
+    let mut out = Vec::with_capacity(len);
+    for px in pixels.chunks_exact(4) {
+        out.extend_from_slice(&[px[2], px[1], px[0], 255]);
+    }
+    RgbaImage::from_raw(width, height, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgrx_to_rgba_swaps_channels() {
+        let data = [10, 20, 30, 0, 1, 2, 3, 0];
+        let img = bgrx_to_rgba(&data, 2, 1).unwrap();
+        assert_eq!(img.get_pixel(0, 0).0, [30, 20, 10, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [3, 2, 1, 255]);
+        assert!(bgrx_to_rgba(&data, 2, 2).is_none());
+    }
+
+    #[test]
+    fn wm_class_name_prefers_class() {
+        assert_eq!(
+            wm_class_name("navigator\0Firefox\0").as_deref(),
+            Some("Firefox")
+        );
+        assert_eq!(wm_class_name("xterm\0").as_deref(), Some("xterm"));
+        assert!(wm_class_name("").is_none());
+    }
+}
@@ -0,0 +1,228 @@
+//! Click listener using XInput2 raw button events on the X11 root window.
+//!
+//! Raw events are delivered for every pointer press regardless of which client
+//! owns the window, without grabbing the pointer. They carry no coordinates, so
+//! the pointer position is queried when each press arrives.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::ConnectionExt as _;
+use x11rb::protocol::Event;
+
+use super::super::click_event::{ClickEvent, MouseButton};
+
+/// X doesn't report click counts; presses this close in time and space count up.
+const MULTI_CLICK_MS: i64 = 400;
+const MULTI_CLICK_SLOP: i32 = 4;
+
+/// A listener for global mouse click events on X11.
+pub struct ClickListener {
+    running: Arc<AtomicBool>,
+    receiver: Receiver<ClickEvent>,
+    _handle: JoinHandle<()>,
+}
+
+impl ClickListener {
+    /// Start listening for mouse clicks.
+    ///
+    /// Connects to the X server in a background thread and returns once raw
+    /// button events are selected. Fails without an X display or XInput2.
+    pub fn start() -> Result<Self, String> {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+        let (tx, rx) = mpsc::channel::<ClickEvent>();
+        let (setup_tx, setup_rx) = mpsc::channel::<Result<(), String>>();
+
+        let handle = thread::spawn(move || {
+            if let Err(e) = Self::run_event_loop(&running_clone, &tx, &setup_tx) {
+                let _ = setup_tx.send(Err(e));
+            }
+        });
+
+        match setup_rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(Ok(())) => Ok(Self {
+                running,
+                receiver: rx,
+                _handle: handle,
+            }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("Timeout waiting for X11 input setup".to_string()),
+        }
+    }
+
+    fn run_event_loop(
+        running: &AtomicBool,
+        tx: &Sender<ClickEvent>,
+        setup_tx: &Sender<Result<(), String>>,
+    ) -> Result<(), String> {
+        let (conn, screen_num) =
+            x11rb::connect(None).map_err(|e| format!("Cannot open X display: {e}"))?;
+        let root = conn.setup().roots[screen_num].root;
+        select_raw_presses(&conn, root)?;
+        let _ = setup_tx.send(Ok(()));
+
+        let mut last_press: Option<LastPress> = None;
+        while running.load(Ordering::SeqCst) {
+            let polled = conn.poll_for_event().map_err(|e| e.to_string())?;
+            let Some(Event::XinputRawButtonPress(raw)) = polled else {
+                if polled.is_none() {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                continue;
+            };
+            // 1 = left, 3 = right; 2 is middle, 4-7 are scroll wheel "buttons".
+            let button = match raw.detail {
+                1 => MouseButton::Left,
+                3 => MouseButton::Right,
+                _ => continue,
+            };
+            let Some(pointer) = conn.query_pointer(root).ok().and_then(|c| c.reply().ok()) else {
+                continue;
+            };
+            let point = (i32::from(pointer.root_x), i32::from(pointer.root_y));
+            let mut click = ClickEvent::new(point.0, point.1, button, 1);
+            let mut press = LastPress {
+                ts: click.timestamp_ms,
+                point,
+                button,
+                count: 1,
+            };
+            press.count = click_count(last_press.as_ref(), &press);
+            click.click_count = press.count;
+            last_press = Some(press);
+            let _ = tx.send(click);
+        }
+        Ok(())
+    }
+
+    /// Signal the listener to stop; the thread exits within one poll interval.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Drop every queued click event, e.g. the stale burst after a wake. Returns how
+    /// many were dropped.
+    pub fn clear_queue(&self) -> usize {
+        self.receiver.try_iter().count()
+    }
+
+    /// Receive a click event with timeout.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ClickEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// XInput2 has no equivalent of a disabled event tap.
+    pub fn tap_disabled_since(&self) -> Option<Instant> {
+        None
+    }
+}
+
+impl Drop for ClickListener {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Whether the X server supports XInput 2.0, required for global raw events.
+pub fn xinput2_available() -> bool {
+    let Ok((conn, _)) = x11rb::connect(None) else {
+        return false;
+    };
+    conn.xinput_xi_query_version(2, 0)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some_and(|v| v.major_version >= 2)
+}
+
+fn select_raw_presses(conn: &impl Connection, root: u32) -> Result<(), String> {
+    conn.xinput_xi_query_version(2, 0)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|_| "X server does not support XInput2".to_string())?;
+    conn.xinput_xi_select_events(
+        root,
+        &[xinput::EventMask {
+            deviceid: xinput::Device::ALL_MASTER.into(),
+            mask: vec![xinput::XIEventMask::RAW_BUTTON_PRESS],
+        }],
+    )
+    .map_err(|e| e.to_string())?
+    .check()
+    .map_err(|e| format!("Cannot select XInput2 events: {e}"))?;
+    conn.flush().map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastPress {
+    ts: i64,
+    point: (i32, i32),
+    button: MouseButton,
+    count: i64,
+}
+
+/// Click count for `press` given the previous press (1 = single, 2 = double, ...).
+fn click_count(previous: Option<&LastPress>, press: &LastPress) -> i64 {
+    match previous {
+        Some(prev)
+            if prev.button == press.button
+                && press.ts - prev.ts <= MULTI_CLICK_MS
+                && (press.point.0 - prev.point.0).abs() <= MULTI_CLICK_SLOP
+                && (press.point.1 - prev.point.1).abs() <= MULTI_CLICK_SLOP =>
+        {
+            prev.count + 1
+        }
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(ts: i64, x: i32, button: MouseButton) -> LastPress {
+        LastPress {
+            ts,
+            point: (x, 100),
+            button,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn click_count_counts_quick_presses_in_place() {
+        let first = press(1_000, 50, MouseButton::Left);
+        assert_eq!(click_count(None, &first), 1);
+        assert_eq!(
+            click_count(Some(&first), &press(1_250, 52, MouseButton::Left)),
+            2
+        );
+        let second = LastPress { count: 2, ..first };
+        assert_eq!(
+            click_count(Some(&second), &press(1_300, 50, MouseButton::Left)),
+            3
+        );
+    }
+
+    #[test]
+    fn click_count_resets_on_delay_distance_or_button() {
+        let first = press(1_000, 50, MouseButton::Left);
+        assert_eq!(
+            click_count(Some(&first), &press(1_500, 50, MouseButton::Left)),
+            1
+        );
+        assert_eq!(
+            click_count(Some(&first), &press(1_100, 80, MouseButton::Left)),
+            1
+        );
+        assert_eq!(
+            click_count(Some(&first), &press(1_100, 50, MouseButton::Right)),
+            1
+        );
+    }
+}
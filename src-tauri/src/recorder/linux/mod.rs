@@ -0,0 +1,24 @@
+//! Linux (X11) recorder backend.
+//!
+//! Global clicks come from XInput2 raw button events and screenshots from the X
+//! server's `GetImage`, so it works on any X11 session (and XWayland apps under
+//! Wayland only when the compositor forwards input). There is no accessibility
+//! metadata: steps carry the active window's title and `WM_CLASS` only.
+
+mod capture;
+mod click_listener;
+mod pipeline;
+
+pub use click_listener::ClickListener;
+pub use pipeline::process_click;
+
+/// Whether the X server lets us read other windows' pixels (always true on X11;
+/// false under a pure Wayland session without `DISPLAY`).
+pub fn can_capture_screen() -> bool {
+    x11rb::connect(None).is_ok()
+}
+
+/// Whether global click events can be observed, i.e. the server speaks XInput2.
+pub fn can_observe_input() -> bool {
+    click_listener::xinput2_available()
+}
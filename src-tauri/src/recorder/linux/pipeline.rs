@@ -0,0 +1,195 @@
+//! Click → active window → screenshot → step, without accessibility metadata.
+
+use x11rb::connection::Connection;
+
+use super::super::click_event::ClickEvent;
+use super::super::session::Session;
+use super::super::types::{ActionType, CaptureBounds, CaptureStatus, CaptureTimings, Step};
+use super::super::window_info::WindowBounds;
+use super::capture::{active_window, capture_region};
+use std::time::Instant;
+
+/// Presses within this many points of the previous step upgrade it to a double-click.
+const DOUBLE_CLICK_SLOP: i32 = 4;
+
+/// Record a step for `click`, capturing the focused window (or the whole screen).
+///
+/// Returns an error for clicks that don't produce a new step: clicks on StepCast
+/// itself and the second press of a double-click, which upgrades the previous step.
+pub fn process_click(click: &ClickEvent, session: &mut Session) -> Result<Step, String> {
+    session.diagnostics.clicks_received += 1;
+
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|e| format!("Cannot open X display: {e}"))?;
+    let screen = &conn.setup().roots[screen_num];
+    let root = screen.root;
+    let display = WindowBounds {
+        x: 0,
+        y: 0,
+        width: u32::from(screen.width_in_pixels),
+        height: u32::from(screen.height_in_pixels),
+    };
+
+    let window_started = Instant::now();
+    let active = active_window(&conn, root);
+    let window_resolve_ms = window_started.elapsed().as_millis() as u64;
+    if active
+        .as_ref()
+        .is_some_and(|w| w.pid == Some(std::process::id()))
+    {
+        session.diagnostics.clicks_filtered += 1;
+        return Err("click on own app".into());
+    }
+
+    if click.click_count >= 2 {
+        if let Some(last) = session.last_step_mut() {
+            let near = (last.x - click.x).abs() <= DOUBLE_CLICK_SLOP
+                && (last.y - click.y).abs() <= DOUBLE_CLICK_SLOP;
+            if near && last.action == ActionType::Click {
+                last.action = ActionType::DoubleClick;
+                return Err("upgraded previous step to double-click".into());
+            }
+            if near {
+                session.diagnostics.clicks_filtered += 1;
+                return Err("click debounced (too fast)".into());
+            }
+        }
+    }
+
+    let bounds = active
+        .as_ref()
+        .and_then(|w| clamp_to(&w.bounds, &display))
+        .unwrap_or_else(|| display.clone());
+    let step_id = session.next_step_id();
+    let path = session.screenshot_path(&step_id);
+    let capture_started = Instant::now();
+    let (capture_status, capture_error, screenshot_path) =
+        match capture_region(&conn, root, &bounds, &path) {
+            Ok(()) => (
+                CaptureStatus::Ok,
+                None,
+                Some(path.to_string_lossy().to_string()),
+            ),
+            Err(err) => {
+                session.diagnostics.captures_failed += 1;
+                session.diagnostics.failure_reasons.push(err.to_string());
+                (CaptureStatus::Failed, Some(err.to_string()), None)
+            }
+        };
+
+    // No accessibility lookup or pre-click buffer on Linux.
+    let timings = CaptureTimings {
+        window_resolve_ms: Some(window_resolve_ms),
+        capture_ms: Some(capture_started.elapsed().as_millis() as u64),
+        ..CaptureTimings::default()
+    };
+
+    let (app, window_title) = active
+        .map(|w| (w.app_name, w.title))
+        .unwrap_or_else(|| ("Desktop".to_string(), String::new()));
+    let step = Step {
+        id: step_id,
+        ts: click.timestamp_ms,
+        action: click.action(),
+        x: click.x,
+        y: click.y,
+        click_x_percent: percent_in(click.x, bounds.x, bounds.width),
+        click_y_percent: percent_in(click.y, bounds.y, bounds.height),
+        app,
+        window_title: if window_title.trim().is_empty() {
+            "Window".to_string()
+        } else {
+            window_title.clone()
+        },
+        screenshot_path,
+        note: None,
+        description: None,
+        description_source: None,
+        description_status: None,
+        description_error: None,
+        ax: None,
+        capture_status: Some(capture_status),
+        capture_error,
+        crop_region: None,
+        is_secure_placeholder: false,
+        drag_end: None,
+        context_title: Some(window_title).filter(|t| !t.trim().is_empty()),
+        capture_bounds: Some(CaptureBounds {
+            x: bounds.x,
+            y: bounds.y,
+            width: bounds.width,
+            height: bounds.height,
+            display_width: display.width as i32,
+            display_height: display.height as i32,
+        }),
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: None,
+        annotations: Vec::new(),
+        capture_timings: Some(timings),
+        page_url: None,
+        keys: None,
+        title: None,
+    };
+    session.add_step(step.clone());
+    Ok(step)
+}
+
+/// Intersection of `bounds` with the screen, or None when the window is off-screen.
+fn clamp_to(bounds: &WindowBounds, screen: &WindowBounds) -> Option<WindowBounds> {
+    let left = bounds.x.max(screen.x);
+    let top = bounds.y.max(screen.y);
+    let right = (bounds.x + bounds.width as i32).min(screen.x + screen.width as i32);
+    let bottom = (bounds.y + bounds.height as i32).min(screen.y + screen.height as i32);
+    (right > left && bottom > top).then(|| WindowBounds {
+        x: left,
+        y: top,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+    })
+}
+
+fn percent_in(coord: i32, offset: i32, size: u32) -> f32 {
+    if size == 0 {
+        return 0.0;
+    }
+    ((coord - offset) as f32 / size as f32 * 100.0).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn clamp_to_keeps_window_on_screen() {
+        let screen = rect(0, 0, 1920, 1080);
+        let inside = clamp_to(&rect(100, 100, 800, 600), &screen).unwrap();
+        assert_eq!((inside.x, inside.width), (100, 800));
+        let hanging = clamp_to(&rect(-50, 900, 400, 400), &screen).unwrap();
+        assert_eq!(
+            (hanging.x, hanging.y, hanging.width, hanging.height),
+            (0, 900, 350, 180)
+        );
+        assert!(clamp_to(&rect(2000, 0, 100, 100), &screen).is_none());
+    }
+
+    #[test]
+    fn percent_in_is_relative_to_capture() {
+        assert_eq!(percent_in(500, 100, 800), 50.0);
+        assert_eq!(percent_in(0, 100, 800), 0.0);
+        assert_eq!(percent_in(10, 0, 0), 0.0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Recovery for a click listener whose event tap stopped delivering events.
+//!
+//! macOS disables a CGEventTap when its callback is too slow or after events like
+//! screen lock and fast user switching, and recording then silently stops. The tap
+//! thread re-enables it as soon as the callback reports the disable; if that keeps
+//! failing, the processing loop replaces the whole listener.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// A listener whose tap stayed disabled, with no clicks, for this long is recreated.
+pub const LISTENER_RESTART_AFTER: Duration = Duration::from_secs(5);
+
+/// The event tap calls the watchdog needs, so the policy can be tested without
+/// CoreGraphics.
+pub trait EventTapControl {
+    /// Whether the tap currently delivers events.
+    fn is_enabled(&self) -> bool;
+    /// Ask the system to deliver events to the tap again.
+    fn enable(&self);
+}
+
+/// Tracks how long the tap has been disabled and re-enables it.
+#[derive(Debug, Default)]
+pub struct TapWatchdog {
+    disabled_since: Option<Instant>,
+}
+
+impl TapWatchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-enable `tap` when it is disabled. Returns since when it has been disabled
+    /// if re-enabling didn't take, `None` once it delivers events again.
+    pub fn poll(&mut self, tap: &impl EventTapControl, now: Instant) -> Option<Instant> {
+        if !tap.is_enabled() {
+            let since = *self.disabled_since.get_or_insert(now);
+            tap.enable();
+            if !tap.is_enabled() {
+                return Some(since);
+            }
+        }
+        self.disabled_since = None;
+        None
+    }
+}
+
+/// Whether the processing loop should tear down and recreate the click listener:
+/// its tap has been disabled, and no click arrived, for longer than `after`.
+pub fn should_restart_listener(
+    disabled_since: Option<Instant>,
+    last_click: Instant,
+    now: Instant,
+    after: Duration,
+) -> bool {
+    disabled_since.is_some_and(|since| now.saturating_duration_since(since) > after)
+        && now.saturating_duration_since(last_click) > after
+}
+
+/// Payload of the `recorder-health` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RecorderHealth {
+    /// The click listener was recreated and records clicks again.
+    Recovered { listener_restarts: u32 },
+    /// Recreating the listener failed; it is retried after another interval.
+    ListenerFailed { error: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// A tap that comes back on `enable` unless `stuck`.
+    #[derive(Default)]
+    struct MockTap {
+        enabled: Cell<bool>,
+        stuck: bool,
+        enable_calls: Cell<u32>,
+    }
+
+    impl EventTapControl for MockTap {
+        fn is_enabled(&self) -> bool {
+            self.enabled.get()
+        }
+
+        fn enable(&self) {
+            self.enable_calls.set(self.enable_calls.get() + 1);
+            if !self.stuck {
+                self.enabled.set(true);
+            }
+        }
+    }
+
+    #[test]
+    fn enabled_tap_is_left_alone() {
+        let tap = MockTap {
+            enabled: Cell::new(true),
+            ..MockTap::default()
+        };
+        let mut watchdog = TapWatchdog::new();
+        assert_eq!(watchdog.poll(&tap, Instant::now()), None);
+        assert_eq!(tap.enable_calls.get(), 0);
+    }
+
+    #[test]
+    fn disabled_tap_is_reenabled() {
+        let tap = MockTap::default();
+        let mut watchdog = TapWatchdog::new();
+        assert_eq!(watchdog.poll(&tap, Instant::now()), None);
+        assert_eq!(tap.enable_calls.get(), 1);
+        assert!(tap.is_enabled());
+    }
+
+    #[test]
+    fn stuck_tap_reports_when_it_was_first_disabled() {
+        let tap = MockTap {
+            stuck: true,
+            ..MockTap::default()
+        };
+        let mut watchdog = TapWatchdog::new();
+        let start = Instant::now();
+        assert_eq!(watchdog.poll(&tap, start), Some(start));
+        assert_eq!(
+            watchdog.poll(&tap, start + Duration::from_secs(3)),
+            Some(start)
+        );
+        assert_eq!(tap.enable_calls.get(), 2);
+
+        // Recovers on its own: the next disable starts a new interval.
+        tap.enabled.set(true);
+        assert_eq!(watchdog.poll(&tap, start + Duration::from_secs(4)), None);
+        tap.enabled.set(false);
+        let later = start + Duration::from_secs(10);
+        assert_eq!(watchdog.poll(&tap, later), Some(later));
+    }
+
+    #[test]
+    fn restarts_only_when_disabled_and_quiet_for_long_enough() {
+        let start = Instant::now();
+        let after = LISTENER_RESTART_AFTER;
+        let later = start + after + Duration::from_millis(1);
+
+        assert!(should_restart_listener(Some(start), start, later, after));
+        // Healthy tap.
+        assert!(!should_restart_listener(None, start, later, after));
+        // Disabled only briefly.
+        assert!(!should_restart_listener(
+            Some(later - Duration::from_secs(1)),
+            start,
+            later,
+            after
+        ));
+        // Clicks still arrive, so the listener works despite the report.
+        assert!(!should_restart_listener(
+            Some(start),
+            later - Duration::from_secs(1),
+            later,
+            after
+        ));
+    }
+
+    #[test]
+    fn health_serializes_with_a_status_tag() {
+        assert_eq!(
+            serde_json::to_string(&RecorderHealth::Recovered {
+                listener_restarts: 2
+            })
+            .unwrap(),
+            r#"{"status":"recovered","listener_restarts":2}"#
+        );
+    }
+}
@@ -1,12 +1,27 @@
 mod ax_helpers;
+pub mod browser;
+pub mod bundle;
 pub mod capture;
 pub mod cg_capture;
 pub mod click_event;
+#[cfg(target_os = "macos")]
 pub mod click_listener;
+pub mod dedup;
+pub mod exclusions;
+pub mod idle_timeout;
+pub mod interruption;
+pub mod key_combo;
+#[cfg(target_os = "linux")]
+pub mod linux;
+pub mod listener_watchdog;
 pub mod macos_screencapture;
+pub mod permission_watchdog;
+pub mod phash;
 pub mod pipeline;
 pub mod pre_click_buffer;
 pub mod session;
+pub mod session_registry;
+pub mod space_observer;
 pub mod state;
 pub mod storage;
 pub mod types;
@@ -0,0 +1,128 @@
+//! Periodic permission re-check while recording.
+//!
+//! Revoking Screen Recording (or Accessibility) in System Settings mid-session
+//! doesn't notify the app; every capture after that just fails. The processing loop
+//! polls the permissions every few seconds and pauses the recorder when one is gone.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// How often permissions are re-checked while recording.
+pub const PERMISSION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A permission the recorder needs. Sent as the `permission-lost` event payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ScreenRecording,
+    Accessibility,
+}
+
+/// Rate-limits permission checks to one per interval.
+pub struct PermissionWatchdog {
+    interval: Duration,
+    last_check: Option<Instant>,
+}
+
+impl PermissionWatchdog {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_check: None,
+        }
+    }
+
+    /// Check on the next poll regardless of the interval, e.g. after a capture failed.
+    pub fn check_soon(&mut self) {
+        self.last_check = None;
+    }
+
+    /// Run `missing` when a check is due and return the permission it reports gone.
+    pub fn poll(
+        &mut self,
+        now: Instant,
+        missing: impl FnOnce() -> Option<Permission>,
+    ) -> Option<Permission> {
+        if self
+            .last_check
+            .is_some_and(|last| now.saturating_duration_since(last) < self.interval)
+        {
+            return None;
+        }
+        self.last_check = Some(now);
+        missing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::state::{RecorderState, SessionState};
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn checks_at_most_once_per_interval() {
+        let mut watchdog = PermissionWatchdog::new(PERMISSION_CHECK_INTERVAL);
+        let checks = Cell::new(0);
+        let check = || {
+            checks.set(checks.get() + 1);
+            None
+        };
+        let start = Instant::now();
+
+        watchdog.poll(start, check);
+        watchdog.poll(start + Duration::from_secs(2), check);
+        watchdog.poll(start + Duration::from_millis(4_999), check);
+        assert_eq!(checks.get(), 1);
+
+        watchdog.poll(start + Duration::from_secs(5), check);
+        assert_eq!(checks.get(), 2);
+
+        watchdog.check_soon();
+        watchdog.poll(start + Duration::from_secs(6), check);
+        assert_eq!(checks.get(), 3);
+    }
+
+    #[test]
+    fn revoked_permission_pauses_recording_until_resumed() {
+        let mut recorder = RecorderState::new();
+        recorder.start().unwrap();
+        let mut watchdog = PermissionWatchdog::new(PERMISSION_CHECK_INTERVAL);
+        let granted = Cell::new(true);
+        let missing = || (!granted.get()).then_some(Permission::ScreenRecording);
+        let start = Instant::now();
+
+        assert_eq!(watchdog.poll(start, missing), None);
+        assert_eq!(recorder.current_state(), SessionState::Recording);
+
+        granted.set(false);
+        let lost = watchdog.poll(start + PERMISSION_CHECK_INTERVAL, missing);
+        assert_eq!(lost, Some(Permission::ScreenRecording));
+        assert!(recorder.on_permission_lost());
+        assert_eq!(recorder.current_state(), SessionState::Paused);
+
+        // A second detection while paused changes nothing; unlock doesn't prompt to resume.
+        assert!(!recorder.on_permission_lost());
+        assert!(!recorder.on_interruption_ended());
+        assert_eq!(recorder.current_state(), SessionState::Paused);
+
+        // Re-granted: resume_recording re-checks and resumes.
+        granted.set(true);
+        recorder.resume().unwrap();
+        let later = start + PERMISSION_CHECK_INTERVAL * 2;
+        assert_eq!(watchdog.poll(later, missing), None);
+        assert_eq!(recorder.current_state(), SessionState::Recording);
+    }
+
+    #[test]
+    fn permission_serializes_as_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&Permission::ScreenRecording).unwrap(),
+            r#""screen_recording""#
+        );
+        assert_eq!(
+            serde_json::to_string(&Permission::Accessibility).unwrap(),
+            r#""accessibility""#
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Perceptual hashing for comparing screenshots.
+//!
+//! A difference hash (dHash): shrink to 9×8 grayscale and record whether each pixel
+//! is brighter than its right neighbour. Robust to scaling, compression noise and
+//! small brightness shifts; two captures of the same screen differ by a few bits.
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+use std::path::Path;
+
+/// 64-bit difference hash of `img`.
+pub fn dhash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+/// Hash of the image at `path`, or None if it can't be read.
+pub fn dhash_file(path: &Path) -> Option<u64> {
+    image::open(path).ok().map(|img| dhash(&img))
+}
+
+/// Number of differing bits; 0 means visually identical at hash resolution.
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn gradient(width: u32, height: u32, offset: u8) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            let v =
+                ((x * 255 / width) as u8).saturating_add(offset) ^ ((y * 8 / height) as u8 * 16);
+            Rgba([v, v, v, 255])
+        }))
+    }
+
+    #[test]
+    fn identical_images_hash_equal() {
+        let img = gradient(320, 200, 0);
+        assert_eq!(distance(dhash(&img), dhash(&img.clone())), 0);
+    }
+
+    #[test]
+    fn resize_and_brightness_stay_close() {
+        let base = dhash(&gradient(320, 200, 0));
+        assert!(distance(base, dhash(&gradient(640, 400, 0))) <= 4);
+        assert!(distance(base, dhash(&gradient(320, 200, 6))) <= 6);
+    }
+
+    #[test]
+    fn different_content_hashes_far_apart() {
+        let base = dhash(&gradient(320, 200, 0));
+        let flipped =
+            DynamicImage::ImageRgba8(image::imageops::flip_horizontal(&gradient(320, 200, 0)));
+        assert!(distance(base, dhash(&flipped)) > 20);
+    }
+
+    #[test]
+    fn distance_counts_bits() {
+        assert_eq!(distance(0b1010, 0b1010), 0);
+        assert_eq!(distance(0b1010, 0b0101), 4);
+        assert_eq!(distance(0, u64::MAX), 64);
+    }
+}
@@ -0,0 +1,623 @@
+//! Rule-based step descriptions for machines without Apple Intelligence: the clicked
+//! element's AX role and label, the app and the window are usually enough for
+//! "Click the "Save" button in the "Export" dialog of Pages".
+//!
+//! Written at capture time with [`DescriptionSource::Auto`]; AI generation may replace
+//! them, manual edits win over both.
+
+use super::super::types::{ActionType, AxClickInfo, DescriptionSource, Step};
+use super::WindowControlKind;
+use crate::i18n::{self, Locale};
+
+/// Longest element label quoted in a description; longer ones are cut with "…".
+const MAX_LABEL_CHARS: usize = 60;
+
+/// What was clicked, as far as the description cares.
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Button(String),
+    MenuItem(String),
+    MenuBarItem(String),
+    /// `toggle` is what the click did, when the state before or after it is known.
+    Checkbox {
+        label: String,
+        toggle: Option<Toggle>,
+    },
+    RadioButton(String),
+    Tab(String),
+    PopUpButton(String),
+    Link(String),
+    TextField(String),
+    DockItem(String),
+    WindowControl(WindowControlKind),
+    Other(String),
+}
+
+/// Give `step` its rule-based description unless it already has one.
+pub fn apply_auto_description(step: &mut Step, locale: Locale) {
+    if step.description.is_some() {
+        return;
+    }
+    if let Some(description) = describe_step(step, locale) {
+        step.description = Some(description);
+        step.description_source = Some(DescriptionSource::Auto);
+    }
+}
+
+/// Describe a recorded click, or `None` when the AX data says too little to beat the
+/// export's "Clicked in App — "Window"" baseline.
+pub fn describe_step(step: &Step, locale: Locale) -> Option<String> {
+    if step.is_auth_placeholder() {
+        return None;
+    }
+    if let Some(keys) = step
+        .keys
+        .as_deref()
+        .filter(|_| step.action == ActionType::Shortcut)
+    {
+        return Some(format!(
+            "{}{}",
+            i18n::step_action_press_keys(locale, keys),
+            app_context(step)
+        ));
+    }
+    let verb = match step.action {
+        ActionType::Click => Verb::Click,
+        ActionType::DoubleClick => Verb::DoubleClick,
+        ActionType::RightClick => Verb::RightClick,
+        ActionType::Hover => Verb::Point,
+        _ => return None,
+    };
+    let target = classify(step)?;
+    let action = describe_target(&target, verb, locale);
+    Some(match target {
+        Target::DockItem(_) => action,
+        Target::MenuItem(_) | Target::MenuBarItem(_) => {
+            format!("{action}{}", app_context(step))
+        }
+        _ => format!("{action}{}", window_context(step, locale)),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verb {
+    Click,
+    DoubleClick,
+    RightClick,
+    /// Hover shortcut: the pointer rests on the element.
+    Point,
+}
+
+fn classify(step: &Step) -> Option<Target> {
+    let ax = step.ax.as_ref()?;
+    let subrole = ax.subrole.as_deref().unwrap_or("");
+    if let Some(kind) = WindowControlKind::from_subrole(subrole) {
+        return Some(Target::WindowControl(kind));
+    }
+    if ax.role == "AXDockItem" || subrole == "AXApplicationDockItem" {
+        return clean_label(&ax.label).map(Target::DockItem);
+    }
+    if matches!(
+        ax.role.as_str(),
+        "AXTextField" | "AXTextArea" | "AXComboBox"
+    ) {
+        let label =
+            clean_label(&ax.label).or_else(|| ax.placeholder.as_deref().and_then(clean_label));
+        return label.map(Target::TextField);
+    }
+
+    let label = clean_label(&ax.label)?;
+    Some(match (ax.role.as_str(), subrole) {
+        (_, "AXTabButton") | ("AXTab", _) => Target::Tab(label),
+        ("AXMenuItem", _) => Target::MenuItem(label),
+        ("AXMenuBarItem", _) => Target::MenuBarItem(label),
+        ("AXCheckBox" | "AXSwitch" | "AXToggle", _) | (_, "AXSwitch" | "AXToggle") => {
+            Target::Checkbox {
+                label,
+                toggle: toggle_outcome(ax.was_checked.or(ax.is_checked), ax.now_checked),
+            }
+        }
+        ("AXRadioButton", _) => Target::RadioButton(label),
+        ("AXPopUpButton" | "AXMenuButton", _) => Target::PopUpButton(label),
+        ("AXButton", _) => Target::Button(label),
+        ("AXLink", _) => Target::Link(label),
+        _ => Target::Other(label),
+    })
+}
+
+/// Trimmed, single-line label; `None` when empty.
+fn clean_label(raw: &str) -> Option<String> {
+    let label = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if label.is_empty() {
+        return None;
+    }
+    if label.chars().count() <= MAX_LABEL_CHARS {
+        return Some(label);
+    }
+    let cut: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+    Some(format!("{}…", cut.trim_end()))
+}
+
+fn describe_target(target: &Target, verb: Verb, locale: Locale) -> String {
+    match (locale, target, verb) {
+        // Targets with their own verb for a plain click.
+        (Locale::En, Target::MenuItem(l), Verb::Click) => format!("Choose \"{l}\" from the menu"),
+        (Locale::De, Target::MenuItem(l), Verb::Click) => format!("Wähle „{l}“ im Menü"),
+        (Locale::En, Target::MenuBarItem(l), Verb::Click) => format!("Open the \"{l}\" menu"),
+        (Locale::De, Target::MenuBarItem(l), Verb::Click) => format!("Öffne das Menü „{l}“"),
+        (
+            Locale::En,
+            Target::Checkbox {
+                label,
+                toggle: Some(toggle),
+            },
+            Verb::Click,
+        ) => match toggle {
+            Toggle::TurnedOn => format!("Turn on \"{label}\""),
+            Toggle::TurnedOff => format!("Turn off \"{label}\""),
+            Toggle::StaysOn => format!("Make sure \"{label}\" is turned on"),
+            Toggle::StaysOff => format!("Make sure \"{label}\" is turned off"),
+        },
+        (
+            Locale::De,
+            Target::Checkbox {
+                label,
+                toggle: Some(toggle),
+            },
+            Verb::Click,
+        ) => match toggle {
+            Toggle::TurnedOn => format!("Aktiviere „{label}“"),
+            Toggle::TurnedOff => format!("Deaktiviere „{label}“"),
+            Toggle::StaysOn => format!("Stelle sicher, dass „{label}“ aktiviert ist"),
+            Toggle::StaysOff => format!("Stelle sicher, dass „{label}“ deaktiviert ist"),
+        },
+        (Locale::En, Target::RadioButton(l), Verb::Click) => format!("Select \"{l}\""),
+        (Locale::De, Target::RadioButton(l), Verb::Click) => format!("Wähle „{l}“"),
+        (Locale::En, Target::Tab(l), Verb::Click) => format!("Switch to the \"{l}\" tab"),
+        (Locale::De, Target::Tab(l), Verb::Click) => format!("Wechsle zum Tab „{l}“"),
+        (Locale::En, Target::PopUpButton(l), Verb::Click) => {
+            format!("Open the \"{l}\" pop-up menu")
+        }
+        (Locale::De, Target::PopUpButton(l), Verb::Click) => {
+            format!("Öffne das Einblendmenü „{l}“")
+        }
+        (Locale::En, Target::DockItem(l), Verb::Click) => format!("Open {l} from the Dock"),
+        (Locale::De, Target::DockItem(l), Verb::Click) => format!("Öffne {l} im Dock"),
+        (Locale::En, Target::WindowControl(kind), Verb::Click) => {
+            format!("Click the \"{}\" button", kind.label())
+        }
+        (Locale::De, Target::WindowControl(kind), Verb::Click) => {
+            format!(
+                "Klicke auf die Schaltfläche „{}“",
+                i18n::window_control_label(Locale::De, *kind)
+            )
+        }
+        // Everything else: verb + the element.
+        (Locale::En, _, _) => {
+            let verb = match verb {
+                Verb::Click => "Click",
+                Verb::DoubleClick => "Double-click",
+                Verb::RightClick => "Right-click",
+                Verb::Point => "Point at",
+            };
+            format!("{verb} {}", element_en(target))
+        }
+        (Locale::De, _, _) => {
+            let verb = match verb {
+                Verb::Click => "Klicke",
+                Verb::DoubleClick => "Doppelklicke",
+                Verb::RightClick => "Klicke mit der rechten Maustaste",
+                Verb::Point => "Zeige",
+            };
+            let preposition = if matches!(target, Target::TextField(_)) && verb == "Klicke" {
+                "in"
+            } else {
+                "auf"
+            };
+            format!("{verb} {preposition} {}", element_de(target))
+        }
+    }
+}
+
+/// What a click did to a checkbox or switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Toggle {
+    TurnedOn,
+    TurnedOff,
+    /// Clicked, but the state read back is the one it had: the app refused the change
+    /// or reverted it. The step still tells readers which state to end up with.
+    StaysOn,
+    StaysOff,
+}
+
+/// Outcome of a click given the states before and after it. Without the state after
+/// the click (the element went away), the click is taken to have flipped it.
+fn toggle_outcome(was: Option<bool>, now: Option<bool>) -> Option<Toggle> {
+    Some(match (was, now) {
+        (Some(true), Some(true)) => Toggle::StaysOn,
+        (Some(false), Some(false)) => Toggle::StaysOff,
+        (_, Some(true)) | (Some(false), None) => Toggle::TurnedOn,
+        (_, Some(false)) | (Some(true), None) => Toggle::TurnedOff,
+        (None, None) => return None,
+    })
+}
+
+fn element_en(target: &Target) -> String {
+    match target {
+        Target::Button(l) => format!("the \"{l}\" button"),
+        Target::MenuItem(l) => format!("the \"{l}\" menu item"),
+        Target::MenuBarItem(l) => format!("the \"{l}\" menu"),
+        Target::Checkbox { label, .. } => format!("the \"{label}\" checkbox"),
+        Target::RadioButton(l) => format!("the \"{l}\" option"),
+        Target::Tab(l) => format!("the \"{l}\" tab"),
+        Target::PopUpButton(l) => format!("the \"{l}\" pop-up menu"),
+        Target::Link(l) => format!("the \"{l}\" link"),
+        Target::TextField(l) => format!("the \"{l}\" field"),
+        Target::DockItem(l) => format!("{l} in the Dock"),
+        Target::WindowControl(kind) => format!("the \"{}\" button", kind.label()),
+        Target::Other(l) => format!("\"{l}\""),
+    }
+}
+
+fn element_de(target: &Target) -> String {
+    match target {
+        Target::Button(l) => format!("die Schaltfläche „{l}“"),
+        Target::MenuItem(l) => format!("den Menüeintrag „{l}“"),
+        Target::MenuBarItem(l) => format!("das Menü „{l}“"),
+        Target::Checkbox { label, .. } => format!("das Kontrollkästchen „{label}“"),
+        Target::RadioButton(l) => format!("die Option „{l}“"),
+        Target::Tab(l) => format!("den Tab „{l}“"),
+        Target::PopUpButton(l) => format!("das Einblendmenü „{l}“"),
+        Target::Link(l) => format!("den Link „{l}“"),
+        Target::TextField(l) => format!("das Feld „{l}“"),
+        Target::DockItem(l) => format!("{l} im Dock"),
+        Target::WindowControl(kind) => {
+            format!(
+                "die Schaltfläche „{}“",
+                i18n::window_control_label(Locale::De, *kind)
+            )
+        }
+        Target::Other(l) => format!("„{l}“"),
+    }
+}
+
+/// " in Pages", the same in English and German; empty without an app name.
+fn app_context(step: &Step) -> String {
+    let app = step.app.trim();
+    if app.is_empty() {
+        return String::new();
+    }
+    format!(" in {app}")
+}
+
+/// " in the "Export" dialog of Pages" for dialogs and sheets, else [`app_context`].
+fn window_context(step: &Step, locale: Locale) -> String {
+    let app = step.app.trim();
+    let in_dialog = step.ax.as_ref().is_some_and(is_dialog);
+    if !in_dialog || app.is_empty() {
+        return app_context(step);
+    }
+    let title = step.window_title.trim();
+    match (locale, title.is_empty()) {
+        (Locale::En, false) => format!(" in the \"{title}\" dialog of {app}"),
+        (Locale::En, true) => format!(" in a dialog of {app}"),
+        (Locale::De, false) => format!(" im Dialog „{title}“ von {app}"),
+        (Locale::De, true) => format!(" in einem Dialog von {app}"),
+    }
+}
+
+fn is_dialog(ax: &AxClickInfo) -> bool {
+    let dialog =
+        |value: Option<&str>| matches!(value, Some("AXDialog" | "AXSystemDialog" | "AXSheet"));
+    ax.parent_dialog_role.is_some()
+        || dialog(ax.window_subrole.as_deref())
+        || dialog(ax.window_role.as_deref())
+        || dialog(ax.top_level_subrole.as_deref())
+        || dialog(ax.top_level_role.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ax(role: &str, label: &str) -> AxClickInfo {
+        AxClickInfo {
+            role: role.to_string(),
+            subrole: None,
+            role_description: None,
+            identifier: None,
+            label: label.to_string(),
+            element_bounds: None,
+            container_role: None,
+            container_subrole: None,
+            container_identifier: None,
+            container_bounds: None,
+            window_role: None,
+            window_subrole: None,
+            top_level_role: None,
+            top_level_subrole: None,
+            parent_dialog_role: None,
+            parent_dialog_subrole: None,
+            is_checked: None,
+            was_checked: None,
+            now_checked: None,
+            value: None,
+            placeholder: None,
+            is_cancel_button: false,
+            is_default_button: false,
+            menu_path: None,
+            accessibility_label: None,
+            help: None,
+        }
+    }
+
+    fn step(action: ActionType, ax: AxClickInfo) -> Step {
+        let mut step = Step::sample();
+        step.action = action;
+        step.app = "Pages".to_string();
+        step.window_title = "Export".to_string();
+        step.ax = Some(ax);
+        step
+    }
+
+    fn dialog(mut info: AxClickInfo) -> AxClickInfo {
+        info.window_subrole = Some("AXDialog".to_string());
+        info
+    }
+
+    fn with_subrole(mut info: AxClickInfo, subrole: &str) -> AxClickInfo {
+        info.subrole = Some(subrole.to_string());
+        info
+    }
+
+    fn checked(mut info: AxClickInfo, value: bool) -> AxClickInfo {
+        info.is_checked = Some(value);
+        info
+    }
+
+    #[test]
+    fn describes_shortcuts_by_their_keys() {
+        let mut shortcut = Step::sample();
+        shortcut.action = ActionType::Shortcut;
+        shortcut.app = "Pages".to_string();
+        shortcut.keys = Some("⌘S".to_string());
+        assert_eq!(
+            describe_step(&shortcut, Locale::En).as_deref(),
+            Some("Press ⌘S in Pages")
+        );
+        assert_eq!(
+            describe_step(&shortcut, Locale::De).as_deref(),
+            Some("Drücke ⌘S in Pages")
+        );
+        shortcut.keys = None;
+        assert_eq!(describe_step(&shortcut, Locale::En), None);
+    }
+
+    #[test]
+    fn describes_roles_in_english_and_german() {
+        use ActionType::{Click, DoubleClick, Hover, RightClick};
+        let cases: Vec<(ActionType, AxClickInfo, &str, &str)> = vec![
+            (
+                Click,
+                dialog(ax("AXButton", "Save")),
+                "Click the \"Save\" button in the \"Export\" dialog of Pages",
+                "Klicke auf die Schaltfläche „Save“ im Dialog „Export“ von Pages",
+            ),
+            (
+                Click,
+                ax("AXButton", "Share"),
+                "Click the \"Share\" button in Pages",
+                "Klicke auf die Schaltfläche „Share“ in Pages",
+            ),
+            (
+                DoubleClick,
+                ax("AXButton", "Share"),
+                "Double-click the \"Share\" button in Pages",
+                "Doppelklicke auf die Schaltfläche „Share“ in Pages",
+            ),
+            (
+                Click,
+                ax("AXMenuItem", "Export To…"),
+                "Choose \"Export To…\" from the menu in Pages",
+                "Wähle „Export To…“ im Menü in Pages",
+            ),
+            (
+                Click,
+                ax("AXMenuBarItem", "File"),
+                "Open the \"File\" menu in Pages",
+                "Öffne das Menü „File“ in Pages",
+            ),
+            (
+                Click,
+                checked(ax("AXCheckBox", "Include comments"), false),
+                "Turn on \"Include comments\" in Pages",
+                "Aktiviere „Include comments“ in Pages",
+            ),
+            (
+                Click,
+                checked(ax("AXCheckBox", "Include comments"), true),
+                "Turn off \"Include comments\" in Pages",
+                "Deaktiviere „Include comments“ in Pages",
+            ),
+            (
+                Click,
+                ax("AXCheckBox", "Include comments"),
+                "Click the \"Include comments\" checkbox in Pages",
+                "Klicke auf das Kontrollkästchen „Include comments“ in Pages",
+            ),
+            (
+                Click,
+                dialog(ax("AXPopUpButton", "Format")),
+                "Open the \"Format\" pop-up menu in the \"Export\" dialog of Pages",
+                "Öffne das Einblendmenü „Format“ im Dialog „Export“ von Pages",
+            ),
+            (
+                Click,
+                with_subrole(ax("AXDockItem", "Safari"), "AXApplicationDockItem"),
+                "Open Safari from the Dock",
+                "Öffne Safari im Dock",
+            ),
+            (
+                Click,
+                with_subrole(ax("AXButton", ""), "AXCloseButton"),
+                "Click the \"Close\" button in Pages",
+                "Klicke auf die Schaltfläche „Schließen“ in Pages",
+            ),
+            (
+                Click,
+                with_subrole(ax("AXButton", ""), "AXMinimizeButton"),
+                "Click the \"Minimize\" button in Pages",
+                "Klicke auf die Schaltfläche „Minimieren“ in Pages",
+            ),
+            (
+                Click,
+                with_subrole(ax("AXRadioButton", "Layout"), "AXTabButton"),
+                "Switch to the \"Layout\" tab in Pages",
+                "Wechsle zum Tab „Layout“ in Pages",
+            ),
+            (
+                Click,
+                ax("AXRadioButton", "Portrait"),
+                "Select \"Portrait\" in Pages",
+                "Wähle „Portrait“ in Pages",
+            ),
+            (
+                Click,
+                ax("AXLink", "Learn more"),
+                "Click the \"Learn more\" link in Pages",
+                "Klicke auf den Link „Learn more“ in Pages",
+            ),
+            (
+                Click,
+                ax("AXTextField", "  File\n name "),
+                "Click the \"File name\" field in Pages",
+                "Klicke in das Feld „File name“ in Pages",
+            ),
+            (
+                RightClick,
+                ax("AXStaticText", "Chapter 1"),
+                "Right-click \"Chapter 1\" in Pages",
+                "Klicke mit der rechten Maustaste auf „Chapter 1“ in Pages",
+            ),
+            (
+                Hover,
+                ax("AXButton", "Share"),
+                "Point at the \"Share\" button in Pages",
+                "Zeige auf die Schaltfläche „Share“ in Pages",
+            ),
+            (
+                Hover,
+                ax("AXTextField", "File name"),
+                "Point at the \"File name\" field in Pages",
+                "Zeige auf das Feld „File name“ in Pages",
+            ),
+            (
+                Hover,
+                with_subrole(ax("AXDockItem", "Safari"), "AXApplicationDockItem"),
+                "Point at Safari in the Dock",
+                "Zeige auf Safari im Dock",
+            ),
+        ];
+
+        for (action, info, en, de) in cases {
+            let step = step(action, info);
+            assert_eq!(describe_step(&step, Locale::En).as_deref(), Some(en));
+            assert_eq!(describe_step(&step, Locale::De).as_deref(), Some(de));
+        }
+    }
+
+    #[test]
+    fn describes_toggle_state_before_and_after_the_click() {
+        let cases = [
+            (
+                false,
+                true,
+                "Turn on \"Use dark menu bar\" in Pages",
+                "Aktiviere „Use dark menu bar“ in Pages",
+            ),
+            (
+                true,
+                false,
+                "Turn off \"Use dark menu bar\" in Pages",
+                "Deaktiviere „Use dark menu bar“ in Pages",
+            ),
+            (
+                true,
+                true,
+                "Make sure \"Use dark menu bar\" is turned on in Pages",
+                "Stelle sicher, dass „Use dark menu bar“ aktiviert ist in Pages",
+            ),
+            (
+                false,
+                false,
+                "Make sure \"Use dark menu bar\" is turned off in Pages",
+                "Stelle sicher, dass „Use dark menu bar“ deaktiviert ist in Pages",
+            ),
+        ];
+        for (was, now, en, de) in cases {
+            let mut info = with_subrole(ax("AXCheckBox", "Use dark menu bar"), "AXSwitch");
+            info.is_checked = Some(was);
+            info.was_checked = Some(was);
+            info.now_checked = Some(now);
+            let step = step(ActionType::Click, info);
+            assert_eq!(describe_step(&step, Locale::En).as_deref(), Some(en));
+            assert_eq!(describe_step(&step, Locale::De).as_deref(), Some(de));
+        }
+    }
+
+    #[test]
+    fn text_field_falls_back_to_placeholder() {
+        let mut info = ax("AXTextField", "");
+        info.placeholder = Some("name@example.com".to_string());
+        assert_eq!(
+            describe_step(&step(ActionType::Click, info), Locale::En).as_deref(),
+            Some("Click the \"name@example.com\" field in Pages")
+        );
+    }
+
+    #[test]
+    fn skips_steps_without_usable_ax_data() {
+        assert_eq!(
+            describe_step(&step(ActionType::Click, ax("AXButton", " ")), Locale::En),
+            None
+        );
+        assert_eq!(
+            describe_step(&step(ActionType::Drag, ax("AXButton", "Save")), Locale::En),
+            None
+        );
+
+        let mut no_ax = Step::sample();
+        no_ax.ax = None;
+        assert_eq!(describe_step(&no_ax, Locale::En), None);
+
+        let mut auth = step(ActionType::Click, ax("AXButton", "Unlock"));
+        auth.is_secure_placeholder = true;
+        assert_eq!(describe_step(&auth, Locale::En), None);
+    }
+
+    #[test]
+    fn long_labels_are_shortened() {
+        let label = "word ".repeat(30);
+        let description =
+            describe_step(&step(ActionType::Click, ax("AXButton", &label)), Locale::En).unwrap();
+        assert!(description.contains("word…\" button"), "{description}");
+        assert!(description.chars().count() < 100);
+    }
+
+    #[test]
+    fn apply_keeps_existing_descriptions() {
+        let mut fresh = step(ActionType::Click, ax("AXButton", "Save"));
+        apply_auto_description(&mut fresh, Locale::En);
+        assert_eq!(
+            fresh.description.as_deref(),
+            Some("Click the \"Save\" button in Pages")
+        );
+        assert_eq!(fresh.description_source, Some(DescriptionSource::Auto));
+
+        let mut written = step(ActionType::Click, ax("AXButton", "Save"));
+        written.description = Some("Save the draft".to_string());
+        apply_auto_description(&mut written, Locale::En);
+        assert_eq!(written.description.as_deref(), Some("Save the draft"));
+        assert_eq!(written.description_source, None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Section breaks: when the frontmost window title changes between steps, insert an
+//! auto-generated Note step ("Now in: …") so long single-app recordings stay readable.
+
+use super::super::session::Session;
+use super::super::types::{ActionType, DescriptionSource, Step};
+use crate::i18n::{self, Locale};
+
+/// Insert a section-break step before the session's last step when its context title
+/// differs from the previous step that had one. Returns the inserted marker.
+pub fn insert_context_break(session: &mut Session, locale: Locale) -> Option<Step> {
+    let (last, earlier) = session.steps.split_last()?;
+    let title = last.context_title.as_deref()?;
+    let previous = earlier
+        .iter()
+        .rev()
+        .find_map(|s| s.context_title.as_deref())?;
+    if !context_changed(previous, title) {
+        return None;
+    }
+
+    let marker = Step {
+        id: session.next_step_id(),
+        ts: last.ts,
+        action: ActionType::Note,
+        x: 0,
+        y: 0,
+        click_x_percent: 0.0,
+        click_y_percent: 0.0,
+        app: last.app.clone(),
+        window_title: title.to_string(),
+        screenshot_path: None,
+        note: None,
+        description: Some(i18n::step_context_break(
+            locale,
+            &collapse_whitespace(title),
+        )),
+        description_source: Some(DescriptionSource::Auto),
+        description_status: None,
+        description_error: None,
+        ax: None,
+        capture_status: None,
+        capture_error: None,
+        crop_region: None,
+        is_secure_placeholder: false,
+        drag_end: None,
+        context_title: Some(title.to_string()),
+        capture_bounds: None,
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: None,
+        annotations: Vec::new(),
+        capture_timings: None,
+        page_url: None,
+        keys: None,
+        title: None,
+    };
+    let index = session.steps.len() - 1;
+    session.insert_step(index, marker.clone());
+    Some(marker)
+}
+
+/// True when two window titles name different places once noise is stripped.
+pub fn context_changed(previous: &str, next: &str) -> bool {
+    let previous = normalize_context_title(previous);
+    let next = normalize_context_title(next);
+    !previous.is_empty() && !next.is_empty() && previous != next
+}
+
+/// Lowercased title with whitespace collapsed and counters such as "(3 unread)",
+/// "(12)" or "[2]" removed, so badge updates don't count as a context change.
+pub fn normalize_context_title(title: &str) -> String {
+    let mut out = String::with_capacity(title.len());
+    let mut chars = title.chars().peekable();
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '(' => ')',
+            '[' => ']',
+            _ => {
+                out.push(c);
+                continue;
+            }
+        };
+        if !chars.peek().is_some_and(|n| n.is_ascii_digit()) {
+            out.push(c);
+            continue;
+        }
+        let group: String = chars.clone().take_while(|&n| n != close).collect();
+        let closed = chars.clone().nth(group.chars().count()) == Some(close);
+        if closed && is_counter(&group) {
+            for _ in 0..=group.chars().count() {
+                chars.next();
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    collapse_whitespace(&out).to_lowercase()
+}
+
+/// "3", "12 unread", "1 new message": a number followed by at most two words.
+fn is_counter(group: &str) -> bool {
+    let mut words = group.split_whitespace();
+    let number = words.next().unwrap_or("");
+    !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit() || c == '+')
+        && words.count() <= 2
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step_with_context(id: &str, title: Option<&str>) -> Step {
+        let mut step = Step::sample();
+        step.id = id.to_string();
+        step.app = "Safari".into();
+        step.context_title = title.map(str::to_string);
+        step
+    }
+
+    #[test]
+    fn normalize_strips_counters_and_whitespace() {
+        assert_eq!(
+            normalize_context_title("  Inbox (3 unread)  —  Mail "),
+            "inbox — mail"
+        );
+        assert_eq!(normalize_context_title("(12) Inbox"), "inbox");
+        assert_eq!(normalize_context_title("Tasks [2]"), "tasks");
+        // Parenthesised text that isn't a counter stays.
+        assert_eq!(
+            normalize_context_title("Report (draft) (2024 annual review summary)"),
+            "report (draft) (2024 annual review summary)"
+        );
+        assert_eq!(normalize_context_title("Broken (3"), "broken (3");
+    }
+
+    #[test]
+    fn context_changed_ignores_noise() {
+        assert!(!context_changed("Inbox (3 unread)", "Inbox (4 unread)"));
+        assert!(!context_changed(
+            "Settings  —  Privacy",
+            "settings — privacy"
+        ));
+        assert!(context_changed("Settings — General", "Settings — Privacy"));
+        assert!(!context_changed("", "Settings"));
+    }
+
+    #[test]
+    fn insert_context_break_marks_title_change() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(step_with_context("step-001", Some("Settings — General")));
+        assert!(insert_context_break(&mut session, Locale::En).is_none());
+
+        session.add_step(step_with_context("step-002", Some("Settings — Privacy")));
+        let marker = insert_context_break(&mut session, Locale::En).expect("marker");
+        assert_eq!(marker.action, ActionType::Note);
+        assert!(marker.is_auto_generated());
+        assert_eq!(
+            marker.description.as_deref(),
+            Some("Now in: Settings — Privacy")
+        );
+        let ids: Vec<_> = session.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["step-001", "step-003", "step-002"]);
+
+        // Same context again: the marker itself carries the title, so no duplicate.
+        session.add_step(step_with_context("step-004", Some("Settings — Privacy")));
+        assert!(insert_context_break(&mut session, Locale::En).is_none());
+
+        // Steps without a context title (e.g. auth placeholders) are skipped over.
+        session.add_step(step_with_context("step-005", None));
+        assert!(insert_context_break(&mut session, Locale::En).is_none());
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+}
@@ -0,0 +1,321 @@
+//! Right-click, then pick a menu item: by the time the item click is processed the menu
+//! has closed, so a step of its own would show a screenshot without the menu. Instead the
+//! selection is folded into the right-click step, whose screenshot still shows the open
+//! menu with the chosen item.
+
+use super::super::ax_helpers::AxElementLabel;
+use super::super::click_event::ClickEvent;
+use super::super::session::Session;
+use super::super::types::{AxClickInfo, Step};
+use super::super::window_info::{WindowBounds, WindowSnapshot};
+use super::helpers::{
+    auto_crop_for_step, ax_click_info, ax_info_for_capture, calculate_click_percent, debug_log,
+    find_context_menu_near_click,
+};
+use super::types::{PendingContextMenu, PipelineState, CONTEXT_MENU_MERGE_WINDOW_MS};
+use std::sync::Mutex;
+
+/// Bounds within this many points count as the same menu (rounding between AX and CG).
+const MENU_BOUNDS_TOLERANCE_PT: i32 = 2;
+
+fn contains_point(bounds: &WindowBounds, x: i32, y: i32) -> bool {
+    x >= bounds.x
+        && x < bounds.x + bounds.width as i32
+        && y >= bounds.y
+        && y < bounds.y + bounds.height as i32
+}
+
+fn contains_rect(outer: &WindowBounds, inner: &WindowBounds) -> bool {
+    let t = MENU_BOUNDS_TOLERANCE_PT;
+    inner.x >= outer.x - t
+        && inner.y >= outer.y - t
+        && inner.x + inner.width as i32 <= outer.x + outer.width as i32 + t
+        && inner.y + inner.height as i32 <= outer.y + outer.height as i32 + t
+}
+
+fn same_bounds(a: &WindowBounds, b: &WindowBounds) -> bool {
+    let t = MENU_BOUNDS_TOLERANCE_PT;
+    (a.x - b.x).abs() <= t
+        && (a.y - b.y).abs() <= t
+        && (a.width as i32 - b.width as i32).abs() <= t
+        && (a.height as i32 - b.height as i32).abs() <= t
+}
+
+/// Whether a click is the selection of an item in `pending`'s menu, as captured.
+///
+/// `item_bounds` is the clicked element's screen rect and `current_menu` the menu's
+/// bounds now, when they can still be measured. A menu that moved, resized or scrolled
+/// its item out of the captured area no longer matches the screenshot, so those clicks
+/// stay separate steps.
+pub fn is_context_menu_selection(
+    pending: &PendingContextMenu,
+    timestamp_ms: i64,
+    click_x: i32,
+    click_y: i32,
+    ax_role: Option<&str>,
+    item_bounds: Option<&WindowBounds>,
+    current_menu: Option<&WindowBounds>,
+) -> bool {
+    let elapsed = timestamp_ms - pending.timestamp_ms;
+    if !(0..=CONTEXT_MENU_MERGE_WINDOW_MS).contains(&elapsed) {
+        return false;
+    }
+    if ax_role != Some(accessibility_sys::kAXMenuItemRole) {
+        return false;
+    }
+    if !contains_point(&pending.menu_bounds, click_x, click_y) {
+        return false;
+    }
+    if current_menu.is_some_and(|menu| !same_bounds(menu, &pending.menu_bounds)) {
+        return false;
+    }
+    item_bounds.is_none_or(|item| contains_rect(&pending.menu_bounds, item))
+}
+
+/// Fold the selected menu item into the right-click `step`: the marker moves to the
+/// item within the original capture and the title names the item.
+pub fn apply_context_menu_selection(
+    step: &mut Step,
+    pending: &PendingContextMenu,
+    click_x: i32,
+    click_y: i32,
+    item: AxClickInfo,
+    item_bounds: Option<&WindowBounds>,
+) {
+    let (marker_x, marker_y) = item_bounds
+        .map(|b| (b.x + b.width as i32 / 2, b.y + b.height as i32 / 2))
+        .unwrap_or((click_x, click_y));
+    let capture = &pending.capture;
+    step.click_x_percent =
+        calculate_click_percent(marker_x, capture.x, capture.width as i32) as f32;
+    step.click_y_percent =
+        calculate_click_percent(marker_y, capture.y, capture.height as i32) as f32;
+    step.x = click_x;
+    step.y = click_y;
+
+    let label = item.label.trim().to_string();
+    if !label.is_empty() {
+        step.window_title = format!("Menu - {label}");
+    }
+    step.ax = ax_info_for_capture(Some(item), item_bounds, None, Some(capture));
+    step.crop_region = auto_crop_for_step(step);
+}
+
+/// Merge `click` into the pending right-click step when it selects an item of that
+/// step's context menu. Returns the updated step's ID. The pending menu is consumed
+/// either way; it only applies to the click right after the right-click.
+pub(super) fn merge_context_menu_selection(
+    click: &ClickEvent,
+    session: &mut Session,
+    pipeline_state: &Mutex<PipelineState>,
+    clicked_ax: Option<&AxElementLabel>,
+    windows: &WindowSnapshot,
+) -> Option<String> {
+    let pending = pipeline_state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .pending_context_menu
+        .take()?;
+    let ax = clicked_ax?;
+    let item_bounds = ax.element_bounds.as_ref();
+
+    // Cheap checks first; re-measuring the menu scans the window list.
+    if !is_context_menu_selection(
+        &pending,
+        click.timestamp_ms,
+        click.x,
+        click.y,
+        Some(ax.role.as_str()),
+        item_bounds,
+        None,
+    ) {
+        return None;
+    }
+    let current_menu =
+        find_context_menu_near_click(windows, pending.click_x, pending.click_y, &pending.app_name);
+    if !is_context_menu_selection(
+        &pending,
+        click.timestamp_ms,
+        click.x,
+        click.y,
+        Some(ax.role.as_str()),
+        item_bounds,
+        current_menu.as_ref(),
+    ) {
+        debug_log(
+            session,
+            &format!(
+                "context_menu_merge: menu changed since capture (window_id={}), keeping separate steps",
+                pending.window_id
+            ),
+        );
+        return None;
+    }
+
+    let step = session.step_mut(&pending.step_id)?;
+    apply_context_menu_selection(
+        step,
+        &pending,
+        click.x,
+        click.y,
+        ax_click_info(ax),
+        item_bounds,
+    );
+    let step_id = step.id.clone();
+    debug_log(
+        session,
+        &format!(
+            "context_menu_merge: '{}' merged into {step_id} (window_id={})",
+            ax.label, pending.window_id
+        ),
+    );
+    Some(step_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::types::ActionType;
+    use super::*;
+
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn pending() -> PendingContextMenu {
+        PendingContextMenu {
+            step_id: "step-002".to_string(),
+            window_id: 7,
+            app_name: "Finder".to_string(),
+            click_x: 300,
+            click_y: 200,
+            timestamp_ms: 10_000,
+            menu_bounds: bounds(300, 200, 200, 300),
+            capture: bounds(0, 0, 1000, 800),
+        }
+    }
+
+    const MENU_ITEM: Option<&str> = Some(accessibility_sys::kAXMenuItemRole);
+
+    #[test]
+    fn menu_item_click_inside_menu_is_a_selection() {
+        let item = bounds(300, 240, 200, 20);
+        assert!(is_context_menu_selection(
+            &pending(),
+            12_000,
+            350,
+            250,
+            MENU_ITEM,
+            Some(&item),
+            Some(&bounds(300, 200, 200, 300)),
+        ));
+        // Menu already closed: nothing to compare against.
+        assert!(is_context_menu_selection(
+            &pending(),
+            12_000,
+            350,
+            250,
+            MENU_ITEM,
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn selection_requires_menu_item_role_time_window_and_position() {
+        let p = pending();
+        assert!(!is_context_menu_selection(
+            &p,
+            12_000,
+            350,
+            250,
+            Some("AXButton"),
+            None,
+            None
+        ));
+        assert!(!is_context_menu_selection(
+            &p, 12_000, 350, 250, None, None, None
+        ));
+        assert!(!is_context_menu_selection(
+            &p, 15_001, 350, 250, MENU_ITEM, None, None
+        ));
+        assert!(!is_context_menu_selection(
+            &p, 9_999, 350, 250, MENU_ITEM, None, None
+        ));
+        assert!(!is_context_menu_selection(
+            &p, 12_000, 600, 250, MENU_ITEM, None, None
+        ));
+    }
+
+    #[test]
+    fn resized_or_scrolled_menu_falls_back_to_separate_steps() {
+        let p = pending();
+        // Menu grew after capture.
+        assert!(!is_context_menu_selection(
+            &p,
+            12_000,
+            350,
+            250,
+            MENU_ITEM,
+            None,
+            Some(&bounds(300, 200, 200, 420)),
+        ));
+        // Scrolled: the item sits partly outside the captured menu.
+        assert!(!is_context_menu_selection(
+            &p,
+            12_000,
+            350,
+            490,
+            MENU_ITEM,
+            Some(&bounds(300, 485, 200, 30)),
+            None,
+        ));
+    }
+
+    #[test]
+    fn apply_selection_moves_marker_to_item_and_names_it() {
+        let mut step = Step {
+            id: "step-002".to_string(),
+            action: ActionType::RightClick,
+            x: 300,
+            y: 200,
+            click_x_percent: 30.0,
+            click_y_percent: 25.0,
+            window_title: "Downloads".to_string(),
+            screenshot_path: Some("/tmp/step-002.png".to_string()),
+            ..Step::sample()
+        };
+        let item: AxClickInfo = serde_json::from_value(serde_json::json!({
+            "role": "AXMenuItem",
+            "label": "Get Info",
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .unwrap();
+
+        apply_context_menu_selection(
+            &mut step,
+            &pending(),
+            320,
+            250,
+            item,
+            Some(&bounds(300, 240, 200, 20)),
+        );
+
+        assert_eq!(step.window_title, "Menu - Get Info");
+        assert_eq!((step.x, step.y), (320, 250));
+        assert_eq!(step.click_x_percent, 40.0);
+        assert_eq!(step.click_y_percent, 31.25);
+        let ax = step.ax.expect("menu item ax");
+        assert_eq!(ax.label, "Get Info");
+        assert_eq!(
+            ax.element_bounds.map(|b| (b.x_percent, b.width_percent)),
+            Some((30.0, 20.0))
+        );
+        assert!(step.screenshot_path.is_some());
+    }
+}
@@ -0,0 +1,252 @@
+//! Alerts owned by background daemons (UserNotificationCenter, helper tools) have no
+//! accessibility element under the click, so `get_clicked_element_info` returns None
+//! and the regular path ends up capturing the whole desktop. When the topmost window
+//! at the click looks like such a dialog, capture just that window instead.
+
+use super::super::ax_helpers::{is_security_agent_process, is_system_ui_process};
+use super::super::cg_capture::capture_window_cg;
+use super::super::click_event::ClickEvent;
+use super::super::session::Session;
+use super::super::types::{CaptureStatus, CaptureTimings, Step};
+use super::super::window_info::{WindowBounds, WindowInfo};
+use super::helpers::{
+    auto_crop_for_step, capture_bounds_on_display, debug_log, elapsed_ms,
+    get_display_bounds_for_click, record_capture_pixels,
+};
+use super::{is_own_app_name, PipelineError};
+use std::path::Path;
+use std::time::Instant;
+
+/// Daemon alerts are small; anything this large is a regular app window.
+const MAX_DIALOG_WIDTH: u32 = 600;
+const MAX_DIALOG_HEIGHT: u32 = 400;
+/// Smaller windows are tooltips, badges or menu-bar extras rather than dialogs.
+const MIN_DIALOG_WIDTH: u32 = 120;
+const MIN_DIALOG_HEIGHT: u32 = 60;
+/// Margin (points) added around the captured dialog.
+const DIALOG_PADDING_PT: u32 = 24;
+
+/// Whether `bounds` is dialog-sized: bigger than a tooltip, smaller than ~600x400.
+pub fn is_dialog_sized(bounds: &WindowBounds) -> bool {
+    (MIN_DIALOG_WIDTH..MAX_DIALOG_WIDTH).contains(&bounds.width)
+        && (MIN_DIALOG_HEIGHT..MAX_DIALOG_HEIGHT).contains(&bounds.height)
+}
+
+/// Whether a window owned by `owner` may be a daemon dialog. Dock, menu bar and
+/// Control Center are excluded, but UserNotificationCenter (which `is_system_ui_process`
+/// lumps in with Notification Center) owns exactly these alerts. Auth dialogs and our
+/// own windows have their own handling.
+pub fn is_daemon_dialog_owner(owner: &str) -> bool {
+    let name = owner.trim();
+    if name.is_empty() || is_own_app_name(name) || is_security_agent_process(name) {
+        return false;
+    }
+    let is_alert_daemon = name.to_lowercase().contains("usernotificationcenter");
+    is_alert_daemon || !is_system_ui_process(name)
+}
+
+/// Dialog-sized window with a dialog owner, titled or not.
+pub fn is_daemon_dialog(window: &WindowInfo) -> bool {
+    is_dialog_sized(&window.bounds) && is_daemon_dialog_owner(&window.app_name)
+}
+
+pub fn daemon_dialog_title(owner: &str) -> String {
+    format!("Dialog — {}", owner.trim())
+}
+
+/// `bounds` grown by `pad` points on every side.
+fn padded_bounds(bounds: &WindowBounds, pad: u32) -> WindowBounds {
+    WindowBounds {
+        x: bounds.x - pad as i32,
+        y: bounds.y - pad as i32,
+        width: bounds.width + pad * 2,
+        height: bounds.height + pad * 2,
+    }
+}
+
+/// Add a transparent margin around the captured window. The capture is in pixels, so
+/// the margin is scaled from points by the window's backing scale.
+fn pad_screenshot(path: &Path, bounds: &WindowBounds, pad: u32) -> Result<(), String> {
+    use image::{imageops, RgbaImage};
+
+    let img = image::open(path)
+        .map_err(|e| format!("open dialog capture: {e}"))?
+        .to_rgba8();
+    let scale = img.width() as f64 / bounds.width.max(1) as f64;
+    let pad_px = (pad as f64 * scale).round() as u32;
+    let mut canvas = RgbaImage::new(img.width() + pad_px * 2, img.height() + pad_px * 2);
+    imageops::overlay(&mut canvas, &img, pad_px as i64, pad_px as i64);
+    canvas
+        .save(path)
+        .map_err(|e| format!("save padded dialog capture: {e}"))
+}
+
+/// Capture `dialog` for a click that had no accessibility element and record the step.
+pub fn capture_daemon_dialog(
+    click: &ClickEvent,
+    session: &mut Session,
+    dialog: &WindowInfo,
+    mut timings: CaptureTimings,
+) -> Result<Step, PipelineError> {
+    let step_id = session.next_step_id();
+    let screenshot_path = session.screenshot_path(&step_id);
+    let capture = padded_bounds(&dialog.bounds, DIALOG_PADDING_PT);
+
+    debug_log(
+        session,
+        &format!(
+            "daemon_dialog_path: id={} owner='{}' title='{}' bounds=({}, {}, {}x{})",
+            dialog.window_id,
+            dialog.app_name,
+            dialog.window_title,
+            dialog.bounds.x,
+            dialog.bounds.y,
+            dialog.bounds.width,
+            dialog.bounds.height
+        ),
+    );
+
+    let capture_started = Instant::now();
+    capture_window_cg(dialog.window_id, &screenshot_path)
+        .map_err(|e| PipelineError::ScreenshotFailed(format!("{e}")))?;
+    if let Err(err) = pad_screenshot(&screenshot_path, &dialog.bounds, DIALOG_PADDING_PT) {
+        debug_log(
+            session,
+            &format!("daemon_dialog_path: padding skipped ({err})"),
+        );
+    }
+
+    timings.capture_ms = Some(elapsed_ms(capture_started));
+
+    let click_x_percent =
+        ((click.x - capture.x) as f64 / capture.width as f64 * 100.0).clamp(0.0, 100.0);
+    let click_y_percent =
+        ((click.y - capture.y) as f64 / capture.height as f64 * 100.0).clamp(0.0, 100.0);
+    let (_, _, display_w, display_h) = get_display_bounds_for_click(click.x, click.y);
+
+    let action = click.action();
+
+    let mut step = Step {
+        id: step_id,
+        ts: click.timestamp_ms,
+        action,
+        x: click.x,
+        y: click.y,
+        click_x_percent: click_x_percent as f32,
+        click_y_percent: click_y_percent as f32,
+        app: dialog.app_name.clone(),
+        window_title: daemon_dialog_title(&dialog.app_name),
+        screenshot_path: Some(screenshot_path.to_string_lossy().to_string()),
+        note: None,
+        description: None,
+        description_source: None,
+        description_status: None,
+        description_error: None,
+        ax: None,
+        capture_status: Some(CaptureStatus::Ok),
+        capture_error: None,
+        crop_region: None,
+        is_secure_placeholder: false,
+        drag_end: None,
+        context_title: None,
+        capture_bounds: Some(capture_bounds_on_display(&capture, display_w, display_h)),
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: None,
+        annotations: Vec::new(),
+        capture_timings: Some(timings),
+        page_url: None,
+        keys: None,
+        title: None,
+    };
+    step.crop_region = auto_crop_for_step(&step);
+    record_capture_pixels(&mut step);
+
+    session.add_step(step.clone());
+    Ok(step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app: &str, width: u32, height: u32) -> WindowInfo {
+        WindowInfo {
+            app_name: app.to_string(),
+            window_title: String::new(),
+            window_id: 7,
+            bounds: WindowBounds {
+                x: 500,
+                y: 300,
+                width,
+                height,
+            },
+        }
+    }
+
+    #[test]
+    fn dialog_size_excludes_tooltips_and_app_windows() {
+        assert!(is_dialog_sized(&window("x", 420, 180).bounds));
+        assert!(!is_dialog_sized(&window("x", 80, 30).bounds));
+        assert!(!is_dialog_sized(&window("x", 600, 200).bounds));
+        assert!(!is_dialog_sized(&window("x", 420, 400).bounds));
+    }
+
+    #[test]
+    fn daemon_dialog_owner_excludes_system_ui_and_auth() {
+        assert!(is_daemon_dialog_owner("UserNotificationCenter"));
+        assert!(is_daemon_dialog_owner("Finder"));
+        assert!(!is_daemon_dialog_owner("Dock"));
+        assert!(!is_daemon_dialog_owner("NotificationCenter"));
+        assert!(!is_daemon_dialog_owner("SecurityAgent"));
+        assert!(!is_daemon_dialog_owner("StepCast"));
+        assert!(!is_daemon_dialog_owner("  "));
+    }
+
+    #[test]
+    fn daemon_dialog_combines_size_and_owner() {
+        assert!(is_daemon_dialog(&window(
+            "UserNotificationCenter",
+            420,
+            180
+        )));
+        assert!(!is_daemon_dialog(&window(
+            "UserNotificationCenter",
+            1440,
+            900
+        )));
+        assert!(!is_daemon_dialog(&window("ControlCenter", 420, 180)));
+    }
+
+    #[test]
+    fn daemon_dialog_title_names_owner() {
+        assert_eq!(
+            daemon_dialog_title("UserNotificationCenter"),
+            "Dialog — UserNotificationCenter"
+        );
+    }
+
+    #[test]
+    fn padded_bounds_grows_every_side() {
+        let padded = padded_bounds(&window("x", 400, 200).bounds, 24);
+        assert_eq!((padded.x, padded.y), (476, 276));
+        assert_eq!((padded.width, padded.height), (448, 248));
+    }
+
+    #[test]
+    fn pad_screenshot_scales_margin_to_pixels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dialog.png");
+        // Retina capture: 2px per point.
+        image::RgbaImage::from_pixel(800, 400, image::Rgba([255, 255, 255, 255]))
+            .save(&path)
+            .unwrap();
+        pad_screenshot(&path, &window("x", 400, 200).bounds, 24).unwrap();
+        let padded = image::open(&path).unwrap();
+        assert_eq!((padded.width(), padded.height()), (896, 496));
+    }
+}
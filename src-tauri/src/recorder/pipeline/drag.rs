@@ -0,0 +1,310 @@
+//! Drag steps: a mouse-up far from its mouse-down upgrades the click step it
+//! started into an `ActionType::Drag` with a screenshot covering the source and
+//! destination windows.
+
+use super::super::click_event::ClickEvent;
+use super::super::session::Session;
+use super::super::types::{ActionType, CaptureStatus, DragEnd, Step};
+use super::super::window_info::{get_topmost_window_at_point, WindowBounds};
+use super::helpers::{
+    calculate_click_percent, capture_region_best, debug_log, display_scale_at,
+    get_display_bounds_for_click, measure_capture,
+};
+use super::types::PipelineError;
+
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+
+const START_COLOR: Rgba<u8> = Rgba([255, 59, 48, 255]);
+const PATH_COLOR: Rgba<u8> = Rgba([255, 59, 48, 200]);
+const END_COLOR: Rgba<u8> = Rgba([52, 199, 89, 255]);
+const OUTLINE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Upgrade the click step that began this drag and recapture it.
+///
+/// Returns the updated step. Drag ends that don't match the last recorded click
+/// (filtered, debounced, or already replaced) are ignored.
+pub fn process_drag_end(click: &ClickEvent, session: &mut Session) -> Result<Step, PipelineError> {
+    let start = click.drag_start.ok_or(PipelineError::UnmatchedDragEnd)?;
+    let end = (click.x, click.y);
+    debug_log(
+        session,
+        &format!(
+            "drag_end: start=({}, {}) end=({}, {}) ts={}",
+            start.0, start.1, end.0, end.1, click.timestamp_ms
+        ),
+    );
+
+    let matches_last = session.last_step_mut().is_some_and(|step| {
+        step.action == ActionType::Click
+            && (step.x, step.y) == start
+            && step.screenshot_path.is_some()
+            && !step.is_auth_placeholder()
+    });
+    if !matches_last {
+        debug_log(session, "drag_end ignored: no matching click step");
+        return Err(PipelineError::UnmatchedDragEnd);
+    }
+
+    let start_display = get_display_bounds_for_click(start.0, start.1);
+    let end_display = get_display_bounds_for_click(end.0, end.1);
+    let source = get_topmost_window_at_point(start.0, start.1);
+    let target = get_topmost_window_at_point(end.0, end.1);
+    let (rx, ry, rw, rh) = clamp_region(
+        spanning_region(
+            source
+                .as_ref()
+                .map_or(start_display, |w| window_rect(&w.bounds)),
+            target
+                .as_ref()
+                .map_or(end_display, |w| window_rect(&w.bounds)),
+        ),
+        spanning_region(start_display, end_display),
+    );
+    debug_log(
+        session,
+        &format!(
+            "drag_end: source={:?} target={:?} region=({rx}, {ry}, {rw}, {rh})",
+            source.as_ref().map(|w| &w.app_name),
+            target.as_ref().map(|w| &w.app_name),
+        ),
+    );
+    let step_id = session
+        .last_step_mut()
+        .map(|s| s.id.clone())
+        .unwrap_or_default();
+    let path = session.screenshot_path(&step_id);
+    capture_region_best(session, rx, ry, rw, rh, &path)
+        .map_err(|e| PipelineError::ScreenshotFailed(e.to_string()))?;
+
+    let start_pct = (
+        calculate_click_percent(start.0, rx, rw) as f32,
+        calculate_click_percent(start.1, ry, rh) as f32,
+    );
+    let end_pct = (
+        calculate_click_percent(end.0, rx, rw) as f32,
+        calculate_click_percent(end.1, ry, rh) as f32,
+    );
+    if let Err(err) = annotate_drag(&path, start_pct, end_pct) {
+        debug_log(session, &format!("drag annotate failed: {err}"));
+    }
+
+    let step = session
+        .last_step_mut()
+        .ok_or(PipelineError::UnmatchedDragEnd)?;
+    step.action = ActionType::Drag;
+    step.click_x_percent = start_pct.0;
+    step.click_y_percent = start_pct.1;
+    let (end_app, end_window_title) = match target {
+        Some(w) => (w.app_name, w.window_title),
+        None => (step.app.clone(), step.window_title.clone()),
+    };
+    step.drag_end = Some(DragEnd {
+        x: end.0,
+        y: end.1,
+        x_percent: end_pct.0,
+        y_percent: end_pct.1,
+        app: end_app,
+        window_title: end_window_title,
+    });
+    step.screenshot_path = Some(path.to_string_lossy().to_string());
+    step.capture_status = Some(CaptureStatus::Ok);
+    step.capture_error = None;
+    // The old crop and element bounds referred to the previous capture. No capture
+    // bounds either: a click-centred auto-crop would cut off the release point.
+    step.crop_region = None;
+    step.capture_bounds = None;
+    // Spans both ends' displays, so the scale may differ from the press point's.
+    let region = WindowBounds {
+        x: rx,
+        y: ry,
+        width: rw.max(0) as u32,
+        height: rh.max(0) as u32,
+    };
+    let measured = measure_capture(&path, &region, display_scale_at(start.0, start.1));
+    step.pixel_scale = measured.map(|(scale, _)| scale);
+    step.capture_pixels = measured.map(|(_, size)| size);
+    if let Some(ax) = step.ax.as_mut() {
+        ax.element_bounds = None;
+    }
+    Ok(step.clone())
+}
+
+/// Bounding box of two (x, y, w, h) rects, so a drag across windows or displays
+/// stays in frame.
+fn spanning_region(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let left = a.0.min(b.0);
+    let top = a.1.min(b.1);
+    let right = (a.0 + a.2).max(b.0 + b.2);
+    let bottom = (a.1 + a.3).max(b.1 + b.3);
+    (left, top, right - left, bottom - top)
+}
+
+/// Intersect `region` with `bounds`; falls back to `bounds` when they don't overlap.
+fn clamp_region(
+    region: (i32, i32, i32, i32),
+    bounds: (i32, i32, i32, i32),
+) -> (i32, i32, i32, i32) {
+    let left = region.0.max(bounds.0);
+    let top = region.1.max(bounds.1);
+    let right = (region.0 + region.2).min(bounds.0 + bounds.2);
+    let bottom = (region.1 + region.3).min(bounds.1 + bounds.3);
+    if right <= left || bottom <= top {
+        return bounds;
+    }
+    (left, top, right - left, bottom - top)
+}
+
+fn window_rect(b: &WindowBounds) -> (i32, i32, i32, i32) {
+    (b.x, b.y, b.width as i32, b.height as i32)
+}
+
+/// Draw the drag path into the screenshot: ring at the start, line, dot at the end.
+pub(super) fn annotate_drag(
+    path: &Path,
+    start_pct: (f32, f32),
+    end_pct: (f32, f32),
+) -> Result<(), String> {
+    let mut img = image::open(path).map_err(|e| e.to_string())?.to_rgba8();
+    draw_drag_marks(&mut img, start_pct, end_pct);
+    img.save(path).map_err(|e| e.to_string())
+}
+
+fn draw_drag_marks(img: &mut RgbaImage, start_pct: (f32, f32), end_pct: (f32, f32)) {
+    let (w, h) = img.dimensions();
+    let to_px = |(x, y): (f32, f32)| (x / 100.0 * w as f32, y / 100.0 * h as f32);
+    let start = to_px(start_pct);
+    let end = to_px(end_pct);
+    let radius = (w.min(h) as f32 / 80.0).max(8.0);
+    let stroke = (radius / 4.0).max(2.0);
+
+    // Path first so the endpoint marks sit on top.
+    let len = (end.0 - start.0).hypot(end.1 - start.1);
+    let steps = (len / (stroke / 2.0)).ceil().max(1.0) as usize;
+    for i in 0..=steps {
+        let t = i as f32 / steps as f32;
+        let p = (
+            start.0 + (end.0 - start.0) * t,
+            start.1 + (end.1 - start.1) * t,
+        );
+        fill_disc(img, p, stroke / 2.0, PATH_COLOR);
+    }
+
+    // Arrowhead pointing at the release point, its tip touching the end dot.
+    let end_outer = radius * 0.8 + stroke;
+    let head_len = radius * 1.6;
+    if len > end_outer + head_len {
+        let dir = ((end.0 - start.0) / len, (end.1 - start.1) / len);
+        let tip = (end.0 - dir.0 * end_outer, end.1 - dir.1 * end_outer);
+        let base = (tip.0 - dir.0 * head_len, tip.1 - dir.1 * head_len);
+        let side = (-dir.1 * radius, dir.0 * radius);
+        fill_triangle(
+            img,
+            [
+                tip,
+                (base.0 + side.0, base.1 + side.1),
+                (base.0 - side.0, base.1 - side.1),
+            ],
+            START_COLOR,
+        );
+    }
+
+    fill_disc(img, start, radius + stroke, OUTLINE_COLOR);
+    fill_disc(img, start, radius, START_COLOR);
+    fill_disc(img, start, radius - stroke, OUTLINE_COLOR);
+    fill_disc(img, end, end_outer, OUTLINE_COLOR);
+    fill_disc(img, end, radius * 0.8, END_COLOR);
+}
+
+fn fill_triangle(img: &mut RgbaImage, [a, b, c]: [(f32, f32); 3], color: Rgba<u8>) {
+    let (w, h) = img.dimensions();
+    let min_x = a.0.min(b.0).min(c.0).floor().max(0.0) as u32;
+    let min_y = a.1.min(b.1).min(c.1).floor().max(0.0) as u32;
+    let max_x = (a.0.max(b.0).max(c.0).ceil().max(0.0) as u32).min(w.saturating_sub(1));
+    let max_y = (a.1.max(b.1).max(c.1).ceil().max(0.0) as u32).min(h.saturating_sub(1));
+    // Signed area of (p, q, r): same sign for all three edges means inside.
+    let edge = |p: (f32, f32), q: (f32, f32), r: (f32, f32)| {
+        (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0)
+    };
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            let (e0, e1, e2) = (edge(a, b, p), edge(b, c, p), edge(c, a, p));
+            let inside =
+                (e0 >= 0.0 && e1 >= 0.0 && e2 >= 0.0) || (e0 <= 0.0 && e1 <= 0.0 && e2 <= 0.0);
+            if inside {
+                blend(img.get_pixel_mut(x, y), color);
+            }
+        }
+    }
+}
+
+fn fill_disc(img: &mut RgbaImage, center: (f32, f32), radius: f32, color: Rgba<u8>) {
+    if radius <= 0.0 {
+        return;
+    }
+    let (w, h) = img.dimensions();
+    let x0 = (center.0 - radius).floor().max(0.0) as u32;
+    let y0 = (center.1 - radius).floor().max(0.0) as u32;
+    let x1 = ((center.0 + radius).ceil() as u32).min(w.saturating_sub(1));
+    let y1 = ((center.1 + radius).ceil() as u32).min(h.saturating_sub(1));
+    let r2 = radius * radius;
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            let dx = x as f32 + 0.5 - center.0;
+            let dy = y as f32 + 0.5 - center.1;
+            if dx * dx + dy * dy <= r2 {
+                blend(img.get_pixel_mut(x, y), color);
+            }
+        }
+    }
+}
+
+fn blend(dst: &mut Rgba<u8>, src: Rgba<u8>) {
+    let a = f32::from(src[3]) / 255.0;
+    for (d, s) in dst.0.iter_mut().zip(src.0).take(3) {
+        *d = (f32::from(s) * a + f32::from(*d) * (1.0 - a)).round() as u8;
+    }
+    dst[3] = 255;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spanning_region_covers_both_displays() {
+        let main = (0, 0, 1440, 900);
+        let right = (1440, -100, 1920, 1080);
+        assert_eq!(spanning_region(main, main), main);
+        assert_eq!(spanning_region(main, right), (0, -100, 3360, 1080));
+    }
+
+    #[test]
+    fn draw_drag_marks_colors_endpoints() {
+        let mut img = RgbaImage::from_pixel(400, 200, Rgba([0, 0, 0, 255]));
+        draw_drag_marks(&mut img, (25.0, 50.0), (75.0, 50.0));
+        // Start ring: red stroke with a white centre; end: solid green.
+        assert_eq!(*img.get_pixel(100, 100), OUTLINE_COLOR);
+        assert_eq!(*img.get_pixel(300, 100), END_COLOR);
+        // Midpoint of the path is tinted red.
+        let mid = img.get_pixel(200, 100);
+        assert!(mid[0] > 150 && mid[1] < 100);
+        // Arrowhead before the end dot is wider than the path stroke.
+        assert_eq!(*img.get_pixel(280, 104), START_COLOR);
+        assert_eq!(*img.get_pixel(200, 104), Rgba([0, 0, 0, 255]));
+        // Far corner untouched.
+        assert_eq!(*img.get_pixel(5, 5), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn clamp_region_keeps_capture_on_screen() {
+        let display = (0, 0, 1440, 900);
+        // Source window hangs off the left edge, destination off the bottom.
+        let windows = spanning_region((-200, 100, 600, 400), (800, 500, 500, 600));
+        assert_eq!(windows, (-200, 100, 1500, 1000));
+        assert_eq!(clamp_region(windows, display), (0, 100, 1300, 800));
+        // Fully off-screen regions fall back to the display.
+        assert_eq!(clamp_region((2000, 0, 100, 100), display), display);
+    }
+}
@@ -1,17 +1,22 @@
 //! Pipeline helper functions: capture, filtering, debouncing, context menu detection.
 
-use super::super::ax_helpers::{get_clicked_element_info, is_security_agent_process};
-use super::super::capture::CaptureError;
+use super::super::ax_helpers::{
+    get_clicked_element_info, get_toggle_state_at, is_security_agent_process, AxElementLabel,
+};
+use super::super::capture::{CaptureError, CaptureOptions};
 use super::super::cg_capture::{capture_region_cg, capture_region_fast};
 use super::super::click_event::ClickEvent;
+use super::super::exclusions::mask_exclusions_in_file;
 use super::super::session::Session;
-use super::super::types::{ActionType, BoundsPercent, Step};
-use super::super::window_info::find_auth_dialog_window;
-use super::super::window_info::WindowBounds;
+use super::super::types::{
+    ActionType, AxClickInfo, BoundsPercent, CaptureBounds, PixelSize, Step, AUTH_PLACEHOLDER_TITLE,
+};
+use super::super::window_info::{WindowBounds, WindowSnapshot};
 use super::types::*;
 
 use std::path::Path;
 use std::sync::Mutex;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TitlelessOverlayKind {
@@ -76,6 +81,32 @@ pub fn should_prefer_transient_region_capture(
         || (role == accessibility_sys::kAXGroupRole && overlay_kind == TitlelessOverlayKind::Popup)
 }
 
+/// First capture attempt for a click without a dedicated path (right-click, auth dialog).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickCaptureSource {
+    /// Full-display frame buffered just before the click; clipped to the display.
+    PreClickFrame,
+    /// The window's own bitmap by window ID, including parts beyond the display.
+    /// Region capture is the fallback.
+    WindowId,
+}
+
+/// With `prefer_full_window`, clicks on a window that can be captured by ID skip the
+/// pre-click frame. Hovers keep it: a window-ID capture misses the tooltip window.
+pub fn first_click_capture(
+    prefer_full_window: bool,
+    is_hover: bool,
+    window_id: u32,
+    bounds: &WindowBounds,
+) -> ClickCaptureSource {
+    let capturable = window_id > 0 && bounds.width > 0 && bounds.height > 0;
+    if prefer_full_window && capturable && !is_hover {
+        ClickCaptureSource::WindowId
+    } else {
+        ClickCaptureSource::PreClickFrame
+    }
+}
+
 /// Classify titleless overlay windows (menus vs popovers) for capture decisions.
 ///
 /// We only treat a titleless window as a dropdown menu when it is near the menu bar;
@@ -166,6 +197,7 @@ pub fn write_auth_placeholder(path: &Path, width: u32, height: u32) -> Result<()
     Ok(())
 }
 
+/// Region capture with the session's [`CaptureOptions`], logged to its debug log.
 pub fn capture_region_best(
     session: &Session,
     x: i32,
@@ -174,20 +206,55 @@ pub fn capture_region_best(
     height: i32,
     output_path: &Path,
 ) -> Result<(), CaptureError> {
-    match capture_region_fast(x, y, width, height, output_path) {
+    capture_region_logged(
+        x,
+        y,
+        width,
+        height,
+        output_path,
+        session.capture_options,
+        |msg| debug_log(session, msg),
+    )?;
+    if !session.capture_exclusions.is_empty() {
+        let capture = WindowBounds {
+            x,
+            y,
+            width: width.max(0) as u32,
+            height: height.max(0) as u32,
+        };
+        // A screenshot that can't be masked must not be kept.
+        mask_exclusions_in_file(output_path, &capture, &session.capture_exclusions).map_err(
+            |err| {
+                let _ = std::fs::remove_file(output_path);
+                CaptureError::CgImage(format!("excluded regions: {err}"))
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Fast region capture with CG fallback, reporting the path taken to `log`.
+pub fn capture_region_logged(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    output_path: &Path,
+    options: CaptureOptions,
+    log: impl Fn(&str),
+) -> Result<(), CaptureError> {
+    match capture_region_fast(x, y, width, height, output_path, options) {
         Ok(()) => {
-            debug_log(
-                session,
-                &format!("fast_region_capture ok: x={x} y={y} w={width} h={height}",),
-            );
+            log(&format!(
+                "fast_region_capture ok: x={x} y={y} w={width} h={height}",
+            ));
             Ok(())
         }
         Err(err) => {
-            debug_log(
-                session,
-                &format!("fast_region_capture failed: {err} (x={x} y={y} w={width} h={height})",),
-            );
-            capture_region_cg(x, y, width, height, output_path)
+            log(&format!(
+                "fast_region_capture failed: {err} (x={x} y={y} w={width} h={height})",
+            ));
+            capture_region_cg(x, y, width, height, output_path, options)
         }
     }
 }
@@ -200,10 +267,21 @@ pub fn validate_screenshot(path: &Path) -> bool {
     }
 }
 
+/// Whether a click at `now` is the phantom click macOS delivers while an auth dialog
+/// closes. Clicks stamped before the auth click (clock skew, reordered events) and
+/// clicks at the same instant are real.
+pub fn is_phantom_after_auth(last_auth_ms: Option<i64>, now: i64, cooldown_ms: i64) -> bool {
+    let Some(last_auth_ms) = last_auth_ms else {
+        return false;
+    };
+    let since_auth = now - last_auth_ms;
+    since_auth > 0 && since_auth < cooldown_ms
+}
+
 pub fn should_emit_auth_prompt(ps: &mut PipelineState, window_id: u32, timestamp_ms: i64) -> bool {
     match ps.last_auth_prompt {
         Some((prev_id, prev_ts))
-            if prev_id == window_id && timestamp_ms - prev_ts < AUTH_PROMPT_DEDUP_MS =>
+            if prev_id == window_id && timestamp_ms - prev_ts < ps.auth_prompt_dedup_ms =>
         {
             false
         }
@@ -215,13 +293,12 @@ pub fn should_emit_auth_prompt(ps: &mut PipelineState, window_id: u32, timestamp
 }
 
 pub fn find_security_auth_window(
+    windows: &WindowSnapshot,
     click_x: i32,
     click_y: i32,
     clicked_info_missing: bool,
 ) -> Option<super::super::window_info::WindowInfo> {
-    let auth_window = find_auth_dialog_window(click_x, click_y, clicked_info_missing)
-        .ok()
-        .flatten()?;
+    let auth_window = windows.auth_dialog_window(click_x, click_y, clicked_info_missing)?;
     if auth_window.window_id == 0 {
         return None;
     }
@@ -235,15 +312,17 @@ pub fn handle_auth_prompt(
     click: &ClickEvent,
     session: &mut Session,
     pipeline_state: &Mutex<PipelineState>,
+    windows: &WindowSnapshot,
 ) -> (Option<Step>, bool) {
     const AUTH_PLACEHOLDER_DESCRIPTION: &str =
         "Authenticate with Touch ID or enter your password to continue.";
 
     let clicked_info = get_clicked_element_info(click.x, click.y);
-    let auth_window = match find_security_auth_window(click.x, click.y, clicked_info.is_none()) {
-        Some(window) => window,
-        None => return (None, false),
-    };
+    let auth_window =
+        match find_security_auth_window(windows, click.x, click.y, clicked_info.is_none()) {
+            Some(window) => window,
+            None => return (None, false),
+        };
 
     {
         let mut ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
@@ -292,7 +371,7 @@ pub fn handle_auth_prompt(
         click_x_percent: 50.0,
         click_y_percent: 50.0,
         app: "Authentication".to_string(),
-        window_title: "Authentication dialog (secure)".to_string(),
+        window_title: AUTH_PLACEHOLDER_TITLE.to_string(),
         screenshot_path: Some(screenshot_path.to_string_lossy().to_string()),
         note: None,
         description: Some(AUTH_PLACEHOLDER_DESCRIPTION.to_string()),
@@ -303,6 +382,22 @@ pub fn handle_auth_prompt(
         capture_status: None,
         capture_error: None,
         crop_region: None,
+        is_secure_placeholder: true,
+        drag_end: None,
+        context_title: None,
+        capture_bounds: None,
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: None,
+        annotations: Vec::new(),
+        capture_timings: None,
+        page_url: None,
+        keys: None,
+        title: None,
     };
 
     debug_log(
@@ -398,127 +493,93 @@ pub fn get_display_bounds_for_click(click_x: i32, click_y: i32) -> (i32, i32, i3
     )
 }
 
-/// Find a context menu window near the click position.
-/// Context menus are typically: empty title, small layer, appear near the click.
-pub fn find_context_menu_near_click(
-    click_x: i32,
-    click_y: i32,
+/// Whether a click landed on the bare desktop: Finder's icon area or background
+/// rather than one of its windows. The desktop is a Finder window named "Desktop",
+/// or one at the normal window layer covering the whole display.
+pub fn is_desktop_click(
     app_name: &str,
-) -> Option<super::super::window_info::WindowBounds> {
-    use core_foundation::base::{CFType, TCFType};
-    use core_foundation::dictionary::CFDictionaryRef;
-    use core_foundation::number::CFNumber;
-    use core_foundation::string::CFString;
-    use core_graphics::display::*;
-
-    let window_list = unsafe {
-        CGWindowListCopyWindowInfo(
-            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
-            kCGNullWindowID,
-        )
-    };
-
-    if window_list.is_null() {
-        return None;
+    ax_role: &str,
+    window_title: &str,
+    window_bounds: &WindowBounds,
+    window_layer: Option<i32>,
+    display: &WindowBounds,
+) -> bool {
+    if !app_name.eq_ignore_ascii_case("Finder") {
+        return false;
     }
+    if ax_role != accessibility_sys::kAXScrollAreaRole && ax_role != accessibility_sys::kAXGroupRole
+    {
+        return false;
+    }
+    if window_title == "Desktop" {
+        return true;
+    }
+    let covers_display = window_bounds.x <= display.x
+        && window_bounds.y <= display.y
+        && window_bounds.x + window_bounds.width as i32 >= display.x + display.width as i32
+        && window_bounds.y + window_bounds.height as i32 >= display.y + display.height as i32;
+    window_layer == Some(0) && covers_display
+}
 
-    let windows: Vec<CFDictionaryRef> = unsafe {
-        let count = core_foundation::array::CFArrayGetCount(window_list as _);
-        (0..count)
-            .map(|i| {
-                core_foundation::array::CFArrayGetValueAtIndex(window_list as _, i)
-                    as CFDictionaryRef
-            })
-            .collect()
-    };
-
-    for window_dict in windows {
-        let dict = unsafe {
-            core_foundation::dictionary::CFDictionary::<CFString, CFType>::wrap_under_get_rule(
-                window_dict,
-            )
-        };
-
-        // Get window title - context menus typically have empty titles
-        let title_key = CFString::new("kCGWindowName");
-        let window_title = dict
-            .find(&title_key)
-            .map(|v| {
-                let s: CFString = unsafe { CFString::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                s.to_string()
-            })
-            .unwrap_or_default();
-
-        if !window_title.is_empty() {
-            continue;
-        }
-
-        // Get owner name
-        let owner_name_key = CFString::new("kCGWindowOwnerName");
-        let owner_name = dict
-            .find(&owner_name_key)
-            .map(|v| {
-                let s: CFString = unsafe { CFString::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                s.to_string()
-            })
-            .unwrap_or_default();
-
-        // Context menu should be from the same app (use contains for flexibility)
-        let owner_lower = owner_name.to_lowercase();
-        let app_lower = app_name.to_lowercase();
-        if !owner_lower.contains(&app_lower) && !app_lower.contains(&owner_lower) {
-            if cfg!(debug_assertions) {
-                eprintln!("Context menu search: skipping window from '{owner_name}' (looking for '{app_name}')");
+/// Bounds of the on-screen windows without a title owned by `app_name`, front to back.
+/// Menus, dropdowns and popovers are such windows.
+fn untitled_windows_of_app(windows: &WindowSnapshot, app_name: &str) -> Vec<WindowBounds> {
+    let app_lower = app_name.to_lowercase();
+    windows
+        .windows()
+        .iter()
+        .filter(|window| window.title.is_empty())
+        .filter(|window| {
+            // Only the app's own windows (use contains for flexibility)
+            let owner_lower = window.owner_name.to_lowercase();
+            let owned = owner_lower.contains(&app_lower) || app_lower.contains(&owner_lower);
+            if !owned && cfg!(debug_assertions) {
+                eprintln!(
+                    "Untitled window search: skipping window from '{}' (looking for '{app_name}')",
+                    window.owner_name
+                );
             }
-            continue;
-        }
+            owned
+        })
+        .map(|window| window.bounds.clone())
+        .collect()
+}
 
-        // Get window bounds
-        let bounds_key = CFString::new("kCGWindowBounds");
-        let bounds = match dict.find(&bounds_key) {
-            Some(v) => {
-                let bounds_dict: core_foundation::dictionary::CFDictionary<CFString, CFNumber> = unsafe {
-                    core_foundation::dictionary::CFDictionary::wrap_under_get_rule(
-                        v.as_CFTypeRef() as _
-                    )
-                };
-
-                let x = bounds_dict
-                    .find(CFString::new("X"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0);
-                let y = bounds_dict
-                    .find(CFString::new("Y"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0);
-                let width = bounds_dict
-                    .find(CFString::new("Width"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0) as u32;
-                let height = bounds_dict
-                    .find(CFString::new("Height"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0) as u32;
-
-                super::super::window_info::WindowBounds {
-                    x,
-                    y,
-                    width,
-                    height,
-                }
-            }
-            None => continue,
-        };
+/// Shape and position checks shared by both context menu lookups: context menus are
+/// narrow (Finder's reach ~500px) and open within 300px of the click.
+pub fn is_plausible_context_menu(bounds: &WindowBounds, click_x: i32, click_y: i32) -> bool {
+    let narrow = (50..=600).contains(&bounds.width) && bounds.height >= 50;
+    let near = (bounds.x - click_x).abs() <= 300 && (bounds.y - click_y).abs() <= 300;
+    narrow && near
+}
 
-        // Context menus are typically narrow, but Finder menus can reach ~500px
-        if bounds.width > 600 || bounds.width < 50 || bounds.height < 50 {
-            continue;
-        }
+/// Capture area for a right-click: the window plus its context menu, with generous
+/// padding around the menu for macOS drop shadows (~20-30px) and items that render
+/// beyond the reported bounds.
+pub fn context_menu_union(window: &WindowBounds, menu: &WindowBounds) -> WindowBounds {
+    const MENU_PAD: i32 = 50;
+    let x = window.x.min(menu.x - MENU_PAD);
+    let y = window.y.min(menu.y - MENU_PAD);
+    let right = (window.x + window.width as i32).max(menu.x + menu.width as i32 + MENU_PAD);
+    let bottom = (window.y + window.height as i32).max(menu.y + menu.height as i32 + MENU_PAD);
+    WindowBounds {
+        x,
+        y,
+        width: (right - x) as u32,
+        height: (bottom - y) as u32,
+    }
+}
 
-        // Context menu should be near the click position (within 300px)
-        let dx = (bounds.x - click_x).abs();
-        let dy = (bounds.y - click_y).abs();
-        if dx > 300 || dy > 300 {
+/// Find a context menu window near the click position.
+/// Context menus are typically: empty title, small layer, appear near the click.
+pub fn find_context_menu_near_click(
+    windows: &WindowSnapshot,
+    click_x: i32,
+    click_y: i32,
+    app_name: &str,
+) -> Option<WindowBounds> {
+    for bounds in untitled_windows_of_app(windows, app_name) {
+        if !is_plausible_context_menu(&bounds, click_x, click_y) {
             continue;
         }
 
@@ -538,6 +599,88 @@ pub fn find_context_menu_near_click(
     None
 }
 
+/// Tallest menu bar (notched displays use ~37pt). Untitled windows at most this tall
+/// hanging from the top are status items, not dropdowns.
+pub const MENU_BAR_MAX_HEIGHT: i32 = 40;
+/// How far a dropdown may sit beside the click. macOS pushes status item menus near
+/// the notch a few hundred points to the left.
+const MENU_BAR_DROPDOWN_MAX_DX: i32 = 600;
+/// Margin around a menu bar dropdown for its drop shadow.
+const MENU_BAR_DROPDOWN_PAD: i32 = 20;
+
+fn horizontal_distance(bounds: &WindowBounds, x: i32) -> i32 {
+    let right = bounds.x + bounds.width as i32;
+    if x < bounds.x {
+        bounds.x - x
+    } else if x >= right {
+        x - right + 1
+    } else {
+        0
+    }
+}
+
+/// Pick the dropdown a menu bar click opened among the clicked app's untitled windows:
+/// hanging from the menu bar of the clicked display, nearest to the click.
+/// `display` is (x, y, width, height).
+pub fn pick_menu_bar_dropdown(
+    candidates: &[WindowBounds],
+    display: (i32, i32, i32, i32),
+    click_x: i32,
+) -> Option<WindowBounds> {
+    let (display_x, display_y, display_w, _) = display;
+    candidates
+        .iter()
+        .filter(|b| {
+            (display_y..=display_y + MENU_BAR_MAX_HEIGHT).contains(&b.y)
+                && b.height as i32 > MENU_BAR_MAX_HEIGHT
+                && b.x < display_x + display_w
+                && b.x + b.width as i32 > display_x
+                && horizontal_distance(b, click_x) <= MENU_BAR_DROPDOWN_MAX_DX
+        })
+        .min_by_key(|b| horizontal_distance(b, click_x))
+        .cloned()
+}
+
+/// Capture region for a menu bar click with a known dropdown: the menu bar strip from
+/// the click to the dropdown plus the dropdown itself, padded and clamped to `display`.
+pub fn menu_bar_dropdown_region(
+    dropdown: &WindowBounds,
+    display: (i32, i32, i32, i32),
+    click_x: i32,
+) -> WindowBounds {
+    let (display_x, display_y, display_w, display_h) = display;
+    let left = (dropdown.x.min(click_x) - MENU_BAR_DROPDOWN_PAD).max(display_x);
+    let right = ((dropdown.x + dropdown.width as i32).max(click_x) + MENU_BAR_DROPDOWN_PAD)
+        .min(display_x + display_w);
+    let bottom =
+        (dropdown.y + dropdown.height as i32 + MENU_BAR_DROPDOWN_PAD).min(display_y + display_h);
+    WindowBounds {
+        x: left,
+        y: display_y,
+        width: (right - left).max(1) as u32,
+        height: (bottom - display_y).max(1) as u32,
+    }
+}
+
+/// Wait up to ~300ms for the dropdown of a menu bar click owned by `app_name`, reading
+/// a fresh window list from `capture_windows` on every attempt.
+pub fn poll_menu_bar_dropdown(
+    capture_windows: &mut dyn FnMut() -> WindowSnapshot,
+    app_name: &str,
+    display: (i32, i32, i32, i32),
+    click_x: i32,
+) -> Option<WindowBounds> {
+    for _ in 0..5 {
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        let untitled = untitled_windows_of_app(&capture_windows(), app_name);
+        let found = pick_menu_bar_dropdown(&untitled, display, click_x);
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
 /// Check if click should be debounced (too close in time/position to previous)
 /// Returns (should_debounce, should_upgrade_previous) - upgrade means replace last Click with DoubleClick
 pub fn is_debounced(
@@ -559,7 +702,7 @@ pub fn is_debounced(
         }
 
         // Debounce if click is within threshold time AND at nearly same position AND same click_count
-        if time_diff < DEBOUNCE_MS && same_position && click_count == last_count {
+        if time_diff < ps.debounce_ms && same_position && click_count == last_count {
             return (true, false);
         }
     }
@@ -581,6 +724,21 @@ pub fn is_debounced(
 ///
 /// The click position as a percentage (0.0 to 100.0), clamped to valid range.
 /// Returns 0.0 if window_size is zero or negative.
+/// Milliseconds since `started`, for `CaptureTimings`.
+pub fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+/// Bounds to map a click into a window-ID capture. The image shows the window where it
+/// was when captured, so bounds re-read right after the capture win over the ones read
+/// before it, which are stale if the window moved meanwhile (dragged, sheet animation).
+pub fn window_capture_bounds(
+    read_before: WindowBounds,
+    read_after: Option<WindowBounds>,
+) -> WindowBounds {
+    read_after.unwrap_or(read_before)
+}
+
 pub fn calculate_click_percent(click_coord: i32, window_offset: i32, window_size: i32) -> f64 {
     if window_size <= 0 {
         return 0.0;
@@ -657,6 +815,80 @@ pub fn suggested_focus_crop_for_capture(
     })
 }
 
+/// How an auto focus-crop was chosen, for the debug log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusCropStrategy {
+    /// Snapped to the clicked element's container (sidebar, toolbar, list, ...).
+    Container,
+    /// Centered on the click or the clicked element.
+    Click,
+}
+
+impl FocusCropStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Container => "container",
+            Self::Click => "click",
+        }
+    }
+}
+
+/// Focus crop that snaps to the clicked element's container, with a small margin.
+///
+/// Each side is kept between a minimum and maximum fraction of the capture. An axis
+/// where the container is wider than the maximum is centered on the click instead.
+/// Returns None when the click lies outside the container or the container spans
+/// (almost) the whole capture both ways, so snapping would not narrow anything.
+pub fn container_focus_crop(
+    container: &BoundsPercent,
+    click_x_percent: f64,
+    click_y_percent: f64,
+) -> Option<BoundsPercent> {
+    const MARGIN: f64 = 2.0;
+    const MIN_SPAN: f64 = 24.0;
+    const MAX_SPAN: f64 = 80.0;
+
+    let x = clamp_percent(container.x_percent as f64);
+    let y = clamp_percent(container.y_percent as f64);
+    let w = clamp_percent(container.width_percent as f64);
+    let h = clamp_percent(container.height_percent as f64);
+    if w <= 0.0 || h <= 0.0 {
+        return None;
+    }
+    let click_x = clamp_percent(click_x_percent);
+    let click_y = clamp_percent(click_y_percent);
+    let inside = |start: f64, size: f64, click: f64| {
+        (start - MARGIN..=start + size + MARGIN).contains(&click)
+    };
+    if !inside(x, w, click_x) || !inside(y, h, click_y) {
+        return None;
+    }
+    if w + 2.0 * MARGIN > MAX_SPAN && h + 2.0 * MARGIN > MAX_SPAN {
+        return None;
+    }
+
+    // One axis: (start, span) of the crop.
+    let snap = |start: f64, size: f64, click: f64| {
+        let padded = size + 2.0 * MARGIN;
+        let center = if padded > MAX_SPAN {
+            click
+        } else {
+            start + size / 2.0
+        };
+        let span = padded.clamp(MIN_SPAN, MAX_SPAN);
+        ((center - span / 2.0).clamp(0.0, 100.0 - span), span)
+    };
+    let (crop_x, crop_w) = snap(x, w, click_x);
+    let (crop_y, crop_h) = snap(y, h, click_y);
+
+    Some(BoundsPercent {
+        x_percent: crop_x as f32,
+        y_percent: crop_y as f32,
+        width_percent: crop_w as f32,
+        height_percent: crop_h as f32,
+    })
+}
+
 /// Decide whether an auto focus-crop should be applied for a capture.
 ///
 /// We keep this conservative: only for large captures where full-frame output
@@ -689,6 +921,189 @@ pub fn should_apply_focus_crop(
         || (width_ratio >= MIN_WIDTH_RATIO && height_ratio >= MIN_HEIGHT_RATIO)
 }
 
+/// Auto-crop for a step from its stored capture bounds, click point and element bounds.
+///
+/// The pipeline uses this for new steps; `reset_step_crop_to_auto` uses it to undo a manual crop.
+pub fn auto_crop_for_step(step: &Step) -> Option<BoundsPercent> {
+    focus_crop_for_step(step).map(|(crop, _)| crop)
+}
+
+/// Like [`auto_crop_for_step`], also telling how the crop was chosen. The container
+/// crop wins when AX reported container bounds that contain the click.
+pub fn focus_crop_for_step(step: &Step) -> Option<(BoundsPercent, FocusCropStrategy)> {
+    let capture = step.capture_bounds?;
+    let bounds = WindowBounds {
+        x: capture.x,
+        y: capture.y,
+        width: capture.width,
+        height: capture.height,
+    };
+    if !should_apply_focus_crop(&bounds, capture.display_width, capture.display_height) {
+        return None;
+    }
+    let (click_x, click_y) = (step.click_x_percent as f64, step.click_y_percent as f64);
+    if let Some(crop) = step
+        .ax
+        .as_ref()
+        .and_then(|ax| ax.container_bounds.as_ref())
+        .and_then(|container| container_focus_crop(container, click_x, click_y))
+    {
+        return Some((crop, FocusCropStrategy::Container));
+    }
+    suggested_focus_crop_for_capture(
+        &bounds,
+        click_x,
+        click_y,
+        step.ax.as_ref().and_then(|ax| ax.element_bounds.as_ref()),
+    )
+    .map(|crop| (crop, FocusCropStrategy::Click))
+}
+
+/// Set `step`'s auto-crop, noting in the debug log how it was chosen.
+pub fn apply_auto_crop(session: &Session, step: &mut Step) {
+    let focus = focus_crop_for_step(step);
+    if let Some((_, strategy)) = &focus {
+        debug_log(
+            session,
+            &format!("focus_crop: {} step={}", strategy.as_str(), step.id),
+        );
+    }
+    step.crop_region = focus.map(|(crop, _)| crop);
+}
+
+/// Stored form of a capture rect on the display of the given size.
+pub fn capture_bounds_on_display(
+    bounds: &WindowBounds,
+    display_width: i32,
+    display_height: i32,
+) -> CaptureBounds {
+    CaptureBounds {
+        x: bounds.x,
+        y: bounds.y,
+        width: bounds.width,
+        height: bounds.height,
+        display_width,
+        display_height,
+    }
+}
+
+/// Backing scale of the display containing the point: pixels per point, 2.0 on Retina.
+pub fn display_scale_at(x: i32, y: i32) -> Option<f32> {
+    use core_graphics::display::CGDisplay;
+
+    let displays = CGDisplay::active_displays().unwrap_or_default();
+    let display = displays
+        .iter()
+        .map(|&id| CGDisplay::new(id))
+        .find(|disp| {
+            let b = disp.bounds();
+            let (dx, dy) = (b.origin.x as i32, b.origin.y as i32);
+            x >= dx && x < dx + b.size.width as i32 && y >= dy && y < dy + b.size.height as i32
+        })
+        .unwrap_or_else(CGDisplay::main);
+    let mode = display.display_mode()?;
+    let points = mode.width();
+    (points > 0).then(|| mode.pixel_width() as f32 / points as f32)
+}
+
+/// Pixels per point of a capture of `bounds` that produced an `image`-sized file.
+///
+/// Takes the display's scale when the image agrees with it. A capture spanning displays
+/// of different scale (or taken on another display than the click) comes out at one of
+/// them, which only the image size tells.
+pub fn resolve_pixel_scale(
+    image: PixelSize,
+    bounds: &WindowBounds,
+    display_scale: Option<f32>,
+) -> Option<f32> {
+    let display_scale = display_scale.filter(|s| s.is_finite() && *s > 0.0);
+    if bounds.width == 0 || image.width == 0 {
+        return display_scale;
+    }
+    let measured = image.width as f32 / bounds.width as f32;
+    // Captures are off by a pixel or two at most; 3% is far below the 1x/2x gap.
+    match display_scale {
+        Some(scale) if (measured - scale).abs() <= scale * 0.03 => Some(scale),
+        _ => Some(measured),
+    }
+}
+
+/// Pixel size and scale of the screenshot at `path`, captured from `bounds` (points).
+pub fn measure_capture(
+    path: &Path,
+    bounds: &WindowBounds,
+    display_scale: Option<f32>,
+) -> Option<(f32, PixelSize)> {
+    let (width, height) = image::image_dimensions(path).ok()?;
+    let image = PixelSize { width, height };
+    Some((resolve_pixel_scale(image, bounds, display_scale)?, image))
+}
+
+/// Store the pixel size and scale of `step`'s screenshot, measured against its capture
+/// bounds. Cleared when either is missing, e.g. for failed captures and auth placeholders.
+pub fn record_capture_pixels(step: &mut Step) {
+    let measured = match (&step.screenshot_path, step.capture_bounds) {
+        (Some(path), Some(capture)) if !step.is_auth_placeholder() => {
+            let bounds = WindowBounds {
+                x: capture.x,
+                y: capture.y,
+                width: capture.width,
+                height: capture.height,
+            };
+            measure_capture(Path::new(path), &bounds, display_scale_at(step.x, step.y))
+        }
+        _ => None,
+    };
+    step.pixel_scale = measured.map(|(scale, _)| scale);
+    step.capture_pixels = measured.map(|(_, size)| size);
+}
+
+/// Time after the click at which a toggle's new state is read back: long enough for
+/// the app to flip it, short of the 150ms a capture may wait for it.
+pub const TOGGLE_RECHECK_DELAY_MS: i64 = 120;
+
+/// Checkboxes, radio buttons and switches: elements whose click flips a state worth
+/// naming. Tab buttons are radio buttons too, but their state is which tab is open.
+pub fn is_toggle_role(role: &str, subrole: Option<&str>) -> bool {
+    match subrole {
+        Some("AXTabButton") => false,
+        Some("AXSwitch" | "AXToggle") => true,
+        _ => matches!(
+            role,
+            "AXCheckBox" | "AXRadioButton" | "AXSwitch" | "AXToggle"
+        ),
+    }
+}
+
+/// Milliseconds still to wait at `now_ms` before reading back a toggle clicked at
+/// `click_ms`; zero once the capture itself took that long.
+pub fn toggle_recheck_wait_ms(click_ms: i64, now_ms: i64) -> u64 {
+    (click_ms + TOGGLE_RECHECK_DELAY_MS - now_ms).clamp(0, TOGGLE_RECHECK_DELAY_MS) as u64
+}
+
+/// Record the before and after state of a clicked checkbox, radio button or switch, so
+/// descriptions can say whether it was turned on or off.
+pub fn record_toggle_state(step: &mut Step, click: &ClickEvent) {
+    if step.action != ActionType::Click {
+        return;
+    }
+    let Some(ax) = step.ax.as_mut() else {
+        return;
+    };
+    if !is_toggle_role(&ax.role, ax.subrole.as_deref()) {
+        return;
+    }
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    std::thread::sleep(std::time::Duration::from_millis(toggle_recheck_wait_ms(
+        click.timestamp_ms,
+        now_ms,
+    )));
+    ax.was_checked = ax.is_checked;
+    ax.now_checked = get_toggle_state_at(click.x as f32, click.y as f32, &ax.role);
+}
+
+/// Element rect as percent of `capture`, clipped to the capture. None when the
+/// element lies entirely outside it.
 pub fn bounds_percent_in_capture(
     element: &WindowBounds,
     capture: &WindowBounds,
@@ -699,15 +1114,23 @@ pub fn bounds_percent_in_capture(
         return None;
     }
 
-    let x_pct = (((element.x - capture.x) as f64 / cw) * 100.0).clamp(0.0, 100.0);
-    let y_pct = (((element.y - capture.y) as f64 / ch) * 100.0).clamp(0.0, 100.0);
-    let w_pct = ((element.width as f64 / cw) * 100.0).clamp(0.0, 100.0);
-    let h_pct = ((element.height as f64 / ch) * 100.0).clamp(0.0, 100.0);
-
-    if w_pct <= 0.0 || h_pct <= 0.0 {
+    // Clip in screen coordinates first so a partly visible element keeps only its
+    // visible part instead of being shifted to the capture edge.
+    let left = element.x.max(capture.x) as i64;
+    let top = element.y.max(capture.y) as i64;
+    let right =
+        (element.x as i64 + element.width as i64).min(capture.x as i64 + capture.width as i64);
+    let bottom =
+        (element.y as i64 + element.height as i64).min(capture.y as i64 + capture.height as i64);
+    if right <= left || bottom <= top {
         return None;
     }
 
+    let x_pct = (left - capture.x as i64) as f64 / cw * 100.0;
+    let y_pct = (top - capture.y as i64) as f64 / ch * 100.0;
+    let w_pct = (right - left) as f64 / cw * 100.0;
+    let h_pct = (bottom - top) as f64 / ch * 100.0;
+
     Some(BoundsPercent {
         x_percent: x_pct as f32,
         y_percent: y_pct as f32,
@@ -716,10 +1139,171 @@ pub fn bounds_percent_in_capture(
     })
 }
 
+/// Serializable step metadata for a clicked AX element. `element_bounds` is filled
+/// in per capture by [`ax_info_for_capture`].
+pub(super) fn ax_click_info(ax: &AxElementLabel) -> AxClickInfo {
+    AxClickInfo {
+        role: ax.role.clone(),
+        subrole: ax.subrole.clone(),
+        role_description: ax.role_description.clone(),
+        identifier: ax.identifier.clone(),
+        label: ax.label.clone(),
+        element_bounds: None,
+        container_role: ax.container_role.clone(),
+        container_subrole: ax.container_subrole.clone(),
+        container_identifier: ax.container_identifier.clone(),
+        container_bounds: None,
+        window_role: ax.window_role.clone(),
+        window_subrole: ax.window_subrole.clone(),
+        top_level_role: ax.top_level_role.clone(),
+        top_level_subrole: ax.top_level_subrole.clone(),
+        parent_dialog_role: ax.parent_dialog_role.clone(),
+        parent_dialog_subrole: ax.parent_dialog_subrole.clone(),
+        is_checked: ax.is_checked,
+        was_checked: None,
+        now_checked: None,
+        value: ax.value.clone(),
+        placeholder: ax.placeholder.clone(),
+        is_cancel_button: ax.is_cancel_button,
+        is_default_button: ax.is_default_button,
+        menu_path: ax.menu_path.clone(),
+        accessibility_label: ax.accessibility_label.clone(),
+        help: ax.help.clone(),
+    }
+}
+
+/// `ax_info` for a step whose screenshot covers `capture`, with the clicked element's
+/// and its container's screen rects mapped into the capture. Steps without a usable
+/// capture get no rects.
+pub fn ax_info_for_capture(
+    ax_info: Option<AxClickInfo>,
+    element_screen_bounds: Option<&WindowBounds>,
+    container_screen_bounds: Option<&WindowBounds>,
+    capture: Option<&WindowBounds>,
+) -> Option<AxClickInfo> {
+    let in_capture = |bounds: Option<&WindowBounds>| {
+        bounds
+            .zip(capture)
+            .and_then(|(bounds, capture)| bounds_percent_in_capture(bounds, capture))
+    };
+    ax_info.map(|mut info| {
+        info.element_bounds = in_capture(element_screen_bounds);
+        info.container_bounds = in_capture(container_screen_bounds);
+        info
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn click_percent_in(x: i32, y: i32, capture: &WindowBounds) -> (f64, f64) {
+        (
+            calculate_click_percent(x, capture.x, capture.width as i32),
+            calculate_click_percent(y, capture.y, capture.height as i32),
+        )
+    }
+
+    fn contains_percent(rect: &BoundsPercent, (x, y): (f64, f64)) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        (rect.x_percent..=rect.x_percent + rect.width_percent).contains(&x)
+            && (rect.y_percent..=rect.y_percent + rect.height_percent).contains(&y)
+    }
+
+    #[test]
+    fn toggles_are_checkboxes_radio_buttons_and_switches() {
+        assert!(is_toggle_role("AXCheckBox", None));
+        assert!(is_toggle_role("AXCheckBox", Some("AXSwitch")));
+        assert!(is_toggle_role("AXRadioButton", None));
+        assert!(!is_toggle_role("AXRadioButton", Some("AXTabButton")));
+        assert!(!is_toggle_role("AXButton", None));
+    }
+
+    #[test]
+    fn toggle_recheck_waits_only_the_rest_of_the_delay() {
+        assert_eq!(toggle_recheck_wait_ms(1_000, 1_000), 120);
+        assert_eq!(toggle_recheck_wait_ms(1_000, 1_050), 70);
+        assert_eq!(toggle_recheck_wait_ms(1_000, 1_400), 0);
+        // A clock that went backwards never makes the capture wait longer.
+        assert_eq!(toggle_recheck_wait_ms(1_000, 900), 120);
+    }
+
+    #[test]
+    fn marker_stays_on_the_element_when_the_window_moved_before_capture() {
+        let stale = WindowBounds {
+            x: 100,
+            y: 100,
+            width: 800,
+            height: 600,
+        };
+        // Dragged 200pt right and 150pt down before the click and the capture.
+        let moved = WindowBounds {
+            x: 300,
+            y: 250,
+            width: 800,
+            height: 600,
+        };
+        let button = WindowBounds {
+            x: moved.x + 40,
+            y: moved.y + 20,
+            width: 120,
+            height: 30,
+        };
+        let (click_x, click_y) = (button.x + 60, button.y + 15);
+
+        let capture = window_capture_bounds(stale.clone(), Some(moved.clone()));
+        assert_eq!(capture, moved);
+        let button_pct = bounds_percent_in_capture(&button, &capture).expect("button in capture");
+        assert!(contains_percent(
+            &button_pct,
+            click_percent_in(click_x, click_y, &capture)
+        ));
+        // Against the stale bounds the marker lands 200pt to the right of the button.
+        assert!(!contains_percent(
+            &button_pct,
+            click_percent_in(click_x, click_y, &stale)
+        ));
+    }
+
+    #[test]
+    fn marker_stays_on_the_element_when_a_sheet_resized_the_window() {
+        let before = WindowBounds {
+            x: 200,
+            y: 120,
+            width: 700,
+            height: 400,
+        };
+        // A sheet animation grew the window downwards by the time it was captured.
+        let after = WindowBounds {
+            height: 560,
+            ..before.clone()
+        };
+        let default_button = WindowBounds {
+            x: 760,
+            y: 630,
+            width: 80,
+            height: 24,
+        };
+        let capture = window_capture_bounds(before.clone(), Some(after));
+        let button_pct =
+            bounds_percent_in_capture(&default_button, &capture).expect("button in capture");
+        assert!(contains_percent(
+            &button_pct,
+            click_percent_in(800, 640, &capture)
+        ));
+    }
+
+    #[test]
+    fn window_capture_keeps_earlier_bounds_when_the_window_is_gone() {
+        let before = WindowBounds {
+            x: 10,
+            y: 20,
+            width: 300,
+            height: 200,
+        };
+        assert_eq!(window_capture_bounds(before.clone(), None), before);
+    }
+
     #[test]
     fn bounds_percent_in_capture_basic() {
         let capture = WindowBounds {
@@ -741,6 +1325,38 @@ mod tests {
         assert!((pct.height_percent - 10.0).abs() < 0.01);
     }
 
+    #[test]
+    fn pixel_scale_prefers_display_and_falls_back_to_image_size() {
+        let bounds = WindowBounds {
+            x: 0,
+            y: 0,
+            width: 1440,
+            height: 900,
+        };
+        let image = |width| PixelSize { width, height: 900 };
+        assert_eq!(
+            resolve_pixel_scale(image(2880), &bounds, Some(2.0)),
+            Some(2.0)
+        );
+        // Off by a pixel is still the display's scale.
+        assert_eq!(
+            resolve_pixel_scale(image(1441), &bounds, Some(1.0)),
+            Some(1.0)
+        );
+        // Clicked on a 1x display, captured at 2x: a window reaching onto the Retina one.
+        assert_eq!(
+            resolve_pixel_scale(image(2880), &bounds, Some(1.0)),
+            Some(2.0)
+        );
+        assert_eq!(resolve_pixel_scale(image(2160), &bounds, None), Some(1.5));
+        let empty = WindowBounds { width: 0, ..bounds };
+        assert_eq!(
+            resolve_pixel_scale(image(2880), &empty, Some(2.0)),
+            Some(2.0)
+        );
+        assert_eq!(resolve_pixel_scale(image(2880), &empty, None), None);
+    }
+
     #[test]
     fn suggested_focus_crop_only_for_large_captures() {
         let small = WindowBounds {
@@ -867,6 +1483,49 @@ mod tests {
         );
     }
 
+    fn wb(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn rect(b: &WindowBounds) -> (i32, i32, u32, u32) {
+        (b.x, b.y, b.width, b.height)
+    }
+
+    #[test]
+    fn menu_bar_dropdown_pushed_left_of_notch_is_found() {
+        let display = (0, 0, 1512, 982);
+        // Status item right of the notch at x=820; its menu was shifted left.
+        let candidates = [
+            wb(805, 0, 30, 37),     // the status item itself
+            wb(560, 37, 280, 340),  // its dropdown
+            wb(100, 300, 400, 300), // unrelated popover further down
+        ];
+        assert_eq!(
+            pick_menu_bar_dropdown(&candidates, display, 820).map(|b| rect(&b)),
+            Some((560, 37, 280, 340))
+        );
+        assert!(pick_menu_bar_dropdown(&candidates[..1], display, 820).is_none());
+        // Too far from the click.
+        assert!(pick_menu_bar_dropdown(&[wb(1300, 24, 200, 300)], display, 100).is_none());
+    }
+
+    #[test]
+    fn menu_bar_dropdown_region_covers_click_and_menu() {
+        let display = (0, 0, 1512, 982);
+        let region = menu_bar_dropdown_region(&wb(560, 37, 280, 340), display, 880);
+        assert_eq!(rect(&region), (540, 0, 360, 397));
+
+        // Clamped to the display on a secondary screen.
+        let display = (1512, -200, 1920, 1080);
+        let region = menu_bar_dropdown_region(&wb(3200, -176, 240, 900), display, 3400);
+        assert_eq!(rect(&region), (3180, -200, 252, 944));
+    }
+
     #[test]
     fn should_use_menu_region_capture_rules() {
         assert!(should_use_menu_region_capture(
@@ -931,6 +1590,37 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn full_window_preference_picks_window_id_for_capturable_windows() {
+        let bounds = WindowBounds {
+            x: -200,
+            y: 100,
+            width: 1600,
+            height: 900,
+        };
+        assert_eq!(
+            first_click_capture(true, false, 42, &bounds),
+            ClickCaptureSource::WindowId
+        );
+        assert_eq!(
+            first_click_capture(false, false, 42, &bounds),
+            ClickCaptureSource::PreClickFrame
+        );
+        assert_eq!(
+            first_click_capture(true, true, 42, &bounds),
+            ClickCaptureSource::PreClickFrame
+        );
+        assert_eq!(
+            first_click_capture(true, false, 0, &bounds),
+            ClickCaptureSource::PreClickFrame
+        );
+        let empty = WindowBounds { width: 0, ..bounds };
+        assert_eq!(
+            first_click_capture(true, false, 42, &empty),
+            ClickCaptureSource::PreClickFrame
+        );
+    }
+
     #[test]
     fn no_transient_region_capture_for_right_click_or_dropdown_menu() {
         assert!(!should_prefer_transient_region_capture(
@@ -945,3 +1635,309 @@ mod tests {
         ));
     }
 }
+
+#[cfg(test)]
+mod bounds_percent_tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn assert_pct(pct: &BoundsPercent, expected: (f32, f32, f32, f32)) {
+        let actual = (
+            pct.x_percent,
+            pct.y_percent,
+            pct.width_percent,
+            pct.height_percent,
+        );
+        for (a, e) in [
+            (actual.0, expected.0),
+            (actual.1, expected.1),
+            (actual.2, expected.2),
+            (actual.3, expected.3),
+        ] {
+            assert!((a - e).abs() < 0.01, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn pct(x: f32, y: f32, width: f32, height: f32) -> BoundsPercent {
+        BoundsPercent {
+            x_percent: x,
+            y_percent: y,
+            width_percent: width,
+            height_percent: height,
+        }
+    }
+
+    #[test]
+    fn container_crop_pads_small_groups_up_to_the_minimum() {
+        // A 10% x 6% group: padded by 2% a side, then grown to 24% around its center.
+        let crop = container_focus_crop(&pct(40.0, 40.0, 10.0, 6.0), 45.0, 43.0).unwrap();
+        assert_pct(&crop, (33.0, 31.0, 24.0, 24.0));
+    }
+
+    #[test]
+    fn container_crop_snaps_to_a_sidebar_and_centers_its_long_side_on_the_click() {
+        // Sidebar 30% wide, full height: width follows it, height is capped at 80%.
+        let crop = container_focus_crop(&pct(0.0, 0.0, 30.0, 100.0), 10.0, 90.0).unwrap();
+        assert_pct(&crop, (0.0, 20.0, 34.0, 80.0));
+
+        // Toolbar across the top.
+        let crop = container_focus_crop(&pct(0.0, 2.0, 100.0, 5.0), 50.0, 4.0).unwrap();
+        assert_pct(&crop, (10.0, 0.0, 80.0, 24.0));
+    }
+
+    #[test]
+    fn container_crop_falls_back_when_snapping_does_not_help() {
+        // Container covers the whole capture.
+        assert_eq!(
+            container_focus_crop(&pct(0.0, 0.0, 95.0, 90.0), 50.0, 50.0),
+            None
+        );
+        // Click outside the container (beyond the margin).
+        assert_eq!(
+            container_focus_crop(&pct(10.0, 10.0, 20.0, 20.0), 60.0, 15.0),
+            None
+        );
+        assert_eq!(
+            container_focus_crop(&pct(10.0, 10.0, 0.0, 20.0), 10.0, 15.0),
+            None
+        );
+    }
+
+    #[test]
+    fn focus_crop_for_step_prefers_the_container_and_reports_the_strategy() {
+        let mut info: AxClickInfo = serde_json::from_value(serde_json::json!({
+            "role": "AXStaticText",
+            "label": "Downloads",
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .expect("ax info");
+        let mut step = Step {
+            capture_bounds: Some(CaptureBounds {
+                x: 0,
+                y: 0,
+                width: 2560,
+                height: 1440,
+                display_width: 2560,
+                display_height: 1440,
+            }),
+            click_x_percent: 10.0,
+            click_y_percent: 50.0,
+            ..Step::sample()
+        };
+
+        step.ax = Some(info.clone());
+        let (_, strategy) = focus_crop_for_step(&step).expect("click crop");
+        assert_eq!(strategy, FocusCropStrategy::Click);
+
+        info.container_bounds = Some(pct(0.0, 5.0, 20.0, 95.0));
+        step.ax = Some(info);
+        let (crop, strategy) = focus_crop_for_step(&step).expect("container crop");
+        assert_eq!(strategy, FocusCropStrategy::Container);
+        assert_pct(&crop, (0.0, 10.0, 24.0, 80.0));
+        assert_eq!(auto_crop_for_step(&step), Some(crop));
+    }
+
+    #[test]
+    fn element_partially_outside_is_clipped_to_visible_part() {
+        let capture = rect(100, 100, 1000, 500);
+        // Hangs 50pt off the left edge and 100pt off the bottom.
+        let element = rect(50, 500, 200, 200);
+        let pct = bounds_percent_in_capture(&element, &capture).expect("visible part");
+        assert_pct(&pct, (0.0, 80.0, 15.0, 20.0));
+    }
+
+    #[test]
+    fn element_fully_outside_has_no_bounds() {
+        let capture = rect(100, 100, 1000, 500);
+        assert_eq!(
+            bounds_percent_in_capture(&rect(1200, 200, 50, 50), &capture),
+            None
+        );
+        assert_eq!(
+            bounds_percent_in_capture(&rect(100, 0, 50, 100), &capture),
+            None
+        );
+    }
+
+    #[test]
+    fn element_spanning_displays_keeps_part_on_captured_display() {
+        // Secondary display left of the main one, captured in full.
+        let capture = rect(-1920, 0, 1920, 1080);
+        // Element straddles the boundary at x = 0.
+        let element = rect(-96, 540, 192, 54);
+        let pct = bounds_percent_in_capture(&element, &capture).expect("left half");
+        assert_pct(&pct, (95.0, 50.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn degenerate_capture_has_no_bounds() {
+        assert_eq!(
+            bounds_percent_in_capture(&rect(0, 0, 10, 10), &rect(0, 0, 1, 500)),
+            None
+        );
+    }
+
+    #[test]
+    fn ax_info_for_capture_maps_or_clears_element_bounds() {
+        let info: AxClickInfo = serde_json::from_value(serde_json::json!({
+            "role": "AXButton",
+            "label": "OK",
+            "is_cancel_button": false,
+            "is_default_button": true,
+        }))
+        .expect("ax info");
+        let capture = rect(0, 0, 1000, 500);
+        let element = rect(100, 50, 100, 50);
+
+        let container = rect(0, 0, 250, 500);
+
+        let mapped = ax_info_for_capture(
+            Some(info.clone()),
+            Some(&element),
+            Some(&container),
+            Some(&capture),
+        )
+        .expect("ax info kept");
+        assert_pct(
+            mapped.element_bounds.as_ref().expect("bounds"),
+            (10.0, 10.0, 10.0, 10.0),
+        );
+        assert_pct(
+            mapped.container_bounds.as_ref().expect("container bounds"),
+            (0.0, 0.0, 25.0, 100.0),
+        );
+
+        let without_capture =
+            ax_info_for_capture(Some(info), Some(&element), Some(&container), None).unwrap();
+        assert_eq!(without_capture.element_bounds, None);
+        assert_eq!(without_capture.container_bounds, None);
+        assert_eq!(
+            ax_info_for_capture(None, Some(&element), None, Some(&capture)),
+            None
+        );
+    }
+
+    #[test]
+    fn context_menu_union_covers_window_and_padded_menu() {
+        let window = wb(100, 100, 800, 600);
+
+        // Menu inside the window: the padding still reaches past the right edge.
+        let inside = context_menu_union(&window, &wb(700, 300, 180, 200));
+        assert_eq!(
+            (inside.x, inside.y, inside.width, inside.height),
+            (100, 100, 830, 600)
+        );
+
+        // Menu hanging off the bottom-left, on a display left of the main one.
+        let outside = context_menu_union(&wb(-1200, 100, 800, 600), &wb(-1250, 600, 200, 300));
+        assert_eq!(
+            (outside.x, outside.y, outside.width, outside.height),
+            (-1300, 100, 900, 850)
+        );
+    }
+
+    #[test]
+    fn plausible_context_menu_is_narrow_and_near_the_click() {
+        assert!(is_plausible_context_menu(&wb(510, 410, 220, 300), 500, 400));
+        assert!(!is_plausible_context_menu(
+            &wb(510, 410, 900, 300),
+            500,
+            400
+        ));
+        assert!(!is_plausible_context_menu(
+            &wb(900, 410, 220, 300),
+            500,
+            400
+        ));
+        assert!(!is_plausible_context_menu(&wb(510, 410, 220, 20), 500, 400));
+    }
+
+    #[test]
+    fn desktop_click_needs_finder_and_a_desktop_role() {
+        let display = wb(0, 0, 1440, 900);
+        let desktop = wb(0, 0, 1440, 900);
+        assert!(is_desktop_click(
+            "Finder",
+            "AXScrollArea",
+            "Desktop",
+            &desktop,
+            None,
+            &display
+        ));
+        assert!(is_desktop_click(
+            "Finder",
+            "AXGroup",
+            "",
+            &desktop,
+            Some(0),
+            &display
+        ));
+        // Another app's full-screen window.
+        assert!(!is_desktop_click(
+            "Preview",
+            "AXScrollArea",
+            "Desktop",
+            &desktop,
+            Some(0),
+            &display
+        ));
+        // A button on the desktop window is a real control.
+        assert!(!is_desktop_click(
+            "Finder",
+            "AXButton",
+            "Desktop",
+            &desktop,
+            Some(0),
+            &display
+        ));
+    }
+
+    #[test]
+    fn desktop_click_ignores_regular_finder_windows() {
+        let display = wb(-1920, 0, 1920, 1080);
+        // A browser window's file list is a scroll area too.
+        assert!(!is_desktop_click(
+            "Finder",
+            "AXScrollArea",
+            "Downloads",
+            &wb(-1800, 100, 900, 600),
+            Some(0),
+            &display
+        ));
+        // Covers the display but sits at another layer.
+        assert!(!is_desktop_click(
+            "Finder",
+            "AXScrollArea",
+            "",
+            &wb(-1920, 0, 1920, 1080),
+            Some(3),
+            &display
+        ));
+        // Covers the display on the main screen, not the clicked one.
+        assert!(!is_desktop_click(
+            "Finder",
+            "AXScrollArea",
+            "",
+            &wb(0, 0, 1920, 1080),
+            Some(0),
+            &display
+        ));
+        assert!(is_desktop_click(
+            "Finder",
+            "AXScrollArea",
+            "",
+            &wb(-1920, 0, 1920, 1080),
+            Some(0),
+            &display
+        ));
+    }
+}
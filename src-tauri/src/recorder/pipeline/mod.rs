@@ -6,31 +6,58 @@
 //! - Captures a screenshot of that window
 //! - Creates a Step with the click position as percentages within the window
 
+mod auto_description;
+mod context;
+mod context_menu;
+mod daemon_dialog;
+mod drag;
 mod helpers;
+mod overview;
+mod preview;
+mod privacy_blur;
+mod recapture;
+mod result_frame;
+mod space_change;
 mod types;
 
-pub use helpers::{handle_auth_prompt, record_panel_bounds, record_tray_click, set_panel_visible};
+pub use context::insert_context_break;
+pub use drag::process_drag_end;
+pub use helpers::{
+    auto_crop_for_step, handle_auth_prompt, record_panel_bounds, record_tray_click,
+    set_panel_visible, validate_screenshot,
+};
+pub use overview::capture_overview_step;
+pub use preview::{preview_capture, preview_capture_path, CapturePreview};
+pub use privacy_blur::apply_privacy_blur;
+pub use recapture::recapture_step;
+pub use result_frame::{
+    capture_result_frame, schedule_result_frame, take_due_result_frame,
+    DEFAULT_RESULT_FRAME_DELAY_MS,
+};
+pub use space_change::mark_space_change;
 pub use types::*;
 
+use super::browser;
 use super::cg_capture::capture_window_cg;
 use super::click_event::ClickEvent;
 use super::macos_screencapture::capture_window as capture_window_by_id;
 use super::pre_click_buffer::PreClickFrameBuffer;
 use super::session::Session;
-use super::types::{ActionType, AxClickInfo, CaptureStatus, Step};
-use super::window_info::{
-    find_attached_dialog_window, get_frontmost_window, get_main_window_for_pid,
-    get_security_agent_window, get_topmost_window_at_point, get_window_for_pid_at_click,
-    WindowBounds,
+use super::types::{
+    ActionType, AxClickInfo, CaptureBounds, CaptureStatus, CaptureTimings, Step, WindowControlKind,
+    AUTH_PLACEHOLDER_TITLE,
 };
+use super::window_info::{bundle_id_for_pid, get_window_bounds, WindowBounds, WindowSnapshot};
+use auto_description::apply_auto_description;
 use helpers::*;
 
 use super::ax_helpers::{
-    get_clicked_element_info, get_clicked_element_label, is_security_agent_process,
-    is_system_ui_process,
+    get_browser_page_url, get_clicked_element_info, get_clicked_element_label,
+    get_open_context_menu_bounds, is_security_agent_process, is_system_ui_process,
 };
 
 use std::sync::Mutex;
+use std::time::Instant;
 
 fn normalize_app_name(name: &str) -> String {
     name.chars()
@@ -45,6 +72,24 @@ fn app_names_match(left: &str, right: &str) -> bool {
     !left_norm.is_empty() && left_norm == right_norm
 }
 
+/// Whether a click in `clicked_app` should be recorded under `record_only_app`.
+/// Clicks whose app can't be resolved are dropped while a scope is set.
+fn in_recording_scope(record_only_app: Option<&str>, clicked_app: Option<&str>) -> bool {
+    match record_only_app {
+        None => true,
+        Some(scope) => clicked_app.is_some_and(|app| app_names_match(app, scope)),
+    }
+}
+
+/// Whether a click in `clicked_app` (with `bundle_id`) hits an entry of `blocked_apps`.
+/// Entries are app names or bundle IDs, matched like [`app_names_match`].
+fn is_blocked_app(blocked_apps: &[String], clicked_app: &str, bundle_id: Option<&str>) -> bool {
+    blocked_apps.iter().any(|entry| {
+        app_names_match(entry, clicked_app)
+            || bundle_id.is_some_and(|id| app_names_match(entry, id))
+    })
+}
+
 fn is_own_app_name(name: &str) -> bool {
     let normalized = normalize_app_name(name);
     !normalized.is_empty() && normalized.contains("stepcast")
@@ -86,39 +131,6 @@ fn bounds_contained_with_margin(inner: &WindowBounds, outer: &WindowBounds, marg
         && inner_bottom <= outer_bottom + margin
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum WindowControlKind {
-    Close,
-    Minimize,
-    Zoom,
-}
-
-impl WindowControlKind {
-    fn label(self) -> &'static str {
-        match self {
-            Self::Close => "Close",
-            Self::Minimize => "Minimize",
-            Self::Zoom => "Zoom",
-        }
-    }
-
-    fn subrole(self) -> &'static str {
-        match self {
-            Self::Close => "AXCloseButton",
-            Self::Minimize => "AXMinimizeButton",
-            Self::Zoom => "AXZoomButton",
-        }
-    }
-
-    fn role_description(self) -> &'static str {
-        match self {
-            Self::Close => "Close button",
-            Self::Minimize => "Minimize button",
-            Self::Zoom => "Zoom button",
-        }
-    }
-}
-
 fn infer_window_control_kind(
     click_x: i32,
     click_y: i32,
@@ -189,7 +201,8 @@ fn infer_window_control_kind(
     }
 }
 
-/// Process a click event and create a step with screenshot.
+/// Process a click, a hover synthesized by the hover shortcut, or a keyboard shortcut,
+/// and create a step with screenshot.
 ///
 /// This function orchestrates the full capture pipeline:
 /// 1. Get frontmost window info
@@ -199,17 +212,40 @@ fn infer_window_control_kind(
 ///
 /// # Arguments
 ///
-/// * `click` - The click event to process
+/// * `click` - The click event to process; hovers and shortcuts skip the click-only
+///   debouncing, double-click and context menu handling
 /// * `session` - The current recording session (used for step IDs and screenshot paths)
+/// * `windows` - The click's copy of the window list, shared with [`handle_auth_prompt`]
 ///
 /// # Returns
 ///
 /// Returns the created Step on success, or a PipelineError if any step fails.
-pub fn process_click(
+pub fn process_pointer_event(
+    click: &ClickEvent,
+    session: &mut Session,
+    pipeline_state: &Mutex<PipelineState>,
+    pre_click_buffer: Option<&PreClickFrameBuffer>,
+    windows: &WindowSnapshot,
+) -> Result<Step, PipelineError> {
+    process_pointer_event_with(
+        click,
+        session,
+        pipeline_state,
+        pre_click_buffer,
+        windows,
+        &mut || WindowSnapshot::capture().unwrap_or_default(),
+    )
+}
+
+/// [`process_pointer_event`] taking a fresh window list from `capture_windows` per
+/// attempt while polling for menus; every other lookup reads `windows`.
+fn process_pointer_event_with(
     click: &ClickEvent,
     session: &mut Session,
     pipeline_state: &Mutex<PipelineState>,
     pre_click_buffer: Option<&PreClickFrameBuffer>,
+    windows: &WindowSnapshot,
+    capture_windows: &mut dyn FnMut() -> WindowSnapshot,
 ) -> Result<Step, PipelineError> {
     debug_log(
         session,
@@ -222,7 +258,7 @@ pub fn process_click(
     session.diagnostics.clicks_received += 1;
 
     // Filter clicks on our panel / tray icon
-    {
+    let (locale, auto_crop, prefer_full_window, ignore_desktop_clicks) = {
         let ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
         if should_filter_panel_click(&ps, click) {
             debug_log(session, "filtered: panel click");
@@ -234,13 +270,38 @@ pub fn process_click(
             session.diagnostics.clicks_filtered += 1;
             return Err(PipelineError::OwnAppClick);
         }
-    }
+        if let Some(keys) = click
+            .shortcut
+            .as_ref()
+            .filter(|keys| ps.own_shortcuts.contains(keys))
+        {
+            debug_log(session, &format!("filtered: own shortcut {keys}"));
+            session.diagnostics.clicks_filtered += 1;
+            return Err(PipelineError::OwnAppClick);
+        }
+        (
+            ps.locale,
+            ps.auto_crop,
+            ps.prefer_full_window_capture,
+            ps.ignore_desktop_clicks,
+        )
+    };
 
-    // 0a. Get info about the actual clicked element
+    // 0a. Get info about the actual clicked element. A shortcut acts on the focused
+    // element, not the one under the pointer, so it gets no element metadata.
+    let ax_started = Instant::now();
     let clicked_info = get_clicked_element_info(click.x, click.y);
-    let clicked_ax = get_clicked_element_label(click.x as f32, click.y as f32);
+    let clicked_ax = click
+        .shortcut
+        .is_none()
+        .then(|| get_clicked_element_label(click.x as f32, click.y as f32))
+        .flatten();
+    let mut timings = CaptureTimings {
+        ax_lookup_ms: Some(elapsed_ms(ax_started)),
+        ..CaptureTimings::default()
+    };
     if let Some(ax) = clicked_ax.as_ref() {
-        if ax.role == accessibility_sys::kAXMenuBarItemRole {
+        if ax.role == accessibility_sys::kAXMenuBarItemRole && !click.is_synthetic() {
             let mut ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
             ps.last_menu_bar_click_ms = Some(click.timestamp_ms);
         }
@@ -263,26 +324,12 @@ pub fn process_click(
         );
     }
 
-    let mut ax_info: Option<AxClickInfo> = clicked_ax.as_ref().map(|ax| AxClickInfo {
-        role: ax.role.clone(),
-        subrole: ax.subrole.clone(),
-        role_description: ax.role_description.clone(),
-        identifier: ax.identifier.clone(),
-        label: ax.label.clone(),
-        element_bounds: None,
-        container_role: ax.container_role.clone(),
-        container_subrole: ax.container_subrole.clone(),
-        container_identifier: ax.container_identifier.clone(),
-        window_role: ax.window_role.clone(),
-        window_subrole: ax.window_subrole.clone(),
-        top_level_role: ax.top_level_role.clone(),
-        top_level_subrole: ax.top_level_subrole.clone(),
-        parent_dialog_role: ax.parent_dialog_role.clone(),
-        parent_dialog_subrole: ax.parent_dialog_subrole.clone(),
-        is_checked: ax.is_checked,
-        is_cancel_button: ax.is_cancel_button,
-        is_default_button: ax.is_default_button,
-    });
+    // Screen rect of the clicked element; each capture branch maps it into its capture.
+    let element_screen_bounds = clicked_ax.as_ref().and_then(|ax| ax.element_bounds.clone());
+    let container_screen_bounds = clicked_ax
+        .as_ref()
+        .and_then(|ax| ax.container_bounds.clone());
+    let mut ax_info: Option<AxClickInfo> = clicked_ax.as_ref().map(ax_click_info);
 
     // 0b. Filter clicks on our own app using Accessibility API
     if let Some((clicked_pid, ref clicked_app)) = clicked_info {
@@ -305,8 +352,44 @@ pub fn process_click(
         }
     }
 
+    // Drop clicks outside the app the recording is scoped to
+    let record_only_app = pipeline_state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .record_only_app
+        .clone();
+    let clicked_app = clicked_info.as_ref().map(|(_, app)| app.as_str());
+    if !in_recording_scope(record_only_app.as_deref(), clicked_app) {
+        let app = clicked_app.unwrap_or_default().to_string();
+        debug_log(session, &format!("filtered: out of scope click {app}"));
+        session.diagnostics.clicks_filtered += 1;
+        return Err(PipelineError::IgnoredOutOfScope(app));
+    }
+
+    // Never capture apps on the user's blocklist (password managers, banking)
+    let clicked_bundle_id = clicked_info
+        .as_ref()
+        .and_then(|(clicked_pid, _)| bundle_id_for_pid(*clicked_pid));
+    let blocked_apps = pipeline_state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .blocked_apps
+        .clone();
+    if let Some((_, clicked_app)) = clicked_info.as_ref() {
+        if is_blocked_app(&blocked_apps, clicked_app, clicked_bundle_id.as_deref()) {
+            debug_log(
+                session,
+                &format!("filtered: blocked app click {clicked_app}"),
+            );
+            session.diagnostics.clicks_filtered += 1;
+            return Err(PipelineError::BlockedApp(clicked_app.clone()));
+        }
+    }
+
     // 0c. Debounce rapid duplicate clicks (but allow double-click upgrades)
-    let (should_debounce, should_upgrade) = {
+    let (should_debounce, should_upgrade) = if click.is_synthetic() {
+        (false, false)
+    } else {
         let mut ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
         is_debounced(
             &mut ps,
@@ -344,24 +427,57 @@ pub fn process_click(
     }
 
     // 0d. Check cooldown after auth dialog clicks (phantom click prevention)
-    {
+    if !click.is_synthetic() {
         let ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
-        if let Some(last_ts) = ps.last_auth_click_ms {
-            let time_since_auth = click.timestamp_ms - last_ts;
-            if time_since_auth > 0 && time_since_auth < AUTH_DIALOG_COOLDOWN_MS {
-                debug_log(
-                    session,
-                    &format!("filtered: phantom click {time_since_auth}ms after auth dialog"),
+        if is_phantom_after_auth(
+            ps.last_auth_click_ms,
+            click.timestamp_ms,
+            ps.auth_dialog_cooldown_ms,
+        ) {
+            let time_since_auth = click.timestamp_ms - ps.last_auth_click_ms.unwrap_or_default();
+            debug_log(
+                session,
+                &format!("filtered: phantom click {time_since_auth}ms after auth dialog"),
+            );
+            if cfg!(debug_assertions) {
+                eprintln!(
+                    "Filtered phantom click at ({}, {}) - {time_since_auth}ms after auth dialog",
+                    click.x, click.y
                 );
-                if cfg!(debug_assertions) {
-                    eprintln!(
-                        "Filtered phantom click at ({}, {}) - {time_since_auth}ms after auth dialog",
-                        click.x, click.y
-                    );
-                }
-                session.diagnostics.clicks_filtered += 1;
-                return Err(PipelineError::DebouncedClick);
             }
+            session.diagnostics.clicks_filtered += 1;
+            return Err(PipelineError::DebouncedClick);
+        }
+    }
+
+    // Browser clicks record the tab's URL. Lookup failures leave it unset; the
+    // budget caps what a slow browser can add to the capture.
+    let page_url = clicked_info
+        .as_ref()
+        .filter(|(_, app)| browser::is_known_browser(clicked_bundle_id.as_deref(), app))
+        .and_then(|(pid, _)| {
+            get_browser_page_url(
+                *pid,
+                click.x as f32,
+                click.y as f32,
+                browser::PAGE_URL_BUDGET,
+            )
+        });
+    if let Some(url) = page_url.as_deref() {
+        debug_log(session, &format!("page_url: {url}"));
+    }
+
+    // 0e. Picking an item from the previous right-click's context menu updates that step;
+    // its screenshot still shows the menu, which has closed by now.
+    if !click.is_synthetic() {
+        if let Some(step_id) = context_menu::merge_context_menu_selection(
+            click,
+            session,
+            pipeline_state,
+            clicked_ax.as_ref(),
+            windows,
+        ) {
+            return Err(PipelineError::MergedIntoContextMenu(step_id));
         }
     }
 
@@ -369,16 +485,16 @@ pub fn process_click(
     // Primary: heuristic window detection, fallback: process name list
     let mut auth_window = if let Some((_, ref clicked_app)) = clicked_info {
         if is_security_agent_process(clicked_app) {
-            find_security_auth_window(click.x, click.y, clicked_info.is_none())
+            find_security_auth_window(windows, click.x, click.y, clicked_info.is_none())
         } else {
             None
         }
     } else {
-        find_security_auth_window(click.x, click.y, true)
+        find_security_auth_window(windows, click.x, click.y, true)
     };
 
     if auth_window.is_none() {
-        if let Ok(Some(window)) = get_security_agent_window() {
+        if let Some(window) = windows.security_agent_window() {
             auth_window = Some(window);
             if cfg!(debug_assertions) {
                 eprintln!("Auth dialog detected via security agent name fallback");
@@ -410,7 +526,7 @@ pub fn process_click(
     );
 
     // Record auth dialog click timestamp for phantom click prevention
-    if is_auth_dialog {
+    if is_auth_dialog && !click.is_synthetic() {
         let mut ps = pipeline_state.lock().unwrap_or_else(|e| e.into_inner());
         ps.last_auth_click_ms = Some(click.timestamp_ms);
     }
@@ -480,7 +596,7 @@ pub fn process_click(
                 (x, y, w, h, "fallback")
             };
 
-        if let Ok(parent_window) = get_frontmost_window() {
+        if let Ok(parent_window) = windows.frontmost_window() {
             let parent = parent_window.bounds;
             let region_bounds = WindowBounds {
                 x: region_x,
@@ -558,6 +674,7 @@ pub fn process_click(
             ),
         );
 
+        let capture_started = Instant::now();
         capture_region_best(
             session,
             region_x,
@@ -567,6 +684,7 @@ pub fn process_click(
             &screenshot_path,
         )
         .map_err(|e| PipelineError::ScreenshotFailed(format!("{e}")))?;
+        timings.capture_ms = Some(elapsed_ms(capture_started));
 
         let click_x_percent =
             ((click.x - region_x) as f64 / region_width as f64 * 100.0).clamp(0.0, 100.0);
@@ -579,13 +697,7 @@ pub fn process_click(
             height: region_height as u32,
         };
 
-        use super::click_event::MouseButton;
-        let action = match (click.button, click.click_count) {
-            (MouseButton::Right, _) => ActionType::RightClick,
-            (MouseButton::Left, 2) => ActionType::DoubleClick,
-            (MouseButton::Left, n) if n >= 3 => ActionType::DoubleClick,
-            _ => ActionType::Click,
-        };
+        let action = click.action();
 
         let app_name = clicked_info
             .as_ref()
@@ -598,29 +710,13 @@ pub fn process_click(
             }
         }
 
-        let mut ax_info_for_step = ax_info.clone();
-        if let (Some(ref mut info), Some(ax_label)) =
-            (ax_info_for_step.as_mut(), clicked_ax.as_ref())
-        {
-            info.element_bounds = ax_label
-                .element_bounds
-                .as_ref()
-                .and_then(|b| bounds_percent_in_capture(b, &capture_bounds));
-        }
-        let auto_crop_region = if should_apply_focus_crop(&capture_bounds, display_w, display_h) {
-            suggested_focus_crop_for_capture(
-                &capture_bounds,
-                click_x_percent,
-                click_y_percent,
-                ax_info_for_step
-                    .as_ref()
-                    .and_then(|info| info.element_bounds.as_ref()),
-            )
-        } else {
-            None
-        };
-
-        let step = Step {
+        let ax_info_for_step = ax_info_for_capture(
+            ax_info.clone(),
+            element_screen_bounds.as_ref(),
+            container_screen_bounds.as_ref(),
+            Some(&capture_bounds),
+        );
+        let mut step = Step {
             id: step_id,
             ts: click.timestamp_ms,
             action,
@@ -639,16 +735,68 @@ pub fn process_click(
             ax: ax_info_for_step,
             capture_status: Some(CaptureStatus::Ok),
             capture_error: None,
-            crop_region: auto_crop_region,
+            crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: Some(capture_bounds_on_display(
+                &capture_bounds,
+                display_w,
+                display_h,
+            )),
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: clicked_bundle_id.clone(),
+            annotations: Vec::new(),
+            capture_timings: Some(timings),
+            page_url: page_url.clone(),
+            keys: click.shortcut.clone(),
+            title: None,
         };
+        if auto_crop {
+            apply_auto_crop(session, &mut step);
+        }
+        record_capture_pixels(&mut step);
+        record_toggle_state(&mut step, click);
+        apply_auto_description(&mut step, locale);
 
         session.add_step(step.clone());
         return Ok(step);
     }
 
+    // Daemon dialogs: no AX element under the click, but a small window on top.
+    // Capture just that window instead of falling through to a fullscreen capture.
+    if clicked_info.is_none() && !is_auth_dialog {
+        let window_started = Instant::now();
+        let topmost = windows.topmost_window_at_point(click.x, click.y);
+        let is_dialog = topmost
+            .as_ref()
+            .is_some_and(daemon_dialog::is_daemon_dialog);
+        debug_log(
+            session,
+            &format!(
+                "daemon_dialog_check: topmost={} owner='{}' is_dialog={}",
+                topmost.as_ref().map(|w| w.window_id).unwrap_or(0),
+                topmost.as_ref().map(|w| w.app_name.as_str()).unwrap_or(""),
+                is_dialog
+            ),
+        );
+        if let Some(dialog) = topmost.filter(|_| is_dialog) {
+            timings.window_resolve_ms = Some(elapsed_ms(window_started));
+            return daemon_dialog::capture_daemon_dialog(click, session, &dialog, timings);
+        }
+    }
+
     // 1. Get the main (largest) window of the frontmost app
-    let window_info =
-        get_frontmost_window().map_err(|e| PipelineError::WindowInfoFailed(format!("{e}")))?;
+    let window_started = Instant::now();
+    let window_info = windows
+        .frontmost_window()
+        .map_err(|e| PipelineError::WindowInfoFailed(format!("{e}")))?;
+    let context_title = Some(window_info.window_title.trim().to_string()).filter(|t| !t.is_empty());
 
     // Detect traffic-light window controls early and capture immediately.
     // This avoids dark "closing animation" frames for close/minimize/zoom clicks.
@@ -677,6 +825,7 @@ pub fn process_click(
     };
 
     if let Some(control) = inferred_window_control {
+        timings.window_resolve_ms = Some(elapsed_ms(window_started));
         if let Some(ref mut info) = ax_info {
             info.role = accessibility_sys::kAXButtonRole.to_string();
             info.subrole = Some(control.subrole().to_string());
@@ -735,6 +884,7 @@ pub fn process_click(
             ),
         );
 
+        let capture_started = Instant::now();
         capture_region_best(
             session,
             capture_bounds.x,
@@ -744,40 +894,22 @@ pub fn process_click(
             &screenshot_path,
         )
         .map_err(|e| PipelineError::ScreenshotFailed(format!("{e}")))?;
+        timings.capture_ms = Some(elapsed_ms(capture_started));
 
-        if let (Some(ref mut info), Some(ax_label)) = (ax_info.as_mut(), clicked_ax.as_ref()) {
-            info.element_bounds = ax_label
-                .element_bounds
-                .as_ref()
-                .and_then(|b| bounds_percent_in_capture(b, &capture_bounds));
-        }
+        let ax_info = ax_info_for_capture(
+            ax_info,
+            element_screen_bounds.as_ref(),
+            container_screen_bounds.as_ref(),
+            Some(&capture_bounds),
+        );
 
-        use super::click_event::MouseButton;
-        let action = match (click.button, click.click_count) {
-            (MouseButton::Right, _) => ActionType::RightClick,
-            (MouseButton::Left, 2) => ActionType::DoubleClick,
-            (MouseButton::Left, n) if n >= 3 => ActionType::DoubleClick,
-            _ => ActionType::Click,
-        };
+        let action = click.action();
 
         let click_x_percent =
             calculate_click_percent(click.x, capture_bounds.x, capture_bounds.width as i32);
         let click_y_percent =
             calculate_click_percent(click.y, capture_bounds.y, capture_bounds.height as i32);
-        let auto_crop_region = if should_apply_focus_crop(&capture_bounds, display_w, display_h) {
-            suggested_focus_crop_for_capture(
-                &capture_bounds,
-                click_x_percent,
-                click_y_percent,
-                ax_info
-                    .as_ref()
-                    .and_then(|info| info.element_bounds.as_ref()),
-            )
-        } else {
-            None
-        };
-
-        let step = Step {
+        let mut step = Step {
             id: step_id,
             ts: click.timestamp_ms,
             action,
@@ -803,8 +935,34 @@ pub fn process_click(
             ax: ax_info,
             capture_status: Some(CaptureStatus::Ok),
             capture_error: None,
-            crop_region: auto_crop_region,
+            crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title,
+            capture_bounds: Some(capture_bounds_on_display(
+                &capture_bounds,
+                display_w,
+                display_h,
+            )),
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: clicked_bundle_id.clone(),
+            annotations: Vec::new(),
+            capture_timings: Some(timings),
+            page_url: page_url.clone(),
+            keys: click.shortcut.clone(),
+            title: None,
         };
+        if auto_crop {
+            apply_auto_crop(session, &mut step);
+        }
+        record_capture_pixels(&mut step);
+        record_toggle_state(&mut step, click);
+        apply_auto_description(&mut step, locale);
 
         session.add_step(step.clone());
         return Ok(step);
@@ -812,7 +970,7 @@ pub fn process_click(
 
     // 2. Check if click is on a popup/menu window (only for frontmost app's windows)
     //    We look for smaller overlay windows that belong to the same app
-    let topmost_at_click = get_topmost_window_at_point(click.x, click.y);
+    let topmost_at_click = windows.topmost_window_at_point(click.x, click.y);
 
     // Determine which window to use for capture:
     // - For auth dialogs, use the security agent window
@@ -824,12 +982,17 @@ pub fn process_click(
     let attached_dialog = if !is_auth_dialog {
         if let Some(ref topmost) = topmost_at_click {
             if topmost.window_id == window_info.window_id {
-                find_attached_dialog_window(click.x, click.y, &window_info, attached_dialog_owner)
+                windows.attached_dialog_window(
+                    click.x,
+                    click.y,
+                    &window_info,
+                    attached_dialog_owner,
+                )
             } else {
                 None
             }
         } else {
-            find_attached_dialog_window(click.x, click.y, &window_info, attached_dialog_owner)
+            windows.attached_dialog_window(click.x, click.y, &window_info, attached_dialog_owner)
         }
     } else {
         None
@@ -882,7 +1045,7 @@ pub fn process_click(
                 );
             }
             auth_window
-        } else if let Ok(Some(auth_window)) = get_security_agent_window() {
+        } else if let Some(auth_window) = windows.security_agent_window() {
             debug_log(
                 session,
                 &format!(
@@ -1024,7 +1187,7 @@ pub fn process_click(
             // Only switch capture windows when we can resolve a concrete window
             // under the click for the clicked PID. Falling back to the "largest"
             // window can jump to unrelated apps/windows.
-            if let Some(clicked_window) = get_window_for_pid_at_click(
+            if let Some(clicked_window) = windows.window_for_pid_at_click(
                 clicked_pid,
                 clicked_app,
                 click.x,
@@ -1125,6 +1288,41 @@ pub fn process_click(
         }
     }
 
+    // Stray clicks on the wallpaper or desktop icon area, e.g. to dismiss something.
+    if ignore_desktop_clicks && !is_auth_dialog && !click.is_synthetic() {
+        if let Some(ax_label) = clicked_ax.as_ref() {
+            let (display_x, display_y, display_w, display_h) =
+                get_display_bounds_for_click(click.x, click.y);
+            let display = WindowBounds {
+                x: display_x,
+                y: display_y,
+                width: display_w.max(0) as u32,
+                height: display_h.max(0) as u32,
+            };
+            let window_layer = windows.layer_of(capture_window.window_id);
+            if is_desktop_click(
+                &actual_app_name,
+                &ax_label.role,
+                &capture_window.window_title,
+                &capture_window.bounds,
+                window_layer,
+                &display,
+            ) {
+                debug_log(
+                    session,
+                    &format!(
+                        "filtered: desktop click role={} window='{}' layer={window_layer:?}",
+                        ax_label.role, capture_window.window_title
+                    ),
+                );
+                session.diagnostics.clicks_filtered += 1;
+                return Err(PipelineError::IgnoredDesktopClick);
+            }
+        }
+    }
+
+    timings.window_resolve_ms = Some(elapsed_ms(window_started));
+
     // 2. Generate step ID and screenshot path
     let step_id = session.next_step_id();
     let screenshot_path = session.screenshot_path(&step_id);
@@ -1149,6 +1347,7 @@ pub fn process_click(
     // Track capture outcome across all branches
     let mut final_capture_status = CaptureStatus::Ok;
     let mut final_capture_error: Option<String> = None;
+    let mut wrote_auth_placeholder = false;
     let (click_display_x, click_display_y, click_display_w, click_display_h) =
         get_display_bounds_for_click(click.x, click.y);
 
@@ -1159,12 +1358,42 @@ pub fn process_click(
     // Keep right-click and auth dialogs on dedicated paths:
     // - right-click wants post-click context menu capture
     // - auth dialogs may require secure placeholders/window-ID capture semantics
+    //
+    // With the full-window preference, capturable windows go to window-ID capture
+    // instead, which isn't clipped where the window runs off the display.
     let is_right_click = matches!(click.button, super::click_event::MouseButton::Right);
+    let first_capture = first_click_capture(
+        prefer_full_window,
+        click.hover,
+        capture_window.window_id,
+        &capture_window.bounds,
+    );
+    if first_capture == ClickCaptureSource::WindowId {
+        debug_log(
+            session,
+            &format!(
+                "preclick_fullframe_capture skipped: full window preferred id={}",
+                capture_window.window_id
+            ),
+        );
+    }
     const PRECLICK_MAX_AGE_MS: i64 = 250;
-    let pre_click_fullframe_capture = if !is_right_click && !is_auth_dialog {
+    // A hover's tooltip stays up, so an older frame still shows it.
+    const PREHOVER_MAX_AGE_MS: i64 = 1_000;
+    let max_frame_age_ms = if click.hover {
+        PREHOVER_MAX_AGE_MS
+    } else {
+        PRECLICK_MAX_AGE_MS
+    };
+    let capture_started = Instant::now();
+    let pre_click_fullframe_capture = if !is_right_click
+        && !is_auth_dialog
+        && first_capture == ClickCaptureSource::PreClickFrame
+    {
         if let Some(buffer) = pre_click_buffer {
             match buffer.capture_for_click(click.x, click.y, click.timestamp_ms, &screenshot_path) {
-                Ok(Some(pre)) if (0..=PRECLICK_MAX_AGE_MS).contains(&pre.frame_age_ms) => {
+                Ok(Some(pre)) if (0..=max_frame_age_ms).contains(&pre.frame_age_ms) => {
+                    timings.pre_click_frame_age_ms = Some(pre.frame_age_ms);
                     debug_log(
                         session,
                         &format!(
@@ -1183,7 +1412,7 @@ pub fn process_click(
                         session,
                         &format!(
                             "preclick_fullframe_capture stale: age_ms={} (max={})",
-                            pre.frame_age_ms, PRECLICK_MAX_AGE_MS
+                            pre.frame_age_ms, max_frame_age_ms
                         ),
                     );
                     None
@@ -1207,6 +1436,8 @@ pub fn process_click(
         None
     };
 
+    // Window ID and bounds of a context menu included in the capture.
+    let mut captured_context_menu: Option<(u32, WindowBounds)> = None;
     let (click_x_percent, click_y_percent, capture_bounds_for_step) = if let Some(pre) =
         pre_click_fullframe_capture
     {
@@ -1299,8 +1530,9 @@ pub fn process_click(
             write_auth_placeholder(&screenshot_path, bounds.width, bounds.height)
                 .map_err(|e| PipelineError::ScreenshotFailed(format!("{e}")))?;
 
-            actual_window_title = "Authentication dialog (secure)".to_string();
+            actual_window_title = AUTH_PLACEHOLDER_TITLE.to_string();
             resolved_window_title = actual_window_title.clone();
+            wrote_auth_placeholder = true;
             debug_log(session, "auth_placeholder_written");
 
             let x_pct = calculate_click_percent(click.x, bounds.x, bounds.width as i32);
@@ -1368,17 +1600,64 @@ pub fn process_click(
 
         if use_region_capture {
             resolved_window_title = "Menu".to_string();
-            // Menubar/dropdown click - capture a region around the click
-            let region_height = 500.min(click_display_h.max(1)); // Include dropdown content
-            let region_width = 600.min(click_display_w.max(1));
+            // Menu bar clicks open a dropdown that macOS may shift sideways (away from
+            // the notch), so look for it before falling back to a fixed region.
+            let click_display = (
+                click_display_x,
+                click_display_y,
+                click_display_w,
+                click_display_h,
+            );
+            let dropdown = if click_y_in_display < MENU_BAR_MAX_HEIGHT {
+                let owner = clicked_info
+                    .as_ref()
+                    .map_or(capture_window.app_name.as_str(), |(_, app)| app.as_str());
+                poll_menu_bar_dropdown(capture_windows, owner, click_display, click.x)
+            } else {
+                None
+            };
 
-            // Center horizontally on click, clamped to clicked display bounds.
-            let min_region_x = click_display_x;
-            let max_region_x = (click_display_x + click_display_w - region_width).max(min_region_x);
-            let region_x = (click.x - region_width / 2).clamp(min_region_x, max_region_x);
-            // For dropdown clicks, start capture from top of the clicked display
-            // (not global y=0) so secondary-display menubars are captured correctly.
-            let region_y = click_display_y;
+            let (region_x, region_y, region_width, region_height) = if let Some(dropdown) = dropdown
+            {
+                let region = menu_bar_dropdown_region(&dropdown, click_display, click.x);
+                debug_log(
+                    session,
+                    &format!(
+                        "menu_region_capture: dropdown ({}, {}, {}x{}) -> region ({}, {}, {}x{})",
+                        dropdown.x,
+                        dropdown.y,
+                        dropdown.width,
+                        dropdown.height,
+                        region.x,
+                        region.y,
+                        region.width,
+                        region.height
+                    ),
+                );
+                (
+                    region.x,
+                    region.y,
+                    region.width as i32,
+                    region.height as i32,
+                )
+            } else {
+                debug_log(
+                    session,
+                    "menu_region_capture: no dropdown found, using fixed region around click",
+                );
+                // Menubar/dropdown click - capture a region around the click
+                let region_height = 500.min(click_display_h.max(1)); // Include dropdown content
+                let region_width = 600.min(click_display_w.max(1));
+
+                // Center horizontally on click, clamped to clicked display bounds.
+                let min_region_x = click_display_x;
+                let max_region_x =
+                    (click_display_x + click_display_w - region_width).max(min_region_x);
+                let region_x = (click.x - region_width / 2).clamp(min_region_x, max_region_x);
+                // For dropdown clicks, start capture from top of the clicked display
+                // (not global y=0) so secondary-display menubars are captured correctly.
+                (region_x, click_display_y, region_width, region_height)
+            };
 
             // Capture the region
             capture_region_best(
@@ -1397,31 +1676,24 @@ pub fn process_click(
             let y_pct =
                 ((click.y - region_y) as f64 / region_height as f64 * 100.0).clamp(0.0, 100.0);
 
-            let mut ax_info_for_step = ax_info.clone();
-            if let (Some(ref mut info), Some(ax_label)) =
-                (ax_info_for_step.as_mut(), clicked_ax.as_ref())
-            {
-                let capture_bounds = super::window_info::WindowBounds {
-                    x: region_x,
-                    y: region_y,
-                    width: region_width as u32,
-                    height: region_height as u32,
-                };
-                info.element_bounds = ax_label
-                    .element_bounds
-                    .as_ref()
-                    .and_then(|b| bounds_percent_in_capture(b, &capture_bounds));
-            }
+            let region = WindowBounds {
+                x: region_x,
+                y: region_y,
+                width: region_width as u32,
+                height: region_height as u32,
+            };
+            let ax_info_for_step = ax_info_for_capture(
+                ax_info.clone(),
+                element_screen_bounds.as_ref(),
+                container_screen_bounds.as_ref(),
+                Some(&region),
+            );
 
-            let step = Step {
+            timings.capture_ms = Some(elapsed_ms(capture_started));
+            let mut step = Step {
                 id: step_id,
                 ts: click.timestamp_ms,
-                action: match (click.button, click.click_count) {
-                    (super::click_event::MouseButton::Right, _) => ActionType::RightClick,
-                    (super::click_event::MouseButton::Left, 2) => ActionType::DoubleClick,
-                    (super::click_event::MouseButton::Left, n) if n >= 3 => ActionType::DoubleClick,
-                    _ => ActionType::Click,
-                },
+                action: click.action(),
                 x: click.x,
                 y: click.y,
                 click_x_percent: x_pct as f32,
@@ -1438,7 +1710,33 @@ pub fn process_click(
                 capture_status: Some(CaptureStatus::Ok),
                 capture_error: None,
                 crop_region: None,
+                is_secure_placeholder: false,
+                drag_end: None,
+                context_title,
+                capture_bounds: Some(CaptureBounds {
+                    x: region_x,
+                    y: region_y,
+                    width: region_width as u32,
+                    height: region_height as u32,
+                    display_width: click_display_w,
+                    display_height: click_display_h,
+                }),
+                click_approximate: false,
+                result_screenshot_path: None,
+                wait_ms: None,
+                pixel_scale: None,
+                capture_pixels: None,
+                space_changed: false,
+                bundle_id: clicked_bundle_id.clone(),
+                annotations: Vec::new(),
+                capture_timings: Some(timings),
+                page_url: page_url.clone(),
+                keys: click.shortcut.clone(),
+                title: None,
             };
+            record_capture_pixels(&mut step);
+            record_toggle_state(&mut step, click);
+            apply_auto_description(&mut step, locale);
             session.add_step(step.clone());
             return Ok(step);
         }
@@ -1468,14 +1766,39 @@ pub fn process_click(
                     click.x, click.y, &capture_window.app_name
                 );
             }
+            // The app's AX tree lists an open menu as soon as it exists, with its final
+            // size; window-list polling is the fallback for apps whose menus AX misses.
+            let clicked_pid = clicked_info.as_ref().map(|(pid, _)| *pid);
             let mut found = None;
+            for attempt in 0..3 {
+                std::thread::sleep(std::time::Duration::from_millis(30));
+                found = get_open_context_menu_bounds(clicked_pid, click.x, click.y)
+                    .filter(|menu| is_plausible_context_menu(menu, click.x, click.y));
+                if found.is_some() {
+                    debug_log(
+                        session,
+                        &format!("context_menu found via AX on attempt {}", attempt + 1),
+                    );
+                    // Bounds are final, but the menu still fades in on screen.
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                    break;
+                }
+            }
             for attempt in 0..5 {
+                if found.is_some() {
+                    break;
+                }
                 std::thread::sleep(std::time::Duration::from_millis(if attempt == 0 {
                     80
                 } else {
                     40
                 }));
-                found = find_context_menu_near_click(click.x, click.y, &capture_window.app_name);
+                found = find_context_menu_near_click(
+                    &capture_windows(),
+                    click.x,
+                    click.y,
+                    &capture_window.app_name,
+                );
                 if found.is_some() {
                     debug_log(
                         session,
@@ -1485,9 +1808,12 @@ pub fn process_click(
                     // Finder menus can be slow to populate (Quick Actions, extensions …).
                     std::thread::sleep(std::time::Duration::from_millis(150));
                     // Re-measure — the menu may have grown during its animation
-                    if let Some(refreshed) =
-                        find_context_menu_near_click(click.x, click.y, &capture_window.app_name)
-                    {
+                    if let Some(refreshed) = find_context_menu_near_click(
+                        &capture_windows(),
+                        click.x,
+                        click.y,
+                        &capture_window.app_name,
+                    ) {
                         found = Some(refreshed);
                     }
                     break;
@@ -1579,7 +1905,7 @@ pub fn process_click(
             // screenshots by capturing the union with the main window for context.
             let mut main_bounds = window_info.bounds.clone();
             if let Some((clicked_pid, clicked_app)) = clicked_info.as_ref() {
-                if let Some(candidate) = get_window_for_pid_at_click(
+                if let Some(candidate) = windows.window_for_pid_at_click(
                     *clicked_pid,
                     clicked_app,
                     click.x,
@@ -1587,7 +1913,7 @@ pub fn process_click(
                     Some(capture_window.window_id),
                 ) {
                     main_bounds = candidate.bounds;
-                } else if let Some(candidate) = get_main_window_for_pid(*clicked_pid, clicked_app) {
+                } else if let Some(candidate) = windows.main_window_of(*clicked_pid, clicked_app) {
                     main_bounds = candidate.bounds;
                 }
             }
@@ -1633,24 +1959,7 @@ pub fn process_click(
             (true, union_bounds)
         } else if let Some(ref menu_bounds) = context_menu_bounds {
             // Right-click with context menu found - include both window and menu.
-            // Add generous padding around the menu to account for macOS drop shadows
-            // (~20-30px) and items that may render beyond reported window bounds.
-            const MENU_PAD: i32 = 50;
-            let main = &capture_window.bounds;
-
-            let union_x = main.x.min(menu_bounds.x - MENU_PAD);
-            let union_y = main.y.min(menu_bounds.y - MENU_PAD);
-            let union_right = (main.x + main.width as i32)
-                .max(menu_bounds.x + menu_bounds.width as i32 + MENU_PAD);
-            let union_bottom = (main.y + main.height as i32)
-                .max(menu_bounds.y + menu_bounds.height as i32 + MENU_PAD);
-
-            let union_bounds = super::window_info::WindowBounds {
-                x: union_x,
-                y: union_y,
-                width: (union_right - union_x) as u32,
-                height: (union_bottom - union_y) as u32,
-            };
+            let union_bounds = context_menu_union(&capture_window.bounds, menu_bounds);
 
             if cfg!(debug_assertions) {
                 eprintln!(
@@ -1839,12 +2148,13 @@ pub fn process_click(
 
         // For normal window captures (no popup/context menu), try window-ID capture first.
         // This avoids race conditions where the window closes before the region capture
-        // (e.g., clicking a close button). Fall back to region capture on failure.
+        // (e.g., clicking a close button), and gets parts of the window beyond the
+        // display. Fall back to region capture on failure.
         let mut capture_ok = false;
         let mut used_fallback = false;
         let mut last_capture_err: Option<String> = None;
 
-        if prefer_transient_region_capture && !is_right_click {
+        if (prefer_transient_region_capture || click.hover) && !is_right_click {
             if let Some(buffer) = pre_click_buffer {
                 match buffer.capture_for_click(
                     click.x,
@@ -1855,6 +2165,7 @@ pub fn process_click(
                     Ok(Some(pre)) => {
                         actual_bounds = pre.bounds;
                         capture_ok = true;
+                        timings.pre_click_frame_age_ms = Some(pre.frame_age_ms);
                         debug_log(
                             session,
                             &format!(
@@ -1895,6 +2206,20 @@ pub fn process_click(
                         &format!("window_id_capture ok: id={}", capture_window.window_id),
                     );
                     capture_ok = true;
+                    let fresh = get_window_bounds(capture_window.window_id);
+                    if fresh.as_ref().is_some_and(|b| *b != actual_bounds) {
+                        debug_log(
+                            session,
+                            &format!(
+                                "window_id_capture: window moved from ({}, {}, {}x{})",
+                                actual_bounds.x,
+                                actual_bounds.y,
+                                actual_bounds.width,
+                                actual_bounds.height
+                            ),
+                        );
+                    }
+                    actual_bounds = window_capture_bounds(actual_bounds, fresh);
                 }
                 Ok(()) => {
                     debug_log(
@@ -1997,6 +2322,10 @@ pub fn process_click(
             eprintln!("Click percent: x={x_pct}%, y={y_pct}%");
         }
 
+        if let Some(menu_bounds) = context_menu_bounds {
+            captured_context_menu = Some((capture_window.window_id, menu_bounds));
+        }
+
         (x_pct, y_pct, actual_bounds)
     } else {
         // No valid window - check if click is in menubar/dropdown region on the clicked display,
@@ -2091,13 +2420,6 @@ pub fn process_click(
         }
     };
 
-    if let (Some(ref mut ax_info), Some(ax_label)) = (ax_info.as_mut(), clicked_ax.as_ref()) {
-        ax_info.element_bounds = ax_label
-            .element_bounds
-            .as_ref()
-            .and_then(|b| bounds_percent_in_capture(b, &capture_bounds_for_step));
-    }
-
     if resolved_window_title.is_empty() {
         if is_sheet_dialog {
             resolved_window_title = "Dialog".to_string();
@@ -2106,6 +2428,8 @@ pub fn process_click(
         }
     }
 
+    timings.capture_ms = Some(elapsed_ms(capture_started));
+
     if !is_auth_dialog {
         if let Some(ax_label) = clicked_ax {
             let role = ax_label.role.as_str();
@@ -2126,30 +2450,21 @@ pub fn process_click(
         }
     }
 
-    // 6. Determine action type based on click count and button
-    use super::click_event::MouseButton;
-    let action = match (click.button, click.click_count) {
-        (MouseButton::Right, _) => ActionType::RightClick,
-        (MouseButton::Left, 2) => ActionType::DoubleClick,
-        (MouseButton::Left, n) if n >= 3 => ActionType::DoubleClick, // Triple+ as double
-        _ => ActionType::Click,
-    };
+    // 6. Determine action type based on click count, button, hover and shortcut
+    let action = click.action();
 
-    let auto_crop_region = if final_capture_status != CaptureStatus::Failed
-        && !is_auth_dialog
-        && should_apply_focus_crop(&capture_bounds_for_step, click_display_w, click_display_h)
-    {
-        suggested_focus_crop_for_capture(
-            &capture_bounds_for_step,
-            click_x_percent,
-            click_y_percent,
-            ax_info
-                .as_ref()
-                .and_then(|info| info.element_bounds.as_ref()),
-        )
-    } else {
-        None
-    };
+    // Auth dialogs and failed captures keep the full frame; no bounds means no auto-crop
+    // and no element highlight.
+    let has_capture = final_capture_status != CaptureStatus::Failed && !is_auth_dialog;
+    let capture_bounds = has_capture.then(|| {
+        capture_bounds_on_display(&capture_bounds_for_step, click_display_w, click_display_h)
+    });
+    let ax_info = ax_info_for_capture(
+        ax_info,
+        element_screen_bounds.as_ref(),
+        container_screen_bounds.as_ref(),
+        has_capture.then_some(&capture_bounds_for_step),
+    );
 
     // 7. Create step
     let screenshot = if final_capture_status == CaptureStatus::Failed {
@@ -2157,7 +2472,7 @@ pub fn process_click(
     } else {
         Some(screenshot_path.to_string_lossy().to_string())
     };
-    let step = Step {
+    let mut step = Step {
         id: step_id,
         ts: click.timestamp_ms,
         action,
@@ -2176,10 +2491,50 @@ pub fn process_click(
         ax: ax_info,
         capture_status: Some(final_capture_status),
         capture_error: final_capture_error,
-        crop_region: auto_crop_region,
+        crop_region: None,
+        is_secure_placeholder: wrote_auth_placeholder,
+        drag_end: None,
+        context_title,
+        capture_bounds,
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: clicked_bundle_id.clone(),
+        annotations: Vec::new(),
+        capture_timings: Some(timings),
+        page_url: page_url.clone(),
+        keys: click.shortcut.clone(),
+        title: None,
     };
+    if auto_crop {
+        apply_auto_crop(session, &mut step);
+    }
+    record_capture_pixels(&mut step);
+
+    // Remember a captured context menu so the item picked next can be merged into this step.
+    let pending_context_menu = captured_context_menu
+        .filter(|_| step.action == ActionType::RightClick && step.screenshot_path.is_some())
+        .map(|(window_id, menu_bounds)| PendingContextMenu {
+            step_id: step.id.clone(),
+            window_id,
+            app_name: capture_window.app_name.clone(),
+            click_x: click.x,
+            click_y: click.y,
+            timestamp_ms: click.timestamp_ms,
+            menu_bounds,
+            capture: capture_bounds_for_step.clone(),
+        });
+    pipeline_state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .pending_context_menu = pending_context_menu;
 
     // 8. Add to session
+    record_toggle_state(&mut step, click);
+    apply_auto_description(&mut step, locale);
     session.add_step(step.clone());
 
     Ok(step)
@@ -2257,6 +2612,42 @@ mod tests {
         assert!(app_names_match("WireGuard", "wireguard"));
     }
 
+    #[test]
+    fn recording_scope_matches_normalized_app_names() {
+        assert!(in_recording_scope(None, Some("Finder")));
+        assert!(in_recording_scope(None, None));
+        assert!(in_recording_scope(Some("Safari"), Some("‎safari")));
+        assert!(!in_recording_scope(Some("Safari"), Some("Finder")));
+        assert!(!in_recording_scope(Some("Safari"), None));
+    }
+
+    #[test]
+    fn blocked_apps_filter_by_name_or_bundle_id() {
+        let blocked = vec!["1Password".to_string(), "com.example.Bank".to_string()];
+        assert!(is_blocked_app(&blocked, "1password", None));
+        assert!(is_blocked_app(
+            &blocked,
+            "My Bank",
+            Some("com.example.bank")
+        ));
+        assert!(!is_blocked_app(
+            &blocked,
+            "Safari",
+            Some("com.apple.Safari")
+        ));
+        assert!(!is_blocked_app(&[], "1Password", None));
+    }
+
+    #[test]
+    fn pipeline_reset_keeps_recording_scope() {
+        let mut ps = PipelineState::new();
+        ps.record_only_app = Some("Safari".into());
+        ps.split_on_context_change = true;
+        ps.reset();
+        assert_eq!(ps.record_only_app.as_deref(), Some("Safari"));
+        assert!(!ps.split_on_context_change);
+    }
+
     #[test]
     fn app_name_match_rejects_different_names() {
         assert!(!app_names_match("Finder", "Preview"));
@@ -2444,9 +2835,12 @@ mod tests {
         });
         ps.last_auth_prompt = Some((42, 1000));
 
+        ps.pending_hover = Some(ClickEvent::hover(10, 20));
+
         ps.reset();
 
         assert!(ps.last_click.is_none());
+        assert!(ps.pending_hover.is_none());
         assert!(ps.last_auth_click_ms.is_none());
         assert!(ps.last_tray_click.is_none());
         assert!(!ps.panel_state.visible);
@@ -2494,6 +2888,9 @@ mod tests {
             button: MouseButton::Left,
             click_count: 1,
             timestamp_ms: 1500,
+            drag_start: None,
+            hover: false,
+            shortcut: None,
         };
         assert!(should_filter_tray_click(&ps, &click));
     }
@@ -2516,6 +2913,9 @@ mod tests {
             button: MouseButton::Left,
             click_count: 1,
             timestamp_ms: 3000, // > 1s after tray click
+            drag_start: None,
+            hover: false,
+            shortcut: None,
         };
         assert!(!should_filter_tray_click(&ps, &click));
     }
@@ -2538,6 +2938,9 @@ mod tests {
             button: MouseButton::Left,
             click_count: 1,
             timestamp_ms: 1000,
+            drag_start: None,
+            hover: false,
+            shortcut: None,
         };
         assert!(should_filter_panel_click(&ps, &click));
     }
@@ -2558,6 +2961,9 @@ mod tests {
             button: MouseButton::Left,
             click_count: 1,
             timestamp_ms: 1000,
+            drag_start: None,
+            hover: false,
+            shortcut: None,
         };
         assert!(!should_filter_panel_click(&ps, &click));
     }
@@ -2595,6 +3001,55 @@ mod tests {
         assert!(should_emit_auth_prompt(&mut ps, 99, 2000));
     }
 
+    #[test]
+    fn auth_prompt_dedup_uses_configured_window() {
+        let mut ps = PipelineState::new();
+        ps.set_auth_prompt_dedup_ms(1_000);
+        assert!(should_emit_auth_prompt(&mut ps, 42, 1000));
+        assert!(!should_emit_auth_prompt(&mut ps, 42, 1999));
+        assert!(should_emit_auth_prompt(&mut ps, 42, 2000));
+    }
+
+    // --- is_phantom_after_auth ---
+
+    #[test]
+    fn phantom_click_just_inside_cooldown() {
+        assert!(is_phantom_after_auth(Some(1_000), 1_001, 800));
+        assert!(is_phantom_after_auth(Some(1_000), 1_799, 800));
+    }
+
+    #[test]
+    fn real_click_just_outside_cooldown() {
+        assert!(!is_phantom_after_auth(Some(1_000), 1_800, 800));
+        assert!(!is_phantom_after_auth(Some(1_000), 5_000, 800));
+    }
+
+    #[test]
+    fn phantom_check_ignores_same_instant_and_earlier_clicks() {
+        assert!(!is_phantom_after_auth(Some(1_000), 1_000, 800));
+        // Negative diff: click stamped before the auth click.
+        assert!(!is_phantom_after_auth(Some(1_000), 900, 800));
+    }
+
+    #[test]
+    fn phantom_check_needs_auth_click_and_cooldown() {
+        assert!(!is_phantom_after_auth(None, 1_000, 800));
+        assert!(!is_phantom_after_auth(Some(1_000), 1_001, 0));
+    }
+
+    #[test]
+    fn pipeline_reset_keeps_auth_timing() {
+        let mut ps = PipelineState::new();
+        assert_eq!(ps.auth_dialog_cooldown_ms, AUTH_DIALOG_COOLDOWN_MS);
+        ps.set_auth_dialog_cooldown_ms(300);
+        ps.set_auth_prompt_dedup_ms(-5);
+        ps.last_auth_click_ms = Some(1_000);
+        ps.reset();
+        assert_eq!(ps.auth_dialog_cooldown_ms, 300);
+        assert_eq!(ps.auth_prompt_dedup_ms, 0);
+        assert_eq!(ps.last_auth_click_ms, None);
+    }
+
     #[test]
     fn infer_window_control_from_subrole() {
         let bounds = WindowBounds {
@@ -2669,6 +3124,56 @@ mod tests {
         assert!(!validate_screenshot(&path));
     }
 
+    #[test]
+    fn click_reads_one_window_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = Session::new_in(dir.path()).unwrap();
+        // A click on our panel stops before any element lookup or capture.
+        let pipeline_state = Mutex::new(PipelineState::new());
+        {
+            let mut ps = pipeline_state.lock().unwrap();
+            ps.panel_state.visible = true;
+            ps.panel_state.rect = Some(PanelRect {
+                x: 300,
+                y: 300,
+                width: 200,
+                height: 200,
+            });
+        }
+        let click = ClickEvent {
+            x: 400,
+            y: 400,
+            button: MouseButton::Left,
+            click_count: 1,
+            timestamp_ms: 1000,
+            drag_start: None,
+            hover: false,
+            shortcut: None,
+        };
+        let mut copies = 0;
+        let mut capture_windows = || {
+            copies += 1;
+            WindowSnapshot::default()
+        };
+
+        // The click loop's path: one copy for the auth prompt check and the pointer event.
+        let windows = capture_windows();
+        let (prompt_step, suppress_click) =
+            handle_auth_prompt(&click, &mut session, &pipeline_state, &windows);
+        assert!(prompt_step.is_none());
+        assert!(!suppress_click);
+        let result = process_pointer_event_with(
+            &click,
+            &mut session,
+            &pipeline_state,
+            None,
+            &windows,
+            &mut capture_windows,
+        );
+        assert!(matches!(result, Err(PipelineError::OwnAppClick)));
+        assert_eq!(copies, 1);
+    }
+
     #[test]
     fn validate_screenshot_valid_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,65 @@
+//! Optional overview step: a screenshot of the whole display taken when recording
+//! starts, so a guide opens with the big picture before the tight crops of its steps.
+//! With several displays it can instead show the whole desktop stitched together.
+
+use super::super::cg_capture::capture_all_displays;
+use super::super::session::Session;
+use super::super::types::{CaptureBounds, Step};
+use super::super::window_info::get_frontmost_window;
+use super::helpers::{capture_region_best, get_display_bounds_for_click, validate_screenshot};
+use super::is_own_app_name;
+use super::types::PipelineError;
+
+/// Capture the display containing the frontmost window (the main display when only
+/// StepCast is frontmost), or all displays when `all_displays` is set, and prepend it
+/// to `session` as the overview step.
+pub fn capture_overview_step(
+    session: &mut Session,
+    timestamp_ms: i64,
+    all_displays: bool,
+) -> Result<Step, PipelineError> {
+    let window = get_frontmost_window()
+        .ok()
+        .filter(|w| !is_own_app_name(&w.app_name));
+    // (0, 0) is the main display's origin in global coordinates.
+    let (center_x, center_y) = window.as_ref().map_or((0, 0), |w| {
+        (
+            w.bounds.x + w.bounds.width as i32 / 2,
+            w.bounds.y + w.bounds.height as i32 / 2,
+        )
+    });
+    let path = session.screenshot_path(&session.next_step_id());
+    let (x, y, width, height) = if all_displays {
+        let desktop = capture_all_displays(&path)?;
+        (
+            desktop.x,
+            desktop.y,
+            desktop.width as i32,
+            desktop.height as i32,
+        )
+    } else {
+        let (x, y, width, height) = get_display_bounds_for_click(center_x, center_y);
+        capture_region_best(session, x, y, width, height, &path)?;
+        (x, y, width, height)
+    };
+    if !validate_screenshot(&path) {
+        return Err(PipelineError::ScreenshotFailed(
+            "overview capture produced an empty file".to_string(),
+        ));
+    }
+
+    let (app, window_title) = window
+        .map(|w| (w.app_name, w.window_title))
+        .unwrap_or_default();
+    let bounds = CaptureBounds {
+        x,
+        y,
+        width: width as u32,
+        height: height as u32,
+        display_width: width,
+        display_height: height,
+    };
+    Ok(session
+        .add_context_step(timestamp_ms, app, window_title, bounds, &path)
+        .clone())
+}
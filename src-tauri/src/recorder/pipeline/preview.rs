@@ -0,0 +1,80 @@
+//! Dry-run capture for checking permissions before a recording: resolve the frontmost
+//! window and capture it once, without a session or any steps.
+
+use super::super::capture::CaptureOptions;
+use super::super::window_info::{get_frontmost_window, WindowBounds};
+use super::helpers::{capture_region_logged, validate_screenshot};
+use super::is_own_app_name;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Result of [`preview_capture`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturePreview {
+    /// Captured PNG, or None when the capture itself failed.
+    pub path: Option<String>,
+    pub app_name: String,
+    pub window_title: String,
+    pub bounds: WindowBounds,
+    /// Whether the capture produced a non-empty file.
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// Where preview captures go; overwritten on every preview.
+pub fn preview_capture_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("com.w0nk1.stepcast")
+        .join("preview")
+        .join("preview.png")
+}
+
+/// Capture the frontmost window's bounds to `output_path`.
+///
+/// Errors only when no frontmost window can be resolved; capture failures are
+/// reported in the returned preview so the UI can show which step went wrong.
+pub fn preview_capture(output_path: &Path) -> Result<CapturePreview, String> {
+    let window = get_frontmost_window().map_err(|e| format!("No frontmost window: {e}"))?;
+    let bounds = window.bounds.clone();
+
+    if let Some(dir) = output_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create preview dir: {e}"))?;
+    }
+    let _ = std::fs::remove_file(output_path);
+
+    let result = capture_region_logged(
+        bounds.x,
+        bounds.y,
+        bounds.width as i32,
+        bounds.height as i32,
+        output_path,
+        CaptureOptions::default(),
+        |msg| {
+            if cfg!(debug_assertions) {
+                eprintln!("preview_capture: {msg}");
+            }
+        },
+    );
+    let valid = result.is_ok() && validate_screenshot(output_path);
+    let error = match result {
+        Err(e) => Some(e.to_string()),
+        Ok(()) if !valid => Some("capture produced an empty file".to_string()),
+        // Recording ignores our own windows, so this is not what would be captured.
+        Ok(()) if is_own_app_name(&window.app_name) => {
+            Some("StepCast itself is the frontmost app".to_string())
+        }
+        Ok(()) => None,
+    };
+
+    Ok(CapturePreview {
+        path: output_path
+            .exists()
+            .then(|| output_path.to_string_lossy().to_string()),
+        app_name: window.app_name,
+        window_title: window.window_title,
+        bounds,
+        valid,
+        error,
+    })
+}
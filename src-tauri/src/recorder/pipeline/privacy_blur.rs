@@ -0,0 +1,149 @@
+//! "Blur all text" privacy mode: every text region Vision finds in a screenshot is
+//! blurred in place before the step is shown or exported.
+//!
+//! Detection runs in the Swift helper and costs noticeably more than a capture, so it
+//! only runs when the recording enabled it. Without the helper (or Vision) the
+//! screenshot is left as is.
+
+use super::super::session::Session;
+use super::super::types::{BoundsPercent, Step};
+use super::helpers::debug_log;
+use image::{imageops, RgbaImage};
+use std::path::Path;
+use std::time::Instant;
+
+/// Blur strength; enough to make small UI text unreadable on Retina captures.
+const BLUR_SIGMA: f32 = 10.0;
+/// Vision boxes hug the glyphs; grow them so ascenders and descenders are covered.
+const REGION_PADDING_PX: u32 = 4;
+
+/// Pixel rect (x, y, w, h) for a percent region, padded and clamped to the image.
+fn region_rect_px(region: &BoundsPercent, img_w: u32, img_h: u32) -> Option<(u32, u32, u32, u32)> {
+    let to_px = |percent: f32, size: u32| ((percent / 100.0) * size as f32).round() as i64;
+    let pad = REGION_PADDING_PX as i64;
+    let left = (to_px(region.x_percent, img_w) - pad).clamp(0, img_w as i64);
+    let top = (to_px(region.y_percent, img_h) - pad).clamp(0, img_h as i64);
+    let right =
+        (to_px(region.x_percent + region.width_percent, img_w) + pad).clamp(0, img_w as i64);
+    let bottom =
+        (to_px(region.y_percent + region.height_percent, img_h) + pad).clamp(0, img_h as i64);
+    (right > left && bottom > top).then(|| {
+        (
+            left as u32,
+            top as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        )
+    })
+}
+
+/// Blur each region of `img` in place. Returns how many regions were blurred.
+pub fn blur_regions(img: &mut RgbaImage, regions: &[BoundsPercent]) -> usize {
+    let (img_w, img_h) = img.dimensions();
+    let mut blurred = 0;
+    for region in regions {
+        let Some((x, y, w, h)) = region_rect_px(region, img_w, img_h) else {
+            continue;
+        };
+        let patch = imageops::crop_imm(img, x, y, w, h).to_image();
+        let patch = imageops::fast_blur(&patch, BLUR_SIGMA);
+        imageops::replace(img, &patch, x as i64, y as i64);
+        blurred += 1;
+    }
+    blurred
+}
+
+/// Detect text in the screenshot at `path` and overwrite it with the text blurred.
+fn blur_text_in_file(path: &Path) -> Result<usize, String> {
+    let regions = crate::apple_intelligence::detect_text_regions(path)?;
+    if regions.is_empty() {
+        return Ok(0);
+    }
+    let mut img = image::open(path)
+        .map_err(|e| format!("open screenshot: {e}"))?
+        .to_rgba8();
+    let blurred = blur_regions(&mut img, &regions);
+    img.save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("save blurred screenshot: {e}"))?;
+    Ok(blurred)
+}
+
+/// Blur all text in `step`'s screenshot, recording the time taken in the session
+/// diagnostics. Auth placeholders are skipped; failures leave the screenshot as is.
+pub fn apply_privacy_blur(session: &mut Session, step: &Step) {
+    let Some(path) = step.screenshot_path.as_deref() else {
+        return;
+    };
+    if step.is_secure_placeholder {
+        return;
+    }
+
+    let started = Instant::now();
+    let result = blur_text_in_file(Path::new(path));
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    session.diagnostics.privacy_blur_runs += 1;
+    session.diagnostics.privacy_blur_ms += elapsed_ms;
+
+    let msg = match result {
+        Ok(count) => format!("privacy_blur: {count} regions in {elapsed_ms}ms"),
+        Err(err) => format!("privacy_blur: skipped ({err}) after {elapsed_ms}ms"),
+    };
+    debug_log(session, &msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn region(x: f32, y: f32, w: f32, h: f32) -> BoundsPercent {
+        BoundsPercent {
+            x_percent: x,
+            y_percent: y,
+            width_percent: w,
+            height_percent: h,
+        }
+    }
+
+    #[test]
+    fn region_rect_px_pads_and_clamps() {
+        assert_eq!(
+            region_rect_px(&region(10.0, 20.0, 30.0, 10.0), 200, 100),
+            Some((16, 16, 68, 18))
+        );
+        // Padding never leaves the image.
+        assert_eq!(
+            region_rect_px(&region(0.0, 95.0, 100.0, 5.0), 200, 100),
+            Some((0, 91, 200, 9))
+        );
+        assert_eq!(
+            region_rect_px(&region(120.0, 0.0, 10.0, 10.0), 200, 100),
+            None
+        );
+    }
+
+    #[test]
+    fn blur_regions_only_touches_text_boxes() {
+        // Black/white stripes so blurring visibly changes pixels.
+        let mut img = RgbaImage::from_fn(200, 100, |x, _| {
+            if x % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let original = img.clone();
+
+        let count = blur_regions(&mut img, &[region(10.0, 10.0, 20.0, 20.0)]);
+        assert_eq!(count, 1);
+        assert_ne!(img.get_pixel(30, 20), original.get_pixel(30, 20));
+        assert_eq!(img.get_pixel(150, 80), original.get_pixel(150, 80));
+    }
+
+    #[test]
+    fn blur_regions_skips_empty_regions() {
+        let mut img = RgbaImage::new(50, 50);
+        assert_eq!(blur_regions(&mut img, &[region(200.0, 0.0, 5.0, 5.0)]), 0);
+        assert_eq!(blur_regions(&mut img, &[]), 0);
+    }
+}
@@ -0,0 +1,157 @@
+//! Retake a single step's screenshot after recording, e.g. when the original capture
+//! fell back, caught the wrong window, or landed on an animation frame.
+
+use super::super::cg_capture::capture_window_cg;
+use super::super::session::Session;
+use super::super::types::{ActionType, CaptureBounds, CaptureStatus, Step};
+use super::super::window_info::{
+    find_running_app_pid, get_main_window_for_pid, get_window_for_pid_at_click, WindowBounds,
+};
+use super::drag::annotate_drag;
+use super::helpers::{
+    auto_crop_for_step, calculate_click_percent, capture_bounds_on_display, capture_region_best,
+    debug_log, get_display_bounds_for_click, record_capture_pixels,
+};
+use super::types::PipelineError;
+
+/// Recapture the window of the app recorded on `step_id`, overwriting its screenshot.
+///
+/// Click percentages are recomputed against the new window bounds. When the window
+/// moved or resized since recording, the marker is flagged as approximate and the
+/// crop is replaced by a fresh auto-crop.
+pub fn recapture_step(step_id: &str, session: &mut Session) -> Result<Step, PipelineError> {
+    let step = session
+        .steps
+        .iter()
+        .find(|s| s.id == step_id)
+        .cloned()
+        .ok_or(PipelineError::StepNotFound)?;
+    if step.action == ActionType::Note || step.is_auth_placeholder() {
+        return Err(PipelineError::NotRecapturable);
+    }
+
+    let pid = find_running_app_pid(&step.app)
+        .ok_or_else(|| PipelineError::AppNotRunning(step.app.clone()))?;
+    let window = get_window_for_pid_at_click(pid, &step.app, step.x, step.y, None)
+        .or_else(|| get_main_window_for_pid(pid, &step.app))
+        .ok_or_else(|| PipelineError::WindowClosed(step.app.clone()))?;
+
+    let path = step
+        .screenshot_path
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| session.screenshot_path(&step.id));
+    debug_log(
+        session,
+        &format!(
+            "recapture: step={} window_id={} title='{}' path={}",
+            step.id,
+            window.window_id,
+            window.window_title,
+            path.to_string_lossy()
+        ),
+    );
+
+    let (capture_status, capture_error) = match capture_window_cg(window.window_id, &path) {
+        Ok(()) => (CaptureStatus::Ok, None),
+        Err(err) => {
+            debug_log(session, &format!("recapture window capture failed: {err}"));
+            let b = &window.bounds;
+            capture_region_best(session, b.x, b.y, b.width as i32, b.height as i32, &path)
+                .map_err(|e| PipelineError::ScreenshotFailed(e.to_string()))?;
+            (
+                CaptureStatus::Fallback,
+                Some(format!("window capture failed, used region: {err}")),
+            )
+        }
+    };
+
+    let (_, _, display_w, display_h) = get_display_bounds_for_click(step.x, step.y);
+    let moved = bounds_changed(step.capture_bounds.as_ref(), &window.bounds);
+
+    let updated = session
+        .step_mut(step_id)
+        .ok_or(PipelineError::StepNotFound)?;
+    updated.screenshot_path = Some(path.to_string_lossy().to_string());
+    updated.capture_status = Some(capture_status);
+    updated.capture_error = capture_error;
+    updated.click_x_percent = percent_in(step.x, window.bounds.x, window.bounds.width);
+    updated.click_y_percent = percent_in(step.y, window.bounds.y, window.bounds.height);
+    if let Some(end) = updated.drag_end.as_mut() {
+        end.x_percent = percent_in(end.x, window.bounds.x, window.bounds.width);
+        end.y_percent = percent_in(end.y, window.bounds.y, window.bounds.height);
+    }
+    updated.capture_bounds = Some(capture_bounds_on_display(
+        &window.bounds,
+        display_w,
+        display_h,
+    ));
+    record_capture_pixels(updated);
+    if moved {
+        updated.click_approximate = true;
+        // Element bounds and any crop were relative to the old frame.
+        if let Some(ax) = updated.ax.as_mut() {
+            ax.element_bounds = None;
+        }
+        updated.crop_region = auto_crop_for_step(updated);
+    }
+    if let Some(end) = &updated.drag_end {
+        let start = (updated.click_x_percent, updated.click_y_percent);
+        if let Err(err) = annotate_drag(&path, start, (end.x_percent, end.y_percent)) {
+            eprintln!("recapture: drag annotate failed: {err}");
+        }
+    }
+    Ok(updated.clone())
+}
+
+/// True when the recorded capture area differs from the window's current bounds.
+/// Steps recorded before capture bounds were stored always count as changed.
+fn bounds_changed(recorded: Option<&CaptureBounds>, current: &WindowBounds) -> bool {
+    recorded.is_none_or(|r| {
+        (r.x, r.y, r.width, r.height) != (current.x, current.y, current.width, current.height)
+    })
+}
+
+fn percent_in(coord: i32, offset: i32, size: u32) -> f32 {
+    calculate_click_percent(coord, offset, size as i32) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(x: i32, y: i32, width: u32, height: u32) -> CaptureBounds {
+        CaptureBounds {
+            x,
+            y,
+            width,
+            height,
+            display_width: 1440,
+            display_height: 900,
+        }
+    }
+
+    #[test]
+    fn bounds_changed_compares_frame() {
+        let current = WindowBounds {
+            x: 100,
+            y: 50,
+            width: 800,
+            height: 600,
+        };
+        assert!(!bounds_changed(
+            Some(&recorded(100, 50, 800, 600)),
+            &current
+        ));
+        assert!(bounds_changed(Some(&recorded(120, 50, 800, 600)), &current));
+        assert!(bounds_changed(Some(&recorded(100, 50, 640, 600)), &current));
+        assert!(bounds_changed(None, &current));
+    }
+
+    #[test]
+    fn percent_in_clamps_points_outside_the_window() {
+        assert_eq!(percent_in(500, 100, 800), 50.0);
+        assert_eq!(percent_in(50, 100, 800), 0.0);
+        assert_eq!(percent_in(2000, 100, 800), 100.0);
+    }
+}
@@ -0,0 +1,153 @@
+//! Optional result frame: a second screenshot taken shortly after a click that launches
+//! or switches apps. The step's own screenshot shows the Dock the user clicked; the
+//! result frame shows the window that came up.
+
+use super::super::cg_capture::capture_window_cg;
+use super::super::session::Session;
+use super::super::types::{ActionType, Step};
+use super::super::window_info::get_frontmost_window;
+use super::helpers::{debug_log, validate_screenshot};
+use super::is_own_app_name;
+use super::types::{PendingResultFrame, PipelineError, PipelineState};
+
+/// Default wait before the result frame, long enough for most apps to show a window.
+pub const DEFAULT_RESULT_FRAME_DELAY_MS: i64 = 800;
+
+/// Whether the click on `step` most likely brought another app to the front: Dock
+/// items, which launch or activate their app. Hovering over them launches nothing.
+pub fn wants_result_frame(step: &Step) -> bool {
+    if step.action == ActionType::Hover {
+        return false;
+    }
+    let dock_item = step.ax.as_ref().is_some_and(|ax| {
+        ax.role == "AXDockItem" || ax.subrole.as_deref() == Some("AXApplicationDockItem")
+    });
+    dock_item || step.app.eq_ignore_ascii_case("dock")
+}
+
+/// Queue a result frame for `step` when the feature is on and the click warrants one.
+/// A newer click replaces the pending frame: its screenshot shows the result already.
+pub fn schedule_result_frame(ps: &mut PipelineState, step: &Step, now_ms: i64) {
+    ps.pending_result_frame = ps
+        .result_frame_delay_ms
+        .filter(|_| wants_result_frame(step))
+        .map(|delay_ms| PendingResultFrame {
+            step_id: step.id.clone(),
+            due_ms: now_ms + delay_ms,
+        });
+}
+
+/// Take the pending result frame once its delay has passed.
+pub fn take_due_result_frame(ps: &mut PipelineState, now_ms: i64) -> Option<PendingResultFrame> {
+    if ps
+        .pending_result_frame
+        .as_ref()
+        .is_some_and(|pending| now_ms >= pending.due_ms)
+    {
+        ps.pending_result_frame.take()
+    } else {
+        None
+    }
+}
+
+/// Capture the frontmost window and attach it to the step as its result frame.
+pub fn capture_result_frame(session: &mut Session, step_id: &str) -> Result<Step, PipelineError> {
+    if !session.steps.iter().any(|s| s.id == step_id) {
+        return Err(PipelineError::StepNotFound);
+    }
+    let window = get_frontmost_window()?;
+    if is_own_app_name(&window.app_name) || window.app_name.eq_ignore_ascii_case("dock") {
+        return Err(PipelineError::WindowInfoFailed(format!(
+            "no app window in front, only {}",
+            window.app_name
+        )));
+    }
+
+    let path = session.screenshot_path(&format!("{step_id}-result"));
+    capture_window_cg(window.window_id, &path)?;
+    if !validate_screenshot(&path) {
+        return Err(PipelineError::ScreenshotFailed(
+            "result frame capture produced an empty file".to_string(),
+        ));
+    }
+    debug_log(
+        session,
+        &format!(
+            "result_frame: step={step_id} app='{}' window_id={}",
+            window.app_name, window.window_id
+        ),
+    );
+
+    let step = session
+        .step_mut(step_id)
+        .ok_or(PipelineError::StepNotFound)?;
+    step.result_screenshot_path = Some(path.to_string_lossy().into_owned());
+    Ok(step.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::types::AxClickInfo;
+
+    fn dock_step(id: &str) -> Step {
+        let mut step = Step::sample();
+        step.id = id.to_string();
+        step.app = "Dock".to_string();
+        step
+    }
+
+    #[test]
+    fn dock_items_want_a_result_frame() {
+        assert!(wants_result_frame(&dock_step("step-1")));
+        assert!(!wants_result_frame(&Step::sample()));
+
+        let mut launcher = Step::sample();
+        launcher.ax = serde_json::from_value::<Option<AxClickInfo>>(serde_json::json!({
+            "role": "AXDockItem",
+            "subrole": "AXApplicationDockItem",
+            "label": "Safari",
+            "is_cancel_button": false,
+            "is_default_button": false,
+        }))
+        .unwrap();
+        assert!(wants_result_frame(&launcher));
+
+        let mut hover = dock_step("step-2");
+        hover.action = ActionType::Hover;
+        assert!(!wants_result_frame(&hover));
+    }
+
+    #[test]
+    fn result_frame_waits_for_its_delay_and_only_when_enabled() {
+        let mut ps = PipelineState::new();
+        schedule_result_frame(&mut ps, &dock_step("step-1"), 1_000);
+        assert!(
+            ps.pending_result_frame.is_none(),
+            "feature is off by default"
+        );
+
+        ps.result_frame_delay_ms = Some(DEFAULT_RESULT_FRAME_DELAY_MS);
+        schedule_result_frame(&mut ps, &dock_step("step-1"), 1_000);
+        assert!(take_due_result_frame(&mut ps, 1_799).is_none());
+        let due = take_due_result_frame(&mut ps, 1_800).expect("due");
+        assert_eq!(due.step_id, "step-1");
+        assert!(take_due_result_frame(&mut ps, 5_000).is_none());
+    }
+
+    #[test]
+    fn newer_click_replaces_or_cancels_pending_frame() {
+        let mut ps = PipelineState::new();
+        ps.result_frame_delay_ms = Some(500);
+        schedule_result_frame(&mut ps, &dock_step("step-1"), 0);
+        schedule_result_frame(&mut ps, &dock_step("step-2"), 100);
+        assert_eq!(
+            take_due_result_frame(&mut ps, 600).map(|p| p.step_id),
+            Some("step-2".to_string())
+        );
+
+        schedule_result_frame(&mut ps, &dock_step("step-3"), 1_000);
+        schedule_result_frame(&mut ps, &Step::sample(), 1_100);
+        assert!(take_due_result_frame(&mut ps, 2_000).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Space switches: the first step recorded after the user moved to another desktop
+//! gets flagged, or preceded by an auto-generated Note, so readers aren't left
+//! wondering why the screenshots suddenly look different.
+
+use super::super::session::Session;
+use super::super::types::{ActionType, DescriptionSource, Step};
+use crate::i18n::{self, Locale};
+
+/// Annotate `step`, just recorded into `session`, as coming after a Space switch.
+///
+/// With `as_note` an auto-generated Note step is inserted before it and returned;
+/// otherwise the step's `space_changed` flag is set, both in the session and on `step`.
+pub fn mark_space_change(
+    session: &mut Session,
+    step: &mut Step,
+    locale: Locale,
+    as_note: bool,
+) -> Option<Step> {
+    if !as_note {
+        step.space_changed = true;
+        session.step_mut(&step.id)?.space_changed = true;
+        return None;
+    }
+
+    let index = session.steps.iter().position(|s| s.id == step.id)?;
+    let note = Step {
+        id: session.next_step_id(),
+        ts: step.ts,
+        action: ActionType::Note,
+        x: 0,
+        y: 0,
+        click_x_percent: 0.0,
+        click_y_percent: 0.0,
+        app: step.app.clone(),
+        window_title: step.window_title.clone(),
+        screenshot_path: None,
+        note: None,
+        description: Some(i18n::step_space_changed(locale).to_string()),
+        description_source: Some(DescriptionSource::Auto),
+        description_status: None,
+        description_error: None,
+        ax: None,
+        capture_status: None,
+        capture_error: None,
+        crop_region: None,
+        is_secure_placeholder: false,
+        drag_end: None,
+        context_title: None,
+        capture_bounds: None,
+        click_approximate: false,
+        result_screenshot_path: None,
+        wait_ms: None,
+        pixel_scale: None,
+        capture_pixels: None,
+        space_changed: false,
+        bundle_id: None,
+        annotations: Vec::new(),
+        capture_timings: None,
+        page_url: None,
+        keys: None,
+        title: None,
+    };
+    session.insert_step(index, note.clone());
+    Some(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with_step() -> (Session, Step) {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step {
+            id: "step-001".into(),
+            ..Step::sample()
+        });
+        let step = Step {
+            id: "step-002".into(),
+            ..Step::sample()
+        };
+        session.add_step(step.clone());
+        (session, step)
+    }
+
+    #[test]
+    fn tags_the_step_after_a_switch() {
+        let (mut session, mut step) = session_with_step();
+        let revision = session.revision();
+
+        assert!(mark_space_change(&mut session, &mut step, Locale::En, false).is_none());
+        assert!(step.space_changed);
+        assert!(session.steps[1].space_changed);
+        assert!(!session.steps[0].space_changed);
+        assert!(session.revision() > revision);
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn inserts_a_note_before_the_step() {
+        let (mut session, mut step) = session_with_step();
+
+        let note = mark_space_change(&mut session, &mut step, Locale::De, true).expect("note");
+        assert_eq!(note.action, ActionType::Note);
+        assert!(note.is_auto_generated());
+        assert_eq!(
+            note.description.as_deref(),
+            Some("Zu einem anderen Schreibtisch (Space) gewechselt")
+        );
+        assert!(!step.space_changed);
+        let ids: Vec<_> = session.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["step-001", note.id.as_str(), "step-002"]);
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+}
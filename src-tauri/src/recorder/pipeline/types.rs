@@ -1,20 +1,26 @@
 //! Pipeline types, state, and error definitions.
 
 use std::fmt;
+use std::time::Duration;
 
 use super::super::capture::CaptureError;
-use super::super::window_info::WindowError;
+use super::super::click_event::ClickEvent;
+use super::super::window_info::{WindowBounds, WindowError};
+use crate::i18n::Locale;
 
 /// Minimum time between clicks to avoid duplicates (milliseconds)
 pub const DEBOUNCE_MS: i64 = 150;
 
-/// Cooldown after auth dialog clicks to prevent phantom clicks when dialog closes (milliseconds)
+/// Default cooldown after auth dialog clicks to prevent phantom clicks when dialog closes (milliseconds)
 /// This is longer than normal debounce because the phantom click appears at a DIFFERENT position
 /// and can occur with significant delay as the dialog animates closed
 pub const AUTH_DIALOG_COOLDOWN_MS: i64 = 800;
 
 pub const TRAY_CLICK_WINDOW_MS: i64 = 1_000;
+/// Default window in which a repeated prompt for the same auth window is not re-emitted.
 pub const AUTH_PROMPT_DEDUP_MS: i64 = 5_000;
+/// A menu item click this soon after a right-click is merged into the right-click step.
+pub const CONTEXT_MENU_MERGE_WINDOW_MS: i64 = 5_000;
 
 /// All transient pipeline state that should be reset between recording sessions.
 ///
@@ -30,6 +36,81 @@ pub struct PipelineState {
     pub panel_state: PanelState,
     pub last_auth_prompt: Option<(u32, i64)>,
     pub last_menu_bar_click_ms: Option<i64>,
+    /// Insert "Now in: …" section breaks when the window title changes (opt-in per recording).
+    pub split_on_context_change: bool,
+    /// Language of generated section breaks.
+    pub locale: Locale,
+    /// Blur every detected text region in new screenshots (opt-in per recording).
+    pub privacy_blur_text: bool,
+    /// Only record clicks in this app; clicks elsewhere are dropped. Chosen by the
+    /// user, so it survives [`PipelineState::reset`].
+    pub record_only_app: Option<String>,
+    /// Clicks this soon after an auth dialog click are treated as phantom clicks.
+    /// Tuned by the user, so it survives [`PipelineState::reset`].
+    pub auth_dialog_cooldown_ms: i64,
+    /// Same-window auth prompts within this window are not emitted again. Survives reset.
+    pub auth_prompt_dedup_ms: i64,
+    /// Context menu captured by the most recent right-click step, waiting for the
+    /// menu item selection.
+    pub pending_context_menu: Option<PendingContextMenu>,
+    /// Delay of the result frame taken after app-switching clicks; `None` turns it
+    /// off (opt-in per recording).
+    pub result_frame_delay_ms: Option<i64>,
+    /// Result frame waiting for its delay to pass.
+    pub pending_result_frame: Option<PendingResultFrame>,
+    /// Stop the recording after this long without a recorded step; `None` never
+    /// stops (per recording).
+    pub idle_timeout: Option<Duration>,
+    /// The active Space changed since the last recorded step; the next one gets annotated.
+    pub space_change_pending: bool,
+    /// Annotate Space switches with a Note step instead of a flag on the next step
+    /// (per recording).
+    pub space_change_notes: bool,
+    /// Drop clicks on the bare desktop (per recording).
+    pub ignore_desktop_clicks: bool,
+    /// Repeated clicks at the same spot within this many ms are dropped.
+    pub debounce_ms: i64,
+    /// Crop new steps to the clicked element's surroundings.
+    pub auto_crop: bool,
+    /// App names or bundle IDs whose clicks are never recorded.
+    pub blocked_apps: Vec<String>,
+    /// Pause the recording when a click lands in a blocked app.
+    pub pause_on_blocked_app: bool,
+    /// Hover requested with the hover shortcut, waiting for the click loop to capture it.
+    pub pending_hover: Option<ClickEvent>,
+    /// Labels ("⇧⌘S") of StepCast's own global shortcuts; pressing them records no step.
+    pub own_shortcuts: Vec<String>,
+    /// Capture clicked windows by window ID, off-screen parts included, instead of
+    /// the pre-click display frame.
+    pub prefer_full_window_capture: bool,
+    /// When the screen last locked or the Mac last went to sleep (ms since epoch).
+    /// Steps captured shortly after are dropped as likely lock screen shots.
+    pub interrupted_at_ms: Option<i64>,
+}
+
+/// A step waiting for its result frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingResultFrame {
+    pub step_id: String,
+    /// Capture once the clock reaches this (ms since epoch).
+    pub due_ms: i64,
+}
+
+/// A right-click step whose screenshot shows an open context menu.
+#[derive(Debug, Clone)]
+pub struct PendingContextMenu {
+    pub step_id: String,
+    /// Window the menu belongs to.
+    pub window_id: u32,
+    pub app_name: String,
+    /// Screen position of the right-click, used to find the menu again.
+    pub click_x: i32,
+    pub click_y: i32,
+    pub timestamp_ms: i64,
+    /// Menu bounds when the step was captured.
+    pub menu_bounds: WindowBounds,
+    /// Screen rect covered by the step's screenshot.
+    pub capture: WindowBounds,
 }
 
 impl PipelineState {
@@ -41,12 +122,48 @@ impl PipelineState {
             panel_state: PanelState::new(),
             last_auth_prompt: None,
             last_menu_bar_click_ms: None,
+            split_on_context_change: false,
+            locale: Locale::En,
+            privacy_blur_text: false,
+            record_only_app: None,
+            auth_dialog_cooldown_ms: AUTH_DIALOG_COOLDOWN_MS,
+            auth_prompt_dedup_ms: AUTH_PROMPT_DEDUP_MS,
+            pending_context_menu: None,
+            result_frame_delay_ms: None,
+            pending_result_frame: None,
+            idle_timeout: None,
+            space_change_pending: false,
+            space_change_notes: false,
+            ignore_desktop_clicks: true,
+            debounce_ms: DEBOUNCE_MS,
+            auto_crop: true,
+            blocked_apps: Vec::new(),
+            pause_on_blocked_app: false,
+            pending_hover: None,
+            own_shortcuts: Vec::new(),
+            prefer_full_window_capture: false,
+            interrupted_at_ms: None,
         }
     }
 
     /// Reset all transient state so a new recording session starts cleanly.
     pub fn reset(&mut self) {
+        let record_only_app = self.record_only_app.take();
+        let (cooldown_ms, dedup_ms) = (self.auth_dialog_cooldown_ms, self.auth_prompt_dedup_ms);
         *self = Self::new();
+        self.record_only_app = record_only_app;
+        self.auth_dialog_cooldown_ms = cooldown_ms;
+        self.auth_prompt_dedup_ms = dedup_ms;
+    }
+
+    /// Set the phantom-click cooldown after auth dialogs; 0 disables it.
+    pub fn set_auth_dialog_cooldown_ms(&mut self, ms: i64) {
+        self.auth_dialog_cooldown_ms = ms.max(0);
+    }
+
+    /// Set the same-window auth prompt dedup window; 0 emits every prompt.
+    pub fn set_auth_prompt_dedup_ms(&mut self, ms: i64) {
+        self.auth_prompt_dedup_ms = ms.max(0);
     }
 }
 
@@ -112,8 +229,26 @@ pub enum PipelineError {
     DebouncedClick,
     /// This click upgrades the previous step to DoubleClick (no new step needed).
     UpgradedToDblClick,
+    /// Menu item click folded into the right-click step with this ID (no new step needed).
+    MergedIntoContextMenu(String),
     /// Click was a menu open/expand action that shouldn't create a step.
     IgnoredMenuOpen,
+    /// Click landed on the desktop background or icon area, not in a window.
+    IgnoredDesktopClick,
+    /// Click landed in an app outside the recording scope.
+    IgnoredOutOfScope(String),
+    /// Click landed in an app on the blocklist; nothing was captured.
+    BlockedApp(String),
+    /// Drag release with no matching click step to upgrade.
+    UnmatchedDragEnd,
+    /// No step with the requested ID in the session.
+    StepNotFound,
+    /// Step has no screenshot to retake (notes, auth placeholders).
+    NotRecapturable,
+    /// The app recorded on the step has quit since.
+    AppNotRunning(String),
+    /// The app recorded on the step is running but has no window left to capture.
+    WindowClosed(String),
 }
 
 impl fmt::Display for PipelineError {
@@ -126,7 +261,30 @@ impl fmt::Display for PipelineError {
             PipelineError::UpgradedToDblClick => {
                 write!(f, "upgraded previous step to double-click")
             }
+            PipelineError::MergedIntoContextMenu(step_id) => {
+                write!(f, "merged menu selection into context menu step {step_id}")
+            }
             PipelineError::IgnoredMenuOpen => write!(f, "ignored menu open click"),
+            PipelineError::IgnoredDesktopClick => write!(f, "ignored click on the desktop"),
+            PipelineError::IgnoredOutOfScope(app) => {
+                write!(f, "ignored click outside recording scope: {app}")
+            }
+            PipelineError::BlockedApp(app) => write!(f, "ignored click in blocked app: {app}"),
+            PipelineError::UnmatchedDragEnd => write!(f, "drag end without a matching click"),
+            PipelineError::StepNotFound => write!(f, "step not found"),
+            PipelineError::NotRecapturable => write!(f, "step has no screenshot to retake"),
+            PipelineError::AppNotRunning(app) => {
+                write!(
+                    f,
+                    "{app} is no longer running; reopen it to retake the screenshot"
+                )
+            }
+            PipelineError::WindowClosed(app) => {
+                write!(
+                    f,
+                    "{app} has no open window; open one to retake the screenshot"
+                )
+            }
         }
     }
 }
@@ -1,8 +1,13 @@
 use std::collections::VecDeque;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::recorder::window_info::WindowBounds;
+
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct BufferedFrameMeta {
     pub captured_at_ms: i64,
+    /// Screen rect the frame shows, read when it arrived: a display moved or resized
+    /// since the buffer started still maps clicks onto the right pixels.
+    pub bounds: WindowBounds,
 }
 
 pub(crate) fn pick_frame_index(
@@ -35,8 +40,7 @@ mod imp {
         SCStreamConfiguration, SCStreamOutputType,
     };
 
-    use super::{pick_frame_index, BufferedFrameMeta};
-    use crate::recorder::window_info::WindowBounds;
+    use super::{pick_frame_index, BufferedFrameMeta, WindowBounds};
 
     const MAX_RING_FRAMES: usize = 4;
     const TARGET_FPS: u32 = 16;
@@ -154,7 +158,8 @@ mod imp {
                 let Some(ring) = frame_map.get(&target.display_id()) else {
                     return Ok(None);
                 };
-                let metas: VecDeque<BufferedFrameMeta> = ring.iter().map(|f| f.meta).collect();
+                let metas: VecDeque<BufferedFrameMeta> =
+                    ring.iter().map(|f| f.meta.clone()).collect();
                 let Some(idx) = pick_frame_index(&metas, click_ts_ms) else {
                     return Ok(None);
                 };
@@ -173,7 +178,7 @@ mod imp {
 
             let frame_age_ms = click_ts_ms.saturating_sub(frame.meta.captured_at_ms);
             Ok(Some(PreClickCaptureResult {
-                bounds: target.bounds,
+                bounds: frame.meta.bounds,
                 frame_age_ms,
             }))
         }
@@ -291,6 +296,7 @@ mod imp {
                 let frame = BufferedFrame {
                     meta: BufferedFrameMeta {
                         captured_at_ms: now_ms(),
+                        bounds: current_display_bounds(display_id),
                     },
                     width: width as u32,
                     height: height as u32,
@@ -326,6 +332,18 @@ mod imp {
             .as_millis() as i64
     }
 
+    /// The display's rect in global points right now. A cheap CoreGraphics lookup, so it
+    /// runs for every frame.
+    fn current_display_bounds(display_id: u32) -> WindowBounds {
+        let rect = core_graphics::display::CGDisplay::new(display_id).bounds();
+        WindowBounds {
+            x: rect.origin.x.round() as i32,
+            y: rect.origin.y.round() as i32,
+            width: rect.size.width.round() as u32,
+            height: rect.size.height.round() as u32,
+        }
+    }
+
     fn display_to_target(display: SCDisplay) -> DisplayTarget {
         let frame = display.frame();
         DisplayTarget {
@@ -359,7 +377,7 @@ mod imp {
 mod imp {
     use std::path::Path;
 
-    use crate::recorder::window_info::WindowBounds;
+    use super::WindowBounds;
 
     #[derive(Debug, Clone)]
     pub struct PreClickCaptureResult {
@@ -395,9 +413,21 @@ pub use imp::PreClickFrameBuffer;
 mod tests {
     use super::*;
 
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
     fn frames(ts: &[i64]) -> VecDeque<BufferedFrameMeta> {
         ts.iter()
-            .map(|t| BufferedFrameMeta { captured_at_ms: *t })
+            .map(|t| BufferedFrameMeta {
+                captured_at_ms: *t,
+                bounds: bounds(0, 0, 1440, 900),
+            })
             .collect()
     }
 
@@ -415,6 +445,18 @@ mod tests {
         assert_eq!(idx, None);
     }
 
+    #[test]
+    fn picked_frame_keeps_the_bounds_recorded_with_it() {
+        // The display was moved in the arrangement between the second and third frame.
+        let mut ring = frames(&[1_000, 1_060, 1_120]);
+        ring[2].bounds = bounds(-1440, 0, 1440, 900);
+        let idx = pick_frame_index(&ring, 1_100).expect("frame index");
+        assert_eq!(ring[idx].bounds, bounds(0, 0, 1440, 900));
+
+        let idx = pick_frame_index(&ring, 1_130).expect("frame index");
+        assert_eq!(ring[idx].bounds, bounds(-1440, 0, 1440, 900));
+    }
+
     #[test]
     fn pick_frame_returns_none_for_empty_ring() {
         let ring = VecDeque::<BufferedFrameMeta>::new();
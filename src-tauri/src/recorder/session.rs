@@ -1,6 +1,14 @@
-use super::types::{BoundsPercent, DescriptionSource, DescriptionStatus, Step};
+use super::capture::CaptureOptions;
+use super::exclusions::CaptureExclusions;
+use super::storage::{self, StorageInfo, StoragePolicy};
+use super::types::{
+    ActionType, Annotation, BoundsPercent, CaptureBounds, CaptureStatus, CaptureTimings,
+    DescriptionSource, DescriptionStatus, SessionMetadata, Step,
+};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Lightweight diagnostics collected during a recording session.
@@ -17,6 +25,170 @@ pub struct SessionDiagnostics {
     pub captures_failed: u32,
     /// Per-failure reasons, in order of occurrence.
     pub failure_reasons: Vec<String>,
+    /// Screenshots run through the "blur all text" privacy pass.
+    pub privacy_blur_runs: u32,
+    /// Total time spent in the privacy blur pass, in milliseconds.
+    pub privacy_blur_ms: u64,
+    /// Click listeners recreated after their event tap stopped delivering events.
+    pub listener_restarts: u32,
+}
+
+/// Most failure reasons included in a [`DiagnosticsSnapshot`].
+pub const MAX_SNAPSHOT_FAILURE_REASONS: usize = 20;
+
+/// Point-in-time copy of [`SessionDiagnostics`] for the frontend, with the
+/// failure reasons capped to the most recent [`MAX_SNAPSHOT_FAILURE_REASONS`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DiagnosticsSnapshot {
+    pub clicks_received: u32,
+    pub clicks_filtered: u32,
+    pub captures_fallback: u32,
+    pub captures_failed: u32,
+    pub privacy_blur_runs: u32,
+    pub privacy_blur_ms: u64,
+    pub listener_restarts: u32,
+    /// Most recent failure reasons, oldest first.
+    pub failure_reasons: Vec<String>,
+    /// Older reasons left out of `failure_reasons`.
+    pub failure_reasons_omitted: usize,
+}
+
+impl SessionDiagnostics {
+    pub fn snapshot(&self) -> DiagnosticsSnapshot {
+        let omitted = self
+            .failure_reasons
+            .len()
+            .saturating_sub(MAX_SNAPSHOT_FAILURE_REASONS);
+        DiagnosticsSnapshot {
+            clicks_received: self.clicks_received,
+            clicks_filtered: self.clicks_filtered,
+            captures_fallback: self.captures_fallback,
+            captures_failed: self.captures_failed,
+            privacy_blur_runs: self.privacy_blur_runs,
+            privacy_blur_ms: self.privacy_blur_ms,
+            listener_restarts: self.listener_restarts,
+            failure_reasons: self.failure_reasons[omitted..].to_vec(),
+            failure_reasons_omitted: omitted,
+        }
+    }
+}
+
+/// Capture counters plus figures derived from the recorded steps, for the
+/// diagnostics view and the `diagnostics-updated` event.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SessionStats {
+    #[serde(flatten)]
+    pub diagnostics: DiagnosticsSnapshot,
+    pub step_count: usize,
+    /// Share (0.0-1.0) of captured steps that got a screenshot, fallback included.
+    /// None until a step with a capture status exists.
+    pub capture_success_rate: Option<f32>,
+    /// Mean gap between consecutive recorded actions (notes excluded).
+    pub avg_step_interval_ms: Option<u64>,
+    /// Step counts keyed by action type name ("Click", "Drag", ...).
+    pub steps_by_action: BTreeMap<String, usize>,
+    /// Step counts per app, most steps first.
+    pub steps_by_app: Vec<AppStepCount>,
+    /// Spread of the per-step capture timings.
+    pub capture_timings: CaptureTimingStats,
+}
+
+/// Min / median / max of one capture phase across the session's steps, in ms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimingSummary {
+    pub min: i64,
+    pub median: i64,
+    pub max: i64,
+}
+
+impl TimingSummary {
+    /// None without samples. With an even count the median is the lower middle value.
+    fn of(mut samples: Vec<i64>) -> Option<Self> {
+        samples.sort_unstable();
+        Some(Self {
+            min: *samples.first()?,
+            median: samples[(samples.len() - 1) / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// [`TimingSummary`] per phase of [`CaptureTimings`]; None where no step has that phase.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CaptureTimingStats {
+    pub ax_lookup_ms: Option<TimingSummary>,
+    pub window_resolve_ms: Option<TimingSummary>,
+    pub capture_ms: Option<TimingSummary>,
+    pub pre_click_frame_age_ms: Option<TimingSummary>,
+}
+
+impl CaptureTimingStats {
+    fn of(steps: &[Step]) -> Self {
+        let timings: Vec<&CaptureTimings> = steps
+            .iter()
+            .filter_map(|s| s.capture_timings.as_ref())
+            .collect();
+        let summary = |phase: fn(&CaptureTimings) -> Option<i64>| {
+            TimingSummary::of(timings.iter().filter_map(|t| phase(t)).collect())
+        };
+        Self {
+            ax_lookup_ms: summary(|t| t.ax_lookup_ms.map(|ms| ms as i64)),
+            window_resolve_ms: summary(|t| t.window_resolve_ms.map(|ms| ms as i64)),
+            capture_ms: summary(|t| t.capture_ms.map(|ms| ms as i64)),
+            pre_click_frame_age_ms: summary(|t| t.pre_click_frame_age_ms),
+        }
+    }
+}
+
+/// A session directory on disk, for listing recent recordings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub dir: PathBuf,
+    /// Milliseconds since the Unix epoch; 0 when the directory has no manifest.
+    pub created_at: i64,
+    /// 0 when the directory has no manifest.
+    pub step_count: usize,
+    /// Size of everything in the directory.
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AppStepCount {
+    pub app: String,
+    pub steps: usize,
+}
+
+/// Default `dedupe_consecutive` thresholds: a double-fired click lands on the same
+/// pixel or next to it, well within a human's fastest repeat.
+pub const DEFAULT_DEDUPE_DISTANCE_PX: i32 = 4;
+pub const DEFAULT_DEDUPE_INTERVAL_MS: i64 = 300;
+
+/// Editor changes kept for undo; older ones are forgotten.
+pub const MAX_UNDO_DEPTH: usize = 50;
+
+/// One editor change, kept as what it takes to revert it rather than a copy of every
+/// step. Steps are found by ID, so steps recorded since stay where they are.
+#[derive(Debug, Clone)]
+enum StepEdit {
+    /// Steps as they were before being edited in place.
+    Changed(Vec<Step>),
+    /// Removed steps with their former index, in ascending order.
+    Removed(Vec<(usize, Step)>),
+    /// IDs of steps that were put back.
+    Restored(Vec<String>),
+    /// Step order before a reorder.
+    Reordered(Vec<String>),
+}
+
+impl StepEdit {
+    fn steps_mut(&mut self) -> Vec<&mut Step> {
+        match self {
+            StepEdit::Changed(steps) => steps.iter_mut().collect(),
+            StepEdit::Removed(removed) => removed.iter_mut().map(|(_, step)| step).collect(),
+            StepEdit::Restored(_) | StepEdit::Reordered(_) => Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -24,18 +196,82 @@ pub struct Session {
     pub steps: Vec<Step>,
     pub temp_dir: PathBuf,
     pub diagnostics: SessionDiagnostics,
+    pub storage_policy: StoragePolicy,
+    pub capture_options: CaptureOptions,
+    /// Screen areas masked in every region capture.
+    pub capture_exclusions: CaptureExclusions,
+    /// Title, author and tags; the title is the default for exports.
+    pub metadata: SessionMetadata,
+    /// Size of each stored screenshot, so retaken ones replace their old size.
+    stored_bytes: HashMap<PathBuf, u64>,
+    /// The size cap was reached and reported; it pauses recording only once.
+    storage_cap_reported: bool,
+    /// Bumped by every change to the steps, so the editor can tell its copy is stale.
+    revision: u64,
+    /// Steps handed to description generation and still waiting for their answer.
+    pending_descriptions: HashSet<String>,
+    /// Editor changes, oldest first.
+    undo_history: VecDeque<StepEdit>,
+    /// Changes reverted by undo, most recently undone last.
+    redo_history: Vec<StepEdit>,
+    /// Highest step number handed out, so a deleted step's ID and screenshot file are
+    /// never reused.
+    last_step_number: u64,
+}
+
+/// A write was computed against an older revision of the steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RevisionConflict {
+    pub expected: u64,
+    pub current: u64,
+}
+
+impl fmt::Display for RevisionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "steps changed since revision {} (now {}); reload and try again",
+            self.expected, self.current
+        )
+    }
+}
+
+/// Number in a `step-N` ID, as handed out by [`Session::next_step_id`].
+fn step_number(id: &str) -> Option<u64> {
+    id.strip_prefix("step-")?.parse().ok()
+}
+
+/// `path` with a numbered suffix (`step-001-1.png`, ...) that no file uses yet.
+fn unused_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem}-{n}.png")))
+        .find(|candidate| !candidate.exists())
+        .expect("some suffix is free")
+}
+
+/// Cache directory holding one directory per session.
+pub fn sessions_root() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("com.w0nk1.stepcast")
+        .join("sessions")
 }
 
 impl Session {
     pub fn new() -> std::io::Result<Self> {
+        Self::new_in(&sessions_root())
+    }
+
+    /// New session whose directory is created under `root`.
+    pub fn new_in(root: &Path) -> std::io::Result<Self> {
         let id = Uuid::new_v4().to_string();
 
         // Create temp directory for this session
-        let temp_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("com.w0nk1.stepcast")
-            .join("sessions")
-            .join(&id);
+        let temp_dir = root.join(&id);
 
         std::fs::create_dir_all(&temp_dir)?;
 
@@ -43,9 +279,112 @@ impl Session {
             steps: Vec::new(),
             temp_dir,
             diagnostics: SessionDiagnostics::default(),
+            storage_policy: StoragePolicy::default(),
+            capture_options: CaptureOptions::default(),
+            capture_exclusions: CaptureExclusions::default(),
+            metadata: SessionMetadata {
+                created_at: chrono::Utc::now().timestamp_millis(),
+                ..SessionMetadata::default()
+            },
+            stored_bytes: HashMap::new(),
+            storage_cap_reported: false,
+            revision: 0,
+            pending_descriptions: HashSet::new(),
+            undo_history: VecDeque::new(),
+            redo_history: Vec::new(),
+            last_step_number: 0,
+        })
+    }
+
+    /// Reopen a session saved by [`Session::persist`] from its directory. Diagnostics
+    /// and capture settings start fresh.
+    pub fn open(dir: &Path) -> Result<Self, storage::StorageError> {
+        let steps = storage::read_steps(&dir.join(storage::STEPS_FILE))?;
+        let metadata = storage::read_session_metadata(dir).unwrap_or_default();
+        let manifest = storage::read_session_manifest(dir).unwrap_or_default();
+        let last_step_number = steps
+            .iter()
+            .filter_map(|s| step_number(&s.id))
+            .fold(manifest.last_step_number, u64::max);
+        Ok(Self {
+            steps,
+            temp_dir: dir.to_path_buf(),
+            diagnostics: SessionDiagnostics::default(),
+            storage_policy: StoragePolicy::default(),
+            capture_options: CaptureOptions::default(),
+            capture_exclusions: CaptureExclusions::default(),
+            metadata,
+            stored_bytes: HashMap::new(),
+            storage_cap_reported: false,
+            revision: 0,
+            pending_descriptions: HashSet::new(),
+            undo_history: VecDeque::new(),
+            redo_history: Vec::new(),
+            last_step_number,
         })
     }
 
+    /// Write steps and metadata into the session directory, so the session can be
+    /// reopened after it leaves the editor or the app restarts.
+    pub fn persist(&self) -> Result<(), storage::StorageError> {
+        storage::write_steps(&self.temp_dir, &self.steps)?;
+        storage::write_session_metadata(&self.temp_dir, &self.metadata)?;
+        self.write_manifest()
+    }
+
+    /// Record the creation time, step count and last step number in the session's
+    /// manifest.
+    fn write_manifest(&self) -> Result<(), storage::StorageError> {
+        storage::write_session_manifest(
+            &self.temp_dir,
+            &storage::SessionManifest {
+                created_at: self.metadata.created_at,
+                step_count: self.steps.len(),
+                last_step_number: self.last_step_number,
+            },
+        )
+    }
+
+    /// Every session directory in the cache, newest first.
+    pub fn list_sessions() -> Vec<SessionSummary> {
+        Self::list_sessions_in(&sessions_root())
+    }
+
+    /// Every session directory under `root`, newest first.
+    pub fn list_sessions_in(root: &Path) -> Vec<SessionSummary> {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<SessionSummary> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|dir| dir.is_dir())
+            .map(|dir| {
+                let manifest = storage::read_session_manifest(&dir).unwrap_or_default();
+                SessionSummary {
+                    id: dir
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    created_at: manifest.created_at,
+                    step_count: manifest.step_count,
+                    total_bytes: storage::dir_usage(&dir).1,
+                    dir,
+                }
+            })
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        sessions
+    }
+
+    /// Stable identifier: the name of the session directory.
+    pub fn id(&self) -> String {
+        self.temp_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
     /// Remove this session's temp directory and all screenshots.
     pub fn cleanup(&self) {
         if self.temp_dir.exists() {
@@ -53,19 +392,18 @@ impl Session {
         }
     }
 
-    /// Remove all session directories and temp exports from the cache.
-    pub fn cleanup_all_sessions() {
+    /// Remove the `orphans` session directories (never persisted, so not in the
+    /// session registry) and temp exports from the cache.
+    pub fn cleanup_orphaned_sessions(orphans: &[PathBuf]) {
+        for dir in orphans {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+
         let cache = match dirs::cache_dir() {
             Some(d) => d,
             None => return,
         };
 
-        // Session screenshot directories
-        let sessions_dir = cache.join("com.w0nk1.stepcast").join("sessions");
-        if sessions_dir.is_dir() {
-            let _ = std::fs::remove_dir_all(&sessions_dir);
-        }
-
         // Temp HTML files from PDF export
         let exports_dir = cache.join("stepcast");
         if exports_dir.is_dir() {
@@ -73,23 +411,190 @@ impl Session {
         }
     }
 
+    /// Recorded steps aren't editor changes: undo leaves them in place.
     pub fn add_step(&mut self, step: Step) {
-        self.steps.push(step);
+        self.insert_step(self.steps.len(), step);
+    }
+
+    /// [`Session::add_step`] at `index`, e.g. for a marker placed before the last step.
+    pub fn insert_step(&mut self, index: usize, step: Step) {
+        self.claim_step_id(&step.id);
+        self.steps.insert(index, step);
+        self.touch();
+        let _ = self.write_manifest();
+    }
+
+    /// Count `id` as handed out, so [`Session::next_step_id`] moves past it.
+    fn claim_step_id(&mut self, id: &str) {
+        if let Some(number) = step_number(id) {
+            self.last_step_number = self.last_step_number.max(number);
+        }
     }
 
     pub fn get_steps(&self) -> &[Step] {
         &self.steps
     }
 
+    /// Counts as a change, since the caller is about to edit the step.
     pub fn last_step_mut(&mut self) -> Option<&mut Step> {
+        self.touch();
         self.steps.last_mut()
     }
 
+    /// The current revision of the steps.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Bump the revision. Code that edits `steps` directly calls this itself.
+    pub fn touch(&mut self) {
+        self.revision += 1;
+    }
+
+    /// Reject a write the caller computed against another revision; `None` skips the check.
+    pub fn check_revision(&self, expected: Option<u64>) -> Result<(), RevisionConflict> {
+        match expected {
+            Some(expected) if expected != self.revision => Err(RevisionConflict {
+                expected,
+                current: self.revision,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The step with `step_id`, counting the lookup as a change.
+    pub fn step_mut(&mut self, step_id: &str) -> Option<&mut Step> {
+        let index = self.steps.iter().position(|s| s.id == step_id)?;
+        self.touch();
+        Some(&mut self.steps[index])
+    }
+
+    /// Remember how to revert an editor change, for [`Session::undo`].
+    fn record_edit(&mut self, edit: StepEdit) {
+        if self.undo_history.len() == MAX_UNDO_DEPTH {
+            self.undo_history.pop_front();
+        }
+        self.undo_history.push_back(edit);
+        self.redo_history.clear();
+    }
+
+    /// Apply an editor change to the step with `step_id`. Only a change that leaves
+    /// the step different is recorded for undo and bumps the revision.
+    fn edit_step(&mut self, step_id: &str, edit: impl FnOnce(&mut Step)) -> Option<&Step> {
+        let index = self.steps.iter().position(|s| s.id == step_id)?;
+        let before = self.steps[index].clone();
+        edit(&mut self.steps[index]);
+        if self.steps[index] != before {
+            self.record_edit(StepEdit::Changed(vec![before]));
+            self.touch();
+        }
+        Some(&self.steps[index])
+    }
+
+    fn clear_edit_history(&mut self) {
+        self.undo_history.clear();
+        self.redo_history.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_history.is_empty()
+    }
+
+    /// Revert the last editor change. Returns false when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo_history.pop_back() else {
+            return false;
+        };
+        let redo = self.revert(edit);
+        self.redo_history.push(redo);
+        self.restored();
+        true
+    }
+
+    /// Reapply the last undone change. Returns false when there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo_history.pop() else {
+            return false;
+        };
+        let undo = self.revert(edit);
+        self.undo_history.push_back(undo);
+        self.restored();
+        true
+    }
+
+    /// Apply `edit` to the steps. Returns the edit that reverts this in turn.
+    fn revert(&mut self, edit: StepEdit) -> StepEdit {
+        match edit {
+            StepEdit::Changed(steps) => StepEdit::Changed(
+                steps
+                    .into_iter()
+                    .filter_map(|step| {
+                        let current = self.steps.iter_mut().find(|s| s.id == step.id)?;
+                        Some(std::mem::replace(current, step))
+                    })
+                    .collect(),
+            ),
+            StepEdit::Removed(removed) => {
+                let ids = removed.iter().map(|(_, step)| step.id.clone()).collect();
+                for (index, step) in removed {
+                    let index = index.min(self.steps.len());
+                    self.steps.insert(index, step);
+                }
+                StepEdit::Restored(ids)
+            }
+            StepEdit::Restored(ids) => StepEdit::Removed(self.take_steps(|s| ids.contains(&s.id))),
+            StepEdit::Reordered(order) => {
+                let current = self.steps.iter().map(|s| s.id.clone()).collect();
+                self.arrange_steps(&order);
+                StepEdit::Reordered(current)
+            }
+        }
+    }
+
+    /// Remove the steps matching `remove`, with the index each had. They no longer wait
+    /// for a description.
+    fn take_steps(&mut self, remove: impl Fn(&Step) -> bool) -> Vec<(usize, Step)> {
+        let mut removed = Vec::new();
+        for (index, step) in std::mem::take(&mut self.steps).into_iter().enumerate() {
+            if remove(&step) {
+                self.pending_descriptions.remove(&step.id);
+                removed.push((index, step));
+            } else {
+                self.steps.push(step);
+            }
+        }
+        removed
+    }
+
+    /// After undo/redo: a restored step can't get the description it was generating
+    /// once its wait ended, so it stops showing as generating.
+    fn restored(&mut self) {
+        for step in &mut self.steps {
+            if step.description_status == Some(DescriptionStatus::Generating)
+                && !self.pending_descriptions.contains(&step.id)
+            {
+                step.description_status = Some(DescriptionStatus::Idle);
+            }
+        }
+        self.touch();
+    }
+
     /// Update a step's note by ID. Returns the updated step or None if not found.
     pub fn update_step_note(&mut self, step_id: &str, note: Option<String>) -> Option<&Step> {
-        let step = self.steps.iter_mut().find(|s| s.id == step_id)?;
-        step.note = note;
-        Some(step)
+        self.edit_step(step_id, |step| step.note = note)
+    }
+
+    /// Set or clear a step's title. Blank titles clear it. Returns the updated step or
+    /// None if not found.
+    pub fn update_step_title(&mut self, step_id: &str, title: Option<String>) -> Option<&Step> {
+        let title = title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        self.edit_step(step_id, |step| step.title = title)
     }
 
     /// Update a step's crop region by ID. `None` resets to full image.
@@ -98,9 +603,69 @@ impl Session {
         step_id: &str,
         crop_region: Option<BoundsPercent>,
     ) -> Option<&Step> {
-        let step = self.steps.iter_mut().find(|s| s.id == step_id)?;
-        step.crop_region = crop_region;
-        Some(step)
+        self.edit_step(step_id, |step| step.crop_region = crop_region)
+    }
+
+    /// Set or clear how long to wait after a step. Returns the updated step or None if
+    /// not found.
+    pub fn set_step_wait(&mut self, step_id: &str, wait_ms: Option<u32>) -> Option<&Step> {
+        self.edit_step(step_id, |step| step.wait_ms = wait_ms.filter(|&ms| ms > 0))
+    }
+
+    /// Replace a step's annotations, dropping any that would draw nothing. Returns the
+    /// updated step or None if not found.
+    pub fn set_step_annotations(
+        &mut self,
+        step_id: &str,
+        annotations: Vec<Annotation>,
+    ) -> Option<&Step> {
+        let annotations = annotations
+            .into_iter()
+            .filter_map(Annotation::normalized)
+            .collect();
+        self.edit_step(step_id, |step| step.annotations = annotations)
+    }
+
+    /// Update the session's title, author and tags and save them next to its
+    /// screenshots, so they survive alongside the steps.
+    pub fn set_metadata(
+        &mut self,
+        title: &str,
+        author: Option<&str>,
+        tags: &[String],
+    ) -> Result<&SessionMetadata, storage::StorageError> {
+        self.metadata.update(title, author, tags);
+        storage::write_session_metadata(&self.temp_dir, &self.metadata)?;
+        Ok(&self.metadata)
+    }
+
+    /// Check that every step's screenshot is still on disk and non-empty, e.g. after
+    /// crash recovery or files moved by hand. Steps whose file is missing are marked
+    /// [`CaptureStatus::Failed`]; returns their IDs.
+    pub fn validate_screenshots(&mut self) -> Vec<String> {
+        let mut missing = Vec::new();
+        for step in &mut self.steps {
+            let Some(path) = step.screenshot_path.as_deref() else {
+                continue;
+            };
+            if !super::pipeline::validate_screenshot(Path::new(path)) {
+                step.capture_status = Some(CaptureStatus::Failed);
+                missing.push(step.id.clone());
+            }
+        }
+        if !missing.is_empty() {
+            self.touch();
+        }
+        missing
+    }
+
+    /// Replace a step's crop with the auto-crop recomputed from its capture bounds.
+    /// Returns None if the step is missing or predates stored capture bounds.
+    pub fn reset_step_crop_to_auto(&mut self, step_id: &str) -> Option<&Step> {
+        let step = self.steps.iter().find(|s| s.id == step_id)?;
+        step.capture_bounds?;
+        let crop_region = super::pipeline::auto_crop_for_step(step);
+        self.edit_step(step_id, |step| step.crop_region = crop_region)
     }
 
     /// Set a step's manual description. Passing `None` clears the description and related metadata.
@@ -109,28 +674,60 @@ impl Session {
         step_id: &str,
         description: Option<String>,
     ) -> Option<&Step> {
-        let step = self.steps.iter_mut().find(|s| s.id == step_id)?;
         let desc = description
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
-        step.description = desc;
-        step.description_source = step.description.as_ref().map(|_| DescriptionSource::Manual);
-        step.description_status = None;
+        self.edit_step(step_id, |step| {
+            step.description_source = desc.as_ref().map(|_| DescriptionSource::Manual);
+            step.description = desc;
+            step.description_status = None;
+            step.description_error = None;
+        })
+    }
+
+    /// Mark a step as generating a description. Only steps marked here accept the
+    /// generated text or failure later on.
+    pub fn begin_step_description(&mut self, step_id: &str) -> Option<&Step> {
+        let step = self.step_mut(step_id)?;
+        step.description_status = Some(DescriptionStatus::Generating);
         step.description_error = None;
-        Some(step)
+        self.pending_descriptions.insert(step_id.to_string());
+        self.steps.iter().find(|s| s.id == step_id)
+    }
+
+    /// The step awaiting the description answer for `step_id`, ending the wait. `None`
+    /// when the step was deleted, its ID reused, or its status changed in the meantime.
+    fn take_pending_description(&mut self, step_id: &str) -> Option<&mut Step> {
+        let pending = self.pending_descriptions.remove(step_id);
+        let generating = self.steps.iter().any(|s| {
+            s.id == step_id && s.description_status == Some(DescriptionStatus::Generating)
+        });
+        if !(pending && generating) {
+            eprintln!("Skipping description for {step_id}: step no longer generating");
+            return None;
+        }
+        self.step_mut(step_id)
     }
 
-    /// Apply an AI-generated description to a step.
+    /// Apply an AI-generated description to a step marked by
+    /// [`Session::begin_step_description`]. A manual description is kept unless
+    /// `overwrite_manual` is set; the step's status is still cleared. Editing the
+    /// description while it generates ends the wait, so the edit wins.
     pub fn apply_step_description_ai(
         &mut self,
         step_id: &str,
         description: String,
+        overwrite_manual: bool,
     ) -> Option<&Step> {
-        let step = self.steps.iter_mut().find(|s| s.id == step_id)?;
         let desc = description.trim().to_string();
         if desc.is_empty() {
             return None;
         }
+        let step = self.take_pending_description(step_id)?;
+        if !overwrite_manual && step.description_source == Some(DescriptionSource::Manual) {
+            step.description_status = Some(DescriptionStatus::Idle);
+            return Some(step);
+        }
         step.description = Some(desc);
         step.description_source = Some(DescriptionSource::Ai);
         step.description_status = Some(DescriptionStatus::Idle);
@@ -138,9 +735,9 @@ impl Session {
         Some(step)
     }
 
-    /// Mark a step description generation as failed.
+    /// Mark a step description generation as failed, if the step still awaits it.
     pub fn mark_step_description_failed(&mut self, step_id: &str, error: String) -> Option<&Step> {
-        let step = self.steps.iter_mut().find(|s| s.id == step_id)?;
+        let step = self.take_pending_description(step_id)?;
         step.description_status = Some(DescriptionStatus::Failed);
         step.description_error = Some(error);
         Some(step)
@@ -148,33 +745,371 @@ impl Session {
 
     /// Remove a step by ID. Returns true if found and removed.
     pub fn delete_step(&mut self, step_id: &str) -> bool {
-        let before = self.steps.len();
-        self.steps.retain(|s| s.id != step_id);
-        self.steps.len() < before
+        let removed = self.take_steps(|s| s.id == step_id);
+        if removed.is_empty() {
+            return false;
+        }
+        self.record_edit(StepEdit::Removed(removed));
+        self.touch();
+        true
+    }
+
+    /// Remove steps the recorder itself rejects, e.g. duplicates or lock-screen
+    /// captures. Unlike [`Session::delete_step`] this isn't an editor change: undo
+    /// never brings them back and what can be redone is kept. Returns the removed steps.
+    pub fn discard_steps(&mut self, remove: impl Fn(&Step) -> bool) -> Vec<Step> {
+        let removed = self.take_steps(remove);
+        if !removed.is_empty() {
+            self.touch();
+        }
+        removed.into_iter().map(|(_, step)| step).collect()
+    }
+
+    /// Remove all recorder-generated steps (section breaks). Returns the removed IDs.
+    pub fn delete_auto_steps(&mut self) -> Vec<String> {
+        let removed = self.take_steps(Step::is_auto_generated);
+        let ids = removed.iter().map(|(_, step)| step.id.clone()).collect();
+        if !removed.is_empty() {
+            self.record_edit(StepEdit::Removed(removed));
+            self.touch();
+        }
+        ids
+    }
+
+    /// Remove steps that repeat the previous step: same app and action, clicked within
+    /// `max_distance_px` screen pixels and `max_interval_ms` of it. Some apps deliver one
+    /// click twice. Each step is compared with the last kept one, so a burst collapses
+    /// into its first step. Screenshots of removed steps are deleted when they live in
+    /// this session's directory. Returns the removed IDs.
+    pub fn dedupe_consecutive(
+        &mut self,
+        max_distance_px: i32,
+        max_interval_ms: i64,
+    ) -> Vec<String> {
+        let is_repeat = |prev: &Step, step: &Step| {
+            let dx = i64::from(step.x) - i64::from(prev.x);
+            let dy = i64::from(step.y) - i64::from(prev.y);
+            let max_distance = i64::from(max_distance_px.max(0));
+            step.action != ActionType::Note
+                && !step.is_auth_placeholder()
+                && !step.is_auto_generated()
+                && step.action == prev.action
+                && step.app == prev.app
+                && (0..=max_interval_ms).contains(&(step.ts - prev.ts))
+                && dx * dx + dy * dy <= max_distance * max_distance
+        };
+
+        let mut kept: Option<&Step> = None;
+        let mut repeats = HashSet::new();
+        for step in &self.steps {
+            if kept.is_some_and(|prev| is_repeat(prev, step)) {
+                repeats.insert(step.id.clone());
+            } else {
+                kept = Some(step);
+            }
+        }
+        let removed = self.discard_steps(|s| repeats.contains(&s.id));
+        for path in removed
+            .iter()
+            .flat_map(|step| [&step.screenshot_path, &step.result_screenshot_path])
+            .flatten()
+        {
+            if Path::new(path).starts_with(&self.temp_dir) {
+                let _ = std::fs::remove_file(path);
+                self.stored_bytes.remove(Path::new(path));
+            }
+        }
+        removed.into_iter().map(|step| step.id).collect()
     }
 
-    /// Reorder steps to match the given ID sequence.
-    /// IDs not in the list are dropped; unknown IDs are ignored.
+    /// Reorder steps to match the given ID sequence. Steps missing from the list, e.g.
+    /// recorded after the caller read the steps, follow in their current order;
+    /// unknown IDs are ignored.
     pub fn reorder_steps(&mut self, step_ids: &[String]) {
-        let mut reordered = Vec::with_capacity(step_ids.len());
+        let before: Vec<String> = self.steps.iter().map(|s| s.id.clone()).collect();
+        self.arrange_steps(step_ids);
+        if self.steps.iter().map(|s| &s.id).ne(before.iter()) {
+            self.record_edit(StepEdit::Reordered(before));
+            self.touch();
+        }
+    }
+
+    fn arrange_steps(&mut self, step_ids: &[String]) {
+        let mut rest: Vec<Option<Step>> = std::mem::take(&mut self.steps)
+            .into_iter()
+            .map(Some)
+            .collect();
         for id in step_ids {
-            if let Some(pos) = self.steps.iter().position(|s| s.id == *id) {
-                reordered.push(self.steps.swap_remove(pos));
+            if let Some(step) = rest
+                .iter_mut()
+                .find(|s| s.as_ref().is_some_and(|s| s.id == *id))
+                .and_then(Option::take)
+            {
+                self.steps.push(step);
+            }
+        }
+        self.steps.extend(rest.into_iter().flatten());
+    }
+
+    /// Rename screenshots in the session cache so their file names follow display
+    /// order (`step-001.png` for the first step, ...), updating each
+    /// `screenshot_path`. Files outside `temp_dir` are left alone. Returns how many
+    /// files were renamed.
+    ///
+    /// Target names can belong to another step's current file, so every file first
+    /// moves to a temporary name and only then to its final one.
+    pub fn renumber_screenshots(&mut self) -> std::io::Result<usize> {
+        let mut moves: Vec<(usize, PathBuf)> = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            let Some(path) = step.screenshot_path.as_deref().map(PathBuf::from) else {
+                continue;
+            };
+            let target = self.temp_dir.join(format!("step-{:03}.png", index + 1));
+            if path == target || !path.starts_with(&self.temp_dir) || !path.is_file() {
+                continue;
+            }
+            moves.push((index, target));
+        }
+        if !moves.is_empty() {
+            self.touch();
+        }
+        let mut renamed: HashMap<String, String> = moves
+            .iter()
+            .filter_map(|(index, target)| {
+                let from = self.steps[*index].screenshot_path.clone()?;
+                Some((from, target.to_string_lossy().to_string()))
+            })
+            .collect();
+
+        for (index, _) in &moves {
+            let step = &mut self.steps[*index];
+            let Some(current) = step.screenshot_path.as_deref().map(PathBuf::from) else {
+                continue;
+            };
+            let temp = self.temp_dir.join(format!(".renumber-{index}.png"));
+            std::fs::rename(&current, &temp)?;
+            step.screenshot_path = Some(temp.to_string_lossy().to_string());
+        }
+        for (index, target) in &moves {
+            // Whatever still holds the name belongs to a step kept only for undo, e.g.
+            // a deleted one: it moves aside instead of being overwritten.
+            if target.exists() {
+                let aside = unused_path(target);
+                std::fs::rename(target, &aside)?;
+                renamed.insert(
+                    target.to_string_lossy().to_string(),
+                    aside.to_string_lossy().to_string(),
+                );
+            }
+            let step = &mut self.steps[*index];
+            let Some(temp) = step.screenshot_path.as_deref().map(PathBuf::from) else {
+                continue;
+            };
+            std::fs::rename(&temp, target)?;
+            step.screenshot_path = Some(target.to_string_lossy().to_string());
+        }
+        // Steps kept for undo point at the files under their new names too.
+        for step in self
+            .undo_history
+            .iter_mut()
+            .chain(self.redo_history.iter_mut())
+            .flat_map(StepEdit::steps_mut)
+        {
+            if let Some(to) = step.screenshot_path.as_ref().and_then(|p| renamed.get(p)) {
+                step.screenshot_path = Some(to.clone());
+            }
+        }
+        Ok(moves.len())
+    }
+
+    /// Apply the storage policy to the screenshots `step` just wrote: downscale
+    /// oversized ones and count their bytes. Returns true the first time the session
+    /// goes over its size cap.
+    pub fn store_step_screenshots(&mut self, step: &Step) -> bool {
+        for path in [&step.screenshot_path, &step.result_screenshot_path]
+            .into_iter()
+            .flatten()
+        {
+            let path = PathBuf::from(path);
+            match storage::fit_screenshot(&path, self.storage_policy.max_screenshot_edge_px) {
+                Ok(bytes) => {
+                    self.stored_bytes.insert(path, bytes);
+                }
+                Err(e) => eprintln!("Storing screenshot {} failed: {e}", path.display()),
             }
         }
-        self.steps = reordered;
+        if self.storage_cap_reported || self.bytes_stored() <= self.storage_policy.max_session_bytes
+        {
+            return false;
+        }
+        self.storage_cap_reported = true;
+        true
+    }
+
+    /// Total size of the screenshots stored so far.
+    pub fn bytes_stored(&self) -> u64 {
+        self.stored_bytes.values().sum()
+    }
+
+    /// Total size in bytes of the screenshots and result frames the steps reference.
+    pub fn disk_usage(&self) -> u64 {
+        let mut seen = std::collections::HashSet::new();
+        self.steps
+            .iter()
+            .flat_map(|step| [&step.screenshot_path, &step.result_screenshot_path])
+            .flatten()
+            .filter(|path| seen.insert(path.as_str()))
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum()
+    }
+
+    /// Where this session keeps its files and how much space they take.
+    pub fn storage_info(&self) -> StorageInfo {
+        let (file_count, total_bytes) = storage::dir_usage(&self.temp_dir);
+        StorageInfo {
+            dir: self.temp_dir.to_string_lossy().into_owned(),
+            file_count,
+            total_bytes,
+            max_bytes: self.storage_policy.max_session_bytes,
+        }
     }
 
+    /// ID for the next recorded step. Numbers only grow, so it never matches a step
+    /// that was deleted, nor overwrites its screenshot.
     pub fn next_step_id(&self) -> String {
-        format!("step-{:03}", self.steps.len() + 1)
+        format!("step-{:03}", self.last_step_number + 1)
     }
 
     pub fn screenshot_path(&self, step_id: &str) -> PathBuf {
         self.temp_dir.join(format!("{step_id}.png"))
     }
 
-    /// Write diagnostics.json to the session cache directory.
+    /// Prepend the full-display overview captured at `screenshot_path` when recording
+    /// started. `app`/`window_title` name the frontmost window, if any.
+    pub fn add_context_step(
+        &mut self,
+        ts: i64,
+        app: String,
+        window_title: String,
+        capture_bounds: CaptureBounds,
+        screenshot_path: &Path,
+    ) -> &Step {
+        let step = Step {
+            id: self.next_step_id(),
+            ts,
+            action: ActionType::Overview,
+            x: capture_bounds.x + capture_bounds.width as i32 / 2,
+            y: capture_bounds.y + capture_bounds.height as i32 / 2,
+            click_x_percent: 50.0,
+            click_y_percent: 50.0,
+            app,
+            window_title,
+            screenshot_path: Some(screenshot_path.to_string_lossy().to_string()),
+            note: None,
+            description: None,
+            description_source: None,
+            description_status: None,
+            description_error: None,
+            ax: None,
+            capture_status: Some(CaptureStatus::Ok),
+            capture_error: None,
+            crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: Some(capture_bounds),
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
+            title: None,
+        };
+        self.claim_step_id(&step.id);
+        self.steps.insert(0, step);
+        self.clear_edit_history();
+        self.touch();
+        &self.steps[0]
+    }
+
+    /// Step counts per app for recorded actions (notes and the overview excluded), most steps
+    /// first and ties by name.
+    pub fn step_counts_by_app(&self) -> Vec<AppStepCount> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for step in self
+            .steps
+            .iter()
+            .filter(|s| !matches!(s.action, ActionType::Note | ActionType::Overview))
+        {
+            let app = step.app.trim();
+            if !app.is_empty() {
+                *counts.entry(app).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<AppStepCount> = counts
+            .into_iter()
+            .map(|(app, steps)| AppStepCount {
+                app: app.to_string(),
+                steps,
+            })
+            .collect();
+        // Stable sort keeps the BTreeMap's name order among equal counts.
+        counts.sort_by_key(|c| std::cmp::Reverse(c.steps));
+        counts
+    }
+
+    pub fn stats(&self) -> SessionStats {
+        let captured: Vec<&CaptureStatus> = self
+            .steps
+            .iter()
+            .filter_map(|s| s.capture_status.as_ref())
+            .collect();
+        let capture_success_rate = (!captured.is_empty()).then(|| {
+            let ok = captured
+                .iter()
+                .filter(|c| !matches!(c, CaptureStatus::Failed))
+                .count();
+            ok as f32 / captured.len() as f32
+        });
+
+        let mut times: Vec<i64> = self
+            .steps
+            .iter()
+            .filter(|s| s.action != ActionType::Note)
+            .map(|s| s.ts)
+            .collect();
+        times.sort_unstable();
+        let avg_step_interval_ms = (times.len() >= 2)
+            .then(|| ((times[times.len() - 1] - times[0]) / (times.len() as i64 - 1)) as u64);
+
+        let mut steps_by_action = BTreeMap::new();
+        for step in &self.steps {
+            *steps_by_action
+                .entry(format!("{:?}", step.action))
+                .or_default() += 1;
+        }
+
+        SessionStats {
+            diagnostics: self.diagnostics.snapshot(),
+            step_count: self.steps.len(),
+            capture_success_rate,
+            avg_step_interval_ms,
+            steps_by_action,
+            steps_by_app: self.step_counts_by_app(),
+            capture_timings: CaptureTimingStats::of(&self.steps),
+        }
+    }
+
+    /// Write diagnostics.json and the manifest to the session cache directory.
     pub fn write_diagnostics(&self) {
+        let _ = self.write_manifest();
         let path = self.temp_dir.join("diagnostics.json");
         match serde_json::to_string_pretty(&self.diagnostics) {
             Ok(json) => {
@@ -191,6 +1126,7 @@ impl Session {
 
 #[cfg(test)]
 mod tests {
+    use super::super::types::CaptureBounds;
     use super::*;
 
     #[test]
@@ -213,6 +1149,81 @@ mod tests {
         std::fs::remove_dir_all(&session.temp_dir).ok();
     }
 
+    #[test]
+    fn step_ids_of_deleted_steps_are_not_reused() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let mut session = Session::new_in(root.path()).expect("create session");
+        for _ in 0..3 {
+            let mut step = Step::sample();
+            step.id = session.next_step_id();
+            let path = session.screenshot_path(&step.id);
+            std::fs::write(&path, b"png").expect("write screenshot");
+            step.screenshot_path = Some(path.to_string_lossy().to_string());
+            session.add_step(step);
+        }
+        assert!(session.delete_step("step-002"));
+
+        let id = session.next_step_id();
+        assert_eq!(id, "step-004");
+        assert!(session.steps.iter().all(|s| s.id != id));
+        assert!(!session.screenshot_path(&id).exists());
+
+        // The counter survives reopening, even with the last step gone.
+        assert!(session.delete_step("step-003"));
+        session.persist().expect("persist");
+        let reopened = Session::open(&session.temp_dir).expect("open");
+        assert_eq!(reopened.next_step_id(), "step-004");
+    }
+
+    #[test]
+    fn context_step_adds_one_overview_step_at_session_start() {
+        let mut session = Session::new().expect("create session");
+        let path = session.screenshot_path(&session.next_step_id());
+        let bounds = CaptureBounds {
+            x: 0,
+            y: 0,
+            width: 1440,
+            height: 900,
+            display_width: 1440,
+            display_height: 900,
+        };
+
+        let step =
+            session.add_context_step(1_000, "Finder".into(), "Downloads".into(), bounds, &path);
+        assert_eq!(step.id, "step-001");
+        assert_eq!(step.action, ActionType::Overview);
+        assert_eq!(step.capture_bounds, Some(bounds));
+        assert_eq!(session.steps.len(), 1);
+        assert_eq!(session.next_step_id(), "step-002");
+        // Not a recorded action, so it doesn't count towards any app.
+        assert!(session.step_counts_by_app().is_empty());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn disk_usage_sums_referenced_screenshots_once() {
+        let mut session = Session::new().expect("create session");
+        let shot = session.screenshot_path("step-001");
+        let result = session.temp_dir.join("step-001-result.png");
+        std::fs::write(&shot, vec![0u8; 300]).unwrap();
+        std::fs::write(&result, vec![0u8; 200]).unwrap();
+        std::fs::write(session.temp_dir.join("recording.log"), "not a screenshot").unwrap();
+
+        let mut step = Step::sample();
+        step.screenshot_path = Some(shot.to_string_lossy().into_owned());
+        step.result_screenshot_path = Some(result.to_string_lossy().into_owned());
+        session.add_step(step.clone());
+        // A duplicate sharing the file counts it once; a missing file counts nothing.
+        session.add_step(Step {
+            result_screenshot_path: Some("/nonexistent/step.png".into()),
+            ..step
+        });
+        assert_eq!(session.disk_usage(), 500);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
     #[test]
     fn update_step_note_sets_note() {
         let mut session = Session::new().expect("create session");
@@ -236,37 +1247,530 @@ mod tests {
     }
 
     #[test]
-    fn update_step_crop_sets_crop_region() {
+    fn set_metadata_updates_and_persists() {
         let mut session = Session::new().expect("create session");
-        session.add_step(Step::sample());
-
-        let crop = BoundsPercent {
-            x_percent: 10.0,
-            y_percent: 20.0,
-            width_percent: 60.0,
-            height_percent: 50.0,
-        };
-        let updated = session.update_step_crop("step-1", Some(crop.clone()));
-        assert!(updated.is_some());
-        assert_eq!(updated.unwrap().crop_region, Some(crop));
+        assert!(session.metadata.created_at > 0);
+        assert_eq!(session.metadata.title, "");
 
-        let updated = session.update_step_crop("step-1", None);
-        assert!(updated.is_some());
-        assert_eq!(updated.unwrap().crop_region, None);
+        let meta = session
+            .set_metadata(" Onboarding ", Some("Ana"), &["setup".into()])
+            .expect("save metadata")
+            .clone();
+        assert_eq!(meta.title, "Onboarding");
+        assert_eq!(meta.author.as_deref(), Some("Ana"));
+        assert_eq!(
+            storage::read_session_metadata(&session.temp_dir).expect("read back"),
+            meta
+        );
 
-        assert!(session.update_step_crop("missing", None).is_none());
         std::fs::remove_dir_all(&session.temp_dir).ok();
     }
 
     #[test]
-    fn write_diagnostics_creates_json() {
+    fn set_step_wait_sets_and_clears_wait() {
         let mut session = Session::new().expect("create session");
-        session.diagnostics.clicks_received = 10;
-        session.diagnostics.clicks_filtered = 3;
-        session.diagnostics.captures_fallback = 1;
-        session.diagnostics.captures_failed = 0;
-        session
-            .diagnostics
+        session.add_step(Step::sample());
+
+        let updated = session.set_step_wait("step-1", Some(3_000));
+        assert_eq!(updated.and_then(|s| s.wait_ms), Some(3_000));
+        let cleared = session.set_step_wait("step-1", Some(0));
+        assert_eq!(cleared.map(|s| s.wait_ms), Some(None));
+        assert!(session.set_step_wait("nonexistent", Some(1)).is_none());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn set_step_annotations_keeps_only_drawable_ones() {
+        use super::super::types::PointPercent;
+
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+
+        let arrow = Annotation::Arrow {
+            from: PointPercent { x: 10.0, y: 10.0 },
+            to: PointPercent { x: 40.0, y: 30.0 },
+        };
+        let blank = Annotation::Text {
+            pos: PointPercent { x: 5.0, y: 5.0 },
+            content: " ".to_string(),
+        };
+        let updated = session.set_step_annotations("step-1", vec![arrow.clone(), blank]);
+        assert_eq!(updated.map(|s| s.annotations.clone()), Some(vec![arrow]));
+        let cleared = session.set_step_annotations("step-1", Vec::new());
+        assert_eq!(cleared.map(|s| s.annotations.len()), Some(0));
+        assert!(session
+            .set_step_annotations("nonexistent", Vec::new())
+            .is_none());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn update_step_crop_sets_crop_region() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+
+        let crop = BoundsPercent {
+            x_percent: 10.0,
+            y_percent: 20.0,
+            width_percent: 60.0,
+            height_percent: 50.0,
+        };
+        let updated = session.update_step_crop("step-1", Some(crop.clone()));
+        assert!(updated.is_some());
+        assert_eq!(updated.unwrap().crop_region, Some(crop));
+
+        let updated = session.update_step_crop("step-1", None);
+        assert!(updated.is_some());
+        assert_eq!(updated.unwrap().crop_region, None);
+
+        assert!(session.update_step_crop("missing", None).is_none());
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn reset_step_crop_to_auto_restores_pipeline_crop() {
+        let mut session = Session::new().expect("create session");
+        let mut step = Step::sample();
+        step.click_x_percent = 30.0;
+        step.click_y_percent = 60.0;
+        step.capture_bounds = Some(CaptureBounds {
+            x: 0,
+            y: 25,
+            width: 2560,
+            height: 1415,
+            display_width: 2560,
+            display_height: 1440,
+        });
+        // What the pipeline stores on a new step.
+        let auto = super::super::pipeline::auto_crop_for_step(&step);
+        assert!(auto.is_some());
+        step.crop_region = auto.clone();
+        session.add_step(step);
+
+        session.update_step_crop(
+            "step-1",
+            Some(BoundsPercent {
+                x_percent: 5.0,
+                y_percent: 5.0,
+                width_percent: 20.0,
+                height_percent: 20.0,
+            }),
+        );
+        let reset = session.reset_step_crop_to_auto("step-1").unwrap();
+        assert_eq!(reset.crop_region, auto);
+
+        // Legacy steps without capture bounds can't be reset.
+        let mut legacy = Step::sample();
+        legacy.id = "step-2".into();
+        session.add_step(legacy);
+        assert!(session.reset_step_crop_to_auto("step-2").is_none());
+        assert!(session.reset_step_crop_to_auto("missing").is_none());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn update_step_title_trims_and_clears_blank_titles() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+
+        let updated = session.update_step_title("step-1", Some("  Save the draft ".into()));
+        assert_eq!(updated.unwrap().title.as_deref(), Some("Save the draft"));
+        let updated = session.update_step_title("step-1", Some("   ".into()));
+        assert_eq!(updated.unwrap().title, None);
+        assert!(session
+            .update_step_title("nonexistent", Some("x".into()))
+            .is_none());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn ai_description_replaces_auto_but_not_manual_text() {
+        let mut session = Session::new().expect("create session");
+        let mut step = Step::sample();
+        step.title = Some("Save".into());
+        step.description = Some("Click the \"Save\" button".into());
+        step.description_source = Some(DescriptionSource::Auto);
+        session.add_step(step);
+
+        session.begin_step_description("step-1");
+        let step = session
+            .apply_step_description_ai("step-1", "Save the draft".into(), false)
+            .expect("applied");
+        assert_eq!(step.description.as_deref(), Some("Save the draft"));
+        assert_eq!(step.description_source, Some(DescriptionSource::Ai));
+
+        session.set_step_description_manual("step-1", Some("My words".into()));
+        session.begin_step_description("step-1");
+        let step = session
+            .apply_step_description_ai("step-1", "Save it".into(), false)
+            .expect("status cleared");
+        assert_eq!(step.description.as_deref(), Some("My words"));
+        assert_eq!(step.description_status, Some(DescriptionStatus::Idle));
+
+        session.begin_step_description("step-1");
+        let step = session
+            .apply_step_description_ai("step-1", "Save it".into(), true)
+            .expect("applied");
+        assert_eq!(step.description.as_deref(), Some("Save it"));
+        // Titles are the user's; generation never touches them.
+        assert_eq!(step.title.as_deref(), Some("Save"));
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn ai_description_skips_steps_no_longer_generating() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+        // Not dispatched: nothing to apply.
+        assert!(session
+            .apply_step_description_ai("step-1", "Save it".into(), true)
+            .is_none());
+
+        // Edited by hand while generating: the edit wins.
+        session.begin_step_description("step-1");
+        session.set_step_description_manual("step-1", Some("My words".into()));
+        assert!(session
+            .apply_step_description_ai("step-1", "Save it".into(), true)
+            .is_none());
+        assert_eq!(session.steps[0].description.as_deref(), Some("My words"));
+
+        // Deleted while generating, then another step recorded: the answer is dropped.
+        session.begin_step_description("step-1");
+        assert!(session.delete_step("step-1"));
+        let mut next = Step::sample();
+        next.id = session.next_step_id();
+        session.add_step(next);
+        assert!(session
+            .mark_step_description_failed("step-1", "No model output.".into())
+            .is_none());
+        assert_eq!(session.steps[0].description_status, None);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn delete_auto_steps_keeps_recorded_steps() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+        let mut marker = Step::sample();
+        marker.id = "step-2".into();
+        marker.action = ActionType::Note;
+        marker.description_source = Some(DescriptionSource::Auto);
+        session.add_step(marker);
+        let mut described = Step::sample();
+        described.id = "step-3".into();
+        described.description = Some("Click the \"Save\" button".into());
+        described.description_source = Some(DescriptionSource::Auto);
+        session.add_step(described);
+
+        assert_eq!(session.delete_auto_steps(), vec!["step-2".to_string()]);
+        assert_eq!(session.steps.len(), 2);
+        assert!(session.delete_auto_steps().is_empty());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn undo_restores_a_deleted_step_and_redo_removes_it_again() {
+        let mut session = Session::new().expect("create session");
+        for id in ["step-1", "step-2", "step-3"] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            session.add_step(step);
+        }
+        let ids = |session: &Session| -> Vec<String> {
+            session.steps.iter().map(|s| s.id.clone()).collect()
+        };
+        assert!(!session.can_undo());
+
+        assert!(session.delete_step("step-2"));
+        assert_eq!(ids(&session), ["step-1", "step-3"]);
+        let revision = session.revision();
+
+        assert!(session.undo());
+        assert_eq!(ids(&session), ["step-1", "step-2", "step-3"]);
+        assert!(session.revision() > revision);
+        assert!(!session.undo());
+
+        assert!(session.redo());
+        assert_eq!(ids(&session), ["step-1", "step-3"]);
+        assert!(!session.redo());
+
+        // A new edit drops what could be redone.
+        session.undo();
+        session.update_step_note("step-1", Some("Check first".into()));
+        assert!(!session.can_redo());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn discarded_steps_bypass_undo_and_keep_redo() {
+        let mut session = Session::new().expect("create session");
+        for id in ["step-1", "step-2", "step-3"] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            session.add_step(step);
+        }
+        session.update_step_note("step-1", Some("Check first".into()));
+        session.update_step_note("step-1", Some("Check twice".into()));
+        assert!(session.undo());
+
+        let revision = session.revision();
+        let discarded = session.discard_steps(|s| s.id == "step-3");
+        assert_eq!(discarded.len(), 1);
+        assert!(session.revision() > revision);
+        assert!(session.can_redo());
+
+        assert!(session.undo());
+        assert!(!session.undo());
+        let ids: Vec<_> = session.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["step-1", "step-2"]);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn undo_after_recording_a_click_keeps_the_click() {
+        let mut session = Session::new().expect("create session");
+        let mut step = Step::sample();
+        step.id = "step-1".into();
+        session.add_step(step);
+        let mut step = Step::sample();
+        step.id = "step-2".into();
+        session.add_step(step);
+        let ids = |session: &Session| -> Vec<String> {
+            session.steps.iter().map(|s| s.id.clone()).collect()
+        };
+
+        session.update_step_title("step-1", Some("Open the menu".into()));
+        session.reorder_steps(&["step-2".into(), "step-1".into()]);
+        assert!(session.delete_step("step-1"));
+        let mut click = Step::sample();
+        click.id = session.next_step_id();
+        let click_id = click.id.clone();
+        session.add_step(click);
+
+        assert!(session.undo());
+        assert_eq!(ids(&session), ["step-2", "step-1", &click_id]);
+        assert!(session.undo());
+        assert_eq!(ids(&session), ["step-1", "step-2", &click_id]);
+        assert!(session.undo());
+        assert_eq!(session.steps[0].title, None);
+        assert!(!session.undo());
+
+        assert!(session.redo());
+        assert_eq!(session.steps[0].title.as_deref(), Some("Open the menu"));
+        assert!(session.redo());
+        assert!(session.redo());
+        assert_eq!(ids(&session), ["step-2", &click_id]);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn edits_that_change_nothing_are_not_recorded() {
+        let mut session = Session::new().expect("create session");
+        for id in ["step-1", "step-2"] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            session.add_step(step);
+        }
+        session.update_step_note("step-1", Some("Check first".into()));
+        assert!(session.undo());
+        let revision = session.revision();
+
+        // No capture bounds to recompute the crop from.
+        assert!(session.reset_step_crop_to_auto("step-1").is_none());
+        // Same order as before.
+        session.reorder_steps(&["step-1".into(), "step-2".into()]);
+        // Same value as before.
+        assert!(session.update_step_note("step-1", None).is_some());
+
+        assert_eq!(session.revision(), revision);
+        assert!(!session.can_undo());
+        assert!(session.can_redo());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn undo_history_keeps_the_most_recent_edits() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step::sample());
+        for wait in 1..=MAX_UNDO_DEPTH as u32 + 5 {
+            session.set_step_wait("step-1", Some(wait));
+        }
+        let mut undone = 0;
+        while session.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_UNDO_DEPTH);
+        assert_eq!(session.steps[0].wait_ms, Some(5));
+        // Edits of unknown steps aren't recorded.
+        session.set_step_wait("missing", Some(1));
+        assert!(session.can_redo());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn validate_screenshots_flags_steps_with_missing_files() {
+        let mut session = Session::new().expect("create session");
+        for id in ["step-1", "step-2", "step-3"] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            step.screenshot_path = Some(session.screenshot_path(id).to_string_lossy().into());
+            std::fs::write(session.screenshot_path(id), b"png").unwrap();
+            session.add_step(step);
+        }
+        std::fs::remove_file(session.screenshot_path("step-2")).unwrap();
+
+        assert_eq!(session.validate_screenshots(), vec!["step-2".to_string()]);
+        let statuses: Vec<Option<CaptureStatus>> = session
+            .steps
+            .iter()
+            .map(|s| s.capture_status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Step::sample().capture_status,
+                Some(CaptureStatus::Failed),
+                Step::sample().capture_status,
+            ]
+        );
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn dedupe_consecutive_drops_double_fired_clicks_only() {
+        let mut session = Session::new().expect("create session");
+        let click = |id: &str, ts: i64, x: i32, app: &str| {
+            let mut step = Step::sample();
+            step.id = id.into();
+            step.ts = ts;
+            step.x = x;
+            step.app = app.into();
+            step.screenshot_path = Some(session.screenshot_path(id).to_string_lossy().into());
+            std::fs::write(session.screenshot_path(id), b"png").unwrap();
+            step
+        };
+        let steps = vec![
+            click("step-1", 1_000, 100, "Finder"),
+            // Fired twice by the app: same spot, 40 ms later.
+            click("step-2", 1_040, 101, "Finder"),
+            // And a third time, still close to the kept step-1.
+            click("step-3", 1_090, 99, "Finder"),
+            // The user clicking the same button again a second later.
+            click("step-4", 2_100, 100, "Finder"),
+            // Same time and place, but another app.
+            click("step-5", 2_120, 100, "Safari"),
+            // Same app, but a different spot.
+            click("step-6", 2_150, 300, "Safari"),
+        ];
+        for step in steps {
+            session.add_step(step);
+        }
+
+        let removed = session.dedupe_consecutive(3, 250);
+
+        assert_eq!(removed, vec!["step-2".to_string(), "step-3".to_string()]);
+        let ids: Vec<&str> = session.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["step-1", "step-4", "step-5", "step-6"]);
+        assert!(!session.screenshot_path("step-2").exists());
+        assert!(!session.screenshot_path("step-3").exists());
+        assert!(session.screenshot_path("step-1").exists());
+        assert!(session.dedupe_consecutive(3, 250).is_empty());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn dedupe_consecutive_keeps_different_actions_and_notes() {
+        let mut session = Session::new().expect("create session");
+        let mut first = Step::sample();
+        first.id = "step-1".into();
+        let mut right_click = first.clone();
+        right_click.id = "step-2".into();
+        right_click.action = ActionType::RightClick;
+        let mut note = first.clone();
+        note.id = "step-3".into();
+        note.action = ActionType::Note;
+        let mut note_again = note.clone();
+        note_again.id = "step-4".into();
+        for step in [first, right_click, note, note_again] {
+            session.add_step(step);
+        }
+
+        assert!(session.dedupe_consecutive(10, 1_000).is_empty());
+        assert_eq!(session.steps.len(), 4);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn dedupe_consecutive_handles_clicks_at_opposite_screen_edges() {
+        let mut session = Session::new().expect("create session");
+        for (id, x) in [("step-1", -1_500_000_000), ("step-2", 1_500_000_000)] {
+            let mut step = Step::sample();
+            step.id = id.into();
+            step.x = x;
+            session.add_step(step);
+        }
+
+        assert!(session.dedupe_consecutive(4, 300).is_empty());
+        assert_eq!(session.steps.len(), 2);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn storage_cap_is_reported_once_and_counts_retakes_once() {
+        let mut session = Session::new().expect("create session");
+        session.storage_policy = StoragePolicy {
+            max_screenshot_edge_px: 64,
+            max_session_bytes: 1,
+        };
+        let path = session.screenshot_path("step-001");
+        image::RgbaImage::new(200, 100).save(&path).unwrap();
+        let mut step = Step::sample();
+        step.id = "step-001".into();
+        step.screenshot_path = Some(path.to_string_lossy().into());
+
+        assert!(session.store_step_screenshots(&step));
+        assert_eq!(image::image_dimensions(&path).unwrap(), (64, 32));
+        let stored = session.bytes_stored();
+        assert_eq!(stored, std::fs::metadata(&path).unwrap().len());
+
+        // A retake of the same file replaces its size instead of adding to it.
+        assert!(!session.store_step_screenshots(&step));
+        assert_eq!(session.bytes_stored(), stored);
+
+        let info = session.storage_info();
+        assert_eq!(info.file_count, 1);
+        assert_eq!(info.total_bytes, stored);
+        assert_eq!(info.max_bytes, 1);
+        assert_eq!(info.dir, session.temp_dir.to_string_lossy());
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn write_diagnostics_creates_json() {
+        let mut session = Session::new().expect("create session");
+        session.diagnostics.clicks_received = 10;
+        session.diagnostics.clicks_filtered = 3;
+        session.diagnostics.captures_fallback = 1;
+        session.diagnostics.captures_failed = 0;
+        session
+            .diagnostics
             .failure_reasons
             .push("window capture produced empty file".into());
 
@@ -288,4 +1792,433 @@ mod tests {
         // Cleanup
         std::fs::remove_dir_all(&session.temp_dir).ok();
     }
+
+    #[test]
+    fn list_sessions_reports_manifest_step_count_and_size() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let mut session = Session::new_in(root.path()).expect("create session");
+        session.add_step(Step::sample());
+        session.add_step(Step::sample());
+        std::fs::write(session.temp_dir.join("step-001.png"), [0u8; 100]).expect("write");
+
+        let older = root.path().join("older");
+        std::fs::create_dir(&older).expect("create dir");
+        storage::write_session_manifest(
+            &older,
+            &storage::SessionManifest {
+                created_at: 1,
+                step_count: 5,
+                last_step_number: 5,
+            },
+        )
+        .expect("write manifest");
+        std::fs::create_dir(root.path().join("no-manifest")).expect("create dir");
+
+        let sessions = Session::list_sessions_in(root.path());
+        let summary: Vec<_> = sessions
+            .iter()
+            .map(|s| (s.id.as_str(), s.step_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(session.id().as_str(), 2), ("older", 5), ("no-manifest", 0)]
+        );
+        let manifest_bytes = std::fs::metadata(session.temp_dir.join("manifest.json"))
+            .expect("manifest written")
+            .len();
+        assert_eq!(sessions[0].total_bytes, 100 + manifest_bytes);
+        assert_eq!(sessions[0].created_at, session.metadata.created_at);
+        assert_eq!(sessions[2].total_bytes, 0);
+    }
+
+    #[test]
+    fn diagnostics_snapshot_caps_failure_reasons() {
+        let mut diagnostics = SessionDiagnostics {
+            clicks_received: 40,
+            captures_fallback: 25,
+            ..Default::default()
+        };
+        let snapshot = diagnostics.snapshot();
+        assert_eq!(snapshot.clicks_received, 40);
+        assert!(snapshot.failure_reasons.is_empty());
+        assert_eq!(snapshot.failure_reasons_omitted, 0);
+
+        diagnostics.failure_reasons = (0..25).map(|i| format!("reason {i}")).collect();
+        let snapshot = diagnostics.snapshot();
+        assert_eq!(snapshot.captures_fallback, 25);
+        assert_eq!(snapshot.failure_reasons.len(), MAX_SNAPSHOT_FAILURE_REASONS);
+        assert_eq!(snapshot.failure_reasons_omitted, 5);
+        assert_eq!(snapshot.failure_reasons[0], "reason 5");
+        assert_eq!(snapshot.failure_reasons.last().unwrap(), "reason 24");
+    }
+
+    fn step(app: &str, action: ActionType, ts: i64, status: Option<CaptureStatus>) -> Step {
+        Step {
+            app: app.to_string(),
+            action,
+            ts,
+            capture_status: status,
+            ..Step::sample()
+        }
+    }
+
+    #[test]
+    fn step_counts_by_app_sorts_by_count_then_name() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(step("Safari", ActionType::Click, 0, None));
+        session.add_step(step("Finder", ActionType::Click, 0, None));
+        session.add_step(step("Mail", ActionType::Click, 0, None));
+        session.add_step(step("Safari", ActionType::Drag, 0, None));
+        session.add_step(step("Mail", ActionType::Note, 0, None));
+        session.add_step(step(" ", ActionType::Click, 0, None));
+
+        let counts: Vec<(String, usize)> = session
+            .step_counts_by_app()
+            .into_iter()
+            .map(|c| (c.app, c.steps))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Safari".to_string(), 2),
+                ("Finder".to_string(), 1),
+                ("Mail".to_string(), 1),
+            ]
+        );
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn stats_derive_rates_intervals_and_action_counts() {
+        let mut session = Session::new().expect("create session");
+        let empty = session.stats();
+        assert_eq!(empty.step_count, 0);
+        assert_eq!(empty.capture_success_rate, None);
+        assert_eq!(empty.avg_step_interval_ms, None);
+
+        session.diagnostics.clicks_received = 6;
+        session.add_step(step(
+            "Finder",
+            ActionType::Click,
+            1_000,
+            Some(CaptureStatus::Ok),
+        ));
+        session.add_step(step("Finder", ActionType::Note, 1_500, None));
+        session.add_step(step(
+            "Finder",
+            ActionType::DoubleClick,
+            3_000,
+            Some(CaptureStatus::Fallback),
+        ));
+        session.add_step(step(
+            "Safari",
+            ActionType::Click,
+            7_000,
+            Some(CaptureStatus::Failed),
+        ));
+        session.add_step(step(
+            "Safari",
+            ActionType::Click,
+            9_000,
+            Some(CaptureStatus::Ok),
+        ));
+
+        let stats = session.stats();
+        assert_eq!(stats.diagnostics.clicks_received, 6);
+        assert_eq!(stats.step_count, 5);
+        assert_eq!(stats.capture_success_rate, Some(0.75));
+        assert_eq!(stats.avg_step_interval_ms, Some(2_666));
+        assert_eq!(stats.steps_by_action.get("Click"), Some(&3));
+        assert_eq!(stats.steps_by_action.get("DoubleClick"), Some(&1));
+        assert_eq!(stats.steps_by_action.get("Note"), Some(&1));
+        assert_eq!(stats.steps_by_app[0].app, "Finder");
+
+        let json = serde_json::to_value(&stats).expect("serialize stats");
+        assert_eq!(json["clicks_received"], 6);
+        assert_eq!(json["steps_by_app"][1]["app"], "Safari");
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn stats_summarize_capture_timings_per_phase() {
+        let mut session = Session::new().expect("create session");
+        assert_eq!(
+            session.stats().capture_timings,
+            CaptureTimingStats::default()
+        );
+
+        for (ax, capture, frame_age) in [(12, 40, Some(80)), (3, 25, None), (30, 900, Some(20))] {
+            session.add_step(Step {
+                capture_timings: Some(CaptureTimings {
+                    ax_lookup_ms: Some(ax),
+                    window_resolve_ms: None,
+                    capture_ms: Some(capture),
+                    pre_click_frame_age_ms: frame_age,
+                }),
+                ..Step::sample()
+            });
+        }
+        // Steps recorded before timings existed are left out.
+        session.add_step(Step::sample());
+
+        let timings = session.stats().capture_timings;
+        assert_eq!(
+            timings.ax_lookup_ms,
+            Some(TimingSummary {
+                min: 3,
+                median: 12,
+                max: 30
+            })
+        );
+        assert_eq!(
+            timings.capture_ms,
+            Some(TimingSummary {
+                min: 25,
+                median: 40,
+                max: 900
+            })
+        );
+        assert_eq!(
+            timings.pre_click_frame_age_ms,
+            Some(TimingSummary {
+                min: 20,
+                median: 20,
+                max: 80
+            })
+        );
+        assert_eq!(timings.window_resolve_ms, None);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn renumber_screenshots_keeps_files_of_steps_held_for_undo() {
+        let mut session = Session::new().expect("create session");
+        for i in 1..=3 {
+            let id = format!("step-{i:03}");
+            let path = session.screenshot_path(&id);
+            std::fs::write(&path, format!("shot {i}")).expect("write screenshot");
+            session.add_step(Step {
+                id,
+                screenshot_path: Some(path.to_string_lossy().to_string()),
+                ..Step::sample()
+            });
+        }
+        // step-001.png now belongs to a step only undo can bring back.
+        assert!(session.delete_step("step-001"));
+
+        assert_eq!(session.renumber_screenshots().expect("renumber"), 2);
+        assert!(session.undo());
+
+        for (index, expected) in ["shot 1", "shot 2", "shot 3"].iter().enumerate() {
+            let path = session.steps[index].screenshot_path.as_deref().unwrap();
+            assert_eq!(std::fs::read_to_string(path).unwrap(), *expected);
+        }
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn renumber_screenshots_follows_reversed_order_without_overwriting() {
+        let mut session = Session::new().expect("create session");
+        for i in 1..=3 {
+            let id = format!("step-{i:03}");
+            let path = session.screenshot_path(&id);
+            std::fs::write(&path, format!("shot {i}")).expect("write screenshot");
+            session.add_step(Step {
+                id,
+                screenshot_path: Some(path.to_string_lossy().to_string()),
+                ..Step::sample()
+            });
+        }
+        session.reorder_steps(&[
+            "step-003".to_string(),
+            "step-002".to_string(),
+            "step-001".to_string(),
+        ]);
+
+        // step-002 already sits at position 2.
+        assert_eq!(session.renumber_screenshots().expect("renumber"), 2);
+
+        for (index, expected) in ["shot 3", "shot 2", "shot 1"].iter().enumerate() {
+            let step = &session.get_steps()[index];
+            let path = step.screenshot_path.as_deref().unwrap();
+            assert_eq!(
+                PathBuf::from(path),
+                session.temp_dir.join(format!("step-{:03}.png", index + 1))
+            );
+            assert_eq!(std::fs::read_to_string(path).unwrap(), *expected);
+        }
+        let leftovers = std::fs::read_dir(&session.temp_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".renumber-"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn renumber_screenshots_skips_missing_and_external_files() {
+        let mut session = Session::new().expect("create session");
+        session.add_step(Step {
+            id: "step-001".to_string(),
+            screenshot_path: Some("/elsewhere/step-009.png".to_string()),
+            ..Step::sample()
+        });
+        session.add_step(Step {
+            id: "step-002".to_string(),
+            screenshot_path: Some(
+                session
+                    .screenshot_path("step-007")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            ..Step::sample()
+        });
+
+        assert_eq!(session.renumber_screenshots().expect("renumber"), 0);
+        assert_eq!(
+            session.get_steps()[0].screenshot_path.as_deref(),
+            Some("/elsewhere/step-009.png")
+        );
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    fn step_with_id(id: &str) -> Step {
+        Step {
+            id: id.to_string(),
+            ..Step::sample()
+        }
+    }
+
+    fn ids(session: &Session) -> Vec<&str> {
+        session.get_steps().iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn reorder_keeps_steps_missing_from_the_list() {
+        let mut session = Session::new().expect("create session");
+        for id in ["a", "b", "c", "d"] {
+            session.add_step(step_with_id(id));
+        }
+
+        // The caller only knew about a and c; b and d keep their relative order.
+        session.reorder_steps(&["c".to_string(), "a".to_string(), "x".to_string()]);
+        assert_eq!(ids(&session), ["c", "a", "b", "d"]);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn mutations_bump_the_revision_and_stale_writes_are_detected() {
+        let mut session = Session::new().expect("create session");
+        assert_eq!(session.revision(), 0);
+
+        session.add_step(step_with_id("a"));
+        session.add_step(step_with_id("b"));
+        let seen = session.revision();
+        assert_eq!(seen, 2);
+        assert_eq!(session.check_revision(Some(seen)), Ok(()));
+        assert_eq!(session.check_revision(None), Ok(()));
+
+        session.update_step_note("a", Some("hi".into()));
+        assert_eq!(
+            session.check_revision(Some(seen)),
+            Err(RevisionConflict {
+                expected: 2,
+                current: 3
+            })
+        );
+
+        // Misses leave the revision alone.
+        assert!(session.update_step_note("missing", None).is_none());
+        assert!(!session.delete_step("missing"));
+        assert_eq!(session.revision(), 3);
+
+        session.reorder_steps(&["b".to_string(), "a".to_string()]);
+        assert!(session.delete_step("a"));
+        assert_eq!(session.revision(), 5);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn concurrent_add_and_reorder_never_drops_steps() {
+        use std::sync::{Arc, Mutex};
+
+        let session = Arc::new(Mutex::new(Session::new().expect("create session")));
+        let recorder = {
+            let session = Arc::clone(&session);
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    session
+                        .lock()
+                        .unwrap()
+                        .add_step(step_with_id(&format!("step-{i:03}")));
+                }
+            })
+        };
+        // The editor reorders from a snapshot that steps may have been added after.
+        for _ in 0..200 {
+            let mut snapshot: Vec<String> = ids(&session.lock().unwrap())
+                .into_iter()
+                .map(String::from)
+                .collect();
+            snapshot.reverse();
+            session.lock().unwrap().reorder_steps(&snapshot);
+        }
+        recorder.join().unwrap();
+
+        let session = session.lock().unwrap();
+        let mut found = ids(&session);
+        found.sort_unstable();
+        let expected: Vec<String> = (0..200).map(|i| format!("step-{i:03}")).collect();
+        assert_eq!(found, expected);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
+
+    #[test]
+    fn concurrent_add_and_delete_removes_only_the_deleted_steps() {
+        use std::sync::{Arc, Mutex};
+
+        let session = Arc::new(Mutex::new(Session::new().expect("create session")));
+        let recorder = {
+            let session = Arc::clone(&session);
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    session
+                        .lock()
+                        .unwrap()
+                        .add_step(step_with_id(&format!("step-{i:03}")));
+                }
+            })
+        };
+        // Delete every even step once it shows up.
+        let mut deleted = 0;
+        while deleted < 100 {
+            let target = format!("step-{:03}", deleted * 2);
+            if session.lock().unwrap().delete_step(&target) {
+                deleted += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+        recorder.join().unwrap();
+
+        let session = session.lock().unwrap();
+        let expected: Vec<String> = (0..200)
+            .filter(|i| i % 2 == 1)
+            .map(|i| format!("step-{i:03}"))
+            .collect();
+        assert_eq!(ids(&session), expected);
+        assert_eq!(session.revision(), 300);
+
+        std::fs::remove_dir_all(&session.temp_dir).ok();
+    }
 }
@@ -0,0 +1,229 @@
+//! Finished sessions kept next to the one in the editor, so starting a new recording
+//! doesn't lose the previous guide.
+//!
+//! A session is registered when it leaves the editor: its steps and metadata are
+//! written into its directory, which then survives app restarts. Directories without
+//! a `steps.json` were never registered and are orphans.
+
+use super::session::Session;
+use super::storage::{self, SessionDirUsage, StorageError};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A registered session, as listed in the recording history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompletedSession {
+    pub id: String,
+    pub title: String,
+    /// Milliseconds since the Unix epoch; 0 when unknown.
+    pub created_at: i64,
+    pub step_count: usize,
+    pub dir: PathBuf,
+}
+
+impl CompletedSession {
+    fn from_session(session: &Session) -> Self {
+        Self {
+            id: session.id(),
+            title: session.metadata.title.clone(),
+            created_at: session.metadata.created_at,
+            step_count: session.steps.len(),
+            dir: session.temp_dir.clone(),
+        }
+    }
+}
+
+/// Registered sessions, newest first.
+#[derive(Debug, Default)]
+pub struct SessionRegistry {
+    sessions: Vec<CompletedSession>,
+}
+
+impl SessionRegistry {
+    /// Register the persisted sessions found under `root`. Returns the registry and
+    /// the session directories that were never persisted.
+    pub fn restore(root: &Path) -> (Self, Vec<PathBuf>) {
+        let mut registry = Self::default();
+        let mut orphans = Vec::new();
+        let Ok(entries) = std::fs::read_dir(root) else {
+            return (registry, orphans);
+        };
+        for dir in entries.flatten().map(|entry| entry.path()) {
+            if !dir.is_dir() {
+                continue;
+            }
+            match Session::open(&dir) {
+                Ok(session) => registry.insert(CompletedSession::from_session(&session)),
+                Err(_) => orphans.push(dir),
+            }
+        }
+        (registry, orphans)
+    }
+
+    pub fn list(&self) -> &[CompletedSession] {
+        &self.sessions
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CompletedSession> {
+        self.sessions.iter().find(|s| s.id == id)
+    }
+
+    /// Persist `session` and add it, replacing an older entry for the same session.
+    pub fn register(&mut self, session: &Session) -> Result<CompletedSession, StorageError> {
+        session.persist()?;
+        let entry = CompletedSession::from_session(session);
+        self.insert(entry.clone());
+        Ok(entry)
+    }
+
+    /// Take `id` out of the registry; its directory stays on disk.
+    pub fn remove(&mut self, id: &str) -> Option<CompletedSession> {
+        let index = self.sessions.iter().position(|s| s.id == id)?;
+        Some(self.sessions.remove(index))
+    }
+
+    /// Reopen a registered session for editing and take it out of the registry.
+    pub fn take_session(&mut self, id: &str) -> Option<Result<Session, StorageError>> {
+        let entry = self.get(id)?;
+        let opened = Session::open(&entry.dir);
+        if opened.is_ok() {
+            self.remove(id);
+        }
+        Some(opened)
+    }
+
+    /// Unregister `id` and delete its directory. `Ok(None)` when `id` isn't registered.
+    pub fn delete(&mut self, id: &str) -> Result<Option<CompletedSession>, StorageError> {
+        let Some(entry) = self.get(id).cloned() else {
+            return Ok(None);
+        };
+        if entry.dir.exists() {
+            std::fs::remove_dir_all(&entry.dir)?;
+        }
+        self.remove(id);
+        Ok(Some(entry))
+    }
+
+    /// Delete sessions last changed more than `max_age` ago, then the least recently
+    /// changed ones beyond `max_total_bytes`. Returns the deleted sessions.
+    pub fn prune(
+        &mut self,
+        now: SystemTime,
+        max_age: Option<Duration>,
+        max_total_bytes: Option<u64>,
+    ) -> Vec<CompletedSession> {
+        let usage = self
+            .sessions
+            .iter()
+            .filter_map(|s| SessionDirUsage::of(&s.dir).ok())
+            .collect();
+        let ids: Vec<String> = storage::sessions_to_prune(usage, now, max_age, max_total_bytes)
+            .iter()
+            .filter_map(|dir| self.sessions.iter().find(|s| &s.dir == dir))
+            .map(|s| s.id.clone())
+            .collect();
+        ids.iter()
+            .filter_map(|id| self.delete(id).ok().flatten())
+            .collect()
+    }
+
+    fn insert(&mut self, entry: CompletedSession) {
+        self.sessions.retain(|s| s.id != entry.id);
+        let index = self
+            .sessions
+            .iter()
+            .position(|s| s.created_at < entry.created_at)
+            .unwrap_or(self.sessions.len());
+        self.sessions.insert(index, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::types::Step;
+
+    fn recorded_session(root: &Path, title: &str, created_at: i64) -> Session {
+        let mut session = Session::new_in(root).expect("create session");
+        session.metadata.title = title.to_string();
+        session.metadata.created_at = created_at;
+        session.add_step(Step::sample());
+        session
+    }
+
+    #[test]
+    fn registered_sessions_survive_a_restart_and_orphans_are_reported() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let older = recorded_session(root.path(), "Older", 1_000);
+        let newer = recorded_session(root.path(), "Newer", 2_000);
+        let orphan = Session::new_in(root.path()).expect("create session");
+
+        let mut registry = SessionRegistry::default();
+        registry.register(&older).expect("register older");
+        let entry = registry.register(&newer).expect("register newer");
+        assert_eq!(entry.step_count, 1);
+
+        let (restored, orphans) = SessionRegistry::restore(root.path());
+        let titles: Vec<&str> = restored.list().iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Newer", "Older"]);
+        assert_eq!(restored.list(), registry.list());
+        assert_eq!(orphans, vec![orphan.temp_dir.clone()]);
+    }
+
+    #[test]
+    fn taking_a_session_reopens_its_steps() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let session = recorded_session(root.path(), "Guide", 1_000);
+        let mut registry = SessionRegistry::default();
+        registry.register(&session).expect("register");
+
+        let reopened = registry
+            .take_session(&session.id())
+            .expect("registered")
+            .expect("open");
+        assert_eq!(reopened.steps.len(), 1);
+        assert_eq!(reopened.steps[0].id, session.steps[0].id);
+        assert_eq!(reopened.metadata.title, "Guide");
+        assert!(registry.list().is_empty());
+        assert!(registry.take_session("missing").is_none());
+
+        // Registering it again replaces instead of duplicating.
+        registry.register(&reopened).expect("register again");
+        registry.register(&reopened).expect("register again");
+        assert_eq!(registry.list().len(), 1);
+    }
+
+    #[test]
+    fn pruning_deletes_sessions_over_the_budget() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let session = recorded_session(root.path(), "Guide", 1_000);
+        let mut registry = SessionRegistry::default();
+        registry.register(&session).expect("register");
+
+        assert!(registry
+            .prune(SystemTime::now(), Some(Duration::from_secs(3600)), None)
+            .is_empty());
+        let pruned = registry.prune(SystemTime::now(), None, Some(1));
+        assert_eq!(pruned.len(), 1);
+        assert!(!session.temp_dir.exists());
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn deleting_removes_the_directory() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let session = recorded_session(root.path(), "Guide", 1_000);
+        let mut registry = SessionRegistry::default();
+        registry.register(&session).expect("register");
+
+        let deleted = registry.delete(&session.id()).expect("delete");
+        assert_eq!(deleted.map(|s| s.title), Some("Guide".to_string()));
+        assert!(!session.temp_dir.exists());
+        assert!(registry.list().is_empty());
+        assert!(registry
+            .delete(&session.id())
+            .expect("delete again")
+            .is_none());
+    }
+}
@@ -0,0 +1,88 @@
+//! Active Space (virtual desktop) switches via `NSWorkspace` notifications.
+//!
+//! Moving to another Space (Ctrl+→, Mission Control, clicking an app that lives on
+//! another desktop) makes the next screenshots show a different desktop with no
+//! explanation. The observer only raises a flag; the processing loop records it in
+//! the pipeline state and annotates the next step.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Watches for Space switches while a recording runs. Created by `start_recording`;
+/// dropping it (stop, discard) unregisters the observer.
+pub struct SpaceObserver {
+    changed: Arc<AtomicBool>,
+    #[cfg(target_os = "macos")]
+    token: Option<
+        objc2::rc::Retained<objc2::runtime::ProtocolObject<dyn objc2::runtime::NSObjectProtocol>>,
+    >,
+}
+
+// SAFETY: the token is only used to unregister, and NSNotificationCenter may be
+// called from any thread.
+#[cfg(target_os = "macos")]
+unsafe impl Send for SpaceObserver {}
+
+impl SpaceObserver {
+    /// Start observing. Notifications arrive on the main thread's run loop, whatever
+    /// thread this is called from.
+    #[cfg(target_os = "macos")]
+    pub fn start() -> Self {
+        use block2::RcBlock;
+        use objc2_app_kit::{NSWorkspace, NSWorkspaceActiveSpaceDidChangeNotification};
+        use objc2_foundation::NSNotification;
+        use std::ptr::NonNull;
+
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&changed);
+        let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            flag.store(true, Ordering::SeqCst);
+        });
+        let center = NSWorkspace::sharedWorkspace().notificationCenter();
+        // SAFETY: the block only captures an atomic flag; the notification name is a
+        // constant exported by AppKit.
+        let token = unsafe {
+            center.addObserverForName_object_queue_usingBlock(
+                Some(NSWorkspaceActiveSpaceDidChangeNotification),
+                None,
+                None,
+                &block,
+            )
+        };
+        Self {
+            changed,
+            token: Some(token),
+        }
+    }
+
+    /// Spaces are a macOS concept; elsewhere no switch is ever reported.
+    #[cfg(not(target_os = "macos"))]
+    pub fn start() -> Self {
+        Self {
+            changed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// True when the active Space changed since the last call.
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Drop for SpaceObserver {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        if let Some(token) = self.token.take() {
+            use objc2::runtime::AnyObject;
+            use objc2_app_kit::NSWorkspace;
+
+            let observer: &AnyObject = (*token).as_ref();
+            // SAFETY: `token` was returned by addObserverForName on this same center.
+            unsafe {
+                NSWorkspace::sharedWorkspace()
+                    .notificationCenter()
+                    .removeObserver(observer)
+            };
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionState {
     Idle,
@@ -24,12 +26,22 @@ pub enum RecorderStateError {
 
 pub struct RecorderState {
     state: SessionState,
+    /// Paused by a screen lock or sleep rather than the user; only these pauses
+    /// prompt to resume once the user is back.
+    paused_by_interruption: bool,
+    /// Time spent in `Recording` before the current stretch.
+    recorded: Duration,
+    /// Start of the current stretch in `Recording`.
+    recording_since: Option<Instant>,
 }
 
 impl RecorderState {
     pub fn new() -> Self {
         Self {
             state: SessionState::Idle,
+            paused_by_interruption: false,
+            recorded: Duration::ZERO,
+            recording_since: None,
         }
     }
 
@@ -37,6 +49,14 @@ impl RecorderState {
         self.state
     }
 
+    /// How long the session has been recording, not counting pauses.
+    pub fn recorded_time(&self) -> Duration {
+        self.recorded
+            + self
+                .recording_since
+                .map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
     fn transition(
         &mut self,
         allowed: &[SessionState],
@@ -44,7 +64,17 @@ impl RecorderState {
         action: RecorderAction,
     ) -> Result<(), RecorderStateError> {
         if allowed.contains(&self.state) {
+            if let Some(since) = self.recording_since.take() {
+                self.recorded += since.elapsed();
+            }
+            if action == RecorderAction::Start {
+                self.recorded = Duration::ZERO;
+            }
+            if to == SessionState::Recording {
+                self.recording_since = Some(Instant::now());
+            }
             self.state = to;
+            self.paused_by_interruption = false;
             Ok(())
         } else {
             Err(RecorderStateError::InvalidTransition {
@@ -85,6 +115,32 @@ impl RecorderState {
             RecorderAction::Stop,
         )
     }
+
+    /// Screen locked or the Mac went to sleep: pause if recording. Returns true when
+    /// the state changed.
+    pub fn on_interrupted(&mut self) -> bool {
+        if self.pause().is_ok() {
+            self.paused_by_interruption = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A required permission was revoked: pause if recording. Unlike a lock pause this
+    /// never prompts to resume; `resume_recording` re-checks permissions first.
+    /// Returns true when the state changed.
+    pub fn on_permission_lost(&mut self) -> bool {
+        self.pause().is_ok()
+    }
+
+    /// Screen unlocked or the Mac woke up. The session stays paused; returns true when
+    /// `on_interrupted` paused it, so the user can be asked whether to resume.
+    pub fn on_interruption_ended(&mut self) -> bool {
+        let interrupted = self.paused_by_interruption && self.state == SessionState::Paused;
+        self.paused_by_interruption = false;
+        interrupted
+    }
 }
 
 #[cfg(test)]
@@ -105,4 +161,81 @@ mod tests {
         let mut state = RecorderState::new();
         assert!(state.pause().is_err());
     }
+
+    #[test]
+    fn screen_lock_pauses_and_unlock_reports_the_interruption_once() {
+        let mut state = RecorderState::new();
+        state.start().unwrap();
+        assert!(state.on_interrupted());
+        assert_eq!(state.current_state(), SessionState::Paused);
+        assert!(state.on_interruption_ended());
+        assert_eq!(state.current_state(), SessionState::Paused);
+        assert!(!state.on_interruption_ended());
+    }
+
+    #[test]
+    fn sleep_during_a_lock_keeps_the_interruption() {
+        let mut state = RecorderState::new();
+        state.start().unwrap();
+        assert!(state.on_interrupted());
+        assert!(!state.on_interrupted());
+        assert!(state.on_interruption_ended());
+    }
+
+    #[test]
+    fn screen_unlock_keeps_manual_pause() {
+        let mut state = RecorderState::new();
+        state.start().unwrap();
+        state.pause().unwrap();
+        assert!(!state.on_interrupted());
+        assert!(!state.on_interruption_ended());
+        assert_eq!(state.current_state(), SessionState::Paused);
+    }
+
+    #[test]
+    fn screen_lock_ignored_when_not_recording() {
+        let mut state = RecorderState::new();
+        assert!(!state.on_interrupted());
+        assert!(!state.on_interruption_ended());
+        assert_eq!(state.current_state(), SessionState::Idle);
+    }
+
+    #[test]
+    fn permission_loss_pauses_only_while_recording() {
+        let mut state = RecorderState::new();
+        assert!(!state.on_permission_lost());
+        state.start().unwrap();
+        assert!(state.on_interrupted());
+        assert!(!state.on_permission_lost());
+        state.on_interruption_ended();
+        state.resume().unwrap();
+        assert!(state.on_permission_lost());
+        assert_eq!(state.current_state(), SessionState::Paused);
+    }
+
+    #[test]
+    fn recorded_time_stands_still_while_paused() {
+        let mut state = RecorderState::new();
+        assert_eq!(state.recorded_time(), Duration::ZERO);
+        state.start().unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        state.pause().unwrap();
+        let paused_at = state.recorded_time();
+        assert!(paused_at >= Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(state.recorded_time(), paused_at);
+        state.stop().unwrap();
+        state.start().unwrap();
+        assert!(state.recorded_time() < paused_at);
+    }
+
+    #[test]
+    fn stop_while_locked_does_not_resume_on_unlock() {
+        let mut state = RecorderState::new();
+        state.start().unwrap();
+        assert!(state.on_interrupted());
+        state.stop().unwrap();
+        assert!(!state.on_interruption_ended());
+        assert_eq!(state.current_state(), SessionState::Stopped);
+    }
 }
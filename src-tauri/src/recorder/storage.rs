@@ -1,10 +1,53 @@
-use super::types::Step;
-use std::{fmt, io, path::Path};
+use super::types::{SessionMetadata, Step};
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fmt, io};
+
+/// Longest screenshot edge kept by default: full detail up to 4K, and still enough
+/// for crops of 5K/6K captures.
+pub const DEFAULT_MAX_SCREENSHOT_EDGE_PX: u32 = 3840;
+/// Session size at which recording pauses by default.
+pub const DEFAULT_MAX_SESSION_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Finished sessions older than this are deleted at startup by default.
+pub const DEFAULT_SESSION_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Total size of finished sessions kept by default; the least recently used go first.
+pub const DEFAULT_SESSION_CACHE_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Limits on what a recording session keeps on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoragePolicy {
+    /// Screenshots with a longer edge are downscaled to it when stored.
+    pub max_screenshot_edge_px: u32,
+    /// Recording pauses once the session's screenshots take more than this.
+    pub max_session_bytes: u64,
+}
+
+impl Default for StoragePolicy {
+    fn default() -> Self {
+        Self {
+            max_screenshot_edge_px: DEFAULT_MAX_SCREENSHOT_EDGE_PX,
+            max_session_bytes: DEFAULT_MAX_SESSION_BYTES,
+        }
+    }
+}
+
+/// Disk usage of a session directory, for the panel.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StorageInfo {
+    pub dir: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
 
 #[derive(Debug)]
 pub enum StorageError {
     Io(io::Error),
     Json(serde_json::Error),
+    Image(image::ImageError),
 }
 
 impl fmt::Display for StorageError {
@@ -12,6 +55,7 @@ impl fmt::Display for StorageError {
         match self {
             StorageError::Io(error) => write!(formatter, "io error: {error}"),
             StorageError::Json(error) => write!(formatter, "json error: {error}"),
+            StorageError::Image(error) => write!(formatter, "image error: {error}"),
         }
     }
 }
@@ -30,14 +74,161 @@ impl From<serde_json::Error> for StorageError {
     }
 }
 
-#[allow(dead_code)]
+impl From<image::ImageError> for StorageError {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+/// Downscale the screenshot at `path` in place when its longer edge exceeds
+/// `max_edge_px`, keeping the aspect ratio. Crops and click markers are stored in
+/// percent, so they stay valid. Returns the file size afterwards.
+pub fn fit_screenshot(path: &Path, max_edge_px: u32) -> Result<u64, StorageError> {
+    let (width, height) = image::image_dimensions(path)?;
+    if width.max(height) > max_edge_px.max(1) {
+        let img = image::open(path)?;
+        img.resize(max_edge_px, max_edge_px, FilterType::CatmullRom)
+            .save(path)?;
+    }
+    Ok(std::fs::metadata(path)?.len())
+}
+
+/// A session directory as seen by the startup cleanup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionDirUsage {
+    pub dir: PathBuf,
+    /// Last change to the directory; recently edited sessions are kept longest.
+    pub modified: SystemTime,
+    pub bytes: u64,
+}
+
+impl SessionDirUsage {
+    pub fn of(dir: &Path) -> io::Result<Self> {
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            modified: std::fs::metadata(dir)?.modified()?,
+            bytes: dir_usage(dir).1,
+        })
+    }
+}
+
+/// Session directories to delete: everything last modified more than `max_age` before
+/// `now`, then the least recently modified until the rest fits in `max_total_bytes`.
+/// `None` disables a limit.
+pub fn sessions_to_prune(
+    mut sessions: Vec<SessionDirUsage>,
+    now: SystemTime,
+    max_age: Option<Duration>,
+    max_total_bytes: Option<u64>,
+) -> Vec<PathBuf> {
+    // Newest first, so the budget is spent on the most recently used sessions.
+    sessions.sort_by(|a, b| b.modified.cmp(&a.modified));
+    let mut kept_bytes = 0u64;
+    sessions
+        .into_iter()
+        .filter(|session| {
+            let too_old = max_age.is_some_and(|max| {
+                now.duration_since(session.modified)
+                    .is_ok_and(|age| age > max)
+            });
+            let over_budget =
+                !too_old && max_total_bytes.is_some_and(|max| kept_bytes + session.bytes > max);
+            if !too_old && !over_budget {
+                kept_bytes += session.bytes;
+            }
+            too_old || over_budget
+        })
+        .map(|session| session.dir)
+        .collect()
+}
+
+/// Number of files in `dir` and their total size. Subdirectories are included.
+pub fn dir_usage(dir: &Path) -> (usize, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    entries
+        .flatten()
+        .fold((0, 0), |(count, bytes), entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => {
+                let (sub_count, sub_bytes) = dir_usage(&entry.path());
+                (count + sub_count, bytes + sub_bytes)
+            }
+            Ok(meta) => (count + 1, bytes + meta.len()),
+            Err(_) => (count, bytes),
+        })
+}
+
+/// File in a session directory holding its steps.
+pub const STEPS_FILE: &str = "steps.json";
+
 pub fn write_steps(dir: &Path, steps: &[Step]) -> Result<(), StorageError> {
     let json = serde_json::to_string_pretty(steps)?;
-    let path = dir.join("steps.json");
+    let path = dir.join(STEPS_FILE);
     std::fs::write(path, json)?;
     Ok(())
 }
 
+/// File next to `steps.json` holding the session's [`SessionMetadata`].
+pub const SESSION_METADATA_FILE: &str = "session.json";
+
+pub fn write_session_metadata(dir: &Path, metadata: &SessionMetadata) -> Result<(), StorageError> {
+    let json = serde_json::to_string_pretty(metadata)?;
+    std::fs::write(dir.join(SESSION_METADATA_FILE), json)?;
+    Ok(())
+}
+
+/// Load metadata saved by [`write_session_metadata`] from `dir`.
+pub fn read_session_metadata(dir: &Path) -> Result<SessionMetadata, StorageError> {
+    let json = std::fs::read_to_string(dir.join(SESSION_METADATA_FILE))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// File in a session directory summarizing it, so listing sessions doesn't parse
+/// every `steps.json`.
+pub const SESSION_MANIFEST_FILE: &str = "manifest.json";
+
+/// Contents of [`SESSION_MANIFEST_FILE`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionManifest {
+    /// Milliseconds since the Unix epoch; 0 when unknown.
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub step_count: usize,
+    /// Highest step number handed out; step IDs continue after it when reopened.
+    #[serde(default)]
+    pub last_step_number: u64,
+}
+
+pub fn write_session_manifest(dir: &Path, manifest: &SessionManifest) -> Result<(), StorageError> {
+    let json = serde_json::to_string_pretty(manifest)?;
+    std::fs::write(dir.join(SESSION_MANIFEST_FILE), json)?;
+    Ok(())
+}
+
+/// Load a manifest saved by [`write_session_manifest`] from `dir`.
+pub fn read_session_manifest(dir: &Path) -> Result<SessionManifest, StorageError> {
+    let json = std::fs::read_to_string(dir.join(SESSION_MANIFEST_FILE))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Load steps saved by [`write_steps`]. Relative screenshot paths are resolved against
+/// the file's directory, so a guide can be moved together with its screenshots.
+pub fn read_steps(path: &Path) -> Result<Vec<Step>, StorageError> {
+    let json = std::fs::read_to_string(path)?;
+    let mut steps: Vec<Step> = serde_json::from_str(&json)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    for step in &mut steps {
+        if let Some(screenshot) = step.screenshot_path.as_mut() {
+            if Path::new(screenshot.as_str()).is_relative() {
+                *screenshot = base.join(&*screenshot).to_string_lossy().into_owned();
+            }
+        }
+    }
+    Ok(steps)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,4 +248,109 @@ mod tests {
 
         assert_eq!(steps, parsed);
     }
+
+    #[test]
+    fn read_steps_resolves_relative_screenshots() {
+        let dir = tempdir().expect("tempdir");
+        let mut relative = Step::sample();
+        relative.screenshot_path = Some("shots/step-1.png".to_string());
+        let mut absolute = Step::sample();
+        absolute.screenshot_path = Some("/tmp/step-2.png".to_string());
+        write_steps(dir.path(), &[relative, absolute]).expect("write steps");
+
+        let steps = read_steps(&dir.path().join("steps.json")).expect("read steps");
+
+        let expected = dir.path().join("shots/step-1.png");
+        assert_eq!(
+            steps[0].screenshot_path.as_deref(),
+            Some(expected.to_str().unwrap())
+        );
+        assert_eq!(steps[1].screenshot_path.as_deref(), Some("/tmp/step-2.png"));
+    }
+
+    #[test]
+    fn prunes_old_sessions_then_least_recently_used_over_budget() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        let day = Duration::from_secs(24 * 60 * 60);
+        let session = |name: &str, days_ago: u32, mb: u64| SessionDirUsage {
+            dir: PathBuf::from(name),
+            modified: now - day * days_ago,
+            bytes: mb * 1024 * 1024,
+        };
+        let sessions = vec![
+            session("old", 40, 10),
+            session("recent", 1, 300),
+            session("middle", 5, 300),
+            session("older", 9, 300),
+            session("today", 0, 300),
+        ];
+        let names = |dirs: Vec<PathBuf>| -> Vec<String> {
+            dirs.iter().map(|d| d.display().to_string()).collect()
+        };
+
+        let pruned = sessions_to_prune(
+            sessions.clone(),
+            now,
+            Some(DEFAULT_SESSION_MAX_AGE),
+            Some(1000 * 1024 * 1024),
+        );
+        // "old" is past the age limit; of the rest, "today", "recent" and "middle" fit.
+        assert_eq!(names(pruned), ["older", "old"]);
+
+        // A session larger than the remaining budget goes, smaller older ones may stay.
+        let pruned = sessions_to_prune(sessions.clone(), now, None, Some(700 * 1024 * 1024));
+        assert_eq!(names(pruned), ["middle", "older"]);
+
+        assert!(sessions_to_prune(sessions, now, None, None).is_empty());
+    }
+
+    #[test]
+    fn fit_screenshot_downscales_only_oversized_images() {
+        let dir = tempdir().expect("tempdir");
+        let large = dir.path().join("large.png");
+        let small = dir.path().join("small.png");
+        image::RgbaImage::new(512, 288).save(&large).unwrap();
+        image::RgbaImage::new(144, 90).save(&small).unwrap();
+
+        let size = fit_screenshot(&large, 384).expect("fit large");
+        assert_eq!(image::image_dimensions(&large).unwrap(), (384, 216));
+        assert_eq!(size, fs::metadata(&large).unwrap().len());
+
+        let before = fs::read(&small).unwrap();
+        fit_screenshot(&small, 384).expect("fit small");
+        assert_eq!(
+            fs::read(&small).unwrap(),
+            before,
+            "small files are not rewritten"
+        );
+
+        assert!(matches!(
+            fit_screenshot(&dir.path().join("missing.png"), 384),
+            Err(StorageError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn dir_usage_counts_nested_files() {
+        let dir = tempdir().expect("tempdir");
+        fs::write(dir.path().join("a.png"), [0u8; 100]).unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/b.png"), [0u8; 50]).unwrap();
+
+        assert_eq!(dir_usage(dir.path()), (2, 150));
+        assert_eq!(dir_usage(&dir.path().join("missing")), (0, 0));
+    }
+
+    #[test]
+    fn read_steps_reports_malformed_json() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("steps.json");
+        fs::write(&path, "{\"steps\": 1}").expect("write");
+
+        assert!(matches!(read_steps(&path), Err(StorageError::Json(_))));
+        assert!(matches!(
+            read_steps(&dir.path().join("missing.json")),
+            Err(StorageError::Io(_))
+        ));
+    }
 }
@@ -7,6 +7,14 @@ pub enum ActionType {
     RightClick,
     Shortcut,
     Note,
+    /// Left-button press, move, release. Start is the step's click point.
+    Drag,
+    /// Full-display screenshot taken when recording starts; exporters show it as an
+    /// intro rather than an action.
+    Overview,
+    /// Pointer resting over an element, captured with the hover shortcut to show a
+    /// tooltip or hover state. Nothing was clicked.
+    Hover,
 }
 
 /// Status of the screenshot capture for a step.
@@ -25,6 +33,8 @@ pub enum CaptureStatus {
 pub enum DescriptionSource {
     Ai,
     Manual,
+    /// Generated by the recorder itself (e.g. context section breaks), not by a model or the user.
+    Auto,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +45,59 @@ pub enum DescriptionStatus {
     Failed,
 }
 
+/// Where a drag was released.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DragEnd {
+    pub x: i32,
+    pub y: i32,
+    /// Release point within the screenshot (percent, origin top-left).
+    pub x_percent: f32,
+    pub y_percent: f32,
+    /// App and window under the release point. Empty for drags recorded before
+    /// these were tracked.
+    #[serde(default)]
+    pub app: String,
+    #[serde(default)]
+    pub window_title: String,
+}
+
+/// Screen area a screenshot was captured from (global points) and the size of the
+/// display it was on. Kept so the auto-crop suggestion can be recomputed later.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CaptureBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub display_width: i32,
+    pub display_height: i32,
+}
+
+/// Size of a captured image in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PixelSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// How long each phase of capturing a click took, in milliseconds. A phase the
+/// capture path skipped stays `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureTimings {
+    /// Accessibility lookup of the clicked element and its app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ax_lookup_ms: Option<u64>,
+    /// Picking the window (or dialog, popup) to capture.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_resolve_ms: Option<u64>,
+    /// Taking the screenshot, fallbacks and waits for menus included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_ms: Option<u64>,
+    /// Age of the pre-click frame at the click, when the screenshot came from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_click_frame_age_ms: Option<i64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AxClickInfo {
     pub role: String,
@@ -54,6 +117,9 @@ pub struct AxClickInfo {
     pub container_subrole: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub container_identifier: Option<String>,
+    /// Bounds of that container within the captured screenshot (percent, origin top-left).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_bounds: Option<BoundsPercent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub window_role: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -68,8 +134,71 @@ pub struct AxClickInfo {
     pub parent_dialog_subrole: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_checked: Option<bool>,
+    /// For checkboxes, radio buttons and switches: the state before the click.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub was_checked: Option<bool>,
+    /// The state read back shortly after the click; unset when the element went away.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub now_checked: Option<bool>,
+    /// Text in the clicked form field at click time. Never recorded for password fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Placeholder of the clicked form field ("name@example.com").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
     pub is_cancel_button: bool,
     pub is_default_button: bool,
+    /// Menu breadcrumb for menu-item clicks, e.g. "File ▸ Export ▸ PDF".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub menu_path: Option<String>,
+    /// `AXDescription` of the clicked element: what screen readers announce as its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<String>,
+    /// `AXHelp` of the clicked element (its help tag).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+}
+
+/// A traffic-light button in a window's title bar. Inferred at capture time and kept
+/// on the step as the AX subrole, so exports can name the button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowControlKind {
+    Close,
+    Minimize,
+    Zoom,
+}
+
+impl WindowControlKind {
+    /// Kind named by an AX subrole, as set on inferred window-control clicks.
+    pub fn from_subrole(subrole: &str) -> Option<Self> {
+        [Self::Close, Self::Minimize, Self::Zoom]
+            .into_iter()
+            .find(|kind| kind.subrole() == subrole)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Close => "Close",
+            Self::Minimize => "Minimize",
+            Self::Zoom => "Zoom",
+        }
+    }
+
+    pub fn subrole(self) -> &'static str {
+        match self {
+            Self::Close => "AXCloseButton",
+            Self::Minimize => "AXMinimizeButton",
+            Self::Zoom => "AXZoomButton",
+        }
+    }
+
+    pub fn role_description(self) -> &'static str {
+        match self {
+            Self::Close => "Close button",
+            Self::Minimize => "Minimize button",
+            Self::Zoom => "Zoom button",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -80,6 +209,77 @@ pub struct BoundsPercent {
     pub height_percent: f32,
 }
 
+/// A point in percent of a step's cropped screenshot (origin top-left).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PointPercent {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl PointPercent {
+    fn clamped(self) -> Option<Self> {
+        (self.x.is_finite() && self.y.is_finite()).then(|| Self {
+            x: self.x.clamp(0.0, 100.0),
+            y: self.y.clamp(0.0, 100.0),
+        })
+    }
+}
+
+/// A shape drawn over a step's screenshot, kept as vectors and baked into the images
+/// at export time. Coordinates are percent of the cropped screenshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Annotation {
+    Arrow {
+        from: PointPercent,
+        to: PointPercent,
+    },
+    Rect {
+        bounds: BoundsPercent,
+    },
+    Text {
+        pos: PointPercent,
+        content: String,
+    },
+}
+
+impl Annotation {
+    /// Clamp coordinates into the image and trim text. `None` for annotations that
+    /// would draw nothing: non-finite coordinates, empty rects or blank text.
+    pub fn normalized(self) -> Option<Self> {
+        match self {
+            Self::Arrow { from, to } => {
+                let (from, to) = (from.clamped()?, to.clamped()?);
+                (from != to).then_some(Self::Arrow { from, to })
+            }
+            Self::Rect { bounds } => {
+                let origin = PointPercent {
+                    x: bounds.x_percent,
+                    y: bounds.y_percent,
+                }
+                .clamped()?;
+                let width = bounds.width_percent.min(100.0 - origin.x);
+                let height = bounds.height_percent.min(100.0 - origin.y);
+                (width > 0.0 && height > 0.0).then_some(Self::Rect {
+                    bounds: BoundsPercent {
+                        x_percent: origin.x,
+                        y_percent: origin.y,
+                        width_percent: width,
+                        height_percent: height,
+                    },
+                })
+            }
+            Self::Text { pos, content } => {
+                let content = content.trim();
+                (!content.is_empty()).then_some(Self::Text {
+                    pos: pos.clamped()?,
+                    content: content.to_string(),
+                })
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Step {
     pub id: String,
@@ -93,6 +293,9 @@ pub struct Step {
     pub window_title: String,
     pub screenshot_path: Option<String>,
     pub note: Option<String>,
+    /// Short heading set by hand, shown in bold above the description in exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Optional enhanced description (e.g. Apple Intelligence). When absent, exporters fall back to templates.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -114,6 +317,126 @@ pub struct Step {
     /// Optional non-destructive crop region within the screenshot (percent, origin top-left).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub crop_region: Option<BoundsPercent>,
+    /// Screenshot is the secure auth placeholder (Touch ID / password dialog), not real pixels.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_secure_placeholder: bool,
+    /// Release point for `ActionType::Drag` steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drag_end: Option<DragEnd>,
+    /// Frontmost window title at click time, raw (before "Menu"/"Dialog" fallbacks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_title: Option<String>,
+    /// Where the screenshot was captured from, for recomputing the auto-crop.
+    /// `None` for legacy steps, drags, auth dialogs and failed captures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_bounds: Option<CaptureBounds>,
+    /// Click marker position is a best guess: the screenshot was retaken after the window moved or resized.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub click_approximate: bool,
+    /// Screenshot of the frontmost window taken shortly after the click, for clicks
+    /// that launch or switch apps. Exports show it below the step's screenshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_screenshot_path: Option<String>,
+    /// How long to wait after this step before the next, for timed guides and demo
+    /// scripts. Set by hand; exporters show it as "Wait ~3s".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_ms: Option<u32>,
+    /// Screenshot pixels per point at capture time: 2.0 on Retina, 1.0 on most external
+    /// displays. Lets exports snap crops and markers to whole points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pixel_scale: Option<f32>,
+    /// Pixel size of the screenshot as captured, before any storage downscale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_pixels: Option<PixelSize>,
+    /// The user switched to another desktop (Space) just before this step, so its
+    /// screenshot shows a different desktop. Exports note it above the step.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub space_changed: bool,
+    /// Bundle identifier of the clicked app ("com.apple.Safari"), looked up from its
+    /// PID at click time. Unknown for steps recorded before it was captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// Arrows, boxes and labels drawn over the screenshot by hand.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Per-phase capture timings, for diagnosing stale or wrong-frame captures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_timings: Option<CaptureTimings>,
+    /// URL of the browser tab the click landed in; only set for known browsers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_url: Option<String>,
+    /// Keys pressed for `ActionType::Shortcut` steps, as macOS menus print them ("⇧⌘S").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<String>,
+}
+
+/// Window title written for auth placeholder steps. Sessions recorded before
+/// `is_secure_placeholder` existed only to carry this marker.
+pub const AUTH_PLACEHOLDER_TITLE: &str = "Authentication dialog (secure)";
+
+impl Step {
+    /// Check if this step represents an authentication placeholder.
+    ///
+    /// Older sessions predate `is_secure_placeholder`, so fall back to the
+    /// title/app markers the recorder used to write.
+    pub fn is_auth_placeholder(&self) -> bool {
+        self.is_secure_placeholder
+            || self.window_title == AUTH_PLACEHOLDER_TITLE
+            || self.app.to_lowercase() == "authentication"
+    }
+
+    /// Recorder-generated step (section break) that can be bulk-removed. Recorded
+    /// clicks with a rule-based description are `Auto` too but not Notes.
+    pub fn is_auto_generated(&self) -> bool {
+        self.action == ActionType::Note && self.description_source == Some(DescriptionSource::Auto)
+    }
+
+    /// The window button this step clicked, from the AX subrole recorded with it.
+    pub fn window_control(&self) -> Option<WindowControlKind> {
+        let subrole = self.ax.as_ref()?.subrole.as_deref()?;
+        WindowControlKind::from_subrole(subrole)
+    }
+}
+
+/// Guide-level details kept with a session: the default export title, who recorded
+/// it, when, and free-form tags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Milliseconds since the Unix epoch, like `Step::ts`. 0 when unknown.
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl SessionMetadata {
+    /// Set the editable fields: title and author trimmed (blank authors dropped), tags
+    /// trimmed with blanks and repeats removed, first spelling kept.
+    pub fn update(&mut self, title: &str, author: Option<&str>, tags: &[String]) {
+        self.title = title.trim().to_string();
+        self.author = author
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::to_string);
+        self.tags.clear();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    /// Local calendar date the session was created, `None` when unknown.
+    pub fn created_on(&self) -> Option<chrono::NaiveDate> {
+        (self.created_at > 0)
+            .then(|| chrono::DateTime::from_timestamp_millis(self.created_at))
+            .flatten()
+            .map(|t| t.with_timezone(&chrono::Local).date_naive())
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +454,7 @@ impl Step {
             window_title: "Downloads".to_string(),
             screenshot_path: Some("screenshots/step-001.png".to_string()),
             note: None,
+            title: None,
             description: None,
             description_source: None,
             description_status: None,
@@ -139,6 +463,21 @@ impl Step {
             capture_status: None,
             capture_error: None,
             crop_region: None,
+            is_secure_placeholder: false,
+            drag_end: None,
+            context_title: None,
+            capture_bounds: None,
+            click_approximate: false,
+            result_screenshot_path: None,
+            wait_ms: None,
+            pixel_scale: None,
+            capture_pixels: None,
+            space_changed: false,
+            bundle_id: None,
+            annotations: Vec::new(),
+            capture_timings: None,
+            page_url: None,
+            keys: None,
         }
     }
 }
@@ -154,4 +493,114 @@ mod tests {
         let back: Step = serde_json::from_str(&json).unwrap();
         assert_eq!(step, back);
     }
+
+    #[test]
+    fn step_wait_roundtrips_and_is_omitted_when_unset() {
+        let mut step = Step::sample();
+        let value = serde_json::to_value(&step).unwrap();
+        assert!(value.get("wait_ms").is_none());
+        let back: Step = serde_json::from_value(value).unwrap();
+        assert_eq!(back.wait_ms, None);
+
+        step.wait_ms = Some(3_000);
+        let json = serde_json::to_string(&step).unwrap();
+        assert!(json.contains("\"wait_ms\":3000"));
+        let back: Step = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, step);
+    }
+
+    #[test]
+    fn annotations_serialize_tagged_and_normalize() {
+        let arrow: Annotation = serde_json::from_value(serde_json::json!({
+            "kind": "arrow",
+            "from": { "x": -5.0, "y": 50.0 },
+            "to": { "x": 80.0, "y": 120.0 },
+        }))
+        .unwrap();
+        assert_eq!(
+            arrow.normalized(),
+            Some(Annotation::Arrow {
+                from: PointPercent { x: 0.0, y: 50.0 },
+                to: PointPercent { x: 80.0, y: 100.0 },
+            })
+        );
+
+        let rect = Annotation::Rect {
+            bounds: BoundsPercent {
+                x_percent: 90.0,
+                y_percent: 10.0,
+                width_percent: 30.0,
+                height_percent: 0.0,
+            },
+        };
+        assert_eq!(rect.normalized(), None);
+
+        let text = Annotation::Text {
+            pos: PointPercent { x: 10.0, y: 10.0 },
+            content: "  Click here ".to_string(),
+        };
+        let json = serde_json::to_value(text.normalized().unwrap()).unwrap();
+        assert_eq!(json["kind"], "text");
+        assert_eq!(json["content"], "Click here");
+
+        let step = Step::sample();
+        assert!(serde_json::to_value(&step)
+            .unwrap()
+            .get("annotations")
+            .is_none());
+    }
+
+    #[test]
+    fn session_metadata_update_normalizes_fields() {
+        let mut meta = SessionMetadata {
+            created_at: 1_700_000_000_000,
+            ..SessionMetadata::default()
+        };
+        meta.update(
+            "  Onboarding ",
+            Some("   "),
+            &[" setup".into(), "".into(), "Setup".into(), "mac".into()],
+        );
+        assert_eq!(meta.title, "Onboarding");
+        assert_eq!(meta.author, None);
+        assert_eq!(meta.tags, vec!["setup", "mac"]);
+        assert_eq!(meta.created_at, 1_700_000_000_000);
+        assert!(meta.created_on().is_some());
+        assert_eq!(SessionMetadata::default().created_on(), None);
+
+        let json = serde_json::to_value(&meta).unwrap();
+        assert!(json.get("author").is_none());
+        let back: SessionMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(back, meta);
+    }
+
+    #[test]
+    fn step_without_placeholder_flag_deserializes() {
+        let mut value = serde_json::to_value(Step::sample()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("is_secure_placeholder");
+        let step: Step = serde_json::from_value(value).unwrap();
+        assert!(!step.is_secure_placeholder);
+    }
+
+    #[test]
+    fn is_auth_placeholder_checks() {
+        let mut s = Step::sample();
+        assert!(!s.is_auth_placeholder());
+
+        s.is_secure_placeholder = true;
+        s.window_title = "Authentifizierungsdialog (sicher)".into();
+        assert!(s.is_auth_placeholder());
+
+        // Legacy sessions without the flag.
+        s.is_secure_placeholder = false;
+        s.window_title = AUTH_PLACEHOLDER_TITLE.into();
+        assert!(s.is_auth_placeholder());
+
+        s.window_title = "Normal".into();
+        s.app = "Authentication".into();
+        assert!(s.is_auth_placeholder());
+    }
 }
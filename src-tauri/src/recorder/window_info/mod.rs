@@ -3,13 +3,16 @@
 
 mod auth;
 mod query;
+mod snapshot;
 mod topmost;
 mod types;
 
-pub use auth::{find_auth_dialog_window, get_security_agent_window};
+pub use auth::get_security_agent_window;
 pub use query::{
-    get_frontmost_window, get_main_window_for_pid, get_window_at_click, get_window_for_pid_at_click,
+    bundle_id_for_pid, find_running_app_pid, get_frontmost_window, get_main_window_for_pid,
+    get_window_at_click, get_window_bounds, get_window_for_pid_at_click, get_window_layer,
 };
+pub use snapshot::{WindowEntry, WindowSnapshot};
 pub use topmost::{find_attached_dialog_window, get_topmost_window_at_point};
 pub use types::{WindowBounds, WindowError, WindowInfo};
 
@@ -1,6 +1,7 @@
 //! Window query functions: find windows by click position, frontmost app, or PID.
 #![allow(dead_code)]
 
+use super::snapshot::WindowSnapshot;
 use super::types::{WindowBounds, WindowError, WindowInfo};
 
 /// Get the window that contains the click point.
@@ -160,160 +161,7 @@ pub fn get_window_at_click(click_x: i32, click_y: i32) -> Result<WindowInfo, Win
 /// Using the largest window ensures we get the parent window, not a modal/sheet.
 #[cfg(target_os = "macos")]
 pub fn get_frontmost_window() -> Result<WindowInfo, WindowError> {
-    use core_foundation::base::{CFType, TCFType};
-    use core_foundation::dictionary::CFDictionaryRef;
-    use core_foundation::number::CFNumber;
-    use core_foundation::string::CFString;
-    use core_graphics::display::*;
-    use objc2_app_kit::NSWorkspace;
-
-    // Get frontmost app
-    let workspace = NSWorkspace::sharedWorkspace();
-    let frontmost = workspace
-        .frontmostApplication()
-        .ok_or(WindowError::NoFrontmostApp)?;
-
-    let app_name = frontmost
-        .localizedName()
-        .map(|n| n.to_string())
-        .unwrap_or_else(|| "Unknown".to_string());
-
-    let pid = frontmost.processIdentifier();
-
-    // Get windows for this app
-    let window_list = unsafe {
-        CGWindowListCopyWindowInfo(
-            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
-            kCGNullWindowID,
-        )
-    };
-
-    if window_list.is_null() {
-        return Err(WindowError::NoWindows);
-    }
-
-    let windows: Vec<CFDictionaryRef> = unsafe {
-        let count = core_foundation::array::CFArrayGetCount(window_list as _);
-        (0..count)
-            .map(|i| {
-                core_foundation::array::CFArrayGetValueAtIndex(window_list as _, i)
-                    as CFDictionaryRef
-            })
-            .collect()
-    };
-
-    // Find the largest window of this app (the main window, not modals/sheets)
-    let mut best_window: Option<WindowInfo> = None;
-    let mut best_area: u64 = 0;
-
-    for window_dict in windows {
-        let dict = unsafe {
-            core_foundation::dictionary::CFDictionary::<CFString, CFType>::wrap_under_get_rule(
-                window_dict,
-            )
-        };
-
-        // Check if window belongs to frontmost app
-        let owner_pid_key = CFString::new("kCGWindowOwnerPID");
-        if let Some(owner_pid) = dict.find(owner_pid_key) {
-            let owner_pid: CFNumber =
-                unsafe { CFNumber::wrap_under_get_rule(owner_pid.as_CFTypeRef() as _) };
-            if let Some(owner_pid_val) = owner_pid.to_i32() {
-                if owner_pid_val != pid {
-                    continue;
-                }
-            }
-        }
-
-        // Get window ID
-        let window_id_key = CFString::new("kCGWindowNumber");
-        let window_id = dict
-            .find(window_id_key)
-            .and_then(|v| {
-                let num: CFNumber = unsafe { CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                num.to_i32().map(|n| n as u32)
-            })
-            .unwrap_or(0);
-
-        if window_id == 0 {
-            continue;
-        }
-
-        // Get window title
-        let title_key = CFString::new("kCGWindowName");
-        let window_title = dict
-            .find(title_key)
-            .map(|v| {
-                let s: CFString = unsafe { CFString::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                s.to_string()
-            })
-            .unwrap_or_default();
-
-        // Get window bounds
-        let bounds_key = CFString::new("kCGWindowBounds");
-        let bounds = dict.find(bounds_key)
-            .map(|v| {
-                let bounds_dict: core_foundation::dictionary::CFDictionary<CFString, CFNumber> =
-                    unsafe { core_foundation::dictionary::CFDictionary::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-
-                let x = bounds_dict.find(CFString::new("X"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0);
-                let y = bounds_dict.find(CFString::new("Y"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0);
-                let width = bounds_dict.find(CFString::new("Width"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0) as u32;
-                let height = bounds_dict.find(CFString::new("Height"))
-                    .and_then(|n| n.to_i32())
-                    .unwrap_or(0) as u32;
-
-                WindowBounds { x, y, width, height }
-            })
-            .unwrap_or(WindowBounds { x: 0, y: 0, width: 0, height: 0 });
-
-        let area = bounds.width as u64 * bounds.height as u64;
-
-        // Keep track of the largest window
-        if area > best_area {
-            best_area = area;
-            best_window = Some(WindowInfo {
-                app_name: app_name.clone(),
-                window_title,
-                window_id,
-                bounds,
-            });
-        }
-    }
-
-    if let Some(window) = best_window {
-        if cfg!(debug_assertions) {
-            eprintln!(
-                "Main window: '{}' id={} bounds=({}, {}, {}x{})",
-                window.window_title,
-                window.window_id,
-                window.bounds.x,
-                window.bounds.y,
-                window.bounds.width,
-                window.bounds.height
-            );
-        }
-        return Ok(window);
-    }
-
-    // Fallback: return app info without specific window
-    Ok(WindowInfo {
-        app_name,
-        window_title: String::new(),
-        window_id: 0,
-        bounds: WindowBounds {
-            x: 0,
-            y: 0,
-            width: 800,
-            height: 600,
-        },
-    })
+    WindowSnapshot::capture()?.frontmost_window()
 }
 
 /// Find the largest on-screen window belonging to a specific PID.
@@ -428,6 +276,31 @@ pub fn get_main_window_for_pid(pid: i32, app_name: &str) -> Option<WindowInfo> {
     best_window
 }
 
+/// Find the PID of a running app by its localized name (as recorded on steps).
+/// Returns None if no such app is running.
+pub fn find_running_app_pid(app_name: &str) -> Option<i32> {
+    use objc2_app_kit::NSWorkspace;
+
+    let wanted = app_name.trim().to_lowercase();
+    if wanted.is_empty() {
+        return None;
+    }
+    let workspace = NSWorkspace::sharedWorkspace();
+    workspace.runningApplications().iter().find_map(|app| {
+        let name = app.localizedName()?.to_string();
+        (name.trim().to_lowercase() == wanted).then(|| app.processIdentifier())
+    })
+}
+
+/// Bundle identifier ("com.apple.Safari") of the running app with this PID.
+pub fn bundle_id_for_pid(pid: i32) -> Option<String> {
+    use objc2_app_kit::NSRunningApplication;
+
+    NSRunningApplication::runningApplicationWithProcessIdentifier(pid)?
+        .bundleIdentifier()
+        .map(|id| id.to_string())
+}
+
 /// Find the topmost visible window for a given PID that contains the click point.
 /// This is useful when an app has multiple windows and we need the local context
 /// for popup/overlay captures.
@@ -439,132 +312,85 @@ pub fn get_window_for_pid_at_click(
     click_y: i32,
     exclude_window_id: Option<u32>,
 ) -> Option<WindowInfo> {
+    WindowSnapshot::capture().ok()?.window_for_pid_at_click(
+        pid,
+        app_name,
+        click_x,
+        click_y,
+        exclude_window_id,
+    )
+}
+
+/// Current on-screen bounds of one window, read fresh from the window list. None when
+/// the window is gone.
+#[cfg(target_os = "macos")]
+pub fn get_window_bounds(window_id: u32) -> Option<WindowBounds> {
+    use core_foundation::array::CFArray;
     use core_foundation::base::{CFType, TCFType};
-    use core_foundation::dictionary::CFDictionaryRef;
+    use core_foundation::dictionary::CFDictionary;
     use core_foundation::number::CFNumber;
     use core_foundation::string::CFString;
     use core_graphics::display::*;
 
-    let window_list = unsafe {
-        CGWindowListCopyWindowInfo(
-            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
-            kCGNullWindowID,
-        )
-    };
-
+    if window_id == 0 {
+        return None;
+    }
+    let window_list =
+        unsafe { CGWindowListCopyWindowInfo(kCGWindowListOptionIncludingWindow, window_id) };
     if window_list.is_null() {
         return None;
     }
+    // SAFETY: the Copy function returns an owned array, released when this drops.
+    let windows: CFArray<CFDictionary<CFString, CFType>> =
+        unsafe { CFArray::wrap_under_create_rule(window_list as _) };
+    let dict = windows.get(0)?;
+    let bounds = dict.find(CFString::new("kCGWindowBounds"))?;
+    let bounds: CFDictionary<CFString, CFNumber> =
+        unsafe { CFDictionary::wrap_under_get_rule(bounds.as_CFTypeRef() as _) };
+    let value = |key: &str| bounds.find(CFString::new(key)).and_then(|n| n.to_i32());
+    Some(WindowBounds {
+        x: value("X")?,
+        y: value("Y")?,
+        width: value("Width")?.max(0) as u32,
+        height: value("Height")?.max(0) as u32,
+    })
+    .filter(|b| b.width > 0 && b.height > 0)
+}
 
-    let windows: Vec<CFDictionaryRef> = unsafe {
-        let count = core_foundation::array::CFArrayGetCount(window_list as _);
-        (0..count)
-            .map(|i| {
-                core_foundation::array::CFArrayGetValueAtIndex(window_list as _, i)
-                    as CFDictionaryRef
-            })
-            .collect()
-    };
-
-    let mut best_window: Option<WindowInfo> = None;
-    let mut best_area: u64 = 0;
-
-    for window_dict in windows {
-        let dict = unsafe {
-            core_foundation::dictionary::CFDictionary::<CFString, CFType>::wrap_under_get_rule(
-                window_dict,
-            )
-        };
-
-        let owner_pid_key = CFString::new("kCGWindowOwnerPID");
-        let owner_matches = dict.find(owner_pid_key).and_then(|owner_pid| {
-            let owner_pid: CFNumber =
-                unsafe { CFNumber::wrap_under_get_rule(owner_pid.as_CFTypeRef() as _) };
-            owner_pid.to_i32()
-        }) == Some(pid);
-        if !owner_matches {
-            continue;
-        }
-
-        let bounds_key = CFString::new("kCGWindowBounds");
-        let bounds = dict.find(bounds_key).map(|v| {
-            let bounds_dict: core_foundation::dictionary::CFDictionary<CFString, CFNumber> =
-                unsafe {
-                    core_foundation::dictionary::CFDictionary::wrap_under_get_rule(
-                        v.as_CFTypeRef() as _,
-                    )
-                };
-
-            let x = bounds_dict
-                .find(CFString::new("X"))
-                .and_then(|n| n.to_i32())
-                .unwrap_or(0);
-            let y = bounds_dict
-                .find(CFString::new("Y"))
-                .and_then(|n| n.to_i32())
-                .unwrap_or(0);
-            let width = bounds_dict
-                .find(CFString::new("Width"))
-                .and_then(|n| n.to_i32())
-                .unwrap_or(0) as u32;
-            let height = bounds_dict
-                .find(CFString::new("Height"))
-                .and_then(|n| n.to_i32())
-                .unwrap_or(0) as u32;
-            WindowBounds {
-                x,
-                y,
-                width,
-                height,
-            }
-        })?;
-
-        if bounds.width < 10 || bounds.height < 10 {
-            continue;
-        }
-
-        let inside_x = click_x >= bounds.x && click_x < bounds.x + bounds.width as i32;
-        let inside_y = click_y >= bounds.y && click_y < bounds.y + bounds.height as i32;
-        if !inside_x || !inside_y {
-            continue;
-        }
-
-        let window_id_key = CFString::new("kCGWindowNumber");
-        let window_id = dict
-            .find(window_id_key)
-            .and_then(|v| {
-                let num: CFNumber = unsafe { CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                num.to_i32().map(|n| n as u32)
-            })
-            .unwrap_or(0);
-        if window_id == 0 {
-            continue;
-        }
-        if exclude_window_id.is_some_and(|id| id == window_id) {
-            continue;
-        }
+#[cfg(not(target_os = "macos"))]
+pub fn get_window_bounds(_window_id: u32) -> Option<WindowBounds> {
+    None
+}
 
-        let title_key = CFString::new("kCGWindowName");
-        let window_title = dict
-            .find(title_key)
-            .map(|v| {
-                let s: CFString = unsafe { CFString::wrap_under_get_rule(v.as_CFTypeRef() as _) };
-                s.to_string()
-            })
-            .unwrap_or_default();
+/// Window layer (`kCGWindowLayer`) of one window: 0 for normal windows, negative for
+/// desktop-level ones. None when the window is gone.
+#[cfg(target_os = "macos")]
+pub fn get_window_layer(window_id: u32) -> Option<i32> {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+    use core_graphics::display::*;
 
-        let candidate = WindowInfo {
-            app_name: app_name.to_string(),
-            window_title,
-            window_id,
-            bounds,
-        };
-        let area = candidate.bounds.width as u64 * candidate.bounds.height as u64;
-        if area > best_area {
-            best_area = area;
-            best_window = Some(candidate);
-        }
+    if window_id == 0 {
+        return None;
     }
+    let window_list =
+        unsafe { CGWindowListCopyWindowInfo(kCGWindowListOptionIncludingWindow, window_id) };
+    if window_list.is_null() {
+        return None;
+    }
+    // SAFETY: the Copy function returns an owned array, released when this drops.
+    let windows: CFArray<CFDictionary<CFString, CFType>> =
+        unsafe { CFArray::wrap_under_create_rule(window_list as _) };
+    let dict = windows.get(0)?;
+    let layer = dict.find(CFString::new("kCGWindowLayer"))?;
+    let layer: CFNumber = unsafe { CFNumber::wrap_under_get_rule(layer.as_CFTypeRef() as _) };
+    layer.to_i32()
+}
 
-    best_window
+#[cfg(not(target_os = "macos"))]
+pub fn get_window_layer(_window_id: u32) -> Option<i32> {
+    None
 }
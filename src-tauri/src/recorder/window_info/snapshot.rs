@@ -0,0 +1,517 @@
+//! One copy of the on-screen window list, shared by every window lookup a click makes.
+//! Copying the list is a full WindowServer round trip; doing it once per click keeps
+//! busy desktops fast and gives all lookups the same view of the screen.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::auth::is_security_agent_name;
+use super::topmost::{app_names_match, get_process_name_by_pid};
+use super::types::{WindowBounds, WindowError, WindowInfo};
+
+/// One window of the list, as `CGWindowListCopyWindowInfo` describes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowEntry {
+    pub window_id: u32,
+    pub owner_pid: Option<i32>,
+    /// Localized owner name (`kCGWindowOwnerName`).
+    pub owner_name: String,
+    pub title: String,
+    /// Zero-sized when the list has no bounds for the window.
+    pub bounds: WindowBounds,
+    pub layer: i32,
+    /// Opacity from 0 to 1; fully transparent windows draw nothing.
+    pub alpha: f32,
+}
+
+impl WindowEntry {
+    pub(super) fn contains(&self, x: i32, y: i32) -> bool {
+        let b = &self.bounds;
+        x >= b.x && x < b.x + b.width as i32 && y >= b.y && y < b.y + b.height as i32
+    }
+}
+
+/// On-screen windows front to back, desktop elements excluded.
+pub struct WindowSnapshot {
+    windows: Vec<WindowEntry>,
+    process_name: fn(i32) -> Option<String>,
+    /// Process names by PID; looking one up spawns `ps`.
+    process_names: RefCell<HashMap<i32, Option<String>>>,
+}
+
+impl Default for WindowSnapshot {
+    fn default() -> Self {
+        Self::from_windows(Vec::new())
+    }
+}
+
+impl WindowSnapshot {
+    pub fn from_windows(windows: Vec<WindowEntry>) -> Self {
+        Self {
+            windows,
+            process_name: get_process_name_by_pid,
+            process_names: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Copy the current window list.
+    #[cfg(target_os = "macos")]
+    pub fn capture() -> Result<Self, WindowError> {
+        use core_foundation::array::CFArray;
+        use core_foundation::base::{CFType, TCFType};
+        use core_foundation::dictionary::CFDictionary;
+        use core_foundation::number::CFNumber;
+        use core_foundation::string::CFString;
+        use core_graphics::display::*;
+
+        let window_list = unsafe {
+            CGWindowListCopyWindowInfo(
+                kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+                kCGNullWindowID,
+            )
+        };
+        if window_list.is_null() {
+            return Err(WindowError::NoWindows);
+        }
+        // SAFETY: the Copy function returns an owned array, released when this drops.
+        let list: CFArray<CFDictionary<CFString, CFType>> =
+            unsafe { CFArray::wrap_under_create_rule(window_list as _) };
+
+        let windows = list
+            .iter()
+            .map(|dict| {
+                let cf_number = |key: &str| {
+                    dict.find(CFString::new(key)).map(|v| {
+                        let num: CFNumber =
+                            unsafe { CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _) };
+                        num
+                    })
+                };
+                let number = |key: &str| cf_number(key).and_then(|n| n.to_i32());
+                let string = |key: &str| {
+                    dict.find(CFString::new(key)).map(|v| {
+                        let s: CFString =
+                            unsafe { CFString::wrap_under_get_rule(v.as_CFTypeRef() as _) };
+                        s.to_string()
+                    })
+                };
+                let bounds = dict
+                    .find(CFString::new("kCGWindowBounds"))
+                    .map(|v| {
+                        let bounds: CFDictionary<CFString, CFNumber> =
+                            unsafe { CFDictionary::wrap_under_get_rule(v.as_CFTypeRef() as _) };
+                        let value = |key: &str| {
+                            bounds
+                                .find(CFString::new(key))
+                                .and_then(|n| n.to_i32())
+                                .unwrap_or(0)
+                        };
+                        WindowBounds {
+                            x: value("X"),
+                            y: value("Y"),
+                            width: value("Width").max(0) as u32,
+                            height: value("Height").max(0) as u32,
+                        }
+                    })
+                    .unwrap_or(WindowBounds {
+                        x: 0,
+                        y: 0,
+                        width: 0,
+                        height: 0,
+                    });
+                WindowEntry {
+                    window_id: number("kCGWindowNumber").map_or(0, |n| n as u32),
+                    owner_pid: number("kCGWindowOwnerPID"),
+                    owner_name: string("kCGWindowOwnerName")
+                        .unwrap_or_else(|| "Unknown".to_string()),
+                    title: string("kCGWindowName").unwrap_or_default(),
+                    bounds,
+                    layer: number("kCGWindowLayer").unwrap_or(0),
+                    alpha: cf_number("kCGWindowAlpha")
+                        .and_then(|n| n.to_f64())
+                        .map_or(1.0, |a| a as f32),
+                }
+            })
+            .collect();
+        Ok(Self::from_windows(windows))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn capture() -> Result<Self, WindowError> {
+        Ok(Self::default())
+    }
+
+    pub fn windows(&self) -> &[WindowEntry] {
+        &self.windows
+    }
+
+    /// Layer of a window. None for windows missing from the list, e.g. off screen or
+    /// desktop-level ones.
+    pub fn layer_of(&self, window_id: u32) -> Option<i32> {
+        self.windows
+            .iter()
+            .find(|w| w.window_id == window_id)
+            .map(|w| w.layer)
+    }
+
+    /// Executable name of the window's process (language-independent), else the
+    /// localized owner name.
+    fn app_name(&self, window: &WindowEntry) -> String {
+        let process_name = window.owner_pid.and_then(|pid| {
+            self.process_names
+                .borrow_mut()
+                .entry(pid)
+                .or_insert_with(|| (self.process_name)(pid))
+                .clone()
+        });
+        process_name.unwrap_or_else(|| window.owner_name.clone())
+    }
+
+    /// The largest window of `pid`: the app's main window rather than a sheet or panel
+    /// on top of it.
+    pub fn main_window_of(&self, pid: i32, app_name: &str) -> Option<WindowInfo> {
+        let mut best: Option<&WindowEntry> = None;
+        let mut best_area: u64 = 0;
+        for window in &self.windows {
+            if window.owner_pid.is_some_and(|owner| owner != pid) || window.window_id == 0 {
+                continue;
+            }
+            let area = window.bounds.width as u64 * window.bounds.height as u64;
+            if area > best_area {
+                best_area = area;
+                best = Some(window);
+            }
+        }
+        best.map(|window| WindowInfo {
+            app_name: app_name.to_string(),
+            window_title: window.title.clone(),
+            window_id: window.window_id,
+            bounds: window.bounds.clone(),
+        })
+    }
+
+    /// Main (largest) window of the frontmost app. Falls back to the app without a
+    /// window when none is listed.
+    #[cfg(target_os = "macos")]
+    pub fn frontmost_window(&self) -> Result<WindowInfo, WindowError> {
+        use objc2_app_kit::NSWorkspace;
+
+        let workspace = NSWorkspace::sharedWorkspace();
+        let frontmost = workspace
+            .frontmostApplication()
+            .ok_or(WindowError::NoFrontmostApp)?;
+        let app_name = frontmost
+            .localizedName()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let pid = frontmost.processIdentifier();
+
+        if let Some(window) = self.main_window_of(pid, &app_name) {
+            if cfg!(debug_assertions) {
+                eprintln!(
+                    "Main window: '{}' id={} bounds=({}, {}, {}x{})",
+                    window.window_title,
+                    window.window_id,
+                    window.bounds.x,
+                    window.bounds.y,
+                    window.bounds.width,
+                    window.bounds.height
+                );
+            }
+            return Ok(window);
+        }
+        Ok(WindowInfo {
+            app_name,
+            window_title: String::new(),
+            window_id: 0,
+            bounds: WindowBounds {
+                x: 0,
+                y: 0,
+                width: 800,
+                height: 600,
+            },
+        })
+    }
+
+    /// Topmost window at the point across all apps, so popups, context menus and other
+    /// overlays are found. Desktop-level windows and system UI (Dock, Spotlight) are
+    /// skipped: their full-screen overlays would shadow the real windows beneath.
+    pub fn topmost_window_at_point(&self, click_x: i32, click_y: i32) -> Option<WindowInfo> {
+        for window in &self.windows {
+            if window.layer < 0 || window.bounds.width < 10 || window.bounds.height < 10 {
+                continue;
+            }
+            if !window.contains(click_x, click_y) || window.window_id == 0 {
+                continue;
+            }
+            let app_name = self.app_name(window);
+            let bounds = &window.bounds;
+            if super::super::ax_helpers::is_system_ui_process(&app_name) {
+                if cfg!(debug_assertions) {
+                    eprintln!(
+                        "Skipping system UI window at click: '{app_name}' id={} layer={} bounds=({}, {}, {}x{})",
+                        window.window_id, window.layer, bounds.x, bounds.y, bounds.width, bounds.height
+                    );
+                }
+                continue;
+            }
+            if cfg!(debug_assertions) {
+                eprintln!(
+                    "Topmost window at click: '{app_name}' - '{}' id={} layer={} bounds=({}, {}, {}x{})",
+                    window.title, window.window_id, window.layer, bounds.x, bounds.y, bounds.width, bounds.height
+                );
+            }
+            return Some(WindowInfo {
+                app_name,
+                window_title: window.title.clone(),
+                window_id: window.window_id,
+                bounds: window.bounds.clone(),
+            });
+        }
+        None
+    }
+
+    /// Attached dialog or sheet at the point: smaller than `main_window` and mostly on
+    /// top of it, owned by `expected_owner` or else by the main window's app.
+    pub fn attached_dialog_window(
+        &self,
+        click_x: i32,
+        click_y: i32,
+        main_window: &WindowInfo,
+        expected_owner: Option<(i32, &str)>,
+    ) -> Option<WindowInfo> {
+        let main = &main_window.bounds;
+        let main_area = (main.width as i64) * (main.height as i64);
+        let main_left = main.x;
+        let main_top = main.y;
+        let main_right = main.x + main.width as i32;
+        let main_bottom = main.y + main.height as i32;
+        let expected_owner_pid = expected_owner.map(|(pid, _)| pid);
+        let expected_owner_name = expected_owner.map(|(_, name)| name);
+        let main_owner_pid = self
+            .windows
+            .iter()
+            .find(|w| w.window_id == main_window.window_id)
+            .and_then(|w| w.owner_pid);
+
+        for window in &self.windows {
+            let bounds = &window.bounds;
+            if bounds.width < 50 || bounds.height < 20 || !window.contains(click_x, click_y) {
+                continue;
+            }
+            if window.window_id == 0 || window.window_id == main_window.window_id {
+                continue;
+            }
+
+            let area = (bounds.width as i64) * (bounds.height as i64);
+            if area >= main_area {
+                continue;
+            }
+            let area_ratio = if main_area > 0 {
+                area as f32 / main_area as f32
+            } else {
+                0.0
+            };
+            if !(0.04..0.95).contains(&area_ratio) {
+                continue;
+            }
+
+            let right = bounds.x + bounds.width as i32;
+            let bottom = bounds.y + bounds.height as i32;
+            let inter_w = (right.min(main_right) - bounds.x.max(main_left)).max(0) as i64;
+            let inter_h = (bottom.min(main_bottom) - bounds.y.max(main_top)).max(0) as i64;
+            let overlap_ratio = if area > 0 {
+                (inter_w * inter_h) as f32 / area as f32
+            } else {
+                0.0
+            };
+            let contained = bounds.x >= main_left - 6
+                && bounds.y >= main_top - 6
+                && right <= main_right + 6
+                && bottom <= main_bottom + 6;
+            if overlap_ratio < 0.6 && !contained {
+                continue;
+            }
+
+            let app_name = self.app_name(window);
+            if super::super::ax_helpers::is_system_ui_process(&app_name) {
+                continue;
+            }
+            if let Some(pid) = expected_owner_pid.or(main_owner_pid) {
+                if window.owner_pid != Some(pid) {
+                    continue;
+                }
+            } else if let Some(name) = expected_owner_name {
+                if !app_names_match(&app_name, name) {
+                    continue;
+                }
+            } else if !app_names_match(&app_name, &main_window.app_name) {
+                continue;
+            }
+
+            return Some(WindowInfo {
+                app_name,
+                window_title: window.title.clone(),
+                window_id: window.window_id,
+                bounds: window.bounds.clone(),
+            });
+        }
+        None
+    }
+
+    /// Largest window of `pid` containing the point, skipping `exclude_window_id`. For
+    /// apps with several windows, where the click's own window is the better capture.
+    pub fn window_for_pid_at_click(
+        &self,
+        pid: i32,
+        app_name: &str,
+        click_x: i32,
+        click_y: i32,
+        exclude_window_id: Option<u32>,
+    ) -> Option<WindowInfo> {
+        self.windows
+            .iter()
+            .filter(|w| w.owner_pid == Some(pid))
+            .filter(|w| w.bounds.width >= 10 && w.bounds.height >= 10)
+            .filter(|w| w.contains(click_x, click_y))
+            .filter(|w| w.window_id != 0 && exclude_window_id != Some(w.window_id))
+            .fold(None::<&WindowEntry>, |best, w| {
+                let area = |w: &WindowEntry| w.bounds.width as u64 * w.bounds.height as u64;
+                match best {
+                    Some(b) if area(b) >= area(w) => Some(b),
+                    _ => Some(w),
+                }
+            })
+            .map(|window| WindowInfo {
+                app_name: app_name.to_string(),
+                window_title: window.title.clone(),
+                window_id: window.window_id,
+                bounds: window.bounds.clone(),
+            })
+    }
+
+    /// System authentication dialog (Touch ID / SecurityAgent): the highest-layer
+    /// window of such a process, the largest on ties.
+    pub fn security_agent_window(&self) -> Option<WindowInfo> {
+        let best = self
+            .windows
+            .iter()
+            .filter(|w| w.window_id != 0 && is_security_agent_name(&w.owner_name))
+            .filter(|w| w.bounds.width > 0 && w.bounds.height > 0)
+            .fold(None::<&WindowEntry>, |best, w| {
+                let area = |w: &WindowEntry| w.bounds.width as u64 * w.bounds.height as u64;
+                match best {
+                    Some(b) if (b.layer, area(b)) >= (w.layer, area(w)) => Some(b),
+                    _ => Some(w),
+                }
+            })?;
+        if cfg!(debug_assertions) {
+            eprintln!(
+                "Found security agent window: '{}' id={} bounds=({}, {}, {}x{})",
+                best.owner_name,
+                best.window_id,
+                best.bounds.x,
+                best.bounds.y,
+                best.bounds.width,
+                best.bounds.height
+            );
+        }
+        Some(WindowInfo {
+            app_name: best.owner_name.clone(),
+            window_title: best.title.clone(),
+            window_id: best.window_id,
+            bounds: best.bounds.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+impl WindowSnapshot {
+    /// Front to back: a Dock overlay, a Finder sheet, the Finder window, a Safari
+    /// window behind it and a SecurityAgent prompt.
+    pub fn sample() -> Self {
+        let window =
+            |window_id: u32, pid: i32, owner: &str, bounds: (i32, i32, u32, u32)| WindowEntry {
+                window_id,
+                owner_pid: Some(pid),
+                owner_name: owner.to_string(),
+                title: String::new(),
+                bounds: WindowBounds {
+                    x: bounds.0,
+                    y: bounds.1,
+                    width: bounds.2,
+                    height: bounds.3,
+                },
+                layer: 0,
+                alpha: 1.0,
+            };
+        let mut dock = window(1, 10, "Dock", (0, 0, 1440, 900));
+        dock.layer = 20;
+        let mut finder = window(3, 20, "Finder", (100, 100, 800, 600));
+        finder.title = "Downloads".into();
+        let mut agent = window(5, 40, "SecurityAgent", (500, 300, 400, 250));
+        agent.layer = 1000;
+        let mut snapshot = Self::from_windows(vec![
+            dock,
+            window(2, 20, "Finder", (300, 100, 400, 300)),
+            finder,
+            window(4, 30, "Safari", (0, 0, 1200, 850)),
+            agent,
+        ]);
+        // Owner names stand in for process names; tests never spawn `ps`.
+        snapshot.process_name = |_| None;
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups_resolve_windows_from_the_list() {
+        let windows = WindowSnapshot::sample();
+
+        let main = windows.main_window_of(20, "Finder").expect("main window");
+        assert_eq!(main.window_id, 3);
+        let topmost = windows.topmost_window_at_point(350, 150).expect("topmost");
+        assert_eq!(
+            (topmost.window_id, topmost.app_name.as_str()),
+            (2, "Finder")
+        );
+        let sheet = windows
+            .attached_dialog_window(350, 150, &main, None)
+            .expect("sheet");
+        assert_eq!(sheet.window_id, 2);
+        let clicked = windows
+            .window_for_pid_at_click(30, "Safari", 50, 50, None)
+            .expect("Safari window");
+        assert_eq!(clicked.window_id, 4);
+        let agent = windows.security_agent_window().expect("security agent");
+        assert_eq!(agent.window_id, 5);
+        assert_eq!(windows.layer_of(1), Some(20));
+        assert_eq!(windows.layer_of(99), None);
+    }
+
+    #[test]
+    fn topmost_skips_system_ui_and_misses_outside_every_window() {
+        let windows = WindowSnapshot::sample();
+        // Only the Dock overlay and Safari cover this point.
+        let topmost = windows.topmost_window_at_point(50, 50).expect("topmost");
+        assert_eq!(topmost.window_id, 4);
+        assert!(windows.topmost_window_at_point(1300, 880).is_none());
+    }
+
+    #[test]
+    fn attached_dialog_must_belong_to_the_expected_owner() {
+        let windows = WindowSnapshot::sample();
+        let main = windows.main_window_of(20, "Finder").unwrap();
+        assert!(windows
+            .attached_dialog_window(350, 150, &main, Some((30, "Safari")))
+            .is_none());
+        // Outside the sheet: nothing attached there.
+        assert!(windows
+            .attached_dialog_window(850, 650, &main, None)
+            .is_none());
+    }
+}
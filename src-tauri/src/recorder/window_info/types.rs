@@ -18,7 +18,7 @@ impl fmt::Display for WindowError {
 
 impl std::error::Error for WindowError {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WindowBounds {
     pub x: i32,
     pub y: i32,
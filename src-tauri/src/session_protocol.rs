@@ -0,0 +1,165 @@
+//! `stepcast-session://` URLs for files of the active recording session. The export
+//! preview links screenshots through it instead of inlining them as data URIs.
+//!
+//! Only regular files inside the session directory are served; anything else,
+//! including `..` segments and symlinks pointing out of it, is a 404.
+
+use std::path::{Component, Path, PathBuf};
+use tauri::http::{Response, StatusCode};
+
+pub const SCHEME: &str = "stepcast-session";
+
+/// URL for `path`, or `None` when it is not inside `session_dir`.
+pub fn url_for(session_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(session_dir).ok()?;
+    let segments = relative
+        .components()
+        .map(|component| match component {
+            Component::Normal(name) => name.to_str().map(encode_segment),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if segments.is_empty() {
+        return None;
+    }
+    Some(format!("{SCHEME}://localhost/{}", segments.join("/")))
+}
+
+/// Serve the file `url_path` names from `session_dir`.
+pub fn respond(session_dir: Option<&Path>, url_path: &str) -> Response<Vec<u8>> {
+    let file = session_dir
+        .and_then(|dir| resolve(dir, url_path))
+        .and_then(|path| std::fs::read(&path).ok().map(|bytes| (path, bytes)));
+    let Some((path, bytes)) = file else {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Vec::new())
+            .unwrap_or_default();
+    };
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type(&path))
+        .body(bytes)
+        .unwrap_or_default()
+}
+
+/// Map a URL path to a file inside `session_dir`, refusing anything that would leave it.
+fn resolve(session_dir: &Path, url_path: &str) -> Option<PathBuf> {
+    let mut path = session_dir.to_path_buf();
+    for segment in url_path.split('/').filter(|s| !s.is_empty()) {
+        let segment = decode_segment(segment)?;
+        if segment == "." || segment == ".." || segment.contains(['/', '\\', '\0']) {
+            return None;
+        }
+        path.push(segment);
+    }
+    // Symlinks could still point elsewhere; compare the real locations.
+    let real = path.canonicalize().ok()?;
+    let root = session_dir.canonicalize().ok()?;
+    (real.starts_with(&root) && real != root && real.is_file()).then_some(real)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Percent-encode everything but unreserved URL characters.
+fn encode_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn decode_segment(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with_file() -> (tempfile::TempDir, PathBuf) {
+        let root = tempfile::tempdir().expect("tempdir");
+        let session = root.path().join("session");
+        std::fs::create_dir_all(session.join("shots")).unwrap();
+        std::fs::write(session.join("shots").join("step 1.png"), b"png").unwrap();
+        std::fs::write(root.path().join("secret.txt"), b"secret").unwrap();
+        (root, session)
+    }
+
+    #[test]
+    fn urls_round_trip_to_session_files() {
+        let (_root, session) = session_with_file();
+        let file = session.join("shots").join("step 1.png");
+
+        let url = url_for(&session, &file).expect("inside session");
+        assert_eq!(url, "stepcast-session://localhost/shots/step%201.png");
+        let path = url.trim_start_matches("stepcast-session://localhost");
+        assert_eq!(resolve(&session, path), file.canonicalize().ok());
+
+        let response = respond(Some(&session), path);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["Content-Type"], "image/png");
+        assert_eq!(response.body(), b"png");
+
+        assert_eq!(url_for(&session, Path::new("/elsewhere/step-1.png")), None);
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_session() {
+        let (_root, session) = session_with_file();
+        for path in [
+            "/../secret.txt",
+            "/shots/../../secret.txt",
+            "/%2E%2E/secret.txt",
+            "/..%2Fsecret.txt",
+            "/shots",
+            "/",
+            "/missing.png",
+            "/bad%zz.png",
+        ] {
+            assert_eq!(resolve(&session, path), None, "{path}");
+        }
+        assert_eq!(
+            respond(Some(&session), "/../secret.txt").status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            respond(None, "/shots/step%201.png").status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_leaving_the_session() {
+        let (root, session) = session_with_file();
+        std::os::unix::fs::symlink(root.path().join("secret.txt"), session.join("link.png"))
+            .unwrap();
+        assert_eq!(resolve(&session, "/link.png"), None);
+    }
+}
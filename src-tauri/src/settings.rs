@@ -0,0 +1,211 @@
+//! Persisted recorder tunables, so they survive restarts. `start_recording` seeds the
+//! pipeline state and capture options from them; its arguments still override them
+//! for a single recording.
+
+use crate::export::branding::ExportBranding;
+use crate::export::upload::PostExportWebhook;
+use crate::export::{ExportImageFormat, DEFAULT_EXPORT_MAX_DIMENSION};
+use crate::recorder::exclusions::CaptureExclusions;
+use crate::recorder::idle_timeout::DEFAULT_IDLE_TIMEOUT;
+use crate::recorder::pipeline::{AUTH_DIALOG_COOLDOWN_MS, AUTH_PROMPT_DEDUP_MS, DEBOUNCE_MS};
+use crate::recorder::storage::{
+    DEFAULT_MAX_SCREENSHOT_EDGE_PX, DEFAULT_MAX_SESSION_BYTES, DEFAULT_SESSION_CACHE_BYTES,
+    DEFAULT_SESSION_MAX_AGE,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Global shortcut that captures the element under the pointer while recording.
+pub const DEFAULT_HOVER_SHORTCUT: &str = "Cmd+Shift+D";
+
+/// Every field falls back to its default, so files written by older versions load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderSettings {
+    /// Repeated clicks at the same spot within this many ms are dropped.
+    pub debounce_ms: i64,
+    /// Clicks this soon after an auth dialog click are treated as phantom clicks.
+    pub auth_dialog_cooldown_ms: i64,
+    /// Same-window auth prompts within this window are not recorded again.
+    pub auth_prompt_dedup_ms: i64,
+    /// Crop new steps to the clicked element's surroundings.
+    pub auto_crop: bool,
+    /// Show the mouse pointer in screenshots.
+    pub include_cursor: bool,
+    /// Default encoding of exported screenshots.
+    pub image_format: ExportImageFormat,
+    /// Exported images with a longer edge, after cropping, are downscaled to it; 0
+    /// exports them at full size.
+    pub export_max_edge_px: u32,
+    /// Screenshots with a longer edge are downscaled to it when stored.
+    pub max_screenshot_edge_px: u32,
+    /// Recording pauses once a session's screenshots take more than this.
+    pub storage_cap_mb: u64,
+    /// Finished sessions untouched for this many days are deleted at startup; 0 keeps them.
+    pub session_max_age_days: u32,
+    /// Finished sessions beyond this total size are deleted at startup, least recently
+    /// used first; 0 never deletes for size.
+    pub session_cache_mb: u64,
+    /// Stop a recording after this many minutes without clicks; 0 never stops.
+    pub idle_timeout_min: u32,
+    /// Insert "Now in: …" section breaks when the window title changes.
+    pub split_on_context_change: bool,
+    /// Blur detected text in new screenshots.
+    pub privacy_blur_text: bool,
+    /// App names or bundle IDs whose clicks are never recorded.
+    pub blocked_apps: Vec<String>,
+    /// Also pause the recording when a click lands in a blocked app.
+    pub pause_on_blocked_app: bool,
+    /// Shortcut that records a hover step at the pointer, for tooltips and hover
+    /// states; empty turns it off. Takes effect after a restart.
+    pub hover_shortcut: String,
+    /// Capture clicked windows whole, including parts beyond the display, instead of
+    /// what was visible on screen.
+    pub prefer_full_window_capture: bool,
+    /// Upload every finished export to this endpoint; off when unset.
+    pub post_export_webhook: Option<PostExportWebhook>,
+    /// Logo, colors, footer and font of HTML and PDF exports.
+    pub export_branding: ExportBranding,
+    /// Screen areas blacked out or blurred in every screenshot, e.g. an overlay that
+    /// should never appear in guides.
+    pub capture_exclusions: CaptureExclusions,
+}
+
+impl Default for RecorderSettings {
+    fn default() -> Self {
+        Self {
+            debounce_ms: DEBOUNCE_MS,
+            auth_dialog_cooldown_ms: AUTH_DIALOG_COOLDOWN_MS,
+            auth_prompt_dedup_ms: AUTH_PROMPT_DEDUP_MS,
+            auto_crop: true,
+            include_cursor: false,
+            image_format: ExportImageFormat::Auto,
+            export_max_edge_px: DEFAULT_EXPORT_MAX_DIMENSION,
+            max_screenshot_edge_px: DEFAULT_MAX_SCREENSHOT_EDGE_PX,
+            storage_cap_mb: DEFAULT_MAX_SESSION_BYTES / (1024 * 1024),
+            session_max_age_days: (DEFAULT_SESSION_MAX_AGE.as_secs() / (24 * 60 * 60)) as u32,
+            session_cache_mb: DEFAULT_SESSION_CACHE_BYTES / (1024 * 1024),
+            idle_timeout_min: (DEFAULT_IDLE_TIMEOUT.as_secs() / 60) as u32,
+            split_on_context_change: false,
+            privacy_blur_text: false,
+            blocked_apps: Vec::new(),
+            pause_on_blocked_app: false,
+            hover_shortcut: DEFAULT_HOVER_SHORTCUT.to_string(),
+            prefer_full_window_capture: false,
+            post_export_webhook: None,
+            export_branding: ExportBranding::default(),
+            capture_exclusions: CaptureExclusions::default(),
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("com.w0nk1.stepcast").join("recorder_settings.json"))
+}
+
+pub fn load() -> RecorderSettings {
+    settings_path()
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+pub fn save(settings: &RecorderSettings) -> Result<(), String> {
+    let path = settings_path().ok_or("config dir not found")?;
+    save_to(&path, settings)
+}
+
+/// Missing or unreadable files load as defaults.
+fn load_from(path: &Path) -> RecorderSettings {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => RecorderSettings::default(),
+    }
+}
+
+fn save_to(path: &Path, settings: &RecorderSettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    // The webhook's auth header is a credential: keep the file private like the
+    // description settings.
+    #[cfg(unix)]
+    if settings.post_export_webhook.is_some() {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_built_in_values() {
+        let settings = RecorderSettings::default();
+        assert_eq!(settings.debounce_ms, 150);
+        assert_eq!(settings.auth_dialog_cooldown_ms, AUTH_DIALOG_COOLDOWN_MS);
+        assert!(settings.auto_crop);
+        assert!(!settings.include_cursor);
+        assert_eq!(settings.image_format, ExportImageFormat::Auto);
+        assert_eq!(settings.idle_timeout_min, 15);
+        assert_eq!(
+            settings.storage_cap_mb * 1024 * 1024,
+            DEFAULT_MAX_SESSION_BYTES
+        );
+    }
+
+    #[test]
+    fn save_load_roundtrip() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("nested").join("recorder_settings.json");
+        let settings = RecorderSettings {
+            debounce_ms: 300,
+            auto_crop: false,
+            include_cursor: true,
+            image_format: ExportImageFormat::WebP,
+            idle_timeout_min: 0,
+            blocked_apps: vec!["1Password".into(), "com.example.bank".into()],
+            capture_exclusions: CaptureExclusions {
+                regions: vec![crate::recorder::window_info::WindowBounds {
+                    x: 1380,
+                    y: 0,
+                    width: 300,
+                    height: 40,
+                }],
+                blur: true,
+            },
+            ..RecorderSettings::default()
+        };
+
+        save_to(&path, &settings).expect("save");
+        assert_eq!(load_from(&path), settings);
+        let json = std::fs::read_to_string(&path).expect("read");
+        assert!(json.contains(r#""image_format": "webp""#));
+    }
+
+    #[test]
+    fn old_file_fills_in_new_fields() {
+        let settings: RecorderSettings =
+            serde_json::from_str(r#"{"debounce_ms": 80}"#).expect("deserialize");
+        assert_eq!(
+            settings,
+            RecorderSettings {
+                debounce_ms: 80,
+                ..RecorderSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn missing_or_corrupt_file_loads_defaults() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("recorder_settings.json");
+        assert_eq!(load_from(&path), RecorderSettings::default());
+
+        std::fs::write(&path, "not valid json").expect("write corrupt file");
+        assert_eq!(load_from(&path), RecorderSettings::default());
+    }
+}
@@ -1,7 +1,8 @@
 use std::path::PathBuf;
+use std::sync::Mutex;
 use std::time::Duration;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{IconMenuItem, Menu, MenuItem, PredefinedMenuItem};
 use tauri::path::BaseDirectory;
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent, TrayIconId};
 use tauri::{AppHandle, Emitter, Manager};
@@ -10,9 +11,33 @@ use tauri_nspanel::ManagerExt;
 use crate::panel::TrayIconMetrics;
 use crate::panel::{panel_label, position_panel_at_tray_icon};
 use crate::recorder::pipeline::PanelRect;
+use crate::recorder::state::SessionState;
 
 const TRAY_ID: &str = "tray";
 
+/// Longest edge of the last-step preview next to the tray status line, in pixels.
+const THUMBNAIL_MAX_EDGE: u32 = 64;
+
+/// Tray menu items only shown while a session runs. Managed as app state by `create`.
+struct RecordingMenu {
+    menu: Menu<tauri::Wry>,
+    status: IconMenuItem<tauri::Wry>,
+    pause_resume: MenuItem<tauri::Wry>,
+    stop: MenuItem<tauri::Wry>,
+    separator: PredefinedMenuItem<tauri::Wry>,
+    shown: Mutex<bool>,
+    /// Screenshot the status item's preview was made from, so it's only decoded once.
+    thumbnail_path: Mutex<Option<String>>,
+}
+
+/// What the tray shows for a running session.
+struct RecordingStatus {
+    paused: bool,
+    step_count: usize,
+    recorded: Duration,
+    last_screenshot: Option<String>,
+}
+
 macro_rules! get_or_init_panel {
     ($app_handle:expr) => {{
         let label = panel_label();
@@ -36,6 +61,30 @@ macro_rules! get_or_init_panel {
     }};
 }
 
+/// Time AppKit may take to apply a new panel frame after showing or moving it.
+const PANEL_SETTLE_DELAY: Duration = Duration::from_millis(120);
+
+/// Store the panel's frame for filtering clicks on it once the panel settled. A frame
+/// read back off screen is not stored, so it can't swallow clicks next session.
+pub fn record_panel_bounds_when_settled(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(PANEL_SETTLE_DELAY);
+        let app_handle_inner = app_handle.clone();
+        let _ = app_handle.run_on_main_thread(move || {
+            match crate::panel::on_screen_panel_bounds(&app_handle_inner) {
+                Ok(bounds) => {
+                    let ps = &app_handle_inner
+                        .state::<crate::RecorderAppState>()
+                        .pipeline_state;
+                    crate::recorder::pipeline::record_panel_bounds(ps, bounds);
+                }
+                Err(err) => eprintln!("Panel bounds not recorded: {err}"),
+            }
+        });
+    });
+}
+
 /// Show the panel positioned at the tray icon. Used by tray menu and global shortcut.
 pub fn show_panel(app_handle: &AppHandle) {
     let Some(panel) = get_or_init_panel!(app_handle) else {
@@ -50,10 +99,7 @@ pub fn show_panel(app_handle: &AppHandle) {
         }
     }
     let _ = app_handle.emit("panel-positioned", !is_fallback);
-    if let Ok(bounds) = crate::panel::panel_bounds(app_handle) {
-        let ps = &app_handle.state::<crate::RecorderAppState>().pipeline_state;
-        crate::recorder::pipeline::record_panel_bounds(ps, bounds);
-    }
+    record_panel_bounds_when_settled(app_handle);
     let ps = &app_handle.state::<crate::RecorderAppState>().pipeline_state;
     crate::recorder::pipeline::set_panel_visible(ps, true);
 }
@@ -151,8 +197,8 @@ fn should_hide_panel(
     aligned_x && aligned_y
 }
 
-/// Set tray to recording state with red recording icon
-pub fn set_recording_icon(app_handle: &AppHandle) -> tauri::Result<()> {
+/// Set tray to recording state with red recording icon, or its orange paused variant
+fn set_recording_icon(app_handle: &AppHandle, paused: bool) -> tauri::Result<()> {
     let tray = app_handle
         .tray_by_id(&TrayIconId::new(TRAY_ID))
         .ok_or_else(|| {
@@ -163,9 +209,12 @@ pub fn set_recording_icon(app_handle: &AppHandle) -> tauri::Result<()> {
         })?;
 
     // Load recording icon
-    let icon_path = app_handle
-        .path()
-        .resolve("icons/recording.png", BaseDirectory::Resource)?;
+    let rel = if paused {
+        "icons/paused.png"
+    } else {
+        "icons/recording.png"
+    };
+    let icon_path = app_handle.path().resolve(rel, BaseDirectory::Resource)?;
     let icon = Image::from_path(icon_path)?;
 
     tray.set_icon(Some(icon))?;
@@ -176,7 +225,7 @@ pub fn set_recording_icon(app_handle: &AppHandle) -> tauri::Result<()> {
 }
 
 /// Reset tray to default state
-pub fn set_default_icon(app_handle: &AppHandle) -> tauri::Result<()> {
+fn set_default_icon(app_handle: &AppHandle) -> tauri::Result<()> {
     let tray = app_handle
         .tray_by_id(&TrayIconId::new(TRAY_ID))
         .ok_or_else(|| {
@@ -197,6 +246,141 @@ pub fn set_default_icon(app_handle: &AppHandle) -> tauri::Result<()> {
     Ok(())
 }
 
+fn recording_status(app_handle: &AppHandle) -> Option<RecordingStatus> {
+    let state = app_handle.state::<crate::RecorderAppState>();
+    let (paused, recorded) = {
+        let recorder = state.recorder_state.lock().ok()?;
+        let paused = match recorder.current_state() {
+            SessionState::Recording => false,
+            SessionState::Paused => true,
+            SessionState::Idle | SessionState::Stopped => return None,
+        };
+        (paused, recorder.recorded_time())
+    };
+    let session_lock = state.session.lock().ok()?;
+    let steps = session_lock.as_ref().map(|s| s.get_steps()).unwrap_or(&[]);
+    Some(RecordingStatus {
+        paused,
+        step_count: steps.len(),
+        recorded,
+        last_screenshot: steps.iter().rev().find_map(|s| s.screenshot_path.clone()),
+    })
+}
+
+/// Downscale a screenshot for the tray menu.
+fn load_thumbnail(path: &str) -> Option<Image<'static>> {
+    let thumbnail = image::open(path)
+        .inspect_err(|e| eprintln!("Tray preview of {path} failed: {e}"))
+        .ok()?
+        .thumbnail(THUMBNAIL_MAX_EDGE, THUMBNAIL_MAX_EDGE)
+        .to_rgba8();
+    let (width, height) = thumbnail.dimensions();
+    Some(Image::new_owned(thumbnail.into_raw(), width, height))
+}
+
+/// Bring the tray icon, tooltip and recording menu items in line with the recorder
+/// state: step count, recorded time and a preview of the last capture while a session
+/// runs, the default menu otherwise. Callable from any thread; the UI changes run on
+/// the main thread.
+pub fn refresh_recording_status(app_handle: &AppHandle) {
+    let Some(menu) = app_handle.try_state::<RecordingMenu>() else {
+        return;
+    };
+    let status = recording_status(app_handle);
+    // Decode here rather than on the main thread; `Some(None)` clears the preview.
+    let thumbnail = menu.thumbnail_path.lock().ok().and_then(|mut shown| {
+        let latest = status.as_ref().and_then(|s| s.last_screenshot.clone());
+        if *shown == latest {
+            return None;
+        }
+        let image = latest.as_deref().and_then(load_thumbnail);
+        *shown = latest;
+        Some(image)
+    });
+
+    let app = app_handle.clone();
+    let _ = app_handle.run_on_main_thread(move || {
+        if let Err(e) = apply_recording_status(&app, status, thumbnail) {
+            eprintln!("Failed to update tray: {e}");
+        }
+    });
+}
+
+fn apply_recording_status(
+    app_handle: &AppHandle,
+    status: Option<RecordingStatus>,
+    thumbnail: Option<Option<Image<'static>>>,
+) -> tauri::Result<()> {
+    let menu = app_handle.state::<RecordingMenu>();
+    let Ok(mut shown) = menu.shown.lock() else {
+        return Ok(());
+    };
+    if let Some(image) = thumbnail {
+        menu.status.set_icon(image)?;
+    }
+    let Some(status) = status else {
+        if *shown {
+            menu.menu.remove(&menu.status)?;
+            menu.menu.remove(&menu.pause_resume)?;
+            menu.menu.remove(&menu.stop)?;
+            menu.menu.remove(&menu.separator)?;
+            *shown = false;
+        }
+        return set_default_icon(app_handle);
+    };
+
+    set_recording_icon(app_handle, status.paused)?;
+    let locale = crate::i18n::system_locale();
+    let text = crate::i18n::tray_recording_status(
+        locale,
+        status.paused,
+        status.step_count,
+        status.recorded,
+    );
+    if let Some(tray) = app_handle.tray_by_id(&TrayIconId::new(TRAY_ID)) {
+        tray.set_tooltip(Some(format!("StepCast - {text}")))?;
+    }
+    menu.status.set_text(text)?;
+    menu.pause_resume.set_text(if status.paused {
+        crate::i18n::tray_menu_resume(locale)
+    } else {
+        crate::i18n::tray_menu_pause(locale)
+    })?;
+    if !*shown {
+        menu.menu.insert_items(
+            &[
+                &menu.status,
+                &menu.pause_resume,
+                &menu.stop,
+                &menu.separator,
+            ],
+            0,
+        )?;
+        *shown = true;
+    }
+    Ok(())
+}
+
+/// Pause or resume from the tray menu, through the same code as the panel's buttons.
+fn toggle_pause(app_handle: &AppHandle) {
+    let recording = app_handle
+        .state::<crate::RecorderAppState>()
+        .recorder_state
+        .lock()
+        .is_ok_and(|r| r.current_state() == SessionState::Recording);
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = if recording {
+            crate::pause_session(&app_handle)
+        } else {
+            crate::resume_session(&app_handle).await
+        };
+        if let Err(e) = result {
+            eprintln!("Tray pause/resume failed: {e}");
+        }
+    });
+}
+
 pub fn position_panel_at_current_tray_icon(app_handle: &AppHandle) -> Result<(), String> {
     let tray = app_handle
         .tray_by_id(&TrayIconId::new(TRAY_ID))
@@ -258,6 +442,34 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<()> {
         None::<&str>,
     )?;
     let menu = Menu::with_items(app_handle, &[&open, &quick_start, &sep, &quit])?;
+    app_handle.manage(RecordingMenu {
+        menu: menu.clone(),
+        status: IconMenuItem::with_id(
+            app_handle,
+            "recording_status",
+            "",
+            false,
+            None,
+            None::<&str>,
+        )?,
+        pause_resume: MenuItem::with_id(
+            app_handle,
+            "pause_resume",
+            crate::i18n::tray_menu_pause(locale),
+            true,
+            None::<&str>,
+        )?,
+        stop: MenuItem::with_id(
+            app_handle,
+            "stop",
+            crate::i18n::tray_menu_stop(locale),
+            true,
+            None::<&str>,
+        )?,
+        separator: PredefinedMenuItem::separator(app_handle)?,
+        shown: Mutex::new(false),
+        thumbnail_path: Mutex::new(None),
+    });
 
     TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
@@ -272,6 +484,12 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<()> {
                     show_panel(app_handle);
                     let _ = app_handle.emit("show-quick-start", ());
                 }
+                "pause_resume" => toggle_pause(app_handle),
+                "stop" => {
+                    if let Err(e) = crate::stop_session(app_handle, None) {
+                        eprintln!("Tray stop failed: {e}");
+                    }
+                }
                 "quit" => app_handle.exit(0),
                 _ => {}
             }
@@ -371,10 +589,7 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<()> {
                                         );
                                     }
                                 }
-                                if let Ok(bounds) = crate::panel::panel_bounds(&app_handle_inner) {
-                                    let ps_inner = &app_handle_inner.state::<crate::RecorderAppState>().pipeline_state;
-                                    crate::recorder::pipeline::record_panel_bounds(ps_inner, bounds);
-                                }
+                                record_panel_bounds_when_settled(&app_handle_inner);
                             });
                         });
                     }
@@ -386,9 +601,6 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<()> {
                             );
                         }
                     }
-                    if let Ok(bounds) = crate::panel::panel_bounds(app_handle) {
-                        crate::recorder::pipeline::record_panel_bounds(ps, bounds);
-                    }
                     crate::recorder::pipeline::set_panel_visible(ps, true);
                 }
             }
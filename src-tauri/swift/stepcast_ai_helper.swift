@@ -33,8 +33,13 @@ struct AxInfo: Codable {
   let parentDialogRole: String?
   let parentDialogSubrole: String?
   let isChecked: Bool?
+  let wasChecked: Bool?
+  let nowChecked: Bool?
+  let value: String?
+  let placeholder: String?
   let isCancelButton: Bool
   let isDefaultButton: Bool
+  let menuPath: String?
 }
 
 struct StepInput: Codable {
@@ -47,6 +52,7 @@ struct StepInput: Codable {
   let screenshotPath: String?
   let note: String?
   let ax: AxInfo?
+  let pageUrl: String?
 }
 
 struct GenerateRequest: Codable {
@@ -188,7 +194,9 @@ func checkAvailability() -> AvailabilityResponse {
 }
 #endif
 
-func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
+/// With `stream`, every result and failure is printed as its own JSON line as soon as it
+/// is ready, and the returned response is empty.
+func generateDescriptions(_ req: GenerateRequest, stream: Bool = false) async -> GenerateResponse {
   activeLocale = HelperLocale.fromAppLanguage(req.appLanguage)
   let maxChars = max(20, min(req.maxChars ?? 110, 140))
   let availability = checkAvailability()
@@ -202,6 +210,10 @@ func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
         )
       )
     }
+    if stream {
+      failures.forEach { writeStdout(encodeJSON($0)) }
+      return GenerateResponse(results: [], failures: [])
+    }
     return GenerateResponse(results: [], failures: failures)
   }
 
@@ -213,6 +225,13 @@ func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
   var results: [GenerateResultItem] = []
   let failures: [GenerateFailureItem] = []
   results.reserveCapacity(req.steps.count)
+  func emit(_ item: GenerateResultItem) {
+    if stream {
+      writeStdout(encodeJSON(item))
+    } else {
+      results.append(item)
+    }
+  }
 
   for step in req.steps {
     do {
@@ -231,7 +250,7 @@ func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
       if kind == "close button" || kind == "minimize button" || kind == "zoom button"
         || kind == "menu item" || kind == "menu bar item" || kind == "checkbox"
       {
-        results.append(GenerateResultItem(
+        emit(GenerateResultItem(
           id: step.id,
           text: baseline,
           debug: GenerateResultDebug(
@@ -274,7 +293,7 @@ func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
       let decision = (text: baseline, reason: "model_unavailable_fallback")
       let finalText = baseline
       #endif
-      results.append(GenerateResultItem(
+      emit(GenerateResultItem(
         id: step.id,
         text: finalText,
         debug: GenerateResultDebug(
@@ -299,7 +318,7 @@ func generateDescriptions(_ req: GenerateRequest) async -> GenerateResponse {
         location: location,
         maxChars: maxChars
       )
-      results.append(GenerateResultItem(
+      emit(GenerateResultItem(
         id: step.id,
         text: baseline,
         debug: GenerateResultDebug(
@@ -343,8 +362,17 @@ struct StepCastAIHelper {
         writeStdout(encodeJSON(resp))
         exit(2)
       }
-      let resp = await generateDescriptions(req)
-      writeStdout(encodeJSON(resp))
+      let stream = args.contains("--stream")
+      let resp = await generateDescriptions(req, stream: stream)
+      if !stream {
+        writeStdout(encodeJSON(resp))
+      }
+    case "text-regions":
+      guard args.count > 1 else {
+        writeStdout(encodeTextRegions(TextRegionsResponse(regions: [], error: "missing image path")))
+        exit(2)
+      }
+      writeStdout(encodeTextRegions(detectTextRegions(atPath: args[1])))
     default:
       writeStdout(Data("{}".utf8))
       exit(2)
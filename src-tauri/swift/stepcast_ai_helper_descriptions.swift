@@ -8,6 +8,8 @@ func actionVerb(_ action: String) -> String {
     return "Right-click"
   case "Shortcut":
     return "Press"
+  case "Drag":
+    return "Drag"
   case "Note":
     return "Add a note"
   default:
@@ -800,6 +802,10 @@ func classifyKind(_ step: StepInput) -> String {
 }
 
 func checkboxVerb(_ step: StepInput) -> String {
+  if let now = step.ax?.nowChecked {
+    // State read back after the click: what the user ended up with.
+    return now ? "Enable" : "Disable"
+  }
   if let checked = step.ax?.isChecked {
     // AXValue reflects current state at click-time; action semantics are state toggle.
     return checked ? "Disable" : "Enable"
@@ -810,7 +816,7 @@ func checkboxVerb(_ step: StepInput) -> String {
 func preferredVerb(_ step: StepInput, kind: String) -> String {
   let action = actionVerb(step.action)
   if isDockStep(step) { return "Open" }
-  if action == "Right-click" || action == "Double-click" || action == "Press" {
+  if action == "Right-click" || action == "Double-click" || action == "Press" || action == "Drag" {
     return action
   }
   switch kind {
@@ -1372,12 +1378,14 @@ func promptForStep(
   if !step.windowTitle.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty {
     lines.append(l("Window title: \(step.windowTitle)", "Fenstertitel: \(step.windowTitle)"))
   }
+  if let url = step.pageUrl { lines.append(l("Page URL: \(url)", "Seiten-URL: \(url)")) }
   if let ax = step.ax {
     lines.append(l("AX role: \(ax.role)", "AX-Rolle: \(ax.role)"))
     if let sub = ax.subrole { lines.append(l("AX subrole: \(sub)", "AX-Unterrolle: \(sub)")) }
     if let desc = ax.roleDescription { lines.append(l("AX role description: \(desc)", "AX-Rollenbeschreibung: \(desc)")) }
     if let ident = ax.identifier { lines.append(l("AX identifier: \(ident)", "AX-Identifier: \(ident)")) }
     lines.append(l("AX label: \(ax.label)", "AX-Label: \(ax.label)"))
+    if let menuPath = ax.menuPath { lines.append(l("Menu path: \(menuPath)", "Menüpfad: \(menuPath)")) }
     if let containerRole = ax.containerRole { lines.append(l("AX container role: \(containerRole)", "AX-Containerrolle: \(containerRole)")) }
     if let containerSub = ax.containerSubrole { lines.append(l("AX container subrole: \(containerSub)", "AX-Containerunterrolle: \(containerSub)")) }
     if let containerIdent = ax.containerIdentifier { lines.append(l("AX container identifier: \(containerIdent)", "AX-Container-Identifier: \(containerIdent)")) }
@@ -1388,6 +1396,9 @@ func promptForStep(
     if let dialogRole = ax.parentDialogRole { lines.append(l("AX dialog role: \(dialogRole)", "AX-Dialogrolle: \(dialogRole)")) }
     if let dialogSub = ax.parentDialogSubrole { lines.append(l("AX dialog subrole: \(dialogSub)", "AX-Dialogunterrolle: \(dialogSub)")) }
     if let checked = ax.isChecked { lines.append(l("AX checked: \(checked ? "true" : "false")", "AX aktiviert: \(checked ? "true" : "false")")) }
+    if let now = ax.nowChecked { lines.append(l("AX checked after click: \(now ? "true" : "false")", "AX aktiviert nach Klick: \(now ? "true" : "false")")) }
+    if let placeholder = ax.placeholder { lines.append(l("Field placeholder: \(placeholder)", "Feld-Platzhalter: \(placeholder)")) }
+    if let value = ax.value { lines.append(l("Field value: \(value)", "Feldinhalt: \(value)")) }
     if ax.isDefaultButton { lines.append(l("AX hint: default button", "AX-Hinweis: Standardbutton")) }
     if ax.isCancelButton { lines.append(l("AX hint: cancel button", "AX-Hinweis: Abbrechen-Button")) }
   }
@@ -0,0 +1,52 @@
+import Foundation
+import Vision
+
+/// Text rectangle in screenshot percent (origin top-left), matching Rust's `BoundsPercent`.
+struct TextRegion: Codable {
+  let xPercent: Double
+  let yPercent: Double
+  let widthPercent: Double
+  let heightPercent: Double
+}
+
+struct TextRegionsResponse: Codable {
+  let regions: [TextRegion]
+  let error: String?
+}
+
+/// Detect every text line in the image for the "blur all text" privacy mode.
+/// Uses the fast recognizer: only the boxes are needed, not the strings.
+func detectTextRegions(atPath path: String) -> TextRegionsResponse {
+  guard let image = loadCGImage(atPath: path) else {
+    return TextRegionsResponse(regions: [], error: "could not load image")
+  }
+
+  let request = VNRecognizeTextRequest()
+  request.recognitionLevel = .fast
+  request.usesLanguageCorrection = false
+
+  let handler = VNImageRequestHandler(cgImage: image, options: [:])
+  do {
+    try handler.perform([request])
+  } catch {
+    return TextRegionsResponse(regions: [], error: error.localizedDescription)
+  }
+
+  let regions = (request.results ?? []).map { obs -> TextRegion in
+    // Vision boxes are normalized with origin bottom-left.
+    let b = obs.boundingBox
+    return TextRegion(
+      xPercent: Double(clamp01(b.minX)) * 100.0,
+      yPercent: Double(clamp01(1.0 - b.maxY)) * 100.0,
+      widthPercent: Double(clamp01(b.width)) * 100.0,
+      heightPercent: Double(clamp01(b.height)) * 100.0
+    )
+  }
+  return TextRegionsResponse(regions: regions, error: nil)
+}
+
+func encodeTextRegions(_ value: TextRegionsResponse) -> Data {
+  let encoder = JSONEncoder()
+  encoder.keyEncodingStrategy = .convertToSnakeCase
+  return (try? encoder.encode(value)) ?? Data("{\"regions\":[]}".utf8)
+}
@@ -0,0 +1,128 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn fixture_guide() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/guide/steps.json")
+}
+
+fn run_export(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_stepcast"))
+        .arg("--export")
+        .args(args)
+        .output()
+        .expect("run stepcast --export")
+}
+
+#[test]
+fn exports_fixture_guide_to_html() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output = dir.path().join("guide.html");
+    let guide = fixture_guide();
+
+    let result = run_export(&[
+        "--input",
+        guide.to_str().unwrap(),
+        "--format",
+        "html",
+        "--output",
+        output.to_str().unwrap(),
+        "--locale",
+        "en",
+        "--title",
+        "Copy a download",
+    ]);
+
+    assert!(
+        result.status.success(),
+        "export failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let html = std::fs::read_to_string(&output).expect("read html");
+    assert!(html.contains("Copy a download"));
+    assert!(
+        html.contains("data:image/"),
+        "screenshot should be embedded"
+    );
+    assert!(html.contains("Check that the file was copied."));
+}
+
+#[test]
+fn exports_text_only_html_without_images() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output = dir.path().join("guide.html");
+    let guide = fixture_guide();
+
+    let result = run_export(&[
+        "--input",
+        guide.to_str().unwrap(),
+        "--format",
+        "html",
+        "--output",
+        output.to_str().unwrap(),
+        "--locale",
+        "en",
+        "--text-only",
+    ]);
+
+    assert!(
+        result.status.success(),
+        "export failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let html = std::fs::read_to_string(&output).expect("read html");
+    assert!(!html.contains("<img"));
+    assert!(html.contains("Check that the file was copied."));
+}
+
+#[test]
+fn exports_fixture_guide_to_markdown_bundle() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output = dir.path().join("guide.zip");
+    let guide = fixture_guide();
+
+    let result = run_export(&[
+        "--input",
+        guide.to_str().unwrap(),
+        "--format",
+        "md",
+        "--output",
+        output.to_str().unwrap(),
+        "--locale",
+        "de",
+    ]);
+
+    assert!(
+        result.status.success(),
+        "export failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let bytes = std::fs::read(&output).expect("read zip");
+    assert!(bytes.starts_with(b"PK"), "markdown export is a zip bundle");
+    let listing = String::from_utf8_lossy(&bytes);
+    assert!(listing.contains("guide.md"));
+}
+
+#[test]
+fn reports_errors_on_stderr_with_failing_status() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let missing = dir.path().join("missing.json");
+    let output = dir.path().join("guide.html");
+
+    let result = run_export(&[
+        "--input",
+        missing.to_str().unwrap(),
+        "--format",
+        "html",
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("Cannot read"), "stderr: {stderr}");
+    assert!(!output.exists());
+
+    let result = run_export(&["--format", "docx"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Unknown export format: docx"));
+}
@@ -5,6 +5,7 @@ import { convertFileSrc } from "@tauri-apps/api/core";
 import CropEditorModal from "./CropEditorModal";
 import StepScreenshot from "./StepScreenshot";
 import type { BoundsPercent, Step } from "../types/step";
+import { isAuthPlaceholder as isStepAuthPlaceholder } from "../utils/authPlaceholder";
 import { useI18n } from "../i18n";
 
 type EditorStepCardProps = {
@@ -83,9 +84,7 @@ export default memo(function EditorStepCard({
     [step.screenshot_path],
   );
 
-  const isAuthPlaceholder =
-    step.window_title === "Authentication dialog (secure)" ||
-    step.app.toLowerCase() === "authentication";
+  const isAuthPlaceholder = isStepAuthPlaceholder(step);
 
   const actionDesc =
     step.action === "DoubleClick"
@@ -94,7 +93,11 @@ export default memo(function EditorStepCard({
         ? t("step.action.right_clicked_in")
         : step.action === "Shortcut"
           ? t("step.action.shortcut_in")
-          : t("step.action.clicked_in");
+          : step.action === "Drag"
+            ? t("step.action.dragged_in")
+            : step.action === "Hover"
+              ? t("step.action.pointed_in")
+              : t("step.action.clicked_in");
 
   const authDescription =
     step.description && step.description.trim().length > 0
@@ -176,7 +179,7 @@ export default memo(function EditorStepCard({
           index + 1
         )}
         {(step.action === "DoubleClick" || step.action === "RightClick" || step.action === "Shortcut") && (
-          <span className="editor-badge-action" title={step.action === "DoubleClick" ? t("step.badge.double_click") : step.action === "RightClick" ? t("step.badge.right_click") : t("step.badge.shortcut")}>
+          <span className="editor-badge-action" title={step.action === "DoubleClick" ? t("step.badge.double_click") : step.action === "RightClick" ? t("step.badge.right_click") : step.keys ?? t("step.badge.shortcut")}>
             {step.action === "DoubleClick" ? (
               <svg width="8" height="8" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="3">
                 <circle cx="12" cy="12" r="10" /><circle cx="12" cy="12" r="4" />
@@ -81,11 +81,12 @@ describe("EditorWindow", () => {
   });
 
   it("still loads when get_steps fails", async () => {
-    mockInvoke.mockRejectedValueOnce(new Error("session lock poisoned"));
+    mockInvoke.mockRejectedValueOnce({ code: "lock_poisoned", message: "session lock poisoned" });
     render(<EditorWindow />);
     await waitFor(() => {
       expect(screen.getByText("No steps recorded yet")).toBeInTheDocument();
     });
+    expect(screen.getByText("session lock poisoned")).toBeInTheDocument();
   });
 
   it("appends new step from step-captured event", async () => {
@@ -260,10 +261,13 @@ describe("EditorWindow", () => {
 
     act(() => {
       listeners["steps-reordered"]({
-        payload: [
-          makeStep({ id: "step-2", app: "Safari" }),
-          makeStep({ id: "step-1", app: "Finder" }),
-        ],
+        payload: {
+          steps: [
+            makeStep({ id: "step-2", app: "Safari" }),
+            makeStep({ id: "step-1", app: "Finder" }),
+          ],
+          revision: 1,
+        },
       });
     });
 
@@ -367,10 +371,13 @@ describe("EditorWindow", () => {
 
     act(() => {
       listeners["steps-reordered"]({
-        payload: [
-          makeStep({ id: "step-1", description: "done" }),
-          makeStep({ id: "step-2", description: "done too", app: "Safari" }),
-        ],
+        payload: {
+          steps: [
+            makeStep({ id: "step-1", description: "done" }),
+            makeStep({ id: "step-2", description: "done too", app: "Safari" }),
+          ],
+          revision: 1,
+        },
       });
     });
     await user.click(screen.getByRole("button", { name: /Enhance Steps/i }));
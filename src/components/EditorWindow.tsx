@@ -5,19 +5,14 @@ import { DndContext, closestCenter, type DragEndEvent } from "@dnd-kit/core";
 import { SortableContext, verticalListSortingStrategy, arrayMove } from "@dnd-kit/sortable";
 import EditorStepCard from "./EditorStepCard";
 import UndoToast from "./UndoToast";
-import type { BoundsPercent, Step } from "../types/step";
+import type { BoundsPercent, Step, StepsReordered } from "../types/step";
 import { mergeUpdatedStep } from "../utils/stepEvents";
+import { isAuthPlaceholder } from "../utils/authPlaceholder";
+import { errorMessage } from "../utils/commandError";
 import { isSupportedAppLanguage, type AppLanguage, useI18n } from "../i18n";
 
 type PendingDelete = { step: Step; index: number; timerId: ReturnType<typeof setTimeout> };
 
-function isAuthPlaceholder(step: Step) {
-  return (
-    step.window_title === "Authentication dialog (secure)" ||
-    step.app.toLowerCase() === "authentication"
-  );
-}
-
 export default function EditorWindow() {
   const { appLanguage, locale, setAppLanguage, t } = useI18n();
   const [steps, setSteps] = useState<Step[]>([]);
@@ -32,6 +27,11 @@ export default function EditorWindow() {
   const [aiEnabled, setAiEnabled] = useState(
     () => localStorage.getItem("appleIntelligenceDescriptions") === "true",
   );
+  const [error, setError] = useState<string | null>(null);
+
+  const reportError = useCallback((err: unknown) => {
+    setError(errorMessage(err));
+  }, []);
 
   // Load initial steps from backend
   useEffect(() => {
@@ -40,7 +40,8 @@ export default function EditorWindow() {
         setSteps(result);
         setLoaded(true);
       })
-      .catch(() => {
+      .catch((err) => {
+        setError(errorMessage(err));
         setLoaded(true);
       });
   }, []);
@@ -122,8 +123,8 @@ export default function EditorWindow() {
       setSteps((prev) => prev.filter((s) => s.id !== event.payload));
     }).then((fn) => unlisteners.push(fn));
 
-    listen<Step[]>("steps-reordered", (event) => {
-      setSteps(event.payload);
+    listen<StepsReordered>("steps-reordered", (event) => {
+      setSteps(event.payload.steps);
     }).then((fn) => unlisteners.push(fn));
 
     return () => {
@@ -132,14 +133,14 @@ export default function EditorWindow() {
   }, []);
 
   const handleUpdateNote = useCallback((stepId: string, note: string | null) => {
-    invoke("update_step_note", { stepId, note }).catch(() => {});
+    invoke("update_step_note", { stepId, note }).catch(reportError);
     setSteps((prev) =>
       prev.map((s) => (s.id === stepId ? { ...s, note } : s)),
     );
-  }, []);
+  }, [reportError]);
 
   const handleUpdateDescription = useCallback((stepId: string, description: string | null) => {
-    invoke("update_step_description", { stepId, description }).catch(() => {});
+    invoke("update_step_description", { stepId, description }).catch(reportError);
     setSteps((prev) =>
       prev.map((s) =>
         s.id === stepId
@@ -153,14 +154,14 @@ export default function EditorWindow() {
           : s,
       ),
     );
-  }, []);
+  }, [reportError]);
 
   const flushPendingDelete = useCallback((pending: PendingDelete) => {
     clearTimeout(pending.timerId);
-    invoke("delete_step", { stepId: pending.step.id }).catch(() => {});
+    invoke("delete_step", { stepId: pending.step.id }).catch(reportError);
     setPendingDelete((cur) => (cur === pending ? null : cur));
     pendingDeleteRef.current = null;
-  }, []);
+  }, [reportError]);
 
   const handleDelete = useCallback((stepId: string) => {
     setSteps((prev) => {
@@ -172,13 +173,13 @@ export default function EditorWindow() {
       const existing = pendingDeleteRef.current;
       if (existing) {
         clearTimeout(existing.timerId);
-        invoke("delete_step", { stepId: existing.step.id }).catch(() => {});
+        invoke("delete_step", { stepId: existing.step.id }).catch(reportError);
       }
 
       const timerId = setTimeout(() => {
         const cur = pendingDeleteRef.current;
         if (cur && cur.step.id === deletedStep.id) {
-          invoke("delete_step", { stepId: deletedStep.id }).catch(() => {});
+          invoke("delete_step", { stepId: deletedStep.id }).catch(reportError);
           setPendingDelete(null);
           pendingDeleteRef.current = null;
         }
@@ -190,7 +191,7 @@ export default function EditorWindow() {
 
       return prev.filter((s) => s.id !== stepId);
     });
-  }, []);
+  }, [reportError]);
 
   const handleUndoDelete = useCallback(() => {
     const pending = pendingDeleteRef.current;
@@ -224,19 +225,19 @@ export default function EditorWindow() {
   }, []);
 
   const handleUpdateCrop = useCallback((stepId: string, cropRegion: BoundsPercent | null) => {
-    invoke("update_step_crop", { stepId, cropRegion }).catch(() => {});
+    invoke("update_step_crop", { stepId, cropRegion }).catch(reportError);
     setSteps((prev) =>
       prev.map((s) => (s.id === stepId ? { ...s, crop_region: cropRegion } : s)),
     );
-  }, []);
+  }, [reportError]);
 
   const handleGenerateDescription = useCallback(
     (stepId: string) => {
       if (!aiEnabled) return;
       const aiLanguage = appLanguage === "system" ? locale : appLanguage;
-      invoke("generate_step_descriptions", { stepIds: [stepId], appLanguage: aiLanguage }).catch(() => {});
+      invoke("generate_step_descriptions", { stepIds: [stepId], appLanguage: aiLanguage }).catch(reportError);
     },
-    [aiEnabled, appLanguage, locale],
+    [aiEnabled, appLanguage, locale, reportError],
   );
 
   const handleToggleCollapse = useCallback((stepId: string) => {
@@ -283,21 +284,21 @@ export default function EditorWindow() {
 
   const handleBulkDelete = useCallback(() => {
     for (const id of selection) {
-      invoke("delete_step", { stepId: id }).catch(() => {});
+      invoke("delete_step", { stepId: id }).catch(reportError);
     }
     setSteps((prev) => prev.filter((s) => !selection.has(s.id)));
     setSelection(new Set());
     lastSelectedRef.current = null;
-  }, [selection]);
+  }, [reportError, selection]);
 
   const handleBulkGenerate = useCallback(() => {
     if (!aiEnabled) return;
     const aiLanguage = appLanguage === "system" ? locale : appLanguage;
     const ids = steps.filter((s) => selection.has(s.id)).map((s) => s.id);
     if (ids.length > 0) {
-      invoke("generate_step_descriptions", { stepIds: ids, appLanguage: aiLanguage }).catch(() => {});
+      invoke("generate_step_descriptions", { stepIds: ids, appLanguage: aiLanguage }).catch(reportError);
     }
-  }, [aiEnabled, appLanguage, locale, selection, steps]);
+  }, [aiEnabled, appLanguage, locale, reportError, selection, steps]);
 
   // Prune stale selection IDs when steps change
   useEffect(() => {
@@ -346,10 +347,10 @@ export default function EditorWindow() {
       const newIndex = prev.findIndex((s) => s.id === over.id);
       if (oldIndex === -1 || newIndex === -1) return prev;
       const reordered = arrayMove(prev, oldIndex, newIndex);
-      invoke("reorder_steps", { stepIds: reordered.map((s) => s.id) }).catch(() => {});
+      invoke("reorder_steps", { stepIds: reordered.map((s) => s.id) }).catch(reportError);
       return reordered;
     });
-  }, []);
+  }, [reportError]);
 
   const handleEnhanceAll = useCallback(() => {
     if (!aiEnabled) return;
@@ -359,12 +360,13 @@ export default function EditorWindow() {
       (s) =>
         !isAuthPlaceholder(s) &&
         s.action !== "Note" &&
-        (!s.description || s.description.trim().length === 0) &&
-        s.description_source !== "manual",
+        s.description_source !== "manual" &&
+        // Rule-based descriptions count as missing, like in the backend.
+        (s.description_source === "auto" || !s.description || s.description.trim().length === 0),
     );
     const mode = missing.length > 0 ? "missing_only" : "all";
-    invoke("generate_step_descriptions", { mode, appLanguage: aiLanguage }).catch(() => {});
-  }, [aiEnabled, appLanguage, locale, steps]);
+    invoke("generate_step_descriptions", { mode, appLanguage: aiLanguage }).catch(reportError);
+  }, [aiEnabled, appLanguage, locale, reportError, steps]);
 
   const isAnyGenerating = useMemo(
     () => steps.some((s) => s.description_status === "generating"),
@@ -446,6 +448,7 @@ export default function EditorWindow() {
           )}
         </div>
       </header>
+      {error && <div className="error-banner">{error}</div>}
       <div className="editor-scroll" ref={scrollRef} tabIndex={0} onKeyDown={handleScrollKeyDown}>
         {steps.length === 0 ? (
           <div className="editor-empty">{t("editor.empty")}</div>
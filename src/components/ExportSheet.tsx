@@ -1,18 +1,19 @@
 import { useState } from "react";
 import { useI18n } from "../i18n";
 
-type ExportFormat = "html" | "md" | "pdf";
+type ExportFormat = "html" | "html-zip" | "md" | "md-notion" | "md-confluence" | "pdf" | "gif" | "automation";
 
 interface ExportSheetProps {
   stepCount: number;
   exporting: boolean;
+  progress?: { done: number; total: number } | null;
   onExport: (title: string, format: ExportFormat) => void;
   onClose: () => void;
 }
 
-const FORMAT_OPTIONS: ExportFormat[] = ["html", "md", "pdf"];
+const FORMAT_OPTIONS: ExportFormat[] = ["html", "html-zip", "md", "md-notion", "md-confluence", "pdf", "gif", "automation"];
 
-export default function ExportSheet({ stepCount, exporting, onExport, onClose }: ExportSheetProps) {
+export default function ExportSheet({ stepCount, exporting, progress, onExport, onClose }: ExportSheetProps) {
   const { t } = useI18n();
   const [title, setTitle] = useState("New StepCast Guide");
   const [format, setFormat] = useState<ExportFormat>(
@@ -70,7 +71,11 @@ export default function ExportSheet({ stepCount, exporting, onExport, onClose }:
             onClick={() => onExport(title.trim(), format)}
             disabled={!titleValid || exporting}
           >
-            {exporting ? t("common.exporting") : t("common.export")}
+            {!exporting
+              ? t("common.export")
+              : progress && progress.total > 0
+                ? t("export.progress", { done: progress.done, total: progress.total })
+                : t("common.exporting")}
           </button>
         </div>
       </div>
@@ -8,7 +8,7 @@ import { save, ask } from "@tauri-apps/plugin-dialog";
 import { check, type Update } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
 import RecorderPanel from "./RecorderPanel";
-import type { Step } from "../types/step";
+import type { Step, StepsReordered } from "../types/step";
 
 let dndOnDragEnd: ((event: { active: { id: string }; over: { id: string } | null }) => void) | null = null;
 
@@ -66,8 +66,11 @@ function makeStep(overrides: Partial<Step> = {}): Step {
 let stepCapturedCallback: ((event: { payload: Step }) => void) | null = null;
 let stepUpdatedCallback: ((event: { payload: Step }) => void) | null = null;
 let stepDeletedCallback: ((event: { payload: string }) => void) | null = null;
-let stepsReorderedCallback: ((event: { payload: Step[] }) => void) | null = null;
+let stepsReorderedCallback: ((event: { payload: StepsReordered }) => void) | null = null;
 let panelPositionedCallback: ((event: { payload: boolean }) => void) | null = null;
+let exportUploadFailedCallback: ((event: { payload: { error: string } }) => void) | null = null;
+let recordingInterruptedCallback: ((event: { payload: { reason: string } }) => void) | null =
+  null;
 
 beforeEach(() => {
   dndOnDragEnd = null;
@@ -76,6 +79,8 @@ beforeEach(() => {
   stepDeletedCallback = null;
   stepsReorderedCallback = null;
   panelPositionedCallback = null;
+  recordingInterruptedCallback = null;
+  exportUploadFailedCallback = null;
   mockInvoke.mockReset();
   mockListen.mockReset();
   mockSave.mockReset();
@@ -102,9 +107,13 @@ beforeEach(() => {
     } else if (event === "step-deleted") {
       stepDeletedCallback = handler as (event: { payload: string }) => void;
     } else if (event === "steps-reordered") {
-      stepsReorderedCallback = handler as (event: { payload: Step[] }) => void;
+      stepsReorderedCallback = handler as (event: { payload: StepsReordered }) => void;
     } else if (event === "panel-positioned") {
       panelPositionedCallback = handler as (event: { payload: boolean }) => void;
+    } else if (event === "recording-interrupted") {
+      recordingInterruptedCallback = handler as (event: { payload: { reason: string } }) => void;
+    } else if (event === "export-upload-failed") {
+      exportUploadFailedCallback = handler as (event: { payload: { error: string } }) => void;
     }
     return vi.fn() as unknown as () => void; // unlisten
   });
@@ -258,6 +267,32 @@ describe("RecorderPanel", () => {
       expect(screen.getByText("Recording")).toBeInTheDocument();
     });
 
+    it("asks to resume after a screen lock paused the recording", async () => {
+      const user = userEvent.setup();
+      render(<RecorderPanel />);
+      await user.click(await screen.findByText("Start Recording"));
+      await user.click(screen.getByText("Pause"));
+      act(() => {
+        recordingInterruptedCallback?.({ payload: { reason: "screen_lock" } });
+      });
+      expect(screen.getByText(/while the screen was locked/)).toBeInTheDocument();
+
+      await user.click(screen.getByText("Resume"));
+      expect(mockInvoke).toHaveBeenCalledWith("resume_recording");
+      expect(screen.queryByText(/while the screen was locked/)).not.toBeInTheDocument();
+    });
+
+    it("reports a failed upload after export", async () => {
+      render(<RecorderPanel />);
+      await screen.findByText("Start Recording");
+      act(() => {
+        exportUploadFailedCallback?.({ payload: { error: "Upload returned HTTP 403." } });
+      });
+      expect(
+        screen.getByText("Exported, but the upload failed: Upload returned HTTP 403."),
+      ).toBeInTheDocument();
+    });
+
     it("recording → stop shows stopped state with steps", async () => {
       const user = userEvent.setup();
       render(<RecorderPanel />);
@@ -288,7 +323,10 @@ describe("RecorderPanel", () => {
           return { screen_recording: true, accessibility: true };
         }
         if (cmd === "start_recording") {
-          throw new Error("missing screen recording permission");
+          throw {
+            code: "missing_permission",
+            message: "missing screen recording or accessibility permission",
+          };
         }
         return undefined;
       });
@@ -320,6 +358,25 @@ describe("RecorderPanel", () => {
         screen.getByText("Error: Something went wrong"),
       ).toBeInTheDocument();
     });
+
+    it("shows the message of structured command errors", async () => {
+      const user = userEvent.setup();
+      mockInvoke.mockImplementation(async (cmd: string) => {
+        if (cmd === "check_permissions") {
+          return { screen_recording: true, accessibility: true };
+        }
+        if (cmd === "start_recording") {
+          throw { code: "failed", message: "Failed to start click listener: denied" };
+        }
+        return undefined;
+      });
+
+      render(<RecorderPanel />);
+      await user.click(await screen.findByText("Start Recording"));
+      expect(
+        screen.getByText("Failed to start click listener: denied"),
+      ).toBeInTheDocument();
+    });
   });
 
   describe("step-captured event", () => {
@@ -428,10 +485,12 @@ describe("RecorderPanel", () => {
       await user.click(sheetExportBtn);
       expect(mockSave).toHaveBeenCalled();
       expect(mockInvoke).toHaveBeenCalledWith("export_guide", {
-        title: "New StepCast Guide",
-        format: "pdf",
-        outputPath: "/tmp/guide.pdf",
-        appLanguage: "en",
+        request: {
+          title: "New StepCast Guide",
+          format: "pdf",
+          outputPath: "/tmp/guide.pdf",
+          appLanguage: "en",
+        },
       });
     });
 
@@ -738,7 +797,7 @@ describe("RecorderPanel", () => {
         } else if (event === "step-deleted") {
           stepDeletedCallback = handler as (event: { payload: string }) => void;
         } else if (event === "steps-reordered") {
-          stepsReorderedCallback = handler as (event: { payload: Step[] }) => void;
+          stepsReorderedCallback = handler as (event: { payload: StepsReordered }) => void;
         } else if (event === "panel-positioned") {
           panelPositionedCallback = handler as (event: { payload: boolean }) => void;
         } else if (event === "show-quick-start") {
@@ -887,10 +946,13 @@ describe("RecorderPanel", () => {
 
       act(() => {
         stepsReorderedCallback?.({
-          payload: [
-            makeStep({ id: "s2", app: "Safari" }),
-            makeStep({ id: "s1", app: "Finder" }),
-          ],
+          payload: {
+            steps: [
+              makeStep({ id: "s2", app: "Safari" }),
+              makeStep({ id: "s1", app: "Finder" }),
+            ],
+            revision: 1,
+          },
         });
       });
 
@@ -14,8 +14,9 @@ import ExportSheet from "./ExportSheet";
 import SettingsSheet from "./SettingsSheet";
 import WelcomeBanner from "./WelcomeBanner";
 import ReleaseNotes from "./ReleaseNotes";
-import type { Step } from "../types/step";
+import type { Step, StepsReordered } from "../types/step";
 import { mergeUpdatedStep } from "../utils/stepEvents";
+import { errorMessage, isCommandError } from "../utils/commandError";
 import { useI18n } from "../i18n";
 
 const SettingsIcon = () => (
@@ -111,6 +112,7 @@ export default function RecorderPanel() {
   const [showExportSheet, setShowExportSheet] = useState(false);
   const [showNewMenu, setShowNewMenu] = useState(false);
   const [exporting, setExporting] = useState(false);
+  const [exportProgress, setExportProgress] = useState<{ done: number; total: number } | null>(null);
   const [updateAvailable, setUpdateAvailable] = useState<string | null>(null);
   const [updateNotes, setUpdateNotes] = useState<string | null>(null);
   const [updating, setUpdating] = useState(false);
@@ -118,6 +120,7 @@ export default function RecorderPanel() {
   const [showWelcome, setShowWelcome] = useState(false);
   const [whatsNew, setWhatsNew] = useState<string | null>(null);
   const [showNotch, setShowNotch] = useState(true);
+  const [interrupted, setInterrupted] = useState<"screen_lock" | "sleep" | null>(null);
 
   const permissionsReady = Boolean(
     permissions && permissions.screen_recording && permissions.accessibility,
@@ -172,7 +175,7 @@ export default function RecorderPanel() {
       const next = await invoke<PermissionStatus>("check_permissions");
       setPermissions(next);
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     }
   }, []);
 
@@ -219,6 +222,162 @@ export default function RecorderPanel() {
     };
   }, []);
 
+  // Per-step progress while an export is running
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<{ done: number; total: number; finished: boolean }>("export-progress", (event) => {
+      const { done, total, finished } = event.payload;
+      setExportProgress(finished ? null : { done, total });
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Optional upload after export runs in the background; the backend shows the panel on failure
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<{ path: string; url: string; error: string }>("export-upload-failed", (event) => {
+      setError(t("export.upload_failed", { error: event.payload.error }));
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [t]);
+
+  // Backend auto-pauses on screen lock and resumes on unlock
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<RecorderStatus>("recorder-state-changed", (event) => {
+      setStatus(event.payload);
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Backend pauses on screen lock or sleep; once the user is back, ask about resuming
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<{ reason: "screen_lock" | "sleep" }>("recording-interrupted", (event) => {
+      setInterrupted(event.payload.reason);
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  useEffect(() => {
+    if (status !== "paused") setInterrupted(null);
+  }, [status]);
+
+  // Backend pauses when a permission is revoked mid-recording
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<"screen_recording" | "accessibility">("permission-lost", (event) => {
+      setError(
+        t("recorder.error.permission_lost", {
+          permission: t(`recorder.permissions.${event.payload}`),
+        }),
+      );
+      refreshPermissions();
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [t, refreshPermissions]);
+
+  // Backend stops a recording left idle for too long
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<{ idle_ms: number; steps: Step[] }>("recording-auto-stopped", (event) => {
+      setSteps(event.payload.steps);
+      setError(
+        t("recorder.error.auto_stopped", {
+          minutes: String(Math.round(event.payload.idle_ms / 60000)),
+        }),
+      );
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [t]);
+
+  // Backend recreated a click listener that stopped receiving events
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<
+      | { status: "recovered"; listener_restarts: number }
+      | { status: "listener_failed"; error: string }
+    >("recorder-health", (event) => {
+      const health = event.payload;
+      setError(
+        health.status === "recovered"
+          ? t("recorder.error.listener_recovered")
+          : t("recorder.error.listener_failed", { error: health.error }),
+      );
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [t]);
+
+  // Backend dropped a click in an app on the sensitive-app blocklist
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    listen<{ app: string; paused: boolean }>("recording-blocked-app", (event) => {
+      const { app, paused } = event.payload;
+      setError(
+        paused
+          ? t("recorder.error.blocked_app_paused", { app })
+          : t("recorder.error.blocked_app", { app }),
+      );
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [t]);
+
   // Listen for panel positioning mode (tray vs fallback)
   useEffect(() => {
     let unlisten: UnlistenFn | null = null;
@@ -301,8 +460,8 @@ export default function RecorderPanel() {
       setSteps((prev) => prev.filter((s) => s.id !== event.payload));
     }).then((fn) => unlisteners.push(fn));
 
-    listen<Step[]>("steps-reordered", (event) => {
-      setSteps(event.payload);
+    listen<StepsReordered>("steps-reordered", (event) => {
+      setSteps(event.payload.steps);
     }).then((fn) => unlisteners.push(fn));
 
     return () => {
@@ -343,11 +502,10 @@ export default function RecorderPanel() {
           setStatus(nextStatus);
         }
       } catch (err) {
-        const message = String(err);
-        if (message.includes("missing screen recording")) {
+        if (isCommandError(err) && err.code === "missing_permission") {
           setError(t("recorder.error.permissions_required"));
         } else {
-          setError(message);
+          setError(errorMessage(err));
         }
       }
     },
@@ -360,7 +518,7 @@ export default function RecorderPanel() {
       const next = await invoke<PermissionStatus>("request_screen_recording");
       setPermissions(next);
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     }
   }, []);
 
@@ -370,36 +528,80 @@ export default function RecorderPanel() {
       const next = await invoke<PermissionStatus>("request_accessibility");
       setPermissions(next);
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     }
   }, []);
 
-  const handleExport = useCallback(async (title: string, format: "html" | "md" | "pdf") => {
+  const handleExport = useCallback(async (
+    title: string,
+    format: "html" | "html-zip" | "md" | "md-notion" | "md-confluence" | "pdf" | "gif" | "automation",
+  ) => {
     setError(null);
     setExporting(true);
     try {
-      const ext = { html: "html", md: "zip", pdf: "pdf" }[format];
-      const name = { html: "HTML", md: "Markdown Archive", pdf: "PDF" }[format];
+      const ext = {
+        html: "html",
+        "html-zip": "zip",
+        md: "zip",
+        "md-notion": "zip",
+        "md-confluence": "zip",
+        pdf: "pdf",
+        gif: "gif",
+        automation: "json",
+      }[format];
+      const name = {
+        html: "HTML",
+        "html-zip": "HTML Archive",
+        md: "Markdown Archive",
+        "md-notion": "Notion Markdown Archive",
+        "md-confluence": "Confluence Markdown Archive",
+        pdf: "PDF",
+        gif: "GIF",
+        automation: "Automation JSON",
+      }[format];
       const path = await save({
         defaultPath: `${title}.${ext}`,
         filters: [{ name, extensions: [ext] }],
       });
       if (!path) return;
       const exportLanguage = appLanguage === "system" ? locale : appLanguage;
-      await invoke("export_guide", {
-        title,
-        format,
-        outputPath: path,
-        appLanguage: exportLanguage,
+      const summary = await invoke<{
+        path: string;
+        images_downscaled: number;
+        output_bytes: number;
+        warnings?: string[];
+      }>("export_guide", {
+        request: {
+          title,
+          format,
+          outputPath: path,
+          appLanguage: exportLanguage,
+        },
       });
       setShowExportSheet(false);
+      // Stay open for problems that did not stop the export, e.g. a missing logo.
+      if (summary?.warnings?.length) {
+        setError(t("export.warnings_notice", { warnings: summary.warnings.join(" ") }));
+        return;
+      }
+      // Stay open to say that oversized screenshots were scaled down.
+      if (summary?.images_downscaled) {
+        setError(
+          t("export.downscaled_notice", {
+            count: summary.images_downscaled,
+            size: (summary.output_bytes / (1024 * 1024)).toFixed(1),
+          }),
+        );
+        return;
+      }
       getCurrentWindow().hide();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setExporting(false);
+      setExportProgress(null);
     }
-  }, [appLanguage, locale]);
+  }, [appLanguage, locale, t]);
 
   const handleDiscard = useCallback(async () => {
     const confirmed = await ask(
@@ -594,6 +796,9 @@ export default function RecorderPanel() {
       {/* Recording/Paused State */}
       {isRecordingOrPaused && (
         <section className="panel-card" style={{ flex: 1, minHeight: 0 }}>
+          {status === "paused" && interrupted && (
+            <div className="permission-banner warn">{t(`recorder.interrupted.${interrupted}`)}</div>
+          )}
           <div className="controls">
             {status === "recording" && (
               <>
@@ -746,6 +951,7 @@ export default function RecorderPanel() {
         <ExportSheet
           stepCount={steps.length}
           exporting={exporting}
+          progress={exportProgress}
           onExport={handleExport}
           onClose={() => setShowExportSheet(false)}
         />
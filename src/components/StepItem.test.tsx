@@ -86,6 +86,29 @@ describe("StepItem", () => {
     expect(screen.getByText("Right-clicked in Finder")).toBeInTheDocument();
   });
 
+  it("shows Hover description", () => {
+    render(
+      <StepItem step={makeStep({ action: "Hover" })} index={0} />,
+    );
+    expect(screen.getByText("Pointed at an item in Finder")).toBeInTheDocument();
+  });
+
+  it("shows Shortcut description", () => {
+    render(
+      <StepItem step={makeStep({ action: "Shortcut" })} index={0} />,
+    );
+    expect(
+      screen.getByText("Used keyboard shortcut in Finder"),
+    ).toBeInTheDocument();
+  });
+
+  it("shows Drag description", () => {
+    render(
+      <StepItem step={makeStep({ action: "Drag" })} index={0} />,
+    );
+    expect(screen.getByText("Dragged in Finder")).toBeInTheDocument();
+  });
+
   it("hides marker for auth placeholder", () => {
     const { container } = render(
       <StepItem
@@ -4,6 +4,7 @@ import { useSortable } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
 import type { Step } from "../types/step";
 import { getCroppedImageStyles, markerPositionForStep } from "../utils/stepCrop";
+import { isAuthPlaceholder as isStepAuthPlaceholder } from "../utils/authPlaceholder";
 import { useI18n } from "../i18n";
 
 type StepItemProps = {
@@ -48,9 +49,7 @@ export default memo(function StepItem({ step, index, onDelete, sortable }: StepI
     return `${thumbnailBaseSrc}${sep}retry=${thumbRetry}`;
   }, [thumbnailBaseSrc, thumbRetry]);
 
-  const isAuthPlaceholder =
-    step.window_title === "Authentication dialog (secure)" ||
-    step.app.toLowerCase() === "authentication";
+  const isAuthPlaceholder = isStepAuthPlaceholder(step);
 
   // Determine marker class based on action type
   const markerClass =
@@ -74,7 +73,13 @@ export default memo(function StepItem({ step, index, onDelete, sortable }: StepI
       ? t("step.action.double_clicked_in")
       : step.action === "RightClick"
         ? t("step.action.right_clicked_in")
-        : t("step.action.clicked_in");
+        : step.action === "Shortcut"
+          ? t("step.action.shortcut_in")
+          : step.action === "Drag"
+            ? t("step.action.dragged_in")
+            : step.action === "Hover"
+              ? t("step.action.pointed_in")
+              : t("step.action.clicked_in");
 
   const authDescription =
     step.description && step.description.trim().length > 0
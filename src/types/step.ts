@@ -1,8 +1,8 @@
-export type ActionType = "Click" | "DoubleClick" | "RightClick" | "Shortcut" | "Note";
+export type ActionType = "Click" | "DoubleClick" | "RightClick" | "Shortcut" | "Note" | "Drag" | "Overview" | "Hover";
 
 export type CaptureStatus = "Ok" | "Fallback" | "Failed";
 
-export type DescriptionSource = "ai" | "manual";
+export type DescriptionSource = "ai" | "manual" | "auto";
 
 export type DescriptionStatus = "idle" | "generating" | "failed";
 
@@ -13,6 +13,49 @@ export type BoundsPercent = {
   height_percent: number;
 };
 
+export type CaptureBounds = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  display_width: number;
+  display_height: number;
+};
+
+export type PixelSize = {
+  width: number;
+  height: number;
+};
+
+export type DragEnd = {
+  x: number;
+  y: number;
+  x_percent: number;
+  y_percent: number;
+  app?: string;
+  window_title?: string;
+};
+
+/** Percent of the cropped screenshot. */
+export type PointPercent = {
+  x: number;
+  y: number;
+};
+
+/** Vector overlay baked into exported images. */
+export type Annotation =
+  | { kind: "arrow"; from: PointPercent; to: PointPercent }
+  | { kind: "rect"; bounds: BoundsPercent }
+  | { kind: "text"; pos: PointPercent; content: string };
+
+/** Per-phase capture durations in ms; phases a capture path skipped are absent. */
+export type CaptureTimings = {
+  ax_lookup_ms?: number;
+  window_resolve_ms?: number;
+  capture_ms?: number;
+  pre_click_frame_age_ms?: number;
+};
+
 export type AxClickInfo = {
   role: string;
   subrole?: string | null;
@@ -23,6 +66,7 @@ export type AxClickInfo = {
   container_role?: string | null;
   container_subrole?: string | null;
   container_identifier?: string | null;
+  container_bounds?: BoundsPercent | null;
   window_role?: string | null;
   window_subrole?: string | null;
   top_level_role?: string | null;
@@ -30,8 +74,18 @@ export type AxClickInfo = {
   parent_dialog_role?: string | null;
   parent_dialog_subrole?: string | null;
   is_checked?: boolean | null;
+  was_checked?: boolean | null;
+  now_checked?: boolean | null;
+  value?: string | null;
+  placeholder?: string | null;
   is_cancel_button: boolean;
   is_default_button: boolean;
+  /** Menu breadcrumb for menu-item clicks, e.g. "File ▸ Export ▸ PDF". */
+  menu_path?: string | null;
+  /** AXDescription: the name screen readers announce. */
+  accessibility_label?: string | null;
+  /** AXHelp: the element's help tag. */
+  help?: string | null;
 };
 
 export interface Step {
@@ -46,6 +100,7 @@ export interface Step {
   window_title: string;
   screenshot_path: string | null;
   note: string | null;
+  title?: string | null;
   description?: string | null;
   description_source?: DescriptionSource | null;
   description_status?: DescriptionStatus | null;
@@ -54,4 +109,34 @@ export interface Step {
   capture_status?: CaptureStatus | null;
   capture_error?: string | null;
   crop_region?: BoundsPercent | null;
+  is_secure_placeholder?: boolean;
+  drag_end?: DragEnd | null;
+  context_title?: string | null;
+  capture_bounds?: CaptureBounds | null;
+  click_approximate?: boolean;
+  result_screenshot_path?: string | null;
+  wait_ms?: number | null;
+  pixel_scale?: number | null;
+  capture_pixels?: PixelSize | null;
+  space_changed?: boolean;
+  bundle_id?: string | null;
+  annotations?: Annotation[];
+  capture_timings?: CaptureTimings | null;
+  page_url?: string | null;
+  keys?: string | null;
 }
+
+/** Guide title, author and tags; exports use them as defaults. */
+export type SessionMetadata = {
+  title: string;
+  author?: string | null;
+  /** Unix milliseconds when recording started. */
+  created_at: number;
+  tags: string[];
+};
+
+/** `steps-reordered` payload. `revision` counts changes to the session's steps. */
+export type StepsReordered = {
+  steps: Step[];
+  revision: number;
+};
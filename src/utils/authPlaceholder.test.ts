@@ -0,0 +1,18 @@
+import { describe, expect, it } from "vitest";
+import type { Step } from "../types/step";
+import { isAuthPlaceholder } from "./authPlaceholder";
+
+const step = (overrides: Partial<Step>): Step =>
+  ({ app: "Finder", window_title: "Downloads", ...overrides }) as Step;
+
+describe("isAuthPlaceholder", () => {
+  it("recognizes every form of the auth placeholder", () => {
+    expect(isAuthPlaceholder(step({ is_secure_placeholder: true }))).toBe(true);
+    expect(isAuthPlaceholder(step({ window_title: "Authentication dialog (secure)" }))).toBe(true);
+    expect(isAuthPlaceholder(step({ app: "Authentication" }))).toBe(true);
+  });
+
+  it("leaves regular steps alone", () => {
+    expect(isAuthPlaceholder(step({}))).toBe(false);
+  });
+});
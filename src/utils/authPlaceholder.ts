@@ -0,0 +1,10 @@
+import type { Step } from "../types/step";
+
+/** Whether `step` stands in for a secure authentication dialog that can't be captured. */
+export function isAuthPlaceholder(step: Step): boolean {
+  return (
+    !!step.is_secure_placeholder ||
+    step.window_title === "Authentication dialog (secure)" ||
+    step.app.toLowerCase() === "authentication"
+  );
+}
@@ -0,0 +1,17 @@
+import { describe, expect, it } from "vitest";
+import { errorMessage, isCommandError } from "./commandError";
+
+describe("commandError", () => {
+  it("recognizes backend command errors", () => {
+    const err = { code: "no_session", message: "no active session" };
+    expect(isCommandError(err)).toBe(true);
+    expect(errorMessage(err)).toBe("no active session");
+  });
+
+  it("falls back to String() for other rejections", () => {
+    expect(isCommandError("boom")).toBe(false);
+    expect(isCommandError(null)).toBe(false);
+    expect(errorMessage("boom")).toBe("boom");
+    expect(errorMessage(new Error("network"))).toBe("Error: network");
+  });
+});
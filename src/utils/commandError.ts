@@ -0,0 +1,19 @@
+/** Error shape returned by backend commands: a stable `code` plus a readable `message`. */
+export type CommandError = {
+  code: string;
+  message: string;
+};
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as CommandError).code === "string" &&
+    typeof (err as CommandError).message === "string"
+  );
+}
+
+/** Readable message for anything a command invocation can reject with. */
+export function errorMessage(err: unknown): string {
+  return isCommandError(err) ? err.message : String(err);
+}